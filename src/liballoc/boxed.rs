@@ -105,6 +105,16 @@ impl<T> Box<T> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     #[inline(always)]
+    #[rustc_const_unstable(feature = "const_box_new")]
+    #[cfg(not(stage0))]
+    pub const fn new(x: T) -> Box<T> {
+        box x
+    }
+
+    #[stable(feature = "rust1", since = "1.0.0")]
+    #[inline(always)]
+    #[cfg(stage0)]
+    #[allow(missing_docs)]
     pub fn new(x: T) -> Box<T> {
         box x
     }
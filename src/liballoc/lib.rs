@@ -117,6 +117,8 @@
 #![feature(on_unimplemented)]
 #![feature(rustc_const_unstable)]
 #![feature(const_vec_new)]
+#![feature(const_vec_from_raw_parts)]
+#![cfg_attr(not(stage0), feature(const_heap, const_let, const_mut_refs, const_slice_len))]
 #![feature(slice_partition_dedup)]
 #![feature(maybe_uninit)]
 #![feature(alloc_layout_extra)]
@@ -403,6 +403,23 @@ impl<T> [T] {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     #[inline]
+    #[rustc_const_unstable(feature = "const_vec_from_box")]
+    #[cfg(not(stage0))]
+    pub const fn into_vec(mut self: Box<Self>) -> Vec<T> {
+        // This is `hack::into_vec` without the calls that are not `const fn`, so that
+        // `vec![...]` can be used during const evaluation.
+        unsafe {
+            let len = self.len();
+            let xs = Vec::from_raw_parts(&mut *self as *mut [T] as *mut T, len, len);
+            mem::ManuallyDrop::new(self);
+            xs
+        }
+    }
+
+    #[stable(feature = "rust1", since = "1.0.0")]
+    #[inline]
+    #[cfg(stage0)]
+    #[allow(missing_docs)]
     pub fn into_vec(self: Box<Self>) -> Vec<T> {
         // NB see hack module in this file
         hack::into_vec(self)
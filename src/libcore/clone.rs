@@ -106,6 +106,7 @@
 /// [impls]: #implementors
 #[stable(feature = "rust1", since = "1.0.0")]
 #[lang = "clone"]
+#[rustc_trivial_field_reads]
 pub trait Clone : Sized {
     /// Returns a copy of the value.
     ///
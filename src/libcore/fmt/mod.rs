@@ -305,8 +305,8 @@ impl<'a> ArgumentV1<'a> {
     #[doc(hidden)]
     #[unstable(feature = "fmt_internals", reason = "internal to format_args!",
                issue = "0")]
-    pub fn new<'b, T>(x: &'b T,
-                      f: fn(&T, &mut Formatter) -> Result) -> ArgumentV1<'b> {
+    pub const fn new<'b, T>(x: &'b T,
+                            f: fn(&T, &mut Formatter) -> Result) -> ArgumentV1<'b> {
         unsafe {
             ArgumentV1 {
                 formatter: mem::transmute(f),
@@ -341,8 +341,8 @@ impl<'a> Arguments<'a> {
     #[doc(hidden)] #[inline]
     #[unstable(feature = "fmt_internals", reason = "internal to format_args!",
                issue = "0")]
-    pub fn new_v1(pieces: &'a [&'a str],
-                  args: &'a [ArgumentV1<'a>]) -> Arguments<'a> {
+    pub const fn new_v1(pieces: &'a [&'a str],
+                        args: &'a [ArgumentV1<'a>]) -> Arguments<'a> {
         Arguments {
             pieces,
             fmt: None,
@@ -359,9 +359,9 @@ impl<'a> Arguments<'a> {
     #[doc(hidden)] #[inline]
     #[unstable(feature = "fmt_internals", reason = "internal to format_args!",
                issue = "0")]
-    pub fn new_v1_formatted(pieces: &'a [&'a str],
-                            args: &'a [ArgumentV1<'a>],
-                            fmt: &'a [rt::v1::Argument]) -> Arguments<'a> {
+    pub const fn new_v1_formatted(pieces: &'a [&'a str],
+                                  args: &'a [ArgumentV1<'a>],
+                                  fmt: &'a [rt::v1::Argument]) -> Arguments<'a> {
         Arguments {
             pieces,
             fmt: Some(fmt),
@@ -549,6 +549,7 @@ impl Display for Arguments<'_> {
 )]
 #[doc(alias = "{:?}")]
 #[lang = "debug_trait"]
+#[rustc_trivial_field_reads]
 pub trait Debug {
     /// Formats the value using the given formatter.
     ///
@@ -615,6 +616,7 @@ pub trait Debug {
           (or {{:#?}} for pretty-print) instead",
 )]
 #[doc(alias = "{}")]
+#[cfg_attr(not(stage0), lang = "display_trait")] // needed by const-eval
 #[stable(feature = "rust1", since = "1.0.0")]
 pub trait Display {
     /// Formats the value using the given formatter.
@@ -374,6 +374,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "iterator_step_by", since = "1.28.0")]
     fn step_by(self, step: usize) -> StepBy<Self> where Self: Sized {
         assert!(step != 0);
@@ -430,6 +431,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn chain<U>(self, other: U) -> Chain<Self, U::IntoIter> where
         Self: Sized, U: IntoIterator<Item=Self::Item>,
@@ -508,6 +510,7 @@ pub trait Iterator {
     /// [`next`]: ../../std/iter/trait.Iterator.html#tymethod.next
     /// [`None`]: ../../std/option/enum.Option.html#variant.None
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn zip<U>(self, other: U) -> Zip<Self, U::IntoIter> where
         Self: Sized, U: IntoIterator
@@ -565,6 +568,7 @@ pub trait Iterator {
     /// }
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn map<B, F>(self, f: F) -> Map<Self, F> where
         Self: Sized, F: FnMut(Self::Item) -> B,
@@ -676,6 +680,7 @@ pub trait Iterator {
     ///
     /// of these layers.
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn filter<P>(self, predicate: P) -> Filter<Self, P> where
         Self: Sized, P: FnMut(&Self::Item) -> bool,
@@ -733,6 +738,7 @@ pub trait Iterator {
     /// [`Some`]: ../../std/option/enum.Option.html#variant.Some
     /// [`None`]: ../../std/option/enum.Option.html#variant.None
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn filter_map<B, F>(self, f: F) -> FilterMap<Self, F> where
         Self: Sized, F: FnMut(Self::Item) -> Option<B>,
@@ -779,6 +785,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn enumerate(self) -> Enumerate<Self> where Self: Sized {
         Enumerate { iter: self, count: 0 }
@@ -825,6 +832,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn peekable(self) -> Peekable<Self> where Self: Sized {
         Peekable{iter: self, peeked: None}
@@ -886,6 +894,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn skip_while<P>(self, predicate: P) -> SkipWhile<Self, P> where
         Self: Sized, P: FnMut(&Self::Item) -> bool,
@@ -966,6 +975,7 @@ pub trait Iterator {
     /// the iteration should stop, but wasn't placed back into the iterator or
     /// some similar thing.
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn take_while<P>(self, predicate: P) -> TakeWhile<Self, P> where
         Self: Sized, P: FnMut(&Self::Item) -> bool,
@@ -990,6 +1000,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn skip(self, n: usize) -> Skip<Self> where Self: Sized {
         Skip { iter: self, n }
@@ -1022,6 +1033,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn take(self, n: usize) -> Take<Self> where Self: Sized, {
         Take { iter: self, n }
@@ -1065,6 +1077,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn scan<St, B, F>(self, initial_state: St, f: F) -> Scan<Self, St, F>
         where Self: Sized, F: FnMut(&mut St, Self::Item) -> Option<B>,
@@ -1103,6 +1116,7 @@ pub trait Iterator {
     /// assert_eq!(merged, "alphabetagamma");
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn flat_map<U, F>(self, f: F) -> FlatMap<Self, U, F>
         where Self: Sized, U: IntoIterator, F: FnMut(Self::Item) -> U,
@@ -1172,6 +1186,7 @@ pub trait Iterator {
     ///
     /// [`flat_map()`]: #method.flat_map
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "iterator_flatten", since = "1.29.0")]
     fn flatten(self) -> Flatten<Self>
     where Self: Sized, Self::Item: IntoIterator {
@@ -1233,6 +1248,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn fuse(self) -> Fuse<Self> where Self: Sized {
         Fuse{iter: self, done: false}
@@ -1314,6 +1330,7 @@ pub trait Iterator {
     /// Sum: 3
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn inspect<F>(self, f: F) -> Inspect<Self, F> where
         Self: Sized, F: FnMut(&Self::Item),
@@ -2190,6 +2207,7 @@ pub trait Iterator {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn rev(self) -> Rev<Self> where Self: Sized + DoubleEndedIterator {
         Rev{iter: self}
@@ -2256,6 +2274,7 @@ pub trait Iterator {
     /// assert_eq!(v_cloned, vec![1, 2, 3]);
     /// assert_eq!(v_map, vec![1, 2, 3]);
     /// ```
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     fn cloned<'a, T: 'a>(self) -> Cloned<Self>
         where Self: Sized + Iterator<Item=&'a T>, T: Clone
@@ -2288,6 +2307,7 @@ pub trait Iterator {
     /// assert_eq!(it.next(), Some(&3));
     /// assert_eq!(it.next(), Some(&1));
     /// ```
+    #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
     #[stable(feature = "rust1", since = "1.0.0")]
     #[inline]
     fn cycle(self) -> Cycle<Self> where Self: Sized + Clone {
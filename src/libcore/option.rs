@@ -189,6 +189,7 @@ impl<T> Option<T> {
     ///
     /// [`Some`]: #variant.Some
     #[inline]
+    #[must_use = "if you intended to assert that this has a value, consider `.unwrap()` instead"]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn is_some(&self) -> bool {
         match *self {
@@ -211,6 +212,7 @@ impl<T> Option<T> {
     ///
     /// [`None`]: #variant.None
     #[inline]
+    #[must_use = "if you intended to assert that this is `None`, use `assert!` on it instead"]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn is_none(&self) -> bool {
         !self.is_some()
@@ -1082,7 +1082,17 @@ impl<T: ?Sized> *const T {
     /// }
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[rustc_const_unstable(feature = "const_ptr_offset")]
     #[inline]
+    #[cfg(not(stage0))]
+    pub const unsafe fn offset(self, count: isize) -> *const T where T: Sized {
+        intrinsics::offset(self, count)
+    }
+
+    #[stable(feature = "rust1", since = "1.0.0")]
+    #[inline]
+    #[cfg(stage0)]
+    #[allow(missing_docs)]
     pub unsafe fn offset(self, count: isize) -> *const T where T: Sized {
         intrinsics::offset(self, count)
     }
@@ -1125,8 +1135,20 @@ impl<T: ?Sized> *const T {
     ///     ptr = ptr.wrapping_offset(step);
     /// }
     /// ```
+    #[stable(feature = "ptr_wrapping_offset", since = "1.16.0")]
+    #[rustc_const_unstable(feature = "const_ptr_offset")]
+    #[inline]
+    #[cfg(not(stage0))]
+    pub const fn wrapping_offset(self, count: isize) -> *const T where T: Sized {
+        unsafe {
+            intrinsics::arith_offset(self, count)
+        }
+    }
+
     #[stable(feature = "ptr_wrapping_offset", since = "1.16.0")]
     #[inline]
+    #[cfg(stage0)]
+    #[allow(missing_docs)]
     pub fn wrapping_offset(self, count: isize) -> *const T where T: Sized {
         unsafe {
             intrinsics::arith_offset(self, count)
@@ -1702,7 +1724,17 @@ impl<T: ?Sized> *mut T {
     /// }
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[rustc_const_unstable(feature = "const_ptr_offset")]
     #[inline]
+    #[cfg(not(stage0))]
+    pub const unsafe fn offset(self, count: isize) -> *mut T where T: Sized {
+        intrinsics::offset(self, count) as *mut T
+    }
+
+    #[stable(feature = "rust1", since = "1.0.0")]
+    #[inline]
+    #[cfg(stage0)]
+    #[allow(missing_docs)]
     pub unsafe fn offset(self, count: isize) -> *mut T where T: Sized {
         intrinsics::offset(self, count) as *mut T
     }
@@ -1744,8 +1776,20 @@ impl<T: ?Sized> *mut T {
     /// }
     /// assert_eq!(&data, &[0, 2, 0, 4, 0]);
     /// ```
+    #[stable(feature = "ptr_wrapping_offset", since = "1.16.0")]
+    #[rustc_const_unstable(feature = "const_ptr_offset")]
+    #[inline]
+    #[cfg(not(stage0))]
+    pub const fn wrapping_offset(self, count: isize) -> *mut T where T: Sized {
+        unsafe {
+            intrinsics::arith_offset(self, count) as *mut T
+        }
+    }
+
     #[stable(feature = "ptr_wrapping_offset", since = "1.16.0")]
     #[inline]
+    #[cfg(stage0)]
+    #[allow(missing_docs)]
     pub fn wrapping_offset(self, count: isize) -> *mut T where T: Sized {
         unsafe {
             intrinsics::arith_offset(self, count) as *mut T
@@ -288,6 +288,7 @@ impl<T, E> Result<T, E> {
     /// assert_eq!(x.is_ok(), false);
     /// ```
     #[inline]
+    #[must_use = "if you intended to assert that this is ok, consider `.unwrap()` instead"]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn is_ok(&self) -> bool {
         match *self {
@@ -312,6 +313,7 @@ impl<T, E> Result<T, E> {
     /// assert_eq!(x.is_err(), true);
     /// ```
     #[inline]
+    #[must_use = "if you intended to assert that this is err, consider `.unwrap_err()` instead"]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn is_err(&self) -> bool {
         !self.is_ok()
@@ -462,6 +464,7 @@ impl<T, E> Result<T, E> {
     /// }
     /// ```
     #[inline]
+    #[must_use = "this returns the mapped `Result`, without modifying the original"]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn map<U, F: FnOnce(T) -> U>(self, op: F) -> Result<U,E> {
         match self {
@@ -523,6 +526,7 @@ impl<T, E> Result<T, E> {
     /// assert_eq!(x.map_err(stringify), Err("error code: 13".to_string()));
     /// ```
     #[inline]
+    #[must_use = "this returns the mapped `Result`, without modifying the original"]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn map_err<F, O: FnOnce(E) -> F>(self, op: O) -> Result<T,F> {
         match self {
@@ -60,6 +60,7 @@
 //! user of the `DepNode` API of having to know how to compute the expected
 //! fingerprint for a given set of node parameters.
 
+use mir;
 use mir::interpret::GlobalId;
 use hir::def_id::{CrateNum, DefId, DefIndex, CRATE_DEF_INDEX};
 use hir::map::DefPathHash;
@@ -479,6 +480,8 @@ define_dep_nodes!( <'tcx>
     // Represents the MIR for a fn; also used as the task node for
     // things read/modify that MIR.
     [] MirConstQualif(DefId),
+    [] MirPromotability(DefId),
+    [] IsPromotable { def_id: DefId, location: mir::Location },
     [] MirBuilt(DefId),
     [] MirConst(DefId),
     [] MirValidated(DefId),
@@ -488,6 +491,7 @@ define_dep_nodes!( <'tcx>
     [] BorrowCheckKrate,
     [] BorrowCheck(DefId),
     [] MirBorrowCheck(DefId),
+    [] DisjointFields(DefId),
     [] UnsafetyCheckResult(DefId),
     [] UnsafeDeriveOnReprPacked(DefId),
 
@@ -528,6 +532,7 @@ define_dep_nodes!( <'tcx>
     [] TypeckBodiesKrate,
     [] TypeckTables(DefId),
     [] UsedTraitImports(DefId),
+    [] ClosureCaptures(DefId),
     [] HasTypeckTables(DefId),
     [] ConstEval { param_env: ParamEnvAnd<'tcx, GlobalId<'tcx>> },
     [] ConstEvalRaw { param_env: ParamEnvAnd<'tcx, GlobalId<'tcx>> },
@@ -575,6 +580,7 @@ define_dep_nodes!( <'tcx>
     [] CodegenFnAttrs(DefId),
     [] FnArgNames(DefId),
     [] RenderedConst(DefId),
+    [] ExternConstValue(DefId),
     [] DylibDepFormats(CrateNum),
     [] IsPanicRuntime(CrateNum),
     [] IsCompilerBuiltins(CrateNum),
@@ -582,6 +588,9 @@ define_dep_nodes!( <'tcx>
     [] HasPanicHandler(CrateNum),
     [input] ExternCrate(DefId),
     [eval_always] LintLevels,
+    // Lint passes mark attributes as used, which isn't tracked by the
+    // query system, so linting a module can't be replayed from the cache.
+    [eval_always] LintMod(DefId),
     [] Specializes { impl1: DefId, impl2: DefId },
     [input] InScopeTraits(DefIndex),
     [input] ModuleExports(DefId),
@@ -342,22 +342,23 @@ This works because `Box` is a pointer, so its size is well-known.
 "##,
 
 E0080: r##"
-This error indicates that the compiler was unable to sensibly evaluate an
-constant expression that had to be evaluated. Attempting to divide by 0
-or causing integer overflow are two ways to induce this error. For example:
+This error indicates that the compiler was unable to sensibly evaluate a
+constant expression that had to be evaluated. For example, the value of an
+extern static is not known at compile-time:
 
 ```compile_fail,E0080
-enum Enum {
-    X = (1 << 500),
-    Y = (1 / 0)
+#![allow(safe_extern_statics)]
+
+extern {
+    static FOO: u32;
 }
-```
 
-Ensure that the expressions given can be evaluated as the desired integer type.
-See the FFI section of the Reference for more information about using a custom
-integer type:
+static BAR: u32 = FOO;
+```
 
-https://doc.rust-lang.org/reference.html#ffi-attributes
+Failures that belong to one of the classes E0722 to E0728 are reported with
+that code instead. Attempting to divide by 0 or causing integer overflow, for
+example, are reported as E0727.
 "##,
 
 E0106: r##"
@@ -2130,10 +2131,155 @@ static X: u32 = 42;
 ```
 "##,
 
+E0722: r##"
+Constant evaluation read memory that was never initialized.
+
+Errors of this class are reported with this code, or through the `const_err`
+lint, in which case the code only shows up in the metadata of JSON
+diagnostics. The metadata also contains the offset of the first uninitialized
+byte.
+
+Erroneous code example:
+
+```compile_fail
+#![feature(const_fn_union)]
+
+union MaybeUninit {
+    init: u32,
+    uninit: (),
+}
+
+const X: u32 = unsafe { MaybeUninit { uninit: () }.init + 1 };
+```
+
+Make sure every byte that is read during constant evaluation has been
+written first.
+"##,
+
+E0723: r##"
+Constant evaluation accessed memory it is not allowed to access: a NULL
+pointer, a dangling pointer, or a location outside the bounds of the
+allocation the pointer points into.
+
+Errors of this class are reported with this code, or through the `const_err`
+lint, in which case the code only shows up in the metadata of JSON
+diagnostics. The metadata also contains the allocation and offset that were
+accessed, if known.
+
+Erroneous code example:
+
+```compile_fail
+#![feature(core_intrinsics)]
+
+const DATA: [u8; 3] = [1, 2, 3];
+const PAST_END: *const u8 = unsafe {
+    std::intrinsics::offset(&DATA as *const [u8; 3] as *const u8, 4)
+};
+```
+"##,
+
+E0724: r##"
+Constant evaluation needed the address of a pointer, or tried to use plain
+bytes as a pointer.
+
+Where an allocation ends up in memory is only decided when the program runs,
+so its address is unknown during constant evaluation. Pointers can still be
+stored, compared for equality and offset, but any computation that depends
+on the actual address cannot be evaluated at compile-time.
+
+Errors of this class are reported with this code, or through the `const_err`
+lint, in which case the code only shows up in the metadata of JSON
+diagnostics.
+
+Erroneous code example:
+
+```compile_fail
+#![feature(const_transmute)]
+
+const ADDR: usize = unsafe { std::mem::transmute::<&i32, usize>(&0) + 1 };
+```
+"##,
+
+E0725: r##"
+Constant evaluation tried to write to immutable memory, such as the memory
+of an immutable static, or to the memory of another static. This also covers
+memory that would become writable at runtime: a mutable reference in a
+constant, or heap memory in anything but an immutable static without interior
+mutability.
+
+Errors of this class are reported with this code, or through the `const_err`
+lint, in which case the code only shows up in the metadata of JSON
+diagnostics.
+
+Erroneous code example:
+
+```compile_fail,E0725
+#![feature(const_raw_ptr_deref)]
+
+static FOO: u32 = 42;
+
+static BAR: () = unsafe {
+    *(&FOO as *const u32 as *mut u32) = 5;
+};
+```
+"##,
+
+E0726: r##"
+Constant evaluation produced a value that is invalid for its type, for
+example a `bool` that is neither `true` nor `false`.
+
+Errors of this class are reported with this code, or through the `const_err`
+lint, in which case the code only shows up in the metadata of JSON
+diagnostics.
+
+Erroneous code example:
+
+```compile_fail,E0726
+#![feature(const_transmute)]
+
+static FOO: bool = unsafe { std::mem::transmute(3u8) };
+```
+"##,
+
+E0727: r##"
+Constant evaluation ran into code that would panic at runtime: an explicit
+`panic!`, an arithmetic overflow, a division by zero or an out-of-bounds
+index.
+
+Errors of this class are reported with this code, or through the `const_err`
+lint, in which case the code only shows up in the metadata of JSON
+diagnostics.
+
+Erroneous code example:
+
+```compile_fail
+const X: u8 = 255 + 1;
+```
+"##,
+
+E0728: r##"
+Constant evaluation exceeded one of its limits: the maximum number of stack
+frames, the step limit set by `-Z const-eval-step-limit`, or the memory limit
+set by `-Z const-eval-memory-limit`. It is also reported for evaluations that
+are detected to loop forever.
+
+Errors of this class are reported with this code, or through the `const_err`
+lint, in which case the code only shows up in the metadata of JSON
+diagnostics.
+
+Erroneous code example:
+
+```compile_fail
+const fn a() -> usize { b() }
+const fn b() -> usize { a() }
+const ARR: [i32; a()] = [5; 6];
+```
+"##,
+
 }
 
 
-register_diagnostics! {
+register_pending_diagnostics! {
 //  E0006, // merged with E0005
 //  E0101, // replaced with E0282
 //  E0102, // replaced with E0282
@@ -756,6 +756,18 @@ impl<'hir> Map<'hir> {
         bug!("expected foreign mod or inlined parent, found {}", self.node_to_string(parent))
     }
 
+    /// Returns the module with the given `DefId`, which may be the crate root,
+    /// along with its span and `NodeId`.
+    pub fn get_module(&self, module: DefId) -> (&'hir Mod, Span, NodeId) {
+        let node_id = self.as_local_node_id(module).unwrap();
+        self.read(node_id);
+        match self.find_entry(node_id).unwrap().node {
+            Node::Item(&Item { span, node: ItemKind::Mod(ref m), .. }) => (m, span, node_id),
+            Node::Crate => (&self.forest.krate.module, self.forest.krate.span, node_id),
+            _ => bug!("expected module, found {}", self.node_to_string(node_id)),
+        }
+    }
+
     pub fn expect_item(&self, id: NodeId) -> &'hir Item {
         match self.find(id) { // read recorded by `find`
             Some(Node::Item(item)) => item,
@@ -22,7 +22,7 @@ pub use self::UnsafeSource::*;
 
 use hir::def::Def;
 use hir::def_id::{DefId, DefIndex, LocalDefId, CRATE_DEF_INDEX};
-use util::nodemap::{NodeMap, FxHashSet};
+use util::nodemap::{NodeMap, FxHashMap, FxHashSet};
 use mir::mono::Linkage;
 
 use syntax_pos::{Span, DUMMY_SP, symbol::InternedString};
@@ -31,7 +31,7 @@ use rustc_target::spec::abi::Abi;
 use syntax::ast::{self, CrateSugar, Ident, Name, NodeId, DUMMY_NODE_ID, AsmDialect};
 use syntax::ast::{Attribute, Lit, StrStyle, FloatTy, IntTy, UintTy};
 use syntax::attr::InlineAttr;
-use syntax::ext::hygiene::SyntaxContext;
+use syntax::ext::hygiene::{Mark, SyntaxContext};
 use syntax::ptr::P;
 use syntax::symbol::{Symbol, keywords};
 use syntax::tokenstream::TokenStream;
@@ -2398,6 +2398,10 @@ pub type TraitMap = NodeMap<Vec<TraitCandidate>>;
 // imported.
 pub type GlobMap = NodeMap<FxHashSet<Name>>;
 
+// Map from the expansion of each resolved macro invocation to the macro it
+// invoked.
+pub type MacroDefMap = FxHashMap<Mark, DefId>;
+
 
 pub fn provide(providers: &mut Providers<'_>) {
     providers.describe_def = map::describe_def;
@@ -36,6 +36,20 @@ impl_stable_hash_for!(struct mir::UpvarDecl { debug_name, var_hir_id, by_ref, mu
 impl_stable_hash_for!(struct mir::BasicBlockData<'tcx> { statements, terminator, is_cleanup });
 impl_stable_hash_for!(struct mir::UnsafetyViolation { source_info, description, details, kind });
 impl_stable_hash_for!(struct mir::UnsafetyCheckResult { violations, unsafe_blocks });
+impl_stable_hash_for!(enum mir::PromotionCandidate {
+    Ref(location),
+    Argument { bb, index },
+});
+impl_stable_hash_for!(enum mir::Unpromotable {
+    NotATemporaryBorrow,
+    MutableBorrow,
+    InteriorMutability,
+    NeedsDrop,
+    FunctionArgument,
+    ComplexTemporary,
+    NotConst,
+});
+impl_stable_hash_for!(struct mir::Promotability { candidates, unpromotable, const_qualif });
 
 impl_stable_hash_for!(enum mir::BorrowKind {
     Shared,
@@ -203,6 +217,7 @@ impl_stable_hash_for!(impl<'gcx> for enum mir::StatementKind<'gcx> [ mir::Statem
     EscapeToRaw(place),
     Retag { fn_entry, two_phase, place },
     AscribeUserType(place, variance, c_ty),
+    CoverageCounter { index, num_counters },
     Nop,
     InlineAsm { asm, outputs, inputs },
 });
@@ -197,6 +197,7 @@ for ty::adjustment::Adjust<'gcx> {
 impl_stable_hash_for!(struct ty::adjustment::Adjustment<'tcx> { kind, target });
 impl_stable_hash_for!(struct ty::adjustment::OverloadedDeref<'tcx> { region, mutbl });
 impl_stable_hash_for!(struct ty::UpvarBorrow<'tcx> { kind, region });
+impl_stable_hash_for!(struct ty::CapturedUpvar<'tcx> { var_hir_id, name, capture, span });
 impl_stable_hash_for!(enum ty::adjustment::AllowTwoPhase {
     Yes,
     No
@@ -355,7 +356,7 @@ impl<'a> HashStable<StableHashingContext<'a>> for mir::interpret::AllocId {
         ty::tls::with_opt(|tcx| {
             trace!("hashing {:?}", *self);
             let tcx = tcx.expect("can't hash AllocIds during hir lowering");
-            let alloc_kind = tcx.alloc_map.lock().get(*self);
+            let alloc_kind = tcx.alloc_map.get(*self);
             alloc_kind.hash_stable(hcx, hasher);
         });
     }
@@ -395,8 +396,10 @@ impl_stable_hash_for!(enum mir::interpret::ErrorHandled {
 
 impl_stable_hash_for!(struct mir::interpret::FrameInfo<'tcx> {
     call_site,
+    span,
     lint_root,
-    instance
+    instance,
+    promoted
 });
 
 impl_stable_hash_for!(struct ty::ClosureSubsts<'tcx> { substs });
@@ -419,6 +422,7 @@ impl_stable_hash_for!(
         InvalidBool,
         InvalidNullPointerUsage,
         ReadPointerAsBytes,
+        ReadPartialPointer,
         ReadBytesAsPointer,
         ReadForeignStatic,
         InvalidPointerMath,
@@ -428,13 +432,15 @@ impl_stable_hash_for!(
         TlsOutOfBounds,
         CalledClosureAsFunction,
         VtableForArgumentlessMethod,
-        ModifiedConstantMemory,
         ModifiedStatic,
         AssumptionNotHeld,
         InlineAsm,
         ReallocateNonBasePtr,
         DeallocateNonBasePtr,
         HeapAllocZeroBytes,
+        LeakedAllocation,
+        MutableHeapAllocation,
+        EscapingMutRef,
         Unreachable,
         ReadFromReturnPointer,
         UnimplementedTraitSelection,
@@ -449,7 +455,11 @@ impl_stable_hash_for!(
         GeneratorResumedAfterPanic,
         ReferencedConstant,
         InfiniteLoop,
+        StepLimitReached,
+        InvalidPointerComparison,
+        MemoryExhausted,
         ReadUndefBytes(offset),
+        DanglingPointer(offset),
         InvalidDiscriminant(val),
         Panic { msg, file, line, col },
         MachineError(err),
@@ -460,6 +470,8 @@ impl_stable_hash_for!(
         UnterminatedCString(ptr),
         PointerOutOfBounds { ptr, check, allocation_size },
         InvalidBoolOp(bop),
+        PointerAsInteger(op),
+        ModifiedConstantMemory(def_span),
         Unimplemented(s),
         BoundsCheck { len, index },
         Intrinsic(s),
@@ -74,6 +74,12 @@ declare_lint! {
     "detect unused, unexported items"
 }
 
+declare_lint! {
+    pub UNUSED_TUPLE_STRUCT_FIELDS,
+    Allow,
+    "detect tuple struct fields that are never read"
+}
+
 declare_lint! {
     pub UNREACHABLE_CODE,
     Warn,
@@ -227,6 +233,24 @@ declare_lint! {
     "unnecessary use of an `unsafe` block"
 }
 
+declare_lint! {
+    pub UNUSED_ATTRIBUTES,
+    Warn,
+    "detects attributes that were not used by the compiler"
+}
+
+declare_lint! {
+    pub UNUSED_PARENS,
+    Warn,
+    "`if`, `match`, `while` and `return` do not need parentheses"
+}
+
+declare_lint! {
+    pub ILL_FORMED_ATTRIBUTE_INPUT,
+    Warn,
+    "ill-formed attribute inputs that were previously accepted and used in practice"
+}
+
 declare_lint! {
     pub UNUSED_MUT,
     Warn,
@@ -350,12 +374,24 @@ declare_lint! {
      cannot be referred to by absolute paths"
 }
 
+declare_lint! {
+    pub BORROWCK_MIGRATION,
+    Warn,
+    "detects code rejected by the MIR borrow checker that the AST borrow checker accepted"
+}
+
 declare_lint! {
     pub EXPLICIT_OUTLIVES_REQUIREMENTS,
     Allow,
     "outlives requirements can be inferred"
 }
 
+declare_lint! {
+    pub UNFULFILLED_LINT_EXPECTATIONS,
+    Warn,
+    "detects `#[expect]` attributes whose lint was never emitted"
+}
+
 /// Some lints that are buffered from `libsyntax`. See `syntax::early_buffered_lints`.
 pub mod parser {
     declare_lint! {
@@ -382,6 +418,7 @@ impl LintPass for HardwiredLints {
             UNUSED_VARIABLES,
             UNUSED_ASSIGNMENTS,
             DEAD_CODE,
+            UNUSED_TUPLE_STRUCT_FIELDS,
             UNREACHABLE_CODE,
             UNREACHABLE_PATTERNS,
             UNUSED_MACROS,
@@ -407,6 +444,9 @@ impl LintPass for HardwiredLints {
             INCOHERENT_FUNDAMENTAL_IMPLS,
             DEPRECATED,
             UNUSED_UNSAFE,
+            UNUSED_ATTRIBUTES,
+            UNUSED_PARENS,
+            ILL_FORMED_ATTRIBUTE_INPUT,
             UNUSED_MUT,
             UNCONDITIONAL_RECURSION,
             SINGLE_USE_LIFETIMES,
@@ -426,6 +466,8 @@ impl LintPass for HardwiredLints {
             PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
             MACRO_USE_EXTERN_CRATE,
             MACRO_EXPANDED_MACRO_EXPORTS_ACCESSED_BY_ABSOLUTE_PATHS,
+            BORROWCK_MIGRATION,
+            UNFULFILLED_LINT_EXPECTATIONS,
             parser::QUESTION_MARK_MACRO_SEP,
         )
     }
@@ -443,6 +485,7 @@ pub enum BuiltinLintDiagnostics {
     MacroExpandedMacroExportsAccessedByAbsolutePaths(Span),
     ElidedLifetimesInPaths(usize, Span, bool, Span, String),
     UnknownCrateTypes(Span, String, String),
+    UnusedImports(String, Vec<(Span, String)>),
 }
 
 impl BuiltinLintDiagnostics {
@@ -529,6 +572,15 @@ impl BuiltinLintDiagnostics {
                     Applicability::MaybeIncorrect
                 );
             }
+            BuiltinLintDiagnostics::UnusedImports(message, replaces) => {
+                if !replaces.is_empty() {
+                    db.tool_only_multipart_suggestion(
+                        &message,
+                        replaces,
+                        Applicability::MachineApplicable,
+                    );
+                }
+            }
         }
     }
 }
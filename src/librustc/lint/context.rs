@@ -27,25 +27,28 @@
 use self::TargetLint::*;
 
 use std::slice;
-use rustc_data_structures::sync::ReadGuard;
-use lint::{EarlyLintPassObject, LateLintPassObject};
+use rustc_data_structures::sync::{ParallelIterator, ReadGuard, par_iter};
+use lint::{EarlyLintPassObject, LateLintPassCtor, LateLintPassObject};
 use lint::{Level, Lint, LintId, LintPass, LintBuffer};
-use lint::builtin::BuiltinLintDiagnostics;
+use lint::builtin::{BuiltinLintDiagnostics, UNFULFILLED_LINT_EXPECTATIONS};
 use lint::levels::{LintLevelSets, LintLevelsBuilder};
 use middle::privacy::AccessLevels;
 use rustc_serialize::{Decoder, Decodable, Encoder, Encodable};
 use session::{config, early_error, Session};
 use ty::{self, TyCtxt, Ty};
+use ty::query::{queries, Providers};
 use ty::layout::{LayoutError, LayoutOf, TyLayout};
 use util::nodemap::FxHashMap;
 
 use std::default::Default as StdDefault;
 use syntax::ast;
+use syntax::attr;
 use syntax::edition;
+use syntax::symbol::Symbol;
 use syntax_pos::{MultiSpan, Span, symbol::LocalInternedString};
 use errors::DiagnosticBuilder;
 use hir;
-use hir::def_id::LOCAL_CRATE;
+use hir::def_id::{CRATE_DEF_INDEX, DefId, LOCAL_CRATE};
 use hir::intravisit as hir_visit;
 use syntax::visit as ast_visit;
 
@@ -63,6 +66,7 @@ pub struct LintStore {
     pre_expansion_passes: Option<Vec<EarlyLintPassObject>>,
     early_passes: Option<Vec<EarlyLintPassObject>>,
     late_passes: Option<Vec<LateLintPassObject>>,
+    late_module_passes: Vec<LateLintPassCtor>,
 
     /// Lints indexed by name.
     by_name: FxHashMap<String, TargetLint>,
@@ -159,6 +163,7 @@ impl LintStore {
             pre_expansion_passes: Some(vec![]),
             early_passes: Some(vec![]),
             late_passes: Some(vec![]),
+            late_module_passes: vec![],
             by_name: Default::default(),
             future_incompatible: Default::default(),
             lint_groups: Default::default(),
@@ -206,6 +211,19 @@ impl LintStore {
         self.late_passes.as_mut().unwrap().push(pass);
     }
 
+    /// Registers a late lint pass that is run on each module separately, under
+    /// the `lint_mod` query. Modules may be linted in parallel, so every module
+    /// gets a fresh pass from `ctor`. The pass can't rely on seeing the crate as
+    /// a whole: it doesn't get `check_crate` calls, and it sees no lint
+    /// attributes above the module being checked.
+    pub fn register_late_mod_pass(&mut self,
+                                  sess: Option<&Session>,
+                                  from_plugin: bool,
+                                  ctor: LateLintPassCtor) {
+        self.push_pass(sess, from_plugin, &ctor());
+        self.late_module_passes.push(ctor);
+    }
+
     // Helper method for register_early/late_pass
     fn push_pass<P: LintPass + ?Sized + 'static>(&mut self,
                                         sess: Option<&Session>,
@@ -311,6 +329,9 @@ impl LintStore {
         }
     }
 
+    /// Registers `old_name` as a former name of the lint `new_name`. Using
+    /// the old name in a lint attribute warns and sets the level of the new
+    /// lint. Tool lints are registered with their `tool::` prefix.
     pub fn register_renamed(&mut self, old_name: &str, new_name: &str) {
         let target = match self.by_name.get(new_name) {
             Some(&Id(lint_id)) => lint_id.clone(),
@@ -319,6 +340,8 @@ impl LintStore {
         self.by_name.insert(old_name.to_string(), Renamed(new_name.to_string(), target));
     }
 
+    /// Registers `name` as a lint that no longer exists, warning with
+    /// `reason` whenever it is used.
     pub fn register_removed(&mut self, name: &str, reason: &str) {
         self.by_name.insert(name.into(), Removed(reason.into()));
     }
@@ -354,7 +377,23 @@ impl LintStore {
                                    sess: &Session,
                                    lint_name: &str,
                                    level: Level) {
-        let db = match self.check_lint_name(lint_name, None) {
+        let (tool_name, lint_name_in_tool) = match lint_name.find("::") {
+            Some(pos) => (Some(&lint_name[..pos]), &lint_name[pos + 2..]),
+            None => (None, lint_name),
+        };
+        let tool_name = match tool_name {
+            Some(tool_name) if !attr::is_known_lint_tool(ast::Ident::from_str(tool_name)) => {
+                let mut db = struct_err!(sess, E0710,
+                                         "an unknown tool name found in scoped lint: `{}`",
+                                         lint_name);
+                db.note(&format!("requested on the command line with `{} {}`",
+                                 level.as_cmdline_flag(), lint_name));
+                db.emit();
+                return;
+            }
+            tool_name => tool_name.map(|tool_name| Symbol::intern(tool_name).as_str()),
+        };
+        let db = match self.check_lint_name(lint_name_in_tool, tool_name) {
             CheckLintNameResult::Ok(_) => None,
             CheckLintNameResult::Warning(ref msg, _) => {
                 Some(sess.struct_warn(msg))
@@ -375,12 +414,7 @@ impl LintStore {
 
         if let Some(mut db) = db {
             let msg = format!("requested on the command line with `{} {}`",
-                              match level {
-                                  Level::Allow => "-A",
-                                  Level::Warn => "-W",
-                                  Level::Deny => "-D",
-                                  Level::Forbid => "-F",
-                              },
+                              level.as_cmdline_flag(),
                               lint_name);
             db.note(&msg);
             db.emit();
@@ -405,9 +439,15 @@ impl LintStore {
             lint_name.to_string()
         };
         // If the lint was scoped with `tool::` check if the tool lint exists
-        if let Some(_) = tool_name {
+        if let Some(tool_name) = tool_name {
             match self.by_name.get(&complete_name) {
                 None => match self.lint_groups.get(&*complete_name) {
+                    // When the tool is running it has registered all of its
+                    // lints, so this one doesn't exist. Otherwise there's no
+                    // way to tell.
+                    None if self.has_tool_lints(&tool_name) => {
+                        return CheckLintNameResult::NoLint;
+                    }
                     None => return CheckLintNameResult::Tool(Err((None, String::new()))),
                     Some(LintGroup { lint_ids, .. }) => {
                         return CheckLintNameResult::Tool(Ok(&lint_ids));
@@ -455,6 +495,13 @@ impl LintStore {
         }
     }
 
+    /// Returns whether a tool registered any lints scoped with `tool_name::`.
+    fn has_tool_lints(&self, tool_name: &str) -> bool {
+        let prefix = format!("{}::", tool_name);
+        self.by_name.keys().any(|name| name.starts_with(&prefix)) ||
+            self.lint_groups.keys().any(|name| name.starts_with(&prefix))
+    }
+
     fn check_tool_name_for_backwards_compat(
         &self,
         lint_name: &str,
@@ -510,6 +557,10 @@ pub struct LateContext<'a, 'tcx: 'a> {
 
     /// Generic type parameters in scope for the item we are in.
     pub generics: Option<&'tcx hir::Generics>,
+
+    /// We are only looking at one module; nested modules are left to their
+    /// own `lint_mod` query.
+    only_module: bool,
 }
 
 /// Context for lint checking of the AST, after expansion, before lowering to
@@ -760,7 +811,7 @@ impl<'a> LintContext<'a> for EarlyContext<'a> {
                           f: F)
         where F: FnOnce(&mut Self)
     {
-        let push = self.builder.push(attrs);
+        let push = self.builder.push(attrs, None);
         self.check_id(id);
         self.enter_attrs(attrs);
         f(self);
@@ -781,6 +832,12 @@ impl<'a, 'tcx> LateContext<'a, 'tcx> {
     pub fn current_lint_root(&self) -> ast::NodeId {
         self.last_ast_node_with_lint_attrs
     }
+
+    fn process_mod(&mut self, m: &'tcx hir::Mod, s: Span, n: ast::NodeId) {
+        run_lints!(self, check_mod, m, s, n);
+        hir_visit::walk_mod(self, m, n);
+        run_lints!(self, check_mod_post, m, s, n);
+    }
 }
 
 impl<'a, 'tcx> LayoutOf for LateContext<'a, 'tcx> {
@@ -912,9 +969,9 @@ impl<'a, 'tcx> hir_visit::Visitor<'tcx> for LateContext<'a, 'tcx> {
     }
 
     fn visit_mod(&mut self, m: &'tcx hir::Mod, s: Span, n: ast::NodeId) {
-        run_lints!(self, check_mod, m, s, n);
-        hir_visit::walk_mod(self, m, n);
-        run_lints!(self, check_mod_post, m, s, n);
+        if !self.only_module {
+            self.process_mod(m, s, n);
+        }
     }
 
     fn visit_local(&mut self, l: &'tcx hir::Local) {
@@ -1185,13 +1242,69 @@ impl<'a> ast_visit::Visitor<'a> for EarlyContext<'a> {
 }
 
 
+/// Runs the per-module late lint passes on one module, leaving nested modules
+/// to their own queries, so that modules can be linted independently of each
+/// other.
+fn lint_mod<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>, module_def_id: DefId) {
+    let access_levels = &tcx.privacy_access_levels(LOCAL_CRATE);
+
+    let store = tcx.sess.lint_store.borrow();
+    let passes = store.late_module_passes.iter().map(|ctor| ctor()).collect();
+
+    let mut cx = LateContext {
+        tcx,
+        tables: &ty::TypeckTables::empty(None),
+        param_env: ty::ParamEnv::empty(),
+        access_levels,
+        lint_sess: LintSession {
+            lints: store,
+            passes: Some(passes),
+        },
+        last_ast_node_with_lint_attrs: tcx.hir().as_local_node_id(module_def_id).unwrap(),
+        generics: None,
+        only_module: true,
+    };
+
+    let (module, span, node_id) = tcx.hir().get_module(module_def_id);
+    cx.process_mod(module, span, node_id);
+
+    // Visit the crate attributes
+    if node_id == ast::CRATE_NODE_ID {
+        for attr in tcx.hir().krate_attrs() {
+            hir_visit::Visitor::visit_attribute(&mut cx, attr);
+        }
+    }
+
+}
+
+pub fn provide(providers: &mut Providers<'_>) {
+    providers.lint_mod = lint_mod;
+}
+
 /// Perform lint checking on a crate.
 ///
 /// Consumes the `lint_store` field of the `Session`.
 pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    // Run the per-module passes first, on all modules in parallel. Their
+    // diagnostics are still reported in source order.
+    let krate = tcx.hir().krate();
+    let modules = Some(DefId::local(CRATE_DEF_INDEX)).into_iter()
+        .chain(krate.items.values().filter_map(|item| match item.node {
+            hir::ItemKind::Mod(..) => Some(tcx.hir().local_def_id(item.id)),
+            _ => None,
+        }))
+        .collect::<Vec<_>>();
+    let handler = tcx.sess.diagnostic();
+    handler.in_source_order(|| {
+        par_iter(&modules).for_each(|&module_def_id| {
+            handler.with_order_key(tcx.diagnostic_order_key(module_def_id), || {
+                queries::lint_mod::ensure(tcx, module_def_id)
+            })
+        });
+    });
+
     let access_levels = &tcx.privacy_access_levels(LOCAL_CRATE);
 
-    let krate = tcx.hir().krate();
     let passes = tcx.sess.lint_store.borrow_mut().late_passes.take();
 
     let passes = {
@@ -1206,6 +1319,7 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
             },
             last_ast_node_with_lint_attrs: ast::CRATE_NODE_ID,
             generics: None,
+            only_module: false,
         };
 
         // Visit the whole crate.
@@ -1223,6 +1337,40 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
 
     // Put the lint store levels and passes back in the session.
     tcx.sess.lint_store.borrow_mut().late_passes = passes;
+
+    // Every lint has had its chance to fire by now, so report the `#[expect]`
+    // attributes that were not fulfilled.
+    check_lint_expectations(tcx);
+}
+
+fn check_lint_expectations<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    let mut unfulfilled = tcx.sess.lint_expectations.borrow()
+        .iter()
+        .filter(|expectation| !expectation.fulfilled)
+        .map(|expectation| {
+            let id = expectation.id.unwrap_or(ast::CRATE_NODE_ID);
+            (expectation.span, id, expectation.lint_name, expectation.reason)
+        })
+        .collect::<Vec<_>>();
+    unfulfilled.sort_by_key(|&(span, ..)| span);
+
+    for (span, id, lint_name, reason) in unfulfilled {
+        let msg = format!("this lint expectation for `{}` is unfulfilled", lint_name);
+        let mut err = tcx.struct_span_lint_node(UNFULFILLED_LINT_EXPECTATIONS, id, span, &msg);
+        if let Some(rationale) = reason {
+            err.note(&rationale.as_str());
+        }
+        err.emit();
+    }
+}
+
+/// Emits `buffered` without running any lint pass, for when compilation is
+/// about to abort before the early lint pass could run.
+pub fn emit_buffered_early_lints(sess: &Session, krate: &ast::Crate, buffered: LintBuffer) {
+    let mut cx = EarlyContext::new(sess, krate, Some(vec![]), buffered);
+    cx.with_lint_attrs(ast::CRATE_NODE_ID, &krate.attrs, |cx| {
+        ast_visit::walk_crate(cx, krate);
+    });
 }
 
 pub fn check_ast_crate(
@@ -24,11 +24,14 @@ use syntax::attr;
 use syntax::feature_gate;
 use syntax::source_map::MultiSpan;
 use syntax::symbol::Symbol;
-use util::nodemap::FxHashMap;
+use util::nodemap::{FxHashMap, FxHashSet};
 
 pub struct LintLevelSets {
     list: Vec<LintSet>,
     lint_cap: Level,
+    // Lints named by `-Z force-lints`, which neither `--cap-lints` nor the
+    // driver's caps apply to.
+    forced: FxHashSet<LintId>,
 }
 
 enum LintSet {
@@ -49,6 +52,7 @@ impl LintLevelSets {
         let mut me = LintLevelSets {
             list: Vec::new(),
             lint_cap: Level::Forbid,
+            forced: Default::default(),
         };
         me.process_command_line(sess);
         return me
@@ -63,21 +67,30 @@ impl LintLevelSets {
         let mut specs = FxHashMap::default();
         self.lint_cap = sess.opts.lint_cap.unwrap_or(Level::Forbid);
 
+        for lint_name in &sess.opts.debugging_opts.force_lints {
+            let lint_name = lint_name.replace("-", "_");
+            match store.find_lints(&lint_name) {
+                Ok(ids) => self.forced.extend(ids),
+                Err(_) => {
+                    sess.err(&format!("unknown lint in `-Z force-lints`: `{}`", lint_name));
+                }
+            }
+        }
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             store.check_lint_name_cmdline(sess, &lint_name, level);
 
-            // If the cap is less than this specified level, e.g., if we've got
-            // `--cap-lints allow` but we've also got `-D foo` then we ignore
-            // this specification as the lint cap will set it to allow anyway.
-            let level = cmp::min(level, self.lint_cap);
-
+            // The level is stored as requested, even if it's above the cap.
+            // Capping happens in `get_lint_level` so that the lint's notes
+            // can still name the flag's own level, and so that a lint named
+            // by `-Z force-lints` escapes the cap.
             let lint_flag_val = Symbol::intern(lint_name);
             let ids = match store.find_lints(&lint_name) {
                 Ok(ids) => ids,
                 Err(_) => continue, // errors handled in check_lint_name_cmdline above
             };
             for id in ids {
-                let src = LintSource::CommandLine(lint_flag_val);
+                let src = LintSource::CommandLine(lint_flag_val, level);
                 specs.insert(id, (level, src));
             }
         }
@@ -116,17 +129,25 @@ impl LintLevelSets {
             }
         }
 
-        // Ensure that we never exceed the `--cap-lints` argument.
-        level = cmp::min(level, self.lint_cap);
-
-        if let Some(driver_level) = sess.driver_lint_caps.get(&LintId::of(lint)) {
-            // Ensure that we never exceed driver level.
-            level = cmp::min(*driver_level, level);
-        }
+        // Ensure that we never exceed `--cap-lints` or the driver's cap.
+        level = cmp::min(level, self.lint_id_cap(LintId::of(lint), sess));
 
         return (level, src)
     }
 
+    /// Returns the highest level `id` may reach: the lower of `--cap-lints`
+    /// and any cap the driver set for it, or `Forbid` if the lint was named
+    /// by `-Z force-lints`.
+    fn lint_id_cap(&self, id: LintId, sess: &Session) -> Level {
+        if self.forced.contains(&id) {
+            return Level::Forbid
+        }
+        match sess.driver_lint_caps.get(&id) {
+            Some(&driver_level) => cmp::min(driver_level, self.lint_cap),
+            None => self.lint_cap,
+        }
+    }
+
     fn get_lint_id_level(&self,
                          id: LintId,
                          mut idx: u32,
@@ -194,8 +215,11 @@ impl<'a> LintLevelsBuilder<'a> {
     /// * Lint attributes are validated, e.g., a #[forbid] can't be switched to
     ///   #[allow]
     ///
+    /// `node_id` is the node carrying `attrs`, if it outlives HIR lowering, and is
+    /// where unfulfilled `#[expect]` attributes get reported.
+    ///
     /// Don't forget to call `pop`!
-    pub fn push(&mut self, attrs: &[ast::Attribute]) -> BuilderPush {
+    pub fn push(&mut self, attrs: &[ast::Attribute], node_id: Option<ast::NodeId>) -> BuilderPush {
         let mut specs = FxHashMap::default();
         let store = self.sess.lint_store.borrow();
         let sess = self.sess;
@@ -300,9 +324,29 @@ impl<'a> LintLevelsBuilder<'a> {
                     None
                 };
                 let name = word.name();
-                match store.check_lint_name(&name.as_str(), tool_name) {
+                let result = store.check_lint_name(&name.as_str(), tool_name);
+
+                // A level set through a renamed lint still applies to the lint
+                // it was renamed to, whether or not we warn about the old name.
+                if let CheckLintNameResult::Warning(_, Some(ref new_name)) = result {
+                    if let Ok(ids) = store.find_lints(new_name) {
+                        let new_name = Symbol::intern(new_name);
+                        let src = LintSource::Node(new_name, level, li.span, reason);
+                        if level == Level::Expect {
+                            sess.expect_lint(li.span, new_name, reason, node_id);
+                        }
+                        for id in ids {
+                            specs.insert(id, (level, src));
+                        }
+                    }
+                }
+
+                match result {
                     CheckLintNameResult::Ok(ids) => {
-                        let src = LintSource::Node(name, li.span, reason);
+                        let src = LintSource::Node(name, level, li.span, reason);
+                        if level == Level::Expect {
+                            sess.expect_lint(li.span, name, reason, node_id);
+                        }
                         for id in ids {
                             specs.insert(*id, (level, src));
                         }
@@ -312,9 +356,11 @@ impl<'a> LintLevelsBuilder<'a> {
                         match result {
                             Ok(ids) => {
                                 let complete_name = &format!("{}::{}", tool_name.unwrap(), name);
-                                let src = LintSource::Node(
-                                    Symbol::intern(complete_name), li.span, reason
-                                );
+                                let complete_name = Symbol::intern(complete_name);
+                                let src = LintSource::Node(complete_name, level, li.span, reason);
+                                if level == Level::Expect {
+                                    sess.expect_lint(li.span, complete_name, reason, node_id);
+                                }
                                 for id in ids {
                                     specs.insert(*id, (level, src));
                                 }
@@ -346,17 +392,16 @@ impl<'a> LintLevelsBuilder<'a> {
                                 ).emit();
 
                                 let src = LintSource::Node(
-                                    Symbol::intern(&new_lint_name), li.span, reason
+                                    Symbol::intern(&new_lint_name), level, li.span, reason
                                 );
                                 for id in ids {
                                     specs.insert(*id, (level, src));
                                 }
                             }
                             Err((None, _)) => {
-                                // If Tool(Err(None, _)) is returned, then either the lint does not
-                                // exist in the tool or the code was not compiled with the tool and
-                                // therefore the lint was never added to the `LintStore`. To detect
-                                // this is the responsibility of the lint tool.
+                                // If Tool(Err(None, _)) is returned, then the code was not
+                                // compiled with the tool and therefore its lints were never
+                                // added to the `LintStore`, so the lint can't be checked.
                             }
                         }
                     }
@@ -391,7 +436,10 @@ impl<'a> LintLevelsBuilder<'a> {
                                                                     self.cur,
                                                                     Some(&specs),
                                                                     self.sess);
-                        let msg = format!("unknown lint: `{}`", name);
+                        let msg = match tool_name {
+                            Some(tool_name) => format!("unknown lint: `{}::{}`", tool_name, name),
+                            None => format!("unknown lint: `{}`", name),
+                        };
                         let mut db = lint::struct_lint_level(self.sess,
                                                 lint,
                                                 level,
@@ -423,17 +471,23 @@ impl<'a> LintLevelsBuilder<'a> {
             if level == Level::Forbid {
                 continue
             }
+            // A `forbid` only locks the level in when it can actually take
+            // effect; under a lower cap it's no stronger than the cap itself,
+            // so overriding it is fine.
+            if self.sets.lint_id_cap(*id, self.sess) < Level::Forbid {
+                continue
+            }
             let forbid_src = match self.sets.get_lint_id_level(*id, self.cur, None) {
                 (Some(Level::Forbid), src) => src,
                 _ => continue,
             };
             let forbidden_lint_name = match forbid_src {
                 LintSource::Default => id.to_string(),
-                LintSource::Node(name, _, _) => name.to_string(),
-                LintSource::CommandLine(name) => name.to_string(),
+                LintSource::Node(name, ..) => name.to_string(),
+                LintSource::CommandLine(name, _) => name.to_string(),
             };
             let (lint_attr_name, lint_attr_span) = match *src {
-                LintSource::Node(name, span, _) => (name, span),
+                LintSource::Node(name, _, span, _) => (name, span),
                 _ => continue,
             };
             let mut diag_builder = struct_span_err!(self.sess,
@@ -446,14 +500,14 @@ impl<'a> LintLevelsBuilder<'a> {
             diag_builder.span_label(lint_attr_span, "overruled by previous forbid");
             match forbid_src {
                 LintSource::Default => {},
-                LintSource::Node(_, forbid_source_span, reason) => {
+                LintSource::Node(_, _, forbid_source_span, reason) => {
                     diag_builder.span_label(forbid_source_span,
                                             "`forbid` level set here");
                     if let Some(rationale) = reason {
                         diag_builder.note(&rationale.as_str());
                     }
                 },
-                LintSource::CommandLine(_) => {
+                LintSource::CommandLine(..) => {
                     diag_builder.note("`forbid` lint level was set on command line");
                 }
             }
@@ -553,9 +607,11 @@ impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
         let LintLevelSets {
             ref list,
             lint_cap,
+            ref forced,
         } = *sets;
 
         lint_cap.hash_stable(hcx, hasher);
+        forced.hash_stable(hcx, hasher);
 
         hcx.while_hashing_spans(true, |hcx| {
             list.len().hash_stable(hcx, hasher);
@@ -33,11 +33,12 @@ pub use self::LintSource::*;
 
 use rustc_data_structures::sync::{self, Lrc};
 
-use errors::{DiagnosticBuilder, DiagnosticId};
+use errors::{self, DiagnosticBuilder, DiagnosticId, FutureIncompatLint};
 use hir::def_id::{CrateNum, LOCAL_CRATE};
 use hir::intravisit;
 use hir;
-use lint::builtin::BuiltinLintDiagnostics;
+use lint::builtin::{BuiltinLintDiagnostics, ILL_FORMED_ATTRIBUTE_INPUT, UNUSED_ATTRIBUTES};
+use lint::builtin::UNUSED_PARENS;
 use lint::builtin::parser::QUESTION_MARK_MACRO_SEP;
 use session::{Session, DiagnosticMessageId};
 use std::{hash, ptr};
@@ -53,8 +54,8 @@ use ty::query::Providers;
 use util::nodemap::NodeMap;
 
 pub use lint::context::{LateContext, EarlyContext, LintContext, LintStore,
-                        check_crate, check_ast_crate, CheckLintNameResult,
-                        FutureIncompatibleInfo, BufferedEarlyLint};
+                        check_crate, check_ast_crate, emit_buffered_early_lints,
+                        CheckLintNameResult, FutureIncompatibleInfo, BufferedEarlyLint};
 
 /// Specification of a single lint.
 #[derive(Copy, Clone, Debug)]
@@ -92,6 +93,9 @@ impl Lint {
     pub fn from_parser_lint_id(lint_id: BufferedEarlyLintId) -> &'static Self {
         match lint_id {
             BufferedEarlyLintId::QuestionMarkMacroSep => QUESTION_MARK_MACRO_SEP,
+            BufferedEarlyLintId::UnusedAttributes => UNUSED_ATTRIBUTES,
+            BufferedEarlyLintId::UnusedParens => UNUSED_PARENS,
+            BufferedEarlyLintId::IllFormedAttributeInput => ILL_FORMED_ATTRIBUTE_INPUT,
         }
     }
 
@@ -383,6 +387,8 @@ pub trait EarlyLintPass: LintPass {
 pub type EarlyLintPassObject = Box<dyn EarlyLintPass + sync::Send + sync::Sync + 'static>;
 pub type LateLintPassObject = Box<dyn for<'a, 'tcx> LateLintPass<'a, 'tcx> + sync::Send
                                                                            + sync::Sync + 'static>;
+/// Makes a fresh lint pass for every module a per-module lint pass is run on.
+pub type LateLintPassCtor = Box<dyn Fn() -> LateLintPassObject + sync::Send + sync::Sync>;
 
 
 
@@ -429,11 +435,12 @@ impl LintId {
 /// Setting for how to handle a lint.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
 pub enum Level {
-    Allow, Warn, Deny, Forbid,
+    Allow, Expect, Warn, Deny, Forbid,
 }
 
 impl_stable_hash_for!(enum self::Level {
     Allow,
+    Expect,
     Warn,
     Deny,
     Forbid
@@ -444,16 +451,29 @@ impl Level {
     pub fn as_str(self) -> &'static str {
         match self {
             Allow => "allow",
+            Expect => "expect",
             Warn => "warn",
             Deny => "deny",
             Forbid => "forbid",
         }
     }
 
+    /// The command line flag that sets a lint to this level.
+    pub fn as_cmdline_flag(self) -> &'static str {
+        match self {
+            Allow => "-A",
+            Warn => "-W",
+            Deny => "-D",
+            Forbid => "-F",
+            Expect => bug!("`expect` is not a command line level"),
+        }
+    }
+
     /// Convert a lower-case string to a level.
     pub fn from_str(x: &str) -> Option<Level> {
         match x {
             "allow" => Some(Allow),
+            "expect" => Some(Expect),
             "warn" => Some(Warn),
             "deny" => Some(Deny),
             "forbid" => Some(Forbid),
@@ -469,21 +489,48 @@ pub enum LintSource {
     /// in rustc or a plugin.
     Default,
 
-    /// Lint level was set by an attribute.
-    Node(ast::Name, Span, Option<Symbol> /* RFC 2383 reason */),
+    /// Lint level was set by an attribute. The `Level` is the one the
+    /// attribute asked for, before any cap was applied.
+    Node(ast::Name, Level, Span, Option<Symbol> /* RFC 2383 reason */),
 
-    /// Lint level was set by a command-line flag.
-    CommandLine(Symbol),
+    /// Lint level was set by a command-line flag, at the given `Level`
+    /// before any cap was applied.
+    CommandLine(Symbol, Level),
 }
 
 impl_stable_hash_for!(enum self::LintSource {
     Default,
-    Node(name, span, reason),
-    CommandLine(text)
+    Node(name, level, span, reason),
+    CommandLine(text, level)
 });
 
+impl LintSource {
+    /// The level this source asked for. The level the lint actually ends up
+    /// at may be lower if `--cap-lints` or the driver capped it.
+    pub fn requested_level(self, lint: &'static Lint, sess: &Session) -> Level {
+        match self {
+            LintSource::Default => lint.default_level(sess),
+            LintSource::Node(_, level, ..) |
+            LintSource::CommandLine(_, level) => level,
+        }
+    }
+}
+
 pub type LevelSource = (Level, LintSource);
 
+/// A lint level set to `expect` by an attribute. The lint is silenced like
+/// with `allow`, but `UNFULFILLED_LINT_EXPECTATIONS` fires at the end of lint
+/// checking if it was never emitted.
+pub struct LintExpectation {
+    /// The span of the lint name in the `#[expect]` attribute.
+    pub span: Span,
+    /// The node carrying the attribute, once lint levels are built for HIR.
+    pub id: Option<ast::NodeId>,
+    pub lint_name: ast::Name,
+    pub reason: Option<Symbol>,
+    pub fulfilled: bool,
+}
+
 pub mod builtin;
 mod context;
 mod levels;
@@ -525,6 +572,15 @@ impl LintBuffer {
     }
 }
 
+/// Whether `lint` was named by `-Z force-lints`, directly or through a group.
+fn is_forced(sess: &Session, lint: &'static Lint) -> bool {
+    let id = LintId::of(lint);
+    let store = sess.lint_store.borrow();
+    sess.opts.debugging_opts.force_lints.iter().any(|name| {
+        store.find_lints(&name.replace("-", "_")).map_or(false, |ids| ids.contains(&id))
+    })
+}
+
 pub fn struct_lint_level<'a>(sess: &'a Session,
                              lint: &'static Lint,
                              level: Level,
@@ -533,62 +589,106 @@ pub fn struct_lint_level<'a>(sess: &'a Session,
                              msg: &str)
     -> DiagnosticBuilder<'a>
 {
+    // Future-incompatible lints are reported even when allowed, so that cargo
+    // can warn about dependencies, whose lints it caps, that are going to
+    // break.
+    if sess.opts.json_future_incompat {
+        let lints = sess.lint_store.borrow();
+        if let Some(future_incompatible) = lints.future_incompatible(LintId::of(lint)) {
+            sess.diagnostic().record_future_incompat_lint(FutureIncompatLint {
+                lint: lint.name_lower(),
+                span: span.clone().unwrap_or_else(MultiSpan::new),
+                reference: future_incompatible.reference.to_string(),
+            });
+        }
+    }
+
     let mut err = match (level, span) {
         (Level::Allow, _) => return sess.diagnostic().struct_dummy(),
-        (Level::Warn, Some(span)) => sess.struct_span_warn(span, msg),
-        (Level::Warn, None) => sess.struct_warn(msg),
+        (Level::Expect, _) => {
+            if let LintSource::Node(lint_attr_name, _, expect_span, _) = src {
+                sess.fulfill_lint_expectation(expect_span, lint_attr_name);
+            }
+            return sess.diagnostic().struct_dummy();
+        }
+        (Level::Warn, span) => {
+            let mut err = DiagnosticBuilder::new(sess.diagnostic(), errors::Level::Warning, msg);
+            if let Some(span) = span {
+                err.set_span(span);
+            }
+            // `--cap-lints allow` keeps the handler from emitting any warning,
+            // but the lints named by `-Z force-lints` escape the cap.
+            if !sess.diagnostic().flags.can_emit_warnings && !is_forced(sess, lint) {
+                err.cancel();
+            }
+            err
+        }
         (Level::Deny, Some(span)) |
         (Level::Forbid, Some(span)) => sess.struct_span_err(span, msg),
         (Level::Deny, None) |
         (Level::Forbid, None) => sess.struct_err(msg),
     };
 
+    // By default each note about where the level came from is only shown the
+    // first time the lint fires; `-Z explain-lint-levels` repeats it on every
+    // emission.
+    let explain_every_time = sess.opts.debugging_opts.explain_lint_levels;
+    let note = |err: &mut DiagnosticBuilder<'a>, msg: &str| {
+        if explain_every_time {
+            err.note(msg);
+        } else {
+            sess.diag_note_once(err, DiagnosticMessageId::from(lint), msg);
+        }
+    };
+
+    // The notes below describe the level as the source asked for it; if a cap
+    // lowered it, that's explained separately afterwards.
+    let requested = src.requested_level(lint, sess);
     let name = lint.name_lower();
     match src {
         LintSource::Default => {
-            sess.diag_note_once(
-                &mut err,
-                DiagnosticMessageId::from(lint),
-                &format!("#[{}({})] on by default", level.as_str(), name));
+            note(&mut err, &format!("#[{}({})] on by default", requested.as_str(), name));
         }
-        LintSource::CommandLine(lint_flag_val) => {
-            let flag = match level {
-                Level::Warn => "-W",
-                Level::Deny => "-D",
-                Level::Forbid => "-F",
-                Level::Allow => panic!(),
-            };
+        LintSource::CommandLine(lint_flag_val, _) => {
+            let flag = requested.as_cmdline_flag();
             let hyphen_case_lint_name = name.replace("_", "-");
             if lint_flag_val.as_str() == name {
-                sess.diag_note_once(
-                    &mut err,
-                    DiagnosticMessageId::from(lint),
-                    &format!("requested on the command line with `{} {}`",
-                             flag, hyphen_case_lint_name));
+                note(&mut err, &format!("requested on the command line with `{} {}`",
+                                        flag, hyphen_case_lint_name));
             } else {
                 let hyphen_case_flag_val = lint_flag_val.as_str().replace("_", "-");
-                sess.diag_note_once(
-                    &mut err,
-                    DiagnosticMessageId::from(lint),
-                    &format!("`{} {}` implied by `{} {}`",
-                             flag, hyphen_case_lint_name, flag,
-                             hyphen_case_flag_val));
+                note(&mut err, &format!("`{} {}` implied by `{} {}`",
+                                        flag, hyphen_case_lint_name, flag,
+                                        hyphen_case_flag_val));
             }
         }
-        LintSource::Node(lint_attr_name, src, reason) => {
+        LintSource::Node(lint_attr_name, _, src, reason) => {
             if let Some(rationale) = reason {
                 err.note(&rationale.as_str());
             }
-            sess.diag_span_note_once(&mut err, DiagnosticMessageId::from(lint),
-                                     src, "lint level defined here");
+            if explain_every_time {
+                err.span_note(src, "lint level defined here");
+            } else {
+                sess.diag_span_note_once(&mut err, DiagnosticMessageId::from(lint),
+                                         src, "lint level defined here");
+            }
             if lint_attr_name.as_str() != name {
-                let level_str = level.as_str();
-                sess.diag_note_once(&mut err, DiagnosticMessageId::from(lint),
-                                    &format!("#[{}({})] implied by #[{}({})]",
-                                             level_str, name, level_str, lint_attr_name));
+                let level_str = requested.as_str();
+                note(&mut err, &format!("#[{}({})] implied by #[{}({})]",
+                                        level_str, name, level_str, lint_attr_name));
             }
         }
     }
+    if level < requested {
+        let driver_cap = sess.driver_lint_caps.get(&LintId::of(lint)).cloned();
+        let by = match sess.opts.lint_cap {
+            Some(cap) if driver_cap.map_or(true, |driver_cap| cap <= driver_cap) => {
+                format!("`--cap-lints {}`", cap.as_str())
+            }
+            _ => "the compiler driver".to_string(),
+        };
+        note(&mut err, &format!("lint level capped at `{}` by {}", level.as_str(), by));
+    }
 
     err.code(DiagnosticId::Lint(name));
 
@@ -664,7 +764,7 @@ impl<'a, 'tcx> LintLevelMapBuilder<'a, 'tcx> {
                           f: F)
         where F: FnOnce(&mut Self)
     {
-        let push = self.levels.push(attrs);
+        let push = self.levels.push(attrs, Some(id));
         self.levels.register_id(self.tcx.hir().definitions().node_to_hir_id(id));
         f(self);
         self.levels.pop(push);
@@ -730,6 +830,7 @@ impl<'a, 'tcx> intravisit::Visitor<'tcx> for LintLevelMapBuilder<'a, 'tcx> {
 
 pub fn provide(providers: &mut Providers<'_>) {
     providers.lint_levels = lint_levels;
+    context::provide(providers);
 }
 
 /// Returns whether `span` originates in a foreign crate's external macro.
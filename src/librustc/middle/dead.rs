@@ -20,14 +20,16 @@ use hir::itemlikevisit::ItemLikeVisitor;
 use hir::def::Def;
 use hir::CodegenFnAttrFlags;
 use hir::def_id::{DefId, LOCAL_CRATE};
+use hir::pat_util::EnumerateAndAdjustIterator;
 use lint;
 use middle::privacy;
 use ty::{self, TyCtxt};
-use util::nodemap::FxHashSet;
+use util::nodemap::{FxHashMap, FxHashSet};
 
 use syntax::{ast, source_map};
 use syntax::attr;
-use syntax_pos;
+use syntax::ptr::P;
+use syntax_pos::{self, MultiSpan};
 
 // Any local node that may call something in its body block should be
 // explored. For example, if it's a live Node::Item that is a
@@ -55,6 +57,12 @@ struct MarkSymbolVisitor<'a, 'tcx: 'a> {
     in_pat: bool,
     inherited_pub_visibility: bool,
     ignore_variant_stack: Vec<DefId>,
+    // Set while visiting the items of a derived impl of a trait marked
+    // `#[rustc_trivial_field_reads]`, whose field reads don't count as uses.
+    in_trivial_derived_impl: bool,
+    // Maps an ADT to the traits whose derived impls were ignored while
+    // looking for field reads, so the lint can mention them.
+    ignored_derived_traits: FxHashMap<DefId, Vec<DefId>>,
 }
 
 impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
@@ -105,6 +113,9 @@ impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
     }
 
     fn handle_field_access(&mut self, lhs: &hir::Expr, node_id: ast::NodeId) {
+        if self.in_trivial_derived_impl {
+            return;
+        }
         match self.tables.expr_ty_adjusted(lhs).sty {
             ty::Adt(def, _) => {
                 let index = self.tcx.field_index(node_id, self.tables);
@@ -117,6 +128,9 @@ impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
 
     fn handle_field_pattern_match(&mut self, lhs: &hir::Pat, def: Def,
                                   pats: &[source_map::Spanned<hir::FieldPat>]) {
+        if self.in_trivial_derived_impl {
+            return;
+        }
         let variant = match self.tables.node_id_to_type(lhs.hir_id).sty {
             ty::Adt(adt, _) => adt.variant_of_def(def),
             _ => span_bug!(lhs.span, "non-ADT in struct pattern")
@@ -130,6 +144,48 @@ impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
         }
     }
 
+    fn handle_tuple_field_pattern_match(&mut self, lhs: &hir::Pat, def: Def,
+                                        pats: &[P<hir::Pat>], ddpos: Option<usize>) {
+        if self.in_trivial_derived_impl {
+            return;
+        }
+        let variant = match self.tables.node_id_to_type(lhs.hir_id).sty {
+            ty::Adt(adt, _) => adt.variant_of_def(def),
+            _ => span_bug!(lhs.span, "non-ADT in tuple struct pattern")
+        };
+        for (index, pat) in pats.iter().enumerate_and_adjust(variant.fields.len(), ddpos) {
+            if let PatKind::Wild = pat.node {
+                continue;
+            }
+            self.insert_def_id(variant.fields[index].did);
+        }
+    }
+
+    /// Whether `impl_item` belongs to an `#[automatically_derived]` impl of a
+    /// trait marked `#[rustc_trivial_field_reads]`, such as `Debug` or `Clone`.
+    /// The self type of such an impl is remembered for the lint's note.
+    fn check_trivial_derived_impl(&mut self, impl_item: &hir::ImplItem) -> bool {
+        let impl_id = self.tcx.hir().get_parent(impl_item.id);
+        let impl_def_id = self.tcx.hir().local_def_id(impl_id);
+        if !self.tcx.has_attr(impl_def_id, "automatically_derived") {
+            return false;
+        }
+        let trait_def_id = match self.tcx.impl_trait_ref(impl_def_id) {
+            Some(trait_ref) => trait_ref.def_id,
+            None => return false,
+        };
+        if !self.tcx.has_attr(trait_def_id, "rustc_trivial_field_reads") {
+            return false;
+        }
+        if let Some(adt) = self.tcx.type_of(impl_def_id).ty_adt_def() {
+            let traits = self.ignored_derived_traits.entry(adt.did).or_default();
+            if !traits.contains(&trait_def_id) {
+                traits.push(trait_def_id);
+            }
+        }
+        true
+    }
+
     fn mark_live_symbols(&mut self) {
         let mut scanned = FxHashSet::default();
         while let Some(id) = self.worklist.pop() {
@@ -177,7 +233,10 @@ impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
                 intravisit::walk_trait_item(self, trait_item);
             }
             Node::ImplItem(impl_item) => {
+                let had_trivial_derived_impl = self.in_trivial_derived_impl;
+                self.in_trivial_derived_impl = self.check_trivial_derived_impl(impl_item);
                 intravisit::walk_impl_item(self, impl_item);
+                self.in_trivial_derived_impl = had_trivial_derived_impl;
             }
             Node::ForeignItem(foreign_item) => {
                 intravisit::walk_foreign_item(self, &foreign_item);
@@ -267,6 +326,10 @@ impl<'a, 'tcx> Visitor<'tcx> for MarkSymbolVisitor<'a, 'tcx> {
             PatKind::Struct(hir::QPath::Resolved(_, ref path), ref fields, _) => {
                 self.handle_field_pattern_match(pat, path.def, fields);
             }
+            PatKind::TupleStruct(ref qpath, ref pats, ddpos) => {
+                let def = self.tables.qpath_def(qpath, pat.hir_id);
+                self.handle_tuple_field_pattern_match(pat, def, pats, ddpos);
+            }
             PatKind::Path(ref qpath @ hir::QPath::TypeRelative(..)) => {
                 let def = self.tables.qpath_def(qpath, pat.hir_id);
                 self.handle_definition(def);
@@ -422,7 +485,7 @@ fn create_and_seed_worklist<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 fn find_live<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                        access_levels: &privacy::AccessLevels,
                        krate: &hir::Crate)
-                       -> FxHashSet<ast::NodeId> {
+                       -> (FxHashSet<ast::NodeId>, FxHashMap<DefId, Vec<DefId>>) {
     let worklist = create_and_seed_worklist(tcx, access_levels, krate);
     let mut symbol_visitor = MarkSymbolVisitor {
         worklist,
@@ -433,9 +496,11 @@ fn find_live<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         in_pat: false,
         inherited_pub_visibility: false,
         ignore_variant_stack: vec![],
+        in_trivial_derived_impl: false,
+        ignored_derived_traits: Default::default(),
     };
     symbol_visitor.mark_live_symbols();
-    symbol_visitor.live_symbols
+    (symbol_visitor.live_symbols, symbol_visitor.ignored_derived_traits)
 }
 
 fn get_struct_ctor_id(item: &hir::Item) -> Option<ast::NodeId> {
@@ -450,6 +515,7 @@ fn get_struct_ctor_id(item: &hir::Item) -> Option<ast::NodeId> {
 struct DeadVisitor<'a, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     live_symbols: FxHashSet<ast::NodeId>,
+    ignored_derived_traits: FxHashMap<DefId, Vec<DefId>>,
 }
 
 impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
@@ -470,8 +536,7 @@ impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
 
     fn should_warn_about_field(&mut self, field: &hir::StructField) -> bool {
         let field_type = self.tcx.type_of(self.tcx.hir().local_def_id(field.id));
-        !field.is_positional()
-            && !self.symbol_is_live(field.id, None)
+        !self.symbol_is_live(field.id, None)
             && !field_type.is_phantom_data()
             && !has_allow_dead_code_or_lang_attr(self.tcx, field.id, &field.attrs)
     }
@@ -539,6 +604,75 @@ impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
                                     node_type, participle, name));
         }
     }
+
+    // Tuple struct fields are only reported by the allow-by-default
+    // `unused_tuple_struct_fields` lint, since removing one of them
+    // renumbers the fields that follow it.
+    fn warn_dead_field(&mut self, field: &hir::StructField) {
+        if field.ident.as_str().starts_with("_") {
+            return;
+        }
+        let lint = if field.is_positional() {
+            lint::builtin::UNUSED_TUPLE_STRUCT_FIELDS
+        } else {
+            lint::builtin::DEAD_CODE
+        };
+        let mut err = self.tcx.struct_span_lint_node(
+            lint,
+            field.id,
+            field.span,
+            &format!("field is never used: `{}`", field.ident.name),
+        );
+        let adt_def_id = self.tcx.hir().local_def_id(self.tcx.hir().get_parent(field.id));
+        if let Some(traits) = self.ignored_derived_traits.get(&adt_def_id) {
+            let adt_name = self.tcx.item_name(adt_def_id);
+            let mut trait_names = traits.iter()
+                .map(|&trait_def_id| self.tcx.item_name(trait_def_id))
+                .collect::<Vec<_>>();
+            trait_names.sort();
+            for trait_name in trait_names {
+                err.note(&format!("`{}` has a derived impl for the trait `{}`, but this is \
+                                   intentionally ignored during dead code analysis",
+                                  adt_name, trait_name));
+            }
+        }
+        err.emit();
+    }
+
+    // All the never constructed variants of an enum are reported together,
+    // instead of one diagnostic per variant. Variants with a different lint
+    // level, e.g. because of an attribute on the variant, get their own group.
+    fn warn_dead_variants(&mut self, variants: &[hir::Variant]) {
+        let mut groups: Vec<(_, Vec<&hir::Variant>)> = vec![];
+        for variant in variants {
+            if !self.should_warn_about_variant(&variant.node)
+                || variant.node.name.as_str().starts_with("_") {
+                continue;
+            }
+            let level = self.tcx.lint_level_at_node(lint::builtin::DEAD_CODE,
+                                                    variant.node.data.id());
+            match groups.iter_mut().find(|&&mut (group_level, _)| group_level == level) {
+                Some(&mut (_, ref mut group)) => group.push(variant),
+                None => groups.push((level, vec![variant])),
+            }
+        }
+        let source_map = self.tcx.sess.source_map();
+        for (_, dead_variants) in groups {
+            let spans = dead_variants.iter().map(|variant| source_map.def_span(variant.span));
+            let names = dead_variants.iter()
+                .map(|variant| format!("`{}`", variant.node.name))
+                .collect::<Vec<_>>();
+            let msg = if names.len() == 1 {
+                format!("variant is never constructed: {}", names[0])
+            } else {
+                format!("variants are never constructed: {}", names.join(", "))
+            };
+            self.tcx.lint_node(lint::builtin::DEAD_CODE,
+                               dead_variants[0].node.data.id(),
+                               MultiSpan::from_spans(spans.collect()),
+                               &msg);
+        }
+    }
 }
 
 impl<'a, 'tcx> Visitor<'tcx> for DeadVisitor<'a, 'tcx> {
@@ -576,6 +710,9 @@ impl<'a, 'tcx> Visitor<'tcx> for DeadVisitor<'a, 'tcx> {
                 participle,
             );
         } else {
+            if let hir::ItemKind::Enum(ref enum_def, _) = item.node {
+                self.warn_dead_variants(&enum_def.variants);
+            }
             // Only continue if we didn't warn
             intravisit::walk_item(self, item);
         }
@@ -585,10 +722,8 @@ impl<'a, 'tcx> Visitor<'tcx> for DeadVisitor<'a, 'tcx> {
                      variant: &'tcx hir::Variant,
                      g: &'tcx hir::Generics,
                      id: ast::NodeId) {
-        if self.should_warn_about_variant(&variant.node) {
-            self.warn_dead_code(variant.node.data.id(), variant.span, variant.node.name,
-                                "variant", "constructed");
-        } else {
+        // Dead variants were already reported with the rest of their enum.
+        if !self.should_warn_about_variant(&variant.node) {
             intravisit::walk_variant(self, variant, g, id);
         }
     }
@@ -603,7 +738,7 @@ impl<'a, 'tcx> Visitor<'tcx> for DeadVisitor<'a, 'tcx> {
 
     fn visit_struct_field(&mut self, field: &'tcx hir::StructField) {
         if self.should_warn_about_field(&field) {
-            self.warn_dead_code(field.id, field.span, field.ident.name, "field", "used");
+            self.warn_dead_field(field);
         }
         intravisit::walk_struct_field(self, field);
     }
@@ -649,10 +784,11 @@ impl<'a, 'tcx> Visitor<'tcx> for DeadVisitor<'a, 'tcx> {
 pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     let access_levels = &tcx.privacy_access_levels(LOCAL_CRATE);
     let krate = tcx.hir().krate();
-    let live_symbols = find_live(tcx, access_levels, krate);
+    let (live_symbols, ignored_derived_traits) = find_live(tcx, access_levels, krate);
     let mut visitor = DeadVisitor {
         tcx,
         live_symbols,
+        ignored_derived_traits,
     };
     intravisit::walk_crate(&mut visitor, krate);
 }
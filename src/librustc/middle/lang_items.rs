@@ -323,11 +323,13 @@ language_item_table! {
     // somewhere. Additionally, there are restrictions on crates that use a weak
     // lang item, but do not have it defined.
     PanicFnLangItem,             "panic",              panic_fn,                Target::Fn;
+    PanicFmtFnLangItem,          "panic_fmt",          panic_fmt_fn,            Target::Fn;
     PanicBoundsCheckFnLangItem,  "panic_bounds_check", panic_bounds_check_fn,   Target::Fn;
     PanicInfoLangItem,           "panic_info",         panic_info,              Target::Struct;
     PanicImplLangItem,           "panic_impl",         panic_impl,              Target::Fn;
     // Libstd panic entry point. Necessary for const eval to be able to catch it
     BeginPanicFnLangItem,        "begin_panic",        begin_panic_fn,          Target::Fn;
+    BeginPanicFmtFnLangItem,     "begin_panic_fmt",    begin_panic_fmt_fn,      Target::Fn;
 
     ExchangeMallocFnLangItem,    "exchange_malloc",    exchange_malloc_fn,      Target::Fn;
     BoxFreeFnLangItem,           "box_free",           box_free_fn,             Target::Fn;
@@ -348,6 +350,7 @@ language_item_table! {
     ManuallyDropItem,            "manually_drop",      manually_drop,           Target::Struct;
 
     DebugTraitLangItem,          "debug_trait",        debug_trait,             Target::Trait;
+    DisplayTraitLangItem,        "display_trait",      display_trait,           Target::Trait;
 
     // A lang item for each of the 128-bit operators we can optionally lower.
     I128AddFnLangItem,           "i128_add",           i128_add_fn,             Target::Fn;
@@ -32,7 +32,8 @@ use syntax::ast;
 use syntax::attr;
 use syntax::ptr::P;
 use syntax::symbol::keywords;
-use syntax_pos::Span;
+use syntax::util::lev_distance::NameMatcher;
+use syntax_pos::{BytePos, Span};
 use util::nodemap::{DefIdMap, FxHashMap, FxHashSet, NodeMap, NodeSet};
 
 use hir::intravisit::{self, NestedVisitorMap, Visitor};
@@ -264,6 +265,11 @@ struct LifetimeContext<'a, 'tcx: 'a> {
     xcrate_object_lifetime_defaults: DefIdMap<Vec<ObjectLifetimeDefault>>,
 
     lifetime_uses: &'a mut DefIdMap<LifetimeUseSet<'tcx>>,
+
+    /// Items that already got a suggestion to introduce an undeclared lifetime.
+    /// Applying two of them would produce e.g. `Foo<'a><'a>`, so only the first
+    /// undeclared lifetime of an item gets one.
+    items_with_lifetime_suggestion: NodeSet,
 }
 
 #[derive(Debug)]
@@ -439,6 +445,7 @@ fn krate<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) -> NamedRegionMap {
             labels_in_fn: vec![],
             xcrate_object_lifetime_defaults: Default::default(),
             lifetime_uses: &mut Default::default(),
+            items_with_lifetime_suggestion: Default::default(),
         };
         for (_, item) in &krate.items {
             visitor.visit_item(item);
@@ -1433,6 +1440,8 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         let labels_in_fn = replace(&mut self.labels_in_fn, vec![]);
         let xcrate_object_lifetime_defaults =
             replace(&mut self.xcrate_object_lifetime_defaults, DefIdMap::default());
+        let items_with_lifetime_suggestion =
+            replace(&mut self.items_with_lifetime_suggestion, NodeSet::default());
         let mut this = LifetimeContext {
             tcx: *tcx,
             map: map,
@@ -1442,6 +1451,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             labels_in_fn,
             xcrate_object_lifetime_defaults,
             lifetime_uses: lifetime_uses,
+            items_with_lifetime_suggestion,
         };
         debug!("entering scope {:?}", this.scope);
         f(self.scope, &mut this);
@@ -1449,6 +1459,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         debug!("exiting scope {:?}", this.scope);
         self.labels_in_fn = this.labels_in_fn;
         self.xcrate_object_lifetime_defaults = this.xcrate_object_lifetime_defaults;
+        self.items_with_lifetime_suggestion = this.items_with_lifetime_suggestion;
     }
 
     /// helper method to determine the span to remove when suggesting the
@@ -1484,11 +1495,70 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         })
     }
 
-    // helper method to issue suggestions from `fn rah<'a>(&'a T)` to `fn rah(&T)`
+    // Returns whether the single use `lifetime` can be written as `'_`: that's
+    // so in fn arguments and impl headers, but not under a `fn` pointer or
+    // `Fn`-sugar type, where `'_` would introduce a fresh late-bound lifetime.
+    fn can_use_anonymous_lifetime(&self, lifetime: &hir::Lifetime) -> bool {
+        let hir = self.tcx.hir();
+        let in_inputs = |decl: &hir::FnDecl| {
+            decl.inputs.iter().any(|input| input.span.contains(lifetime.span))
+        };
+        let mut id = lifetime.id;
+        loop {
+            let parent = hir.get_parent_node(id);
+            if parent == id {
+                return false;
+            }
+            match hir.find(parent) {
+                Some(Node::Ty(ty)) => {
+                    if let hir::TyKind::BareFn(..) = ty.node {
+                        return false;
+                    }
+                }
+                Some(Node::TraitRef(trait_ref)) => {
+                    let parenthesized = trait_ref.path.segments.iter().any(|segment| {
+                        segment.args.as_ref().map_or(false, |args| args.parenthesized)
+                    });
+                    if parenthesized {
+                        return false;
+                    }
+                }
+                Some(Node::Item(item)) => {
+                    return match item.node {
+                        hir::ItemKind::Fn(ref decl, ..) => in_inputs(decl),
+                        hir::ItemKind::Impl(.., ref trait_ref, ref self_ty, _) => {
+                            self_ty.span.contains(lifetime.span) ||
+                                trait_ref.as_ref().map_or(false, |trait_ref| {
+                                    trait_ref.path.span.contains(lifetime.span)
+                                })
+                        }
+                        _ => false,
+                    };
+                }
+                Some(Node::ImplItem(impl_item)) => {
+                    return match impl_item.node {
+                        hir::ImplItemKind::Method(ref sig, _) => in_inputs(&sig.decl),
+                        _ => false,
+                    };
+                }
+                Some(Node::TraitItem(trait_item)) => {
+                    return match trait_item.node {
+                        hir::TraitItemKind::Method(ref sig, _) => in_inputs(&sig.decl),
+                        _ => false,
+                    };
+                }
+                Some(Node::GenericParam(_)) | None => return false,
+                _ => {}
+            }
+            id = parent;
+        }
+    }
+
+    // helper method to issue suggestions from `fn rah<'a>(&'a T)` to `fn rah(&T)`,
+    // or from `impl<'a> Foo<'a>` to `impl Foo<'_>`
     fn suggest_eliding_single_use_lifetime(
         &self, err: &mut DiagnosticBuilder<'_>, def_id: DefId, lifetime: &hir::Lifetime
     ) {
-        // FIXME: future work: also suggest `impl Foo<'_>` for `impl<'a> Foo<'a>`
         let name = lifetime.name.ident();
         let mut remove_decl = None;
         if let Some(parent_def_id) = self.tcx.parent(def_id) {
@@ -1531,21 +1601,30 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             }
         }
 
-        if let (Some(decl_span), Some(use_span)) = (remove_decl, remove_use) {
-            // if both declaration and use deletion spans start at the same
-            // place ("start at" because the latter includes trailing
+        // Where the use can't simply be deleted, as in `Foo<'a>`, it can
+        // often still be written as `'_`.
+        let (use_span, replacement, msg) = match remove_use {
+            Some(use_span) => (use_span, String::new(), "elide the single-use lifetime"),
+            None if self.can_use_anonymous_lifetime(lifetime) => {
+                (lifetime.span, "'_".to_string(), "use `'_` for the single-use lifetime")
+            }
+            None => return,
+        };
+        if let Some(decl_span) = remove_decl {
+            // if both declaration and use spans start at the same place
+            // ("start at" because a deleted use includes trailing
             // whitespace), then this is an in-band lifetime
             if decl_span.shrink_to_lo() == use_span.shrink_to_lo() {
                 err.span_suggestion_with_applicability(
                     use_span,
-                    "elide the single-use lifetime",
-                    String::new(),
+                    msg,
+                    replacement,
                     Applicability::MachineApplicable,
                 );
             } else {
                 err.multipart_suggestion_with_applicability(
-                    "elide the single-use lifetime",
-                    vec![(decl_span, String::new()), (use_span, String::new())],
+                    msg,
+                    vec![(decl_span, String::new()), (use_span, replacement)],
                     Applicability::MachineApplicable,
                 );
             }
@@ -1885,17 +1964,141 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
 
             self.insert_lifetime(lifetime_ref, def);
         } else {
-            struct_span_err!(
+            let mut err = struct_span_err!(
                 self.tcx.sess,
                 lifetime_ref.span,
                 E0261,
                 "use of undeclared lifetime name `{}`",
                 lifetime_ref
-            ).span_label(lifetime_ref.span, "undeclared lifetime")
-                .emit();
+            );
+            err.span_label(lifetime_ref.span, "undeclared lifetime");
+            self.suggest_undeclared_lifetime(&mut err, lifetime_ref);
+            err.emit();
         }
     }
 
+    /// Suggests a similarly named lifetime that is in scope (including `'static`), or else
+    /// declaring the lifetime on the enclosing item. Statics and consts can't declare
+    /// lifetimes, so `'static` is suggested for them instead.
+    fn suggest_undeclared_lifetime(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        lifetime_ref: &hir::Lifetime,
+    ) {
+        let name = lifetime_ref.name.ident().name;
+        let name_str = name.as_str();
+
+        let mut in_scope = Vec::new();
+        let mut scope = self.scope;
+        loop {
+            match *scope {
+                Scope::Binder { ref lifetimes, s, .. } => {
+                    in_scope.extend(lifetimes.keys().filter_map(|param_name| match param_name {
+                        ParamName::Plain(ident) => Some(ident.name),
+                        ParamName::Fresh(_) | ParamName::Error => None,
+                    }));
+                    scope = s;
+                }
+                Scope::Body { s, .. }
+                | Scope::Elision { s, .. }
+                | Scope::ObjectLifetimeDefault { s, .. } => {
+                    scope = s;
+                }
+                Scope::Root => break,
+            }
+        }
+        in_scope.sort_by_key(|name| name.as_str());
+
+        // Single letter names like `'a` and `'b` are placeholders rather than
+        // misspellings of each other.
+        if name_str.chars().count() > 2 {
+            let static_name = keywords::StaticLifetime.name();
+            let candidates = in_scope.iter().chain(Some(&static_name));
+            if let Some(similar) = NameMatcher::new(&name_str).best(candidates) {
+                if similar == static_name {
+                    err.span_suggestion_with_applicability(
+                        lifetime_ref.span,
+                        "you might have meant the `'static` lifetime",
+                        similar.to_string(),
+                        Applicability::MaybeIncorrect,
+                    );
+                    err.note("`'static` is the only lifetime name that is always in scope, \
+                              every other named lifetime has to be declared before it is used");
+                } else {
+                    err.span_suggestion_with_applicability(
+                        lifetime_ref.span,
+                        "a lifetime with a similar name is in scope",
+                        similar.to_string(),
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+                return;
+            }
+        }
+
+        let hir = self.tcx.hir();
+        let item_id = hir.get_parent(lifetime_ref.id);
+        let (ident, generics) = match hir.find(item_id) {
+            Some(Node::Item(&hir::Item { node: hir::ItemKind::Static(..), .. }))
+            | Some(Node::Item(&hir::Item { node: hir::ItemKind::Const(..), .. }))
+            | Some(Node::TraitItem(&hir::TraitItem { node: hir::TraitItemKind::Const(..), .. }))
+            | Some(Node::ImplItem(&hir::ImplItem { node: hir::ImplItemKind::Const(..), .. })) => {
+                err.span_suggestion_with_applicability(
+                    lifetime_ref.span,
+                    "consider using the `'static` lifetime",
+                    keywords::StaticLifetime.name().to_string(),
+                    Applicability::MaybeIncorrect,
+                );
+                return;
+            }
+            // `main` can't have generic parameters.
+            Some(Node::Item(&hir::Item { node: hir::ItemKind::Fn(..), id, .. }))
+                if self.tcx.sess.entry_fn.borrow().map(|(entry_id, ..)| entry_id) == Some(id) => {
+                return;
+            }
+            Some(Node::Item(item)) => (item.ident, hir.get_generics(hir.local_def_id(item_id))),
+            Some(Node::TraitItem(item)) => (item.ident, Some(&item.generics)),
+            Some(Node::ImplItem(item)) => (item.ident, Some(&item.generics)),
+            _ => return,
+        };
+        let generics = match generics {
+            Some(generics) => generics,
+            None => return,
+        };
+
+        // Lifetimes go first in the parameter list, right after the `<`.
+        let (span, sugg) = if !generics.span.is_dummy() {
+            let lo = generics.span.lo() + BytePos(1);
+            (generics.span.with_lo(lo).shrink_to_lo(), format!("{}, ", name))
+        } else if ident.name != keywords::Invalid.name() {
+            (ident.span.shrink_to_hi(), format!("<{}>", name))
+        } else if let Some(Node::Item(&hir::Item { node: hir::ItemKind::Impl(..), span, .. })) =
+            hir.find(item_id)
+        {
+            // Impls have no name, and no span for their generics when they have
+            // none, so the parameter list is added right after the `impl` keyword.
+            let source_map = self.tcx.sess.source_map();
+            match source_map.span_to_snippet(span).ok().and_then(|s| s.find("impl")) {
+                Some(offset) => {
+                    let hi = span.lo() + BytePos((offset + "impl".len()) as u32);
+                    (span.with_hi(hi).shrink_to_hi(), format!("<{}>", name))
+                }
+                None => return,
+            }
+        } else {
+            return;
+        };
+        if !self.items_with_lifetime_suggestion.insert(item_id) {
+            return;
+        }
+        err.span_suggestion_with_applicability(
+            span,
+            &format!("consider introducing lifetime `{}` here", name),
+            sugg,
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     fn visit_segment_args(&mut self, def: Def, depth: usize, generic_args: &'tcx hir::GenericArgs) {
         if generic_args.parenthesized {
             let was_in_fn_syntax = self.is_in_fn_syntax;
@@ -17,11 +17,12 @@ use super::{
 
 use ty::layout::{Size, Align};
 use syntax::ast::Mutability;
-use std::iter;
+use std::{cmp, iter};
 use mir;
 use std::ops::{Deref, DerefMut};
 use rustc_data_structures::sorted_map::SortedMap;
 use rustc_target::abi::HasDataLayout;
+use rustc_serialize::{Encodable, Encoder, Decodable, Decoder};
 
 /// Used by `check_bounds` to indicate whether the pointer needs to be just inbounds
 /// or also inbounds of a *live* allocation.
@@ -141,6 +142,99 @@ impl<Tag, Extra> Allocation<Tag, Extra> {
 
 impl<'tcx> ::serialize::UseSpecializedDecodable for &'tcx Allocation {}
 
+/// Zero runs at least this long are stored as a length instead of byte by byte.
+const MIN_ZERO_RUN: usize = 16;
+
+/// Compact serialization
+impl Allocation {
+    /// Encodes the allocation for crate metadata and the incremental cache. Large constants
+    /// mostly consist of zeros or undefined bytes, so runs of zero bytes and the definedness
+    /// mask are run-length encoded.
+    pub fn encode_compressed<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        let bytes = &self.bytes[..];
+        e.emit_usize(bytes.len())?;
+
+        // Literal bytes and zero runs alternate, starting with literal bytes
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let mut literal_end = pos;
+            let mut zeros = 0;
+            while literal_end < bytes.len() {
+                zeros = bytes[literal_end..].iter().take_while(|&&b| b == 0).count();
+                if zeros >= MIN_ZERO_RUN || literal_end + zeros == bytes.len() {
+                    break;
+                }
+                // Too short to be worth it, keep the zeros and the byte after them as literals
+                literal_end += zeros + 1;
+                zeros = 0;
+            }
+            e.emit_usize(literal_end - pos)?;
+            for &byte in &bytes[pos..literal_end] {
+                e.emit_u8(byte)?;
+            }
+            e.emit_usize(zeros)?;
+            pos = literal_end + zeros;
+        }
+
+        // Runs of undefined and defined bytes alternate, starting with undefined bytes
+        let len = bytes.len() as u64;
+        let mut pos = 0;
+        while pos < len {
+            let undef = (pos..len)
+                .take_while(|&i| !self.undef_mask.get(Size::from_bytes(i)))
+                .count() as u64;
+            let def = (pos + undef..len)
+                .take_while(|&i| self.undef_mask.get(Size::from_bytes(i)))
+                .count() as u64;
+            e.emit_u64(undef)?;
+            e.emit_u64(def)?;
+            pos += undef + def;
+        }
+
+        self.relocations.encode(e)?;
+        self.align.encode(e)?;
+        self.mutability.encode(e)
+    }
+
+    /// Decodes an allocation written by `encode_compressed`.
+    pub fn decode_compressed<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        let len = d.read_usize()?;
+        let mut bytes = Vec::with_capacity(len);
+        while bytes.len() < len {
+            let literal = d.read_usize()?;
+            for _ in 0..literal {
+                bytes.push(d.read_u8()?);
+            }
+            let zeros = d.read_usize()?;
+            bytes.extend(iter::repeat(0).take(zeros));
+        }
+
+        let len = len as u64;
+        let mut undef_mask = UndefMask::new(Size::from_bytes(len));
+        let mut pos = 0;
+        while pos < len {
+            let undef = d.read_u64()?;
+            let def = d.read_u64()?;
+            pos += undef;
+            undef_mask.set_range_inbounds(
+                Size::from_bytes(pos),
+                Size::from_bytes(pos + def),
+                true,
+            );
+            pos += def;
+        }
+
+        Ok(Allocation {
+            bytes,
+            relocations: Decodable::decode(d)?,
+            undef_mask,
+            align: Decodable::decode(d)?,
+            mutability: Decodable::decode(d)?,
+            extra: (),
+        })
+    }
+}
+
 /// Alignment and bounds checks
 impl<'tcx, Tag, Extra> Allocation<Tag, Extra> {
     /// Check if the pointer is "in-bounds". Notice that a pointer pointing at the end
@@ -360,6 +454,11 @@ impl<'tcx, Tag: Copy, Extra> Allocation<Tag, Extra> {
         // FIXME: Working around https://github.com/rust-lang/rust/issues/56209
         where Extra: AllocationExtra<Tag, MemoryExtra>
     {
+        // A pointer-sized read must not pick up a pointer stored at a different offset,
+        // e.g. the remains of a pointer that has been partially overwritten.
+        if size == cx.data_layout().pointer_size {
+            self.check_partial_pointers(cx, ptr, size)?;
+        }
         // get_bytes_unchecked tests relocation edges
         let bytes = self.get_bytes_with_undef_and_ptr(cx, ptr, size)?;
         // Undef check happens *after* we established that the alignment is correct.
@@ -501,12 +600,28 @@ impl<'tcx, Tag: Copy, Extra> Allocation<Tag, Extra> {
         }
     }
 
+    /// Check that the given range does not overlap with any relocation other than one
+    /// starting exactly at `ptr`.
+    fn check_partial_pointers(
+        &self,
+        cx: &impl HasDataLayout,
+        ptr: Pointer<Tag>,
+        size: Size,
+    ) -> EvalResult<'tcx> {
+        if self.relocations(cx, ptr, size).iter().all(|&(offset, _)| offset == ptr.offset) {
+            Ok(())
+        } else {
+            err!(ReadPartialPointer)
+        }
+    }
+
     /// Remove all relocations inside the given range.
     /// If there are relocations overlapping with the edges, they
-    /// are removed as well *and* the bytes they cover are marked as
-    /// uninitialized.  This is a somewhat odd "spooky action at a distance",
-    /// but it allows strictly more code to run than if we would just error
-    /// immediately in that case.
+    /// are removed as well *and* the bytes they cover outside of the range are
+    /// marked as uninitialized, so the remains of a partially overwritten pointer
+    /// can never be read back as (part of) a pointer.  This is a somewhat odd
+    /// "spooky action at a distance", but it allows strictly more code to run
+    /// than if we would just error immediately in that case.
     fn clear_relocations(
         &mut self,
         cx: &impl HasDataLayout,
@@ -530,10 +645,10 @@ impl<'tcx, Tag: Copy, Extra> Allocation<Tag, Extra> {
         // Mark parts of the outermost relocations as undefined if they partially fall outside the
         // given range.
         if first < start {
-            self.undef_mask.set_range(first, start, false);
+            self.undef_mask.set_range_inbounds(first, start, false);
         }
         if last > end {
-            self.undef_mask.set_range(end, last, false);
+            self.undef_mask.set_range_inbounds(end, last, false);
         }
 
         // Forget all the relocations.
@@ -563,7 +678,7 @@ impl<'tcx, Tag, Extra> Allocation<Tag, Extra> {
     /// Checks that a range of bytes is defined. If not, returns the `ReadUndefBytes`
     /// error which will report the first byte which is undefined.
     #[inline]
-    fn check_defined(&self, ptr: Pointer<Tag>, size: Size) -> EvalResult<'tcx> {
+    pub fn check_defined(&self, ptr: Pointer<Tag>, size: Size) -> EvalResult<'tcx> {
         self.undef_mask.is_range_defined(
             ptr.offset,
             ptr.offset + size,
@@ -652,15 +767,28 @@ impl UndefMask {
         if end > self.len {
             return Err(self.len);
         }
+        if start >= end {
+            return Ok(());
+        }
 
-        let idx = (start.bytes()..end.bytes())
-            .map(|i| Size::from_bytes(i))
-            .find(|&i| !self.get(i));
-
-        match idx {
-            Some(idx) => Err(idx),
-            None => Ok(())
+        // Look at a whole block at a time, masking out the bits outside of the range
+        let (start_block, start_bit) = bit_index(start);
+        let (end_block, end_bit) = bit_index(end);
+        let last_block = if end_bit == 0 { end_block - 1 } else { end_block };
+        for block in start_block..=last_block {
+            let mut undef = !self.blocks[block];
+            if block == start_block {
+                undef &= !0 << start_bit;
+            }
+            if block == end_block {
+                undef &= low_bits(end_bit as u64);
+            }
+            if undef != 0 {
+                let idx = block as u64 * BLOCK_SIZE + undef.trailing_zeros() as u64;
+                return Err(Size::from_bytes(idx));
+            }
         }
+        Ok(())
     }
 
     pub fn set_range(&mut self, start: Size, end: Size, new_state: bool) {
@@ -672,8 +800,69 @@ impl UndefMask {
     }
 
     pub fn set_range_inbounds(&mut self, start: Size, end: Size, new_state: bool) {
-        for i in start.bytes()..end.bytes() {
-            self.set(Size::from_bytes(i), new_state);
+        if start >= end {
+            return;
+        }
+        let (start_block, start_bit) = bit_index(start);
+        let (end_block, end_bit) = bit_index(end);
+        if start_block == end_block {
+            let mask = low_bits(end_bit as u64) & !low_bits(start_bit as u64);
+            self.set_masked(start_block, mask, new_state);
+            return;
+        }
+        self.set_masked(start_block, !low_bits(start_bit as u64), new_state);
+        let fill = if new_state { !0 } else { 0 };
+        for block in &mut self.blocks[start_block + 1..end_block] {
+            *block = fill;
+        }
+        if end_bit != 0 {
+            self.set_masked(end_block, low_bits(end_bit as u64), new_state);
+        }
+    }
+
+    /// Copies the definedness of `size` bytes starting at `src_start` in `src` to the bytes
+    /// starting at `dest_start` in `self`. Both ranges must be in bounds.
+    pub fn copy_range(&mut self, src: &UndefMask, src_start: Size, dest_start: Size, size: Size) {
+        assert!(src_start + size <= src.len && dest_start + size <= self.len);
+        let mut offset = 0;
+        while offset < size.bytes() {
+            let n = cmp::min(BLOCK_SIZE, size.bytes() - offset);
+            let bits = src.get_bits(src_start + Size::from_bytes(offset), n);
+            self.set_bits(dest_start + Size::from_bytes(offset), n, bits);
+            offset += n;
+        }
+    }
+
+    #[inline]
+    fn set_masked(&mut self, block: usize, mask: Block, new_state: bool) {
+        if new_state {
+            self.blocks[block] |= mask;
+        } else {
+            self.blocks[block] &= !mask;
+        }
+    }
+
+    /// Returns the state of the `n <= BLOCK_SIZE` bytes starting at `start`, with the
+    /// state of `start` in the lowest bit.
+    fn get_bits(&self, start: Size, n: u64) -> Block {
+        let (block, bit) = bit_index(start);
+        let mut bits = self.blocks[block] >> bit;
+        if bit as u64 + n > BLOCK_SIZE {
+            bits |= self.blocks[block + 1] << (BLOCK_SIZE - bit as u64);
+        }
+        bits & low_bits(n)
+    }
+
+    /// Sets the state of the `n <= BLOCK_SIZE` bytes starting at `start` from the lowest
+    /// bits of `bits`.
+    fn set_bits(&mut self, start: Size, n: u64, bits: Block) {
+        let (block, bit) = bit_index(start);
+        let mask = low_bits(n);
+        let bits = bits & mask;
+        self.blocks[block] = (self.blocks[block] & !(mask << bit)) | (bits << bit);
+        if bit as u64 + n > BLOCK_SIZE {
+            let shift = BLOCK_SIZE - bit as u64;
+            self.blocks[block + 1] = (self.blocks[block + 1] & !(mask >> shift)) | (bits >> shift);
         }
     }
 
@@ -708,6 +897,12 @@ impl UndefMask {
     }
 }
 
+/// A block with the lowest `n` bits set.
+#[inline]
+fn low_bits(n: u64) -> Block {
+    if n >= BLOCK_SIZE { !0 } else { (1 << n) - 1 }
+}
+
 #[inline]
 fn bit_index(bits: Size) -> (usize, usize) {
     let bits = bits.bytes();
@@ -717,3 +912,135 @@ fn bit_index(bits: Size) -> (usize, usize) {
     assert_eq!(b as usize as u64, b);
     (a as usize, b as usize)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Allocation, UndefMask};
+    use serialize::opaque;
+    use ty::layout::{Align, Size};
+
+    fn bytes(n: u64) -> Size {
+        Size::from_bytes(n)
+    }
+
+    /// Builds a mask of `len` bytes together with the same state byte by byte.
+    fn mask_and_model(len: u64, state: impl Fn(u64) -> bool) -> (UndefMask, Vec<bool>) {
+        let mut mask = UndefMask::new(bytes(len));
+        let model = (0..len).map(state).collect::<Vec<_>>();
+        for (i, &defined) in model.iter().enumerate() {
+            mask.set(bytes(i as u64), defined);
+        }
+        (mask, model)
+    }
+
+    fn assert_matches(mask: &UndefMask, model: &[bool]) {
+        for (i, &defined) in model.iter().enumerate() {
+            assert_eq!(mask.get(bytes(i as u64)), defined, "byte {}", i);
+        }
+    }
+
+    // Ranges starting and ending inside, at and across block boundaries.
+    const RANGES: &[(u64, u64)] = &[
+        (0, 0), (0, 1), (3, 5), (0, 64), (1, 64), (0, 65), (63, 65), (64, 128),
+        (10, 130), (60, 200), (127, 129), (128, 200), (199, 200), (0, 200),
+    ];
+
+    #[test]
+    fn set_range_inbounds() {
+        for &(start, end) in RANGES {
+            for &new_state in &[true, false] {
+                let (mut mask, mut model) = mask_and_model(200, |i| i % 3 == 0);
+                mask.set_range_inbounds(bytes(start), bytes(end), new_state);
+                for defined in &mut model[start as usize..end as usize] {
+                    *defined = new_state;
+                }
+                assert_matches(&mask, &model);
+            }
+        }
+    }
+
+    #[test]
+    fn is_range_defined() {
+        let (mask, model) = mask_and_model(200, |i| i != 0 && i != 70 && i != 130 && i != 191);
+        for &(start, end) in RANGES {
+            let first_undef = (start..end).find(|&i| !model[i as usize]);
+            let expected = match first_undef {
+                Some(i) => Err(bytes(i)),
+                None => Ok(()),
+            };
+            assert_eq!(mask.is_range_defined(bytes(start), bytes(end)), expected,
+                       "range {}..{}", start, end);
+        }
+        assert_eq!(mask.is_range_defined(bytes(71), bytes(130)), Ok(()));
+        assert_eq!(mask.is_range_defined(bytes(150), bytes(201)), Err(bytes(200)));
+    }
+
+    #[test]
+    fn copy_range() {
+        let (src, src_model) = mask_and_model(300, |i| i % 7 < 3);
+        for &src_start in &[0, 5, 64, 100] {
+            for &dest_start in &[0, 1, 63, 64, 99] {
+                for &size in &[0, 1, 63, 64, 65, 130, 200] {
+                    let (mut dest, mut model) = mask_and_model(300, |i| i % 2 == 0);
+                    dest.copy_range(&src, bytes(src_start), bytes(dest_start), bytes(size));
+                    for i in 0..size as usize {
+                        model[dest_start as usize + i] = src_model[src_start as usize + i];
+                    }
+                    assert_matches(&dest, &model);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn grow() {
+        let mut mask = UndefMask::new(bytes(60));
+        mask.set_range_inbounds(bytes(0), bytes(60), true);
+        mask.grow(bytes(10), false);
+        mask.grow(bytes(100), true);
+        assert_eq!(mask.is_range_defined(bytes(0), bytes(60)), Ok(()));
+        assert_eq!(mask.is_range_defined(bytes(0), bytes(170)), Err(bytes(60)));
+        assert_eq!(mask.is_range_defined(bytes(70), bytes(170)), Ok(()));
+    }
+
+    /// Returns the size of the encoded allocation.
+    fn round_trip(alloc: &Allocation) -> usize {
+        let mut encoder = opaque::Encoder::new(vec![]);
+        alloc.encode_compressed(&mut encoder).unwrap();
+        let data = encoder.into_inner();
+        let mut decoder = opaque::Decoder::new(&data, 0);
+        assert_eq!(&Allocation::decode_compressed(&mut decoder).unwrap(), alloc);
+        assert_eq!(decoder.position(), data.len());
+        data.len()
+    }
+
+    #[test]
+    fn compressed_round_trip() {
+        round_trip(&Allocation::from_byte_aligned_bytes(&[], ()));
+        round_trip(&Allocation::from_byte_aligned_bytes(&[1, 2, 3], ()));
+        round_trip(&Allocation::undef(bytes(100), Align::from_bytes(8).unwrap(), ()));
+
+        // Zero runs shorter and longer than the ones worth compressing, at the start, in the
+        // middle and at the end, with undefined bytes in between.
+        let mut data = vec![0; 20];
+        data.extend(&[1, 2, 3]);
+        data.extend(vec![0; 5]);
+        data.push(4);
+        data.extend(vec![0; 100]);
+        data.extend(&[5, 6]);
+        data.extend(vec![0; 3]);
+        let mut alloc = Allocation::from_byte_aligned_bytes(&data, ());
+        alloc.undef_mask.set_range_inbounds(bytes(0), bytes(10), false);
+        alloc.undef_mask.set_range_inbounds(bytes(60), bytes(70), false);
+        alloc.undef_mask.set(bytes(data.len() as u64 - 1), false);
+        round_trip(&alloc);
+    }
+
+    #[test]
+    fn compressed_zeros() {
+        let zeros = Allocation::from_byte_aligned_bytes(&[0; 4096], ());
+        assert!(round_trip(&zeros) < 64);
+        let undef = Allocation::undef(bytes(4096), Align::from_bytes(1).unwrap(), ());
+        assert!(round_trip(&undef) < 64);
+    }
+}
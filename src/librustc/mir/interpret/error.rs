@@ -16,12 +16,12 @@ use ty::{self, Ty, layout};
 use ty::layout::{Size, Align, LayoutError};
 use rustc_target::spec::abi::Abi;
 
-use super::{RawConst, Pointer, InboundsCheck, ScalarMaybeUndef};
+use super::{RawConst, Pointer, InboundsCheck, ScalarMaybeUndef, AllocId};
 
 use backtrace::Backtrace;
 
 use ty::query::TyCtxtAt;
-use errors::DiagnosticBuilder;
+use errors::{DiagnosticBuilder, DiagnosticId};
 
 use syntax_pos::{Pos, Span};
 use syntax::ast;
@@ -56,16 +56,23 @@ pub struct ConstEvalErr<'tcx> {
     pub stacktrace: Vec<FrameInfo<'tcx>>,
 }
 
-#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+#[derive(Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct FrameInfo<'tcx> {
     pub call_site: Span, // this span is in the caller!
+    /// The span of the statement this frame was executing.
+    pub span: Span,
     pub instance: ty::Instance<'tcx>,
+    /// The promoted constant of `instance` this frame was evaluating, if any.
+    pub promoted: Option<mir::Promoted>,
     pub lint_root: Option<ast::NodeId>,
 }
 
 impl<'tcx> fmt::Display for FrameInfo<'tcx> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         ty::tls::with(|tcx| {
+            if self.promoted.is_some() {
+                return write!(f, "inside a constant promoted from `{}`", self.instance);
+            }
             if tcx.def_key(self.instance.def_id()).disambiguated_data.data
                 == DefPathData::ClosureExpr
             {
@@ -155,12 +162,50 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
             struct_error(tcx, message)
         };
         err.span_label(self.span, self.error.to_string());
-        // Skip the last, which is just the environment of the constant.  The stacktrace
-        // is sometimes empty because we create "fake" eval contexts in CTFE to do work
-        // on constant values.
-        if self.stacktrace.len() > 0 {
-            for frame_info in &self.stacktrace[..self.stacktrace.len()-1] {
-                err.span_label(frame_info.call_site, frame_info.to_string());
+        if let EvalErrorKind::ModifiedConstantMemory(Some(def_span)) = self.error {
+            err.span_label(def_span, "the modified memory belongs to this static");
+        }
+        if let Some((code, kind)) = self.error.diagnostic_class() {
+            // Lints have no error code of their own, so there the class is only visible
+            // in the metadata.
+            if lint_root.is_none() {
+                err.code(DiagnosticId::Error(code.to_string()));
+            }
+            err.metadata("code", code.to_string());
+            err.metadata("kind", kind.to_string());
+        }
+        if let Some((alloc_id, offset)) = self.error.offending_location() {
+            if let Some(alloc_id) = alloc_id {
+                err.metadata("alloc_id", alloc_id.to_string());
+            }
+            err.metadata("offset", offset.bytes().to_string());
+        }
+        // The last frame is just the environment of the constant, it's only worth mentioning
+        // if that is a promoted.  The stacktrace is sometimes empty because we create "fake"
+        // eval contexts in CTFE to do work on constant values.
+        if let Some((env, frames)) = self.stacktrace.split_last() {
+            // Recursion produces long runs of identical frames, only show each of them once.
+            let mut frames = frames.iter().peekable();
+            while let Some(frame_info) = frames.next() {
+                // Calls from code without spans (e.g. MIR shims) have a dummy call site,
+                // point at what the frame was executing instead.
+                let span = if frame_info.call_site.is_dummy() {
+                    frame_info.span
+                } else {
+                    frame_info.call_site
+                };
+                err.span_label(span, frame_info.to_string());
+                let mut repeated = 0;
+                while frames.peek() == Some(&frame_info) {
+                    frames.next();
+                    repeated += 1;
+                }
+                if repeated > 0 {
+                    err.span_label(span, format!("previous frame repeated {} times", repeated));
+                }
+            }
+            if env.promoted.is_some() {
+                err.note(&env.to_string());
             }
         }
         Ok(err)
@@ -280,10 +325,17 @@ pub enum EvalErrorKind<'tcx, O> {
     },
     InvalidNullPointerUsage,
     ReadPointerAsBytes,
+    ReadPartialPointer,
     ReadBytesAsPointer,
+    /// Integer arithmetic was attempted on a value that still carries pointer provenance,
+    /// e.g. a reference that was transmuted to `usize`
+    PointerAsInteger(mir::BinOp),
     ReadForeignStatic,
     InvalidPointerMath,
     ReadUndefBytes(Size),
+    /// The final value of a constant contains a pointer to deallocated memory, stored at
+    /// the given offset of the allocation holding it
+    DanglingPointer(Size),
     DeadLocal,
     InvalidBoolOp(mir::BinOp),
     Unimplemented(String),
@@ -307,7 +359,9 @@ pub enum EvalErrorKind<'tcx, O> {
     ValidationFailure(String),
     CalledClosureAsFunction,
     VtableForArgumentlessMethod,
-    ModifiedConstantMemory,
+    /// A write to immutable memory; carries the span of the static the memory belongs to,
+    /// if it belongs to one
+    ModifiedConstantMemory(Option<Span>),
     ModifiedStatic,
     AssumptionNotHeld,
     InlineAsm,
@@ -320,6 +374,9 @@ pub enum EvalErrorKind<'tcx, O> {
     Layout(layout::LayoutError<'tcx>),
     HeapAllocZeroBytes,
     HeapAllocNonPowerOfTwoAlignment(u64),
+    LeakedAllocation,
+    MutableHeapAllocation,
+    EscapingMutRef,
     Unreachable,
     Panic {
         msg: Symbol,
@@ -340,6 +397,12 @@ pub enum EvalErrorKind<'tcx, O> {
     GeneratorResumedAfterReturn,
     GeneratorResumedAfterPanic,
     InfiniteLoop,
+    /// The limit set by `-Z const-eval-step-limit` was exceeded
+    StepLimitReached,
+    /// The result of a pointer comparison depends on the runtime addresses of allocations
+    InvalidPointerComparison,
+    /// The limit set by `-Z const-eval-memory-limit` was exceeded
+    MemoryExhausted,
 }
 
 pub type EvalResult<'tcx, T = ()> = Result<T, EvalError<'tcx>>;
@@ -372,8 +435,13 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "type validation failed",
             ReadPointerAsBytes =>
                 "a raw memory access tried to access part of a pointer value as raw bytes",
+            ReadPartialPointer =>
+                "a memory access tried to read a pointer from bytes overlapping another pointer",
             ReadBytesAsPointer =>
                 "a memory access tried to interpret some bytes as a pointer",
+            PointerAsInteger(_) =>
+                "tried to do integer arithmetic on a pointer whose address is not known \
+                at compile-time",
             ReadForeignStatic =>
                 "tried to read from foreign (extern) static",
             InvalidPointerMath =>
@@ -381,6 +449,8 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 e.g., comparing pointers into different allocations",
             ReadUndefBytes(_) =>
                 "attempted to read undefined bytes",
+            DanglingPointer(_) =>
+                "encountered dangling pointer in final constant",
             DeadLocal =>
                 "tried to access a dead local variable",
             InvalidBoolOp(_) =>
@@ -411,7 +481,7 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "tried to call a closure through a function pointer",
             VtableForArgumentlessMethod =>
                 "tried to call a vtable function without arguments",
-            ModifiedConstantMemory =>
+            ModifiedConstantMemory(_) =>
                 "tried to modify constant memory",
             ModifiedStatic =>
                 "tried to modify a static's initial value from another static's initializer",
@@ -441,6 +511,14 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
             HeapAllocNonPowerOfTwoAlignment(_) =>
                 "tried to re-, de-, or allocate heap memory with alignment that is not a power of \
                 two",
+            LeakedAllocation =>
+                "leaked allocation: heap memory was neither deallocated nor made part of the \
+                final value",
+            MutableHeapAllocation =>
+                "heap memory can only be part of the final value of an immutable static without \
+                interior mutability",
+            EscapingMutRef =>
+                "mutable reference escapes into the final value of a constant",
             Unreachable =>
                 "entered unreachable code",
             Panic { .. } =>
@@ -472,6 +550,65 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
             GeneratorResumedAfterPanic => "generator resumed after panicking",
             InfiniteLoop =>
                 "duplicate interpreter state observed here, const evaluation will never terminate",
+            StepLimitReached =>
+                "exceeded the interpreter step limit set by `-Z const-eval-step-limit`",
+            InvalidPointerComparison =>
+                "tried to compare pointers whose relation is not known at compile time",
+            MemoryExhausted =>
+                "exceeded the memory limit set by `-Z const-eval-memory-limit`",
+        }
+    }
+
+    /// The stable error code and the name of the failure class of errors that user code can
+    /// run into during CTFE.  The code replaces E0080 on hard errors, and both end up in the
+    /// metadata of the emitted diagnostic, so tools can tell failures apart without parsing
+    /// the message.
+    pub fn diagnostic_class(&self) -> Option<(&'static str, &'static str)> {
+        use self::EvalErrorKind::*;
+        Some(match *self {
+            ReadUndefBytes(_) => ("E0722", "undefined_bytes"),
+            PointerOutOfBounds { .. } |
+            DanglingPointerDeref |
+            DanglingPointer(_) |
+            InvalidNullPointerUsage |
+            UnterminatedCString(_) => ("E0723", "invalid_memory_access"),
+            ReadPointerAsBytes |
+            ReadPartialPointer |
+            ReadBytesAsPointer |
+            PointerAsInteger(_) |
+            InvalidPointerMath |
+            InvalidPointerComparison => ("E0724", "pointer_provenance"),
+            ModifiedConstantMemory(_) |
+            ModifiedStatic |
+            MutableHeapAllocation |
+            EscapingMutRef => ("E0725", "immutable_memory"),
+            ValidationFailure(_) |
+            InvalidBool |
+            InvalidChar(_) |
+            InvalidDiscriminant(_) => ("E0726", "invalid_value"),
+            Panic { .. } |
+            BoundsCheck { .. } |
+            Overflow(_) |
+            OverflowNeg |
+            DivisionByZero |
+            RemainderByZero => ("E0727", "panic"),
+            StackFrameLimitReached |
+            StepLimitReached |
+            InfiniteLoop |
+            MemoryExhausted => ("E0728", "resource_limit"),
+            _ => return None,
+        })
+    }
+
+    /// The allocation (if known) and offset that the error is about.
+    pub fn offending_location(&self) -> Option<(Option<AllocId>, Size)> {
+        use self::EvalErrorKind::*;
+        match *self {
+            PointerOutOfBounds { ptr, .. } |
+            UnterminatedCString(ptr) => Some((Some(ptr.alloc_id), ptr.offset)),
+            ReadUndefBytes(offset) |
+            DanglingPointer(offset) => Some((None, offset)),
+            _ => None,
         }
     }
 }
@@ -505,6 +642,12 @@ impl<'tcx, O: fmt::Debug> fmt::Debug for EvalErrorKind<'tcx, O> {
                 write!(f, "type validation failed: {}", err)
             }
             NoMirFor(ref func) => write!(f, "no mir for `{}`", func),
+            DanglingPointer(offset) =>
+                write!(f, "encountered dangling pointer in final constant at offset {}",
+                       offset.bytes()),
+            PointerAsInteger(op) =>
+                write!(f, "tried to apply `{:?}` to a pointer that was turned into an integer; \
+                       its address is not known at compile-time", op),
             FunctionAbiMismatch(caller_abi, callee_abi) =>
                 write!(f, "tried to call a function with ABI {:?} using caller ABI {:?}",
                     callee_abi, caller_abi),
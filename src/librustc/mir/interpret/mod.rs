@@ -43,13 +43,15 @@ use middle::region;
 use std::io;
 use std::hash::Hash;
 use rustc_serialize::{Encoder, Decodable, Encodable};
-use rustc_data_structures::fx::FxHashMap;
-use rustc_data_structures::sync::{Lock as Mutex, HashMapExt};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::sync::Lock as Mutex;
 use rustc_data_structures::tiny_list::TinyList;
+use rustc_data_structures::sharded::{IdMap, Sharded};
 use byteorder::{WriteBytesExt, ReadBytesExt, LittleEndian, BigEndian};
 use ty::codec::TyDecoder;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::num::NonZeroU32;
+use syntax::ast::Mutability;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
 pub enum Lock {
@@ -105,12 +107,12 @@ pub fn specialized_encode_alloc_id<
     alloc_id: AllocId,
 ) -> Result<(), E::Error> {
     let alloc_type: AllocType<'tcx, &'tcx Allocation> =
-        tcx.alloc_map.lock().get(alloc_id).expect("no value for AllocId");
+        tcx.alloc_map.get(alloc_id).expect("no value for AllocId");
     match alloc_type {
         AllocType::Memory(alloc) => {
             trace!("encoding {:?} with {:#?}", alloc_id, alloc);
             AllocKind::Alloc.encode(encoder)?;
-            alloc.encode(encoder)?;
+            alloc.encode_compressed(encoder)?;
         }
         AllocType::Function(fn_instance) => {
             trace!("encoding {:?} with {:#?}", alloc_id, fn_instance);
@@ -211,7 +213,7 @@ impl<'s> AllocDecodingSession<'s> {
                         AllocKind::Alloc => {
                             // If this is an allocation, we need to reserve an
                             // AllocId so we can decode cyclic graphs.
-                            let alloc_id = decoder.tcx().alloc_map.lock().reserve();
+                            let alloc_id = decoder.tcx().alloc_map.reserve();
                             *entry = State::InProgress(
                                 TinyList::new_single(self.session_id),
                                 alloc_id);
@@ -252,11 +254,12 @@ impl<'s> AllocDecodingSession<'s> {
         let alloc_id = decoder.with_position(pos, |decoder| {
             match alloc_kind {
                 AllocKind::Alloc => {
-                    let allocation = <&'tcx Allocation as Decodable>::decode(decoder)?;
+                    let allocation = Allocation::decode_compressed(decoder)?;
+                    let allocation = decoder.tcx().intern_const_alloc(allocation);
                     // We already have a reserved AllocId.
                     let alloc_id = alloc_id.unwrap();
                     trace!("decoded alloc {:?} {:#?}", alloc_id, allocation);
-                    decoder.tcx().alloc_map.lock().set_id_same_memory(alloc_id, allocation);
+                    decoder.tcx().alloc_map.set_id_same_memory(alloc_id, allocation);
                     Ok(alloc_id)
                 },
                 AllocKind::Fn => {
@@ -264,14 +267,14 @@ impl<'s> AllocDecodingSession<'s> {
                     trace!("creating fn alloc id");
                     let instance = ty::Instance::decode(decoder)?;
                     trace!("decoded fn alloc instance: {:?}", instance);
-                    let alloc_id = decoder.tcx().alloc_map.lock().create_fn_alloc(instance);
+                    let alloc_id = decoder.tcx().alloc_map.create_fn_alloc(instance);
                     Ok(alloc_id)
                 },
                 AllocKind::Static => {
                     assert!(alloc_id.is_none());
                     trace!("creating extern static alloc id at");
                     let did = DefId::decode(decoder)?;
-                    let alloc_id = decoder.tcx().alloc_map.lock().intern_static(did);
+                    let alloc_id = decoder.tcx().alloc_map.intern_static(did);
                     Ok(alloc_id)
                 }
             }
@@ -303,15 +306,18 @@ pub enum AllocType<'tcx, M> {
 }
 
 pub struct AllocMap<'tcx, M> {
-    /// Lets you know what an AllocId refers to
-    id_to_type: FxHashMap<AllocId, AllocType<'tcx, M>>,
+    /// Lets you know what an AllocId refers to. Looking up an id does not take a lock.
+    id_to_type: IdMap<AllocType<'tcx, M>>,
 
     /// Used to ensure that functions and statics only get one associated AllocId
-    type_interner: FxHashMap<AllocType<'tcx, M>, AllocId>,
+    type_interner: Sharded<FxHashMap<AllocType<'tcx, M>, AllocId>>,
+
+    /// Used to ensure that identical read-only memory only gets one associated AllocId
+    memory_interner: Sharded<FxHashMap<M, AllocId>>,
 
     /// The AllocId to assign to the next requested id.
     /// Always incremented, never gets smaller.
-    next_id: AllocId,
+    next_id: AtomicU64,
 }
 
 impl<'tcx, M: fmt::Debug + Eq + Hash + Clone> AllocMap<'tcx, M> {
@@ -319,44 +325,47 @@ impl<'tcx, M: fmt::Debug + Eq + Hash + Clone> AllocMap<'tcx, M> {
         AllocMap {
             id_to_type: Default::default(),
             type_interner: Default::default(),
-            next_id: AllocId(0),
+            memory_interner: Default::default(),
+            next_id: AtomicU64::new(0),
         }
     }
 
     /// obtains a new allocation ID that can be referenced but does not
     /// yet have an allocation backing it.
     pub fn reserve(
-        &mut self,
+        &self,
     ) -> AllocId {
-        let next = self.next_id;
-        self.next_id.0 = self.next_id.0
-            .checked_add(1)
-            .expect("You overflowed a u64 by incrementing by 1... \
-                     You've just earned yourself a free drink if we ever meet. \
-                     Seriously, how did you do that?!");
-        next
+        let next = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if next == u64::max_value() {
+            bug!("You overflowed a u64 by incrementing by 1... \
+                  You've just earned yourself a free drink if we ever meet. \
+                  Seriously, how did you do that?!");
+        }
+        AllocId(next)
     }
 
-    fn intern(&mut self, alloc_type: AllocType<'tcx, M>) -> AllocId {
-        if let Some(&alloc_id) = self.type_interner.get(&alloc_type) {
+    fn intern(&self, alloc_type: AllocType<'tcx, M>) -> AllocId {
+        // Keep the shard locked until the new id is recorded, so racing threads agree on it
+        let mut type_interner = self.type_interner.write(&alloc_type);
+        if let Some(&alloc_id) = type_interner.get(&alloc_type) {
             return alloc_id;
         }
         let id = self.reserve();
         debug!("creating alloc_type {:?} with id {}", alloc_type, id);
-        self.id_to_type.insert(id, alloc_type.clone());
-        self.type_interner.insert(alloc_type, id);
+        self.id_to_type.insert(id.0, alloc_type.clone());
+        type_interner.insert(alloc_type, id);
         id
     }
 
     // FIXME: Check if functions have identity. If not, we should not intern these,
     // but instead create a new id per use.
     // Alternatively we could just make comparing function pointers an error.
-    pub fn create_fn_alloc(&mut self, instance: Instance<'tcx>) -> AllocId {
+    pub fn create_fn_alloc(&self, instance: Instance<'tcx>) -> AllocId {
         self.intern(AllocType::Function(instance))
     }
 
     pub fn get(&self, id: AllocId) -> Option<AllocType<'tcx, M>> {
-        self.id_to_type.get(&id).cloned()
+        self.id_to_type.get(id.0).cloned()
     }
 
     pub fn unwrap_memory(&self, id: AllocId) -> M {
@@ -366,24 +375,74 @@ impl<'tcx, M: fmt::Debug + Eq + Hash + Clone> AllocMap<'tcx, M> {
         }
     }
 
-    pub fn intern_static(&mut self, static_id: DefId) -> AllocId {
+    pub fn intern_static(&self, static_id: DefId) -> AllocId {
         self.intern(AllocType::Static(static_id))
     }
 
-    pub fn allocate(&mut self, mem: M) -> AllocId {
+    pub fn set_id_memory(&self, id: AllocId, mem: M) {
+        if let (old, false) = self.id_to_type.insert(id.0, AllocType::Memory(mem)) {
+            bug!("tried to set allocation id {}, but it was already existing as {:#?}", id, old);
+        }
+    }
+
+    pub fn set_id_same_memory(&self, id: AllocId, mem: M) {
+        let mem = AllocType::Memory(mem);
+        let (old, _) = self.id_to_type.insert(id.0, mem.clone());
+        assert!(*old == mem, "inserted allocation id {} with a different value", id);
+    }
+
+    /// All ids pointing to memory, in increasing order.
+    pub fn memory_allocs(&self) -> Vec<(AllocId, M)> {
+        self.id_to_type.iter().filter_map(|(id, alloc_type)| match *alloc_type {
+            AllocType::Memory(ref mem) => Some((AllocId(id), mem.clone())),
+            AllocType::Function(_) | AllocType::Static(_) => None,
+        }).collect()
+    }
+}
+
+impl<'tcx> AllocMap<'tcx, &'tcx Allocation> {
+    /// Returns an id pointing to `mem`. Read-only memory is deduplicated by its contents,
+    /// so e.g. repeated string literals share a single id (and a single global once
+    /// codegen'd).
+    pub fn allocate(&self, mem: &'tcx Allocation) -> AllocId {
+        if mem.mutability == Mutability::Mutable {
+            let id = self.reserve();
+            self.set_id_memory(id, mem);
+            return id;
+        }
+        let mut memory_interner = self.memory_interner.write(&mem);
+        if let Some(&id) = memory_interner.get(&mem) {
+            return id;
+        }
         let id = self.reserve();
         self.set_id_memory(id, mem);
+        memory_interner.insert(mem, id);
         id
     }
 
-    pub fn set_id_memory(&mut self, id: AllocId, mem: M) {
-        if let Some(old) = self.id_to_type.insert(id, AllocType::Memory(mem)) {
-            bug!("tried to set allocation id {}, but it was already existing as {:#?}", id, old);
+    /// Removes all memory that is not reachable from `live_roots`, e.g. the results of
+    /// temporary constants that were only needed while evaluating something else.
+    /// Functions and statics are always kept, since their ids are interned.
+    pub fn prune(&self, live_roots: impl IntoIterator<Item = AllocId>) {
+        let mut live = FxHashSet::default();
+        let mut todo: Vec<AllocId> = live_roots.into_iter().collect();
+        while let Some(id) = todo.pop() {
+            if !live.insert(id) {
+                continue;
+            }
+            if let Some(AllocType::Memory(alloc)) = self.get(id) {
+                todo.extend(alloc.relocations.values().map(|&((), id)| id));
+            }
         }
-    }
 
-    pub fn set_id_same_memory(&mut self, id: AllocId, mem: M) {
-       self.id_to_type.insert_same(id, AllocType::Memory(mem));
+        let pruned = self.id_to_type.retain(|id, alloc_type| match *alloc_type {
+            AllocType::Memory(_) => live.contains(&AllocId(id)),
+            AllocType::Function(_) | AllocType::Static(_) => true,
+        });
+        for mut shard in self.memory_interner.write_all() {
+            shard.retain(|_, id| live.contains(id));
+        }
+        debug!("pruned {} unreachable allocations", pruned);
     }
 }
 
@@ -429,3 +488,55 @@ pub fn truncate(value: u128, size: Size) -> u128 {
     // truncate (shift left to drop out leftover values, shift right to fill with zeroes)
     (value << shift) >> shift
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AllocMap, Allocation};
+    use hir::def_id::{DefId, CRATE_DEF_INDEX};
+    use ty::layout::Size;
+
+    fn bytes(byte: u8) -> Allocation {
+        Allocation::from_byte_aligned_bytes(&[byte; 8], ())
+    }
+
+    #[test]
+    fn prune() {
+        let (leaf, dead_leaf) = (bytes(1), bytes(2));
+        // Declared before the map, which must not outlive them
+        let (root, dead): (Allocation, Allocation);
+        let map = AllocMap::new();
+
+        let leaf_id = map.allocate(&leaf);
+        let dead_leaf_id = map.allocate(&dead_leaf);
+        root = {
+            let mut alloc = bytes(3);
+            alloc.relocations.insert(Size::ZERO, ((), leaf_id));
+            alloc
+        };
+        dead = {
+            let mut alloc = bytes(4);
+            alloc.relocations.insert(Size::ZERO, ((), dead_leaf_id));
+            alloc
+        };
+        let root_id = map.allocate(&root);
+        let dead_id = map.allocate(&dead);
+        let static_id = map.intern_static(DefId::local(CRATE_DEF_INDEX));
+
+        map.prune(vec![root_id]);
+
+        // Everything reachable from the root is kept, along with all statics
+        assert!(map.get(root_id).is_some());
+        assert!(map.get(leaf_id).is_some());
+        assert!(map.get(static_id).is_some());
+        assert!(map.get(dead_id).is_none());
+        assert!(map.get(dead_leaf_id).is_none());
+        assert_eq!(map.memory_allocs().len(), 2);
+
+        // Pruned memory is interned anew, live memory is still deduplicated
+        let dead_leaf_again = map.allocate(&dead_leaf);
+        assert_ne!(dead_leaf_again, dead_leaf_id);
+        assert!(map.get(dead_leaf_again).is_some());
+        assert_eq!(map.allocate(&leaf), leaf_id);
+        assert_eq!(map.intern_static(DefId::local(CRATE_DEF_INDEX)), static_id);
+    }
+}
@@ -19,6 +19,7 @@ use mir::interpret::{ConstValue, EvalErrorKind, Scalar};
 use mir::visit::MirVisitable;
 use rustc_apfloat::ieee::{Double, Single};
 use rustc_apfloat::Float;
+use rustc_data_structures::bit_set::BitSet;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::graph::dominators::{dominators, Dominators};
 use rustc_data_structures::graph::{self, GraphPredecessors, GraphSuccessors};
@@ -1806,6 +1807,11 @@ pub enum StatementKind<'tcx> {
     /// - `Bivariant` -- no effect
     AscribeUserType(Place<'tcx>, ty::Variance, Box<UserTypeProjection<'tcx>>),
 
+    /// Increments the counter `index` of the function's `num_counters` coverage counters.
+    /// These statements are only generated when "-Z instrument-mir-coverage" is passed,
+    /// and the span of the statement is the code region the counter measures.
+    CoverageCounter { index: u32, num_counters: u32 },
+
     /// No-op. Useful for deleting instructions without affecting statement indices.
     Nop,
 }
@@ -1852,6 +1858,8 @@ impl<'tcx> Debug for Statement<'tcx> {
                     place,
                 ),
             EscapeToRaw(ref place) => write!(fmt, "EscapeToRaw({:?})", place),
+            CoverageCounter { index, num_counters } =>
+                write!(fmt, "CoverageCounter({}/{})", index, num_counters),
             StorageLive(ref place) => write!(fmt, "StorageLive({:?})", place),
             StorageDead(ref place) => write!(fmt, "StorageDead({:?})", place),
             SetDiscriminant {
@@ -2236,7 +2244,7 @@ pub enum AggregateKind<'tcx> {
     Generator(DefId, GeneratorSubsts<'tcx>, hir::GeneratorMovability),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
 pub enum BinOp {
     /// The `+` operator (addition)
     Add,
@@ -2292,7 +2300,7 @@ pub enum NullOp {
     Box,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
 pub enum UnOp {
     /// The `!` operator for logical inversion
     Not,
@@ -2636,7 +2644,7 @@ pub fn fmt_const_val(f: &mut impl Write, const_val: &ty::Const<'_>) -> fmt::Resu
             if let Scalar::Bits { bits: len, .. } = len {
                 if let Ref(_, &ty::TyS { sty: Str, .. }, _) = ty.sty {
                     return ty::tls::with(|tcx| {
-                        let alloc = tcx.alloc_map.lock().get(ptr.alloc_id);
+                        let alloc = tcx.alloc_map.get(ptr.alloc_id);
                         if let Some(interpret::AllocType::Memory(alloc)) = alloc {
                             assert_eq!(len as usize as u128, len);
                             let slice =
@@ -2804,6 +2812,75 @@ pub struct UnsafetyCheckResult {
     pub unsafe_blocks: Lrc<[(ast::NodeId, bool)]>,
 }
 
+/// A "root candidate" for promotion, which will become the
+/// returned value in a promoted MIR, unless it's a subset
+/// of a larger candidate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PromotionCandidate {
+    /// Borrow of a constant temporary.
+    Ref(Location),
+
+    /// Currently applied to function calls where the callee has the unstable
+    /// `#[rustc_args_required_const]` attribute as well as the SIMD shuffle
+    /// intrinsic. The intrinsic requires the arguments are indeed constant and
+    /// the attribute currently provides the semantic requirement that arguments
+    /// must be constant.
+    Argument { bb: BasicBlock, index: usize },
+}
+
+/// Why a borrow was not promoted to a `'static` constant.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Unpromotable {
+    /// There is no borrow of a temporary at the given location.
+    NotATemporaryBorrow,
+    /// The borrow is mutable.
+    MutableBorrow,
+    /// The borrowed value may contain an `UnsafeCell`.
+    InteriorMutability,
+    /// The borrowed value needs to be dropped.
+    NeedsDrop,
+    /// The borrowed value depends on a function argument.
+    FunctionArgument,
+    /// The borrowed value depends on something that is never promoted, such as
+    /// a temporary that is assigned more than once, or a call to a `const fn`
+    /// that is not `#[rustc_promotable]`.
+    ComplexTemporary,
+    /// The borrowed value is computed by something that can't be part of a
+    /// promoted constant, such as a dereference or a call to a function that
+    /// is not a `const fn`.
+    NotConst,
+}
+
+impl Unpromotable {
+    pub fn description(self) -> &'static str {
+        match self {
+            Unpromotable::NotATemporaryBorrow => "it is not a borrow of a temporary",
+            Unpromotable::MutableBorrow => "it is a mutable borrow",
+            Unpromotable::InteriorMutability => "the value may contain interior mutability",
+            Unpromotable::NeedsDrop => "the value needs to be dropped",
+            Unpromotable::FunctionArgument => "the value depends on a function argument",
+            Unpromotable::ComplexTemporary =>
+                "the value depends on a temporary or `const fn` call that is never promoted",
+            Unpromotable::NotConst =>
+                "the value is computed by an operation that promoted constants cannot contain",
+        }
+    }
+}
+
+/// The result of the promotability analysis of a body.
+#[derive(Clone, Debug, Default)]
+pub struct Promotability {
+    /// Everything that gets promoted, in the order the analysis found it.
+    pub candidates: Vec<PromotionCandidate>,
+    /// Borrows of temporaries that do not get promoted, with the span of the
+    /// borrow, and why.
+    pub unpromotable: Vec<(Location, Span, Unpromotable)>,
+    /// For constants, statics and `const fn`, the qualifications of the value
+    /// of the body and the temporaries that live as long as it does, as
+    /// returned by `mir_const_qualif`.
+    pub const_qualif: Option<(u8, Lrc<BitSet<Local>>)>,
+}
+
 /// The layout of generator state
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct GeneratorLayout<'tcx> {
@@ -3034,6 +3111,7 @@ EnumTypeFoldableImpl! {
         (StatementKind::Retag) { fn_entry, two_phase, place },
         (StatementKind::EscapeToRaw)(place),
         (StatementKind::AscribeUserType)(a, v, b),
+        (StatementKind::CoverageCounter) { index, num_counters },
         (StatementKind::Nop),
     }
 }
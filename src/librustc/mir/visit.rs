@@ -429,6 +429,7 @@ macro_rules! make_mir_visitor {
                     ) => {
                         self.visit_ascribe_user_ty(place, variance, user_ty, location);
                     }
+                    StatementKind::CoverageCounter { .. } |
                     StatementKind::Nop => {}
                 }
             }
@@ -120,6 +120,17 @@ impl CrossLangLto {
     }
 }
 
+/// Which functions get their MIR encoded into the crate metadata, in addition
+/// to the ones that need it to be used from other crates.
+#[derive(Clone, Copy, PartialEq, Hash)]
+pub enum AlwaysEncodeMir {
+    All,
+    /// Non-generic functions whose MIR is no larger than the given size, and
+    /// which only refer to exported items, so other crates can inline them.
+    Small(usize),
+    Disabled,
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum DebugInfo {
     None,
@@ -214,6 +225,7 @@ pub enum ErrorOutputType {
     HumanReadable(ColorConfig),
     Json(bool),
     Short(ColorConfig),
+    HumanAnnotateRs(ColorConfig),
 }
 
 impl Default for ErrorOutputType {
@@ -382,6 +394,15 @@ top_level_options!(
 
         test: bool [TRACKED],
         error_format: ErrorOutputType [UNTRACKED],
+        // Whether the `rendered` field of JSON diagnostics keeps the ANSI
+        // color codes of the human readable output.
+        json_rendered_ansi: bool [UNTRACKED],
+        // Whether to emit a report of the future-incompatible lints that
+        // fired, allowed or not, at the end of the compilation.
+        json_future_incompat: bool [UNTRACKED],
+        // How many errors are shown before the rest are only counted, 0 for
+        // no limit.
+        error_limit: usize [UNTRACKED],
 
         // if Some, enable incremental compilation, using the given
         // directory to store intermediate results
@@ -603,6 +624,9 @@ impl Default for Options {
             borrowck_mode: BorrowckMode::Ast,
             cg: basic_codegen_options(),
             error_format: ErrorOutputType::default(),
+            json_rendered_ansi: false,
+            json_future_incompat: false,
+            error_limit: 0,
             externs: Externs(BTreeMap::new()),
             crate_name: None,
             alt_std_name: None,
@@ -818,11 +842,16 @@ macro_rules! options {
         pub const parse_cross_lang_lto: Option<&str> =
             Some("either a boolean (`yes`, `no`, `on`, `off`, etc), \
                   or the path to the linker plugin");
+        pub const parse_always_encode_mir: Option<&str> =
+            Some("either a boolean (`yes`, `no`, `on`, `off`, etc), \
+                  or the maximum size of the MIR to encode");
+        pub const parse_mir_enable_passes: Option<&str> =
+            Some("a comma-separated list of MIR pass names, each prefixed with `+` or `-`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
-        use super::{$struct_name, Passes, Sanitizer, LtoCli, CrossLangLto};
+        use super::{$struct_name, Passes, Sanitizer, LtoCli, CrossLangLto, AlwaysEncodeMir};
         use rustc_target::spec::{LinkerFlavor, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
 
@@ -1004,6 +1033,29 @@ macro_rules! options {
             }
         }
 
+        fn parse_mir_enable_passes(slot: &mut Vec<(String, bool)>, v: Option<&str>) -> bool {
+            let s = match v {
+                Some(s) => s,
+                None => return false,
+            };
+            for pass in s.split(',') {
+                let (name, enabled) = if pass.starts_with('+') {
+                    (&pass[1..], true)
+                } else if pass.starts_with('-') {
+                    (&pass[1..], false)
+                } else {
+                    return false;
+                };
+                if name.is_empty() {
+                    return false;
+                }
+                // Later mentions of a pass override earlier ones.
+                slot.retain(|&(ref other, _)| other != name);
+                slot.push((name.to_string(), enabled));
+            }
+            true
+        }
+
         fn parse_unpretty(slot: &mut Option<String>, v: Option<&str>) -> bool {
             match v {
                 None => false,
@@ -1056,6 +1108,29 @@ macro_rules! options {
             };
             true
         }
+
+        fn parse_always_encode_mir(slot: &mut AlwaysEncodeMir, v: Option<&str>) -> bool {
+            if v.is_some() {
+                let mut bool_arg = None;
+                if parse_opt_bool(&mut bool_arg, v) {
+                    *slot = if bool_arg.unwrap() {
+                        AlwaysEncodeMir::All
+                    } else {
+                        AlwaysEncodeMir::Disabled
+                    };
+                    return true
+                }
+            }
+
+            *slot = match v {
+                None => AlwaysEncodeMir::All,
+                Some(size) => match size.parse() {
+                    Ok(size) => AlwaysEncodeMir::Small(size),
+                    Err(_) => return false,
+                },
+            };
+            true
+        }
     }
 ) }
 
@@ -1198,6 +1273,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     save_analysis: bool = (false, parse_bool, [UNTRACKED],
         "write syntax and type analysis (in JSON format) information, in \
          addition to normal output"),
+    save_resolve: bool = (false, parse_bool, [UNTRACKED],
+        "write a map from each resolved path and macro invocation to its definition \
+         (in JSON format), in addition to normal output"),
     flowgraph_print_loans: bool = (false, parse_bool, [UNTRACKED],
         "include loan analysis data in -Z unpretty flowgraph output"),
     flowgraph_print_moves: bool = (false, parse_bool, [UNTRACKED],
@@ -1267,6 +1345,20 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print the result of the monomorphization collection pass"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
         "set the MIR optimization level (0-3, default: 1)"),
+    mir_inline_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "set the cost up to which the MIR inliner inlines a function (default: 50)"),
+    mir_enable_passes: Vec<(String, bool)> = (Vec::new(), parse_mir_enable_passes, [TRACKED],
+        "force MIR passes on or off regardless of the MIR optimization level \
+         (e.g. `+Inline,-ConstProp`)"),
+    validate_mir: bool = (false, parse_bool, [UNTRACKED],
+        "check the MIR for internal consistency after every MIR pass"),
+    const_eval_step_limit: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "abort constant evaluation after this many interpreter steps (default: unlimited)"),
+    const_eval_memory_limit: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "abort constant evaluation once it allocates more than this many bytes \
+         (default: unlimited)"),
+    force_const_eval_validation: bool = (false, parse_bool, [TRACKED],
+        "check the validity invariant of every value written during constant evaluation"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "emit noalias metadata for mutable references (default: yes on LLVM >= 6)"),
     arg_align_attributes: bool = (false, parse_bool, [TRACKED],
@@ -1283,16 +1375,34 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "the directory the MIR is dumped into"),
     dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
         "in addition to `.mir` files, create graphviz `.dot` files"),
+    dump_mir_dataflow: bool = (false, parse_bool, [UNTRACKED],
+        "create graphviz `.dot` and `.html` files showing the entry, gen and kill sets of each \
+         dataflow analysis run on the functions selected by `-Z dump-mir`"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
         "if set, exclude the pass number when dumping MIR (used in tests)"),
+    dump_const_allocs: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "write a graphviz `.dot` file of all allocations left after constant evaluation, \
+         with their contents, relocations and the constants owning them"),
+    dump_drop_elaboration: bool = (false, parse_bool, [UNTRACKED],
+        "print a note for each drop flag created by drop elaboration and for each drop \
+         it turned into a no-op or left conditional"),
+    instrument_mir_coverage: bool = (false, parse_bool, [TRACKED],
+        "count the executions of each MIR basic block, and emit a table mapping \
+         the counters of each function to source regions"),
     mir_emit_retag: bool = (false, parse_bool, [TRACKED],
         "emit Retagging MIR statements, interpreted e.g., by miri; implies -Zmir-opt-level=0"),
     perf_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some performance-related statistics"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR"),
-    always_encode_mir: bool = (false, parse_bool, [TRACKED],
-        "encode MIR of all functions into the crate metadata"),
+    mir_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print statistics about the optimized MIR of the crate, and how much \
+         each MIR pass grew or shrank it"),
+    always_encode_mir: AlwaysEncodeMir = (AlwaysEncodeMir::Disabled, parse_always_encode_mir,
+        [TRACKED],
+        "encode MIR of all functions into the crate metadata, or with `=<size>` only of \
+         non-generic functions whose MIR is no larger than the given size, and which only \
+         refer to exported items, so other crates can inline them"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
         "pass `-install_name @rpath/...` to the macOS linker"),
     sanitizer: Option<Sanitizer> = (None, parse_sanitizer, [TRACKED],
@@ -1329,6 +1439,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "in match codegen, do not include FakeRead statements (used by mir-borrowck)"),
     dont_buffer_diagnostics: bool = (false, parse_bool, [UNTRACKED],
         "emit diagnostics rather than buffering (breaks NLL error downgrading, sorting)."),
+    expand_similar_errors: bool = (false, parse_bool, [UNTRACKED],
+        "emit every error instead of folding long runs of errors with the same code"),
+    translate_lang: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "translate diagnostics into this language, using the resources in `share/locale`"),
+    translate_additional_ftl: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "also translate diagnostics with this Fluent resource (for translators)"),
     polonius: bool = (false, parse_bool, [UNTRACKED],
         "enable polonius-based borrow-checker"),
     codegen_time_graph: bool = (false, parse_bool, [UNTRACKED],
@@ -1362,6 +1478,15 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "run `dsymutil` and delete intermediate object files"),
     ui_testing: bool = (false, parse_bool, [UNTRACKED],
         "format compiler diagnostics in a way that's better suitable for UI testing"),
+    terminal_width: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "wrap the labels of diagnostics at this width instead of the detected terminal width"),
+    explain_lint_levels: bool = (false, parse_bool, [UNTRACKED],
+        "note where the level of a lint came from on every emission, not just the first"),
+    edition_migration_report: bool = (false, parse_bool, [UNTRACKED],
+        "list the `extern crate` items that are redundant and may be removed by `cargo fix`"),
+    force_lints: Vec<String> = (Vec::new(), parse_list, [TRACKED],
+        "lints and lint groups (space separated) that `--cap-lints` and driver caps \
+         don't apply to"),
     embed_bitcode: bool = (false, parse_bool, [TRACKED],
         "embed LLVM bitcode in object files"),
     strip_debuginfo_if_disabled: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1721,6 +1846,23 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "How errors and other messages are produced",
             "human|json|short",
         ),
+        opt::multi(
+            "",
+            "json",
+            "Configure the JSON output of the compiler;
+                  `diagnostic-rendered-ansi` keeps the color codes in the
+                  `rendered` field of diagnostics;
+                  `future-incompat` adds a report of the future-incompatible
+                  lints that fired, even allowed ones, at the end",
+            "CONFIG",
+        ),
+        opt::opt(
+            "",
+            "error-limit",
+            "Stop showing errors after N of them and only count the rest
+                  (0 = show all errors, the default)",
+            "N",
+        ),
         opt::opt_s(
             "",
             "color",
@@ -1812,7 +1954,9 @@ pub fn get_cmd_lint_options(matches: &getopts::Matches,
     }
 
     let lint_cap = matches.opt_str("cap-lints").map(|cap| {
+        // `expect` only makes sense on an attribute
         lint::Level::from_str(&cap)
+            .filter(|&level| level != lint::Expect)
             .unwrap_or_else(|| early_error(error_format, &format!("unknown lint level: `{}`", cap)))
     });
     (lint_opts, describe_lints, lint_cap)
@@ -1875,6 +2019,7 @@ pub fn build_session_options_and_crate_config(
             Some("json") => ErrorOutputType::Json(false),
             Some("pretty-json") => ErrorOutputType::Json(true),
             Some("short") => ErrorOutputType::Short(color),
+            Some("human-annotate-rs") => ErrorOutputType::HumanAnnotateRs(color),
             None => ErrorOutputType::HumanReadable(color),
 
             Some(arg) => early_error(
@@ -1890,6 +2035,9 @@ pub fn build_session_options_and_crate_config(
         ErrorOutputType::HumanReadable(color)
     };
 
+    let (json_rendered_ansi, json_future_incompat) = parse_json(matches, error_format);
+    let error_limit = parse_error_limit(matches, error_format);
+
     let unparsed_crate_types = matches.opt_strs("crate-type");
     let crate_types = parse_crate_types_from_list(unparsed_crate_types)
         .unwrap_or_else(|e| early_error(error_format, &e[..]));
@@ -1904,6 +2052,14 @@ pub fn build_session_options_and_crate_config(
             "--error-format=pretty-json is unstable",
         );
     }
+    if let ErrorOutputType::HumanAnnotateRs(_) = error_format {
+        if !debugging_opts.unstable_options {
+            early_error(
+                ErrorOutputType::HumanReadable(color),
+                "--error-format=human-annotate-rs is unstable",
+            );
+        }
+    }
 
     if debugging_opts.pgo_gen.is_some() && !debugging_opts.pgo_use.is_empty() {
         early_error(
@@ -2265,6 +2421,9 @@ pub fn build_session_options_and_crate_config(
             borrowck_mode,
             cg,
             error_format,
+            json_rendered_ansi,
+            json_future_incompat,
+            error_limit,
             externs: Externs(externs),
             crate_name,
             alt_std_name: None,
@@ -2281,6 +2440,48 @@ pub fn build_session_options_and_crate_config(
     )
 }
 
+/// Parses the comma separated values of `--json`, returning whether the JSON
+/// diagnostics should keep the color codes of their rendered output, and
+/// whether to emit a future incompatibility report.
+fn parse_json(matches: &getopts::Matches, error_format: ErrorOutputType) -> (bool, bool) {
+    // Like `--error-format` above, `--json` is unstable and may be missing
+    // from `matches` altogether.
+    if !matches.opts_present(&["json".to_owned()]) {
+        return (false, false);
+    }
+    let mut json_rendered_ansi = false;
+    let mut json_future_incompat = false;
+    for option in matches.opt_strs("json").iter().flat_map(|s| s.split(',')) {
+        match option {
+            "diagnostic-rendered-ansi" => json_rendered_ansi = true,
+            "future-incompat" => json_future_incompat = true,
+            _ => early_error(error_format, &format!("unknown `--json` option `{}`", option)),
+        }
+    }
+    if let ErrorOutputType::Json(_) = error_format {
+        (json_rendered_ansi, json_future_incompat)
+    } else {
+        early_error(error_format, "using `--json` requires also using `--error-format=json`")
+    }
+}
+
+/// Parses `--error-limit`, returning 0 if it wasn't passed.
+fn parse_error_limit(matches: &getopts::Matches, error_format: ErrorOutputType) -> usize {
+    // `--error-limit` is unstable too and may be missing from `matches`.
+    if !matches.opts_present(&["error-limit".to_owned()]) {
+        return 0;
+    }
+    match matches.opt_str("error-limit") {
+        Some(limit) => limit.parse().unwrap_or_else(|_| {
+            early_error(
+                error_format,
+                &format!("argument for --error-limit must be a number (instead was `{}`)", limit),
+            )
+        }),
+        None => 0,
+    }
+}
+
 pub fn parse_crate_types_from_list(list_list: Vec<String>) -> Result<Vec<CrateType>, String> {
     let mut crate_types: Vec<CrateType> = Vec::new();
     for unparsed_crate_type in &list_list {
@@ -2404,7 +2605,7 @@ mod dep_tracking {
     use std::path::PathBuf;
     use std::collections::hash_map::DefaultHasher;
     use super::{CrateType, DebugInfo, ErrorOutputType, OptLevel, OutputTypes,
-                Passes, Sanitizer, LtoCli, CrossLangLto};
+                Passes, Sanitizer, LtoCli, CrossLangLto, AlwaysEncodeMir};
     use syntax::feature_gate::UnstableFeatures;
     use rustc_target::spec::{PanicStrategy, RelroLevel, TargetTriple};
     use syntax::edition::Edition;
@@ -2469,6 +2670,7 @@ mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(TargetTriple);
     impl_dep_tracking_hash_via_hash!(Edition);
     impl_dep_tracking_hash_via_hash!(CrossLangLto);
+    impl_dep_tracking_hash_via_hash!(AlwaysEncodeMir);
 
     impl_dep_tracking_hash_for_sortable_vec_of!(String);
     impl_dep_tracking_hash_for_sortable_vec_of!(PathBuf);
@@ -2480,6 +2682,7 @@ mod dep_tracking {
         Option<cstore::NativeLibraryKind>
     ));
     impl_dep_tracking_hash_for_sortable_vec_of!((String, u64));
+    impl_dep_tracking_hash_for_sortable_vec_of!((String, bool));
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
     where
@@ -2533,7 +2736,7 @@ mod tests {
     use lint;
     use middle::cstore;
     use session::config::{build_configuration, build_session_options_and_crate_config};
-    use session::config::{LtoCli, CrossLangLto};
+    use session::config::{LtoCli, CrossLangLto, AlwaysEncodeMir};
     use session::build_session;
     use std::collections::{BTreeMap, BTreeSet};
     use std::iter::FromIterator;
@@ -3101,6 +3304,8 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.save_analysis = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.save_resolve = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.flowgraph_print_loans = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.flowgraph_print_moves = true;
@@ -3137,6 +3342,16 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_mir_dataflow = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.mir_stats = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_const_allocs = Some(String::from("allocs.dot"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_drop_elaboration = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.validate_mir = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -3187,6 +3402,34 @@ mod tests {
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.mir_inline_threshold = Some(100);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.mir_enable_passes = vec![(String::from("Inline"), true)];
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.always_encode_mir = AlwaysEncodeMir::Small(20);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.instrument_mir_coverage = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.const_eval_step_limit = Some(1000);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.const_eval_memory_limit = Some(1 << 20);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.force_const_eval_validation = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.debugging_opts.relro_level = Some(RelroLevel::Full);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -3194,6 +3437,10 @@ mod tests {
         opts = reference.clone();
         opts.debugging_opts.cross_lang_lto = CrossLangLto::LinkerPluginAuto;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.force_lints = vec![String::from("unused")];
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
     }
 
     #[test]
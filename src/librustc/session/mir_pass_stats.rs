@@ -0,0 +1,75 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use mir::{Mir, StatementKind};
+use rustc_data_structures::fx::FxHashMap;
+
+/// The change in size of MIR bodies caused by a single pass, summed over
+/// every body (and promoted) the pass ran on.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct MirPassDelta {
+    pub runs: usize,
+    pub blocks: i64,
+    pub statements: i64,
+    pub locals: i64,
+}
+
+/// The size of a MIR body, as compared by `MirPassDelta`. `Nop` statements
+/// are not counted, as most passes remove statements by nopping them out.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MirSize {
+    pub blocks: usize,
+    pub statements: usize,
+    pub locals: usize,
+}
+
+impl MirSize {
+    pub fn of(mir: &Mir<'_>) -> MirSize {
+        MirSize {
+            blocks: mir.basic_blocks().len(),
+            statements: mir.basic_blocks().iter().map(|data| {
+                data.statements.iter().filter(|s| match s.kind {
+                    StatementKind::Nop => false,
+                    _ => true,
+                }).count()
+            }).sum(),
+            locals: mir.local_decls.len(),
+        }
+    }
+}
+
+/// How much each MIR pass grew or shrank the MIR of the current crate,
+/// gathered for `-Z mir-stats`. The optimized MIR itself is measured by
+/// `rustc_passes::mir_stats`.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct MirPassStats {
+    pass_deltas: FxHashMap<String, MirPassDelta>,
+}
+
+impl MirPassStats {
+    pub fn record_pass(&mut self, pass: &str, before: MirSize, after: MirSize) {
+        let delta = self.pass_deltas.entry(pass.to_string()).or_default();
+        delta.runs += 1;
+        delta.blocks += after.blocks as i64 - before.blocks as i64;
+        delta.statements += after.statements as i64 - before.statements as i64;
+        delta.locals += after.locals as i64 - before.locals as i64;
+    }
+
+    pub fn print_pass_deltas(&self) {
+        // Sort by the number of statements removed, then by name, so the
+        // most effective passes come first.
+        let mut passes: Vec<_> = self.pass_deltas.iter().collect();
+        passes.sort_by(|a, b| a.1.statements.cmp(&b.1.statements).then_with(|| a.0.cmp(b.0)));
+        for (name, delta) in passes {
+            println!("mir-stats pass `{}`: {} runs, {:+} blocks, {:+} statements, {:+} locals",
+                     name, delta.runs, delta.blocks, delta.statements, delta.locals);
+        }
+    }
+}
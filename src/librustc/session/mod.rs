@@ -10,6 +10,8 @@
 
 pub use self::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo};
 use self::code_stats::CodeStats;
+pub use self::mir_pass_stats::MirSize;
+use self::mir_pass_stats::MirPassStats;
 
 use dep_graph::cgu_reuse_tracker::CguReuseTracker;
 use hir::def_id::CrateNum;
@@ -22,20 +24,25 @@ use middle::dependency_format;
 use session::search_paths::PathKind;
 use session::config::{OutputType, Lto};
 use util::nodemap::{FxHashMap, FxHashSet};
-use util::common::{duration_to_secs_str, ErrorReported};
+use util::common::{duration_to_secs_str, print_time_passes_entry, ErrorReported};
 use util::common::ProfileQueriesMsg;
 
 use rustc_data_structures::base_n;
 use rustc_data_structures::sync::{self, Lrc, Lock, LockCell, OneThread, Once, RwLock};
 
-use errors::{self, DiagnosticBuilder, DiagnosticId, Applicability};
+use errors;
+// Re-exported for the code generated by `#[derive(SessionDiagnostic)]`.
+pub use errors::{Applicability, DiagnosticBuilder, DiagnosticId};
 use errors::emitter::{Emitter, EmitterWriter};
+use errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
+use errors::translation::MessageCatalog;
 use syntax::ast::{self, NodeId};
 use syntax::edition::Edition;
 use syntax::feature_gate::{self, AttributeType};
 use syntax::json::JsonEmitter;
 use syntax::source_map;
 use syntax::parse::{self, ParseSess};
+use syntax::symbol::Symbol;
 use syntax_pos::{MultiSpan, Span};
 use util::profiling::SelfProfiler;
 
@@ -54,6 +61,7 @@ use std::sync::mpsc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 mod code_stats;
+mod mir_pass_stats;
 pub mod config;
 pub mod filesearch;
 pub mod search_paths;
@@ -86,6 +94,9 @@ pub struct Session {
     /// (sub)diagnostics that have been set once, but should not be set again,
     /// in order to avoid redundantly verbose output (Issue #24690, #44953).
     pub one_time_diagnostics: Lock<FxHashSet<(DiagnosticMessageId, Option<Span>, String)>>,
+    /// The `#[expect]` lint attributes seen so far, and whether their lint
+    /// has been emitted.
+    pub lint_expectations: Lock<Vec<lint::LintExpectation>>,
     pub plugin_llvm_passes: OneThread<RefCell<Vec<String>>>,
     pub plugin_attributes: OneThread<RefCell<Vec<(String, AttributeType)>>>,
     pub crate_types: Once<Vec<config::CrateType>>,
@@ -137,6 +148,9 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: Lock<CodeStats>,
 
+    /// How much each MIR pass changed the MIR of the crate, gathered by -Z mir-stats.
+    pub mir_pass_stats: Lock<MirPassStats>,
+
     next_node_id: OneThread<Cell<ast::NodeId>>,
 
     /// If -zfuel=crate=n is specified, Some(crate).
@@ -177,6 +191,12 @@ pub struct PerfStats {
     pub normalize_ty_after_erasing_regions: AtomicUsize,
     /// Number of times this query is invoked.
     pub normalize_projection_ty: AtomicUsize,
+    /// The accumulated time spent in each MIR pass, only recorded with `-Z time-passes`
+    pub mir_pass_times: Lock<FxHashMap<String, Duration>>,
+    /// Number of unwind edges to no-op landing pads removed from MIR.
+    pub nop_landing_pads_removed: AtomicUsize,
+    /// Number of other jumps to no-op landing pads redirected to a single resume block.
+    pub nop_landing_pad_jumps_folded: AtomicUsize,
 }
 
 /// Enum to support dispatch of one-time diagnostics (in Session.diag_once)
@@ -202,6 +222,13 @@ impl From<&'static lint::Lint> for DiagnosticMessageId {
     }
 }
 
+/// A diagnostic declared as a struct, usually with `#[derive(SessionDiagnostic)]` from
+/// `librustc_macros`, and emitted with `Session::emit_err`.
+pub trait SessionDiagnostic<'a> {
+    /// Builds the diagnostic, ready to be emitted.
+    fn into_diagnostic(self, sess: &'a Session) -> DiagnosticBuilder<'a>;
+}
+
 impl Session {
     pub fn local_crate_disambiguator(&self) -> CrateDisambiguator {
         *self.crate_disambiguator.get()
@@ -240,6 +267,9 @@ impl Session {
     ) -> DiagnosticBuilder<'a> {
         self.diagnostic().struct_span_err_with_code(sp, msg, code)
     }
+    pub fn emit_err<'a, D: SessionDiagnostic<'a>>(&'a self, err: D) {
+        err.into_diagnostic(self).emit()
+    }
     // FIXME: This method should be removed (every error should have an associated error code).
     pub fn struct_err<'a>(&'a self, msg: &str) -> DiagnosticBuilder<'a> {
         self.diagnostic().struct_err(msg)
@@ -388,6 +418,45 @@ impl Session {
         }
     }
 
+    /// Records an `#[expect]` attribute on the node `id`. Lint levels are built
+    /// more than once for the same attributes, so an expectation already known
+    /// only has its node filled in.
+    pub fn expect_lint(
+        &self,
+        span: Span,
+        lint_name: ast::Name,
+        reason: Option<Symbol>,
+        id: Option<ast::NodeId>,
+    ) {
+        let mut expectations = self.lint_expectations.borrow_mut();
+        match expectations.iter_mut().find(|expectation| expectation.span == span) {
+            Some(expectation) => expectation.id = expectation.id.or(id),
+            None => expectations.push(lint::LintExpectation {
+                span,
+                id,
+                lint_name,
+                reason,
+                fulfilled: false,
+            }),
+        }
+    }
+
+    /// Marks the `#[expect]` attribute at `span` as fulfilled, because its
+    /// lint was emitted.
+    pub fn fulfill_lint_expectation(&self, span: Span, lint_name: ast::Name) {
+        let mut expectations = self.lint_expectations.borrow_mut();
+        match expectations.iter_mut().find(|expectation| expectation.span == span) {
+            Some(expectation) => expectation.fulfilled = true,
+            None => expectations.push(lint::LintExpectation {
+                span,
+                id: None,
+                lint_name,
+                reason: None,
+                fulfilled: true,
+            }),
+        }
+    }
+
     pub fn reserve_node_ids(&self, count: usize) -> ast::NodeId {
         let id = self.next_node_id.get();
 
@@ -857,6 +926,19 @@ impl Session {
                  self.perf_stats.normalize_ty_after_erasing_regions.load(Ordering::Relaxed));
         println!("normalize_projection_ty:                       {}",
                  self.perf_stats.normalize_projection_ty.load(Ordering::Relaxed));
+        println!("MIR no-op landing pads removed:                {}",
+                 self.perf_stats.nop_landing_pads_removed.load(Ordering::Relaxed));
+        println!("MIR jumps to no-op landing pads folded:        {}",
+                 self.perf_stats.nop_landing_pad_jumps_folded.load(Ordering::Relaxed));
+    }
+
+    pub fn print_mir_pass_times(&self) {
+        let times = self.perf_stats.mir_pass_times.lock();
+        let mut times: Vec<_> = times.iter().collect();
+        times.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (name, &duration) in times {
+            print_time_passes_entry(true, &format!("MIR pass {}", name), duration);
+        }
     }
 
     /// We want to know if we're allowed to do an optimization for crate foo from -z fuel=foo=n.
@@ -1041,6 +1123,13 @@ pub fn build_session_with_source_map(
     let report_delayed_bugs = sopts.debugging_opts.report_delayed_bugs;
 
     let external_macro_backtrace = sopts.debugging_opts.external_macro_backtrace;
+    // Tools reading the JSON output want to see every error.
+    let expand_similar_errors = sopts.debugging_opts.expand_similar_errors ||
+        match sopts.error_format {
+            config::ErrorOutputType::Json(_) => true,
+            _ => false,
+        };
+    let error_limit = sopts.error_limit;
 
     let emitter: Box<dyn Emitter + sync::Send> =
         match (sopts.error_format, emitter_dest) {
@@ -1050,18 +1139,21 @@ pub fn build_session_with_source_map(
                     Some(source_map.clone()),
                     false,
                     sopts.debugging_opts.teach,
-                ).ui_testing(sopts.debugging_opts.ui_testing),
+                ).ui_testing(sopts.debugging_opts.ui_testing)
+                    .terminal_width(sopts.debugging_opts.terminal_width),
             ),
             (config::ErrorOutputType::HumanReadable(_), Some(dst)) => Box::new(
                 EmitterWriter::new(dst, Some(source_map.clone()), false, false)
-                    .ui_testing(sopts.debugging_opts.ui_testing),
+                    .ui_testing(sopts.debugging_opts.ui_testing)
+                    .terminal_width(sopts.debugging_opts.terminal_width),
             ),
             (config::ErrorOutputType::Json(pretty), None) => Box::new(
                 JsonEmitter::stderr(
                     Some(registry),
                     source_map.clone(),
                     pretty,
-                ).ui_testing(sopts.debugging_opts.ui_testing),
+                ).ui_testing(sopts.debugging_opts.ui_testing)
+                    .rendered_ansi(sopts.json_rendered_ansi),
             ),
             (config::ErrorOutputType::Json(pretty), Some(dst)) => Box::new(
                 JsonEmitter::new(
@@ -1069,7 +1161,8 @@ pub fn build_session_with_source_map(
                     Some(registry),
                     source_map.clone(),
                     pretty,
-                ).ui_testing(sopts.debugging_opts.ui_testing),
+                ).ui_testing(sopts.debugging_opts.ui_testing)
+                    .rendered_ansi(sopts.json_rendered_ansi),
             ),
             (config::ErrorOutputType::Short(color_config), None) => Box::new(
                 EmitterWriter::stderr(color_config, Some(source_map.clone()), true, false),
@@ -1077,9 +1170,17 @@ pub fn build_session_with_source_map(
             (config::ErrorOutputType::Short(_), Some(dst)) => {
                 Box::new(EmitterWriter::new(dst, Some(source_map.clone()), true, false))
             }
+            (config::ErrorOutputType::HumanAnnotateRs(color_config), None) => Box::new(
+                AnnotateSnippetEmitterWriter::stderr(color_config, Some(source_map.clone()), false)
+                    .ui_testing(sopts.debugging_opts.ui_testing),
+            ),
+            (config::ErrorOutputType::HumanAnnotateRs(_), Some(dst)) => Box::new(
+                AnnotateSnippetEmitterWriter::new(dst, Some(source_map.clone()), false)
+                    .ui_testing(sopts.debugging_opts.ui_testing),
+            ),
         };
 
-    let diagnostic_handler = errors::Handler::with_emitter_and_flags(
+    let mut diagnostic_handler = errors::Handler::with_emitter_and_flags(
         emitter,
         errors::HandlerFlags {
             can_emit_warnings,
@@ -1087,13 +1188,48 @@ pub fn build_session_with_source_map(
             report_delayed_bugs,
             dont_buffer_diagnostics,
             external_macro_backtrace,
+            expand_similar_errors,
+            error_limit,
             ..Default::default()
         },
     );
+    if let Some(catalog) = load_message_catalog(&sopts, &diagnostic_handler) {
+        diagnostic_handler.set_message_catalog(catalog);
+    }
 
     build_session_(sopts, local_crate_source_file, diagnostic_handler, source_map)
 }
 
+/// Loads the translations of diagnostic messages requested with `-Z translate-lang` and
+/// `-Z translate-additional-ftl`, if any.
+fn load_message_catalog(sopts: &config::Options,
+                        handler: &errors::Handler)
+                        -> Option<MessageCatalog> {
+    let lang = sopts.debugging_opts.translate_lang.as_ref();
+    let additional_ftl = sopts.debugging_opts.translate_additional_ftl.as_ref();
+    if lang.is_none() && additional_ftl.is_none() {
+        return None;
+    }
+
+    let mut catalog = MessageCatalog::new();
+    if let Some(lang) = lang {
+        let sysroot = match sopts.maybe_sysroot {
+            Some(ref sysroot) => sysroot.clone(),
+            None => filesearch::get_or_default_sysroot(),
+        };
+        let dir = sysroot.join("share").join("locale").join(lang);
+        if let Err(e) = catalog.load_dir(&dir) {
+            handler.fatal(&format!("failed to load translations for `{}`: {}", lang, e)).raise();
+        }
+    }
+    if let Some(path) = additional_ftl {
+        if let Err(e) = catalog.load_file(path) {
+            handler.fatal(&format!("failed to load translations: {}", e)).raise();
+        }
+    }
+    Some(catalog)
+}
+
 pub fn build_session_(
     sopts: config::Options,
     local_crate_source_file: Option<PathBuf>,
@@ -1153,6 +1289,7 @@ pub fn build_session_(
         lint_store: RwLock::new(lint::LintStore::new()),
         buffered_lints: Lock::new(Some(Default::default())),
         one_time_diagnostics: Default::default(),
+        lint_expectations: Default::default(),
         plugin_llvm_passes: OneThread::new(RefCell::new(Vec::new())),
         plugin_attributes: OneThread::new(RefCell::new(Vec::new())),
         crate_types: Once::new(),
@@ -1176,8 +1313,12 @@ pub fn build_session_(
             queries_canonicalized: AtomicUsize::new(0),
             normalize_ty_after_erasing_regions: AtomicUsize::new(0),
             normalize_projection_ty: AtomicUsize::new(0),
+            mir_pass_times: Lock::new(FxHashMap::default()),
+            nop_landing_pads_removed: AtomicUsize::new(0),
+            nop_landing_pad_jumps_folded: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        mir_pass_stats: Default::default(),
         optimization_fuel_crate,
         optimization_fuel_limit,
         print_fuel_crate,
@@ -1310,6 +1451,9 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Short(color_config) => {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
+        config::ErrorOutputType::HumanAnnotateRs(color_config) => {
+            Box::new(AnnotateSnippetEmitterWriter::stderr(color_config, None, false))
+        }
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Fatal);
@@ -1325,6 +1469,9 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Short(color_config) => {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
+        config::ErrorOutputType::HumanAnnotateRs(color_config) => {
+            Box::new(AnnotateSnippetEmitterWriter::stderr(color_config, None, false))
+        }
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Warning);
@@ -125,6 +125,7 @@ impl<'a, 'gcx, 'tcx> OverloadedDeref<'tcx> {
 ///     - using x.some_method() syntax, where some_method takes &mut self
 ///     - using Foo::some_method(&mut x, ...) syntax
 ///     - binary assignment operators (+=, -=, *=, etc.)
+///     - the base of overloaded index expressions (`IndexMut`)
 /// Anything else should be rejected until generalized two phase borrow support
 /// is implemented. Right now, dataflow can't handle the general case where there
 /// is more than one use of a mutable borrow, and we don't want to accept too much
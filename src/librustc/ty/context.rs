@@ -32,7 +32,7 @@ use middle::lang_items;
 use middle::resolve_lifetime::{self, ObjectLifetimeDefault};
 use middle::stability;
 use mir::{self, Mir, interpret, ProjectionKind};
-use mir::interpret::Allocation;
+use mir::interpret::{Allocation, ConstValue, Scalar};
 use ty::subst::{CanonicalUserSubsts, Kind, Substs, Subst};
 use ty::ReprOptions;
 use traits;
@@ -392,6 +392,10 @@ pub struct TypeckTables<'tcx> {
     /// Borrows
     pub upvar_capture_map: ty::UpvarCaptureMap<'tcx>,
 
+    /// Records, for each upvar whose capture mode was escalated past the
+    /// closure's default, the use in the closure body that required it.
+    pub upvar_capture_origins: FxHashMap<ty::UpvarId, Span>,
+
     /// Records the reasons that we picked the kind of each closure;
     /// not all closures are present in the map.
     closure_kind_origins: ItemLocalMap<(Span, ast::Name)>,
@@ -448,6 +452,7 @@ impl<'tcx> TypeckTables<'tcx> {
             pat_binding_modes: Default::default(),
             pat_adjustments: Default::default(),
             upvar_capture_map: Default::default(),
+            upvar_capture_origins: Default::default(),
             closure_kind_origins: Default::default(),
             liberated_fn_sigs: Default::default(),
             fru_field_types: Default::default(),
@@ -755,6 +760,7 @@ impl<'a, 'gcx> HashStable<StableHashingContext<'a>> for TypeckTables<'gcx> {
             ref pat_binding_modes,
             ref pat_adjustments,
             ref upvar_capture_map,
+            ref upvar_capture_origins,
             ref closure_kind_origins,
             ref liberated_fn_sigs,
             ref fru_field_types,
@@ -778,7 +784,7 @@ impl<'a, 'gcx> HashStable<StableHashingContext<'a>> for TypeckTables<'gcx> {
             adjustments.hash_stable(hcx, hasher);
             pat_binding_modes.hash_stable(hcx, hasher);
             pat_adjustments.hash_stable(hcx, hasher);
-            hash_stable_hashmap(hcx, hasher, upvar_capture_map, |up_var_id, hcx| {
+            let upvar_id_key = |up_var_id: &ty::UpvarId, hcx: &StableHashingContext<'a>| {
                 let ty::UpvarId {
                     var_path,
                     closure_expr_id
@@ -798,7 +804,9 @@ impl<'a, 'gcx> HashStable<StableHashingContext<'a>> for TypeckTables<'gcx> {
                 (hcx.def_path_hash(var_owner_def_id),
                  var_path.hir_id.local_id,
                  hcx.def_path_hash(closure_def_id))
-            });
+            };
+            hash_stable_hashmap(hcx, hasher, upvar_capture_map, &upvar_id_key);
+            hash_stable_hashmap(hcx, hasher, upvar_capture_origins, &upvar_id_key);
 
             closure_kind_origins.hash_stable(hcx, hasher);
             liberated_fn_sigs.hash_stable(hcx, hasher);
@@ -946,7 +954,7 @@ pub struct GlobalCtxt<'tcx> {
     /// Stores the value of constants (and deduplicates the actual memory)
     allocation_interner: Lock<FxHashMap<&'tcx Allocation, ()>>,
 
-    pub alloc_map: Lock<interpret::AllocMap<'tcx, &'tcx Allocation>>,
+    pub alloc_map: interpret::AllocMap<'tcx, &'tcx Allocation>,
 
     layout_interner: Lock<FxHashMap<&'tcx LayoutDetails, ()>>,
 
@@ -1041,12 +1049,34 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         })
     }
 
+    /// Drops all allocations that are not reachable from an interned constant or
+    /// the result of a `const_eval_raw` query anymore, see `AllocMap::prune`.
+    pub fn prune_alloc_map(self) {
+        let mut roots = query::const_eval_raw_alloc_ids(self.global_tcx());
+        for &Interned(c) in self.gcx.global_interners.const_.borrow().keys() {
+            match c.val {
+                ConstValue::Scalar(Scalar::Ptr(ptr)) => roots.push(ptr.alloc_id),
+                ConstValue::ScalarPair(a, b) => {
+                    for scalar in &[a, b] {
+                        if let Scalar::Ptr(ptr) = *scalar {
+                            roots.push(ptr.alloc_id);
+                        }
+                    }
+                }
+                ConstValue::ByRef(id, ..) => roots.push(id),
+                ConstValue::Scalar(Scalar::Bits { .. }) |
+                ConstValue::Unevaluated(..) => {}
+            }
+        }
+        self.alloc_map.prune(roots);
+    }
+
     /// Allocates a byte or string literal for `mir::interpret`, read-only
     pub fn allocate_bytes(self, bytes: &[u8]) -> interpret::AllocId {
         // create an allocation that just contains these bytes
         let alloc = interpret::Allocation::from_byte_aligned_bytes(bytes, ());
         let alloc = self.intern_const_alloc(alloc);
-        self.alloc_map.lock().allocate(alloc)
+        self.alloc_map.allocate(alloc)
     }
 
     pub fn intern_stability(self, stab: attr::Stability) -> &'gcx attr::Stability {
@@ -1206,7 +1236,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             layout_interner: Default::default(),
             stability_interner: Default::default(),
             allocation_interner: Default::default(),
-            alloc_map: Lock::new(interpret::AllocMap::new()),
+            alloc_map: interpret::AllocMap::new(),
             tx_to_llvm_workers: Lock::new(tx),
             output_filenames: Arc::new(output_filenames.clone()),
         };
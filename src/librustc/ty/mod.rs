@@ -131,7 +131,10 @@ mod sty;
 pub struct CrateAnalysis {
     pub access_levels: Lrc<AccessLevels>,
     pub name: String,
+    /// Only kept with `MakeGlobMap::Yes`.
     pub glob_map: Option<hir::GlobMap>,
+    /// Only kept with `MakeGlobMap::Yes`, like the glob map.
+    pub macro_def_map: Option<hir::MacroDefMap>,
 }
 
 #[derive(Clone)]
@@ -819,6 +822,19 @@ pub struct UpvarBorrow<'tcx> {
 
 pub type UpvarCaptureMap<'tcx> = FxHashMap<UpvarId, UpvarCapture<'tcx>>;
 
+/// The result of capture analysis for one upvar of a closure or
+/// generator, as returned by the `closure_captures` query.
+#[derive(Copy, Clone, Debug)]
+pub struct CapturedUpvar<'tcx> {
+    /// The captured variable.
+    pub var_hir_id: hir::HirId,
+    pub name: ast::Name,
+    pub capture: UpvarCapture<'tcx>,
+    /// The use in the closure body that required `capture`; when the
+    /// capture mode was never escalated, this is the first use.
+    pub span: Span,
+}
+
 #[derive(Copy, Clone)]
 pub struct ClosureUpvar<'tcx> {
     pub def: Def,
@@ -2634,12 +2650,27 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                   .map(move |&body_id| self.hir().body_owner_def_id(body_id))
     }
 
+    /// Runs `f` on every body owner in parallel. Diagnostics are reported
+    /// ordered by the item they are about, see `diagnostic_order_key`, so the
+    /// output does not depend on how the bodies were scheduled.
     pub fn par_body_owners<F: Fn(DefId) + sync::Sync + sync::Send>(self, f: F) {
-        par_iter(&self.hir().krate().body_ids).for_each(|&body_id| {
-            f(self.hir().body_owner_def_id(body_id))
+        let handler = self.sess.diagnostic();
+        handler.in_source_order(|| {
+            par_iter(&self.hir().krate().body_ids).for_each(|&body_id| {
+                let def_id = self.hir().body_owner_def_id(body_id);
+                handler.with_order_key(self.diagnostic_order_key(def_id), || f(def_id))
+            });
         });
     }
 
+    /// The key used to order the diagnostics reported for the local item
+    /// `def_id`, both by the item itself and by the queries about it. Items
+    /// are ordered by their `DefIndex`, which is assigned while walking the
+    /// crate and so doesn't depend on the order the items are checked in.
+    pub fn diagnostic_order_key(self, def_id: DefId) -> usize {
+        def_id.index.as_raw_u32() as usize
+    }
+
     pub fn expr_span(self, id: NodeId) -> Span {
         match self.hir().find(id) {
             Some(Node::Expr(e)) => {
@@ -11,6 +11,7 @@
 use dep_graph::SerializedDepNodeIndex;
 use dep_graph::DepNode;
 use hir::def_id::{CrateNum, DefId, DefIndex};
+use mir;
 use mir::interpret::GlobalId;
 use traits;
 use traits::query::{
@@ -233,6 +234,15 @@ impl<'tcx> QueryDescription<'tcx> for queries::type_param_predicates<'tcx> {
     }
 }
 
+impl<'tcx> QueryDescription<'tcx> for queries::is_promotable<'tcx> {
+    fn describe(tcx: TyCtxt<'_, '_, '_>, (def_id, location): (DefId, mir::Location))
+        -> Cow<'static, str>
+    {
+        format!("checking whether the borrow at {:?} in `{}` gets promoted",
+                location, tcx.item_path_str(def_id)).into()
+    }
+}
+
 impl<'tcx> QueryDescription<'tcx> for queries::coherent_trait<'tcx> {
     fn describe(tcx: TyCtxt<'_, '_, '_>, def_id: DefId) -> Cow<'static, str> {
         format!("coherence checking all impls of trait `{}`",
@@ -531,6 +541,12 @@ impl<'tcx> QueryDescription<'tcx> for queries::lint_levels<'tcx> {
     }
 }
 
+impl<'tcx> QueryDescription<'tcx> for queries::lint_mod<'tcx> {
+    fn describe(tcx: TyCtxt<'_, '_, '_>, key: DefId) -> Cow<'static, str> {
+        format!("linting module `{}`", tcx.item_path_str(key)).into()
+    }
+}
+
 impl<'tcx> QueryDescription<'tcx> for queries::specializes<'tcx> {
     fn describe(_tcx: TyCtxt<'_, '_, '_>, _: (DefId, DefId)) -> Cow<'static, str> {
         "computing whether impls specialize one another".into()
@@ -33,6 +33,13 @@ pub(super) trait Key: Clone + Hash + Eq + Debug {
     /// In the event that a cycle occurs, if no explicit span has been
     /// given for a query with key `self`, what span should we use?
     fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span;
+
+    /// The item this query is computing something for, if any. Diagnostics
+    /// reported by the query are ordered as if that item had reported them,
+    /// no matter which item first caused the query to run.
+    fn order_item(&self) -> Option<DefId> {
+        None
+    }
 }
 
 impl<'tcx> Key for ty::InstanceDef<'tcx> {
@@ -43,6 +50,10 @@ impl<'tcx> Key for ty::InstanceDef<'tcx> {
     fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span {
         tcx.def_span(self.def_id())
     }
+
+    fn order_item(&self) -> Option<DefId> {
+        self.def_id().order_item()
+    }
 }
 
 impl<'tcx> Key for ty::Instance<'tcx> {
@@ -53,6 +64,10 @@ impl<'tcx> Key for ty::Instance<'tcx> {
     fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span {
         tcx.def_span(self.def_id())
     }
+
+    fn order_item(&self) -> Option<DefId> {
+        self.def_id().order_item()
+    }
 }
 
 impl<'tcx> Key for mir::interpret::GlobalId<'tcx> {
@@ -63,6 +78,10 @@ impl<'tcx> Key for mir::interpret::GlobalId<'tcx> {
     fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span {
         self.instance.default_span(tcx)
     }
+
+    fn order_item(&self) -> Option<DefId> {
+        self.instance.order_item()
+    }
 }
 
 impl Key for CrateNum {
@@ -90,6 +109,9 @@ impl Key for DefId {
     fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span {
         tcx.def_span(*self)
     }
+    fn order_item(&self) -> Option<DefId> {
+        if self.is_local() { Some(*self) } else { None }
+    }
 }
 
 impl Key for (DefId, DefId) {
@@ -101,6 +123,15 @@ impl Key for (DefId, DefId) {
     }
 }
 
+impl Key for (DefId, mir::Location) {
+    fn query_crate(&self) -> CrateNum {
+        self.0.krate
+    }
+    fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span {
+        self.0.default_span(tcx)
+    }
+}
+
 impl Key for (CrateNum, DefId) {
     fn query_crate(&self) -> CrateNum {
         self.0
@@ -126,6 +157,9 @@ impl<'tcx> Key for (DefId, &'tcx Substs<'tcx>) {
     fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span {
         self.0.default_span(tcx)
     }
+    fn order_item(&self) -> Option<DefId> {
+        self.0.order_item()
+    }
 }
 
 impl<'tcx> Key for (ty::ParamEnv<'tcx>, ty::PolyTraitRef<'tcx>) {
@@ -180,6 +214,9 @@ impl<'tcx, T: Key> Key for ty::ParamEnvAnd<'tcx, T> {
     fn default_span(&self, tcx: TyCtxt<'_, '_, '_>) -> Span {
         self.value.default_span(tcx)
     }
+    fn order_item(&self) -> Option<DefId> {
+        self.value.order_item()
+    }
 }
 
 impl<'tcx> Key for traits::Environment<'tcx> {
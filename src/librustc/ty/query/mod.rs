@@ -30,7 +30,7 @@ use middle::exported_symbols::{SymbolExportLevel, ExportedSymbol};
 use mir::interpret::{ConstEvalRawResult, ConstEvalResult};
 use mir::mono::CodegenUnit;
 use mir;
-use mir::interpret::GlobalId;
+use mir::interpret::{AllocId, ConstValue, GlobalId};
 use session::{CompileResult, CrateDisambiguator};
 use session::config::OutputFilenames;
 use traits::{self, Vtable};
@@ -230,6 +230,15 @@ define_queries! { <'tcx>
         /// the value isn't known except to the pass itself.
         [] fn mir_const_qualif: MirConstQualif(DefId) -> (u8, Lrc<BitSet<mir::Local>>),
 
+        /// What gets promoted to `'static` constants in a body, and why the other
+        /// borrows of temporaries don't.
+        [] fn promotability: MirPromotability(DefId) -> Lrc<mir::Promotability>,
+
+        /// Whether the borrow at the given location gets promoted. The location is
+        /// in the MIR before promotion, as returned by `mir_const`.
+        [] fn is_promotable: is_promotable_node((DefId, mir::Location))
+            -> Result<(), mir::Unpromotable>,
+
         /// Fetch the MIR for a given def-id right after it's built - this includes
         /// unreachable code.
         [] fn mir_built: MirBuilt(DefId) -> &'tcx Steal<mir::Mir<'tcx>>,
@@ -272,6 +281,10 @@ define_queries! { <'tcx>
 
     Other {
         [] fn used_trait_imports: UsedTraitImports(DefId) -> Lrc<DefIdSet>,
+
+        /// The capture mode of each upvar of a closure or generator, in
+        /// the order of its freevars, along with the use that required it.
+        [] fn closure_captures: ClosureCaptures(DefId) -> Lrc<Vec<ty::CapturedUpvar<'tcx>>>,
     },
 
     TypeChecking {
@@ -286,6 +299,10 @@ define_queries! { <'tcx>
         /// Borrow checks the function body. If this is a closure, returns
         /// additional requirements that the closure's creator must verify.
         [] fn mir_borrowck: MirBorrowCheck(DefId) -> mir::BorrowCheckResult<'tcx>,
+
+        /// The fields of `self` a method restricts itself to with
+        /// `#[disjoint_fields(..)]`, if any.
+        [] fn disjoint_fields: DisjointFields(DefId) -> Option<Lrc<Vec<mir::Field>>>,
     },
 
     TypeChecking {
@@ -353,6 +370,8 @@ define_queries! { <'tcx>
         /// Gets the rendered value of the specified constant or associated constant.
         /// Used by rustdoc.
         [] fn rendered_const: RenderedConst(DefId) -> String,
+        /// Gets the value of a `const` item of an upstream crate, as evaluated by that crate.
+        [] fn extern_const_value: ExternConstValue(DefId) -> Option<ConstValue<'tcx>>,
         [] fn impl_parent: ImplParent(DefId) -> Option<DefId>,
     },
 
@@ -428,6 +447,10 @@ define_queries! { <'tcx>
     Other {
         [] fn module_exports: ModuleExports(DefId) -> Option<Lrc<Vec<Export>>>,
         [] fn lint_levels: lint_levels_node(CrateNum) -> Lrc<lint::LintLevelMap>,
+
+        /// Runs the per-module late lint passes on a module, not including
+        /// the modules nested inside it.
+        [] fn lint_mod: LintMod(DefId) -> (),
     },
 
     TypeChecking {
@@ -748,6 +771,10 @@ fn type_param_predicates<'tcx>((item_id, param_id): (DefId, DefId)) -> DepConstr
     }
 }
 
+fn is_promotable_node<'tcx>((def_id, location): (DefId, mir::Location)) -> DepConstructor<'tcx> {
+    DepConstructor::IsPromotable { def_id, location }
+}
+
 fn fulfill_obligation_dep_node<'tcx>((param_env, trait_ref):
     (ty::ParamEnv<'tcx>, ty::PolyTraitRef<'tcx>)) -> DepConstructor<'tcx> {
     DepConstructor::FulfillObligation {
@@ -896,3 +923,18 @@ fn instance_def_size_estimate_dep_node<'tcx>(instance_def: ty::InstanceDef<'tcx>
         instance_def
     }
 }
+
+/// The allocations holding the results of `const_eval_raw`, which must stay alive
+/// for as long as the query results can be used.
+pub(crate) fn const_eval_raw_alloc_ids(tcx: TyCtxt<'_, '_, '_>) -> Vec<AllocId> {
+    const_eval_raw_results(tcx).into_iter().map(|(_, alloc_id)| alloc_id).collect()
+}
+
+/// The constants and statics evaluated so far, with the allocations holding their values.
+pub fn const_eval_raw_results<'tcx>(tcx: TyCtxt<'_, 'tcx, '_>) -> Vec<(GlobalId<'tcx>, AllocId)> {
+    let cache = queries::const_eval_raw::query_cache(tcx).borrow();
+    cache.results.iter().filter_map(|(key, entry)| match entry.value {
+        Ok(raw) => Some((key.value, raw.alloc_id)),
+        Err(_) => None,
+    }).collect()
+}
@@ -201,6 +201,12 @@ pub fn compile_codegen_unit<'ll, 'tcx>(tcx: TyCtxt<'ll, 'tcx, 'tcx>,
                 }
             }
 
+            // Emit the coverage map before the llvm.used variable, which
+            // keeps the map from being discarded by the linker
+            if cx.sess().opts.debugging_opts.instrument_mir_coverage {
+                cx.create_coverage_map();
+            }
+
             // Create the llvm.used variable
             // This variable has type [N x i8*] and is stored in the llvm.metadata section
             if !cx.used_statics().borrow().is_empty() {
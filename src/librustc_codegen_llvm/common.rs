@@ -316,7 +316,7 @@ impl ConstMethods<'tcx> for CodegenCx<'ll, 'tcx> {
                 }
             },
             Scalar::Ptr(ptr) => {
-                let alloc_type = self.tcx.alloc_map.lock().get(ptr.alloc_id);
+                let alloc_type = self.tcx.alloc_map.get(ptr.alloc_id);
                 let base_addr = match alloc_type {
                     Some(AllocType::Memory(alloc)) => {
                         let init = const_alloc_to_llvm(self, alloc);
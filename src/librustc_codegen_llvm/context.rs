@@ -13,6 +13,7 @@ use llvm;
 use llvm_util;
 use rustc::dep_graph::DepGraphSafe;
 use rustc::hir;
+use coverage;
 use debuginfo;
 use monomorphize::Instance;
 use value::Value;
@@ -88,6 +89,10 @@ pub struct CodegenCx<'ll, 'tcx: 'll> {
     /// See http://llvm.org/docs/LangRef.html#the-llvm-used-global-variable for details
     pub used_statics: RefCell<Vec<&'ll Value>>,
 
+    /// Counters and source regions of the functions instrumented
+    /// by `-Z instrument-mir-coverage`
+    pub coverage_map: RefCell<FxHashMap<Instance<'tcx>, coverage::FunctionCoverage<'ll>>>,
+
     pub lltypes: RefCell<FxHashMap<(Ty<'tcx>, Option<VariantIdx>), &'ll Type>>,
     pub scalar_lltypes: RefCell<FxHashMap<Ty<'tcx>, &'ll Type>>,
     pub pointee_infos: RefCell<FxHashMap<(Ty<'tcx>, Size), Option<PointeeInfo>>>,
@@ -302,6 +307,7 @@ impl<'ll, 'tcx> CodegenCx<'ll, 'tcx> {
             const_globals: Default::default(),
             statics_to_rauw: RefCell::new(Vec::new()),
             used_statics: RefCell::new(Vec::new()),
+            coverage_map: Default::default(),
             lltypes: Default::default(),
             scalar_lltypes: Default::default(),
             pointee_infos: Default::default(),
@@ -0,0 +1,113 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Counters and coverage map for `-Z instrument-mir-coverage`.
+//!
+//! Each instrumented function gets a private array of `u64` counters, which
+//! the `CoverageCounter` statements of its MIR increment. Once the codegen
+//! unit is complete, one record per function is emitted into the
+//! `__rust_mir_cov` section:
+//!
+//! ```ignore (illustrative)
+//! struct Record {
+//!     symbol_name: &'static str,
+//!     regions: &'static str, // "<counter index>\t<span>\n" for each counter
+//!     counters: *mut u64,
+//!     num_counters: usize,
+//! }
+//! ```
+//!
+//! A tool can walk the section after the program has run to report how many
+//! times each region was executed.
+
+use llvm;
+use common::CodegenCx;
+use consts;
+use monomorphize::Instance;
+use value::Value;
+use rustc_codegen_ssa::traits::*;
+use syntax::symbol::Symbol;
+use syntax_pos::Span;
+
+use std::fmt::Write;
+
+pub struct FunctionCoverage<'ll> {
+    counters: &'ll Value,
+    num_counters: u32,
+    regions: Vec<(u32, Span)>,
+}
+
+impl CoverageMethods<'tcx> for CodegenCx<'ll, 'tcx> {
+    fn coverage_counters(&self, instance: Instance<'tcx>, num_counters: u32) -> &'ll Value {
+        if let Some(function) = self.coverage_map.borrow().get(&instance) {
+            return function.counters;
+        }
+
+        let zero = self.const_u64(0);
+        let init = self.const_array(self.type_i64(), &vec![zero; num_counters as usize]);
+        let align = self.tcx.data_layout.i64_align.abi;
+        let counters = self.static_addr_of_mut(init, align, Some("mir_cov_counters"));
+
+        self.coverage_map.borrow_mut().insert(instance, FunctionCoverage {
+            counters,
+            num_counters,
+            regions: vec![],
+        });
+        counters
+    }
+
+    fn add_coverage_region(&self, instance: Instance<'tcx>, index: u32, span: Span) {
+        let mut coverage_map = self.coverage_map.borrow_mut();
+        let function = coverage_map.get_mut(&instance).unwrap_or_else(|| {
+            bug!("coverage region for {:?} before its counters", instance)
+        });
+        function.regions.push((index, span));
+    }
+
+    fn create_coverage_map(&self) {
+        let section = if self.sess().target.target.options.is_like_osx {
+            const_cstr!("__DATA,__rust_mir_cov")
+        } else {
+            const_cstr!("__rust_mir_cov")
+        };
+
+        let mut functions: Vec<_> = self.coverage_map.borrow_mut()
+            .drain()
+            .map(|(instance, function)| (self.tcx.symbol_name(instance).as_str(), function))
+            .collect();
+        // Keep the output deterministic.
+        functions.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let source_map = self.sess().source_map();
+        for (symbol_name, mut function) in functions {
+            function.regions.sort_by_key(|&(index, _)| index);
+            let mut regions = String::new();
+            for &(index, span) in &function.regions {
+                writeln!(regions, "{}\t{}", index, source_map.span_to_string(span)).unwrap();
+            }
+
+            let counters = consts::ptrcast(function.counters, self.type_ptr_to(self.type_i64()));
+            let record = self.const_struct(&[
+                self.const_str_slice(symbol_name),
+                self.const_str_slice(Symbol::intern(&regions).as_str()),
+                counters,
+                self.const_usize(function.num_counters as u64),
+            ], false);
+
+            let align = self.tcx.data_layout.pointer_align.abi;
+            let g = self.static_addr_of_mut(record, align, Some("mir_cov_record"));
+            unsafe {
+                llvm::LLVMSetGlobalConstant(g, llvm::True);
+                llvm::LLVMSetSection(g, section.as_ptr());
+            }
+            self.used_statics.borrow_mut().push(consts::ptrcast(g, self.type_i8p()));
+        }
+    }
+}
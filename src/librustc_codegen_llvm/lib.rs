@@ -114,6 +114,7 @@ mod callee;
 mod common;
 mod consts;
 mod context;
+mod coverage;
 mod debuginfo;
 mod declare;
 mod intrinsic;
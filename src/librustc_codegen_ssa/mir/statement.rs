@@ -104,6 +104,19 @@ impl<'a, 'tcx: 'a, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                 }
                 bx
             }
+            mir::StatementKind::CoverageCounter { index, num_counters } => {
+                let counters = bx.cx().coverage_counters(self.instance, num_counters);
+                bx.cx().add_coverage_region(self.instance, index, statement.source_info.span);
+
+                let indices = [bx.cx().const_usize(0), bx.cx().const_usize(index as u64)];
+                let counter = bx.inbounds_gep(counters, &indices);
+                let align = bx.tcx().data_layout.i64_align.abi;
+                let count = bx.load(counter, align);
+                let one = bx.cx().const_u64(1);
+                let count = bx.add(count, one);
+                bx.store(count, counter, align);
+                bx
+            }
             mir::StatementKind::FakeRead(..) |
             mir::StatementKind::Retag { .. } |
             mir::StatementKind::EscapeToRaw { .. } |
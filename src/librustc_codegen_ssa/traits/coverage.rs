@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::BackendTypes;
+use rustc::ty::Instance;
+use syntax_pos::Span;
+
+pub trait CoverageMethods<'tcx>: BackendTypes {
+    /// Returns the array of `num_counters` `u64` counters of `instance` for
+    /// `-Z instrument-mir-coverage`, creating it on first use.
+    fn coverage_counters(&self, instance: Instance<'tcx>, num_counters: u32) -> Self::Value;
+
+    /// Records that counter `index` of `instance` counts executions of `span`.
+    fn add_coverage_region(&self, instance: Instance<'tcx>, index: u32, span: Span);
+
+    /// Emits the coverage map of the codegen unit, with an entry for each
+    /// instrumented function.
+    fn create_coverage_map(&self);
+}
@@ -29,6 +29,7 @@ mod asm;
 mod backend;
 mod builder;
 mod consts;
+mod coverage;
 mod debuginfo;
 mod declare;
 mod intrinsic;
@@ -42,6 +43,7 @@ pub use self::asm::{AsmBuilderMethods, AsmMethods};
 pub use self::backend::{Backend, BackendTypes, ExtraBackendMethods};
 pub use self::builder::{BuilderMethods, OverflowOp};
 pub use self::consts::ConstMethods;
+pub use self::coverage::CoverageMethods;
 pub use self::debuginfo::{DebugInfoBuilderMethods, DebugInfoMethods};
 pub use self::declare::{DeclareMethods, PreDefineMethods};
 pub use self::intrinsic::IntrinsicCallMethods;
@@ -63,6 +65,7 @@ pub trait CodegenMethods<'tcx>:
     + MiscMethods<'tcx>
     + ConstMethods<'tcx>
     + StaticMethods
+    + CoverageMethods<'tcx>
     + DebugInfoMethods<'tcx>
     + AbiMethods<'tcx>
     + DeclareMethods<'tcx>
@@ -77,6 +80,7 @@ impl<'tcx, T> CodegenMethods<'tcx> for T where
         + MiscMethods<'tcx>
         + ConstMethods<'tcx>
         + StaticMethods
+        + CoverageMethods<'tcx>
         + DebugInfoMethods<'tcx>
         + AbiMethods<'tcx>
         + DeclareMethods<'tcx>
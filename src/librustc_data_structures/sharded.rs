@@ -0,0 +1,335 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::hash::{Hash, Hasher};
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use fx::FxHasher;
+use sync::{Lock, RwLock, ReadGuard, WriteGuard};
+
+const SHARD_BITS: usize = 5;
+const SHARDS: usize = 1 << SHARD_BITS;
+
+/// A value split into `SHARDS` independently locked parts, e.g. a map whose keys are
+/// distributed over the shards by their hash. Accesses to different shards do not contend
+/// with each other, and reads of the same shard only wait for writers.
+pub struct Sharded<T> {
+    shards: Vec<RwLock<T>>,
+}
+
+impl<T: Default> Default for Sharded<T> {
+    #[inline]
+    fn default() -> Self {
+        Sharded::new(T::default)
+    }
+}
+
+impl<T> Sharded<T> {
+    #[inline]
+    pub fn new(mut value: impl FnMut() -> T) -> Self {
+        Sharded {
+            shards: (0..SHARDS).map(|_| RwLock::new(value())).collect(),
+        }
+    }
+
+    /// The shard responsible for keys with the given hash.
+    #[inline]
+    pub fn get_shard_by_hash(&self, hash: u64) -> &RwLock<T> {
+        // The low bits of the hash are used to pick a bucket inside of the shard's map,
+        // so use the high bits to pick the shard.
+        &self.shards[(hash >> (64 - SHARD_BITS)) as usize]
+    }
+
+    /// The shard responsible for `key`.
+    #[inline]
+    pub fn get_shard_by_key<K: Hash + ?Sized>(&self, key: &K) -> &RwLock<T> {
+        self.get_shard_by_hash(make_hash(key))
+    }
+
+    #[inline]
+    pub fn read<K: Hash + ?Sized>(&self, key: &K) -> ReadGuard<T> {
+        self.get_shard_by_key(key).read()
+    }
+
+    #[inline]
+    pub fn write<K: Hash + ?Sized>(&self, key: &K) -> WriteGuard<T> {
+        self.get_shard_by_key(key).write()
+    }
+
+    /// Locks all shards for reading, e.g. to iterate over the entire value.
+    pub fn read_all(&self) -> Vec<ReadGuard<T>> {
+        self.shards.iter().map(|shard| shard.read()).collect()
+    }
+
+    /// Locks all shards, e.g. to modify the entire value.
+    pub fn write_all(&self) -> Vec<WriteGuard<T>> {
+        self.shards.iter().map(|shard| shard.write()).collect()
+    }
+}
+
+#[inline]
+pub fn make_hash<K: Hash + ?Sized>(val: &K) -> u64 {
+    let mut state = FxHasher::default();
+    val.hash(&mut state);
+    state.finish()
+}
+
+const FIRST_CHUNK_BITS: usize = 10;
+const CHUNKS: usize = 64 - FIRST_CHUNK_BITS + 1;
+
+/// A map from densely allocated `u64` ids to values. Looking up an id never takes a lock,
+/// which makes this suitable for ids that are read far more often than they are assigned.
+///
+/// The slots live in chunks that double in size, so the chunk holding an id can be found
+/// without any indirection besides the chunk pointer itself. A value that has been removed
+/// stays allocated until the map is dropped, as other threads may still be reading it.
+pub struct IdMap<T> {
+    chunks: Vec<AtomicPtr<AtomicPtr<T>>>,
+    removed: Lock<Vec<Box<T>>>,
+}
+
+unsafe impl<T: Send + Sync> Send for IdMap<T> {}
+unsafe impl<T: Send + Sync> Sync for IdMap<T> {}
+
+impl<T> Default for IdMap<T> {
+    #[inline]
+    fn default() -> Self {
+        IdMap::new()
+    }
+}
+
+impl<T> IdMap<T> {
+    pub fn new() -> Self {
+        IdMap {
+            chunks: (0..CHUNKS).map(|_| AtomicPtr::new(ptr::null_mut())).collect(),
+            removed: Lock::new(Vec::new()),
+        }
+    }
+
+    /// The number of slots in the `chunk`th chunk.
+    #[inline]
+    fn chunk_len(chunk: usize) -> usize {
+        1 << (chunk + FIRST_CHUNK_BITS)
+    }
+
+    /// The chunk holding `id`, and the index of `id` inside of it.
+    #[inline]
+    fn locate(id: u64) -> (usize, usize) {
+        let biased = (id >> FIRST_CHUNK_BITS) + 1;
+        let chunk = 63 - biased.leading_zeros() as usize;
+        let first_id = ((1 << chunk) - 1) << FIRST_CHUNK_BITS;
+        (chunk, (id - first_id) as usize)
+    }
+
+    /// The slot of `id`, or `None` if no id in its chunk was ever inserted.
+    #[inline]
+    fn slot(&self, id: u64) -> Option<&AtomicPtr<T>> {
+        let (chunk, index) = Self::locate(id);
+        let slots = self.chunks[chunk].load(Ordering::Acquire);
+        if slots.is_null() {
+            None
+        } else {
+            unsafe { Some(&*slots.add(index)) }
+        }
+    }
+
+    /// The slot of `id`, allocating its chunk if needed.
+    fn slot_or_alloc(&self, id: u64) -> &AtomicPtr<T> {
+        if let Some(slot) = self.slot(id) {
+            return slot;
+        }
+        let (chunk, _) = Self::locate(id);
+        let new_slots: Box<[AtomicPtr<T>]> = (0..Self::chunk_len(chunk))
+            .map(|_| AtomicPtr::new(ptr::null_mut()))
+            .collect();
+        let new_slots = Box::into_raw(new_slots) as *mut AtomicPtr<T>;
+        let old = self.chunks[chunk].compare_and_swap(
+            ptr::null_mut(), new_slots, Ordering::AcqRel);
+        if !old.is_null() {
+            // Another thread allocated the chunk first, use theirs.
+            unsafe { Self::free_chunk(new_slots, chunk) };
+        }
+        self.slot(id).unwrap()
+    }
+
+    unsafe fn free_chunk(slots: *mut AtomicPtr<T>, chunk: usize) {
+        let slots = slice::from_raw_parts_mut(slots, Self::chunk_len(chunk));
+        drop(Box::from_raw(slots as *mut [AtomicPtr<T>]));
+    }
+
+    /// Returns the value of `id`, without taking any lock.
+    #[inline]
+    pub fn get(&self, id: u64) -> Option<&T> {
+        let value = self.slot(id)?.load(Ordering::Acquire);
+        if value.is_null() {
+            None
+        } else {
+            unsafe { Some(&*value) }
+        }
+    }
+
+    /// Sets the value of `id` unless it already has one. Returns the value that `id`
+    /// ends up with, and whether that is `value`.
+    pub fn insert(&self, id: u64, value: T) -> (&T, bool) {
+        let value = Box::into_raw(Box::new(value));
+        let old = self.slot_or_alloc(id).compare_and_swap(
+            ptr::null_mut(), value, Ordering::AcqRel);
+        if old.is_null() {
+            unsafe { (&*value, true) }
+        } else {
+            unsafe {
+                drop(Box::from_raw(value));
+                (&*old, false)
+            }
+        }
+    }
+
+    /// All ids that have a value, in increasing order.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (u64, &'a T)> + 'a {
+        self.chunks.iter().enumerate().flat_map(|(chunk, slots)| {
+            let slots = slots.load(Ordering::Acquire);
+            let len = if slots.is_null() { 0 } else { Self::chunk_len(chunk) };
+            let first_id = ((1 << chunk) - 1) << FIRST_CHUNK_BITS;
+            (0..len).filter_map(move |index| {
+                let value = unsafe { (*slots.add(index)).load(Ordering::Acquire) };
+                if value.is_null() {
+                    None
+                } else {
+                    unsafe { Some((first_id + index as u64, &*value)) }
+                }
+            })
+        })
+    }
+
+    /// Removes the value of all ids for which `f` returns `false`, and returns how many
+    /// there were.
+    pub fn retain(&self, mut f: impl FnMut(u64, &T) -> bool) -> usize {
+        let mut removed = self.removed.lock();
+        let before = removed.len();
+        for (chunk, slots) in self.chunks.iter().enumerate() {
+            let slots = slots.load(Ordering::Acquire);
+            if slots.is_null() {
+                continue;
+            }
+            let first_id = ((1 << chunk) - 1) << FIRST_CHUNK_BITS;
+            for index in 0..Self::chunk_len(chunk) {
+                let slot = unsafe { &*slots.add(index) };
+                let value = slot.load(Ordering::Acquire);
+                if value.is_null() || f(first_id + index as u64, unsafe { &*value }) {
+                    continue;
+                }
+                slot.store(ptr::null_mut(), Ordering::Release);
+                removed.push(unsafe { Box::from_raw(value) });
+            }
+        }
+        removed.len() - before
+    }
+}
+
+impl<T> Drop for IdMap<T> {
+    fn drop(&mut self) {
+        for (chunk, slots) in self.chunks.iter().enumerate() {
+            let slots = slots.load(Ordering::Acquire);
+            if slots.is_null() {
+                continue;
+            }
+            unsafe {
+                for index in 0..Self::chunk_len(chunk) {
+                    let value = (*slots.add(index)).load(Ordering::Acquire);
+                    if !value.is_null() {
+                        drop(Box::from_raw(value));
+                    }
+                }
+                Self::free_chunk(slots, chunk);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_sharded_shards_by_key() {
+        let map: Sharded<Vec<u32>> = Sharded::default();
+        for i in 0..100 {
+            map.write(&i).push(i);
+        }
+        for i in 0..100 {
+            assert!(map.read(&i).contains(&i));
+        }
+        let total: usize = map.read_all().iter().map(|shard| shard.len()).sum();
+        assert_eq!(total, 100);
+        assert!(map.read_all().iter().filter(|shard| !shard.is_empty()).count() > 1);
+    }
+
+    #[test]
+    fn test_id_map_locate() {
+        assert_eq!(IdMap::<()>::locate(0), (0, 0));
+        assert_eq!(IdMap::<()>::locate(1023), (0, 1023));
+        assert_eq!(IdMap::<()>::locate(1024), (1, 0));
+        assert_eq!(IdMap::<()>::locate(3071), (1, 2047));
+        assert_eq!(IdMap::<()>::locate(3072), (2, 0));
+        assert_eq!(IdMap::<()>::locate(u64::max_value()), (CHUNKS - 1, 1023));
+    }
+
+    #[test]
+    fn test_id_map_insert_and_get() {
+        let map = IdMap::new();
+        assert_eq!(map.get(5), None);
+        assert_eq!(map.insert(5, "five"), (&"five", true));
+        assert_eq!(map.insert(5, "cinq"), (&"five", false));
+        assert_eq!(map.insert(100_000, "many"), (&"many", true));
+        assert_eq!(map.get(5), Some(&"five"));
+        assert_eq!(map.get(6), None);
+        assert_eq!(map.get(100_000), Some(&"many"));
+        assert_eq!(map.get(1 << 40), None);
+    }
+
+    #[test]
+    fn test_id_map_retain() {
+        let map = IdMap::new();
+        for id in 0..5000 {
+            map.insert(id, id * 2);
+        }
+        assert_eq!(map.retain(|id, &value| id % 2 == 0 && value == id * 2), 2500);
+        assert_eq!(map.get(10), Some(&20));
+        assert_eq!(map.get(11), None);
+        assert_eq!(map.get(4999), None);
+        assert_eq!(map.retain(|_, _| true), 0);
+        assert_eq!(map.iter().count(), 2500);
+        assert!(map.iter().all(|(id, &value)| value == id * 2));
+    }
+
+    #[test]
+    fn test_id_map_concurrent_inserts() {
+        let map = Arc::new(IdMap::new());
+        let threads: Vec<_> = (0..4u64).map(|n| {
+            let map = map.clone();
+            thread::spawn(move || {
+                for id in 0..10_000 {
+                    map.insert(id, n);
+                    assert!(map.get(id).is_some());
+                }
+            })
+        }).collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        // Every id was set exactly once, by whichever thread got there first.
+        for id in 0..10_000 {
+            assert!(*map.get(id).unwrap() < 4);
+        }
+    }
+}
@@ -13,6 +13,7 @@ use rustc::hir;
 use rustc::hir::lowering::lower_crate;
 use rustc::hir::map as hir_map;
 use rustc::lint;
+use rustc::lint::builtin::BuiltinLintDiagnostics;
 use rustc::middle::{self, reachable, resolve_lifetime, stability};
 use rustc::middle::privacy::AccessLevels;
 use rustc::ty::{self, AllArenas, Resolutions, TyCtxt};
@@ -33,7 +34,7 @@ use rustc_incremental;
 use rustc_metadata::creader::CrateLoader;
 use rustc_metadata::cstore::{self, CStore};
 use rustc_mir as mir;
-use rustc_passes::{self, ast_validation, hir_stats, loops, rvalue_promotion};
+use rustc_passes::{self, ast_validation, hir_stats, loops, mir_stats, rvalue_promotion};
 use rustc_plugin as plugin;
 use rustc_plugin::registry::Registry;
 use rustc_privacy;
@@ -46,7 +47,7 @@ use syntax::ext::base::ExtCtxt;
 use syntax::fold::Folder;
 use syntax::parse::{self, PResult};
 use syntax::util::node_count::NodeCounter;
-use syntax::util::lev_distance::find_best_match_for_name;
+use syntax::util::lev_distance::NameMatcher;
 use syntax::symbol::Symbol;
 use syntax_pos::{FileName, hygiene};
 use syntax_ext;
@@ -171,6 +172,10 @@ pub fn compile_input(
             (compile_state.krate.unwrap(), compile_state.registry)
         };
 
+        sess.track_errors(|| {
+            (control.register_lints)(sess, &mut sess.lint_store.borrow_mut());
+        })?;
+
         let outputs = build_output_filenames(input, outdir, output, &krate.attrs, sess);
         let crate_name =
             ::rustc_codegen_utils::link::find_crate_name(Some(sess), &krate.attrs, input);
@@ -339,6 +344,10 @@ pub fn compile_input(
                     }
                 }
 
+                if tcx.sess.opts.debugging_opts.mir_stats {
+                    mir_stats::print_mir_stats(tcx, "MIR STATS");
+                }
+
                 Ok((outputs.clone(), ongoing_codegen, tcx.dep_graph.clone()))
             },
         )??
@@ -350,6 +359,10 @@ pub fn compile_input(
 
     codegen_backend.join_codegen_and_link(ongoing_codegen, sess, &dep_graph, &outputs)?;
 
+    if sess.time_passes() {
+        sess.print_mir_pass_times();
+    }
+
     if sess.opts.debugging_opts.perf_stats {
         sess.print_perf_stats();
     }
@@ -402,6 +415,7 @@ pub struct CompileController<'a> {
 
     // FIXME we probably want to group the below options together and offer a
     // better API, rather than this ad-hoc approach.
+    // Also keeps the macro definition map, see `MakeGlobMap`.
     pub make_glob_map: MakeGlobMap,
     // Whether the compiler should keep the ast beyond parsing.
     pub keep_ast: bool,
@@ -414,6 +428,11 @@ pub struct CompileController<'a> {
     /// Same as `provide`, but only for non-local crates,
     /// applied after `default_provide_extern`.
     pub provide_extern: Box<dyn Fn(&mut ty::query::Providers) + 'a>,
+    /// Allows tools to register additional lint passes, lint groups and
+    /// renamed or removed lint names without going through the plugin
+    /// machinery. Runs after parsing, before plugins are loaded, so the
+    /// registered lints take part in `-W help` and lint attribute checking.
+    pub register_lints: Box<dyn Fn(&Session, &mut lint::LintStore) + 'a>,
 }
 
 impl<'a> CompileController<'a> {
@@ -429,6 +448,7 @@ impl<'a> CompileController<'a> {
             continue_parse_after_error: false,
             provide: box |_| {},
             provide_extern: box |_| {},
+            register_lints: box |_, _| {},
         }
     }
 }
@@ -803,6 +823,13 @@ where
                 } else {
                     None
                 },
+                // Only tools need the macro definitions once expansion is done, so
+                // they're kept along with the glob map.
+                macro_def_map: if resolver.make_glob_map {
+                    Some(resolver.macro_defs)
+                } else {
+                    None
+                },
             },
         }),
         Err(x) => Err(x),
@@ -1015,6 +1042,20 @@ where
             ecx.check_unused_macros();
         });
 
+        if ecx.parse_sess.span_diagnostic.err_count() - ecx.resolve_err_count > err_count {
+            // The early lint pass won't run, so emit the lints buffered during
+            // expansion before giving up.
+            let mut buffered = lint::LintBuffer::default();
+            ecx.parse_sess.buffered_lints.with_lock(|buffered_lints| {
+                for BufferedEarlyLint{id, span, msg, lint_id} in buffered_lints.drain(..) {
+                    let lint = lint::Lint::from_parser_lint_id(lint_id);
+                    buffered.add_lint(lint, id, span, &msg, BuiltinLintDiagnostics::Normal);
+                }
+            });
+            lint::emit_buffered_early_lints(sess, &krate, buffered);
+            ecx.parse_sess.span_diagnostic.abort_if_errors();
+        }
+
         let mut missing_fragment_specifiers: Vec<_> = ecx.parse_sess
             .missing_fragment_specifiers
             .borrow()
@@ -1028,9 +1069,6 @@ where
             let msg = "missing fragment specifier";
             sess.buffer_lint(lint, ast::CRATE_NODE_ID, span, msg);
         }
-        if ecx.parse_sess.span_diagnostic.err_count() - ecx.resolve_err_count > err_count {
-            ecx.parse_sess.span_diagnostic.abort_if_errors();
-        }
         if cfg!(windows) {
             env::set_var("PATH", &old_path);
         }
@@ -1246,6 +1284,8 @@ where
 
     time(sess, "loop checking", || loops::check_crate(sess, &hir_map));
 
+    mir::transform::check_mir_enable_passes(sess);
+
     let mut local_providers = ty::query::Providers::default();
     default_provide(&mut local_providers);
     codegen_backend.provide(&mut local_providers);
@@ -1350,6 +1390,16 @@ where
 
             time(sess, "lint checking", || lint::check_crate(tcx));
 
+            // Constants evaluated during analysis leave their temporary
+            // allocations behind, which nothing needs during codegen.
+            time(sess, "pruning unreachable allocations", || tcx.prune_alloc_map());
+
+            if let Some(ref path) = sess.opts.debugging_opts.dump_const_allocs {
+                if let Err(e) = mir::util::dump_const_allocs(tcx, Path::new(path)) {
+                    sess.err(&format!("could not write allocation dump to `{}`: {}", path, e));
+                }
+            }
+
             return Ok(f(tcx, analysis, rx, tcx.sess.compile_status()));
         },
     )
@@ -1522,11 +1572,8 @@ pub fn collect_crate_types(session: &Session, attrs: &[ast::Attribute]) -> Vec<c
 
                         if let ast::MetaItemKind::NameValue(spanned) = a.meta().unwrap().node {
                             let span = spanned.span;
-                            let lev_candidate = find_best_match_for_name(
-                                crate_types.iter(),
-                                &n.as_str(),
-                                None
-                            );
+                            let lev_candidate = NameMatcher::new(&n.as_str())
+                                .best(crate_types.iter());
                             if let Some(candidate) = lev_candidate {
                                 session.buffer_lint_with_diagnostic(
                                     lint::builtin::UNKNOWN_CRATE_TYPES,
@@ -77,7 +77,7 @@ use pretty::{PpMode, UserIdentifiedItem};
 
 use rustc_resolve as resolve;
 use rustc_save_analysis as save;
-use rustc_save_analysis::DumpHandler;
+use rustc_save_analysis::{DumpHandler, ResolveMapHandler};
 use rustc_data_structures::sync::{self, Lrc};
 use rustc_data_structures::OnDrop;
 use rustc::session::{self, config, Session, build_session, CompileResult};
@@ -129,6 +129,10 @@ pub mod driver;
 pub mod pretty;
 mod proc_macro_decls;
 
+/// The MIR interpreter, for tools that want to evaluate code with their own `Machine`
+/// (see `EvalContext::call_instance`).
+pub use rustc_mir::interpret;
+
 pub mod target_features {
     use syntax::ast;
     use syntax::symbol::Symbol;
@@ -545,7 +549,10 @@ fn run_compiler_with_pool<'a>(
                                               &odir,
                                               &ofile), Some(sess));
 
-        let _sess_abort_error = OnDrop(|| sess.diagnostic().print_error_count());
+        let _sess_abort_error = OnDrop(|| {
+            sess.diagnostic().emit_future_incompat_report();
+            sess.diagnostic().print_error_count();
+        });
 
         let control = callbacks.build_controller(&sess, &matches);
 
@@ -741,32 +748,8 @@ fn stdout_isatty() -> bool {
 fn handle_explain(code: &str,
                   descriptions: &errors::registry::Registry,
                   output: ErrorOutputType) {
-    let normalised = if code.starts_with("E") {
-        code.to_string()
-    } else {
-        format!("E{0:0>4}", code)
-    };
-    match descriptions.find_description(&normalised) {
-        Some(ref description) => {
-            let mut is_in_code_block = false;
-            let mut text = String::new();
-
-            // Slice off the leading newline and print.
-            for line in description[1..].lines() {
-                let indent_level = line.find(|c: char| !c.is_whitespace())
-                    .unwrap_or_else(|| line.len());
-                let dedented_line = &line[indent_level..];
-                if dedented_line.starts_with("```") {
-                    is_in_code_block = !is_in_code_block;
-                    text.push_str(&line[..(indent_level+3)]);
-                } else if is_in_code_block && dedented_line.starts_with("# ") {
-                    continue;
-                } else {
-                    text.push_str(line);
-                }
-                text.push('\n');
-            }
-
+    match descriptions.render_explanation(code) {
+        Some(text) => {
             if stdout_isatty() {
                 show_content_with_pager(&text);
             } else {
@@ -947,6 +930,10 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
             enable_save_analysis(&mut control);
         }
 
+        if sess.opts.debugging_opts.save_resolve {
+            enable_save_resolve(&mut control);
+        }
+
         if sess.print_fuel_crate.is_some() {
             let old_callback = control.compilation_done.callback;
             control.compilation_done.callback = box move |state| {
@@ -979,6 +966,27 @@ pub fn enable_save_analysis(control: &mut CompileController) {
     control.make_glob_map = resolve::MakeGlobMap::Yes;
 }
 
+pub fn enable_save_resolve(control: &mut CompileController) {
+    control.keep_ast = true;
+    // Runs after `-Z save-analysis`, if that's enabled too.
+    let old_callback = mem::replace(&mut control.after_analysis.callback, box |_| {});
+    control.after_analysis.callback = box move |state| {
+        old_callback(state);
+        time(state.session, "save resolve", || {
+            save::process_crate(state.tcx.unwrap(),
+                                state.expanded_crate.unwrap(),
+                                state.analysis.unwrap(),
+                                state.crate_name.unwrap(),
+                                state.input,
+                                None,
+                                ResolveMapHandler::new(state.out_dir,
+                                                       state.crate_name.unwrap()))
+        });
+    };
+    control.after_analysis.run_callback_on_error = true;
+    control.make_glob_map = resolve::MakeGlobMap::Yes;
+}
+
 impl RustcDefaultCalls {
     pub fn list_metadata(sess: &Session,
                          cstore: &CStore,
@@ -0,0 +1,477 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Emit diagnostics in the annotate-snippet style.
+//!
+//! `EmitterWriter` folds every annotation of a source line into a shared
+//! underline row and connects multi-line spans with drawn margins. This
+//! emitter instead gives each annotation a row of its own beneath its line,
+//! and renders each file touched by a diagnostic as a separate snippet. It is
+//! selected with `--error-format=human-annotate-rs`.
+
+use emitter::{Emitter, Destination, ColorConfig, emit_to_destination};
+use emitter::{ANONYMIZED_LINE_NUM, MAX_HIGHLIGHT_LINES, MAX_SUGGESTIONS};
+use snippet::{Style, StyledString};
+use {CodeSuggestion, DiagnosticBuilder, DiagnosticId, Level, SourceMapperDyn, SubDiagnostic};
+
+use syntax_pos::{MultiSpan, SourceFile};
+use rustc_data_structures::sync::Lrc;
+use std::cmp::{max, min};
+use std::io::prelude::*;
+use std::io;
+
+pub struct AnnotateSnippetEmitterWriter {
+    dst: Destination,
+    sm: Option<Lrc<SourceMapperDyn>>,
+    short_message: bool,
+    ui_testing: bool,
+}
+
+/// A single underline beneath a source line.
+struct Annotation {
+    /// Column where the underline begins, starting from 0.
+    start_col: usize,
+    /// Column where the underline ends, exclusive.
+    end_col: usize,
+    is_primary: bool,
+    label: Option<String>,
+}
+
+/// A source line together with everything pointing into it.
+struct AnnotatedLine {
+    /// Index of the line, starting from 0.
+    line_index: usize,
+    annotations: Vec<Annotation>,
+}
+
+/// The annotated lines of one source file.
+struct Snippet {
+    file: Lrc<SourceFile>,
+    lines: Vec<AnnotatedLine>,
+}
+
+type Rendered = Vec<Vec<StyledString>>;
+
+impl Emitter for AnnotateSnippetEmitterWriter {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        let mut rendered = vec![];
+        if self.short_message {
+            self.render_short_message(&mut rendered, &db.level, db.styled_message(), &db.code,
+                                      &db.span);
+        } else {
+            let width = self.line_num_width(&db.span, &db.children);
+            self.render_message(&mut rendered, &db.level, db.styled_message(), &db.code,
+                                &db.span, width, false);
+            if !db.children.is_empty() {
+                rendered.push(separator_row(width));
+            }
+            for child in &db.children {
+                let span = child.render_span.as_ref().unwrap_or(&child.span);
+                self.render_message(&mut rendered, &child.level, child.styled_message(), &None,
+                                    span, width, true);
+            }
+            for sugg in &db.suggestions {
+                self.render_suggestion(&mut rendered, sugg, width);
+            }
+        }
+
+        if let Err(e) = self.write(&rendered, &db.level) {
+            panic!("failed to emit error: {}", e);
+        }
+    }
+
+    fn should_show_explain(&self) -> bool {
+        !self.short_message
+    }
+}
+
+impl AnnotateSnippetEmitterWriter {
+    pub fn stderr(color_config: ColorConfig,
+                  source_map: Option<Lrc<SourceMapperDyn>>,
+                  short_message: bool)
+                  -> AnnotateSnippetEmitterWriter {
+        AnnotateSnippetEmitterWriter {
+            dst: Destination::from_stderr(color_config),
+            sm: source_map,
+            short_message,
+            ui_testing: false,
+        }
+    }
+
+    pub fn new(dst: Box<dyn Write + Send>,
+               source_map: Option<Lrc<SourceMapperDyn>>,
+               short_message: bool)
+               -> AnnotateSnippetEmitterWriter {
+        AnnotateSnippetEmitterWriter {
+            dst: Destination::Raw(dst),
+            sm: source_map,
+            short_message,
+            ui_testing: false,
+        }
+    }
+
+    pub fn ui_testing(mut self, ui_testing: bool) -> Self {
+        self.ui_testing = ui_testing;
+        self
+    }
+
+    fn write(&mut self, rendered: &[Vec<StyledString>], level: &Level) -> io::Result<()> {
+        emit_to_destination(rendered, level, &mut self.dst, self.short_message)?;
+        let mut dst = self.dst.writable();
+        write!(dst, "\n")?;
+        dst.flush()
+    }
+
+    fn line_num(&self, line_index: usize) -> String {
+        if self.ui_testing {
+            ANONYMIZED_LINE_NUM.to_string()
+        } else {
+            (line_index + 1).to_string()
+        }
+    }
+
+    /// The width of the line number gutter, shared by the whole diagnostic so
+    /// that the snippets of its children line up with its own.
+    fn line_num_width(&self, span: &MultiSpan, children: &[SubDiagnostic]) -> usize {
+        if self.ui_testing {
+            return ANONYMIZED_LINE_NUM.len();
+        }
+        let sm = match self.sm {
+            Some(ref sm) => sm,
+            None => return 0,
+        };
+        let max_line = |msp: &MultiSpan| {
+            msp.span_labels()
+                .iter()
+                .filter(|label| !label.span.is_dummy())
+                .map(|label| sm.lookup_char_pos(label.span.hi()).line)
+                .max()
+                .unwrap_or(0)
+        };
+        children.iter()
+            .map(|child| max_line(child.render_span.as_ref().unwrap_or(&child.span)))
+            .fold(max_line(span), max)
+            .to_string()
+            .len()
+    }
+
+    fn render_short_message(&self,
+                            rendered: &mut Rendered,
+                            level: &Level,
+                            msg: &[(String, Style)],
+                            code: &Option<DiagnosticId>,
+                            msp: &MultiSpan) {
+        let mut row = vec![];
+        if let Some(ref sm) = self.sm {
+            if let Some(span) = msp.primary_span() {
+                if !span.is_dummy() {
+                    let lo = sm.lookup_char_pos(span.lo());
+                    row.push(styled(&format!("{}:{}:{}: ",
+                                             lo.file.name,
+                                             sm.doctest_offset_line(&lo.file.name, lo.line),
+                                             lo.col.0 + 1),
+                                    Style::LineAndColumn));
+                }
+            }
+        }
+        row.extend(header(level, code, Style::MainHeaderMsg));
+        for &(ref text, _) in msg {
+            row.push(styled(text, Style::MainHeaderMsg));
+        }
+        rendered.push(row);
+    }
+
+    fn render_message(&self,
+                      rendered: &mut Rendered,
+                      level: &Level,
+                      msg: &[(String, Style)],
+                      code: &Option<DiagnosticId>,
+                      msp: &MultiSpan,
+                      width: usize,
+                      is_secondary: bool) {
+        let snippets = self.snippets(msp);
+
+        if is_secondary && snippets.is_empty() {
+            // A child without any span is attached to the snippet above it.
+            let level_str = level.to_string();
+            let mut row = vec![styled(&" ".repeat(width + 1), Style::NoStyle),
+                               styled("= ", Style::LineNumber)];
+            if !level_str.is_empty() {
+                row.push(styled(&level_str, Style::MainHeaderMsg));
+                row.push(styled(": ", Style::NoStyle));
+            }
+            let padding = width + 3 + level_str.len() + 2;
+            push_message(rendered, row, msg, padding, None);
+            return;
+        }
+
+        let header_style = if is_secondary {
+            Style::HeaderMsg
+        } else {
+            Style::MainHeaderMsg
+        };
+        push_message(rendered, header(level, code, header_style), msg, 0, Some(header_style));
+
+        let sm = match self.sm {
+            Some(ref sm) => sm,
+            None => return,
+        };
+        let primary_lo = msp.primary_span()
+            .filter(|span| !span.is_dummy())
+            .map(|span| sm.lookup_char_pos(span.lo()));
+        for (i, snippet) in snippets.iter().enumerate() {
+            let file_name = &snippet.file.name;
+            // The snippet of the primary span comes first and is introduced
+            // with `-->`, every other file with `:::`.
+            let (arrow, location) = match primary_lo {
+                Some(ref lo) if lo.file.name == *file_name => {
+                    ("--> ", format!("{}:{}:{}",
+                                     file_name,
+                                     sm.doctest_offset_line(file_name, lo.line),
+                                     lo.col.0 + 1))
+                }
+                _ => {
+                    let first_line = &snippet.lines[0];
+                    ("::: ", format!("{}:{}:{}",
+                                     file_name,
+                                     sm.doctest_offset_line(file_name, first_line.line_index + 1),
+                                     first_line.annotations[0].start_col + 1))
+                }
+            };
+            if i != 0 {
+                rendered.push(separator_row(width));
+            }
+            rendered.push(vec![styled(&" ".repeat(width), Style::NoStyle),
+                               styled(arrow, Style::LineNumber),
+                               styled(&location, Style::LineAndColumn)]);
+            rendered.push(separator_row(width));
+            self.render_snippet(rendered, snippet, width);
+        }
+    }
+
+    fn render_snippet(&self, rendered: &mut Rendered, snippet: &Snippet, width: usize) {
+        let mut prev_line_index = None;
+        for line in &snippet.lines {
+            if let Some(prev) = prev_line_index {
+                if line.line_index == prev + 2 {
+                    // Showing a single unannotated line is as short as eliding it.
+                    self.push_source_line(rendered, &snippet.file, prev + 1, width);
+                } else if line.line_index > prev + 2 {
+                    rendered.push(vec![styled("...", Style::LineNumber)]);
+                }
+            }
+            prev_line_index = Some(line.line_index);
+
+            self.push_source_line(rendered, &snippet.file, line.line_index, width);
+            for annotation in &line.annotations {
+                let (underline, underline_style, label_style) = if annotation.is_primary {
+                    ("^", Style::UnderlinePrimary, Style::LabelPrimary)
+                } else {
+                    ("-", Style::UnderlineSecondary, Style::LabelSecondary)
+                };
+                let mut row = vec![styled(&" ".repeat(width + 1), Style::NoStyle),
+                                   styled("| ", Style::LineNumber),
+                                   styled(&" ".repeat(annotation.start_col), Style::NoStyle),
+                                   styled(&underline.repeat(annotation.end_col -
+                                                            annotation.start_col),
+                                          underline_style)];
+                if let Some(ref label) = annotation.label {
+                    row.push(styled(" ", Style::NoStyle));
+                    row.push(styled(label, label_style));
+                }
+                rendered.push(row);
+            }
+        }
+    }
+
+    fn push_source_line(&self,
+                        rendered: &mut Rendered,
+                        file: &SourceFile,
+                        line_index: usize,
+                        width: usize) {
+        let source = file.get_line(line_index).map_or(String::new(), |line| line.into_owned());
+        rendered.push(vec![styled(&format!("{:<1$} ", self.line_num(line_index), width),
+                                  Style::LineNumber),
+                           styled("| ", Style::LineNumber),
+                           styled(&source, Style::Quotation)]);
+    }
+
+    fn render_suggestion(&self, rendered: &mut Rendered, sugg: &CodeSuggestion, width: usize) {
+        let sm = match self.sm {
+            Some(ref sm) => sm,
+            None => return,
+        };
+        let row = vec![styled("help", Style::Level(Level::Help)),
+                       styled(": ", Style::HeaderMsg)];
+        push_message(rendered, row, &[(sugg.msg.clone(), Style::NoStyle)], 0,
+                     Some(Style::HeaderMsg));
+
+        let suggestions = sugg.splice_lines(&**sm);
+        for &(ref complete, ref parts) in suggestions.iter().take(MAX_SUGGESTIONS) {
+            let first_line = match parts.first() {
+                Some(part) => sm.lookup_char_pos(part.span.lo()).line - 1,
+                None => continue,
+            };
+            rendered.push(separator_row(width));
+            for (i, line) in complete.lines().take(MAX_HIGHLIGHT_LINES).enumerate() {
+                rendered.push(vec![styled(&format!("{:<1$} ",
+                                                   self.line_num(first_line + i),
+                                                   width),
+                                          Style::LineNumber),
+                                   styled("| ", Style::LineNumber),
+                                   styled(line, Style::NoStyle)]);
+            }
+        }
+        if suggestions.len() > MAX_SUGGESTIONS {
+            let others = suggestions.len() - MAX_SUGGESTIONS;
+            let plural = if others > 1 { "s" } else { "" };
+            rendered.push(vec![styled(&format!("and {} other candidate{}", others, plural),
+                                      Style::NoStyle)]);
+        }
+    }
+
+    /// Collect the labels of `msp` into one snippet per file, with the file of
+    /// the primary span first and every line's annotations ordered by column.
+    fn snippets(&self, msp: &MultiSpan) -> Vec<Snippet> {
+        let sm = match self.sm {
+            Some(ref sm) => sm,
+            None => return vec![],
+        };
+
+        let mut snippets = vec![];
+        for span_label in msp.span_labels() {
+            if span_label.span.is_dummy() {
+                continue;
+            }
+            let file_lines = match sm.span_to_lines(span_label.span) {
+                Ok(file_lines) => file_lines,
+                Err(_) => continue,
+            };
+            // We can't annotate anything if the source is unavailable.
+            if !sm.ensure_source_file_source_present(file_lines.file.clone()) {
+                continue;
+            }
+            let (first, last) = match (file_lines.lines.first(), file_lines.lines.last()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => continue,
+            };
+            let file = &file_lines.file;
+            let is_primary = span_label.is_primary;
+
+            if first.line_index == last.line_index {
+                add_annotation(&mut snippets, file, first.line_index, Annotation {
+                    start_col: first.start_col.0,
+                    end_col: max(first.end_col.0, first.start_col.0 + 1),
+                    is_primary,
+                    label: span_label.label,
+                });
+            } else {
+                // A span covering several lines underlines the rest of its
+                // first line and the start of its last one, which carries the
+                // label. The lines in between are elided.
+                add_annotation(&mut snippets, file, first.line_index, Annotation {
+                    start_col: first.start_col.0,
+                    end_col: max(first.end_col.0, first.start_col.0 + 1),
+                    is_primary,
+                    label: None,
+                });
+                let indent = file.get_line(last.line_index).map_or(0, |line| {
+                    line.chars().take_while(|c| c.is_whitespace()).count()
+                });
+                let start_col = min(indent, last.end_col.0.saturating_sub(1));
+                add_annotation(&mut snippets, file, last.line_index, Annotation {
+                    start_col,
+                    end_col: max(last.end_col.0, start_col + 1),
+                    is_primary,
+                    label: span_label.label,
+                });
+            }
+        }
+
+        if let Some(span) = msp.primary_span() {
+            let primary_file = sm.lookup_char_pos(span.lo()).file;
+            if let Some(pos) = snippets.iter().position(|s| s.file.name == primary_file.name) {
+                let primary = snippets.remove(pos);
+                snippets.insert(0, primary);
+            }
+        }
+        for snippet in &mut snippets {
+            snippet.lines.sort_by_key(|line| line.line_index);
+            for line in &mut snippet.lines {
+                line.annotations.sort_by_key(|a| (a.start_col, a.end_col));
+            }
+        }
+        snippets
+    }
+}
+
+fn add_annotation(snippets: &mut Vec<Snippet>,
+                  file: &Lrc<SourceFile>,
+                  line_index: usize,
+                  annotation: Annotation) {
+    let existing = snippets.iter().position(|s| s.file.name == file.name);
+    let pos = match existing {
+        Some(pos) => pos,
+        None => {
+            snippets.push(Snippet { file: file.clone(), lines: vec![] });
+            snippets.len() - 1
+        }
+    };
+    let lines = &mut snippets[pos].lines;
+    let existing = lines.iter().position(|line| line.line_index == line_index);
+    match existing {
+        Some(i) => lines[i].annotations.push(annotation),
+        None => lines.push(AnnotatedLine { line_index, annotations: vec![annotation] }),
+    }
+}
+
+fn styled(text: &str, style: Style) -> StyledString {
+    StyledString { text: text.to_string(), style }
+}
+
+fn separator_row(width: usize) -> Vec<StyledString> {
+    vec![styled(&" ".repeat(width + 1), Style::NoStyle), styled("|", Style::LineNumber)]
+}
+
+/// The `error[E0000]: ` prefix of a message; lint codes are not rendered.
+fn header(level: &Level, code: &Option<DiagnosticId>, style: Style) -> Vec<StyledString> {
+    let level_str = level.to_string();
+    let mut row = vec![];
+    if !level_str.is_empty() {
+        row.push(styled(&level_str, Style::Level(*level)));
+    }
+    if let Some(DiagnosticId::Error(ref code)) = *code {
+        row.push(styled(&format!("[{}]", code), Style::Level(*level)));
+    }
+    if !level_str.is_empty() {
+        row.push(styled(": ", style));
+    }
+    row
+}
+
+/// Append `msg` to `row`, starting a new row indented by `padding` at every
+/// newline. Unless `override_style` is given, each part keeps its own style.
+fn push_message(rendered: &mut Rendered,
+                mut row: Vec<StyledString>,
+                msg: &[(String, Style)],
+                padding: usize,
+                override_style: Option<Style>) {
+    for &(ref text, style) in msg {
+        let style = override_style.unwrap_or(style);
+        for (i, line) in text.split('\n').enumerate() {
+            if i != 0 {
+                rendered.push(row);
+                row = vec![styled(&" ".repeat(padding), Style::NoStyle)];
+            }
+            row.push(styled(line, style));
+        }
+    }
+    rendered.push(row);
+}
@@ -13,7 +13,9 @@ use SubstitutionPart;
 use Substitution;
 use Applicability;
 use Level;
+use rustc_data_structures::stable_hasher::StableHasher;
 use std::fmt;
+use std::hash::Hash;
 use syntax_pos::{MultiSpan, Span};
 use snippet::Style;
 
@@ -26,6 +28,8 @@ pub struct Diagnostic {
     pub span: MultiSpan,
     pub children: Vec<SubDiagnostic>,
     pub suggestions: Vec<CodeSuggestion>,
+    /// Machine-readable key/value pairs that are only emitted in the JSON output.
+    pub metadata: Vec<(String, String)>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -96,6 +100,7 @@ impl Diagnostic {
             span: MultiSpan::new(),
             children: vec![],
             suggestions: vec![],
+            metadata: vec![],
         }
     }
 
@@ -254,6 +259,7 @@ impl Diagnostic {
             }],
             msg: msg.to_owned(),
             show_code_when_inline: false,
+            tool_only: false,
             applicability: Applicability::Unspecified,
         });
         self
@@ -286,6 +292,7 @@ impl Diagnostic {
             }],
             msg: msg.to_owned(),
             show_code_when_inline: true,
+            tool_only: false,
             applicability: Applicability::Unspecified,
         });
         self
@@ -306,6 +313,33 @@ impl Diagnostic {
             }],
             msg: msg.to_owned(),
             show_code_when_inline: true,
+            tool_only: false,
+            applicability,
+        });
+        self
+    }
+
+    /// Adds a multipart suggestion that is only emitted for tools, e.g. in the
+    /// JSON output, and never rendered for humans.
+    ///
+    /// This is meant for edits that are obvious from the main message, where
+    /// showing the code would only add noise to the diagnostic.
+    pub fn tool_only_multipart_suggestion(
+        &mut self,
+        msg: &str,
+        suggestion: Vec<(Span, String)>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.suggestions.push(CodeSuggestion {
+            substitutions: vec![Substitution {
+                parts: suggestion
+                    .into_iter()
+                    .map(|(span, snippet)| SubstitutionPart { snippet, span })
+                    .collect(),
+            }],
+            msg: msg.to_owned(),
+            show_code_when_inline: false,
+            tool_only: true,
             applicability,
         });
         self
@@ -336,6 +370,7 @@ impl Diagnostic {
             }).collect(),
             msg: msg.to_owned(),
             show_code_when_inline: true,
+            tool_only: false,
             applicability: Applicability::Unspecified,
         });
         self
@@ -355,6 +390,7 @@ impl Diagnostic {
             }],
             msg: msg.to_owned(),
             show_code_when_inline: true,
+            tool_only: false,
             applicability,
         });
         self
@@ -372,6 +408,7 @@ impl Diagnostic {
             }).collect(),
             msg: msg.to_owned(),
             show_code_when_inline: true,
+            tool_only: false,
             applicability,
         });
         self
@@ -389,6 +426,7 @@ impl Diagnostic {
             }],
             msg: msg.to_owned(),
             show_code_when_inline: false,
+            tool_only: false,
             applicability: applicability,
         });
         self
@@ -408,6 +446,11 @@ impl Diagnostic {
         self.code.clone()
     }
 
+    pub fn metadata(&mut self, key: &str, value: String) -> &mut Self {
+        self.metadata.push((key.to_owned(), value));
+        self
+    }
+
     pub fn message(&self) -> String {
         self.message.iter().map(|i| i.0.as_str()).collect::<String>()
     }
@@ -416,6 +459,29 @@ impl Diagnostic {
         &self.message
     }
 
+    /// Identifies diagnostics that render the same: the code, the primary
+    /// spans, every message and label shown for them and their suggestions.
+    /// Metadata is left out.
+    pub fn fingerprint(&self) -> u128 {
+        let mut hasher = StableHasher::new();
+        self.level.hash(&mut hasher);
+        self.code.hash(&mut hasher);
+        self.span.primary_spans().hash(&mut hasher);
+        self.message().hash(&mut hasher);
+        for span_label in self.span.span_labels() {
+            span_label.span.hash(&mut hasher);
+            span_label.is_primary.hash(&mut hasher);
+            span_label.label.hash(&mut hasher);
+        }
+        for child in &self.children {
+            child.level.hash(&mut hasher);
+            child.span.hash(&mut hasher);
+            child.message().hash(&mut hasher);
+        }
+        self.suggestions.hash(&mut hasher);
+        hasher.finish()
+    }
+
     /// Used by a lint. Copies over all details *but* the "main
     /// message".
     pub fn copy_details_not_message(&mut self, from: &Diagnostic) {
@@ -13,8 +13,10 @@ use self::Destination::*;
 use syntax_pos::{SourceFile, Span, MultiSpan};
 
 use {Level, CodeSuggestion, DiagnosticBuilder, SubDiagnostic, SourceMapperDyn, DiagnosticId};
+use {FutureIncompatLint, SubstitutionPart};
 use snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, StyledString, Style};
 use styled_buffer::StyledBuffer;
+use term;
 
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sync::Lrc;
@@ -22,12 +24,13 @@ use atty;
 use std::borrow::Cow;
 use std::io::prelude::*;
 use std::io;
-use std::cmp::{min, Reverse};
+use std::mem;
+use std::cmp::{max, min, Reverse};
 use termcolor::{StandardStream, ColorChoice, ColorSpec, BufferWriter};
-use termcolor::{WriteColor, Color, Buffer};
-use unicode_width;
+use termcolor::{WriteColor, Color, Buffer, Ansi};
+use unicode_width::{self, UnicodeWidthStr};
 
-const ANONYMIZED_LINE_NUM: &str = "LL";
+pub(crate) const ANONYMIZED_LINE_NUM: &str = "LL";
 
 /// Emitter trait for emitting errors.
 pub trait Emitter {
@@ -38,15 +41,24 @@ pub trait Emitter {
     fn should_show_explain(&self) -> bool {
         true
     }
+
+    /// Emit the future-incompatible lints that fired during the compilation.
+    /// Only machine-readable emitters do, the lints were already shown as
+    /// regular diagnostics.
+    fn emit_future_incompat_report(&mut self, _lints: &[FutureIncompatLint]) {}
 }
 
 impl Emitter for EmitterWriter {
     fn emit(&mut self, db: &DiagnosticBuilder) {
         let mut primary_span = db.span.clone();
         let mut children = db.children.clone();
+        let db_suggestions = self.fix_suggestions_in_macros(&db.suggestions)
+            .into_iter()
+            .filter(|sugg| !sugg.tool_only)
+            .collect::<Vec<_>>();
         let mut suggestions: &[_] = &[];
 
-        if let Some((sugg, rest)) = db.suggestions.split_first() {
+        if let Some((sugg, rest)) = db_suggestions.split_first() {
             if rest.is_empty() &&
                // don't display multi-suggestions as labels
                sugg.substitutions.len() == 1 &&
@@ -70,7 +82,7 @@ impl Emitter for EmitterWriter {
                 // to be consistent. We could try to figure out if we can
                 // make one (or the first one) inline, but that would give
                 // undue importance to a semi-random suggestion
-                suggestions = &db.suggestions;
+                suggestions = &db_suggestions;
             }
         }
 
@@ -98,6 +110,10 @@ pub const MAX_HIGHLIGHT_LINES: usize = 6;
 /// Arbitrary, but taken from trait import suggestion limit
 pub const MAX_SUGGESTIONS: usize = 4;
 
+/// Labels are only wrapped at the edge of the terminal if this many columns are
+/// left for them, otherwise they'd be wrapped into an unreadable ribbon.
+const MIN_WRAPPED_LABEL_WIDTH: usize = 20;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColorConfig {
     Auto,
@@ -109,7 +125,7 @@ impl ColorConfig {
     fn to_color_choice(&self) -> ColorChoice {
         match *self {
             ColorConfig::Always => {
-                if atty::is(atty::Stream::Stderr) {
+                if atty::is(atty::Stream::Stderr) && !ansi_console() {
                     ColorChoice::Always
                 } else {
                     ColorChoice::AlwaysAnsi
@@ -117,19 +133,32 @@ impl ColorConfig {
             }
             ColorConfig::Never => ColorChoice::Never,
             ColorConfig::Auto if atty::is(atty::Stream::Stderr) => {
-                ColorChoice::Auto
+                if ansi_console() {
+                    ColorChoice::AlwaysAnsi
+                } else {
+                    ColorChoice::Auto
+                }
             }
             ColorConfig::Auto => ColorChoice::Never,
         }
     }
 }
 
+/// Whether stderr is a Windows console that understands ANSI escape codes.
+/// Only older consoles need to be colored through the console API, which is
+/// what `termcolor` does on Windows unless asked for ANSI codes explicitly.
+fn ansi_console() -> bool {
+    cfg!(windows) && atty::is(atty::Stream::Stderr) && term::enable_ansi_colors()
+}
+
 pub struct EmitterWriter {
     dst: Destination,
     sm: Option<Lrc<SourceMapperDyn>>,
     short_message: bool,
     teach: bool,
     ui_testing: bool,
+    /// The width of the terminal, labels that would run past it are wrapped.
+    terminal_width: Option<usize>,
 }
 
 struct FileWithAnnotatedLines {
@@ -145,12 +174,18 @@ impl EmitterWriter {
                   teach: bool)
                   -> EmitterWriter {
         let dst = Destination::from_stderr(color_config);
+        let terminal_width = if atty::is(atty::Stream::Stderr) {
+            term::stderr_width()
+        } else {
+            None
+        };
         EmitterWriter {
             dst,
             sm: source_map,
             short_message,
             teach,
             ui_testing: false,
+            terminal_width,
         }
     }
 
@@ -165,6 +200,23 @@ impl EmitterWriter {
             short_message,
             teach,
             ui_testing: false,
+            terminal_width: None,
+        }
+    }
+
+    /// Like `new`, but the output keeps its styling as ANSI escape codes.
+    pub fn new_ansi(dst: Box<dyn Write + Send>,
+                    source_map: Option<Lrc<SourceMapperDyn>>,
+                    short_message: bool,
+                    teach: bool)
+                    -> EmitterWriter {
+        EmitterWriter {
+            dst: Colored(Box::new(Ansi::new(dst))),
+            sm: source_map,
+            short_message,
+            teach,
+            ui_testing: false,
+            terminal_width: None,
         }
     }
 
@@ -173,6 +225,27 @@ impl EmitterWriter {
         self
     }
 
+    /// Overrides the terminal width labels are wrapped at, if `width` is given.
+    pub fn terminal_width(mut self, width: Option<usize>) -> Self {
+        if width.is_some() {
+            self.terminal_width = width;
+        }
+        self
+    }
+
+    /// Splits `label`, to be written at column `col`, into lines that fit in
+    /// the terminal.
+    fn wrap_label(&self, label: &str, col: usize) -> Vec<String> {
+        match self.terminal_width {
+            // Leave the last column empty, some consoles move to the next
+            // line as soon as it is written to.
+            Some(width) if width > col + MIN_WRAPPED_LABEL_WIDTH => {
+                wrap_words(label, width - col - 1)
+            }
+            _ => vec![label.to_string()],
+        }
+    }
+
     fn maybe_anonymized(&self, line_num: usize) -> String {
         if self.ui_testing {
             ANONYMIZED_LINE_NUM.to_string()
@@ -288,6 +361,22 @@ impl EmitterWriter {
             }
         }
 
+        // The pass above only moves the annotations that overlap with the
+        // first one it meets, so with three or more overlapping annotations
+        // some of them can end up at the same depth, drawing their vertical
+        // lines on top of each other. Move each of those to the first depth
+        // that none of the annotations it clashes with uses.
+        for i in 0..multiline_annotations.len() {
+            let (placed, rest) = multiline_annotations.split_at_mut(i);
+            let ann = &mut rest[0].1;
+            let taken = |depth: usize| {
+                placed.iter().any(|&(_, ref other)| other.depth == depth && clash(other, ann))
+            };
+            if taken(ann.depth) {
+                ann.depth = (1..).find(|&depth| !taken(depth)).unwrap();
+            }
+        }
+
         let mut max_depth = 0;  // max overlapping multiline spans
         for (file, ann) in multiline_annotations {
             if ann.depth > max_depth {
@@ -528,9 +617,43 @@ impl EmitterWriter {
             }
         }
 
+        // The loop above never moves a label back up once a label to its right
+        // went down a line, even if the two are nowhere near each other. With
+        // four or more labels that makes for a needlessly tall staircase, so put
+        // each of them on the topmost track that is free where it is written.
+        if annotations.iter().filter(|a| a.has_label()).count() >= 4 &&
+           !annotations.iter().any(|a| a.is_multiline()) {
+            annotations_position = assign_label_tracks(&annotations);
+            line_len = annotations_position.iter().map(|&(p, _)| p).max().unwrap_or(0);
+        }
+
+        // Wrap the labels that would run past the edge of the terminal, and
+        // move the labels below them down to make room for the extra lines.
+        let wrapped_labels = annotations_position.iter().map(|&(pos, annotation)| {
+            let col = if pos == 0 { annotation.end_col + 1 } else { annotation.start_col };
+            annotation.label.as_ref().map(|label| self.wrap_label(label, code_offset + col))
+        }).collect::<Vec<_>>();
+        let max_pos = line_len;
+        let mut label_heights = vec![1; max_pos + 1];
+        for (&(pos, _), label) in annotations_position.iter().zip(&wrapped_labels) {
+            if let Some(ref label) = *label {
+                label_heights[pos] = max(label_heights[pos], label.len());
+            }
+        }
+        let mut rows = Vec::with_capacity(label_heights.len());
+        let mut extra_rows = 0;
+        for (pos, height) in label_heights.iter().enumerate() {
+            rows.push(pos + extra_rows);
+            extra_rows += height - 1;
+        }
+        for position in &mut annotations_position {
+            position.0 = rows[position.0];
+        }
+
         if line_len != 0 {
             line_len += 1;
         }
+        line_len += rows[max_pos] - max_pos + label_heights[max_pos] - 1;
 
         // If there are no annotations or the only annotations on this line are
         // MultilineLine, then there's only code being shown, stop processing.
@@ -656,7 +779,7 @@ impl EmitterWriter {
         // 3 |
         // 4 |   }
         //   |  _  test
-        for &(pos, annotation) in &annotations_position {
+        for (&(pos, annotation), label) in annotations_position.iter().zip(&wrapped_labels) {
             let style = if annotation.is_primary {
                 Style::LabelPrimary
             } else {
@@ -667,11 +790,13 @@ impl EmitterWriter {
             } else {
                 (pos + 2, annotation.start_col)
             };
-            if let Some(ref label) = annotation.label {
-                buffer.puts(line_offset + pos,
-                            code_offset + col,
-                            &label,
-                            style);
+            if let Some(ref label) = *label {
+                for (i, label_line) in label.iter().enumerate() {
+                    buffer.puts(line_offset + pos + i,
+                                code_offset + col,
+                                label_line,
+                                style);
+                }
             }
         }
 
@@ -880,6 +1005,40 @@ impl EmitterWriter {
         }
     }
 
+    // Suggestions pointing into a macro expansion are rendered against the macro definition,
+    // which is rarely where the change has to be made. Move them to the macro call site if the
+    // replacement doesn't depend on the expanded code; otherwise keep them as they are, they'll
+    // be rendered along with the expansion they come from.
+    fn fix_suggestions_in_macros(&self, suggestions: &[CodeSuggestion]) -> Vec<CodeSuggestion> {
+        let sm = match self.sm {
+            Some(ref sm) => sm,
+            None => return suggestions.to_vec(),
+        };
+        suggestions.iter().map(|sugg| {
+            let mut sugg = sugg.clone();
+            for substitution in &mut sugg.substitutions {
+                let parts = substitution.parts.iter().map(|part| {
+                    if part.span.ctxt().outer().expn_info().is_none() {
+                        return Some(part.clone());
+                    }
+                    sm.suggestion_at_call_site(part.span, &part.snippet)
+                        .map(|(span, snippet)| SubstitutionPart { span, snippet })
+                }).collect::<Option<Vec<_>>>();
+                if let Some(parts) = parts {
+                    // Several parts of the same expansion can map to the same invocation,
+                    // splicing all of them there would repeat it.
+                    let mut spans = parts.iter().map(|part| part.span).collect::<Vec<_>>();
+                    spans.sort();
+                    spans.dedup();
+                    if spans.len() == parts.len() {
+                        substitution.parts = parts;
+                    }
+                }
+            }
+            sugg
+        }).collect()
+    }
+
     /// Add a left margin to every line but the first, given a padding length and the label being
     /// displayed, keeping the provided highlighting.
     fn msg_to_buffer(&self,
@@ -1313,6 +1472,32 @@ impl EmitterWriter {
                 let msg = format!("and {} other candidates", suggestions.len() - MAX_SUGGESTIONS);
                 buffer.puts(row_num, 0, &msg, Style::NoStyle);
             }
+
+            // A suggestion still pointing into a macro expansion shows the macro definition,
+            // point out the invocations it was expanded from.
+            let expanded_span = suggestion.substitutions.iter()
+                .flat_map(|substitution| substitution.parts.iter())
+                .map(|part| part.span)
+                .find(|span| span.ctxt().outer().expn_info().is_some());
+            if let Some(span) = expanded_span {
+                let mut row_num = buffer.num_lines();
+                for trace in span.macro_backtrace() {
+                    if trace.macro_decl_name.starts_with("desugaring of ") {
+                        continue;
+                    }
+                    let loc = sm.lookup_char_pos(trace.call_site.lo());
+                    draw_note_separator(&mut buffer, row_num, max_line_num_len + 1);
+                    buffer.append(row_num, &Level::Note.to_string(), Style::MainHeaderMsg);
+                    buffer.append(row_num,
+                                  &format!(": in this expansion of `{}` at {}:{}:{}",
+                                           trace.macro_decl_name,
+                                           loc.file.name,
+                                           sm.doctest_offset_line(&loc.file.name, loc.line),
+                                           loc.col.0 + 1),
+                                  Style::NoStyle);
+                    row_num += 1;
+                }
+            }
             emit_to_destination(&buffer.render(), level, &mut self.dst, self.short_message)?;
         }
         Ok(())
@@ -1423,6 +1608,70 @@ fn draw_multiline_line(buffer: &mut StyledBuffer,
     buffer.putc(line, offset + depth - 1, '|', style);
 }
 
+/// Splits `text` at spaces into lines at most `width` columns wide. Words
+/// wider than that get a line of their own.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if !line.is_empty() && line.width() + 1 + word.width() > width {
+            lines.push(mem::replace(&mut line, String::new()));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines
+}
+
+/// Whether two multiline annotations can't share a depth. They can if they
+/// don't overlap, or if one ends on the line the other starts on, to the right
+/// of where the other starts: the end is drawn first and its vertical line
+/// stops above the start's.
+fn clash(a: &MultilineAnnotation, b: &MultilineAnnotation) -> bool {
+    if num_overlap(a.line_start, a.line_end, b.line_start, b.line_end, false) {
+        true
+    } else if a.line_end == b.line_start {
+        a.end_col <= b.start_col
+    } else if b.line_end == a.line_start {
+        b.end_col <= a.start_col
+    } else {
+        false
+    }
+}
+
+/// Assigns each of the annotations of a line, ordered from right to left, the
+/// track its label is written on: 0 to follow its underline, or the number of
+/// lines below the underlines otherwise. A label goes on the topmost track that
+/// keeps it clear of the annotations to its right, and annotations without a
+/// label stay on the underline.
+fn assign_label_tracks(annotations: &[Annotation]) -> Vec<(usize, &Annotation)> {
+    let mut tracks: Vec<(usize, &Annotation)> = vec![];
+    for (i, annotation) in annotations.iter().enumerate() {
+        if !annotation.has_label() {
+            tracks.push((0, annotation));
+            continue;
+        }
+        let padding = annotation.label.as_ref().map_or(0, |label| label.len() + 2);
+        let blocked_by = tracks.iter()
+            .filter(|&&(_, placed)| overlaps(annotation, placed, padding))
+            .map(|&(track, _)| track)
+            .max();
+        // A label can only follow its underline if no annotation to its left
+        // overlaps it.
+        let must_hang = annotations[i + 1..].iter().any(|next| overlaps(next, annotation, 0));
+        let track = match blocked_by {
+            Some(track) => track + 1,
+            None if must_hang => 1,
+            None => 0,
+        };
+        tracks.push((track, annotation));
+    }
+    tracks
+}
+
 fn num_overlap(a_start: usize, a_end: usize, b_start: usize, b_end:usize, inclusive: bool) -> bool {
     let extra = if inclusive {
         1
@@ -1436,11 +1685,11 @@ fn overlaps(a1: &Annotation, a2: &Annotation, padding: usize) -> bool {
     num_overlap(a1.start_col, a1.end_col + padding, a2.start_col, a2.end_col, false)
 }
 
-fn emit_to_destination(rendered_buffer: &[Vec<StyledString>],
-                       lvl: &Level,
-                       dst: &mut Destination,
-                       short_message: bool)
-                       -> io::Result<()> {
+pub(crate) fn emit_to_destination(rendered_buffer: &[Vec<StyledString>],
+                                  lvl: &Level,
+                                  dst: &mut Destination,
+                                  short_message: bool)
+                                  -> io::Result<()> {
     use lock;
 
     let mut dst = dst.writable();
@@ -1476,16 +1725,18 @@ pub enum Destination {
     Terminal(StandardStream),
     Buffered(BufferWriter),
     Raw(Box<dyn Write + Send>),
+    Colored(Box<dyn WriteColor + Send>),
 }
 
 pub enum WritableDst<'a> {
     Terminal(&'a mut StandardStream),
     Buffered(&'a mut BufferWriter, Buffer),
     Raw(&'a mut Box<dyn Write + Send>),
+    Colored(&'a mut Box<dyn WriteColor + Send>),
 }
 
 impl Destination {
-    fn from_stderr(color: ColorConfig) -> Destination {
+    pub(crate) fn from_stderr(color: ColorConfig) -> Destination {
         let choice = color.to_color_choice();
         // On Windows we'll be performing global synchronization on the entire
         // system for emitting rustc errors, so there's no need to buffer
@@ -1493,14 +1744,17 @@ impl Destination {
         //
         // On non-Windows we rely on the atomicity of `write` to ensure errors
         // don't get all jumbled up.
-        if cfg!(windows) {
+        //
+        // Consoles that understand ANSI codes are written to like Unix
+        // terminals though, as the codes don't need any console calls.
+        if cfg!(windows) && choice != ColorChoice::AlwaysAnsi {
             Terminal(StandardStream::stderr(choice))
         } else {
             Buffered(BufferWriter::stderr(choice))
         }
     }
 
-    fn writable<'a>(&'a mut self) -> WritableDst<'a> {
+    pub(crate) fn writable<'a>(&'a mut self) -> WritableDst<'a> {
         match *self {
             Destination::Terminal(ref mut t) => WritableDst::Terminal(t),
             Destination::Buffered(ref mut t) => {
@@ -1508,6 +1762,7 @@ impl Destination {
                 WritableDst::Buffered(t, buf)
             }
             Destination::Raw(ref mut t) => WritableDst::Raw(t),
+            Destination::Colored(ref mut t) => WritableDst::Colored(t),
         }
     }
 }
@@ -1565,6 +1820,7 @@ impl<'a> WritableDst<'a> {
         match *self {
             WritableDst::Terminal(ref mut t) => t.set_color(color),
             WritableDst::Buffered(_, ref mut t) => t.set_color(color),
+            WritableDst::Colored(ref mut t) => t.set_color(color),
             WritableDst::Raw(_) => Ok(())
         }
     }
@@ -1573,6 +1829,7 @@ impl<'a> WritableDst<'a> {
         match *self {
             WritableDst::Terminal(ref mut t) => t.reset(),
             WritableDst::Buffered(_, ref mut t) => t.reset(),
+            WritableDst::Colored(ref mut t) => t.reset(),
             WritableDst::Raw(_) => Ok(()),
         }
     }
@@ -1584,6 +1841,7 @@ impl<'a> Write for WritableDst<'a> {
             WritableDst::Terminal(ref mut t) => t.write(bytes),
             WritableDst::Buffered(_, ref mut buf) => buf.write(bytes),
             WritableDst::Raw(ref mut w) => w.write(bytes),
+            WritableDst::Colored(ref mut t) => t.write(bytes),
         }
     }
 
@@ -1592,6 +1850,7 @@ impl<'a> Write for WritableDst<'a> {
             WritableDst::Terminal(ref mut t) => t.flush(),
             WritableDst::Buffered(_, ref mut buf) => buf.flush(),
             WritableDst::Raw(ref mut w) => w.flush(),
+            WritableDst::Colored(ref mut t) => t.flush(),
         }
     }
 }
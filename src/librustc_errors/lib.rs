@@ -35,10 +35,11 @@ pub use emitter::ColorConfig;
 use self::Level::*;
 
 use emitter::{Emitter, EmitterWriter};
+use translation::MessageCatalog;
 
 use rustc_data_structures::sync::{self, Lrc, Lock, LockCell};
-use rustc_data_structures::fx::FxHashSet;
-use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::OnDrop;
 
 use std::borrow::Cow;
 use std::cell::Cell;
@@ -52,10 +53,13 @@ use termcolor::{ColorSpec, Color};
 mod diagnostic;
 mod diagnostic_builder;
 pub mod emitter;
+pub mod annotate_snippet_emitter_writer;
 mod snippet;
 pub mod registry;
 mod styled_buffer;
+pub mod translation;
 mod lock;
+mod term;
 
 use syntax_pos::{BytePos,
                  Loc,
@@ -100,6 +104,9 @@ pub struct CodeSuggestion {
     pub substitutions: Vec<Substitution>,
     pub msg: String,
     pub show_code_when_inline: bool,
+    /// Whether the suggestion is only meant for tools consuming the JSON
+    /// output, and shouldn't be rendered for humans at all
+    pub tool_only: bool,
     /// Whether or not the suggestion is approximate
     ///
     /// Sometimes we may show suggestions with placeholders,
@@ -110,6 +117,9 @@ pub struct CodeSuggestion {
 
 #[derive(Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
 /// See the docs on `CodeSuggestion::substitutions`
+///
+/// The parts of a substitution need not be contiguous (e.g. adding an
+/// import and changing a call site), but they must be applied together.
 pub struct Substitution {
     pub parts: Vec<SubstitutionPart>,
 }
@@ -129,6 +139,7 @@ pub trait SourceMapper {
     fn span_to_filename(&self, sp: Span) -> FileName;
     fn merge_spans(&self, sp_lhs: Span, sp_rhs: Span) -> Option<Span>;
     fn call_span_if_macro(&self, sp: Span) -> Span;
+    fn suggestion_at_call_site(&self, sp: Span, snippet: &str) -> Option<(Span, String)>;
     fn ensure_source_file_source_present(&self, source_file: Lrc<SourceFile>) -> bool;
     fn doctest_offset_line(&self, file: &FileName, line: usize) -> usize;
 }
@@ -273,6 +284,17 @@ impl error::Error for ExplicitBug {
 pub use diagnostic::{Diagnostic, SubDiagnostic, DiagnosticStyledString, DiagnosticId};
 pub use diagnostic_builder::DiagnosticBuilder;
 
+/// A future-incompatible lint that fired during the compilation, kept for the
+/// report emitted at its end. (rustc: see `--json=future-incompat`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct FutureIncompatLint {
+    /// The name of the lint, like `safe_packed_borrows`.
+    pub lint: String,
+    pub span: MultiSpan,
+    /// Where to read about the change, usually its tracking issue.
+    pub reference: String,
+}
+
 /// A handler deals with errors; certain errors
 /// (fatal, bug, unimpl) may cause immediate exit,
 /// others log errors for later reporting.
@@ -292,12 +314,38 @@ pub struct Handler {
     /// Used to suggest rustc --explain <error code>
     emitted_diagnostic_codes: Lock<FxHashSet<DiagnosticId>>,
 
-    // This set contains a hash of every diagnostic that has been emitted by
-    // this handler. These hashes is used to avoid emitting the same error
-    // twice.
+    // This set contains the fingerprint of every diagnostic that has been
+    // emitted by this handler. These fingerprints are used to avoid emitting
+    // the same error twice.
     emitted_diagnostics: Lock<FxHashSet<u128>>,
+
+    /// The number of errors reported so far with each error code, used to
+    /// fold long runs of the same error. See `MAX_SIMILAR_ERRORS`.
+    similar_errors: Lock<FxHashMap<String, usize>>,
+
+    /// The number of errors that were about to be shown so far, used to stop
+    /// showing errors past `HandlerFlags::error_limit`.
+    limited_errors: AtomicUsize,
+
+    /// Diagnostics held back by `in_source_order`, with the order key of the
+    /// item that reported them. `None` while not buffering.
+    ordered_diagnostics: Lock<Option<Vec<(usize, Diagnostic)>>>,
+
+    /// Translations of diagnostic messages, see `translate`.
+    message_catalog: Option<MessageCatalog>,
+
+    /// The future-incompatible lints to report at the end of the compilation.
+    future_incompat_lints: Lock<Vec<FutureIncompatLint>>,
 }
 
+// The order key of the item the current thread is working on, as set by
+// `Handler::with_order_key`.
+thread_local!(static ORDER_KEY: Cell<Option<usize>> = Cell::new(None));
+
+/// How many errors with the same code are emitted before the rest are
+/// folded into a single note at the end of the compilation.
+pub const MAX_SIMILAR_ERRORS: usize = 100;
+
 fn default_track_diagnostic(_: &Diagnostic) {}
 
 thread_local!(pub static TRACK_DIAGNOSTICS: Cell<fn(&Diagnostic)> =
@@ -320,6 +368,14 @@ pub struct HandlerFlags {
     /// show macro backtraces even for non-local macros.
     /// (rustc: see `-Z external-macro-backtrace`)
     pub external_macro_backtrace: bool,
+    /// If true, emit every error instead of folding those past
+    /// `MAX_SIMILAR_ERRORS` with the same code into a summary note.
+    /// (rustc: see `-Z expand-similar-errors`)
+    pub expand_similar_errors: bool,
+    /// How many errors are shown before the rest are only counted and
+    /// mentioned in a summary note. 0 means there is no limit.
+    /// (rustc: see `--error-limit`)
+    pub error_limit: usize,
 }
 
 impl Drop for Handler {
@@ -385,9 +441,36 @@ impl Handler {
             taught_diagnostics: Default::default(),
             emitted_diagnostic_codes: Default::default(),
             emitted_diagnostics: Default::default(),
+            similar_errors: Default::default(),
+            limited_errors: AtomicUsize::new(0),
+            ordered_diagnostics: Lock::new(None),
+            message_catalog: None,
+            future_incompat_lints: Lock::new(Vec::new()),
         }
     }
 
+    /// Sets the catalog used to translate diagnostic messages.
+    /// (rustc: see `-Z translate-lang`)
+    pub fn set_message_catalog(&mut self, catalog: MessageCatalog) {
+        self.message_catalog = Some(catalog);
+    }
+
+    /// Returns the translation of message `slug`, or of its attribute `attr`, formatted with
+    /// `args`. Falls back to the English message built by `fallback` if there is no catalog or
+    /// the catalog doesn't have the message.
+    pub fn translate<F>(&self,
+                        slug: &str,
+                        attr: Option<&str>,
+                        args: &[(&str, String)],
+                        fallback: F)
+                        -> String
+        where F: FnOnce() -> String
+    {
+        self.message_catalog.as_ref()
+            .and_then(|catalog| catalog.format(slug, attr, args))
+            .unwrap_or_else(fallback)
+    }
+
     pub fn set_continue_after_error(&self, continue_after_error: bool) {
         self.continue_after_error.set(continue_after_error);
     }
@@ -400,6 +483,8 @@ impl Handler {
     pub fn reset_err_count(&self) {
         // actually frees the underlying memory (which `clear` would not do)
         *self.emitted_diagnostics.borrow_mut() = Default::default();
+        *self.similar_errors.borrow_mut() = Default::default();
+        self.limited_errors.store(0, SeqCst);
         self.err_count.store(0, SeqCst);
     }
 
@@ -617,12 +702,14 @@ impl Handler {
     }
 
     pub fn print_error_count(&self) {
+        self.flush_ordered_diagnostics();
         let s = match self.err_count() {
             0 => return,
             1 => "aborting due to previous error".to_string(),
             _ => format!("aborting due to {} previous errors", self.err_count())
         };
 
+        self.print_omitted_errors();
         let _ = self.fatal(&s);
 
         let can_show_explain = self.emitter.borrow().should_show_explain();
@@ -655,6 +742,51 @@ impl Handler {
         }
     }
 
+    fn print_omitted_errors(&self) {
+        let mut omitted = self.similar_errors
+                              .borrow()
+                              .iter()
+                              .filter(|&(_, &count)| count > MAX_SIMILAR_ERRORS)
+                              .map(|(code, &count)| (code.clone(), count - MAX_SIMILAR_ERRORS))
+                              .collect::<Vec<_>>();
+        omitted.sort();
+        for (code, count) in omitted {
+            self.note_without_error(&format!("{} more error{} with code {} {} omitted; \
+                                              pass `-Z expand-similar-errors` to show {}",
+                                             count,
+                                             if count == 1 { "" } else { "s" },
+                                             code,
+                                             if count == 1 { "was" } else { "were" },
+                                             if count == 1 { "it" } else { "them all" }));
+        }
+
+        let limited = self.limited_errors.load(SeqCst);
+        if self.flags.error_limit != 0 && limited > self.flags.error_limit {
+            let count = limited - self.flags.error_limit;
+            self.note_without_error(&format!("and {} more error{}; \
+                                              rerun with `--error-limit=0` to see all",
+                                             count,
+                                             if count == 1 { "" } else { "s" }));
+        }
+    }
+
+    /// Remembers that `lint` fired, for `emit_future_incompat_report`.
+    pub fn record_future_incompat_lint(&self, lint: FutureIncompatLint) {
+        let mut lints = self.future_incompat_lints.borrow_mut();
+        if !lints.contains(&lint) {
+            lints.push(lint);
+        }
+    }
+
+    /// Hands the future-incompatible lints recorded so far to the emitter, in
+    /// the order they fired.
+    pub fn emit_future_incompat_report(&self) {
+        let lints = self.future_incompat_lints.borrow();
+        if !lints.is_empty() {
+            self.emitter.borrow_mut().emit_future_incompat_report(&lints);
+        }
+    }
+
     pub fn abort_if_errors(&self) {
         if self.err_count() == 0 {
             return;
@@ -698,6 +830,64 @@ impl Handler {
         db.cancel();
     }
 
+    /// Runs `f`, which reports diagnostics for a sequence of items from
+    /// several threads, each item wrapped in `with_order_key`. The
+    /// diagnostics are then emitted ordered by the keys of their items, so
+    /// that the output does not depend on how the threads were scheduled.
+    pub fn in_source_order<R, F: FnOnce() -> R>(&self, f: F) -> R {
+        // Buffering regardless of the number of threads keeps the output of
+        // serial and parallel builds the same.
+        if self.ordered_diagnostics.borrow().is_some() {
+            return f();
+        }
+        *self.ordered_diagnostics.borrow_mut() = Some(vec![]);
+        // Also flush when unwinding, e.g. after a fatal error, so that the
+        // buffered diagnostics still get shown.
+        let _flush = OnDrop(|| self.flush_ordered_diagnostics());
+        f()
+    }
+
+    /// Runs `f` as the item with the given order key. See `in_source_order`.
+    /// Keys can be nested, the innermost one is used.
+    pub fn with_order_key<R, F: FnOnce() -> R>(&self, key: usize, f: F) -> R {
+        let previous = ORDER_KEY.with(|order_key| order_key.replace(Some(key)));
+        let _restore = OnDrop(|| ORDER_KEY.with(|order_key| order_key.set(previous)));
+        f()
+    }
+
+    fn flush_ordered_diagnostics(&self) {
+        let mut diagnostics = match self.ordered_diagnostics.borrow_mut().take() {
+            Some(diagnostics) => diagnostics,
+            None => return,
+        };
+        // Each item reports all its diagnostics from a single thread, so a
+        // stable sort keeps the order they were reported in.
+        diagnostics.sort_by_key(|&(key, _)| key);
+        for (_, diagnostic) in diagnostics {
+            if !self.fold_similar_error(&diagnostic) && !self.limit_error(&diagnostic) {
+                let mut db = DiagnosticBuilder::new_diagnostic(self, diagnostic);
+                self.emitter.borrow_mut().emit(&db);
+                db.cancel();
+            }
+        }
+    }
+
+    /// Holds `diagnostic` back if it was reported from within
+    /// `in_source_order`, returning whether it did.
+    fn buffer_in_source_order(&self, diagnostic: &Diagnostic) -> bool {
+        let key = match ORDER_KEY.with(|order_key| order_key.get()) {
+            Some(key) => key,
+            None => return false,
+        };
+        match *self.ordered_diagnostics.borrow_mut() {
+            Some(ref mut diagnostics) => {
+                diagnostics.push((key, diagnostic.clone()));
+                true
+            }
+            None => false,
+        }
+    }
+
     fn emit_db(&self, db: &DiagnosticBuilder) {
         let diagnostic = &**db;
 
@@ -709,22 +899,47 @@ impl Handler {
             self.emitted_diagnostic_codes.borrow_mut().insert(code.clone());
         }
 
-        let diagnostic_hash = {
-            use std::hash::Hash;
-            let mut hasher = StableHasher::new();
-            diagnostic.hash(&mut hasher);
-            hasher.finish()
-        };
-
         // Only emit the diagnostic if we haven't already emitted an equivalent
-        // one:
-        if self.emitted_diagnostics.borrow_mut().insert(diagnostic_hash) {
-            self.emitter.borrow_mut().emit(db);
+        // one. Errors are counted right away even when the diagnostic itself
+        // is buffered, so that `has_errors` stays accurate.
+        if self.emitted_diagnostics.borrow_mut().insert(diagnostic.fingerprint()) {
+            if !self.buffer_in_source_order(diagnostic) &&
+               !self.fold_similar_error(diagnostic) &&
+               !self.limit_error(diagnostic) {
+                self.emitter.borrow_mut().emit(db);
+            }
             if db.is_error() {
                 self.bump_err_count();
             }
         }
     }
+
+    /// Count `diagnostic` against its error code, returning `true` if enough
+    /// errors with that code have been shown already that it should only be
+    /// mentioned in the summary printed by `print_error_count`.
+    fn fold_similar_error(&self, diagnostic: &Diagnostic) -> bool {
+        if self.flags.expand_similar_errors || diagnostic.level != Level::Error {
+            return false;
+        }
+        let code = match diagnostic.code {
+            Some(DiagnosticId::Error(ref code)) => code,
+            _ => return false,
+        };
+        let mut similar_errors = self.similar_errors.borrow_mut();
+        let count = similar_errors.entry(code.clone()).or_insert(0);
+        *count += 1;
+        *count > MAX_SIMILAR_ERRORS
+    }
+
+    /// Count `diagnostic` against the error limit, returning `true` if the
+    /// limit was reached already and it should only be counted in the summary
+    /// printed by `print_error_count`.
+    fn limit_error(&self, diagnostic: &Diagnostic) -> bool {
+        if self.flags.error_limit == 0 || diagnostic.level != Level::Error {
+            return false;
+        }
+        self.limited_errors.fetch_add(1, SeqCst) >= self.flags.error_limit
+    }
 }
 
 
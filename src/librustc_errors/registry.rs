@@ -10,11 +10,28 @@
 
 use rustc_data_structures::fx::FxHashMap;
 
+/// The extended explanations of the error codes known to the compiler.
 #[derive(Clone)]
 pub struct Registry {
     descriptions: FxHashMap<&'static str, &'static str>,
 }
 
+/// An error code's extended explanation together with the metadata that can
+/// be read off it.
+#[derive(Clone, Debug)]
+pub struct ErrorExplanation {
+    pub code: String,
+    /// The long-form explanation, starting and ending with a newline.
+    pub description: &'static str,
+    /// The release the code first appeared in, taken from a closing
+    /// `Introduced in Rust X.Y.Z.` line of the description.
+    pub since: Option<&'static str>,
+    /// The other registered error codes the description refers to, in order.
+    pub related: Vec<&'static str>,
+}
+
+const SINCE_PREFIX: &str = "Introduced in Rust ";
+
 impl Registry {
     pub fn new(descriptions: &[(&'static str, &'static str)]) -> Registry {
         Registry { descriptions: descriptions.iter().cloned().collect() }
@@ -23,4 +40,74 @@ impl Registry {
     pub fn find_description(&self, code: &str) -> Option<&'static str> {
         self.descriptions.get(code).cloned()
     }
+
+    pub fn find_explanation(&self, code: &str) -> Option<ErrorExplanation> {
+        let description = self.find_description(code)?;
+
+        let since = description.lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .and_then(|line| {
+                let line = line.trim();
+                if line.starts_with(SINCE_PREFIX) && line.ends_with('.') {
+                    Some(&line[SINCE_PREFIX.len()..line.len() - 1])
+                } else {
+                    None
+                }
+            });
+
+        let mut related = vec![];
+        for (i, _) in description.match_indices('E') {
+            let candidate = match description.get(i..i + 5) {
+                Some(candidate) => candidate,
+                None => continue,
+            };
+            if candidate[1..].bytes().all(|b| b.is_ascii_digit()) &&
+               candidate != code &&
+               self.descriptions.contains_key(candidate) &&
+               !related.contains(&candidate) {
+                related.push(candidate);
+            }
+        }
+
+        Some(ErrorExplanation { code: code.to_string(), description, since, related })
+    }
+
+    /// The text `rustc --explain` prints for `code`, which may be given
+    /// without its leading `E` and zeros, e.g. `499` for `E0499`.
+    ///
+    /// Lines hidden from the examples with a leading `# ` are left out, and
+    /// the related error codes are listed at the end.
+    pub fn render_explanation(&self, code: &str) -> Option<String> {
+        let normalised = if code.starts_with("E") {
+            code.to_string()
+        } else {
+            format!("E{0:0>4}", code)
+        };
+        let explanation = self.find_explanation(&normalised)?;
+
+        let mut is_in_code_block = false;
+        let mut text = String::new();
+
+        // Slice off the leading newline.
+        for line in explanation.description[1..].lines() {
+            let indent_level = line.find(|c: char| !c.is_whitespace())
+                .unwrap_or_else(|| line.len());
+            let dedented_line = &line[indent_level..];
+            if dedented_line.starts_with("```") {
+                is_in_code_block = !is_in_code_block;
+                text.push_str(&line[..(indent_level+3)]);
+            } else if is_in_code_block && dedented_line.starts_with("# ") {
+                continue;
+            } else {
+                text.push_str(line);
+            }
+            text.push('\n');
+        }
+
+        if !explanation.related.is_empty() {
+            text.push_str(&format!("\nSee also: {}.\n", explanation.related.join(", ")));
+        }
+        Some(text)
+    }
 }
@@ -0,0 +1,127 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Queries about the terminal diagnostics are written to.
+//!
+//! Like `lock`, the Windows parts declare the few console functions they need
+//! themselves rather than pulling in a bindings crate.
+
+pub use self::imp::{enable_ansi_colors, stderr_width};
+
+#[cfg(windows)]
+#[allow(nonstandard_style)]
+mod imp {
+    use std::mem;
+
+    type BOOL = i32;
+    type DWORD = u32;
+    type HANDLE = *mut u8;
+    type SHORT = i16;
+    type WORD = u16;
+
+    const STD_ERROR_HANDLE: DWORD = -12i32 as DWORD;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: DWORD = 0x0004;
+
+    #[repr(C)]
+    struct COORD {
+        X: SHORT,
+        Y: SHORT,
+    }
+
+    #[repr(C)]
+    struct SMALL_RECT {
+        Left: SHORT,
+        Top: SHORT,
+        Right: SHORT,
+        Bottom: SHORT,
+    }
+
+    #[repr(C)]
+    struct CONSOLE_SCREEN_BUFFER_INFO {
+        dwSize: COORD,
+        dwCursorPosition: COORD,
+        wAttributes: WORD,
+        srWindow: SMALL_RECT,
+        dwMaximumWindowSize: COORD,
+    }
+
+    extern "system" {
+        fn GetStdHandle(nStdHandle: DWORD) -> HANDLE;
+        fn GetConsoleMode(hConsoleHandle: HANDLE, lpMode: *mut DWORD) -> BOOL;
+        fn SetConsoleMode(hConsoleHandle: HANDLE, dwMode: DWORD) -> BOOL;
+        fn GetConsoleScreenBufferInfo(hConsoleOutput: HANDLE,
+                                      lpConsoleScreenBufferInfo: *mut CONSOLE_SCREEN_BUFFER_INFO)
+                                      -> BOOL;
+    }
+
+    /// Turns on the interpretation of ANSI escape sequences by the console
+    /// stderr is attached to, returning whether the console supports them.
+    /// Only consoles of Windows 10 and later do, older ones have to be
+    /// colored through the console API.
+    pub fn enable_ansi_colors() -> bool {
+        unsafe {
+            let handle = GetStdHandle(STD_ERROR_HANDLE);
+            let mut mode = 0;
+            if GetConsoleMode(handle, &mut mode) == 0 {
+                return false;
+            }
+            mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 ||
+                SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+        }
+    }
+
+    /// Returns the width of the console window stderr is attached to.
+    pub fn stderr_width() -> Option<usize> {
+        unsafe {
+            let mut info: CONSOLE_SCREEN_BUFFER_INFO = mem::zeroed();
+            if GetConsoleScreenBufferInfo(GetStdHandle(STD_ERROR_HANDLE), &mut info) == 0 {
+                return None;
+            }
+            // The screen buffer is usually much wider than the window, it's
+            // the window that lines wrap at.
+            Some((info.srWindow.Right - info.srWindow.Left + 1) as usize)
+        }
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use libc;
+    use std::mem;
+
+    /// Terminals always understand ANSI escape sequences on Unix.
+    pub fn enable_ansi_colors() -> bool {
+        true
+    }
+
+    /// Returns the width of the terminal stderr is attached to.
+    pub fn stderr_width() -> Option<usize> {
+        unsafe {
+            let mut size: libc::winsize = mem::zeroed();
+            if libc::ioctl(libc::STDERR_FILENO, libc::TIOCGWINSZ, &mut size) == 0 &&
+               size.ws_col > 0 {
+                Some(size.ws_col as usize)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod imp {
+    pub fn enable_ansi_colors() -> bool {
+        true
+    }
+
+    pub fn stderr_width() -> Option<usize> {
+        None
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Translated diagnostic messages.
+//!
+//! Messages are looked up by slug in a `MessageCatalog` loaded from resource files written in
+//! a subset of [Fluent](https://projectfluent.org/):
+//!
+//! ```text
+//! # Comments start with `#`.
+//! typeck-unrecognized-atomic-operation =
+//!     fonction d'opération atomique non reconnue : `{ $op }`
+//!     .span-label = opération atomique non reconnue
+//! ```
+//!
+//! A message has a value and attributes (`.span-label`), both of which may refer to the
+//! arguments of the diagnostic with `{ $name }`. Literal braces are written `{ "{" }`.
+//! Selectors, terms and functions aren't supported.
+
+use rustc_data_structures::fx::FxHashMap;
+
+use std::fs;
+use std::path::Path;
+
+#[derive(Default)]
+pub struct MessageCatalog {
+    messages: FxHashMap<String, Message>,
+}
+
+#[derive(Default)]
+struct Message {
+    value: Option<String>,
+    attributes: FxHashMap<String, String>,
+}
+
+impl MessageCatalog {
+    pub fn new() -> MessageCatalog {
+        MessageCatalog::default()
+    }
+
+    /// Loads every `.ftl` file of `dir`, in alphabetical order.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<(), String> {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("failed to read `{}`: {}", dir.display(), e))?;
+        let mut paths = vec![];
+        for entry in entries {
+            let path = entry.map_err(|e| format!("failed to read `{}`: {}", dir.display(), e))?
+                .path();
+            if path.extension().map_or(false, |ext| ext == "ftl") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        for path in paths {
+            self.load_file(&path)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_file(&mut self, path: &Path) -> Result<(), String> {
+        let src = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read `{}`: {}", path.display(), e))?;
+        self.add_resource(&path.display().to_string(), &src)
+    }
+
+    /// Parses the resource `src` and adds its messages to the catalog, replacing any previous
+    /// message with the same slug. `name` is only used in errors.
+    pub fn add_resource(&mut self, name: &str, src: &str) -> Result<(), String> {
+        let mut current: Option<(String, Message)> = None;
+        // The value or attribute that indented lines continue.
+        let mut continued: Option<Option<String>> = None;
+
+        for (i, line) in src.lines().enumerate() {
+            let error = |msg: &str| Err(format!("{}:{}: {}", name, i + 1, msg));
+            let trimmed = line.trim();
+            if trimmed.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if !line.starts_with(|c: char| c.is_whitespace()) {
+                // A new message: `slug = value`.
+                let (slug, value) = match split_definition(line) {
+                    Some(definition) => definition,
+                    None => return error("expected a message definition `slug = value`"),
+                };
+                if let Some((slug, message)) = current.take() {
+                    self.messages.insert(slug, message);
+                }
+                let mut message = Message::default();
+                if !value.is_empty() {
+                    message.value = Some(value.to_string());
+                }
+                current = Some((slug.to_string(), message));
+                continued = Some(None);
+                continue;
+            }
+
+            let message = match current {
+                Some((_, ref mut message)) => message,
+                None => return error("indented line outside of a message"),
+            };
+            if trimmed.starts_with('.') {
+                // An attribute: `.name = value`.
+                let (attr, value) = match split_definition(&trimmed[1..]) {
+                    Some(definition) => definition,
+                    None => return error("expected an attribute definition `.name = value`"),
+                };
+                message.attributes.insert(attr.to_string(), value.to_string());
+                continued = Some(Some(attr.to_string()));
+                continue;
+            }
+
+            // A continuation of the value or of the last attribute.
+            let text = match continued {
+                Some(None) => message.value.get_or_insert_with(String::new),
+                Some(Some(ref attr)) => message.attributes.get_mut(attr).unwrap(),
+                None => return error("indented line outside of a message"),
+            };
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(trimmed);
+        }
+
+        if let Some((slug, message)) = current {
+            self.messages.insert(slug, message);
+        }
+        Ok(())
+    }
+
+    /// Formats the value of message `slug`, or its attribute `attr`, with the given arguments.
+    /// Returns `None` if the catalog doesn't have it.
+    pub fn format(&self, slug: &str, attr: Option<&str>, args: &[(&str, String)])
+                  -> Option<String> {
+        let message = self.messages.get(slug)?;
+        let pattern = match attr {
+            Some(attr) => message.attributes.get(attr)?,
+            None => message.value.as_ref()?,
+        };
+        Some(format_pattern(pattern, args))
+    }
+}
+
+/// Splits `name = value` into its trimmed parts if `name` is a valid identifier.
+fn split_definition(line: &str) -> Option<(&str, &str)> {
+    let eq = line.find('=')?;
+    let name = line[..eq].trim();
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic()) &&
+                name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Some((name, line[eq + 1..].trim()))
+    } else {
+        None
+    }
+}
+
+/// Replaces the placeables of `pattern`: `{ $name }` with the argument `name` and `{ "text" }`
+/// with `text`. Unknown arguments are kept as `{$name}` so the message stays readable.
+fn format_pattern(pattern: &str, args: &[(&str, String)]) -> String {
+    let mut result = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        // String literals may contain braces, look for the closing one after them.
+        let search_from = if rest[start + 1..].trim_left().starts_with('"') {
+            let open_quote = start + 1 + rest[start + 1..].find('"').unwrap();
+            rest[open_quote + 1..].find('"').map(|close_quote| open_quote + 1 + close_quote)
+        } else {
+            Some(start)
+        };
+        let end = match search_from.and_then(|from| rest[from..].find('}').map(|end| from + end)) {
+            Some(end) => end,
+            // Unterminated, keep the rest as it is.
+            None => break,
+        };
+        result.push_str(&rest[..start]);
+        let placeable = rest[start + 1..end].trim();
+        if placeable.starts_with('$') {
+            let name = &placeable[1..];
+            match args.iter().find(|&&(arg, _)| arg == name) {
+                Some(&(_, ref value)) => result.push_str(value),
+                None => {
+                    result.push_str("{$");
+                    result.push_str(name);
+                    result.push('}');
+                }
+            }
+        } else if placeable.len() >= 2 && placeable.starts_with('"') && placeable.ends_with('"') {
+            result.push_str(&placeable[1..placeable.len() - 1]);
+        } else {
+            result.push_str(&rest[start..end + 1]);
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
@@ -31,6 +31,7 @@
 use rustc::hir::def::Def;
 use rustc::hir::def_id::DefId;
 use rustc::ty::{self, Ty};
+use rustc::traits;
 use hir::Node;
 use util::nodemap::NodeSet;
 use lint::{LateContext, LintContext, LintArray};
@@ -45,7 +46,8 @@ use syntax::ast::Expr;
 use syntax::attr;
 use syntax::source_map::Spanned;
 use syntax::edition::Edition;
-use syntax::feature_gate::{AttributeGate, AttributeType, Stability, deprecated_attributes};
+use syntax::feature_gate::{AttributeGate, AttributeTemplate, AttributeType, Stability};
+use syntax::feature_gate::deprecated_attributes;
 use syntax_pos::{BytePos, Span, SyntaxContext};
 use syntax::symbol::keywords;
 use syntax::errors::{Applicability, DiagnosticBuilder};
@@ -762,7 +764,7 @@ impl EarlyLintPass for BadRepr {
 pub struct DeprecatedAttr {
     // This is not free to compute, so we want to keep it around, rather than
     // compute it for every attribute.
-    depr_attrs: Vec<&'static (&'static str, AttributeType, AttributeGate)>,
+    depr_attrs: Vec<&'static (&'static str, AttributeType, AttributeTemplate, AttributeGate)>,
 }
 
 impl DeprecatedAttr {
@@ -781,7 +783,7 @@ impl LintPass for DeprecatedAttr {
 
 impl EarlyLintPass for DeprecatedAttr {
     fn check_attribute(&mut self, cx: &EarlyContext, attr: &ast::Attribute) {
-        for &&(n, _, ref g) in &self.depr_attrs {
+        for &&(n, _, _, ref g) in &self.depr_attrs {
             if attr.name() == n {
                 if let &AttributeGate::Gated(Stability::Deprecated(link, suggestion),
                                              ref name,
@@ -1123,6 +1125,10 @@ impl UnreachablePub {
                                                        "consider restricting its visibility",
                                                        replacement,
                                                        applicability);
+                if let Some(module) = private_enclosing_module(cx, id) {
+                    err.note(&format!("this {} is inside the private module `{}`",
+                                      what, module));
+                }
                 if exportable {
                     err.help("or consider exporting it for use by other crates");
                 }
@@ -1133,6 +1139,21 @@ impl UnreachablePub {
     }
 }
 
+/// The innermost module around `id` that isn't `pub`, which is what usually
+/// makes a `pub` item unreachable.
+fn private_enclosing_module(cx: &LateContext, id: ast::NodeId) -> Option<ast::Name> {
+    let hir = cx.tcx.hir();
+    let mut module = hir.get_module_parent_node(id);
+    while module != ast::CRATE_NODE_ID {
+        if let Node::Item(item) = hir.get(module) {
+            if !item.vis.node.is_pub() {
+                return Some(item.name);
+            }
+        }
+        module = hir.get_module_parent_node(module);
+    }
+    None
+}
 
 impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnreachablePub {
     fn check_item(&mut self, cx: &LateContext, item: &hir::Item) {
@@ -1315,9 +1336,31 @@ declare_lint! {
     "these bounds don't depend on an type parameters"
 }
 
+declare_lint! {
+    pub UNSATISFIABLE_BOUNDS,
+    Deny,
+    "trait bounds that don't depend on any type parameters and can never hold"
+}
+
 impl LintPass for TrivialConstraints {
     fn get_lints(&self) -> LintArray {
-        lint_array!(TRIVIAL_BOUNDS)
+        lint_array!(TRIVIAL_BOUNDS, UNSATISFIABLE_BOUNDS)
+    }
+}
+
+impl TrivialConstraints {
+    /// Asks the trait solver whether a global predicate could ever hold.
+    /// Without the `trivial_bounds` feature such a predicate is checked
+    /// directly by `wfcheck`, so this is only needed when it's enabled.
+    fn may_hold<'a, 'tcx>(cx: &LateContext<'a, 'tcx>, predicate: ty::Predicate<'tcx>) -> bool {
+        cx.tcx.infer_ctxt().enter(|infcx| {
+            let obligation = traits::Obligation::new(
+                traits::ObligationCause::dummy(),
+                ty::ParamEnv::empty(),
+                predicate,
+            );
+            infcx.predicate_may_hold(&obligation)
+        })
     }
 }
 
@@ -1350,13 +1393,28 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for TrivialConstraints {
                     Subtype(..) |
                     ConstEvaluatable(..) => continue,
                 };
-                if predicate.is_global() {
-                    cx.span_lint(
-                        TRIVIAL_BOUNDS,
-                        span,
-                        &format!("{} bound {} does not depend on any type \
-                                or lifetime parameters", predicate_kind_name, predicate),
-                    );
+                if !predicate.is_global() {
+                    continue;
+                }
+                cx.span_lint(
+                    TRIVIAL_BOUNDS,
+                    span,
+                    &format!("{} bound {} does not depend on any type \
+                            or lifetime parameters", predicate_kind_name, predicate),
+                );
+                if let Trait(..) = predicate {
+                    if !Self::may_hold(cx, predicate) {
+                        let mut err = cx.struct_span_lint(
+                            UNSATISFIABLE_BOUNDS,
+                            span,
+                            &format!("the trait bound `{}` can never be satisfied", predicate),
+                        );
+                        err.note("`#![feature(trivial_bounds)]` makes this bound assumed to hold \
+                                  inside the item instead of being an error");
+                        err.note("the item can't be used anywhere else, because the bound \
+                                  doesn't hold there");
+                        err.emit();
+                    }
                 }
             }
         }
@@ -1389,7 +1447,8 @@ impl LintPass for SoftLints {
             UNIONS_WITH_DROP_FIELDS,
             UNREACHABLE_PUB,
             TYPE_ALIAS_BOUNDS,
-            TRIVIAL_BOUNDS
+            TRIVIAL_BOUNDS,
+            UNSATISFIABLE_BOUNDS
         )
     }
 }
@@ -1556,52 +1615,44 @@ impl EarlyLintPass for KeywordIdents {
         self.check_tokens(cx, mac.node.tts.clone().into());
     }
     fn check_ident(&mut self, cx: &EarlyContext, ident: ast::Ident) {
-        let ident_str = &ident.as_str()[..];
-        let cur_edition = cx.sess.edition();
-        let is_raw_ident = |ident: ast::Ident| {
-            cx.sess.parse_sess.raw_identifier_spans.borrow().contains(&ident.span)
+        let keyword_edition = match ident.name.keyword_edition() {
+            Some(edition) => edition,
+            None => return,
         };
-        let next_edition = match cur_edition {
-            Edition::Edition2015 => {
-                match ident_str {
-                    "async" | "try" | "dyn" => Edition::Edition2018,
-                    // Only issue warnings for `await` if the `async_await`
-                    // feature isn't being used. Otherwise, users need
-                    // to keep using `await` for the macro exposed by std.
-                    "await" if !cx.sess.features_untracked().async_await => Edition::Edition2018,
-                    _ => return,
-                }
-            }
 
-            // There are no new keywords yet for the 2018 edition and beyond.
-            // However, `await` is a "false" keyword in the 2018 edition,
-            // and can only be used if the `async_await` feature is enabled.
-            // Otherwise, we emit an error.
-            _ => {
-                if "await" == ident_str
-                    && !cx.sess.features_untracked().async_await
-                    && !is_raw_ident(ident)
-                {
-                    let mut err = struct_span_err!(
-                        cx.sess,
-                        ident.span,
-                        E0721,
-                        "`await` is a keyword in the {} edition", cur_edition,
-                    );
-                    err.span_suggestion_with_applicability(
-                        ident.span,
-                        "you can use a raw identifier to stay compatible",
-                        "r#await".to_string(),
-                        Applicability::MachineApplicable,
-                    );
-                    err.emit();
-                }
-                return
-            },
-        };
+        // `await` is only reserved while the `async_await` feature isn't
+        // being used. Otherwise, users need to keep using `await` for the
+        // macro exposed by std.
+        let is_await = ident.name == "await";
+        if is_await && cx.sess.features_untracked().async_await {
+            return;
+        }
 
         // don't lint `r#foo`
-        if is_raw_ident(ident) {
+        if cx.sess.parse_sess.raw_identifier_spans.borrow().contains(&ident.span) {
+            return;
+        }
+
+        let cur_edition = cx.sess.edition();
+        if keyword_edition <= cur_edition {
+            // The keyword is already reserved in this edition, so the parser
+            // takes care of it. `await` is the exception: it's a "false"
+            // keyword in the 2018 edition that only gets rejected here.
+            if is_await {
+                let mut err = struct_span_err!(
+                    cx.sess,
+                    ident.span,
+                    E0721,
+                    "`await` is a keyword in the {} edition", cur_edition,
+                );
+                err.span_suggestion_with_applicability(
+                    ident.span,
+                    "you can use a raw identifier to stay compatible",
+                    "r#await".to_string(),
+                    Applicability::MachineApplicable,
+                );
+                err.emit();
+            }
             return;
         }
 
@@ -1610,7 +1661,7 @@ impl EarlyLintPass for KeywordIdents {
             ident.span,
             &format!("`{}` is a keyword in the {} edition",
                      ident.as_str(),
-                     next_edition),
+                     keyword_edition),
         );
         lint.span_suggestion_with_applicability(
             ident.span,
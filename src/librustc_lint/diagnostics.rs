@@ -8,6 +8,6 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-register_diagnostics! {
+register_pending_diagnostics! {
     E0721, // `await` keyword
 }
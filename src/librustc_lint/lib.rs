@@ -126,13 +126,12 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                                 DeprecatedAttr,
                                 );
 
-    late_lint_methods!(declare_combined_late_lint_pass, [BuiltinCombinedLateLintPass, [
+    late_lint_methods!(declare_combined_late_lint_pass, [BuiltinCombinedModuleLateLintPass, [
         HardwiredLints: HardwiredLints,
         WhileTrue: WhileTrue,
         ImproperCTypes: ImproperCTypes,
         VariantSizeDifferences: VariantSizeDifferences,
         BoxPointers: BoxPointers,
-        UnusedAttributes: UnusedAttributes,
         PathStatements: PathStatements,
         UnusedResults: UnusedResults,
         NonCamelCaseTypes: NonCamelCaseTypes,
@@ -142,20 +141,42 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
         UnsafeCode: UnsafeCode,
         UnusedAllocation: UnusedAllocation,
         MissingCopyImplementations: MissingCopyImplementations,
-        UnstableFeatures: UnstableFeatures,
         InvalidNoMangleItems: InvalidNoMangleItems,
         PluginAsLibrary: PluginAsLibrary,
         MutableTransmutes: MutableTransmutes,
         UnionsWithDropFields: UnionsWithDropFields,
         UnreachablePub: UnreachablePub,
-        UnnameableTestItems: UnnameableTestItems::new(),
         TypeAliasBounds: TypeAliasBounds,
-        UnusedBrokenConst: UnusedBrokenConst,
         TrivialConstraints: TrivialConstraints,
         TypeLimits: TypeLimits::new(),
+        LossyNumericCasts: LossyNumericCasts,
+        ExcessivePadding: ExcessivePadding,
+        ExplicitOutlivesRequirements: ExplicitOutlivesRequirements,
+    ]], ['tcx]);
+
+    store.register_late_mod_pass(sess, false, box || -> lint::LateLintPassObject {
+        box BuiltinCombinedModuleLateLintPass::new()
+    });
+
+    late_lint_methods!(declare_combined_late_lint_pass, [BuiltinCombinedLateLintPass, [
+        // Uses `attr::is_used`, which isn't tracked by the query system.
+        UnusedAttributes: UnusedAttributes,
+
+        // Needs to run after `UnusedAttributes`, as it marks all `feature`
+        // attributes as used.
+        UnstableFeatures: UnstableFeatures,
+
+        // Tracks state across modules.
+        UnnameableTestItems: UnnameableTestItems::new(),
+
+        // Tracks `#[doc(hidden)]` on the parents of the item being checked.
         MissingDoc: MissingDoc::new(),
+
+        // Collects the types implementing `Debug` across the whole crate.
         MissingDebugImplementations: MissingDebugImplementations::new(),
-        ExplicitOutlivesRequirements: ExplicitOutlivesRequirements,
+
+        // May depend on constants from other modules.
+        UnusedBrokenConst: UnusedBrokenConst,
     ]], ['tcx]);
 
     store.register_late_pass(sess, false, box BuiltinCombinedLateLintPass::new());
@@ -330,6 +351,11 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
             reference: "issue #52234 <https://github.com/rust-lang/rust/issues/52234>",
             edition: None,
         },
+        FutureIncompatibleInfo {
+            id: LintId::of(BORROWCK_MIGRATION),
+            reference: "issue #43234 <https://github.com/rust-lang/rust/issues/43234>",
+            edition: None,
+        },
         ]);
 
     // Register renamed and removed lints.
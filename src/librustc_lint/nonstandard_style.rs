@@ -241,8 +241,12 @@ impl LintPass for NonSnakeCase {
 }
 
 impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NonSnakeCase {
-    fn check_crate(&mut self, cx: &LateContext, cr: &hir::Crate) {
-        let attr_crate_name = attr::find_by_name(&cr.attrs, "crate_name")
+    fn check_mod(&mut self, cx: &LateContext, _: &hir::Mod, _: Span, id: ast::NodeId) {
+        if id != ast::CRATE_NODE_ID {
+            return;
+        }
+
+        let attr_crate_name = attr::find_by_name(cx.tcx.hir().krate_attrs(), "crate_name")
             .and_then(|at| at.value_str().map(|s| (at, s)));
         if let Some(ref name) = cx.tcx.sess.opts.crate_name {
             self.check_snake_case(cx, "crate", name, None);
@@ -23,7 +23,7 @@ use std::cmp;
 use std::{i8, i16, i32, i64, u8, u16, u32, u64, f32, f64};
 
 use syntax::{ast, attr};
-use syntax::errors::Applicability;
+use syntax::errors::{Applicability, DiagnosticBuilder};
 use rustc_target::spec::abi::Abi;
 use syntax::edition::Edition;
 use syntax_pos::Span;
@@ -863,11 +863,22 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for VariantSizeDifferences {
                         // We only warn if the largest variant is at least thrice as large as
                         // the second-largest.
                         if largest > slargest * 3 && slargest > 0 {
-                            cx.span_lint(VARIANT_SIZE_DIFFERENCES,
-                                            enum_definition.variants[largest_index].span,
-                                            &format!("enum variant is more than three times \
-                                                      larger ({} bytes) than the next largest",
-                                                     largest));
+                            let variant = &enum_definition.variants[largest_index];
+                            let mut err = cx.struct_span_lint(
+                                VARIANT_SIZE_DIFFERENCES,
+                                variant.span,
+                                &format!("enum variant is more than three times larger \
+                                          ({} bytes) than the next largest ({} byte{})",
+                                         largest,
+                                         slargest,
+                                         if slargest == 1 { "" } else { "s" }),
+                            );
+                            if let ty::Adt(adt, substs) = ty.sty {
+                                let variant_def = &adt.variants[VariantIdx::new(largest_index)];
+                                suggest_boxing_large_field(cx, &mut err, variant, variant_def,
+                                                           substs, largest);
+                            }
+                            err.emit();
                         }
                     }
                 }
@@ -879,3 +890,234 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for VariantSizeDifferences {
         }
     }
 }
+
+/// Suggests boxing the field that makes up most of an oversized enum variant,
+/// so that the variant only holds a pointer to it.
+fn suggest_boxing_large_field<'a, 'tcx>(cx: &LateContext<'a, 'tcx>,
+                                        err: &mut DiagnosticBuilder,
+                                        variant: &hir::Variant,
+                                        variant_def: &ty::VariantDef,
+                                        substs: &'tcx Substs<'tcx>,
+                                        variant_size: u64) {
+    let field_sizes = variant_def.fields.iter().map(|field| {
+        let field_ty = cx.tcx.erase_regions(&field.ty(cx.tcx, substs));
+        cx.layout_of(field_ty).map(|layout| layout.size.bytes()).unwrap_or(0)
+    });
+    let (index, field_size) = match field_sizes.enumerate().max_by_key(|&(_, size)| size) {
+        Some(largest) => largest,
+        None => return,
+    };
+
+    // Boxing only pays off if this one field is what makes the variant large.
+    let pointer_size = cx.tcx.data_layout.pointer_size.bytes();
+    if field_size * 2 <= variant_size || field_size <= pointer_size {
+        return;
+    }
+    let field = &variant.node.data.fields()[index];
+    if let Ok(snippet) = cx.sess().source_map().span_to_snippet(field.ty.span) {
+        err.span_suggestion_with_applicability(
+            field.ty.span,
+            &format!("consider boxing the large field ({} bytes) to reduce the size of the enum",
+                     field_size),
+            format!("Box<{}>", snippet),
+            Applicability::MaybeIncorrect,
+        );
+    }
+}
+
+declare_lint! {
+    EXCESSIVE_PADDING,
+    Allow,
+    "detects `repr(C)` structs that are larger than needed because of their field order"
+}
+
+pub struct ExcessivePadding;
+
+impl LintPass for ExcessivePadding {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(EXCESSIVE_PADDING)
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for ExcessivePadding {
+    fn check_item(&mut self, cx: &LateContext, it: &hir::Item) {
+        let variant_data = match it.node {
+            hir::ItemKind::Struct(ref variant_data, _) => variant_data,
+            _ => return,
+        };
+        let item_def_id = cx.tcx.hir().local_def_id(it.id);
+        let adt = cx.tcx.adt_def(item_def_id);
+        // Without `repr(C)` the compiler is free to reorder the fields itself,
+        // and a packed struct has no padding to begin with.
+        if !adt.repr.c() || adt.repr.packed() {
+            return;
+        }
+        let ty = cx.tcx.erase_regions(&cx.tcx.type_of(item_def_id));
+        let layout = match cx.layout_of(ty) {
+            Ok(layout) => layout,
+            // Generic structs don't have a layout of their own.
+            Err(_) => return,
+        };
+        let substs = match ty.sty {
+            ty::Adt(_, substs) => substs,
+            _ => return,
+        };
+
+        let mut fields = Vec::with_capacity(variant_data.fields().len());
+        for (field, field_def) in variant_data.fields().iter().zip(&adt.non_enum_variant().fields) {
+            let field_ty = cx.tcx.erase_regions(&field_def.ty(cx.tcx, substs));
+            match cx.layout_of(field_ty) {
+                Ok(field_layout) => {
+                    fields.push((field, field_layout.size.bytes(), field_layout.align.abi.bytes()))
+                }
+                Err(_) => return,
+            }
+        }
+
+        // Laying the fields out from the most to the least aligned leaves no
+        // padding between them; only the tail padding can remain.
+        fields.sort_by_key(|&(_, _, align)| cmp::Reverse(align));
+        let struct_align = layout.align.abi.bytes();
+        let round_up = |offset: u64, align: u64| (offset + align - 1) / align * align;
+        let reordered_size = round_up(fields.iter().fold(0, |offset, &(_, size, align)| {
+            round_up(offset, align) + size
+        }), struct_align);
+        let size = layout.size.bytes();
+        if reordered_size >= size {
+            return;
+        }
+
+        let mut err = cx.struct_span_lint(
+            EXCESSIVE_PADDING,
+            it.ident.span,
+            &format!("struct `{}` is {} bytes, {} of which are padding that reordering its \
+                      fields would remove",
+                     it.ident, size, size - reordered_size),
+        );
+        let order = fields.iter()
+            .map(|&(field, _, _)| format!("`{}`", field.ident))
+            .collect::<Vec<_>>()
+            .join(", ");
+        err.note(&format!("declaring the fields in the order {} would make it {} bytes",
+                          order, reordered_size));
+        err.note("this changes the layout that `repr(C)` guarantees, so only do it if nothing \
+                  depends on the field order");
+        err.emit();
+    }
+}
+
+declare_lint! {
+    LOSSY_NUMERIC_CASTS,
+    Allow,
+    "`as` casts between numeric types that may lose information"
+}
+
+declare_lint! {
+    LOSSLESS_NUMERIC_CASTS,
+    Allow,
+    "`as` casts between numeric types that can be written with `From`"
+}
+
+pub struct LossyNumericCasts;
+
+impl LintPass for LossyNumericCasts {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(LOSSY_NUMERIC_CASTS, LOSSLESS_NUMERIC_CASTS)
+    }
+}
+
+/// The smallest and largest number of bits a numeric type can have, and
+/// whether it is signed. Pointer-sized integers are at least 16 and at most 64
+/// bits wide, depending on the target.
+fn int_width_range(ty: Ty) -> Option<(usize, usize, bool)> {
+    match ty.sty {
+        ty::Int(t) => Some(t.bit_width().map_or((16, 64, true), |bits| (bits, bits, true))),
+        ty::Uint(t) => Some(t.bit_width().map_or((16, 64, false), |bits| (bits, bits, false))),
+        _ => None,
+    }
+}
+
+/// Whether every value of `from` can be represented as a `to` on all targets.
+///
+/// Pointer-sized integers are never a lossless source, since the standard library only converts
+/// `u8` and `u16` into `usize` and `u8`, `i8` and `i16` into `isize` with `From`.
+fn is_lossless_cast(from: Ty, to: Ty) -> bool {
+    match (&from.sty, &to.sty) {
+        (&ty::Int(ast::IntTy::Isize), _) | (&ty::Uint(ast::UintTy::Usize), _) => false,
+        (&ty::Float(ast::FloatTy::F32), &ty::Float(_)) => true,
+        (&ty::Float(_), _) => false,
+        (_, &ty::Float(float_ty)) => {
+            let mantissa_bits = match float_ty {
+                ast::FloatTy::F32 => 24,
+                ast::FloatTy::F64 => 53,
+            };
+            match int_width_range(from) {
+                Some((_, max_bits, signed)) => {
+                    let value_bits = if signed { max_bits - 1 } else { max_bits };
+                    value_bits <= mantissa_bits
+                }
+                None => false,
+            }
+        }
+        _ => match (int_width_range(from), int_width_range(to)) {
+            (Some((_, from_max, from_signed)), Some((to_min, _, to_signed))) => {
+                match (from_signed, to_signed) {
+                    (true, false) => false,
+                    (false, true) => from_max < to_min,
+                    _ => from_max <= to_min,
+                }
+            }
+            _ => false,
+        },
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for LossyNumericCasts {
+    fn check_expr(&mut self, cx: &LateContext<'a, 'tcx>, e: &'tcx hir::Expr) {
+        let expr = match e.node {
+            hir::ExprKind::Cast(ref expr, _) => expr,
+            _ => return,
+        };
+        let from = cx.tables.expr_ty(expr);
+        let to = cx.tables.expr_ty(e);
+        // Casts to the same type are left to `trivial_numeric_casts`.
+        if !from.is_numeric() || !to.is_numeric() || from == to {
+            return;
+        }
+
+        if is_lossless_cast(from, to) {
+            // `is_lossless_cast` only accepts pairs of types with a `From` impl.
+            let mut err = cx.struct_span_lint(
+                LOSSLESS_NUMERIC_CASTS,
+                e.span,
+                &format!("numeric cast that can't lose information: `{}` as `{}`", from, to),
+            );
+            if let Ok(snippet) = cx.sess().source_map().span_to_snippet(expr.span) {
+                err.span_suggestion_with_applicability(
+                    e.span,
+                    "use `From` instead",
+                    format!("{}::from({})", to, snippet),
+                    Applicability::MachineApplicable,
+                );
+            }
+            err.emit();
+            return;
+        }
+
+        let mut err = cx.struct_span_lint(LOSSY_NUMERIC_CASTS,
+                                          e.span,
+                                          &format!("lossy numeric cast: `{}` as `{}`", from, to));
+        err.note(&format!("not every `{}` value can be represented as `{}`", from, to));
+        if from.is_integral() && to.is_integral() {
+            if let Ok(snippet) = cx.sess().source_map().span_to_snippet(expr.span) {
+                err.span_suggestion_with_applicability(
+                    e.span,
+                    "use `TryFrom` to handle the values that don't fit",
+                    format!("{}::try_from({})", to, snippet),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+        }
+        err.emit();
+    }
+}
@@ -12,6 +12,7 @@ use rustc::hir::def::Def;
 use rustc::hir::def_id::DefId;
 use rustc::ty;
 use rustc::ty::adjustment;
+use rustc::lint::builtin::{UNUSED_ATTRIBUTES, UNUSED_PARENS};
 use lint::{LateContext, EarlyContext, LintContext, LintArray};
 use lint::{LintPass, EarlyLintPass, LateLintPass};
 
@@ -59,10 +60,41 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedResults {
             return;
         }
 
+        let mut fn_warned = false;
+        let mut op_warned = false;
+        let maybe_def = match expr.node {
+            hir::ExprKind::Call(ref callee, _) => {
+                match callee.node {
+                    hir::ExprKind::Path(ref qpath) => {
+                        let def = cx.tables.qpath_def(qpath, callee.hir_id);
+                        match def {
+                            Def::Fn(_) | Def::Method(_) => Some(def),
+                            // `Def::Local` if it was a closure, for which we
+                            // do not currently support must-use linting
+                            _ => None
+                        }
+                    },
+                    _ => None
+                }
+            },
+            hir::ExprKind::MethodCall(..) => {
+                cx.tables.type_dependent_defs().get(expr.hir_id).cloned()
+            },
+            _ => None
+        };
+        if let Some(def) = maybe_def {
+            let def_id = def.def_id();
+            fn_warned = check_must_use(cx, def_id, s.span, "return value of ", "");
+        }
+
         let t = cx.tables.expr_ty(&expr);
         let type_permits_lack_of_use = if t.is_unit()
             || cx.tcx.is_ty_uninhabited_from(cx.tcx.hir().get_module_parent(expr.id), t) {
             true
+        } else if fn_warned {
+            // The function's `#[must_use]` is more specific than the one on
+            // the type it returns, so only warn about the former.
+            false
         } else {
             match t.sty {
                 ty::Adt(def, _) => check_must_use(cx, def.did, s.span, "", ""),
@@ -95,32 +127,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedResults {
             }
         };
 
-        let mut fn_warned = false;
-        let mut op_warned = false;
-        let maybe_def = match expr.node {
-            hir::ExprKind::Call(ref callee, _) => {
-                match callee.node {
-                    hir::ExprKind::Path(ref qpath) => {
-                        let def = cx.tables.qpath_def(qpath, callee.hir_id);
-                        match def {
-                            Def::Fn(_) | Def::Method(_) => Some(def),
-                            // `Def::Local` if it was a closure, for which we
-                            // do not currently support must-use linting
-                            _ => None
-                        }
-                    },
-                    _ => None
-                }
-            },
-            hir::ExprKind::MethodCall(..) => {
-                cx.tables.type_dependent_defs().get(expr.hir_id).cloned()
-            },
-            _ => None
-        };
-        if let Some(def) = maybe_def {
-            let def_id = def.def_id();
-            fn_warned = check_must_use(cx, def_id, s.span, "return value of ", "");
-        } else if type_permits_lack_of_use {
+        if maybe_def.is_none() && type_permits_lack_of_use {
             // We don't warn about unused unit or uninhabited types.
             // (See https://github.com/rust-lang/rust/issues/43806 for details.)
             return;
@@ -223,18 +230,12 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for PathStatements {
     }
 }
 
-declare_lint! {
-    pub UNUSED_ATTRIBUTES,
-    Warn,
-    "detects attributes that were not used by the compiler"
-}
-
 #[derive(Copy, Clone)]
 pub struct UnusedAttributes;
 
 impl LintPass for UnusedAttributes {
     fn get_lints(&self) -> LintArray {
-        lint_array!(UNUSED_ATTRIBUTES)
+        lint_array!()
     }
 }
 
@@ -242,7 +243,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedAttributes {
     fn check_attribute(&mut self, cx: &LateContext, attr: &ast::Attribute) {
         debug!("checking attribute: {:?}", attr);
         // Note that check_name() marks the attribute as used if it matches.
-        for &(ref name, ty, _) in BUILTIN_ATTRIBUTES {
+        for &(ref name, ty, ..) in BUILTIN_ATTRIBUTES {
             match ty {
                 AttributeType::Whitelisted if attr.check_name(name) => {
                     debug!("{:?} is Whitelisted", name);
@@ -266,7 +267,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedAttributes {
             cx.span_lint(UNUSED_ATTRIBUTES, attr.span, "unused attribute");
             // Is it a builtin attribute that must be used at the crate level?
             let known_crate = BUILTIN_ATTRIBUTES.iter()
-                .find(|&&(builtin, ty, _)| name == builtin && ty == AttributeType::CrateLevel)
+                .find(|&&(builtin, ty, ..)| name == builtin && ty == AttributeType::CrateLevel)
                 .is_some();
 
             // Has a plugin registered this attribute as one that must be used at
@@ -290,12 +291,6 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedAttributes {
     }
 }
 
-declare_lint! {
-    pub(super) UNUSED_PARENS,
-    Warn,
-    "`if`, `match`, `while` and `return` do not need parentheses"
-}
-
 #[derive(Copy, Clone)]
 pub struct UnusedParens;
 
@@ -376,7 +371,7 @@ impl UnusedParens {
 
 impl LintPass for UnusedParens {
     fn get_lints(&self) -> LintArray {
-        lint_array!(UNUSED_PARENS)
+        lint_array!()
     }
 }
 
@@ -0,0 +1,78 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Procedural macros used by the compiler itself.
+//!
+//! `#[derive(SessionDiagnostic)]` turns a struct into a diagnostic that can be emitted with
+//! `Session::emit_err`. The message, error code, labels, notes and suggestions are declared
+//! with attributes instead of being built by hand with `struct_span_err!`:
+//!
+//! ```ignore (requires the rustc crates)
+//! #[derive(SessionDiagnostic)]
+//! #[code = "E0092"]
+//! #[slug = "typeck-unrecognized-atomic-operation"]
+//! #[message = "unrecognized atomic operation function: `{op}`"]
+//! pub struct UnrecognizedAtomicOperation<'a> {
+//!     #[primary_span]
+//!     #[label = "unrecognized atomic operation"]
+//!     pub span: Span,
+//!     pub op: &'a str,
+//! }
+//!
+//! tcx.sess.emit_err(UnrecognizedAtomicOperation { span: it.span, op });
+//! ```
+//!
+//! Messages may refer to fields of the struct by name (`{op}`), which are formatted with their
+//! `Display` implementation.
+//!
+//! With a `#[slug]`, the messages are translated with the message catalog of the session (see
+//! `-Z translate-lang`). The main message is the value of the slug's message, and the others
+//! are its attributes: `.note` and `.help` for the struct (`.note-2`, ... if repeated) and
+//! `.<field>-<kind>` for fields, e.g. `.span-label` above. Fields used by any of the English
+//! messages are passed to the translations as arguments, `{ $op }`. The English messages are
+//! used when there is no translation.
+//!
+//! Attributes on the struct:
+//!
+//! - `#[message = "..."]` (required): the main message.
+//! - `#[code = "E0000"]`: the error code.
+//! - `#[slug = "..."]`: the slug of the message in translations.
+//! - `#[note = "..."]`, `#[help = "..."]`: a note or help without a span.
+//!
+//! Attributes on `Span` or `Option<Span>` fields (nothing is added for a `None`):
+//!
+//! - `#[primary_span]`: the primary span of the diagnostic.
+//! - `#[label = "..."]`: a label on the span.
+//! - `#[note = "..."]`, `#[help = "..."]`: a note or help pointing at the span.
+//! - `#[suggestion(message = "...", code = "...", applicability = "MachineApplicable")]`: a
+//!   suggestion replacing the span with `code`; the applicability defaults to `Unspecified`.
+
+#![feature(nll)]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use proc_macro::TokenStream;
+
+mod session_diagnostic;
+
+#[proc_macro_derive(SessionDiagnostic,
+                    attributes(code, slug, message, primary_span, label, note, help,
+                               suggestion))]
+pub fn session_diagnostic_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    session_diagnostic::expand(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -0,0 +1,293 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use proc_macro2::TokenStream;
+use syn::{self, Attribute, Data, DeriveInput, Field, Fields, Ident, Lit, LitStr, Meta};
+use syn::{NestedMeta, Type};
+
+/// Expands `#[derive(SessionDiagnostic)]` into an implementation of
+/// `rustc::session::SessionDiagnostic`.
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`SessionDiagnostic` can only be derived for structs with named fields",
+            )),
+        },
+        _ => return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`SessionDiagnostic` can only be derived for structs",
+        )),
+    };
+
+    let mut messages = Messages { slug: None, args: vec![] };
+    for attr in &input.attrs {
+        if let Some(meta) = parse_meta(attr)? {
+            if meta.name() == "slug" {
+                messages.slug = Some(string_value(&meta)?);
+            }
+        }
+    }
+
+    let mut message = None;
+    let mut code = None;
+    let mut subdiagnostics = vec![];
+    let mut subdiagnostic_kinds = vec![];
+    for attr in &input.attrs {
+        let meta = match parse_meta(attr)? {
+            Some(meta) => meta,
+            None => continue,
+        };
+        let kind = meta.name().to_string();
+        match &*kind {
+            "message" => message = Some(messages.build(&string_value(&meta)?, None)),
+            "code" => {
+                let code_str = string_value(&meta)?;
+                let code_ident = Ident::new(&code_str.value(), code_str.span());
+                code = Some(quote! {
+                    __diagnostic_used!(#code_ident);
+                    diag.code(::rustc::session::DiagnosticId::Error(#code_str.to_owned()));
+                });
+            }
+            "note" | "help" => {
+                // Repeated notes are translated with `.note`, `.note-2`, ...
+                let count = subdiagnostic_kinds.iter().filter(|id| **id == kind).count();
+                let id = match count {
+                    0 => kind.clone(),
+                    _ => format!("{}-{}", kind, count + 1),
+                };
+                subdiagnostic_kinds.push(kind.clone());
+                let msg = messages.build(&string_value(&meta)?, Some(id));
+                let method = Ident::new(&kind, proc_macro2::Span::call_site());
+                subdiagnostics.push(quote! { diag.#method(&#msg); });
+            }
+            _ => {}
+        }
+    }
+    let message = match message {
+        Some(message) => message,
+        None => return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`#[message = \"...\"]` is required to derive `SessionDiagnostic`",
+        )),
+    };
+
+    let mut spans = vec![];
+    for field in fields {
+        for attr in &field.attrs {
+            let meta = match parse_meta(attr)? {
+                Some(meta) => meta,
+                None => continue,
+            };
+            let kind = meta.name().to_string();
+            // Messages on fields are translated with the `.<field>-<kind>` attribute.
+            let id = format!("{}-{}", field.ident.as_ref().unwrap(), kind);
+            let call = match &*kind {
+                "primary_span" => {
+                    if let Meta::Word(_) = meta {
+                        quote! { diag.set_span(span); }
+                    } else {
+                        return Err(syn::Error::new_spanned(meta, "expected `#[primary_span]`"));
+                    }
+                }
+                "label" => {
+                    let msg = messages.build(&string_value(&meta)?, Some(id));
+                    quote! { diag.span_label(span, #msg); }
+                }
+                "note" => {
+                    let msg = messages.build(&string_value(&meta)?, Some(id));
+                    quote! { diag.span_note(span, &#msg); }
+                }
+                "help" => {
+                    let msg = messages.build(&string_value(&meta)?, Some(id));
+                    quote! { diag.span_help(span, &#msg); }
+                }
+                "suggestion" => suggestion(&meta, &mut messages, id)?,
+                _ => continue,
+            };
+            spans.push(with_span(field, call));
+        }
+    }
+    let args = messages.args();
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, parse_quote!('__session));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::rustc::session::SessionDiagnostic<'__session>
+            for #name #ty_generics #where_clause
+        {
+            fn into_diagnostic(self, sess: &'__session ::rustc::session::Session)
+                               -> ::rustc::session::DiagnosticBuilder<'__session> {
+                #args
+                let mut diag = sess.struct_err(&#message);
+                #code
+                #(#spans)*
+                #(#subdiagnostics)*
+                diag
+            }
+        }
+    })
+}
+
+/// Parses an attribute, returning `None` for attributes that aren't ours (doc comments,
+/// `#[derive]`, ...).
+fn parse_meta(attr: &Attribute) -> syn::Result<Option<Meta>> {
+    let ours = [
+        "slug", "message", "code", "primary_span", "label", "note", "help", "suggestion",
+    ];
+    let is_ours = attr.path.segments.len() == 1 &&
+                  ours.iter().any(|name| attr.path.segments[0].ident == name);
+    if !is_ours {
+        return Ok(None);
+    }
+    attr.parse_meta().map(Some)
+}
+
+/// Returns the string of a `#[name = "..."]` attribute.
+fn string_value(meta: &Meta) -> syn::Result<LitStr> {
+    if let Meta::NameValue(ref name_value) = *meta {
+        if let Lit::Str(ref s) = name_value.lit {
+            return Ok(s.clone());
+        }
+    }
+    Err(syn::Error::new_spanned(meta, format!("expected `#[{} = \"...\"]`", meta.name())))
+}
+
+/// Generates the code for `#[suggestion(message = "...", code = "...", applicability = "...")]`.
+fn suggestion(meta: &Meta, messages: &mut Messages, id: String) -> syn::Result<TokenStream> {
+    let list = match *meta {
+        Meta::List(ref list) => list,
+        _ => return Err(syn::Error::new_spanned(
+            meta,
+            "expected `#[suggestion(message = \"...\", code = \"...\")]`",
+        )),
+    };
+    let mut message = None;
+    let mut code = None;
+    let mut applicability = Ident::new("Unspecified", proc_macro2::Span::call_site());
+    for nested in &list.nested {
+        let nested = match *nested {
+            NestedMeta::Meta(ref nested) => nested,
+            NestedMeta::Literal(ref lit) => {
+                return Err(syn::Error::new_spanned(lit, "expected `name = \"...\"`"));
+            }
+        };
+        let value = string_value(nested)?;
+        match &*nested.name().to_string() {
+            "message" => message = Some(messages.build(&value, Some(id.clone()))),
+            // The replacement is code, it isn't translated.
+            "code" => code = Some(format_message(&value, &mut messages.args)),
+            "applicability" => applicability = Ident::new(&value.value(), value.span()),
+            _ => return Err(syn::Error::new_spanned(nested, "unknown suggestion argument")),
+        }
+    }
+    match (message, code) {
+        (Some(message), Some(code)) => Ok(quote! {
+            diag.span_suggestion_with_applicability(
+                span,
+                &#message,
+                #code,
+                ::rustc::session::Applicability::#applicability,
+            );
+        }),
+        _ => Err(syn::Error::new_spanned(meta, "suggestions need both a `message` and a `code`")),
+    }
+}
+
+/// Runs `call` with `span` bound to the value of `field`, if it has one.
+fn with_span(field: &Field, call: TokenStream) -> TokenStream {
+    let ident = &field.ident;
+    let is_option = match field.ty {
+        Type::Path(ref ty) => {
+            ty.path.segments.last().map_or(false, |segment| segment.value().ident == "Option")
+        }
+        _ => false,
+    };
+    if is_option {
+        quote! { if let Some(span) = self.#ident { #call } }
+    } else {
+        quote! { { let span = self.#ident; #call } }
+    }
+}
+
+/// Builds the messages of a diagnostic, translated if the struct has a `#[slug]`.
+struct Messages {
+    slug: Option<LitStr>,
+    /// The fields used by the messages.
+    args: Vec<String>,
+}
+
+impl Messages {
+    /// Returns an expression building `message`. If the diagnostic has a slug, it's translated
+    /// with the value of the slug's message, or with its attribute `attr`.
+    fn build(&mut self, message: &LitStr, attr: Option<String>) -> TokenStream {
+        let english = format_message(message, &mut self.args);
+        match self.slug {
+            Some(ref slug) => {
+                let attr = match attr {
+                    Some(attr) => quote! { Some(#attr) },
+                    None => quote! { None },
+                };
+                quote! { sess.diagnostic().translate(#slug, #attr, args, || #english) }
+            }
+            None => english,
+        }
+    }
+
+    /// Returns the declaration of the `args` passed to the translations.
+    fn args(&self) -> TokenStream {
+        if self.slug.is_none() {
+            return quote! {};
+        }
+        let names = &self.args;
+        let idents = names.iter().map(|name| Ident::new(name, proc_macro2::Span::call_site()));
+        quote! {
+            let args: &[(&str, String)] = &[#((#names, self.#idents.to_string())),*];
+        }
+    }
+}
+
+/// Turns a message into a `format!` call, passing the fields named in `{...}` placeholders.
+/// The names of these fields are added to `used`.
+fn format_message(message: &LitStr, used: &mut Vec<String>) -> TokenStream {
+    let value = message.value();
+    let mut names: Vec<&str> = vec![];
+    let mut rest = &value[..];
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        if rest.starts_with('{') {
+            // An escaped `{{`.
+            rest = &rest[1..];
+            continue;
+        }
+        let end = rest.find(|c: char| c == '}' || c == ':').unwrap_or(rest.len());
+        let name = rest[..end].trim();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &rest[end..];
+    }
+    for name in &names {
+        if !used.iter().any(|used| used == name) {
+            used.push(name.to_string());
+        }
+    }
+    let args = names.iter().map(|name| {
+        let ident = Ident::new(name, message.span());
+        quote! { #ident = self.#ident }
+    });
+    quote! { format!(#message #(, #args)*) }
+}
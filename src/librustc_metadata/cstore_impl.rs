@@ -159,7 +159,9 @@ provide! { <'tcx> tcx, def_id, other, cdata,
     // This is only used by rustdoc anyway, which shouldn't have
     // incremental recompilation ever enabled.
     fn_arg_names => { cdata.get_fn_arg_names(def_id.index) }
+    disjoint_fields => { cdata.get_disjoint_fields(def_id.index).map(Lrc::new) }
     rendered_const => { cdata.get_rendered_const(def_id.index) }
+    extern_const_value => { cdata.get_const_value(tcx, def_id.index) }
     impl_parent => { cdata.get_parent_impl(def_id.index) }
     trait_of_item => { cdata.get_trait_of_item(def_id.index) }
     const_is_rvalue_promotable_to_static => {
@@ -408,6 +410,14 @@ impl cstore::CStore {
         r
     }
 
+    /// Whether the crate is named by the local crate (through `extern crate`
+    /// or an extern path), as opposed to being loaded only as a dependency.
+    pub fn is_direct_dependency_untracked(&self, cnum: CrateNum) -> bool {
+        let data = self.get_crate_data(cnum);
+        let r = data.extern_crate.lock().map_or(false, |extern_crate| extern_crate.direct);
+        r
+    }
+
     pub fn crate_edition_untracked(&self, cnum: CrateNum) -> Edition {
         self.get_crate_data(cnum).root.edition
     }
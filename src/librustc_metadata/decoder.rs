@@ -842,7 +842,7 @@ impl<'a, 'tcx> CrateMetadata {
     pub fn const_is_rvalue_promotable_to_static(&self, id: DefIndex) -> bool {
         match self.entry(id).kind {
             EntryKind::AssociatedConst(_, data, _) |
-            EntryKind::Const(data, _) => data.ast_promotable,
+            EntryKind::Const(data, ..) => data.ast_promotable,
             _ => bug!(),
         }
     }
@@ -862,9 +862,21 @@ impl<'a, 'tcx> CrateMetadata {
         }
     }
 
+    /// The value of a `const` item as evaluated by the crate defining it, if evaluation
+    /// succeeded there.
+    pub fn get_const_value(&self,
+                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           id: DefIndex)
+                           -> Option<interpret::ConstValue<'tcx>> {
+        match self.entry(id).kind {
+            EntryKind::Const(_, _, value) => value.map(|value| value.decode((self, tcx))),
+            _ => None,
+        }
+    }
+
     pub fn mir_const_qualif(&self, id: DefIndex) -> u8 {
         match self.entry(id).kind {
-            EntryKind::Const(qualif, _) |
+            EntryKind::Const(qualif, ..) |
             EntryKind::AssociatedConst(AssociatedContainer::ImplDefault, qualif, _) |
             EntryKind::AssociatedConst(AssociatedContainer::ImplFinal, qualif, _) => {
                 qualif.mir
@@ -1100,7 +1112,7 @@ impl<'a, 'tcx> CrateMetadata {
 
     pub fn get_rendered_const(&self, id: DefIndex) -> String {
         match self.entry(id).kind {
-            EntryKind::Const(_, data) |
+            EntryKind::Const(_, data, _) |
             EntryKind::AssociatedConst(_, _, data) => data.decode(self).0,
             _ => bug!(),
         }
@@ -1114,6 +1126,20 @@ impl<'a, 'tcx> CrateMetadata {
         }
     }
 
+    pub fn get_disjoint_fields(&self, id: DefIndex) -> Option<Vec<mir::Field>> {
+        match self.entry(id).kind {
+            EntryKind::Method(data) => {
+                let fields: Vec<_> = data.decode(self).disjoint_fields.decode(self).collect();
+                if fields.is_empty() {
+                    None
+                } else {
+                    Some(fields)
+                }
+            }
+            _ => None,
+        }
+    }
+
     crate fn is_const_fn_raw(&self, id: DefIndex) -> bool {
         let constness = match self.entry(id).kind {
             EntryKind::Method(data) => data.decode(self).fn_data.constness,
@@ -96,7 +96,7 @@ well, and you link to them the same way.
 
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
     E0456, // plugin `..` is not available for triple `..`
     E0457, // plugin `..` only found in rlib format, but must be available...
     E0514, // metadata version mismatch
@@ -29,7 +29,7 @@ use rustc::ty::{self, Ty, TyCtxt, ReprOptions, SymbolName};
 use rustc::ty::codec::{self as ty_codec, TyEncoder};
 use rustc::ty::layout::VariantIdx;
 
-use rustc::session::config::{self, CrateType};
+use rustc::session::config::{self, AlwaysEncodeMir, CrateType};
 use rustc::util::nodemap::FxHashMap;
 
 use rustc_data_structures::stable_hasher::StableHasher;
@@ -841,6 +841,7 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                     fn_data,
                     container,
                     has_self: trait_item.method_has_self_argument,
+                    disjoint_fields: LazySeq::empty(),
                 }))
             }
             ty::AssociatedKind::Type => EntryKind::AssociatedType(container),
@@ -890,6 +891,38 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
         !self.tcx.sess.opts.output_types.should_codegen()
     }
 
+    /// Whether `def_id` is a non-generic function whose optimized MIR is small
+    /// enough for `-Z always-encode-mir=<size>`. Such functions are not
+    /// `#[inline]`, so the items they use are not necessarily exported; to stay
+    /// linkable from other crates the MIR must not call anything, run drop glue,
+    /// touch statics or contain constants pointing into memory.
+    fn is_small_leaf_fn(&self, def_id: DefId) -> bool {
+        let threshold = match self.tcx.sess.opts.debugging_opts.always_encode_mir {
+            AlwaysEncodeMir::Small(threshold) => threshold,
+            AlwaysEncodeMir::All | AlwaysEncodeMir::Disabled => return false,
+        };
+        if self.metadata_output_only() ||
+           self.tcx.generics_of(def_id).requires_monomorphization(self.tcx) ||
+           self.tcx.codegen_fn_attrs(def_id).inline == attr::InlineAttr::Never {
+            return false;
+        }
+
+        let mir = self.tcx.optimized_mir(def_id);
+        if !mir.promoted.iter().all(is_leaf_mir) || !is_leaf_mir(mir) {
+            return false;
+        }
+        let size: usize = mir.basic_blocks().iter().map(|data| {
+            let statements = data.statements.iter().filter(|statement| match statement.kind {
+                mir::StatementKind::StorageLive(_) |
+                mir::StatementKind::StorageDead(_) |
+                mir::StatementKind::Nop => false,
+                _ => true,
+            }).count();
+            statements + 1
+        }).sum();
+        size <= threshold
+    }
+
     fn const_qualif(&self, mir: u8, body_id: hir::BodyId) -> ConstQualif {
         let body_owner_def_id = self.tcx.hir().body_owner_def_id(body_id);
         let ast_promotable = self.tcx.const_is_rvalue_promotable_to_static(body_owner_def_id);
@@ -934,10 +967,12 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                 } else {
                     bug!()
                 };
+                let disjoint_fields = tcx.disjoint_fields(def_id).unwrap_or_default();
                 EntryKind::Method(self.lazy(&MethodData {
                     fn_data,
                     container,
                     has_self: impl_item.method_has_self_argument,
+                    disjoint_fields: self.lazy_seq_ref(disjoint_fields.iter()),
                 }))
             }
             ty::AssociatedKind::Existential => EntryKind::AssociatedExistential(container),
@@ -953,8 +988,10 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                                         tcx.codegen_fn_attrs(def_id).requests_inline()) &&
                                         !self.metadata_output_only();
                     let is_const_fn = sig.header.constness == hir::Constness::Const;
-                    let always_encode_mir = self.tcx.sess.opts.debugging_opts.always_encode_mir;
-                    needs_inline || is_const_fn || always_encode_mir
+                    let always_encode_mir =
+                        self.tcx.sess.opts.debugging_opts.always_encode_mir == AlwaysEncodeMir::All;
+                    needs_inline || is_const_fn || always_encode_mir ||
+                        self.is_small_leaf_fn(def_id)
                 },
                 hir::ImplItemKind::Existential(..) |
                 hir::ImplItemKind::Type(..) => false,
@@ -1035,6 +1072,21 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
         self.tcx.lookup_deprecation(def_id).map(|depr| self.lazy(&depr))
     }
 
+    /// Evaluated values of `const` items are exported, so downstream crates do not have to
+    /// evaluate them again.
+    fn encode_const_value(&mut self, def_id: DefId) -> Option<Lazy<interpret::ConstValue<'tcx>>> {
+        let tcx = self.tcx;
+        let cid = interpret::GlobalId {
+            instance: ty::Instance::mono(tcx, def_id),
+            promoted: None,
+        };
+        match tcx.const_eval(ty::ParamEnv::reveal_all().and(cid)) {
+            Ok(value) => Some(self.lazy(&value.val)),
+            // Errors have been reported when checking this crate
+            Err(_) => None,
+        }
+    }
+
     fn encode_rendered_const_for_body(&mut self, body_id: hir::BodyId) -> Lazy<RenderedConst> {
         let body = self.tcx.hir().body(body_id);
         let rendered = hir::print::to_string(self.tcx.hir(), |s| s.print_expr(&body.value));
@@ -1054,7 +1106,8 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                 let mir = tcx.at(item.span).mir_const_qualif(def_id).0;
                 EntryKind::Const(
                     self.const_qualif(mir, body_id),
-                    self.encode_rendered_const_for_body(body_id)
+                    self.encode_rendered_const_for_body(body_id),
+                    self.encode_const_value(def_id),
                 )
             }
             hir::ItemKind::Fn(_, header, .., body) => {
@@ -1264,10 +1317,12 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                         (generics.requires_monomorphization(tcx) ||
                          tcx.codegen_fn_attrs(def_id).requests_inline()) &&
                             !self.metadata_output_only();
-                    let always_encode_mir = self.tcx.sess.opts.debugging_opts.always_encode_mir;
+                    let always_encode_mir =
+                        self.tcx.sess.opts.debugging_opts.always_encode_mir == AlwaysEncodeMir::All;
                     if needs_inline
                         || header.constness == hir::Constness::Const
                         || always_encode_mir
+                        || self.is_small_leaf_fn(def_id)
                     {
                         self.encode_optimized_mir(def_id)
                     } else {
@@ -1388,7 +1443,7 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
         let mir = tcx.mir_const_qualif(def_id).0;
 
         Entry {
-            kind: EntryKind::Const(self.const_qualif(mir, body_id), const_data),
+            kind: EntryKind::Const(self.const_qualif(mir, body_id), const_data, None),
             visibility: self.lazy(&ty::Visibility::Public),
             span: self.lazy(&tcx.def_span(def_id)),
             attributes: LazySeq::empty(),
@@ -1862,6 +1917,57 @@ pub fn encode_metadata<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>)
     EncodedMetadata { raw_data: result }
 }
 
+/// Whether `mir` can be inlined into another crate without referring to any
+/// item that is not exported. See `IsolatedEncoder::is_small_leaf_fn`.
+fn is_leaf_mir(mir: &mir::Mir<'_>) -> bool {
+    use rustc::mir::interpret::{ConstValue, Scalar};
+    use rustc::mir::visit::{PlaceContext, Visitor};
+
+    struct LeafVisitor {
+        is_leaf: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for LeafVisitor {
+        fn visit_terminator_kind(&mut self,
+                                 block: mir::BasicBlock,
+                                 kind: &mir::TerminatorKind<'tcx>,
+                                 location: mir::Location) {
+            match *kind {
+                mir::TerminatorKind::Call { .. } |
+                mir::TerminatorKind::Drop { .. } |
+                mir::TerminatorKind::DropAndReplace { .. } |
+                mir::TerminatorKind::Yield { .. } |
+                mir::TerminatorKind::GeneratorDrop => self.is_leaf = false,
+                _ => self.super_terminator_kind(block, kind, location),
+            }
+        }
+
+        fn visit_static(&mut self,
+                        _static: &mir::Static<'tcx>,
+                        _context: PlaceContext<'tcx>,
+                        _location: mir::Location) {
+            self.is_leaf = false;
+        }
+
+        fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, location: mir::Location) {
+            match constant.literal.ty.sty {
+                ty::FnDef(..) | ty::Closure(..) | ty::Generator(..) => self.is_leaf = false,
+                _ => {}
+            }
+            match constant.literal.val {
+                ConstValue::Scalar(Scalar::Bits { .. }) => {}
+                ConstValue::ScalarPair(Scalar::Bits { .. }, Scalar::Bits { .. }) => {}
+                _ => self.is_leaf = false,
+            }
+            self.super_constant(constant, location);
+        }
+    }
+
+    let mut visitor = LeafVisitor { is_leaf: true };
+    visitor.visit_mir(mir);
+    visitor.is_leaf
+}
+
 pub fn get_repr_options<'a, 'tcx, 'gcx>(tcx: &TyCtxt<'a, 'tcx, 'gcx>, did: DefId) -> ReprOptions {
     let ty = tcx.type_of(did);
     match ty.sty {
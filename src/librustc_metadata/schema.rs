@@ -16,7 +16,7 @@ use rustc::hir::def_id::{DefIndex, DefId, CrateNum};
 use rustc::ich::StableHashingContext;
 use rustc::middle::cstore::{DepKind, LinkagePreference, NativeLibrary, ForeignModule};
 use rustc::middle::lang_items;
-use rustc::mir;
+use rustc::mir::{self, interpret};
 use rustc::session::CrateDisambiguator;
 use rustc::ty::{self, Ty, ReprOptions};
 use rustc_target::spec::{PanicStrategy, TargetTriple};
@@ -42,7 +42,7 @@ pub fn rustc_version() -> String {
 /// Metadata encoding version.
 /// N.B., increment this if you change the format of metadata such that
 /// the rustc version can't be found to compare with `rustc_version()`.
-pub const METADATA_VERSION: u8 = 4;
+pub const METADATA_VERSION: u8 = 5;
 
 /// Metadata header which includes `METADATA_VERSION`.
 /// To get older versions of rustc to ignore this metadata,
@@ -299,7 +299,7 @@ impl_stable_hash_for!(struct Entry<'tcx> {
 
 #[derive(Copy, Clone, RustcEncodable, RustcDecodable)]
 pub enum EntryKind<'tcx> {
-    Const(ConstQualif, Lazy<RenderedConst>),
+    Const(ConstQualif, Lazy<RenderedConst>, Option<Lazy<interpret::ConstValue<'tcx>>>),
     ImmStatic,
     MutStatic,
     ForeignImmStatic,
@@ -346,9 +346,10 @@ impl<'a, 'gcx> HashStable<StableHashingContext<'a>> for EntryKind<'gcx> {
             EntryKind::Type => {
                 // Nothing else to hash here.
             }
-            EntryKind::Const(qualif, ref const_data) => {
+            EntryKind::Const(qualif, ref const_data, ref value) => {
                 qualif.hash_stable(hcx, hasher);
                 const_data.hash_stable(hcx, hasher);
+                value.hash_stable(hcx, hasher);
             }
             EntryKind::Enum(ref repr_options) => {
                 repr_options.hash_stable(hcx, hasher);
@@ -554,8 +555,10 @@ pub struct MethodData<'tcx> {
     pub fn_data: FnData<'tcx>,
     pub container: AssociatedContainer,
     pub has_self: bool,
+    /// The fields of `self` named by `#[disjoint_fields(..)]`, if present.
+    pub disjoint_fields: LazySeq<mir::Field>,
 }
-impl_stable_hash_for!(struct MethodData<'tcx> { fn_data, container, has_self });
+impl_stable_hash_for!(struct MethodData<'tcx> { fn_data, container, has_self, disjoint_fields });
 
 #[derive(RustcEncodable, RustcDecodable)]
 pub struct ClosureData<'tcx> {
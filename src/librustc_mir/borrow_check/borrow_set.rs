@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use borrow_check::disjoint_fields;
 use borrow_check::place_ext::PlaceExt;
 use borrow_check::nll::ToRegionVid;
 use dataflow::indexes::BorrowIndex;
@@ -21,8 +22,10 @@ use rustc::ty::{RegionVid, TyCtxt};
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::sync::Lrc;
 use std::fmt;
 use std::ops::Index;
+use std::slice;
 
 crate struct BorrowSet<'tcx> {
     /// The fundamental map relating bitvector indexes to the borrows
@@ -79,6 +82,21 @@ crate struct BorrowData<'tcx> {
     crate borrowed_place: mir::Place<'tcx>,
     /// Place to which the borrow was stored
     crate assigned_place: mir::Place<'tcx>,
+    /// If this borrows the receiver of a `#[disjoint_fields]` method,
+    /// the fields of `borrowed_place` that the method may use
+    crate field_view: Vec<mir::Place<'tcx>>,
+}
+
+impl<'tcx> BorrowData<'tcx> {
+    /// The places that this borrow may actually access: the fields in
+    /// its view if it has one, and the whole borrowed place otherwise.
+    crate fn accessed_places(&self) -> &[mir::Place<'tcx>] {
+        if self.field_view.is_empty() {
+            slice::from_ref(&self.borrowed_place)
+        } else {
+            &self.field_view
+        }
+    }
 }
 
 impl<'tcx> fmt::Display for BorrowData<'tcx> {
@@ -147,6 +165,7 @@ impl<'tcx> BorrowSet<'tcx> {
             activation_map: Default::default(),
             local_map: Default::default(),
             pending_activations: Default::default(),
+            receiver_views: disjoint_fields::receiver_views(tcx, mir),
             locals_state_at_exit:
                 LocalsStateAtExit::build(locals_are_invalidated_at_exit, mir, move_data),
         };
@@ -190,6 +209,10 @@ struct GatherBorrows<'a, 'gcx: 'tcx, 'tcx: 'a> {
     /// remove from the map (and add to the "tombstone" set below).
     pending_activations: FxHashMap<mir::Local, BorrowIndex>,
 
+    /// Temporaries holding the receiver of a `#[disjoint_fields]`
+    /// method, with the fields that method uses.
+    receiver_views: FxHashMap<mir::Local, Lrc<Vec<mir::Field>>>,
+
     locals_state_at_exit: LocalsStateAtExit,
 }
 
@@ -209,6 +232,13 @@ impl<'a, 'gcx, 'tcx> Visitor<'tcx> for GatherBorrows<'a, 'gcx, 'tcx> {
 
             let region = region.to_region_vid();
 
+            let field_view = match *assigned_place {
+                mir::Place::Local(temp) => self.receiver_views.get(&temp).and_then(|fields| {
+                    disjoint_fields::field_places(self.tcx, self.mir, borrowed_place, fields)
+                }),
+                _ => None,
+            };
+
             let borrow = BorrowData {
                 kind,
                 region,
@@ -216,6 +246,7 @@ impl<'a, 'gcx, 'tcx> Visitor<'tcx> for GatherBorrows<'a, 'gcx, 'tcx> {
                 activation_location: TwoPhaseActivation::NotTwoPhase,
                 borrowed_place: borrowed_place.clone(),
                 assigned_place: assigned_place.clone(),
+                field_view: field_view.unwrap_or_default(),
             };
             let idx = self.idx_vec.push(borrow);
             self.location_map.insert(location, idx);
@@ -0,0 +1,253 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for the experimental `#[disjoint_fields(..)]` attribute.
+//!
+//! A method annotated with `#[disjoint_fields(a, b)]` promises to only
+//! touch the named fields of `self`. Callers then treat the autoref
+//! borrow of the receiver as a borrow of just those fields, so two such
+//! calls that name disjoint fields may hold their results at the same
+//! time. The promise is checked against the body of the method in
+//! `mir_validated`, so that it holds no matter which borrow checker runs
+//! on the method, or whether its errors would be downgraded to warnings.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::{Field, Local, LocalKind, Location, Mir, Operand, Place};
+use rustc::mir::{ProjectionElem, Rvalue, StatementKind, TerminatorKind};
+use rustc::ty::query::Providers;
+use rustc::ty::{self, TyCtxt};
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+use rustc_data_structures::sync::Lrc;
+use syntax_pos::Span;
+
+crate fn provide(providers: &mut Providers) {
+    *providers = Providers {
+        disjoint_fields,
+        ..*providers
+    };
+}
+
+fn disjoint_fields<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+) -> Option<Lrc<Vec<Field>>> {
+    let attr = tcx.get_attrs(def_id)
+        .iter()
+        .find(|attr| attr.check_name("disjoint_fields"))?
+        .clone();
+
+    let has_self = tcx.opt_associated_item(def_id)
+        .map_or(false, |item| item.method_has_self_argument);
+    let self_ty = if has_self {
+        tcx.fn_sig(def_id).skip_binder().inputs().first().cloned()
+    } else {
+        None
+    };
+    let variant = match self_ty.map(|ty| &ty.sty) {
+        Some(&ty::Ref(_, ty, _)) => match ty.sty {
+            ty::Adt(adt_def, _) if adt_def.is_struct() => Some(adt_def.non_enum_variant()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let variant = match variant {
+        Some(variant) => variant,
+        None => {
+            tcx.sess.span_err(
+                attr.span,
+                "`#[disjoint_fields]` may only be used on methods taking `self` by reference \
+                 to a struct",
+            );
+            return None;
+        }
+    };
+
+    let items = attr.meta_item_list().unwrap_or_default();
+    if items.is_empty() {
+        tcx.sess.span_err(attr.span, "`#[disjoint_fields]` must name at least one field");
+        return None;
+    }
+
+    let mut fields = vec![];
+    for item in items {
+        let name = match item.word() {
+            Some(word) => word.name(),
+            None => {
+                tcx.sess.span_err(item.span, "expected a field name");
+                continue;
+            }
+        };
+        match variant.fields.iter().position(|field| field.ident.name == name) {
+            Some(index) => fields.push(Field::new(index)),
+            None => {
+                tcx.sess.span_err(item.span, &format!("no field `{}` on `self`", name));
+            }
+        }
+    }
+
+    if fields.is_empty() {
+        return None;
+    }
+    fields.sort();
+    fields.dedup();
+    Some(Lrc::new(fields))
+}
+
+/// Finds the temporaries that hold the receiver of a call to a
+/// `#[disjoint_fields]` method, mapped to the fields that method uses.
+crate fn receiver_views<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+) -> FxHashMap<Local, Lrc<Vec<Field>>> {
+    let mut views = FxHashMap::default();
+    for block_data in mir.basic_blocks() {
+        if let TerminatorKind::Call { ref func, ref args, .. } = block_data.terminator().kind {
+            let callee = match func.ty(mir, tcx).sty {
+                ty::FnDef(def_id, _) => def_id,
+                _ => continue,
+            };
+            if let Some(&Operand::Move(Place::Local(temp))) = args.first() {
+                if mir.local_kind(temp) != LocalKind::Temp {
+                    continue;
+                }
+                if let Some(fields) = tcx.disjoint_fields(callee) {
+                    views.insert(temp, fields);
+                }
+            }
+        }
+    }
+    views
+}
+
+/// Returns the places for `fields` of the struct stored at `place`, or
+/// `None` if `place` is not a struct with those fields.
+crate fn field_places<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    place: &Place<'tcx>,
+    fields: &[Field],
+) -> Option<Vec<Place<'tcx>>> {
+    match place.ty(mir, tcx).to_ty(tcx).sty {
+        ty::Adt(adt_def, substs) if adt_def.is_struct() => {
+            let variant = adt_def.non_enum_variant();
+            fields.iter().map(|&field| {
+                let field_def = variant.fields.get(field.index())?;
+                Some(place.clone().field(field, field_def.ty(tcx, substs)))
+            }).collect()
+        }
+        _ => None,
+    }
+}
+
+/// Checks that the body of a `#[disjoint_fields]` method only uses the
+/// fields of `self` that it declares.
+crate fn check_method_body<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    mir: &Mir<'tcx>,
+) {
+    let declared = match tcx.disjoint_fields(def_id) {
+        Some(declared) => declared,
+        None => return,
+    };
+    let self_local = Local::new(1);
+    let is_subset = |fields: &[Field]| fields.iter().all(|field| declared.contains(field));
+
+    // Passing `self` (or a reborrow of `*self`) on to another
+    // `#[disjoint_fields]` method is fine as long as that method uses no
+    // more than we do.
+    let views = receiver_views(tcx, mir);
+    let mut allowed = FxHashSet::default();
+    for (block, block_data) in mir.basic_blocks().iter_enumerated() {
+        for (statement_index, statement) in block_data.statements.iter().enumerate() {
+            if let StatementKind::Assign(
+                Place::Local(temp),
+                box Rvalue::Ref(_, _, Place::Projection(ref proj)),
+            ) = statement.kind {
+                let reborrows_self = proj.base == Place::Local(self_local)
+                    && proj.elem == ProjectionElem::Deref;
+                if reborrows_self && views.get(&temp).map_or(false, |view| is_subset(&view[..])) {
+                    allowed.insert(Location { block, statement_index });
+                }
+            }
+        }
+        if let TerminatorKind::Call { ref func, ref args, .. } = block_data.terminator().kind {
+            let passes_self = match args.first() {
+                Some(Operand::Move(Place::Local(local))) |
+                Some(Operand::Copy(Place::Local(local))) => *local == self_local,
+                _ => false,
+            };
+            if let ty::FnDef(callee, _) = func.ty(mir, tcx).sty {
+                let callee_fields = tcx.disjoint_fields(callee);
+                if passes_self && callee_fields.map_or(false, |fields| is_subset(&fields[..])) {
+                    allowed.insert(mir.terminator_loc(block));
+                }
+            }
+        }
+    }
+
+    let mut checker = SelfUseChecker {
+        tcx,
+        mir,
+        self_local,
+        declared: &declared[..],
+        allowed,
+        reported: FxHashSet::default(),
+    };
+    checker.visit_mir(mir);
+}
+
+struct SelfUseChecker<'a, 'b, 'gcx: 'tcx, 'tcx: 'a + 'b> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &'b Mir<'tcx>,
+    self_local: Local,
+    declared: &'b [Field],
+    allowed: FxHashSet<Location>,
+    reported: FxHashSet<Span>,
+}
+
+impl<'a, 'b, 'gcx, 'tcx> Visitor<'tcx> for SelfUseChecker<'a, 'b, 'gcx, 'tcx> {
+    fn visit_place(
+        &mut self,
+        place: &Place<'tcx>,
+        context: PlaceContext<'tcx>,
+        location: Location,
+    ) {
+        // `(*self).field` for one of the declared fields.
+        if let Place::Projection(ref proj) = *place {
+            if let ProjectionElem::Field(field, _) = proj.elem {
+                if let Place::Projection(ref base) = proj.base {
+                    if base.base == Place::Local(self.self_local)
+                        && base.elem == ProjectionElem::Deref
+                        && self.declared.contains(&field)
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, local: &Local, context: PlaceContext<'tcx>, location: Location) {
+        if *local != self.self_local || !context.is_use() || self.allowed.contains(&location) {
+            return;
+        }
+        let span = self.mir.source_info(location).span;
+        if !self.reported.insert(span) {
+            return;
+        }
+        self.tcx.sess.span_err(
+            span,
+            "`#[disjoint_fields]` method uses `self` outside of its declared fields",
+        );
+    }
+}
@@ -168,11 +168,34 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                         ),
                     );
                 } else {
+                    let chain = self.move_chain(move_site.moi, context.loc);
+                    let move_msg = if chain.is_empty() {
+                        move_msg.to_owned()
+                    } else {
+                        self.move_destination_description(move_out.source, &move_spans)
+                    };
                     err.span_label(move_span, format!("value moved{} here", move_msg));
                     move_spans.var_span_label(
                         &mut err,
                         format!("variable moved due to use{}", move_spans.describe()),
                     );
+
+                    for next_moi in chain {
+                        let next_move = self.move_data.moves[next_moi];
+                        let next_place = &self.move_data.move_paths[next_move.path].place;
+                        let next_spans = self.move_spans(next_place, next_move.source);
+                        err.span_label(
+                            next_spans.args_or_use(),
+                            format!(
+                                "then moved{} here",
+                                self.move_destination_description(next_move.source, &next_spans),
+                            ),
+                        );
+                        next_spans.var_span_label(
+                            &mut err,
+                            format!("variable moved due to use{}", next_spans.describe()),
+                        );
+                    }
                 };
             }
 
@@ -522,6 +545,15 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             );
         }
 
+        // A closure requiring unique access may do so because of a later use
+        // than the one labelled above, so point at it.
+        if issued_borrow.kind == BorrowKind::Unique {
+            issued_spans.capture_mode_note(tcx, &mut err);
+        }
+        if gen_borrow_kind == BorrowKind::Unique && borrow_spans != issued_spans {
+            borrow_spans.capture_mode_note(tcx, &mut err);
+        }
+
         explanation
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, first_borrow_desc);
 
@@ -909,6 +941,11 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             _ => {}
         }
         explanation.add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        if let BorrowExplanation::MustBeValidFor { ref region_name, .. } = explanation {
+            if let RegionNameSource::Static = region_name.source {
+                self.explain_unpromoted_temporary(borrow, &mut err);
+            }
+        }
 
         let within = if borrow_spans.for_generator() {
             " by generator"
@@ -924,6 +961,28 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         err
     }
 
+    /// Notes why a temporary that has to live for `'static` was not promoted
+    /// to a constant, which would have made it live long enough.
+    fn explain_unpromoted_temporary(
+        &self,
+        borrow: &BorrowData<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        // Promotion is decided on the MIR before it is simplified for borrowck,
+        // so the borrow is found by its span rather than by its location.
+        let span = self.mir.source_info(borrow.reserve_location).span;
+        let promotability = self.infcx.tcx.promotability(self.mir_def_id);
+        let reason = promotability.unpromotable.iter()
+            .find(|&&(_, borrow_span, _)| borrow_span == span)
+            .map(|&(.., reason)| reason);
+        if let Some(reason) = reason {
+            err.note(&format!(
+                "the temporary was not promoted to a `'static` constant because {}",
+                reason.description(),
+            ));
+        }
+    }
+
     fn report_cannot_return_reference_to_local(
         &self,
         borrow: &BorrowData<'tcx>,
@@ -1107,6 +1166,77 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         err
     }
 
+    /// If the statement at `location` moves a value straight into a local,
+    /// either by assignment or by capturing it in a closure, returns that
+    /// local.
+    fn moved_into_local(&self, location: Location) -> Option<Local> {
+        let stmt = self.mir[location.block].statements.get(location.statement_index)?;
+        match stmt.kind {
+            StatementKind::Assign(Place::Local(local), box Rvalue::Use(Operand::Move(_))) |
+            StatementKind::Assign(
+                Place::Local(local),
+                box Rvalue::Aggregate(box AggregateKind::Closure(..), _),
+            ) => Some(local),
+            _ => None,
+        }
+    }
+
+    /// Follows the value moved out by `moi` as it is moved on into other
+    /// bindings and closures. Only moves that happen on every path to
+    /// `use_location` are followed, so each move in the returned chain is
+    /// known to have happened by the time of the use.
+    fn move_chain(&self, moi: MoveOutIndex, use_location: Location) -> Vec<MoveOutIndex> {
+        let mut chain = vec![];
+        let mut location = self.move_data.moves[moi].source;
+        while let Some(local) = self.moved_into_local(location) {
+            let path = self.move_data.rev_lookup.find_local(local);
+            let candidates: Vec<_> = self.move_data.path_map[path]
+                .iter()
+                .cloned()
+                .filter(|&next| {
+                    let next_location = self.move_data.moves[next].source;
+                    next_location != location
+                        && location.dominates(next_location, &self.dominators)
+                        && next_location.dominates(use_location, &self.dominators)
+                })
+                .collect();
+            // All the candidates dominate the use, so they are ordered by
+            // dominance; the next link is the one that comes first.
+            let next = candidates.iter().cloned().find(|&candidate| {
+                let candidate_location = self.move_data.moves[candidate].source;
+                candidates.iter().all(|&other| {
+                    candidate_location.dominates(self.move_data.moves[other].source,
+                                                 &self.dominators)
+                })
+            });
+            match next {
+                Some(next) => {
+                    chain.push(next);
+                    location = self.move_data.moves[next].source;
+                }
+                None => break,
+            }
+        }
+        chain
+    }
+
+    /// Describes where the move at `location` put the value, for labels
+    /// of the form "value moved{} here".
+    fn move_destination_description(&self, location: Location, move_spans: &UseSpans) -> String {
+        if move_spans.for_closure() {
+            return " into closure".to_owned();
+        }
+        match self.moved_into_local(location) {
+            Some(local) => match self.mir.local_decls[local].name {
+                Some(name) if self.mir.local_decls[local].is_user_variable.is_some() => {
+                    format!(" into `{}`", name)
+                }
+                _ => String::new(),
+            },
+            None => String::new(),
+        }
+    }
+
     fn get_moved_indexes(&mut self, context: Context, mpi: MovePathIndex) -> Vec<MoveSite> {
         let mir = self.mir;
 
@@ -2238,6 +2368,9 @@ pub(super) enum UseSpans {
         args_span: Span,
         // The span of the first use of the captured variable inside the closure.
         var_span: Span,
+        // The closure and the index of the captured variable among its upvars.
+        closure_def_id: DefId,
+        capture_index: usize,
     },
     // This access has a single span associated to it: common case.
     OtherUse(Span),
@@ -2273,6 +2406,40 @@ impl UseSpans {
         }
     }
 
+    // Add a note pointing at the use that determined how the closure captures
+    // the variable, if it is not the use labelled by `var_span_label`.
+    pub(super) fn capture_mode_note(self, tcx: ty::TyCtxt, err: &mut DiagnosticBuilder) {
+        if let UseSpans::ClosureUse {
+            is_generator,
+            var_span,
+            closure_def_id,
+            capture_index,
+            ..
+        } = self {
+            let captures = tcx.closure_captures(closure_def_id);
+            let captured = &captures[capture_index];
+            if captured.span != var_span {
+                let mode = match captured.capture {
+                    ty::UpvarCapture::ByValue => "by value",
+                    ty::UpvarCapture::ByRef(borrow) => match borrow.kind {
+                        ty::BorrowKind::ImmBorrow => "by immutable borrow",
+                        ty::BorrowKind::UniqueImmBorrow => "by unique immutable borrow",
+                        ty::BorrowKind::MutBorrow => "by mutable borrow",
+                    },
+                };
+                err.span_note(
+                    captured.span,
+                    &format!(
+                        "{} captures `{}` {} because of this use",
+                        if is_generator { "generator" } else { "closure" },
+                        captured.name,
+                        mode,
+                    ),
+                );
+            }
+        }
+    }
+
     /// Return `false` if this place is not used in a closure.
     fn for_closure(&self) -> bool {
         match *self {
@@ -2341,11 +2508,15 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 "move_spans: def_id={:?} is_generator={:?} places={:?}",
                 def_id, is_generator, places
             );
-            if let Some((args_span, var_span)) = self.closure_span(*def_id, moved_place, places) {
+            if let Some((args_span, var_span, capture_index)) =
+                self.closure_span(*def_id, moved_place, places)
+            {
                 return ClosureUse {
                     is_generator,
                     args_span,
                     var_span,
+                    closure_def_id: *def_id,
+                    capture_index,
                 };
             }
         }
@@ -2391,13 +2562,15 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                     "borrow_spans: def_id={:?} is_generator={:?} places={:?}",
                     def_id, is_generator, places
                 );
-                if let Some((args_span, var_span)) = self.closure_span(
+                if let Some((args_span, var_span, capture_index)) = self.closure_span(
                     *def_id, &Place::Local(target), places
                 ) {
                     return ClosureUse {
                         is_generator,
                         args_span,
                         var_span,
+                        closure_def_id: *def_id,
+                        capture_index,
                     };
                 } else {
                     return OtherUse(use_span);
@@ -2412,13 +2585,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         OtherUse(use_span)
     }
 
-    /// Finds the span of a captured variable within a closure or generator.
-    fn closure_span(
+    /// Finds the span of a captured variable within a closure or generator,
+    /// along with its index among the closure's upvars.
+    pub(super) fn closure_span(
         &self,
         def_id: DefId,
         target_place: &Place<'tcx>,
         places: &Vec<Operand<'tcx>>,
-    ) -> Option<(Span, Span)> {
+    ) -> Option<(Span, Span, usize)> {
         debug!(
             "closure_span: def_id={:?} target_place={:?} places={:?}",
             def_id, target_place, places
@@ -2429,15 +2603,15 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         if let hir::ExprKind::Closure(
             .., args_span, _
         ) = expr {
-            let var_span = self.infcx.tcx.with_freevars(
+            let (var_span, capture_index) = self.infcx.tcx.with_freevars(
                 node_id,
                 |freevars| {
-                    for (v, place) in freevars.iter().zip(places) {
+                    for (index, (v, place)) in freevars.iter().zip(places).enumerate() {
                         match place {
                             Operand::Copy(place) |
                             Operand::Move(place) if target_place == place => {
                                 debug!("closure_span: found captured local {:?}", place);
-                                return Some(v.span);
+                                return Some((v.span, index));
                             },
                             _ => {}
                         }
@@ -2447,7 +2621,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 },
             )?;
 
-            Some((*args_span, var_span))
+            Some((*args_span, var_span, capture_index))
         } else {
             None
         }
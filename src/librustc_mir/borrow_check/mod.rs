@@ -12,10 +12,10 @@
 
 use borrow_check::nll::region_infer::RegionInferenceContext;
 use rustc::hir;
-use rustc::hir::Node;
 use rustc::hir::def_id::DefId;
 use rustc::infer::InferCtxt;
-use rustc::lint::builtin::UNUSED_MUT;
+use rustc::lint;
+use rustc::lint::builtin::{BORROWCK_MIGRATION, UNUSED_MUT};
 use rustc::middle::borrowck::SignalledError;
 use rustc::mir::{AggregateKind, BasicBlock, BorrowCheckResult, BorrowKind};
 use rustc::mir::{ClearCrossCrate, Local, Location, Mir, Mutability, Operand, Place};
@@ -24,7 +24,7 @@ use rustc::mir::{Terminator, TerminatorKind};
 use rustc::ty::query::Providers;
 use rustc::ty::{self, TyCtxt};
 
-use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, Level};
+use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder};
 use rustc_data_structures::bit_set::BitSet;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::graph::dominators::Dominators;
@@ -32,6 +32,7 @@ use smallvec::SmallVec;
 
 use std::rc::Rc;
 use std::collections::BTreeMap;
+use std::slice;
 
 use syntax_pos::Span;
 
@@ -44,6 +45,7 @@ use dataflow::MoveDataParamEnv;
 use dataflow::{do_dataflow, DebugFormatted};
 use dataflow::EverInitializedPlaces;
 use dataflow::{MaybeInitializedPlaces, MaybeUninitializedPlaces};
+use transform::MirSource;
 use util::borrowck_errors::{BorrowckErrors, Origin};
 
 use self::borrow_set::{BorrowData, BorrowSet};
@@ -56,6 +58,7 @@ use self::mutability_errors::AccessKind;
 use self::path_utils::*;
 
 crate mod borrow_set;
+crate mod disjoint_fields;
 mod error_reporting;
 mod flows;
 mod location;
@@ -74,6 +77,7 @@ pub fn provide(providers: &mut Providers) {
         mir_borrowck,
         ..*providers
     };
+    disjoint_fields::provide(providers);
 }
 
 fn mir_borrowck<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> BorrowCheckResult<'tcx> {
@@ -166,7 +170,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     let mut flow_inits = FlowAtLocation::new(do_dataflow(
         tcx,
         mir,
-        id,
+        MirSource::item(def_id),
         &attributes,
         &dead_unwinds,
         MaybeInitializedPlaces::new(tcx, mir, &mdpe),
@@ -204,7 +208,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     let flow_borrows = FlowAtLocation::new(do_dataflow(
         tcx,
         mir,
-        id,
+        MirSource::item(def_id),
         &attributes,
         &dead_unwinds,
         Borrows::new(tcx, mir, regioncx.clone(), &borrow_set),
@@ -213,7 +217,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     let flow_uninits = FlowAtLocation::new(do_dataflow(
         tcx,
         mir,
-        id,
+        MirSource::item(def_id),
         &attributes,
         &dead_unwinds,
         MaybeUninitializedPlaces::new(tcx, mir, &mdpe),
@@ -222,18 +226,19 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     let flow_ever_inits = FlowAtLocation::new(do_dataflow(
         tcx,
         mir,
-        id,
+        MirSource::item(def_id),
         &attributes,
         &dead_unwinds,
         EverInitializedPlaces::new(tcx, mir, &mdpe),
         |bd, i| DebugFormatted::new(&bd.move_data().inits[i]),
     ));
 
-    let movable_generator = match tcx.hir().get(id) {
-        Node::Expr(&hir::Expr {
-            node: hir::ExprKind::Closure(.., Some(hir::GeneratorMovability::Static)),
-            ..
-        }) => false,
+    // Immovable generators are pinned while they run, so their locals may
+    // be borrowed across suspension points: the generator transform keeps
+    // every borrowed local in the generator interior. Take the movability
+    // from the generator type, as that transform does.
+    let movable_generator = match tcx.type_of(def_id).sty {
+        ty::Generator(_, _, hir::GeneratorMovability::Static) => false,
         _ => true,
     };
 
@@ -368,15 +373,22 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
             match signalled_any_error {
                 SignalledError::NoErrorsSeen => {
                     // if AST-borrowck signalled no errors, then
-                    // downgrade all the buffered MIR-borrowck errors
-                    // to warnings.
+                    // report all the buffered MIR-borrowck errors
+                    // through the `borrowck_migration` future
+                    // compatibility lint, downgrading them to
+                    // warnings by default.
+                    let (level, src) = tcx.lint_level_at_node(BORROWCK_MIGRATION, id);
+                    if level == lint::Level::Allow {
+                        mbcx.errors_buffer.retain(|err| !err.is_error());
+                    }
                     for err in &mut mbcx.errors_buffer {
                         if err.is_error() {
-                            err.level = Level::Warning;
-                            err.warn("This error has been downgraded to a warning \
-                                      for backwards compatibility with previous releases.\n\
-                                      It represents potential unsoundness in your code.\n\
-                                      This warning will become a hard error in the future.");
+                            let mut lint = lint::struct_lint_level(
+                                tcx.sess, BORROWCK_MIGRATION, level, src, None, "",
+                            );
+                            err.level = lint.level;
+                            err.children.extend(lint.children.drain(..));
+                            lint.cancel();
                         }
                     }
                 }
@@ -591,9 +603,10 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
             | StatementKind::AscribeUserType(..)
             | StatementKind::Retag { .. }
             | StatementKind::EscapeToRaw { .. }
+            | StatementKind::CoverageCounter { .. }
             | StatementKind::StorageLive(..) => {
-                // `Nop`, `AscribeUserType`, `Retag`, and `StorageLive` are irrelevant
-                // to borrow check.
+                // `Nop`, `AscribeUserType`, `Retag`, `CoverageCounter` and `StorageLive` are
+                // irrelevant to borrow check.
             }
             StatementKind::StorageDead(local) => {
                 self.access_place(
@@ -1160,13 +1173,22 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                     }
                 };
 
-                self.access_place(
-                    context,
-                    (place, span),
-                    access_kind,
-                    LocalMutationIsAllowed::No,
-                    flow_state,
-                );
+                // A receiver borrowed for a `#[disjoint_fields]` method only
+                // accesses the fields that method declares.
+                let borrow_set = self.borrow_set.clone();
+                let accessed_places = match borrow_set.location_map.get(&context.loc) {
+                    Some(&borrow_index) => borrow_set[borrow_index].accessed_places(),
+                    None => slice::from_ref(place),
+                };
+                for accessed_place in accessed_places {
+                    self.access_place(
+                        context,
+                        (accessed_place, span),
+                        access_kind,
+                        LocalMutationIsAllowed::No,
+                        flow_state,
+                    );
+                }
 
                 let action = if bk == BorrowKind::Shallow {
                     InitializationRequiringAction::MatchOn
@@ -1425,16 +1447,18 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 BorrowKind::Unique | BorrowKind::Mut { .. } => true,
             });
 
-            self.access_place(
-                ContextKind::Activation.new(location),
-                (&borrow.borrowed_place, span),
-                (
-                    Deep,
-                    Activation(WriteKind::MutableBorrow(borrow.kind), borrow_index),
-                ),
-                LocalMutationIsAllowed::No,
-                flow_state,
-            );
+            for accessed_place in borrow.accessed_places() {
+                self.access_place(
+                    ContextKind::Activation.new(location),
+                    (accessed_place, span),
+                    (
+                        Deep,
+                        Activation(WriteKind::MutableBorrow(borrow.kind), borrow_index),
+                    ),
+                    LocalMutationIsAllowed::No,
+                    flow_state,
+                );
+            }
             // We do not need to call `check_if_path_or_subpath_is_moved`
             // again, as we already called it when we made the
             // initial reservation.
@@ -15,7 +15,7 @@ use borrow_check::nll::region_infer::{Cause, RegionName};
 use borrow_check::{Context, MirBorrowckCtxt, WriteKind};
 use rustc::ty::{self, TyCtxt};
 use rustc::mir::{
-    CastKind, ConstraintCategory, FakeReadCause, Local, Location, Mir, Operand,
+    AggregateKind, CastKind, ConstraintCategory, FakeReadCause, Local, Location, Mir, Operand,
     Place, Projection, ProjectionElem, Rvalue, Statement, StatementKind,
     TerminatorKind
 };
@@ -25,8 +25,11 @@ use syntax_pos::Span;
 mod find_use;
 
 pub(in borrow_check) enum BorrowExplanation {
-    UsedLater(LaterUseKind, Span),
-    UsedLaterInLoop(LaterUseKind, Span),
+    /// The borrow is used later at the given span. If the later use is of
+    /// a closure holding the borrow, the last span is where the closure
+    /// body refers to it.
+    UsedLater(LaterUseKind, Span, Option<Span>),
+    UsedLaterInLoop(LaterUseKind, Span, Option<Span>),
     UsedLaterWhenDropped {
         drop_loc: Location,
         dropped_local: Local,
@@ -66,7 +69,7 @@ impl BorrowExplanation {
         borrow_desc: &str,
     ) {
         match *self {
-            BorrowExplanation::UsedLater(later_use_kind, var_or_use_span) => {
+            BorrowExplanation::UsedLater(later_use_kind, var_or_use_span, closure_use_span) => {
                 let message = match later_use_kind {
                     LaterUseKind::TraitCapture => "borrow later captured here by trait object",
                     LaterUseKind::ClosureCapture => "borrow later captured here by closure",
@@ -75,8 +78,10 @@ impl BorrowExplanation {
                     LaterUseKind::Other => "borrow later used here",
                 };
                 err.span_label(var_or_use_span, format!("{}{}", borrow_desc, message));
+                add_closure_use_label(err, closure_use_span, borrow_desc);
             },
-            BorrowExplanation::UsedLaterInLoop(later_use_kind, var_or_use_span) => {
+            BorrowExplanation::UsedLaterInLoop(later_use_kind, var_or_use_span,
+                                               closure_use_span) => {
                 let message = match later_use_kind {
                     LaterUseKind::TraitCapture =>
                         "borrow captured here by trait object, in later iteration of loop",
@@ -87,6 +92,7 @@ impl BorrowExplanation {
                     LaterUseKind::Other => "borrow used here, in later iteration of loop",
                 };
                 err.span_label(var_or_use_span, format!("{}{}", borrow_desc, message));
+                add_closure_use_label(err, closure_use_span, borrow_desc);
             },
             BorrowExplanation::UsedLaterWhenDropped { drop_loc, dropped_local,
                                                       should_note_order } =>
@@ -183,6 +189,16 @@ impl BorrowExplanation {
     }
 }
 
+fn add_closure_use_label(
+    err: &mut DiagnosticBuilder<'_>,
+    closure_use_span: Option<Span>,
+    borrow_desc: &str,
+) {
+    if let Some(span) = closure_use_span {
+        err.span_label(span, format!("{}borrow later used by the closure here", borrow_desc));
+    }
+}
+
 impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     /// Returns structured explanation for *why* the borrow contains the
     /// point from `context`. This is key for the "3-point errors"
@@ -229,16 +245,17 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 let span = mir.source_info(location).span;
                 let spans = self.move_spans(&Place::Local(local), location)
                     .or_else(|| self.borrow_spans(span, location));
+                let closure_use_span = self.closure_use_of_borrow(borrow, local);
 
                 if self.is_borrow_location_in_loop(context.loc) {
                     let later_use = self.later_use_kind(borrow, spans, location);
-                    BorrowExplanation::UsedLaterInLoop(later_use.0, later_use.1)
+                    BorrowExplanation::UsedLaterInLoop(later_use.0, later_use.1, closure_use_span)
                 } else {
                     // Check if the location represents a `FakeRead`, and adapt the error
                     // message to the `FakeReadCause` it is from: in particular,
                     // the ones inserted in optimized `let var = <expr>` patterns.
                     let later_use = self.later_use_kind(borrow, spans, location);
-                    BorrowExplanation::UsedLater(later_use.0, later_use.1)
+                    BorrowExplanation::UsedLater(later_use.0, later_use.1, closure_use_span)
                 }
             }
 
@@ -295,6 +312,52 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// If the borrow is stored in a variable that is captured by the closure
+    /// in `local`, returns where the closure body uses that variable. This
+    /// is the point that actually needs the borrow when the later use of
+    /// `local` is, for example, a call of the closure.
+    fn closure_use_of_borrow(&self, borrow: &BorrowData<'tcx>, local: Local) -> Option<Span> {
+        // Calling a closure goes through a reference to it.
+        let mut ty = self.mir.local_decls[local].ty;
+        while let ty::Ref(_, referent_ty, _) = ty.sty {
+            ty = referent_ty;
+        }
+        let closure_def_id = match ty.sty {
+            ty::Closure(def_id, _) => def_id,
+            _ => return None,
+        };
+        let assigned = match borrow.assigned_place {
+            Place::Local(assigned) => assigned,
+            _ => return None,
+        };
+        // The closure captures the variable either by value, or through a
+        // temporary holding a reference to it.
+        let mut captured_places = vec![Place::Local(assigned)];
+        let mut upvars = None;
+        for block in self.mir.basic_blocks() {
+            for statement in &block.statements {
+                match statement.kind {
+                    StatementKind::Assign(Place::Local(temp), box Rvalue::Ref(_, _, ref place))
+                        if *place == Place::Local(assigned) => {
+                        captured_places.push(Place::Local(temp));
+                    }
+                    StatementKind::Assign(
+                        _,
+                        box Rvalue::Aggregate(box AggregateKind::Closure(def_id, _), ref places),
+                    ) if def_id == closure_def_id => {
+                        upvars = Some(places);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let upvars = upvars?;
+        captured_places.iter()
+            .filter_map(|place| self.closure_span(closure_def_id, place, upvars))
+            .map(|(_, var_span, _)| var_span)
+            .next()
+    }
+
     /// Check if a borrow location is within a loop.
     fn is_borrow_location_in_loop(
         &self,
@@ -28,6 +28,8 @@ use rustc::mir::{Terminator, TerminatorKind};
 use rustc::mir::{Operand, BorrowKind};
 use rustc_data_structures::graph::dominators::Dominators;
 
+use std::slice;
+
 pub(super) fn generate_invalidates<'cx, 'gcx, 'tcx>(
     tcx: TyCtxt<'cx, 'gcx, 'tcx>,
     all_facts: &mut Option<AllFacts>,
@@ -136,9 +138,10 @@ impl<'cx, 'tcx, 'gcx> Visitor<'tcx> for InvalidationGenerator<'cx, 'tcx, 'gcx> {
             StatementKind::AscribeUserType(..) |
             StatementKind::Retag { .. } |
             StatementKind::EscapeToRaw { .. } |
+            StatementKind::CoverageCounter { .. } |
             StatementKind::StorageLive(..) => {
-                // `Nop`, `AscribeUserType`, `Retag`, and `StorageLive` are irrelevant
-                // to borrow check.
+                // `Nop`, `AscribeUserType`, `Retag`, `CoverageCounter` and `StorageLive` are
+                // irrelevant to borrow check.
             }
             StatementKind::StorageDead(local) => {
                 self.access_place(
@@ -343,12 +346,19 @@ impl<'cg, 'cx, 'tcx, 'gcx> InvalidationGenerator<'cx, 'tcx, 'gcx> {
                     }
                 };
 
-                self.access_place(
-                    context,
-                    place,
-                    access_kind,
-                    LocalMutationIsAllowed::No,
-                );
+                let borrow_set = self.borrow_set;
+                let accessed_places = match borrow_set.location_map.get(&context.loc) {
+                    Some(&borrow_index) => borrow_set[borrow_index].accessed_places(),
+                    None => slice::from_ref(place),
+                };
+                for accessed_place in accessed_places {
+                    self.access_place(
+                        context,
+                        accessed_place,
+                        access_kind,
+                        LocalMutationIsAllowed::No,
+                    );
+                }
             }
 
             Rvalue::Use(ref operand)
@@ -0,0 +1,242 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An in-tree, location-sensitive solver for the facts gathered in
+//! `AllFacts`, used by `-Z polonius`.
+//!
+//! It computes the same relations as the "naive" datalog formulation of
+//! Polonius:
+//!
+//! ```text
+//! subset(R1, R2, P) :- outlives(R1, R2, P).
+//! subset(R1, R3, P) :- subset(R1, R2, P), subset(R2, R3, P).
+//! subset(R1, R2, Q) :- subset(R1, R2, P), cfg_edge(P, Q),
+//!                      region_live_at(R1, Q), region_live_at(R2, Q).
+//!
+//! requires(R, B, P) :- borrow_region(R, B, P).
+//! requires(R2, B, P) :- requires(R1, B, P), subset(R1, R2, P).
+//! requires(R, B, Q) :- requires(R, B, P), !killed(B, P), cfg_edge(P, Q),
+//!                      region_live_at(R, Q).
+//!
+//! borrow_live_at(B, P) :- requires(R, B, P), region_live_at(R, P).
+//! errors(B, P) :- invalidates(P, B), borrow_live_at(B, P).
+//! ```
+//!
+//! Universal regions are live at every point. Unlike the location-insensitive
+//! NLL analysis, a subset relation only holds at the points it flows to, so a
+//! borrow that is returned on one path does not stay live on the others.
+//!
+//! Each rule is evaluated to a fixpoint with a worklist of points: whenever
+//! the relations at a point grow, its successors are revisited.
+
+use borrow_check::location::{LocationIndex, LocationTable};
+use borrow_check::nll::facts::AllFacts;
+use dataflow::indexes::BorrowIndex;
+use rustc::ty::RegionVid;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+
+/// The result of solving the borrow check facts: the borrows that are
+/// invalidated while live, indexed by the point at which this happens.
+crate struct PoloniusOutput {
+    errors: FxHashMap<LocationIndex, Vec<BorrowIndex>>,
+}
+
+impl PoloniusOutput {
+    crate fn compute(all_facts: &AllFacts, location_table: &LocationTable) -> PoloniusOutput {
+        Solver::new(all_facts, location_table).solve()
+    }
+
+    /// Converts the output of the `polonius_engine` crate, which is used
+    /// instead of the in-tree solver when `POLONIUS_ALGORITHM` is set.
+    crate fn from_errors_at<'a>(
+        location_table: &LocationTable,
+        errors_at: impl Fn(LocationIndex) -> &'a [BorrowIndex],
+    ) -> PoloniusOutput {
+        let errors = location_table
+            .all_points()
+            .filter_map(|point| {
+                let errors = errors_at(point);
+                if errors.is_empty() {
+                    None
+                } else {
+                    Some((point, errors.to_vec()))
+                }
+            })
+            .collect();
+        PoloniusOutput { errors }
+    }
+
+    crate fn errors_at(&self, location: LocationIndex) -> &[BorrowIndex] {
+        match self.errors.get(&location) {
+            Some(errors) => errors,
+            None => &[],
+        }
+    }
+}
+
+struct Solver<'a> {
+    all_facts: &'a AllFacts,
+    successors: FxHashMap<LocationIndex, Vec<LocationIndex>>,
+    region_live_at: FxHashSet<(RegionVid, LocationIndex)>,
+    killed: FxHashSet<(BorrowIndex, LocationIndex)>,
+}
+
+type Relation<T> = FxHashMap<LocationIndex, FxHashMap<RegionVid, FxHashSet<T>>>;
+
+impl<'a> Solver<'a> {
+    fn new(all_facts: &'a AllFacts, location_table: &LocationTable) -> Self {
+        let mut successors: FxHashMap<_, Vec<_>> = FxHashMap::default();
+        for &(p, q) in &all_facts.cfg_edge {
+            successors.entry(p).or_default().push(q);
+        }
+
+        let mut region_live_at: FxHashSet<_> =
+            all_facts.region_live_at.iter().cloned().collect();
+        for &r in &all_facts.universal_region {
+            region_live_at.extend(location_table.all_points().map(|p| (r, p)));
+        }
+
+        Solver {
+            all_facts,
+            successors,
+            region_live_at,
+            killed: all_facts.killed.iter().cloned().collect(),
+        }
+    }
+
+    fn solve(&self) -> PoloniusOutput {
+        let subset = self.compute_subset();
+        let requires = self.compute_requires(&subset);
+
+        let mut errors: FxHashMap<_, Vec<_>> = FxHashMap::default();
+        for &(point, borrow) in &self.all_facts.invalidates {
+            let live = requires.get(&point).map_or(false, |requires| {
+                requires.iter().any(|(&r, borrows)| {
+                    borrows.contains(&borrow) && self.region_live_at.contains(&(r, point))
+                })
+            });
+            if live {
+                errors.entry(point).or_default().push(borrow);
+            }
+        }
+        PoloniusOutput { errors }
+    }
+
+    /// Computes `subset(R1, R2, P)`, transitively closed at each point.
+    fn compute_subset(&self) -> Relation<RegionVid> {
+        let mut subset: Relation<RegionVid> = FxHashMap::default();
+        let mut worklist = vec![];
+        for &(r1, r2, p) in &self.all_facts.outlives {
+            if subset.entry(p).or_default().entry(r1).or_default().insert(r2) {
+                worklist.push(p);
+            }
+        }
+
+        while let Some(p) = worklist.pop() {
+            let at_p = match subset.get_mut(&p) {
+                Some(at_p) => {
+                    transitive_closure(at_p);
+                    at_p.clone()
+                }
+                None => continue,
+            };
+            for &q in self.successors.get(&p).into_iter().flatten() {
+                let mut changed = false;
+                for (&r1, r2s) in &at_p {
+                    if !self.region_live_at.contains(&(r1, q)) {
+                        continue;
+                    }
+                    for &r2 in r2s {
+                        if self.region_live_at.contains(&(r2, q)) {
+                            changed |= subset.entry(q).or_default().entry(r1).or_default()
+                                .insert(r2);
+                        }
+                    }
+                }
+                if changed {
+                    worklist.push(q);
+                }
+            }
+        }
+        subset
+    }
+
+    /// Computes `requires(R, B, P)`.
+    fn compute_requires(&self, subset: &Relation<RegionVid>) -> Relation<BorrowIndex> {
+        let mut requires: Relation<BorrowIndex> = FxHashMap::default();
+        let mut worklist = vec![];
+        for &(r, b, p) in &self.all_facts.borrow_region {
+            if requires.entry(p).or_default().entry(r).or_default().insert(b) {
+                worklist.push(p);
+            }
+        }
+
+        while let Some(p) = worklist.pop() {
+            let at_p = match requires.get_mut(&p) {
+                Some(at_p) => {
+                    if let Some(subset) = subset.get(&p) {
+                        // `subset` is transitively closed, so one step suffices.
+                        let flowing: Vec<_> = at_p
+                            .iter()
+                            .filter_map(|(r1, borrows)| {
+                                subset.get(r1).map(|r2s| (r2s, borrows.clone()))
+                            })
+                            .collect();
+                        for (r2s, borrows) in flowing {
+                            for &r2 in r2s {
+                                at_p.entry(r2).or_default().extend(borrows.iter().cloned());
+                            }
+                        }
+                    }
+                    at_p.clone()
+                }
+                None => continue,
+            };
+            for &q in self.successors.get(&p).into_iter().flatten() {
+                let mut changed = false;
+                for (&r, borrows) in &at_p {
+                    if !self.region_live_at.contains(&(r, q)) {
+                        continue;
+                    }
+                    for &b in borrows {
+                        if !self.killed.contains(&(b, p)) {
+                            changed |= requires.entry(q).or_default().entry(r).or_default()
+                                .insert(b);
+                        }
+                    }
+                }
+                if changed {
+                    worklist.push(q);
+                }
+            }
+        }
+        requires
+    }
+}
+
+/// Closes the subset relation at a single point under transitivity.
+fn transitive_closure(subset: &mut FxHashMap<RegionVid, FxHashSet<RegionVid>>) {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let regions: Vec<_> = subset.keys().cloned().collect();
+        for r1 in regions {
+            let reachable: Vec<_> = subset[&r1]
+                .iter()
+                .filter_map(|r2| subset.get(r2))
+                .flat_map(|r3s| r3s.iter().cloned())
+                .collect();
+            let r1s = subset.get_mut(&r1).unwrap();
+            for r3 in reachable {
+                changed |= r1s.insert(r3);
+            }
+        }
+    }
+}
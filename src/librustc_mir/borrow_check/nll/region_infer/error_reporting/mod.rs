@@ -13,17 +13,22 @@ use borrow_check::nll::region_infer::RegionInferenceContext;
 use borrow_check::nll::type_check::Locations;
 use borrow_check::nll::universal_regions::DefiningTy;
 use borrow_check::nll::ConstraintDescription;
+use rustc::hir;
 use rustc::hir::def_id::DefId;
+use rustc::hir::{GenericParamKind, LifetimeParamKind};
+use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc::infer::error_reporting::nice_region_error::NiceRegionError;
 use rustc::infer::InferCtxt;
 use rustc::infer::NLLRegionVariableOrigin;
+use rustc::middle::resolve_lifetime as rl;
 use rustc::mir::{ConstraintCategory, Location, Mir};
-use rustc::ty::{self, RegionVid};
+use rustc::ty::{self, RegionVid, TyCtxt};
+use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_errors::{Diagnostic, DiagnosticBuilder};
 use std::collections::VecDeque;
 use syntax::errors::Applicability;
-use syntax::symbol::keywords;
+use syntax::symbol::{keywords, Symbol};
 use syntax_pos::Span;
 use util::borrowck_errors::{BorrowckErrors, Origin};
 
@@ -555,11 +560,168 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             }
         }
 
+        self.add_named_lifetime_suggestion(
+            infcx,
+            mir_def_id,
+            &mut diag,
+            fr,
+            &fr_name,
+            outlived_fr,
+            &outlived_fr_name,
+        );
         self.add_static_impl_trait_suggestion(infcx, &mut diag, fr, fr_name, outlived_fr);
 
         diag.buffer(errors_buffer);
     }
 
+    /// Adds a suggestion to errors between two anonymous lifetimes of the
+    /// function's signature, giving both of them the same name.
+    ///
+    /// ```text
+    /// help: consider introducing a named lifetime `'a` to use for both `'1` and `'2`
+    ///    |
+    /// LL |     fn get<'a>(&'a self, x: &'a str) -> &'a str {
+    ///    |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    /// ```
+    fn add_named_lifetime_suggestion(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+        diag: &mut DiagnosticBuilder<'_>,
+        fr: RegionVid,
+        fr_name: &RegionName,
+        outlived_fr: RegionVid,
+        outlived_fr_name: &RegionName,
+    ) {
+        let tcx = infcx.tcx;
+        let anon_index = |r| match self.to_error_region(r) {
+            Some(&ty::ReFree(ty::FreeRegion {
+                scope,
+                bound_region: ty::BrAnon(index),
+            })) if scope == mir_def_id => Some(index),
+            _ => None,
+        };
+        let anon_indices = match (anon_index(fr), anon_index(outlived_fr)) {
+            (Some(fr_index), Some(outlived_index)) if fr_index != outlived_index => {
+                [fr_index, outlived_index]
+            }
+            _ => return,
+        };
+
+        let node_id = match tcx.hir().as_local_node_id(mir_def_id) {
+            Some(node_id) => node_id,
+            None => return,
+        };
+        let (decl, generics, name_span, span) = match tcx.hir().get(node_id) {
+            hir::Node::Item(hir::Item {
+                node: hir::ItemKind::Fn(decl, _, generics, _),
+                ident,
+                span,
+                ..
+            }) => (decl, generics, ident.span, *span),
+            hir::Node::ImplItem(hir::ImplItem {
+                node: hir::ImplItemKind::Method(sig, _),
+                generics,
+                ident,
+                span,
+                ..
+            })
+            | hir::Node::TraitItem(hir::TraitItem {
+                node: hir::TraitItemKind::Method(sig, _),
+                generics,
+                ident,
+                span,
+                ..
+            }) => (&sig.decl, generics, ident.span, *span),
+            _ => return,
+        };
+
+        // Pick a name that is not already in scope, looking at the function's
+        // own parameters (late-bound lifetimes are not part of `generics_of`)
+        // and at those of its parents.
+        let mut taken = FxHashSet::default();
+        for param in &generics.params {
+            taken.insert(param.name.ident().as_interned_str());
+        }
+        let mut parent = tcx.generics_of(mir_def_id).parent;
+        while let Some(parent_def_id) = parent {
+            let parent_generics = tcx.generics_of(parent_def_id);
+            taken.extend(parent_generics.params.iter().map(|param| param.name));
+            parent = parent_generics.parent;
+        }
+        let lifetime_name = match (b'a'..=b'z')
+            .map(|c| format!("'{}", c as char))
+            .find(|name| !taken.contains(&Symbol::intern(name).as_interned_str()))
+        {
+            Some(name) => name,
+            None => return,
+        };
+
+        let mut namer = AnonLifetimeNamer {
+            tcx,
+            anon_indices,
+            lifetime_name: &lifetime_name,
+            current_index: ty::INNERMOST,
+            edits: vec![],
+            ambiguous: false,
+        };
+        intravisit::walk_fn_decl(&mut namer, decl);
+        if namer.edits.is_empty() {
+            return;
+        }
+        let mut edits = namer.edits;
+        let ambiguous = namer.ambiguous;
+
+        // Parameters synthesized for `impl Trait` arguments and in-band lifetimes
+        // don't show up in the written generics, so don't insert next to them.
+        // In-band lifetimes can't be mixed with explicitly declared ones at all.
+        let mut written_params = vec![];
+        for param in &generics.params {
+            match param.kind {
+                GenericParamKind::Lifetime { kind: LifetimeParamKind::InBand } => return,
+                GenericParamKind::Lifetime { kind: LifetimeParamKind::Explicit } |
+                GenericParamKind::Type { synthetic: None, .. } => written_params.push(param),
+                _ => {}
+            }
+        }
+        edits.push(match written_params.first() {
+            Some(param) => (param.span.shrink_to_lo(), format!("{}, ", lifetime_name)),
+            None => (name_span.shrink_to_hi(), format!("<{}>", lifetime_name)),
+        });
+
+        // Rewrite the signature as a whole, so that the suggestion reads as
+        // the function header the user should end up with.
+        let source_map = tcx.sess.source_map();
+        let sig_span = source_map.def_span(span);
+        if edits.iter().any(|(edit_span, _)| !sig_span.contains(*edit_span)) {
+            return;
+        }
+        let mut sig = match source_map.span_to_snippet(sig_span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        edits.sort_by_key(|(edit_span, _)| edit_span.lo());
+        for (edit_span, replacement) in edits.into_iter().rev() {
+            let lo = (edit_span.lo() - sig_span.lo()).0 as usize;
+            let hi = (edit_span.hi() - sig_span.lo()).0 as usize;
+            sig.replace_range(lo..hi, &replacement);
+        }
+
+        diag.span_suggestion_with_applicability(
+            sig_span,
+            &format!(
+                "consider introducing a named lifetime `{}` to use for both `{}` and `{}`",
+                lifetime_name, fr_name, outlived_fr_name,
+            ),
+            sig,
+            if ambiguous {
+                Applicability::MaybeIncorrect
+            } else {
+                Applicability::MachineApplicable
+            },
+        );
+    }
+
     /// Adds a suggestion to errors where a `impl Trait` is returned.
     ///
     /// ```text
@@ -767,3 +929,73 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         }
     }
 }
+
+/// Collects the edits that give the anonymous lifetimes `anon_indices` of a
+/// function signature the name `lifetime_name`.
+struct AnonLifetimeNamer<'a, 'gcx: 'tcx, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    anon_indices: [u32; 2],
+    lifetime_name: &'a str,
+    current_index: ty::DebruijnIndex,
+    edits: Vec<(Span, String)>,
+    /// Set if one of the lifetimes is elided somewhere it has no syntax we
+    /// can rewrite, such as the implicit lifetime parameter of a path.
+    ambiguous: bool,
+}
+
+impl<'a, 'gcx, 'tcx> AnonLifetimeNamer<'a, 'gcx, 'tcx> {
+    fn is_renamed(&self, lifetime: &hir::Lifetime) -> bool {
+        let hir_id = self.tcx.hir().node_to_hir_id(lifetime.id);
+        match self.tcx.named_region(hir_id) {
+            Some(rl::Region::LateBoundAnon(debruijn_index, anon_index)) => {
+                debruijn_index == self.current_index && self.anon_indices.contains(&anon_index)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a, 'gcx, 'tcx, 'v> Visitor<'v> for AnonLifetimeNamer<'a, 'gcx, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_ty(&mut self, ty: &'v hir::Ty) {
+        match ty.node {
+            hir::TyKind::Rptr(ref lifetime, ref mut_ty)
+                if lifetime.name == hir::LifetimeName::Implicit =>
+            {
+                if self.is_renamed(lifetime) {
+                    let ampersand_span = self.tcx.sess.source_map().start_point(ty.span);
+                    self.edits.push((ampersand_span, format!("&{} ", self.lifetime_name)));
+                }
+                self.visit_ty(&mut_ty.ty);
+            }
+            hir::TyKind::BareFn(_) => {
+                self.current_index.shift_in(1);
+                intravisit::walk_ty(self, ty);
+                self.current_index.shift_out(1);
+            }
+            hir::TyKind::TraitObject(ref bounds, ref lifetime) => {
+                for bound in bounds {
+                    self.current_index.shift_in(1);
+                    self.visit_poly_trait_ref(bound, hir::TraitBoundModifier::None);
+                    self.current_index.shift_out(1);
+                }
+                self.visit_lifetime(lifetime);
+            }
+            _ => intravisit::walk_ty(self, ty),
+        }
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &'v hir::Lifetime) {
+        if self.is_renamed(lifetime) {
+            match lifetime.name {
+                hir::LifetimeName::Underscore => {
+                    self.edits.push((lifetime.span, self.lifetime_name.to_string()));
+                }
+                _ => self.ambiguous = true,
+            }
+        }
+    }
+}
@@ -1317,6 +1317,7 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             | StatementKind::InlineAsm { .. }
             | StatementKind::Retag { .. }
             | StatementKind::EscapeToRaw { .. }
+            | StatementKind::CoverageCounter { .. }
             | StatementKind::Nop => {}
         }
     }
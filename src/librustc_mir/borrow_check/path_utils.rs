@@ -61,14 +61,17 @@ pub(super) fn each_borrow_involving_path<'a, 'tcx, 'gcx: 'tcx, F, I, S> (
     for i in candidates {
         let borrowed = &borrow_set[i];
 
-        if places_conflict::borrow_conflicts_with_place(
-            tcx,
-            mir,
-            &borrowed.borrowed_place,
-            borrowed.kind,
-            place,
-            access,
-        ) {
+        let conflicts = borrowed.accessed_places().iter().any(|borrowed_place| {
+            places_conflict::borrow_conflicts_with_place(
+                tcx,
+                mir,
+                borrowed_place,
+                borrowed.kind,
+                place,
+                access,
+            )
+        });
+        if conflicts {
             debug!(
                 "each_borrow_involving_path: {:?} @ {:?} vs. {:?}/{:?}",
                 i, borrowed, place, access
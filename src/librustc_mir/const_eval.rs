@@ -25,7 +25,7 @@ use rustc::ty::layout::{self, LayoutOf, TyLayout, VariantIdx};
 use rustc::ty::subst::Subst;
 use rustc::traits::Reveal;
 use rustc_data_structures::indexed_vec::IndexVec;
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc::util::common::ErrorReported;
 
 use syntax::ast::Mutability;
@@ -34,7 +34,7 @@ use syntax::source_map::{Span, DUMMY_SP};
 use crate::interpret::{self,
     PlaceTy, MPlaceTy, MemPlace, OpTy, Operand, Immediate, Scalar, RawConst, ConstValue, Pointer,
     EvalResult, EvalError, EvalErrorKind, GlobalId, EvalContext, StackPopCleanup,
-    Allocation, AllocId, MemoryKind,
+    Allocation, AllocId, AllocMap, Memory, MemoryKind,
     snapshot, RefTracking,
 };
 
@@ -45,6 +45,33 @@ const STEPS_UNTIL_DETECTOR_ENABLED: isize = 1_000_000;
 /// Should be a power of two for performance reasons.
 const DETECTOR_SNAPSHOT_PERIOD: isize = 256;
 
+/// Whether the call currently being evaluated is a `dyn Trait` method call, i.e. its callee
+/// resolves to `InstanceDef::Virtual` and the actual function was taken from a vtable.
+fn is_virtual_call<'a, 'mir, 'tcx>(ecx: &CompileTimeEvalContext<'a, 'mir, 'tcx>) -> bool {
+    let frame = match ecx.stack.last() {
+        Some(frame) => frame,
+        None => return false,
+    };
+    let func = match frame.mir[frame.block].terminator().kind {
+        mir::TerminatorKind::Call { ref func, .. } => func,
+        _ => return false,
+    };
+    match func.ty(frame.mir, *ecx.tcx).sty {
+        ty::FnDef(def_id, substs) => {
+            let substs = ecx.tcx.subst_and_normalize_erasing_regions(
+                frame.instance.substs,
+                ecx.param_env,
+                &substs,
+            );
+            match ty::Instance::resolve(*ecx.tcx, ecx.param_env, def_id, substs) {
+                Some(Instance { def: ty::InstanceDef::Virtual(..), .. }) => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 pub fn mk_borrowck_eval_cx<'a, 'mir, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     instance: Instance<'tcx>,
@@ -61,6 +88,7 @@ pub fn mk_borrowck_eval_cx<'a, 'mir, 'tcx>(
         locals: IndexVec::new(),
         instance,
         span,
+        promoted: None,
         mir,
         return_place: None,
         return_to_block: StackPopCleanup::Goto(None), // never pop
@@ -200,24 +228,88 @@ fn eval_body_using_ecx<'mir, 'tcx>(
         Some(ret.into()),
         StackPopCleanup::None { cleanup: false },
     )?;
+    ecx.frame_mut().promoted = cid.promoted;
 
     // The main interpreter loop.
     ecx.run()?;
 
     // Intern the result
-    let internally_mutable = !layout.ty.is_freeze(tcx, param_env, mir.span);
+    let internally_mutable = has_interior_mut(
+        tcx,
+        param_env,
+        layout.ty,
+        mir.span,
+        &mut FxHashSet::default(),
+    );
     let is_static = tcx.is_static(cid.instance.def_id());
     let mutability = if is_static == Some(hir::Mutability::MutMutable) || internally_mutable {
         Mutability::Mutable
     } else {
         Mutability::Immutable
     };
-    ecx.memory.intern_static(ret.ptr.to_ptr()?.alloc_id, mutability)?;
+    let ret_alloc = ret.ptr.to_ptr()?.alloc_id;
+    ecx.memory.check_dangling_pointers(ret_alloc)?;
+    // Heap memory in the final value is owned by it: every use of a constant, or a write
+    // to a mutable static, could free or reallocate memory that belongs to the compiler.
+    if mutability == Mutability::Mutable || is_static != Some(hir::Mutability::MutImmutable) {
+        let heap = MemoryKind::Machine(ConstMemoryKind::Heap);
+        if ecx.memory.reaches_memory_kind(ret_alloc, heap) {
+            return err!(MutableHeapAllocation);
+        }
+    }
+    ecx.memory.intern_static(ret_alloc, mutability)?;
+
+    // Interning moved everything reachable from the final value into `tcx`, so any heap
+    // memory still owned by the machine has been leaked.
+    let leaked = ecx.memory.alloc_map().filter_map_collect(|&id, &(kind, _)| match kind {
+        MemoryKind::Machine(ConstMemoryKind::Heap) => Some(id),
+        MemoryKind::Stack | MemoryKind::Vtable => None,
+    });
+    if !leaked.is_empty() {
+        ecx.memory.dump_allocs(leaked);
+        return err!(LeakedAllocation);
+    }
 
     debug!("eval_body_using_ecx done: {:?}", *ret);
     Ok(ret)
 }
 
+/// Whether there may be an `UnsafeCell` in a value of type `ty` or in any memory reachable
+/// from it.  `is_freeze` stops at pointers, but interning does not: everything the final
+/// value points to ends up with the mutability we pick here.
+fn has_interior_mut<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    ty: ty::Ty<'tcx>,
+    span: Span,
+    visited: &mut FxHashSet<ty::Ty<'tcx>>,
+) -> bool {
+    if !visited.insert(ty) {
+        return false;
+    }
+    match ty.sty {
+        // Trait objects and type parameters are never `Freeze`, even though the value behind
+        // them usually is. Borrowing a value containing an `UnsafeCell` is rejected (E0492)
+        // unless `const_mut_refs` is enabled, so only then there may be one.
+        ty::Dynamic(..) | ty::Param(_) => return tcx.features().const_mut_refs,
+        _ => {}
+    }
+    if !ty.is_freeze(tcx, param_env, span) {
+        return true;
+    }
+    match ty.sty {
+        ty::Ref(_, pointee, _) |
+        ty::RawPtr(ty::TypeAndMut { ty: pointee, .. }) |
+        ty::Array(pointee, _) |
+        ty::Slice(pointee) => has_interior_mut(tcx, param_env, pointee, span, visited),
+        ty::Tuple(tys) => tys.iter().any(|&ty| has_interior_mut(tcx, param_env, ty, span, visited)),
+        ty::Adt(def, substs) => def.all_fields().any(|field| {
+            has_interior_mut(tcx, param_env, field.ty(tcx, substs), span, visited)
+        }),
+        _ => false,
+    }
+}
+
 impl<'tcx> Into<EvalError<'tcx>> for ConstEvalError {
     fn into(self) -> EvalError<'tcx> {
         EvalErrorKind::MachineError(self.to_string()).into()
@@ -265,6 +357,10 @@ pub struct CompileTimeInterpreter<'a, 'mir, 'tcx: 'a+'mir> {
     /// detector period.
     pub(super) steps_since_detector_enabled: isize,
 
+    /// The total number of interpreter steps taken so far, checked against
+    /// `-Z const-eval-step-limit`.
+    pub(super) steps_taken: usize,
+
     /// Extra state to detect loops.
     pub(super) loop_detector: snapshot::InfiniteLoopDetector<'a, 'mir, 'tcx>,
 }
@@ -274,6 +370,24 @@ impl<'a, 'mir, 'tcx> CompileTimeInterpreter<'a, 'mir, 'tcx> {
         CompileTimeInterpreter {
             loop_detector: Default::default(),
             steps_since_detector_enabled: -STEPS_UNTIL_DETECTOR_ENABLED,
+            steps_taken: 0,
+        }
+    }
+}
+
+/// Memory kinds specific to the CTFE machine
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum ConstMemoryKind {
+    /// Memory allocated via `box` while evaluating a constant
+    Heap,
+}
+
+impl interpret::MayLeak for ConstMemoryKind {
+    #[inline(always)]
+    fn may_leak(self) -> bool {
+        match self {
+            // Heap memory must either be freed or end up in the final value
+            ConstMemoryKind::Heap => false,
         }
     }
 }
@@ -353,20 +467,45 @@ impl interpret::MayLeak for ! {
 impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
     for CompileTimeInterpreter<'a, 'mir, 'tcx>
 {
-    type MemoryKinds = !;
+    type MemoryKinds = ConstMemoryKind;
     type PointerTag = ();
 
     type FrameExtra = ();
     type MemoryExtra = ();
     type AllocExtra = ();
 
-    type MemoryMap = FxHashMap<AllocId, (MemoryKind<!>, Allocation)>;
+    type MemoryMap = FxHashMap<AllocId, (MemoryKind<ConstMemoryKind>, Allocation)>;
+
+    const STATIC_KIND: Option<ConstMemoryKind> = None; // no copying of statics allowed
 
-    const STATIC_KIND: Option<!> = None; // no copying of statics allowed
+    #[inline(always)]
+    fn enforce_validity(ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
+        // for now, we only enforce validity on request
+        ecx.tcx.sess.opts.debugging_opts.force_const_eval_validation
+    }
 
     #[inline(always)]
-    fn enforce_validity(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
-        false // for now, we don't enforce validity
+    fn enforce_alignment(_memory: &Memory<'a, 'mir, 'tcx, Self>) -> bool {
+        // Validation of the final value relies on this to reject unaligned references.
+        true
+    }
+
+    #[inline(always)]
+    fn enforce_inbounds(_memory: &Memory<'a, 'mir, 'tcx, Self>) -> bool {
+        // Validation of the final value relies on this to reject dangling references.
+        true
+    }
+
+    #[inline(always)]
+    fn check_union_reads(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
+        // Report reads of undefined bytes at the offending expression, instead of only
+        // noticing them when validating the final value.
+        true
+    }
+
+    #[inline(always)]
+    fn memory_limit(tcx: TyCtxtAt<'a, 'tcx, 'tcx>) -> Option<u64> {
+        tcx.sess.opts.debugging_opts.const_eval_memory_limit.map(|limit| limit as u64)
     }
 
     fn find_fn(
@@ -379,8 +518,16 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
         debug!("eval_fn_call: {:?}", instance);
         // Execution might have wandered off into other crates, so we cannot to a stability-
         // sensitive check here.  But we can at least rule out functions that are not const
-        // at all.
+        // at all. With `const_dyn_dispatch`, this also applies to the impl method picked
+        // from the vtable of a `dyn Trait` method call.
         if !ecx.tcx.is_const_fn_raw(instance.def_id()) {
+            // Const checking could not see which method a `dyn Trait` call ends up in.
+            if is_virtual_call(ecx) {
+                return err!(MachineError(format!(
+                    "calling non-const function `{}` through a vtable",
+                    instance,
+                )));
+            }
             // Some functions we support even if they are non-const -- but avoid testing
             // that for const fn!  We certainly do *not* want to actually call the fn
             // though, so be sure we return here.
@@ -423,16 +570,46 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
     }
 
     fn ptr_op(
-        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
-        _bin_op: mir::BinOp,
-        _left: Scalar,
-        _left_layout: TyLayout<'tcx>,
-        _right: Scalar,
-        _right_layout: TyLayout<'tcx>,
+        ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        bin_op: mir::BinOp,
+        left: Scalar,
+        left_layout: TyLayout<'tcx>,
+        right: Scalar,
+        right_layout: TyLayout<'tcx>,
     ) -> EvalResult<'tcx, (Scalar, bool)> {
-        Err(
-            ConstEvalError::NeedsRfc("pointer arithmetic or comparison".to_string()).into(),
-        )
+        let want_equal = match bin_op {
+            mir::BinOp::Eq => true,
+            mir::BinOp::Ne => false,
+            mir::BinOp::Offset => {
+                let pointee_ty = left_layout.ty
+                    .builtin_deref(true)
+                    .expect("Offset called on non-ptr type")
+                    .ty;
+                let count = right.to_isize(ecx)?;
+                return Ok((ecx.pointer_offset_inbounds(left, pointee_ty, count)?, false));
+            }
+            // A pointer stored at integer type (via `transmute`, a union or a cast) has no
+            // address we could compute with, so say so instead of asking for an RFC.
+            _ if (left.is_ptr() && left_layout.ty.is_integral()) ||
+                (right.is_ptr() && right_layout.ty.is_integral()) =>
+                return err!(PointerAsInteger(bin_op)),
+            _ => return Err(
+                ConstEvalError::NeedsRfc("pointer arithmetic or comparison".to_string()).into(),
+            ),
+        };
+        let equal = match (left, right) {
+            // Pointers into the same allocation are equal iff their offsets are.
+            (Scalar::Ptr(left), Scalar::Ptr(right)) if left.alloc_id == right.alloc_id =>
+                left.offset == right.offset,
+            // A pointer that is in-bounds (or one past the end) of its allocation is never null.
+            (Scalar::Ptr(ptr), Scalar::Bits { bits: 0, .. }) |
+            (Scalar::Bits { bits: 0, .. }, Scalar::Ptr(ptr))
+                if ptr.offset <= ecx.memory().get_size_and_align(ptr.alloc_id).0 => false,
+            // Everything else depends on where allocations end up at runtime, which we do not
+            // know. In particular, distinct allocations may or may not get deduplicated.
+            _ => return err!(InvalidPointerComparison),
+        };
+        Ok((Scalar::from_bool(equal == want_equal), false))
     }
 
     fn find_foreign_static(
@@ -453,15 +630,39 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
     }
 
     fn box_alloc(
-        _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
-        _dest: PlaceTy<'tcx>,
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx> {
-        Err(
-            ConstEvalError::NeedsRfc("heap allocations via `box` keyword".to_string()).into(),
-        )
+        // `dest` is the `Box<T>`, so we need to make room for a `T`
+        let layout = ecx.layout_of(dest.layout.ty.boxed_ty())?;
+        let ptr = ecx.memory_mut().allocate(
+            layout.size,
+            layout.align.abi,
+            MemoryKind::Machine(ConstMemoryKind::Heap),
+        )?;
+        ecx.write_scalar(Scalar::Ptr(ptr), dest)
+    }
+
+    fn box_free(
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        ptr: OpTy<'tcx>,
+    ) -> EvalResult<'tcx, bool> {
+        // Drop the metadata of fat pointers, we only need the address
+        let ptr = ecx.read_immediate(ptr)?.to_scalar_ptr()?.to_ptr()?;
+        ecx.memory_mut().deallocate(ptr, None, MemoryKind::Machine(ConstMemoryKind::Heap))?;
+        Ok(true)
     }
 
     fn before_terminator(ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>) -> EvalResult<'tcx> {
+        // The step limit only depends on the evaluated code, so hitting it is deterministic
+        // (unlike a timeout) and the result can safely be cached.
+        ecx.machine.steps_taken += 1;
+        if let Some(limit) = ecx.tcx.sess.opts.debugging_opts.const_eval_step_limit {
+            if ecx.machine.steps_taken > limit {
+                return err!(StepLimitReached);
+            }
+        }
+
         {
             let steps = &mut ecx.machine.steps_since_detector_enabled;
 
@@ -576,6 +777,10 @@ fn validate_and_turn_into_const<'a, 'tcx>(
         // FIXME: Once the visitor infrastructure landed, change validation to
         // work directly on `MPlaceTy`.
         let mut ref_tracking = RefTracking::new(op);
+        // Only statics may hand out `&mut` to their memory: every use of a constant is a
+        // copy of the same bytes, so a `&mut` in there would be shared by all of them.
+        let def_id = cid.instance.def.def_id();
+        ref_tracking.allow_mut_refs = tcx.is_static(def_id).is_some();
         while let Some((op, path)) = ref_tracking.todo.pop() {
             ecx.validate_operand(
                 op,
@@ -585,13 +790,20 @@ fn validate_and_turn_into_const<'a, 'tcx>(
             )?;
         }
         // Now that we validated, turn this into a proper constant
-        let def_id = cid.instance.def.def_id();
         let normalize = tcx.is_static(def_id).is_none() && cid.promoted.is_none();
         op_to_const(&ecx, op, normalize)
     })();
 
     val.map_err(|error| {
+        let escaping_mut_ref = match error.kind {
+            EvalErrorKind::EscapingMutRef => true,
+            _ => false,
+        };
         let err = error_to_const_error(&ecx, error);
+        if escaping_mut_ref {
+            // Not undefined behavior, just something constants cannot express.
+            return err.report_as_error(ecx.tcx, "any use of this value will cause an error");
+        }
         match err.struct_error(ecx.tcx, "it is undefined behavior to use this value") {
             Ok(mut diag) => {
                 diag.note("The rules on what exactly is undefined behavior aren't clear, \
@@ -625,6 +837,14 @@ pub fn const_eval_provider<'a, 'tcx>(
             other => return other,
         }
     }
+    // Upstream crates export the values of their `const` items, so we do not have to
+    // evaluate them again.
+    let def_id = key.value.instance.def.def_id();
+    if !def_id.is_local() && key.value.promoted.is_none() && key.value.instance.substs.is_empty() {
+        if let Some(val) = tcx.extern_const_value(def_id) {
+            return Ok(ty::Const::from_const_value(tcx, val, tcx.type_of(def_id)));
+        }
+    }
     tcx.const_eval_raw(key).and_then(|val| {
         validate_and_turn_into_const(tcx, val, key)
     })
@@ -689,6 +909,10 @@ pub fn const_eval_raw_provider<'a, 'tcx>(
             ty: place.layout.ty
         })
     }).map_err(|error| {
+        let mutable_heap_allocation = match error.kind {
+            EvalErrorKind::MutableHeapAllocation => true,
+            _ => false,
+        };
         let err = error_to_const_error(&ecx, error);
         // errors in statics are always emitted as fatal errors
         if tcx.is_static(def_id).is_some() {
@@ -707,6 +931,16 @@ pub fn const_eval_raw_provider<'a, 'tcx>(
                 // note that validation may still cause a hard error on this very same constant,
                 // because any code that existed before validation could not have failed validation
                 // thus preventing such a hard error from being a backwards compatibility hazard
+                //
+                // heap memory in constants only became possible with `const_heap`, so there is
+                // no such hazard in rejecting it outright.
+                Some(Def::Const(_)) |
+                Some(Def::AssociatedConst(_)) if mutable_heap_allocation => {
+                    err.report_as_error(
+                        tcx.at(tcx.def_span(def_id)),
+                        "any use of this value will cause an error",
+                    )
+                },
                 Some(Def::Const(_)) | Some(Def::AssociatedConst(_)) => {
                     let node_id = tcx.hir().as_local_node_id(def_id).unwrap();
                     err.report_as_lint(
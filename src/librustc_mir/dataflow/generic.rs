@@ -0,0 +1,466 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A generic dataflow engine.
+//!
+//! Unlike the `BitDenotation` framework in the parent module, which only supports forward
+//! gen/kill problems over bitsets, analyses written against this module choose their own
+//! direction and domain, and describe their transfer functions directly as effects of
+//! statements and terminators on the dataflow state. An analysis implements `Analysis`,
+//! and `Engine` computes its fixpoint with a worklist solver shared by all analyses:
+//!
+//! ```ignore (cross-crate-imports)
+//! let results = Engine::new(tcx, mir, def_id, MaybeLiveLocals).iterate_to_fixpoint();
+//! let live_on_exit = results.entry_set_for_block(bb);
+//! ```
+//!
+//! The domain only has to be a `JoinSemiLattice`. This is implemented for `BitSet` (where
+//! join is union), for `FlatSet` (the usual constant propagation lattice), and pointwise for
+//! `IndexVec`s of lattices.
+//!
+//! The "entry set" of a block is the state on entry to the block *in the direction of the
+//! analysis*: for a backward analysis, it is the state at the end of the block.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc_data_structures::work_queue::WorkQueue;
+
+use dot;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+
+use transform::MirSource;
+use util::pretty::{dump_enabled, dump_path};
+use util::write_graphviz_node_label;
+
+/// A set with a least upper bound ("join") operation, used as the domain of an analysis.
+pub trait JoinSemiLattice: Clone + Eq {
+    /// Replaces `self` with the join of `self` and `other`, and returns whether `self` changed.
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+impl<T: Idx> JoinSemiLattice for BitSet<T> {
+    fn join(&mut self, other: &Self) -> bool {
+        self.union(other)
+    }
+}
+
+impl<I: Idx, T: JoinSemiLattice> JoinSemiLattice for IndexVec<I, T> {
+    fn join(&mut self, other: &Self) -> bool {
+        assert_eq!(self.len(), other.len());
+
+        let mut changed = false;
+        for (a, b) in self.iter_mut().zip(other.iter()) {
+            changed |= a.join(b);
+        }
+        changed
+    }
+}
+
+/// The flat lattice over `T`: `Bottom` means "no value reaches here", `Elem(v)` means "always
+/// `v`", and `Top` means "may be different values".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlatSet<T> {
+    Bottom,
+    Elem(T),
+    Top,
+}
+
+impl<T: Clone + Eq> JoinSemiLattice for FlatSet<T> {
+    fn join(&mut self, other: &Self) -> bool {
+        let result = match (&*self, other) {
+            (_, FlatSet::Bottom) | (FlatSet::Top, _) => return false,
+            (FlatSet::Elem(a), FlatSet::Elem(b)) if a == b => return false,
+            (FlatSet::Bottom, FlatSet::Elem(b)) => FlatSet::Elem(b.clone()),
+            _ => FlatSet::Top,
+        };
+
+        *self = result;
+        true
+    }
+}
+
+/// A dataflow problem, described by its domain, its direction and the effect of each
+/// statement and terminator on the state.
+pub trait Analysis<'tcx> {
+    /// The type of the dataflow state at each point of the MIR.
+    type Domain: JoinSemiLattice + fmt::Debug;
+
+    /// Either `Forward` or `Backward`.
+    type Direction: Direction;
+
+    /// A name for the analysis, used in debug output and `-Z dump-mir-dataflow` files.
+    const NAME: &'static str;
+
+    /// The initial value of every entry set, which must be the bottom of the lattice.
+    fn bottom_value(&self, mir: &Mir<'tcx>) -> Self::Domain;
+
+    /// Mutates the entry set of the start block (for forward analyses), or of every block
+    /// ending in `return` (for backward analyses).
+    fn initialize_start_block(&self, mir: &Mir<'tcx>, state: &mut Self::Domain);
+
+    /// Applies the effect of `statement` on `state`.
+    fn apply_statement_effect(&self,
+                              state: &mut Self::Domain,
+                              statement: &Statement<'tcx>,
+                              location: Location);
+
+    /// Applies the effect of `terminator` on `state`, on all of its outgoing edges.
+    fn apply_terminator_effect(&self,
+                               state: &mut Self::Domain,
+                               terminator: &Terminator<'tcx>,
+                               location: Location);
+
+    /// Applies the effect of a call returning successfully and writing to `dest`, only on the
+    /// edge from the call in `block` to its return block.
+    fn apply_call_return_effect(&self,
+                                _state: &mut Self::Domain,
+                                _block: BasicBlock,
+                                _dest: &Place<'tcx>) {
+    }
+}
+
+/// The direction in which an analysis propagates information through the CFG.
+pub trait Direction {
+    fn is_forward() -> bool;
+
+    /// Applies the effects of the whole of `block` to `state`, in the order they are
+    /// encountered in this direction.
+    fn apply_effects_in_block<'tcx, A>(analysis: &A,
+                                       state: &mut A::Domain,
+                                       block: BasicBlock,
+                                       block_data: &BasicBlockData<'tcx>)
+        where A: Analysis<'tcx>;
+
+    /// Applies the effects of the statements and terminator of `location.block` that come
+    /// before `location` in this direction, starting from the entry set of the block.
+    fn apply_effects_before<'tcx, A>(analysis: &A,
+                                     state: &mut A::Domain,
+                                     block_data: &BasicBlockData<'tcx>,
+                                     location: Location)
+        where A: Analysis<'tcx>;
+
+    /// Calls `propagate` with each block whose entry set `exit_state`, the state after all
+    /// the effects of `block`, flows into.
+    fn join_state_into_successors_of<'tcx, A, F>(analysis: &A,
+                                                 mir: &Mir<'tcx>,
+                                                 exit_state: &mut A::Domain,
+                                                 block: BasicBlock,
+                                                 propagate: F)
+        where A: Analysis<'tcx>,
+              F: FnMut(BasicBlock, &A::Domain);
+}
+
+pub enum Forward {}
+
+impl Direction for Forward {
+    fn is_forward() -> bool {
+        true
+    }
+
+    fn apply_effects_in_block<'tcx, A>(analysis: &A,
+                                       state: &mut A::Domain,
+                                       block: BasicBlock,
+                                       block_data: &BasicBlockData<'tcx>)
+        where A: Analysis<'tcx>
+    {
+        for (statement_index, statement) in block_data.statements.iter().enumerate() {
+            let location = Location { block, statement_index };
+            analysis.apply_statement_effect(state, statement, location);
+        }
+
+        let location = Location { block, statement_index: block_data.statements.len() };
+        analysis.apply_terminator_effect(state, block_data.terminator(), location);
+    }
+
+    fn apply_effects_before<'tcx, A>(analysis: &A,
+                                     state: &mut A::Domain,
+                                     block_data: &BasicBlockData<'tcx>,
+                                     location: Location)
+        where A: Analysis<'tcx>
+    {
+        for statement_index in 0..location.statement_index {
+            let statement = &block_data.statements[statement_index];
+            let location = Location { block: location.block, statement_index };
+            analysis.apply_statement_effect(state, statement, location);
+        }
+    }
+
+    fn join_state_into_successors_of<'tcx, A, F>(analysis: &A,
+                                                 mir: &Mir<'tcx>,
+                                                 exit_state: &mut A::Domain,
+                                                 block: BasicBlock,
+                                                 mut propagate: F)
+        where A: Analysis<'tcx>,
+              F: FnMut(BasicBlock, &A::Domain)
+    {
+        match mir[block].terminator().kind {
+            TerminatorKind::Call { destination: Some((ref dest, target)), cleanup, .. } => {
+                if let Some(cleanup) = cleanup {
+                    propagate(cleanup, exit_state);
+                }
+
+                // The return effect is applied last, as it only holds on the return edge.
+                analysis.apply_call_return_effect(exit_state, block, dest);
+                propagate(target, exit_state);
+            }
+            ref kind => {
+                for &target in kind.successors() {
+                    propagate(target, exit_state);
+                }
+            }
+        }
+    }
+}
+
+pub enum Backward {}
+
+impl Direction for Backward {
+    fn is_forward() -> bool {
+        false
+    }
+
+    fn apply_effects_in_block<'tcx, A>(analysis: &A,
+                                       state: &mut A::Domain,
+                                       block: BasicBlock,
+                                       block_data: &BasicBlockData<'tcx>)
+        where A: Analysis<'tcx>
+    {
+        let location = Location { block, statement_index: block_data.statements.len() };
+        analysis.apply_terminator_effect(state, block_data.terminator(), location);
+
+        for (statement_index, statement) in block_data.statements.iter().enumerate().rev() {
+            let location = Location { block, statement_index };
+            analysis.apply_statement_effect(state, statement, location);
+        }
+    }
+
+    fn apply_effects_before<'tcx, A>(analysis: &A,
+                                     state: &mut A::Domain,
+                                     block_data: &BasicBlockData<'tcx>,
+                                     location: Location)
+        where A: Analysis<'tcx>
+    {
+        let terminator_index = block_data.statements.len();
+        if location.statement_index == terminator_index {
+            return;
+        }
+
+        let terminator_location = Location {
+            block: location.block,
+            statement_index: terminator_index,
+        };
+        analysis.apply_terminator_effect(state, block_data.terminator(), terminator_location);
+
+        for statement_index in (location.statement_index + 1..terminator_index).rev() {
+            let statement = &block_data.statements[statement_index];
+            let location = Location { block: location.block, statement_index };
+            analysis.apply_statement_effect(state, statement, location);
+        }
+    }
+
+    fn join_state_into_successors_of<'tcx, A, F>(analysis: &A,
+                                                 mir: &Mir<'tcx>,
+                                                 exit_state: &mut A::Domain,
+                                                 block: BasicBlock,
+                                                 mut propagate: F)
+        where A: Analysis<'tcx>,
+              F: FnMut(BasicBlock, &A::Domain)
+    {
+        for &pred in mir.predecessors_for(block).iter() {
+            match mir[pred].terminator().kind {
+                // A call's return effect must be undone before reaching the call itself,
+                // but only along the return edge.
+                TerminatorKind::Call { destination: Some((ref dest, target)), .. }
+                    if target == block =>
+                {
+                    let mut state = exit_state.clone();
+                    analysis.apply_call_return_effect(&mut state, pred, dest);
+                    propagate(pred, &state);
+                }
+                _ => propagate(pred, exit_state),
+            }
+        }
+    }
+}
+
+/// Computes the fixpoint of an `Analysis` on a MIR body.
+pub struct Engine<'a, 'gcx: 'tcx, 'tcx: 'a, A>
+    where A: Analysis<'tcx>
+{
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    def_id: DefId,
+    analysis: A,
+    entry_sets: IndexVec<BasicBlock, A::Domain>,
+}
+
+impl<'a, 'gcx, 'tcx, A> Engine<'a, 'gcx, 'tcx, A>
+    where A: Analysis<'tcx>
+{
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>,
+               mir: &'a Mir<'tcx>,
+               def_id: DefId,
+               analysis: A)
+               -> Self {
+        let bottom_value = analysis.bottom_value(mir);
+        let mut entry_sets = IndexVec::from_elem(bottom_value, mir.basic_blocks());
+
+        if A::Direction::is_forward() {
+            analysis.initialize_start_block(mir, &mut entry_sets[START_BLOCK]);
+        } else {
+            for (block, data) in mir.basic_blocks().iter_enumerated() {
+                if let TerminatorKind::Return = data.terminator().kind {
+                    analysis.initialize_start_block(mir, &mut entry_sets[block]);
+                }
+            }
+        }
+
+        Engine { tcx, mir, def_id, analysis, entry_sets }
+    }
+
+    pub fn iterate_to_fixpoint(mut self) -> Results<'tcx, A> {
+        let mir = self.mir;
+        let mut dirty_queue = WorkQueue::with_none(mir.basic_blocks().len());
+
+        // Visiting blocks in reverse postorder (or postorder, when going backwards) means most
+        // blocks are only visited once all of their inputs have been.
+        if A::Direction::is_forward() {
+            for (block, _) in traversal::reverse_postorder(mir) {
+                dirty_queue.insert(block);
+            }
+        } else {
+            for (block, _) in traversal::postorder(mir) {
+                dirty_queue.insert(block);
+            }
+        }
+
+        // Blocks that are unreachable from the start block still get an entry set.
+        for block in mir.basic_blocks().indices() {
+            dirty_queue.insert(block);
+        }
+
+        let mut state = self.analysis.bottom_value(mir);
+        while let Some(block) = dirty_queue.pop() {
+            let block_data = &mir[block];
+            state.clone_from(&self.entry_sets[block]);
+            A::Direction::apply_effects_in_block(&self.analysis, &mut state, block, block_data);
+
+            let entry_sets = &mut self.entry_sets;
+            A::Direction::join_state_into_successors_of(
+                &self.analysis,
+                mir,
+                &mut state,
+                block,
+                |target, state| {
+                    if entry_sets[target].join(state) {
+                        dirty_queue.insert(target);
+                    }
+                },
+            );
+        }
+
+        let results = Results { analysis: self.analysis, entry_sets: self.entry_sets };
+
+        if self.tcx.sess.opts.debugging_opts.dump_mir_dataflow {
+            let source = MirSource::item(self.def_id);
+            if dump_enabled(self.tcx, A::NAME, source) {
+                let path = dump_path(self.tcx, "dot", None, A::NAME, &"dataflow", source);
+                let _: io::Result<()> = try_block! {
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    let mut file = fs::File::create(&path)?;
+                    results.write_graphviz(mir, &mut file)?;
+                };
+            }
+        }
+
+        results
+    }
+}
+
+/// The fixpoint of an `Analysis`, as computed by `Engine`.
+pub struct Results<'tcx, A>
+    where A: Analysis<'tcx>
+{
+    pub analysis: A,
+    entry_sets: IndexVec<BasicBlock, A::Domain>,
+}
+
+impl<'tcx, A> Results<'tcx, A>
+    where A: Analysis<'tcx>
+{
+    /// The state on entry to `block`, in the direction of the analysis.
+    pub fn entry_set_for_block(&self, block: BasicBlock) -> &A::Domain {
+        &self.entry_sets[block]
+    }
+
+    /// The state right before the effect of the statement or terminator at `location` is
+    /// applied, in the direction of the analysis.
+    pub fn state_before(&self, mir: &Mir<'tcx>, location: Location) -> A::Domain {
+        let mut state = self.entry_sets[location.block].clone();
+        A::Direction::apply_effects_before(&self.analysis,
+                                           &mut state,
+                                           &mir[location.block],
+                                           location);
+        state
+    }
+
+    /// Writes a graphviz DOT graph of `mir`, with each block labeled with its entry set and
+    /// the state after the whole block.
+    pub fn write_graphviz<W: Write>(&self, mir: &Mir<'tcx>, w: &mut W) -> io::Result<()> {
+        writeln!(w, "digraph {} {{", A::NAME)?;
+        writeln!(w, r#"    graph [fontname="monospace"];"#)?;
+        writeln!(w, r#"    node [fontname="monospace"];"#)?;
+        writeln!(w, r#"    edge [fontname="monospace"];"#)?;
+
+        let (entry_label, exit_label) = if A::Direction::is_forward() {
+            ("ENTRY", "EXIT")
+        } else {
+            ("EXIT", "ENTRY")
+        };
+
+        for (block, block_data) in mir.basic_blocks().iter_enumerated() {
+            let entry_set = &self.entry_sets[block];
+            let mut exit_set = entry_set.clone();
+            A::Direction::apply_effects_in_block(&self.analysis, &mut exit_set, block, block_data);
+
+            write!(w, r#"    bb{} [shape="none", label=<"#, block.index())?;
+            write_graphviz_node_label(
+                block,
+                mir,
+                w,
+                1,
+                |w| write_state_row(w, entry_label, entry_set),
+                |w| write_state_row(w, exit_label, &exit_set),
+            )?;
+            writeln!(w, ">];")?;
+        }
+
+        for (source, block_data) in mir.basic_blocks().iter_enumerated() {
+            for &target in block_data.terminator().successors() {
+                writeln!(w, "    bb{} -> bb{};", source.index(), target.index())?;
+            }
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+fn write_state_row<W: Write, T: fmt::Debug>(w: &mut W, label: &str, state: &T) -> io::Result<()> {
+    write!(w, r#"<tr><td align="left" balign="left">{}: {}</td></tr>"#,
+           label,
+           dot::escape_html(&format!("{:?}", state)))
+}
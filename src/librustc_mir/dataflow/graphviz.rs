@@ -16,7 +16,7 @@ use rustc::mir::{BasicBlock, Mir};
 use dot;
 
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::marker::PhantomData;
 use std::path::Path;
 
@@ -64,6 +64,43 @@ pub(crate) fn print_borrowck_graph_to<'a, 'tcx, BD, P>(
     fs::write(path, v)
 }
 
+/// Writes the same per-block tables as `print_borrowck_graph_to` as a
+/// standalone HTML page, with links to the successors of each block.
+pub(crate) fn print_borrowck_html_to<'a, 'tcx, BD, P>(
+    mbcx: &DataflowBuilder<'a, 'tcx, BD>,
+    path: &Path,
+    render_idx: P)
+    -> io::Result<()>
+    where BD: BitDenotation,
+          P: Fn(&BD, BD::Idx) -> DebugFormatted
+{
+    let g = Graph { mbcx, phantom: PhantomData, render_idx };
+    let mir = mbcx.mir();
+    let mut v = Vec::new();
+    writeln!(v, "<!DOCTYPE html>")?;
+    writeln!(v, "<html><head><meta charset=\"utf-8\">")?;
+    writeln!(v, "<title>{} for node {}</title>", BD::name(), mbcx.node_id)?;
+    writeln!(v, "<style>td {{ border: 1px solid grey; vertical-align: top; }}</style>")?;
+    writeln!(v, "</head><body>")?;
+    for bb in mir.basic_blocks().indices() {
+        writeln!(v, "<h3 id=\"bb_{}\">{:?}</h3>", bb.index(), bb)?;
+        g.node_label_internal(&bb, &mut v, bb, mir)?;
+        writeln!(v)?;
+        let term = mir[bb].terminator();
+        let labels = term.kind.fmt_successor_labels();
+        write!(v, "<p>")?;
+        for (succ, label) in term.successors().zip(labels.iter()) {
+            write!(v, "{}: <a href=\"#bb_{}\">{:?}</a> ",
+                   dot::escape_html(label), succ.index(), succ)?;
+        }
+        writeln!(v, "</p>")?;
+    }
+    writeln!(v, "</body></html>")?;
+    debug!("print_borrowck_html_to path: {} node_id: {}",
+           path.display(), mbcx.node_id);
+    fs::write(path, v)
+}
+
 pub type Node = BasicBlock;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
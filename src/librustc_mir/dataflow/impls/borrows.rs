@@ -302,6 +302,7 @@ impl<'a, 'gcx, 'tcx> BitDenotation for Borrows<'a, 'gcx, 'tcx> {
             mir::StatementKind::Retag { .. } |
             mir::StatementKind::EscapeToRaw { .. } |
             mir::StatementKind::AscribeUserType(..) |
+            mir::StatementKind::CoverageCounter { .. } |
             mir::StatementKind::Nop => {}
 
         }
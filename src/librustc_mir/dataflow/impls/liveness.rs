@@ -0,0 +1,93 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc_data_structures::bit_set::BitSet;
+
+use dataflow::generic::{Analysis, Backward};
+use util::liveness::{categorize, DefUse};
+
+/// A backward analysis computing the locals that may be read before they are next written to.
+///
+/// Uses in drops count as reads, and borrows only count as a read at the point of the borrow,
+/// like in `util::liveness`.
+#[derive(Copy, Clone)]
+pub struct MaybeLiveLocals;
+
+impl<'tcx> Analysis<'tcx> for MaybeLiveLocals {
+    type Domain = BitSet<Local>;
+    type Direction = Backward;
+
+    const NAME: &'static str = "maybe_live_locals";
+
+    fn bottom_value(&self, mir: &Mir<'tcx>) -> BitSet<Local> {
+        // bottom = dead
+        BitSet::new_empty(mir.local_decls.len())
+    }
+
+    fn initialize_start_block(&self, _mir: &Mir<'tcx>, state: &mut BitSet<Local>) {
+        // `return` reads the return place.
+        state.insert(RETURN_PLACE);
+    }
+
+    fn apply_statement_effect(&self,
+                              state: &mut BitSet<Local>,
+                              statement: &Statement<'tcx>,
+                              location: Location) {
+        TransferFunction::apply(state, |visitor| {
+            visitor.visit_statement(location.block, statement, location);
+        });
+    }
+
+    fn apply_terminator_effect(&self,
+                               state: &mut BitSet<Local>,
+                               terminator: &Terminator<'tcx>,
+                               location: Location) {
+        TransferFunction::apply(state, |visitor| {
+            visitor.visit_terminator(location.block, terminator, location);
+        });
+    }
+}
+
+/// Collects the locals defined and used by a single statement or terminator,
+/// so that a use in the same statement as a def (e.g. `_1 = Add(_1, _2)`)
+/// keeps the local live regardless of the order they are visited in.
+struct TransferFunction {
+    defs: Vec<Local>,
+    uses: Vec<Local>,
+}
+
+impl TransferFunction {
+    fn apply<F>(state: &mut BitSet<Local>, visit: F)
+        where F: FnOnce(&mut TransferFunction)
+    {
+        let mut visitor = TransferFunction { defs: vec![], uses: vec![] };
+        visit(&mut visitor);
+
+        // live = uses ∪ (live - defs)
+        for local in visitor.defs {
+            state.remove(local);
+        }
+        for local in visitor.uses {
+            state.insert(local);
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for TransferFunction {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        match categorize(context) {
+            Some(DefUse::Def) => self.defs.push(local),
+            Some(DefUse::Use) | Some(DefUse::Drop) => self.uses.push(local),
+            None => (),
+        }
+    }
+}
@@ -37,6 +37,10 @@ mod borrowed_locals;
 
 pub use self::borrowed_locals::*;
 
+mod liveness;
+
+pub use self::liveness::MaybeLiveLocals;
+
 pub(super) mod borrows;
 
 /// `MaybeInitializedPlaces` tracks all places that might be
@@ -21,22 +21,28 @@ use rustc::session::Session;
 
 use std::borrow::Borrow;
 use std::fmt;
+use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::usize;
 
+use transform::MirSource;
+use util::pretty::{dump_enabled, dump_path};
+
 pub use self::impls::{MaybeStorageLive};
 pub use self::impls::{MaybeInitializedPlaces, MaybeUninitializedPlaces};
 pub use self::impls::DefinitelyInitializedPlaces;
 pub use self::impls::EverInitializedPlaces;
 pub use self::impls::borrows::Borrows;
 pub use self::impls::HaveBeenBorrowedLocals;
+pub use self::impls::MaybeLiveLocals;
 pub use self::at_location::{FlowAtLocation, FlowsAtLocation};
 pub(crate) use self::drop_flag_effects::*;
 
 use self::move_paths::MoveData;
 
 mod at_location;
+pub mod generic;
 pub mod drop_flag_effects;
 mod graphviz;
 mod impls;
@@ -46,10 +52,12 @@ pub(crate) use self::move_paths::indexes;
 
 pub(crate) struct DataflowBuilder<'a, 'tcx: 'a, BD> where BD: BitDenotation
 {
+    sess: &'a Session,
     node_id: ast::NodeId,
     flow_state: DataflowAnalysis<'a, 'tcx, BD>,
     print_preflow_to: Option<String>,
     print_postflow_to: Option<String>,
+    dump_postflow_to: Option<PathBuf>,
 }
 
 /// `DebugFormatted` encapsulates the "{:?}" rendering of some
@@ -91,9 +99,9 @@ impl<'a, 'tcx: 'a, BD> Dataflow<BD> for DataflowBuilder<'a, 'tcx, BD> where BD:
 {
     fn dataflow<P>(&mut self, p: P) where P: Fn(&BD, BD::Idx) -> DebugFormatted {
         self.flow_state.build_sets();
-        self.pre_dataflow_instrumentation(|c,i| p(c,i)).unwrap();
+        self.pre_dataflow_instrumentation(|c,i| p(c,i));
         self.flow_state.propagate();
-        self.post_dataflow_instrumentation(|c,i| p(c,i)).unwrap();
+        self.post_dataflow_instrumentation(|c,i| p(c,i));
     }
 
     fn build_sets(&mut self) { self.flow_state.build_sets(); }
@@ -122,7 +130,7 @@ pub struct MoveDataParamEnv<'gcx, 'tcx> {
 
 pub(crate) fn do_dataflow<'a, 'gcx, 'tcx, BD, P>(tcx: TyCtxt<'a, 'gcx, 'tcx>,
                                                  mir: &'a Mir<'tcx>,
-                                                 node_id: ast::NodeId,
+                                                 source: MirSource,
                                                  attributes: &[ast::Attribute],
                                                  dead_unwinds: &BitSet<BasicBlock>,
                                                  bd: BD,
@@ -132,14 +140,14 @@ pub(crate) fn do_dataflow<'a, 'gcx, 'tcx, BD, P>(tcx: TyCtxt<'a, 'gcx, 'tcx>,
           P: Fn(&BD, BD::Idx) -> DebugFormatted
 {
     let flow_state = DataflowAnalysis::new(mir, dead_unwinds, bd);
-    flow_state.run(tcx, node_id, attributes, p)
+    flow_state.run(tcx, source, attributes, p)
 }
 
 impl<'a, 'gcx: 'tcx, 'tcx: 'a, BD> DataflowAnalysis<'a, 'tcx, BD> where BD: BitDenotation
 {
     pub(crate) fn run<P>(self,
                          tcx: TyCtxt<'a, 'gcx, 'tcx>,
-                         node_id: ast::NodeId,
+                         source: MirSource,
                          attributes: &[ast::Attribute],
                          p: P) -> DataflowResults<BD>
         where P: Fn(&BD, BD::Idx) -> DebugFormatted
@@ -163,9 +171,20 @@ impl<'a, 'gcx: 'tcx, 'tcx: 'a, BD> DataflowAnalysis<'a, 'tcx, BD> where BD: BitD
         let print_postflow_to =
             name_found(tcx.sess, attributes, "borrowck_graphviz_postflow");
 
+        // With `-Z dump-mir-dataflow`, the fixed point of every analysis run on
+        // a function selected by `-Z dump-mir` is also written to the dump directory,
+        // both as a graphviz `.dot` file and as an `.html` page.
+        let dump_postflow_to = if tcx.sess.opts.debugging_opts.dump_mir_dataflow &&
+                                  dump_enabled(tcx, BD::name(), source) {
+            Some(dump_path(tcx, "dot", None, BD::name(), &"dataflow", source))
+        } else {
+            None
+        };
+
+        let node_id = tcx.hir().as_local_node_id(source.def_id).unwrap();
         let mut mbcx = DataflowBuilder {
-            node_id,
-            print_preflow_to, print_postflow_to, flow_state: self,
+            sess: tcx.sess, node_id,
+            print_preflow_to, print_postflow_to, dump_postflow_to, flow_state: self,
         };
 
         mbcx.dataflow(p);
@@ -261,25 +280,36 @@ fn dataflow_path(context: &str, path: &str) -> PathBuf {
 
 impl<'a, 'tcx: 'a, BD> DataflowBuilder<'a, 'tcx, BD> where BD: BitDenotation
 {
-    fn pre_dataflow_instrumentation<P>(&self, p: P) -> io::Result<()>
+    fn pre_dataflow_instrumentation<P>(&self, p: P)
         where P: Fn(&BD, BD::Idx) -> DebugFormatted
     {
         if let Some(ref path_str) = self.print_preflow_to {
             let path = dataflow_path(BD::name(), path_str);
-            graphviz::print_borrowck_graph_to(self, &path, p)
-        } else {
-            Ok(())
+            self.report_io_error(&path, graphviz::print_borrowck_graph_to(self, &path, p));
         }
     }
 
-    fn post_dataflow_instrumentation<P>(&self, p: P) -> io::Result<()>
+    fn post_dataflow_instrumentation<P>(&self, p: P)
         where P: Fn(&BD, BD::Idx) -> DebugFormatted
     {
+        if let Some(ref path) = self.dump_postflow_to {
+            let result = path.parent().map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| graphviz::print_borrowck_graph_to(self, path, &p));
+            self.report_io_error(path, result);
+            let html_path = path.with_extension("html");
+            self.report_io_error(&html_path,
+                                 graphviz::print_borrowck_html_to(self, &html_path, &p));
+        }
         if let Some(ref path_str) = self.print_postflow_to {
             let path = dataflow_path(BD::name(), path_str);
-            graphviz::print_borrowck_graph_to(self, &path, p)
-        } else {
-            Ok(())
+            self.report_io_error(&path, graphviz::print_borrowck_graph_to(self, &path, p));
+        }
+    }
+
+    fn report_io_error(&self, path: &Path, result: io::Result<()>) {
+        if let Err(e) = result {
+            self.sess.err(&format!("failed to write dataflow results to `{}`: {}",
+                                   path.display(), e));
         }
     }
 }
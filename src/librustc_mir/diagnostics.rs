@@ -2369,11 +2369,13 @@ are stored into aggregate structures like a tuple or struct:
 fn foo() -> i32 { 22 }
 let value = (&foo(), &foo());
 ```
+
+Introduced in Rust 1.31.0.
 "##,
 
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
 //  E0298, // cannot compare constants
 //  E0299, // mismatched types between arms
 //  E0471, // constant evaluation error (in pattern)
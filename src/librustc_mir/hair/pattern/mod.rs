@@ -1275,9 +1275,8 @@ pub fn compare_const_vals<'a, 'tcx>(
                     if let Ok(len_a) = len_a.to_bits(tcx.data_layout.pointer_size) {
                         if let Ok(len_b) = len_b.to_bits(tcx.data_layout.pointer_size) {
                             if len_a == len_b {
-                                let map = tcx.alloc_map.lock();
-                                let alloc_a = map.unwrap_memory(ptr_a.alloc_id);
-                                let alloc_b = map.unwrap_memory(ptr_b.alloc_id);
+                                let alloc_a = tcx.alloc_map.unwrap_memory(ptr_a.alloc_id);
+                                let alloc_b = tcx.alloc_map.unwrap_memory(ptr_b.alloc_id);
                                 if alloc_a.bytes.len() as u128 == len_a {
                                     return from_bool(alloc_a == alloc_b);
                                 }
@@ -13,15 +13,16 @@
 //! and miri.
 
 use syntax::symbol::Symbol;
-use rustc::ty;
-use rustc::ty::layout::{LayoutOf, Primitive};
+use rustc::ty::{self, Ty};
+use rustc::ty::layout::{LayoutOf, Primitive, VariantIdx};
 use rustc::mir::BinOp;
 use rustc::mir::interpret::{
     EvalResult, EvalErrorKind, Scalar,
 };
+use rustc_data_structures::indexed_vec::Idx;
 
 use super::{
-    Machine, PlaceTy, OpTy, EvalContext,
+    Machine, PlaceTy, OpTy, MPlaceTy, EvalContext, MemoryKind,
 };
 
 
@@ -171,6 +172,89 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
             "transmute" => {
                 self.copy_op_transmute(args[0], dest)?;
             }
+            "offset" => {
+                let ptr = self.read_scalar(args[0])?.not_undef()?;
+                let count = self.read_scalar(args[1])?.to_isize(self)?;
+                let pointee_ty = substs.type_at(0);
+                let result = self.pointer_offset_inbounds(ptr, pointee_ty, count)?;
+                self.write_scalar(result, dest)?;
+            }
+            "arith_offset" => {
+                let ptr = self.read_scalar(args[0])?.not_undef()?;
+                let count = self.read_scalar(args[1])?.to_isize(self)?;
+                let pointee_size = self.layout_of(substs.type_at(0))?.size.bytes() as i64;
+                let result = ptr.ptr_wrapping_signed_offset(count.wrapping_mul(pointee_size), self);
+                self.write_scalar(result, dest)?;
+            }
+
+            | "simd_add"
+            | "simd_sub"
+            | "simd_mul"
+            | "simd_div"
+            | "simd_rem"
+            | "simd_shl"
+            | "simd_shr"
+            | "simd_and"
+            | "simd_or"
+            | "simd_xor"
+            | "simd_eq"
+            | "simd_ne"
+            | "simd_lt"
+            | "simd_le"
+            | "simd_gt"
+            | "simd_ge" => {
+                let bin_op = match intrinsic_name {
+                    "simd_add" => BinOp::Add,
+                    "simd_sub" => BinOp::Sub,
+                    "simd_mul" => BinOp::Mul,
+                    "simd_div" => BinOp::Div,
+                    "simd_rem" => BinOp::Rem,
+                    "simd_shl" => BinOp::Shl,
+                    "simd_shr" => BinOp::Shr,
+                    "simd_and" => BinOp::BitAnd,
+                    "simd_or" => BinOp::BitOr,
+                    "simd_xor" => BinOp::BitXor,
+                    "simd_eq" => BinOp::Eq,
+                    "simd_ne" => BinOp::Ne,
+                    "simd_lt" => BinOp::Lt,
+                    "simd_le" => BinOp::Le,
+                    "simd_gt" => BinOp::Gt,
+                    "simd_ge" => BinOp::Ge,
+                    _ => bug!("Already checked for simd ops")
+                };
+                self.simd_binop(intrinsic_name, bin_op, args[0], args[1], dest)?;
+            }
+            "simd_extract" => {
+                let idx = self.simd_lane_index(intrinsic_name, args[0], args[1])?;
+                let lane = self.operand_field(args[0], idx)?;
+                self.copy_op(lane, dest)?;
+            }
+            "simd_insert" => {
+                let idx = self.simd_lane_index(intrinsic_name, args[0], args[1])?;
+                self.copy_op(args[0], dest)?;
+                let lane = self.place_field(dest, idx)?;
+                self.copy_op(args[2], lane)?;
+            }
+            name if name.starts_with("simd_shuffle") => {
+                // simd_shuffleN(x, y, idx: [u32; N]): lane `i` of the result is lane `idx[i]`
+                // of the concatenation of `x` and `y`
+                let (x, y, indices) = (args[0], args[1], args[2]);
+                let len = x.layout.fields.count() as u64;
+                for i in 0..dest.layout.fields.count() as u64 {
+                    let idx = self.read_scalar(self.operand_field(indices, i)?)?.to_u32()? as u64;
+                    let lane = if idx < len {
+                        self.operand_field(x, idx)?
+                    } else if idx < 2 * len {
+                        self.operand_field(y, idx - len)?
+                    } else {
+                        return err!(Intrinsic(
+                            format!("index {} out of bounds in {}", idx, intrinsic_name),
+                        ));
+                    };
+                    let dest = self.place_field(dest, i)?;
+                    self.copy_op(lane, dest)?;
+                }
+            }
 
             _ => return Ok(false),
         }
@@ -178,6 +262,85 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
         Ok(true)
     }
 
+    /// Offsets `ptr` by `count` elements of type `pointee_ty`, with the semantics of the
+    /// `offset` intrinsic: unless the offset is zero, both `ptr` and the result must be in
+    /// bounds (or one past the end) of the same allocation.
+    pub fn pointer_offset_inbounds(
+        &self,
+        ptr: Scalar<M::PointerTag>,
+        pointee_ty: Ty<'tcx>,
+        count: i64,
+    ) -> EvalResult<'tcx, Scalar<M::PointerTag>> {
+        let pointee_size = self.layout_of(pointee_ty)?.size.bytes() as i64;
+        let offset = match count.checked_mul(pointee_size) {
+            Some(offset) => offset,
+            None => return err!(Overflow(BinOp::Mul)),
+        };
+        if offset == 0 {
+            // This is fine for every pointer, including dangling integer ones.
+            return Ok(ptr);
+        }
+        let ptr = ptr.to_ptr()?;
+        self.memory.check_bounds_ptr_maybe_dead(ptr)?;
+        let result = ptr.signed_offset(offset, self)?;
+        self.memory.check_bounds_ptr_maybe_dead(result)?;
+        Ok(Scalar::Ptr(result))
+    }
+
+    /// Applies `bin_op` lane-wise to two SIMD vectors. Comparisons produce a mask with all bits
+    /// of a lane set if the comparison holds, which is what codegen does for them.
+    fn simd_binop(
+        &mut self,
+        intrinsic_name: &str,
+        bin_op: BinOp,
+        left: OpTy<'tcx, M::PointerTag>,
+        right: OpTy<'tcx, M::PointerTag>,
+        dest: PlaceTy<'tcx, M::PointerTag>,
+    ) -> EvalResult<'tcx> {
+        let len = dest.layout.fields.count();
+        assert_eq!(left.layout.fields.count(), len);
+        assert_eq!(right.layout.fields.count(), len);
+        for i in 0..len as u64 {
+            let l = self.read_immediate(self.operand_field(left, i)?)?;
+            let r = self.read_immediate(self.operand_field(right, i)?)?;
+            let dest = self.place_field(dest, i)?;
+            let (val, overflowed) = self.binary_op_imm(bin_op, l, r)?;
+            let val = match bin_op {
+                BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+                    let mask = if val.to_bool()? {
+                        self.truncate(u128::max_value(), dest.layout)
+                    } else {
+                        0
+                    };
+                    Scalar::from_uint(mask, dest.layout.size)
+                }
+                BinOp::Shl | BinOp::Shr if overflowed => {
+                    let r_val = r.to_scalar()?.to_bits(r.layout.size)?;
+                    return err!(Intrinsic(
+                        format!("Overflowing shift by {} in {}", r_val, intrinsic_name),
+                    ));
+                }
+                _ => val,
+            };
+            self.write_scalar(val, dest)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the lane index argument of `simd_extract` and `simd_insert`.
+    fn simd_lane_index(
+        &self,
+        intrinsic_name: &str,
+        vector: OpTy<'tcx, M::PointerTag>,
+        idx: OpTy<'tcx, M::PointerTag>,
+    ) -> EvalResult<'tcx, u64> {
+        let idx = self.read_scalar(idx)?.to_u32()? as u64;
+        if idx >= vector.layout.fields.count() as u64 {
+            return err!(Intrinsic(format!("index {} out of bounds in {}", idx, intrinsic_name)));
+        }
+        Ok(idx)
+    }
+
     /// "Intercept" a function call because we have something special to do for it.
     /// Returns whether an intercept happened.
     pub fn hook_fn(
@@ -220,22 +383,166 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
             assert!(args.len() == 2);
             // &'static str, &(&'static str, u32, u32)
             let msg = args[0];
-            let place = self.deref_operand(args[1])?;
-            let (file, line, col) = (
-                self.mplace_field(place, 0)?,
-                self.mplace_field(place, 1)?,
-                self.mplace_field(place, 2)?,
-            );
-
             let msg_place = self.deref_operand(msg.into())?;
             let msg = Symbol::intern(self.read_str(msg_place)?);
-            let file_place = self.deref_operand(file.into())?;
-            let file = Symbol::intern(self.read_str(file_place)?);
-            let line = self.read_scalar(line.into())?.to_u32()?;
-            let col = self.read_scalar(col.into())?.to_u32()?;
+            let (file, line, col) = self.read_panic_location(args[1])?;
+            return Err(EvalErrorKind::Panic { msg, file, line, col }.into());
+        } else if Some(def_id) == self.tcx.lang_items().panic_fmt_fn() ||
+            Some(def_id) == self.tcx.lang_items().begin_panic_fmt_fn()
+        {
+            assert!(args.len() == 2);
+            // fmt::Arguments (behind a reference for `begin_panic_fmt`),
+            // &(&'static str, u32, u32)
+            let arguments = if Some(def_id) == self.tcx.lang_items().begin_panic_fmt_fn() {
+                self.deref_operand(args[0])?
+            } else {
+                match args[0].try_as_mplace() {
+                    Ok(arguments) => arguments,
+                    Err(_) => {
+                        let arguments = self.allocate(args[0].layout, MemoryKind::Stack)?;
+                        self.copy_op(args[0], arguments.into())?;
+                        arguments
+                    }
+                }
+            };
+            let msg = Symbol::intern(&self.format_arguments(arguments)?);
+            let (file, line, col) = self.read_panic_location(args[1])?;
             return Err(EvalErrorKind::Panic { msg, file, line, col }.into());
         } else {
             return Ok(false);
         }
     }
+
+    /// Reads the `&(&'static str, u32, u32)` location argument of the panic entry points.
+    fn read_panic_location(
+        &self,
+        location: OpTy<'tcx, M::PointerTag>,
+    ) -> EvalResult<'tcx, (Symbol, u32, u32)> {
+        let place = self.deref_operand(location)?;
+        let (file, line, col) = (
+            self.mplace_field(place, 0)?,
+            self.mplace_field(place, 1)?,
+            self.mplace_field(place, 2)?,
+        );
+
+        let file_place = self.deref_operand(file.into())?;
+        let file = Symbol::intern(self.read_str(file_place)?);
+        let line = self.read_scalar(line.into())?.to_u32()?;
+        let col = self.read_scalar(col.into())?.to_u32()?;
+        Ok((file, line, col))
+    }
+
+    /// Renders a `fmt::Arguments` as built by `format_args!`. We cannot run the formatting
+    /// machinery of libcore, so this only supports arguments of primitive types and ignores
+    /// format specs like width and precision.
+    fn format_arguments(
+        &self,
+        arguments: MPlaceTy<'tcx, M::PointerTag>,
+    ) -> EvalResult<'tcx, String> {
+        // struct Arguments {
+        //     pieces: &[&str],
+        //     fmt: Option<&[rt::v1::Argument]>,
+        //     args: &[ArgumentV1],
+        // }
+        let pieces = self.deref_operand(self.mplace_field(arguments, 0)?.into())?;
+        let pieces = self.mplace_array_fields(pieces)?
+            .map(|piece| {
+                let piece = self.deref_operand(piece?.into())?;
+                Ok(self.read_str(piece)?.to_owned())
+            })
+            .collect::<EvalResult<'tcx, Vec<String>>>()?;
+        let args = self.deref_operand(self.mplace_field(arguments, 2)?.into())?;
+        let args = self.mplace_array_fields(args)?.collect::<EvalResult<'tcx, Vec<_>>>()?;
+
+        // Without explicit specs, every piece is followed by the next argument. Otherwise, the
+        // specs determine which argument follows which piece.
+        let fmt = self.mplace_field(arguments, 1)?;
+        let positions = match self.read_discriminant(fmt.into())?.1.index() {
+            0 => (0..args.len()).collect::<Vec<_>>(),
+            _ => {
+                let fmt = self.mplace_downcast(fmt, VariantIdx::new(1))?;
+                let specs = self.deref_operand(self.mplace_field(fmt, 0)?.into())?;
+                let mut next = 0;
+                let mut positions = Vec::new();
+                for spec in self.mplace_array_fields(specs)? {
+                    // enum Position { Next, At(usize) }
+                    let position = self.mplace_field(spec?, 0)?;
+                    positions.push(match self.read_discriminant(position.into())?.1.index() {
+                        0 => { next += 1; next - 1 },
+                        _ => {
+                            let at = self.mplace_downcast(position, VariantIdx::new(1))?;
+                            let at = self.mplace_field(at, 0)?;
+                            self.read_scalar(at.into())?.to_usize(self)? as usize
+                        }
+                    });
+                }
+                positions
+            }
+        };
+
+        let mut msg = String::new();
+        for (i, piece) in pieces.iter().enumerate() {
+            msg.push_str(piece);
+            if let Some(&position) = positions.get(i) {
+                msg.push_str(&self.format_argument(args[position])?);
+            }
+        }
+        Ok(msg)
+    }
+
+    /// Renders a single `fmt::ArgumentV1`. The type of the value and whether it is printed with
+    /// `Display` or `Debug` are recovered from the formatting function.
+    fn format_argument(
+        &self,
+        arg: MPlaceTy<'tcx, M::PointerTag>,
+    ) -> EvalResult<'tcx, String> {
+        // struct ArgumentV1 { value: &Void, formatter: fn(&Void, &mut Formatter) -> Result }
+        let formatter = self.read_scalar(self.mplace_field(arg, 1)?.into())?.to_ptr()?;
+        let instance = self.memory.get_fn(formatter)?;
+        let trait_id = self.tcx.impl_of_method(instance.def_id())
+            .and_then(|impl_id| self.tcx.trait_id_of_impl(impl_id));
+        let lang_items = self.tcx.lang_items();
+        // Other traits like `LowerHex` would need their own rendering
+        let debug = match trait_id {
+            Some(trait_id) if Some(trait_id) == lang_items.debug_trait() => true,
+            Some(trait_id) if Some(trait_id) == lang_items.display_trait() => false,
+            _ => return err!(Unimplemented(format!(
+                "formatting with `{}` during const evaluation",
+                instance,
+            ))),
+        };
+        // The formatting function takes `&Self`, which is the actual type of `value`
+        let sig = instance.ty(*self.tcx).fn_sig(*self.tcx);
+        let sig = self.tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), &sig);
+        let mut value: OpTy<'tcx, M::PointerTag> = self.mplace_field(arg, 0)?.into();
+        value.layout = self.layout_of(sig.inputs()[0])?;
+        self.format_value(self.deref_operand(value)?, debug)
+    }
+
+    fn format_value(
+        &self,
+        value: MPlaceTy<'tcx, M::PointerTag>,
+        debug: bool,
+    ) -> EvalResult<'tcx, String> {
+        Ok(match value.layout.ty.sty {
+            // `Display` and `Debug` for references forward to the referent
+            ty::Ref(..) => return self.format_value(self.deref_operand(value.into())?, debug),
+            ty::Str if debug => format!("{:?}", self.read_str(value)?),
+            ty::Str => self.read_str(value)?.to_owned(),
+            ty::Bool => self.read_scalar(value.into())?.to_bool()?.to_string(),
+            ty::Char if debug => format!("{:?}", self.read_scalar(value.into())?.to_char()?),
+            ty::Char => self.read_scalar(value.into())?.to_char()?.to_string(),
+            ty::Int(_) => {
+                let bits = self.read_scalar(value.into())?.to_bits(value.layout.size)?;
+                (self.sign_extend(bits, value.layout) as i128).to_string()
+            }
+            ty::Uint(_) => {
+                self.read_scalar(value.into())?.to_bits(value.layout.size)?.to_string()
+            }
+            _ => return err!(Unimplemented(format!(
+                "formatting values of type `{}` during const evaluation",
+                value.layout.ty,
+            ))),
+        })
+    }
 }
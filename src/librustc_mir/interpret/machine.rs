@@ -21,7 +21,7 @@ use rustc::ty::{self, layout::TyLayout, query::TyCtxtAt};
 
 use super::{
     Allocation, AllocId, EvalResult, Scalar, AllocationExtra,
-    EvalContext, PlaceTy, MPlaceTy, OpTy, Pointer, MemoryKind,
+    EvalContext, PlaceTy, MPlaceTy, OpTy, Pointer, MemoryKind, Memory,
 };
 
 /// Whether this kind of memory is allowed to leak
@@ -107,6 +107,33 @@ pub trait Machine<'a, 'mir, 'tcx>: Sized {
     /// Whether to enforce the validity invariant
     fn enforce_validity(ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool;
 
+    /// Whether memory accesses have to be sufficiently aligned. Null pointers are
+    /// rejected either way.
+    #[inline]
+    fn enforce_alignment(_memory: &Memory<'a, 'mir, 'tcx, Self>) -> bool {
+        true
+    }
+
+    /// Whether pointers that get checked for alignment or non-null-ness have to be in bounds
+    /// of their (possibly already deallocated) allocation.
+    #[inline]
+    fn enforce_inbounds(_memory: &Memory<'a, 'mir, 'tcx, Self>) -> bool {
+        true
+    }
+
+    /// Whether reading a scalar union field that is not entirely initialized is an error.
+    /// If this returns `false`, such reads produce an undefined value instead.
+    #[inline]
+    fn check_union_reads(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
+        false
+    }
+
+    /// The number of bytes that may be allocated at the same time, or `None` for no limit.
+    #[inline]
+    fn memory_limit(_tcx: TyCtxtAt<'a, 'tcx, 'tcx>) -> Option<u64> {
+        None
+    }
+
     /// Called before a basic block terminator is executed.
     /// You can use this to detect endlessly running programs.
     fn before_terminator(ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>) -> EvalResult<'tcx>;
@@ -180,6 +207,18 @@ pub trait Machine<'a, 'mir, 'tcx>: Sized {
         dest: PlaceTy<'tcx, Self::PointerTag>,
     ) -> EvalResult<'tcx>;
 
+    /// Heap deallocations via the `box_free` lang item, the counterpart to `box_alloc`.
+    ///
+    /// Returns `true` if the deallocation has been performed by this hook, or `false` if
+    /// `box_free` should be called like any other function.
+    #[inline]
+    fn box_free(
+        _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        _ptr: OpTy<'tcx, Self::PointerTag>,
+    ) -> EvalResult<'tcx, bool> {
+        Ok(false)
+    }
+
     /// Add the tag for a newly allocated pointer.
     fn tag_new_allocation(
         ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
@@ -187,6 +226,16 @@ pub trait Machine<'a, 'mir, 'tcx>: Sized {
         kind: MemoryKind<Self::MemoryKinds>,
     ) -> EvalResult<'tcx, Pointer<Self::PointerTag>>;
 
+    /// Add the tag for a pointer to global memory (statics, constants and functions).
+    /// Such memory is not allocated by the machine, so `tag_new_allocation` never sees it.
+    #[inline]
+    fn tag_static_base_pointer(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        ptr: Pointer,
+    ) -> Pointer<Self::PointerTag> {
+        ptr.with_default_tag()
+    }
+
     /// Executed when evaluating the `*` operator: Following a reference.
     /// This has the chance to adjust the tag.  It should not change anything else!
     /// `mutability` can be `None` in case a raw ptr is being dereferenced.
@@ -30,7 +30,7 @@ use syntax::ast::Mutability;
 use super::{
     Pointer, AllocId, Allocation, GlobalId, AllocationExtra,
     EvalResult, Scalar, EvalErrorKind, AllocType, PointerArithmetic,
-    Machine, AllocMap, MayLeak, ErrorHandled, InboundsCheck,
+    Machine, AllocMap, MayLeak, ErrorHandled, InboundsCheck, UndefMask,
 };
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
@@ -73,6 +73,10 @@ pub struct Memory<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'a, 'mir, 'tcx>> {
     /// that do not exist any more.
     dead_alloc_map: FxHashMap<AllocId, (Size, Align)>,
 
+    /// The total size of the live allocations created through `allocate` and
+    /// `allocate_with`, checked against `-Z const-eval-memory-limit`.
+    bytes_allocated: u64,
+
     /// Extra data added by the machine.
     pub extra: M::MemoryExtra,
 
@@ -103,6 +107,7 @@ where
         Memory {
             alloc_map: self.alloc_map.clone(),
             dead_alloc_map: self.dead_alloc_map.clone(),
+            bytes_allocated: self.bytes_allocated,
             extra: (),
             tcx: self.tcx,
         }
@@ -114,13 +119,14 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         Memory {
             alloc_map: M::MemoryMap::default(),
             dead_alloc_map: FxHashMap::default(),
+            bytes_allocated: 0,
             extra: M::MemoryExtra::default(),
             tcx,
         }
     }
 
     pub fn create_fn_alloc(&mut self, instance: Instance<'tcx>) -> Pointer {
-        Pointer::from(self.tcx.alloc_map.lock().create_fn_alloc(instance))
+        Pointer::from(self.tcx.alloc_map.create_fn_alloc(instance))
     }
 
     pub fn allocate_static_bytes(&mut self, bytes: &[u8]) -> Pointer {
@@ -132,7 +138,10 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         alloc: Allocation<M::PointerTag, M::AllocExtra>,
         kind: MemoryKind<M::MemoryKinds>,
     ) -> EvalResult<'tcx, AllocId> {
-        let id = self.tcx.alloc_map.lock().reserve();
+        let size = Size::from_bytes(alloc.bytes.len() as u64);
+        self.check_memory_limit(size)?;
+        self.bytes_allocated += size.bytes();
+        let id = self.tcx.alloc_map.reserve();
         self.alloc_map.insert(id, (kind, alloc));
         Ok(id)
     }
@@ -143,6 +152,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         align: Align,
         kind: MemoryKind<M::MemoryKinds>,
     ) -> EvalResult<'tcx, Pointer> {
+        // Check this before creating the allocation, which might be huge
+        self.check_memory_limit(size)?;
         let extra = AllocationExtra::memory_allocated(size, &self.extra);
         Ok(Pointer::from(self.allocate_with(Allocation::undef(size, align, extra), kind)?))
     }
@@ -176,6 +187,16 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         Ok(new_ptr)
     }
 
+    /// Checks that allocating `size` more bytes stays within the machine's memory limit.
+    fn check_memory_limit(&self, size: Size) -> EvalResult<'tcx> {
+        if let Some(limit) = M::memory_limit(self.tcx) {
+            if self.bytes_allocated.saturating_add(size.bytes()) > limit {
+                return err!(MemoryExhausted);
+            }
+        }
+        Ok(())
+    }
+
     /// Deallocate a local, or do nothing if that local has been made into a static
     pub fn deallocate_local(&mut self, ptr: Pointer<M::PointerTag>) -> EvalResult<'tcx> {
         // The allocation might be already removed by static interning.
@@ -203,7 +224,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
             Some(alloc) => alloc,
             None => {
                 // Deallocating static memory -- always an error
-                return match self.tcx.alloc_map.lock().get(ptr.alloc_id) {
+                return match self.tcx.alloc_map.get(ptr.alloc_id) {
                     Some(AllocType::Function(..)) => err!(DeallocatedWrongMemoryKind(
                         "function".to_string(),
                         format!("{:?}", kind),
@@ -237,6 +258,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         // Let the machine take some extra action
         let size = Size::from_bytes(alloc.bytes.len() as u64);
         AllocationExtra::memory_deallocated(&mut alloc, ptr, size)?;
+        // Copies of statics are not accounted for, so do not rely on this not underflowing
+        self.bytes_allocated = self.bytes_allocated.saturating_sub(size.bytes());
 
         // Don't forget to remember size and align of this now-dead allocation
         let old = self.dead_alloc_map.insert(
@@ -252,6 +275,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
 
     /// Check that the pointer is aligned AND non-NULL. This supports ZSTs in two ways:
     /// You can pass a scalar, and a `Pointer` does not have to actually still be allocated.
+    /// The machine can turn off the alignment and bounds parts of this check, see
+    /// `Machine::enforce_alignment` and `Machine::enforce_inbounds`.
     pub fn check_align(
         &self,
         ptr: Scalar<M::PointerTag>,
@@ -262,7 +287,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
             Scalar::Ptr(ptr) => {
                 // check this is not NULL -- which we can ensure only if this is in-bounds
                 // of some (potentially dead) allocation.
-                let align = self.check_bounds_ptr_maybe_dead(ptr)?;
+                let align = if M::enforce_inbounds(self) {
+                    self.check_bounds_ptr_maybe_dead(ptr)?
+                } else {
+                    self.get_size_and_align(ptr.alloc_id).1
+                };
                 (ptr.offset.bytes(), align)
             }
             Scalar::Bits { bits, size } => {
@@ -277,6 +306,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
             }
         };
         // Check alignment
+        if !M::enforce_alignment(self) {
+            return Ok(());
+        }
         if alloc_align.bytes() < required_align.bytes() {
             return err!(AlignmentCheckFailed {
                 has: alloc_align,
@@ -324,7 +356,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         tcx: TyCtxtAt<'a, 'tcx, 'tcx>,
         memory_extra: &M::MemoryExtra,
     ) -> EvalResult<'tcx, Cow<'tcx, Allocation<M::PointerTag, M::AllocExtra>>> {
-        let alloc = tcx.alloc_map.lock().get(id);
+        let alloc = tcx.alloc_map.get(id);
         let def_id = match alloc {
             Some(AllocType::Memory(mem)) => {
                 // We got tcx memory. Let the machine figure out whether and how to
@@ -360,7 +392,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
                 ErrorHandled::TooGeneric => EvalErrorKind::TooGeneric.into(),
             }
         }).map(|raw_const| {
-            let allocation = tcx.alloc_map.lock().unwrap_memory(raw_const.alloc_id);
+            let allocation = tcx.alloc_map.unwrap_memory(raw_const.alloc_id);
             // We got tcx memory. Let the machine figure out whether and how to
             // turn that into memory with the right pointer tag.
             M::adjust_static_allocation(allocation, memory_extra)
@@ -408,7 +440,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
             // to give us a cheap reference.
             let alloc = Self::get_static_alloc(id, tcx, memory_extra)?;
             if alloc.mutability == Mutability::Immutable {
-                return err!(ModifiedConstantMemory);
+                return Err(Self::modified_constant_memory(tcx, id).into());
             }
             match M::STATIC_KIND {
                 Some(kind) => Ok((MemoryKind::Machine(kind), alloc.into_owned())),
@@ -422,19 +454,32 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
             Ok(a) => {
                 let a = &mut a.1;
                 if a.mutability == Mutability::Immutable {
-                    return err!(ModifiedConstantMemory);
+                    return Err(Self::modified_constant_memory(tcx, id).into());
                 }
                 Ok(a)
             }
         }
     }
 
+    /// The error for a write to the immutable allocation `id`.  If the allocation is a
+    /// static, the error points at its definition as well.
+    fn modified_constant_memory(
+        tcx: TyCtxtAt<'a, 'tcx, 'tcx>,
+        id: AllocId,
+    ) -> EvalErrorKind<'tcx, u64> {
+        let def_span = match tcx.alloc_map.get(id) {
+            Some(AllocType::Static(def_id)) => Some(tcx.def_span(def_id)),
+            _ => None,
+        };
+        EvalErrorKind::ModifiedConstantMemory(def_span)
+    }
+
     pub fn get_size_and_align(&self, id: AllocId) -> (Size, Align) {
         if let Ok(alloc) = self.get(id) {
             return (Size::from_bytes(alloc.bytes.len() as u64), alloc.align);
         }
         // Could also be a fn ptr or extern static
-        match self.tcx.alloc_map.lock().get(id) {
+        match self.tcx.alloc_map.get(id) {
             Some(AllocType::Function(..)) => (Size::ZERO, Align::from_bytes(1).unwrap()),
             Some(AllocType::Static(did)) => {
                 // The only way `get` couldn't have worked here is if this is an extern static
@@ -458,7 +503,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
             return err!(InvalidFunctionPointer);
         }
         trace!("reading fn ptr: {}", ptr.alloc_id);
-        match self.tcx.alloc_map.lock().get(ptr.alloc_id) {
+        match self.tcx.alloc_map.get(ptr.alloc_id) {
             Some(AllocType::Function(instance)) => Ok(instance),
             _ => Err(EvalErrorKind::ExecuteMemory.into()),
         }
@@ -556,7 +601,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
                 },
                 Err(()) => {
                     // static alloc?
-                    match self.tcx.alloc_map.lock().get(id) {
+                    match self.tcx.alloc_map.get(id) {
                         Some(AllocType::Memory(alloc)) => {
                             self.dump_alloc_helper(
                                 &mut allocs_seen, &mut allocs_to_print,
@@ -618,6 +663,61 @@ where
     // FIXME: Working around https://github.com/rust-lang/rust/issues/24159
     M::MemoryMap: AllocMap<AllocId, (MemoryKind<M::MemoryKinds>, Allocation)>,
 {
+    /// Make sure no pointer reachable from `alloc_id` points to memory that has been
+    /// deallocated already. Interning such a pointer into a constant would make the
+    /// dead memory accessible at runtime.
+    pub fn check_dangling_pointers(&self, alloc_id: AllocId) -> EvalResult<'tcx> {
+        let mut todo = vec![alloc_id];
+        let mut seen = FxHashSet::default();
+        seen.insert(alloc_id);
+
+        while let Some(id) = todo.pop() {
+            // Everything that is not local has been interned (and checked) already.
+            let alloc = match self.alloc_map.get_or(id, || Err(())) {
+                Ok((_, alloc)) => alloc,
+                Err(()) => continue,
+            };
+            for &(offset, ((), target)) in alloc.relocations.iter() {
+                if self.dead_alloc_map.contains_key(&target) {
+                    trace!("{} points to dead {} at offset {}", id, target, offset.bytes());
+                    return err!(DanglingPointer(offset));
+                }
+                if seen.insert(target) {
+                    todo.push(target);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `alloc_id` or any not yet interned allocation reachable from it is of the
+    /// given kind.
+    pub fn reaches_memory_kind(
+        &self,
+        alloc_id: AllocId,
+        kind: MemoryKind<M::MemoryKinds>,
+    ) -> bool {
+        let mut todo = vec![alloc_id];
+        let mut seen = FxHashSet::default();
+        seen.insert(alloc_id);
+
+        while let Some(id) = todo.pop() {
+            let (alloc_kind, alloc) = match self.alloc_map.get_or(id, || Err(())) {
+                Ok(&(alloc_kind, ref alloc)) => (alloc_kind, alloc),
+                Err(()) => continue,
+            };
+            if alloc_kind == kind {
+                return true;
+            }
+            for &(_, ((), target)) in alloc.relocations.iter() {
+                if seen.insert(target) {
+                    todo.push(target);
+                }
+            }
+        }
+        false
+    }
+
     /// mark an allocation as static and initialized, either mutable or not
     pub fn intern_static(
         &mut self,
@@ -630,31 +730,25 @@ where
             mutability
         );
         // remove allocation
-        let (kind, mut alloc) = self.alloc_map.remove(&alloc_id).unwrap();
-        match kind {
-            MemoryKind::Machine(_) => bug!("Static cannot refer to machine memory"),
-            MemoryKind::Stack | MemoryKind::Vtable => {},
-        }
+        // Machine memory (e.g. heap allocations made during CTFE) that is reachable from
+        // the final value becomes part of it, just like stack and vtable memory.
+        let (_kind, mut alloc) = self.alloc_map.remove(&alloc_id).unwrap();
+        self.bytes_allocated = self.bytes_allocated.saturating_sub(alloc.bytes.len() as u64);
         // ensure llvm knows not to put this into immutable memory
         alloc.mutability = mutability;
         let alloc = self.tcx.intern_const_alloc(alloc);
-        self.tcx.alloc_map.lock().set_id_memory(alloc_id, alloc);
+        self.tcx.alloc_map.set_id_memory(alloc_id, alloc);
         // recurse into inner allocations
-        for &(_, alloc) in alloc.relocations.values() {
-            // FIXME: Reusing the mutability here is likely incorrect.  It is originally
-            // determined via `is_freeze`, and data is considered frozen if there is no
-            // `UnsafeCell` *immediately* in that data -- however, this search stops
-            // at references.  So whenever we follow a reference, we should likely
-            // assume immutability -- and we should make sure that the compiler
-            // does not permit code that would break this!
+        for &(offset, (_, alloc)) in alloc.relocations.iter() {
+            // Reusing the mutability here is conservative: the caller picks `Mutable` if
+            // there may be an `UnsafeCell` anywhere behind the final value, not just
+            // immediately in it.
             if self.alloc_map.contains_key(&alloc) {
                 // Not yet interned, so proceed recursively
                 self.intern_static(alloc, mutability)?;
             } else if self.dead_alloc_map.contains_key(&alloc) {
-                // dangling pointer
-                return err!(ValidationFailure(
-                    "encountered dangling pointer in final constant".into(),
-                ))
+                // dangling pointer, usually caught by `check_dangling_pointers` already
+                return err!(DanglingPointer(offset))
             }
         }
         Ok(())
@@ -781,18 +875,24 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         // The bits have to be saved locally before writing to dest in case src and dest overlap.
         assert_eq!(size.bytes() as usize as u64, size.bytes());
 
-        let undef_mask = self.get(src.alloc_id)?.undef_mask.clone();
-        let dest_allocation = self.get_mut(dest.alloc_id)?;
-
-        for i in 0..size.bytes() {
-            let defined = undef_mask.get(src.offset + Size::from_bytes(i));
-
-            for j in 0..repeat {
-                dest_allocation.undef_mask.set(
-                    dest.offset + Size::from_bytes(i + (size.bytes() * j)),
-                    defined
-                );
+        let undef_mask = {
+            let src_mask = &self.get(src.alloc_id)?.undef_mask;
+            if src_mask.is_range_defined(src.offset, src.offset + size).is_ok() {
+                None
+            } else {
+                let mut undef_mask = UndefMask::new(size);
+                undef_mask.copy_range(src_mask, src.offset, Size::ZERO, size);
+                Some(undef_mask)
             }
+        };
+        let dest_mask = &mut self.get_mut(dest.alloc_id)?.undef_mask;
+
+        match undef_mask {
+            // Fast path: a fully defined source makes the entire destination defined
+            None => dest_mask.set_range_inbounds(dest.offset, dest.offset + size * repeat, true),
+            Some(undef_mask) => for j in 0..repeat {
+                dest_mask.copy_range(&undef_mask, Size::ZERO, dest.offset + size * j, size);
+            },
         }
 
         Ok(())
@@ -9,6 +9,10 @@
 // except according to those terms.
 
 //! An interpreter for MIR used in CTFE and by miri
+//!
+//! Other tools can drive the interpreter as well: implement `Machine` to customize
+//! memory and function call behavior, create an `EvalContext` with it and evaluate
+//! any `Instance` via `EvalContext::call_instance`.
 
 mod cast;
 mod eval_context;
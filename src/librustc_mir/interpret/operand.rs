@@ -398,6 +398,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
             Ok(mplace) => {
                 // The easy case
                 let field = self.mplace_field(mplace, field)?;
+                if mplace.layout.ty.is_union() && M::check_union_reads(self) {
+                    self.check_union_field_defined(field)?;
+                }
                 return Ok(field.into());
             },
             Err(value) => value
@@ -424,6 +427,30 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
         Ok(OpTy { op: Operand::Immediate(immediate), layout: field_layout })
     }
 
+    /// Make sure a scalar union field is entirely initialized before it gets read.
+    /// `read_scalar` would otherwise turn a partially undefined field into an entirely
+    /// undefined value, losing track of where the problem originated.
+    fn check_union_field_defined(
+        &self,
+        field: MPlaceTy<'tcx, M::PointerTag>,
+    ) -> EvalResult<'tcx> {
+        // Aggregate fields are copied bytewise, undefined padding and all.
+        if let layout::Abi::Scalar(..) = field.layout.abi {
+            if let Scalar::Ptr(ptr) = field.ptr {
+                let alloc = self.memory.get(ptr.alloc_id)?;
+                if let Err(err) = alloc.check_defined(ptr, field.layout.size) {
+                    return match err.kind {
+                        // Report the offset relative to the field, not to the allocation
+                        EvalErrorKind::ReadUndefBytes(offset) =>
+                            err!(ReadUndefBytes(offset - ptr.offset)),
+                        _ => Err(err),
+                    };
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn operand_downcast(
         &self,
         op: OpTy<'tcx, M::PointerTag>,
@@ -564,9 +591,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
             ConstValue::ByRef(id, alloc, offset) => {
                 // We rely on mutability being set correctly in that allocation to prevent writes
                 // where none should happen -- and for `static mut`, we copy on demand anyway.
-                Ok(Operand::Indirect(
-                    MemPlace::from_ptr(Pointer::new(id, offset), alloc.align)
-                ).with_default_tag())
+                let ptr = M::tag_static_base_pointer(self, Pointer::new(id, offset));
+                Ok(Operand::Indirect(MemPlace::from_ptr(ptr, alloc.align)))
             },
             ConstValue::ScalarPair(a, b) =>
                 Ok(Operand::Immediate(Immediate::ScalarPair(
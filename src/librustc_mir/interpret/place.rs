@@ -611,8 +611,9 @@ where
                 // want!  This way, computing statics works concistently between codegen
                 // and miri: They use the same query to eventually obtain a `ty::Const`
                 // and use that for further computation.
-                let alloc = self.tcx.alloc_map.lock().intern_static(cid.instance.def_id());
-                MPlaceTy::from_aligned_ptr(Pointer::from(alloc).with_default_tag(), layout)
+                let alloc = self.tcx.alloc_map.intern_static(cid.instance.def_id());
+                let ptr = M::tag_static_base_pointer(self, Pointer::from(alloc));
+                MPlaceTy::from_aligned_ptr(ptr, layout)
             }
 
             _ => bug!("eval_place_to_mplace called on {:?}", mir_place),
@@ -1018,10 +1019,10 @@ where
         raw: RawConst<'tcx>,
     ) -> EvalResult<'tcx, MPlaceTy<'tcx, M::PointerTag>> {
         // This must be an allocation in `tcx`
-        assert!(self.tcx.alloc_map.lock().get(raw.alloc_id).is_some());
+        assert!(self.tcx.alloc_map.get(raw.alloc_id).is_some());
         let layout = self.layout_of(raw.ty)?;
         Ok(MPlaceTy::from_aligned_ptr(
-            Pointer::new(raw.alloc_id, Size::ZERO).with_default_tag(),
+            M::tag_static_base_pointer(self, Pointer::new(raw.alloc_id, Size::ZERO)),
             layout,
         ))
     }
@@ -52,10 +52,21 @@ impl<'a, 'mir, 'tcx> InfiniteLoopDetector<'a, 'mir, 'tcx>
         memory: &Memory<'a, 'mir, 'tcx, CompileTimeInterpreter<'a, 'mir, 'tcx>>,
         stack: &[Frame<'mir, 'tcx>],
     ) -> EvalResult<'tcx, ()> {
-        // Compute stack's hash before copying anything
+        // Compute the state's hash before copying anything. Besides the stack, this covers
+        // the allocations local to this evaluation, so that loops which only make progress
+        // in memory do not cause a full snapshot every time we get here.
         let mut hcx = tcx.get_stable_hashing_context();
         let mut hasher = StableHasher::<u64>::new();
         stack.hash_stable(&mut hcx, &mut hasher);
+        // The iteration order of the map depends on the `AllocId`s, which differ between
+        // otherwise identical states, so combine the allocations in an order-independent way.
+        let mut alloc_hashes: Vec<u64> = memory.alloc_map().values().map(|(_, alloc)| {
+            let mut hasher = StableHasher::<u64>::new();
+            alloc.hash_stable(&mut hcx, &mut hasher);
+            hasher.finish()
+        }).collect();
+        alloc_hashes.sort_unstable();
+        alloc_hashes.hash_stable(&mut hcx, &mut hasher);
         let hash = hasher.finish();
 
         // Check if we know that hash already
@@ -318,6 +329,7 @@ impl_stable_hash_for!(impl<'tcx, 'mir: 'tcx> for struct Frame<'mir, 'tcx> {
     mir,
     instance,
     span,
+    promoted,
     return_to_block,
     return_place -> (return_place.as_ref().map(|r| &**r)),
     locals,
@@ -336,6 +348,7 @@ impl<'a, 'mir, 'tcx, Ctx> Snapshot<'a, Ctx> for &'a Frame<'mir, 'tcx>
             mir: _,
             instance,
             span,
+            promoted: _,
             return_to_block,
             return_place,
             locals,
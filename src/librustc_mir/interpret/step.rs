@@ -131,6 +131,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
             // Statements we do not track.
             AscribeUserType(..) => {}
 
+            // Coverage is only measured in compiled code.
+            CoverageCounter { .. } => {}
+
             // Defined to do nothing. These are added by optimization passes, to avoid changing the
             // size of MIR constantly.
             Nop => {}
@@ -248,8 +248,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
 
         match instance.def {
             ty::InstanceDef::Intrinsic(..) => {
-                if caller_abi != Abi::RustIntrinsic {
-                    return err!(FunctionAbiMismatch(caller_abi, Abi::RustIntrinsic));
+                match caller_abi {
+                    Abi::RustIntrinsic | Abi::PlatformIntrinsic => {}
+                    _ => return err!(FunctionAbiMismatch(caller_abi, Abi::RustIntrinsic)),
                 }
                 // The intrinsic itself cannot diverge, so if we got here without a return
                 // place... (can happen e.g., for transmute returning `!`)
@@ -272,16 +273,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
             ty::InstanceDef::Item(_) => {
                 // ABI check
                 {
-                    let callee_abi = {
-                        let instance_ty = instance.ty(*self.tcx);
-                        match instance_ty.sty {
-                            ty::FnDef(..) =>
-                                instance_ty.fn_sig(*self.tcx).abi(),
-                            ty::Closure(..) => Abi::RustCall,
-                            ty::Generator(..) => Abi::Rust,
-                            _ => bug!("unexpected callee ty: {:?}", instance_ty),
-                        }
-                    };
+                    let callee_abi = self.instance_abi(instance);
                     // Rust and RustCall are compatible
                     let normalize_abi = |abi| if abi == Abi::RustCall { Abi::Rust } else { abi };
                     if normalize_abi(caller_abi) != normalize_abi(callee_abi) {
@@ -289,6 +281,16 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
                     }
                 }
 
+                // `box_free` is the counterpart to `box_alloc`, so give the machine a chance
+                // to handle it the same way
+                if Some(instance.def_id()) == self.tcx.lang_items().box_free_fn() {
+                    assert_eq!(args.len(), 1);
+                    if M::box_free(self, args[0])? {
+                        self.goto_block(ret)?;
+                        return Ok(());
+                    }
+                }
+
                 // We need MIR for this fn
                 let mir = match M::find_fn(self, instance, args, dest, ret)? {
                     Some(mir) => mir,
@@ -304,104 +306,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
                 )?;
 
                 // We want to pop this frame again in case there was an error, to put
-                // the blame in the right location.  Until the 2018 edition is used in
-                // the compiler, we have to do this with an immediately invoked function.
-                let res = (||{
-                    trace!(
-                        "caller ABI: {:?}, args: {:#?}",
-                        caller_abi,
-                        args.iter()
-                            .map(|arg| (arg.layout.ty, format!("{:?}", **arg)))
-                            .collect::<Vec<_>>()
-                    );
-                    trace!(
-                        "spread_arg: {:?}, locals: {:#?}",
-                        mir.spread_arg,
-                        mir.args_iter()
-                            .map(|local|
-                                (local, self.layout_of_local(self.frame(), local).unwrap().ty)
-                            )
-                            .collect::<Vec<_>>()
-                    );
-
-                    // Figure out how to pass which arguments.
-                    // We have two iterators: Where the arguments come from,
-                    // and where they go to.
-                    let rust_abi = match caller_abi {
-                        Abi::Rust | Abi::RustCall => true,
-                        _ => false
-                    };
-
-                    // For where they come from: If the ABI is RustCall, we untuple the
-                    // last incoming argument.  These two iterators do not have the same type,
-                    // so to keep the code paths uniform we accept an allocation
-                    // (for RustCall ABI only).
-                    let caller_args : Cow<[OpTy<'tcx, M::PointerTag>]> =
-                        if caller_abi == Abi::RustCall && !args.is_empty() {
-                            // Untuple
-                            let (&untuple_arg, args) = args.split_last().unwrap();
-                            trace!("eval_fn_call: Will pass last argument by untupling");
-                            Cow::from(args.iter().map(|&a| Ok(a))
-                                .chain((0..untuple_arg.layout.fields.count()).into_iter()
-                                    .map(|i| self.operand_field(untuple_arg, i as u64))
-                                )
-                                .collect::<EvalResult<Vec<OpTy<'tcx, M::PointerTag>>>>()?)
-                        } else {
-                            // Plain arg passing
-                            Cow::from(args)
-                        };
-                    // Skip ZSTs
-                    let mut caller_iter = caller_args.iter()
-                        .filter(|op| !rust_abi || !op.layout.is_zst())
-                        .map(|op| *op);
-
-                    // Now we have to spread them out across the callee's locals,
-                    // taking into account the `spread_arg`.  If we could write
-                    // this is a single iterator (that handles `spread_arg`), then
-                    // `pass_argument` would be the loop body. It takes care to
-                    // not advance `caller_iter` for ZSTs.
-                    let mut locals_iter = mir.args_iter();
-                    while let Some(local) = locals_iter.next() {
-                        let dest = self.eval_place(&mir::Place::Local(local))?;
-                        if Some(local) == mir.spread_arg {
-                            // Must be a tuple
-                            for i in 0..dest.layout.fields.count() {
-                                let dest = self.place_field(dest, i as u64)?;
-                                self.pass_argument(rust_abi, &mut caller_iter, dest)?;
-                            }
-                        } else {
-                            // Normal argument
-                            self.pass_argument(rust_abi, &mut caller_iter, dest)?;
-                        }
-                    }
-                    // Now we should have no more caller args
-                    if caller_iter.next().is_some() {
-                        trace!("Caller has too many args over");
-                        return err!(FunctionArgCountMismatch);
-                    }
-                    // Don't forget to check the return type!
-                    if let Some(caller_ret) = dest {
-                        let callee_ret = self.eval_place(&mir::Place::Local(mir::RETURN_PLACE))?;
-                        if !Self::check_argument_compat(
-                            rust_abi,
-                            caller_ret.layout,
-                            callee_ret.layout,
-                        ) {
-                            return err!(FunctionRetMismatch(
-                                caller_ret.layout.ty, callee_ret.layout.ty
-                            ));
-                        }
-                    } else {
-                        let callee_layout =
-                            self.layout_of_local(self.frame(), mir::RETURN_PLACE)?;
-                        if !callee_layout.abi.is_uninhabited() {
-                            return err!(FunctionRetMismatch(
-                                self.tcx.types.never, callee_layout.ty
-                            ));
-                        }
-                    }
-                    Ok(())
-                })();
+                // the blame in the right location.
+                let res = self.pass_arguments(mir, caller_abi, args, dest);
                 match res {
                     Err(err) => {
                         self.stack.pop();
@@ -437,6 +343,166 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
         }
     }
 
+    /// The ABI `instance` expects to be called with.
+    fn instance_abi(&self, instance: ty::Instance<'tcx>) -> Abi {
+        let instance_ty = instance.ty(*self.tcx);
+        match instance_ty.sty {
+            ty::FnDef(..) =>
+                instance_ty.fn_sig(*self.tcx).abi(),
+            ty::Closure(..) => Abi::RustCall,
+            ty::Generator(..) => Abi::Rust,
+            _ => bug!("unexpected callee ty: {:?}", instance_ty),
+        }
+    }
+
+    /// Initialize the arguments and check the return place of the frame that was just
+    /// pushed for a call of `mir`.
+    fn pass_arguments(
+        &mut self,
+        mir: &'mir mir::Mir<'tcx>,
+        caller_abi: Abi,
+        args: &[OpTy<'tcx, M::PointerTag>],
+        dest: Option<PlaceTy<'tcx, M::PointerTag>>,
+    ) -> EvalResult<'tcx> {
+        trace!(
+            "caller ABI: {:?}, args: {:#?}",
+            caller_abi,
+            args.iter()
+                .map(|arg| (arg.layout.ty, format!("{:?}", **arg)))
+                .collect::<Vec<_>>()
+        );
+        trace!(
+            "spread_arg: {:?}, locals: {:#?}",
+            mir.spread_arg,
+            mir.args_iter()
+                .map(|local|
+                    (local, self.layout_of_local(self.frame(), local).unwrap().ty)
+                )
+                .collect::<Vec<_>>()
+        );
+
+        // Figure out how to pass which arguments.
+        // We have two iterators: Where the arguments come from,
+        // and where they go to.
+        let rust_abi = match caller_abi {
+            Abi::Rust | Abi::RustCall => true,
+            _ => false
+        };
+
+        // For where they come from: If the ABI is RustCall, we untuple the
+        // last incoming argument.  These two iterators do not have the same type,
+        // so to keep the code paths uniform we accept an allocation
+        // (for RustCall ABI only).
+        let caller_args : Cow<[OpTy<'tcx, M::PointerTag>]> =
+            if caller_abi == Abi::RustCall && !args.is_empty() {
+                // Untuple
+                let (&untuple_arg, args) = args.split_last().unwrap();
+                trace!("eval_fn_call: Will pass last argument by untupling");
+                Cow::from(args.iter().map(|&a| Ok(a))
+                    .chain((0..untuple_arg.layout.fields.count()).into_iter()
+                        .map(|i| self.operand_field(untuple_arg, i as u64))
+                    )
+                    .collect::<EvalResult<Vec<OpTy<'tcx, M::PointerTag>>>>()?)
+            } else {
+                // Plain arg passing
+                Cow::from(args)
+            };
+        // Skip ZSTs
+        let mut caller_iter = caller_args.iter()
+            .filter(|op| !rust_abi || !op.layout.is_zst())
+            .map(|op| *op);
+
+        // Now we have to spread them out across the callee's locals,
+        // taking into account the `spread_arg`.  If we could write
+        // this is a single iterator (that handles `spread_arg`), then
+        // `pass_argument` would be the loop body. It takes care to
+        // not advance `caller_iter` for ZSTs.
+        let mut locals_iter = mir.args_iter();
+        while let Some(local) = locals_iter.next() {
+            let dest = self.eval_place(&mir::Place::Local(local))?;
+            if Some(local) == mir.spread_arg {
+                // Must be a tuple
+                for i in 0..dest.layout.fields.count() {
+                    let dest = self.place_field(dest, i as u64)?;
+                    self.pass_argument(rust_abi, &mut caller_iter, dest)?;
+                }
+            } else {
+                // Normal argument
+                self.pass_argument(rust_abi, &mut caller_iter, dest)?;
+            }
+        }
+        // Now we should have no more caller args
+        if caller_iter.next().is_some() {
+            trace!("Caller has too many args over");
+            return err!(FunctionArgCountMismatch);
+        }
+        // Don't forget to check the return type!
+        if let Some(caller_ret) = dest {
+            let callee_ret = self.eval_place(&mir::Place::Local(mir::RETURN_PLACE))?;
+            if !Self::check_argument_compat(
+                rust_abi,
+                caller_ret.layout,
+                callee_ret.layout,
+            ) {
+                return err!(FunctionRetMismatch(
+                    caller_ret.layout.ty, callee_ret.layout.ty
+                ));
+            }
+        } else {
+            let callee_layout =
+                self.layout_of_local(self.frame(), mir::RETURN_PLACE)?;
+            if !callee_layout.abi.is_uninhabited() {
+                return err!(FunctionRetMismatch(
+                    self.tcx.types.never, callee_layout.ty
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Call `instance` with the given arguments and evaluate it to completion, writing
+    /// the return value to `dest`. This is the entry point for tools that drive the
+    /// interpreter with their own `Machine`; it works on an empty stack as well as
+    /// from within a machine hook.
+    ///
+    /// Only functions that have MIR can be called this way. Intrinsics and virtual
+    /// calls are resolved by the caller's `Call` terminator, so they are rejected.
+    pub fn call_instance(
+        &mut self,
+        instance: ty::Instance<'tcx>,
+        args: &[OpTy<'tcx, M::PointerTag>],
+        dest: PlaceTy<'tcx, M::PointerTag>,
+    ) -> EvalResult<'tcx> {
+        trace!("call_instance: {:#?}", instance);
+        let mir = match instance.def {
+            ty::InstanceDef::Intrinsic(def_id) |
+            ty::InstanceDef::Virtual(def_id, _) => {
+                return err!(NoMirFor(self.tcx.item_path_str(def_id)));
+            }
+            _ => self.load_mir(instance.def)?,
+        };
+        let caller_abi = self.instance_abi(instance);
+
+        let stack_depth = self.stack.len();
+        self.push_stack_frame(
+            instance,
+            mir.span,
+            mir,
+            Some(dest),
+            StackPopCleanup::None { cleanup: true },
+        )?;
+        if let Err(err) = self.pass_arguments(mir, caller_abi, args, Some(dest)) {
+            self.stack.pop();
+            return Err(err);
+        }
+
+        // Only run until our frame is popped again, the frames below belong to the caller.
+        while self.stack.len() > stack_depth {
+            self.step()?;
+        }
+        Ok(())
+    }
+
     fn drop_in_place(
         &mut self,
         place: PlaceTy<'tcx, M::PointerTag>,
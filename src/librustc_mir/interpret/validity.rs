@@ -14,6 +14,7 @@ use std::ops::RangeInclusive;
 
 use syntax_pos::symbol::Symbol;
 use rustc::ty::layout::{self, Size, Align, TyLayout, LayoutOf, VariantIdx};
+use rustc::hir;
 use rustc::ty;
 use rustc_data_structures::fx::FxHashSet;
 use rustc::mir::interpret::{
@@ -87,6 +88,8 @@ pub enum PathElem {
 pub struct RefTracking<'tcx, Tag> {
     pub seen: FxHashSet<(OpTy<'tcx, Tag>)>,
     pub todo: Vec<(OpTy<'tcx, Tag>, Vec<PathElem>)>,
+    /// Whether `&mut` to non-zero-sized memory may appear in the value
+    pub allow_mut_refs: bool,
 }
 
 impl<'tcx, Tag: Copy+Eq+Hash> RefTracking<'tcx, Tag> {
@@ -94,6 +97,7 @@ impl<'tcx, Tag: Copy+Eq+Hash> RefTracking<'tcx, Tag> {
         let mut ref_tracking = RefTracking {
             seen: FxHashSet::default(),
             todo: vec![(op, Vec::new())],
+            allow_mut_refs: true,
         };
         ref_tracking.seen.insert(op);
         ref_tracking
@@ -387,7 +391,7 @@ impl<'rt, 'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>>
                         let ptr = try_validation!(place.ptr.to_ptr(),
                             "integer pointer in non-ZST reference", self.path);
                         // Skip validation entirely for some external statics
-                        let alloc_kind = self.ecx.tcx.alloc_map.lock().get(ptr.alloc_id);
+                        let alloc_kind = self.ecx.tcx.alloc_map.get(ptr.alloc_id);
                         if let Some(AllocType::Static(did)) = alloc_kind {
                             // `extern static` cannot be validated as they have no body.
                             // FIXME: Statics from other crates are also skipped.
@@ -397,6 +401,11 @@ impl<'rt, 'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>>
                                 return Ok(());
                             }
                         }
+                        if !ref_tracking.allow_mut_refs {
+                            if let ty::Ref(_, _, hir::MutMutable) = value.layout.ty.sty {
+                                return err!(EscapingMutRef);
+                            }
+                        }
                         // Maintain the invariant that the place we are checking is
                         // already verified to be in-bounds.
                         try_validation!(
@@ -1161,7 +1161,7 @@ fn collect_miri<'a, 'tcx>(
     alloc_id: AllocId,
     output: &mut Vec<MonoItem<'tcx>>,
 ) {
-    let alloc_type = tcx.alloc_map.lock().get(alloc_id);
+    let alloc_type = tcx.alloc_map.get(alloc_id);
     match alloc_type {
         Some(AllocType::Static(did)) => {
             let instance = Instance::mono(tcx, did);
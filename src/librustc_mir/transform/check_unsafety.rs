@@ -119,6 +119,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
             StatementKind::Retag { .. } |
             StatementKind::EscapeToRaw { .. } |
             StatementKind::AscribeUserType(..) |
+            StatementKind::CoverageCounter { .. } |
             StatementKind::Nop => {
                 // safe (at least as emitted during MIR construction)
             }
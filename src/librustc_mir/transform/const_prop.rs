@@ -9,7 +9,8 @@
 // except according to those terms.
 
 //! Propagates constants for early reporting of statically known
-//! assertion failures
+//! assertion failures, and replaces statements computing a statically
+//! known scalar with that constant.
 
 
 use rustc::hir::def::Def;
@@ -18,25 +19,39 @@ use rustc::mir::{NullOp, UnOp, StatementKind, Statement, BasicBlock, LocalKind};
 use rustc::mir::{TerminatorKind, ClearCrossCrate, SourceInfo, BinOp, ProjectionElem};
 use rustc::mir::visit::{Visitor, PlaceContext, MutatingUseContext, NonMutatingUseContext};
 use rustc::mir::interpret::{EvalErrorKind, Scalar, GlobalId, EvalResult};
+use rustc::session::Session;
 use rustc::ty::{TyCtxt, self, Instance};
 use syntax::source_map::{Span, DUMMY_SP};
 use rustc::ty::subst::Substs;
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc::ty::ParamEnv;
 use rustc::ty::layout::{
-    LayoutOf, TyLayout, LayoutError,
+    self, LayoutOf, TyLayout, LayoutError,
     HasTyCtxt, TargetDataLayout, HasDataLayout,
 };
 
 use interpret::{self, EvalContext, ScalarMaybeUndef, Immediate, OpTy, MemoryKind};
 use const_eval::{
-    CompileTimeInterpreter, const_to_op, error_to_const_error, eval_promoted, mk_borrowck_eval_cx
+    CompileTimeInterpreter, const_to_op, op_to_const, error_to_const_error, eval_promoted,
+    mk_borrowck_eval_cx,
 };
-use transform::{MirPass, MirSource};
+use transform::{is_pass_enabled, MirPass, MirSource};
 
 pub struct ConstProp;
 
 impl MirPass for ConstProp {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 0
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn reports_lints(&self) -> bool {
+        true
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
@@ -69,8 +84,26 @@ impl MirPass for ConstProp {
         // constants, instead of just checking for const-folding succeeding.
         // That would require an uniform one-def no-mutation analysis
         // and RPO (or recursing when needing the value of a local).
-        let mut optimization_finder = ConstPropagator::new(mir, tcx, source);
-        optimization_finder.visit_mir(mir);
+        let replacements = {
+            let mut optimization_finder = ConstPropagator::new(mir, tcx, source);
+            optimization_finder.visit_mir(mir);
+            optimization_finder.replacements
+        };
+
+        // The lints above are emitted independently of the optimization level,
+        // but only actually fold the statements if the pass is enabled.
+        if is_pass_enabled(tcx.sess, self, &self.name()) {
+            for (location, constant) in replacements {
+                trace!("replacing statement at {:?} with {:?}", location, constant);
+                let block = &mut mir.basic_blocks_mut()[location.block];
+                match block.statements[location.statement_index].kind {
+                    StatementKind::Assign(_, ref mut rval) => {
+                        **rval = Rvalue::Use(Operand::Constant(box constant));
+                    }
+                    ref kind => bug!("const prop replacement for non-assignment {:?}", kind),
+                }
+            }
+        }
 
         trace!("ConstProp done for {:?}", source.def_id);
     }
@@ -87,6 +120,8 @@ struct ConstPropagator<'a, 'mir, 'tcx:'a+'mir> {
     places: IndexVec<Local, Option<Const<'tcx>>>,
     can_const_prop: IndexVec<Local, bool>,
     param_env: ParamEnv<'tcx>,
+    /// Assignments whose right-hand side evaluated to a known scalar.
+    replacements: Vec<(Location, Constant<'tcx>)>,
 }
 
 impl<'a, 'b, 'tcx> LayoutOf for ConstPropagator<'a, 'b, 'tcx> {
@@ -130,9 +165,34 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
             param_env,
             can_const_prop: CanConstProp::check(mir),
             places: IndexVec::from_elem(None, &mir.local_decls),
+            replacements: Vec::new(),
         }
     }
 
+    /// Turns a value computed by `const_prop` into a MIR constant, if it is a plain
+    /// scalar. Pointers refer to allocations of `self.ecx`, which are thrown away
+    /// after this pass, so they are never turned into constants.
+    fn to_mir_constant(&self, value: Const<'tcx>) -> Option<Constant<'tcx>> {
+        let (op, span) = value;
+        match op.layout.abi {
+            layout::Abi::Scalar(..) => {}
+            _ => return None,
+        }
+        match op.op {
+            interpret::Operand::Immediate(Immediate::Scalar(
+                ScalarMaybeUndef::Scalar(Scalar::Bits { .. })
+            )) => {}
+            _ => return None,
+        }
+        let literal = op_to_const(&self.ecx, op, false).ok()?;
+        Some(Constant {
+            span,
+            ty: op.layout.ty,
+            user_ty: None,
+            literal,
+        })
+    }
+
     fn use_ecx<F, T>(
         &mut self,
         source_info: SourceInfo,
@@ -188,6 +248,7 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
                     | ValidationFailure(..)
                     | InvalidPointerMath
                     | ReadUndefBytes(_)
+                    | DanglingPointer(_)
                     | DeadLocal
                     | InvalidBoolOp(_)
                     | DerefFunctionPointer
@@ -198,7 +259,7 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
                     | AlignmentCheckFailed{..}
                     | CalledClosureAsFunction
                     | VtableForArgumentlessMethod
-                    | ModifiedConstantMemory
+                    | ModifiedConstantMemory(_)
                     | ModifiedStatic
                     | AssumptionNotHeld
                     // FIXME: should probably be removed and turned into a bug! call
@@ -211,12 +272,17 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
                     | UnterminatedCString(_)
                     | HeapAllocZeroBytes
                     | HeapAllocNonPowerOfTwoAlignment(_)
+                    | LeakedAllocation
+                    | MutableHeapAllocation
+                    | EscapingMutRef
                     | Unreachable
                     | ReadFromReturnPointer
                     | GeneratorResumedAfterReturn
                     | GeneratorResumedAfterPanic
                     | ReferencedConstant
                     | InfiniteLoop
+                    | StepLimitReached
+                    | MemoryExhausted
                     => {
                         // FIXME: report UB here
                     },
@@ -235,6 +301,9 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
 
                     // non deterministic
                     | ReadPointerAsBytes
+                    | ReadPartialPointer
+                    | PointerAsInteger(_)
+                    | InvalidPointerComparison
                     // FIXME: implement
                     => {},
 
@@ -569,6 +638,13 @@ impl<'b, 'a, 'tcx> Visitor<'tcx> for ConstPropagator<'b, 'a, 'tcx> {
                 .to_ty(self.tcx);
             if let Ok(place_layout) = self.tcx.layout_of(self.param_env.and(place_ty)) {
                 if let Some(value) = self.const_prop(rval, place_layout, statement.source_info) {
+                    match **rval {
+                        // nothing to fold
+                        Rvalue::Use(Operand::Constant(_)) => {}
+                        _ => if let Some(constant) = self.to_mir_constant(value) {
+                            self.replacements.push((location, constant));
+                        },
+                    }
                     if let Place::Local(local) = *place {
                         trace!("checking whether {:?} can be stored to {:?}", value, local);
                         if self.can_const_prop[local] {
@@ -25,29 +25,45 @@
 //!     ...
 //!     USE(SRC)
 //!
-//! The assignment `DEST = SRC` must be (a) the only mutation of `DEST` and (b) the only
-//! (non-mutating) use of `SRC`. These restrictions are conservative and may be relaxed in the
-//! future.
+//! The assignment `DEST = SRC` must be the only mutation of `DEST`. If it is not also the only
+//! (non-mutating) use of `SRC`, neither local may ever be borrowed, and:
+//!
+//! * if `SRC` is copied, every `move SRC` that can still be followed by another use of `SRC`
+//!   after the merge is turned into a copy;
+//! * if `SRC` is moved, no other use of `SRC` may be reachable from the move, so that `DEST`
+//!   simply takes over the moved value ("move forwarding").
+//!
+//! These restrictions are conservative and may be relaxed in the future.
+//!
+//! Since the pass runs until nothing changes, chains like `_2 = _1; _3 = _2` are collapsed as
+//! well.
 
-use rustc::mir::{Constant, Local, LocalKind, Location, Place, Mir, Operand, Rvalue, StatementKind};
+use rustc::mir::{BasicBlock, Constant, Local, LocalKind, Location, Place, Mir, Operand, Rvalue};
+use rustc::mir::StatementKind;
 use rustc::mir::visit::MutVisitor;
+use rustc_data_structures::bit_set::BitSet;
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use transform::{MirPass, MirSource};
-use util::def_use::DefUseAnalysis;
+use util::def_use::{DefUseAnalysis, Info};
 
 pub struct CopyPropagation;
 
 impl MirPass for CopyPropagation {
-    fn run_pass<'a, 'tcx>(&self,
-                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                          _source: MirSource,
-                          mir: &mut Mir<'tcx>) {
+    fn is_enabled(&self, sess: &Session) -> bool {
         // We only run when the MIR optimization level is > 1.
         // This avoids a slow pass, and messing up debug info.
-        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
-            return;
-        }
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
 
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          _tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
         let mut def_use_analysis = DefUseAnalysis::new(mir);
         loop {
             def_use_analysis.analyze(mir);
@@ -107,9 +123,13 @@ impl MirPass for CopyPropagation {
                         StatementKind::Assign(Place::Local(local), box Rvalue::Use(ref operand)) if
                                 local == dest_local => {
                             let maybe_action = match *operand {
-                                Operand::Copy(ref src_place) |
+                                Operand::Copy(ref src_place) => {
+                                    Action::local_copy(&mir, &def_use_analysis, dest_local,
+                                                       location, src_place, true)
+                                }
                                 Operand::Move(ref src_place) => {
-                                    Action::local_copy(&mir, &def_use_analysis, src_place)
+                                    Action::local_copy(&mir, &def_use_analysis, dest_local,
+                                                       location, src_place, false)
                                 }
                                 Operand::Constant(ref src_constant) => {
                                     Action::constant(src_constant)
@@ -178,12 +198,23 @@ fn eliminate_self_assignments<'tcx>(
 }
 
 enum Action<'tcx> {
-    PropagateLocalCopy(Local),
+    PropagateLocalCopy {
+        src_local: Local,
+        /// Whether the source has other uses besides the copy into the destination.
+        shared: bool,
+        /// Whether the source is copied rather than moved into the destination.
+        is_copy: bool,
+    },
     PropagateConstant(Constant<'tcx>),
 }
 
 impl<'tcx> Action<'tcx> {
-    fn local_copy(mir: &Mir<'tcx>, def_use_analysis: &DefUseAnalysis, src_place: &Place<'tcx>)
+    fn local_copy(mir: &Mir<'tcx>,
+                  def_use_analysis: &DefUseAnalysis,
+                  dest_local: Local,
+                  location: Location,
+                  src_place: &Place<'tcx>,
+                  is_copy: bool)
                   -> Option<Action<'tcx>> {
         // The source must be a local.
         let src_local = if let Place::Local(local) = *src_place {
@@ -194,16 +225,35 @@ impl<'tcx> Action<'tcx> {
         };
 
         // We're trying to copy propagate a local.
-        // There must be exactly one use of the source used in a statement (not in a terminator).
         let src_use_info = def_use_analysis.local_info(src_local);
         let src_use_count = src_use_info.use_count();
         if src_use_count == 0 {
             debug!("  Can't copy-propagate local: no uses");
             return None
         }
-        if src_use_count != 1 {
-            debug!("  Can't copy-propagate local: {} uses", src_use_info.use_count());
-            return None
+
+        // If the source is used elsewhere too, both locals end up being the same
+        // after propagation. That is only fine if neither local has its address
+        // observed, since a borrow could be used to compare addresses or to mutate
+        // through a `Cell`. A moved source must moreover be dead after the move,
+        // so that the destination can take over its value.
+        let shared = src_use_count != 1;
+        if shared {
+            if !is_copy {
+                let other_uses = other_use_locations(src_use_info, location);
+                if reaches_any(mir, location, &other_uses) {
+                    debug!("  Can't copy-propagate local: moved src used after the move");
+                    return None
+                }
+            }
+            let dest_use_info = def_use_analysis.local_info(dest_local);
+            let is_borrowed = |info: &Info| {
+                info.defs_and_uses.iter().any(|place_use| place_use.context.is_borrow())
+            };
+            if is_borrowed(src_use_info) || is_borrowed(dest_use_info) {
+                debug!("  Can't copy-propagate local: src or dest is borrowed");
+                return None
+            }
         }
 
         // Verify that the source doesn't change in between. This is done conservatively for now,
@@ -230,7 +280,7 @@ impl<'tcx> Action<'tcx> {
             return None
         }
 
-        Some(Action::PropagateLocalCopy(src_local))
+        Some(Action::PropagateLocalCopy { src_local, shared, is_copy })
     }
 
     fn constant(src_constant: &Constant<'tcx>) -> Option<Action<'tcx>> {
@@ -244,7 +294,7 @@ impl<'tcx> Action<'tcx> {
                location: Location)
                -> bool {
         match self {
-            Action::PropagateLocalCopy(src_local) => {
+            Action::PropagateLocalCopy { src_local, shared, is_copy } => {
                 // Eliminate the destination and the assignment.
                 //
                 // First, remove all markers.
@@ -267,6 +317,25 @@ impl<'tcx> Action<'tcx> {
                 // Replace all uses of the destination local with the source local.
                 def_use_analysis.replace_all_defs_and_uses_with(dest_local, mir, src_local);
 
+                // The uses of both locals now refer to the source, so none of them may move
+                // out of it while another one can still follow. A moved source was checked
+                // to be dead after the move in `local_copy`.
+                if shared && is_copy {
+                    let mut merged_uses =
+                        other_use_locations(def_use_analysis.local_info(src_local), location);
+                    merged_uses.extend(
+                        other_use_locations(def_use_analysis.local_info(dest_local), location));
+                    let mut visitor = MoveToCopyVisitor { local: src_local };
+                    for (i, &use_location) in merged_uses.iter().enumerate() {
+                        let mut others = merged_uses.clone();
+                        others.remove(i);
+                        if others.contains(&use_location) ||
+                           reaches_any(mir, use_location, &others) {
+                            visitor.visit_location(mir, use_location)
+                        }
+                    }
+                }
+
                 // Finally, zap the now-useless assignment instruction.
                 debug!("  Deleting assignment");
                 mir.make_statement_nop(location);
@@ -318,6 +387,34 @@ impl<'tcx> Action<'tcx> {
     }
 }
 
+/// Returns the locations of all defs and uses in `info` other than `location` and the storage
+/// markers.
+fn other_use_locations(info: &Info, location: Location) -> Vec<Location> {
+    info.defs_and_uses.iter().filter(|place_use| {
+        !place_use.context.is_storage_marker() && place_use.location != location
+    }).map(|place_use| place_use.location).collect()
+}
+
+/// Returns true if any of `locations` can be executed after `from`.
+fn reaches_any(mir: &Mir, from: Location, locations: &[Location]) -> bool {
+    if locations.iter().any(|l| l.block == from.block && l.statement_index > from.statement_index) {
+        return true
+    }
+
+    let mut visited = BitSet::new_empty(mir.basic_blocks().len());
+    let mut stack: Vec<BasicBlock> = mir[from.block].terminator().successors().cloned().collect();
+    while let Some(block) = stack.pop() {
+        if !visited.insert(block) {
+            continue
+        }
+        if locations.iter().any(|l| l.block == block) {
+            return true
+        }
+        stack.extend(mir[block].terminator().successors().cloned());
+    }
+    false
+}
+
 struct ConstantPropagationVisitor<'tcx> {
     dest_local: Local,
     constant: Constant<'tcx>,
@@ -349,3 +446,19 @@ impl<'tcx> MutVisitor<'tcx> for ConstantPropagationVisitor<'tcx> {
         self.uses_replaced += 1
     }
 }
+
+struct MoveToCopyVisitor {
+    local: Local,
+}
+
+impl<'tcx> MutVisitor<'tcx> for MoveToCopyVisitor {
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        self.super_operand(operand, location);
+
+        if let Operand::Move(Place::Local(local)) = *operand {
+            if local == self.local {
+                *operand = Operand::Copy(Place::Local(local));
+            }
+        }
+    }
+}
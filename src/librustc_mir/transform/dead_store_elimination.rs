@@ -0,0 +1,167 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Dead store elimination.
+//!
+//! This removes assignments to locals whose value is never read before the local is either
+//! overwritten or goes out of scope. We look for patterns like:
+//!
+//!     DEST = RVALUE
+//!     ...            // no reads of `DEST`
+//!     DEST = RVALUE2 // or `StorageDead(DEST)`, or the end of the function
+//!
+//! and replace the first assignment with a `nop`.
+//!
+//! Liveness is computed with the `MaybeLiveLocals` analysis and bounded by `MaybeStorageLive`,
+//! so a local can only be live where its storage may be live. Locals that are
+//! borrowed anywhere in the body are never touched, since the liveness analysis does not track
+//! reads through references, and neither is the return place, which is read by `return`.
+
+use rustc::mir::*;
+use rustc::mir::visit::Visitor;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::BitSet;
+use dataflow::{do_dataflow, DebugFormatted, state_for_location};
+use dataflow::{MaybeLiveLocals, MaybeStorageLive};
+use dataflow::generic::{Analysis, Engine};
+use transform::{MirPass, MirSource};
+use util::liveness::LiveVarSet;
+
+pub struct DeadStoreElimination;
+
+impl MirPass for DeadStoreElimination {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // Like copy propagation, this throws away values of user variables,
+        // which would mess up debug info, so only run it when asked to.
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let dead_stores = find_dead_stores(tcx, source, mir);
+        debug!("run_pass: removing dead stores at {:?}", dead_stores);
+
+        for location in dead_stores {
+            mir.make_statement_nop(location);
+        }
+    }
+}
+
+fn find_dead_stores<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              source: MirSource,
+                              mir: &Mir<'tcx>)
+                              -> Vec<Location> {
+    let dead_unwinds = BitSet::new_empty(mir.basic_blocks().len());
+
+    // Storage liveness gives an upper bound on where a local may be live.
+    let storage_live_analysis = MaybeStorageLive::new(mir);
+    let storage_live =
+        do_dataflow(tcx, mir, source, &[], &dead_unwinds, storage_live_analysis,
+                    |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
+
+    // Locals without any storage statements have their storage live everywhere.
+    let mut ignored = StorageIgnored(BitSet::new_filled(mir.local_decls.len()));
+    ignored.visit_mir(mir);
+
+    let mut borrowed = BorrowedLocals(BitSet::new_empty(mir.local_decls.len()));
+    borrowed.visit_mir(mir);
+
+    let liveness = Engine::new(tcx, mir, source.def_id, MaybeLiveLocals).iterate_to_fixpoint();
+
+    let mut dead_stores = vec![];
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        let terminator_loc = Location {
+            block,
+            statement_index: data.statements.len(),
+        };
+
+        // Start with the locals live on exit from the block, restricted to the
+        // ones whose storage may still be live there.
+        let mut live = state_for_location(terminator_loc,
+                                          &storage_live_analysis,
+                                          &storage_live,
+                                          mir);
+        live.union(&ignored.0);
+        live.intersect(liveness.entry_set_for_block(block));
+
+        liveness.analysis.apply_terminator_effect(&mut live, data.terminator(), terminator_loc);
+
+        for (statement_index, statement) in data.statements.iter().enumerate().rev() {
+            let location = Location { block, statement_index };
+
+            if let StatementKind::Assign(Place::Local(local), _) = statement.kind {
+                if local != RETURN_PLACE && !borrowed.0.contains(local) && !live.contains(local) {
+                    // The statement is going away, so the locals it reads do
+                    // not become live because of it.
+                    dead_stores.push(location);
+                    continue;
+                }
+            }
+
+            liveness.analysis.apply_statement_effect(&mut live, statement, location);
+        }
+    }
+
+    dead_stores
+}
+
+struct StorageIgnored(LiveVarSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for StorageIgnored {
+    fn visit_statement(&mut self,
+                       _block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       _location: Location) {
+        match statement.kind {
+            StatementKind::StorageLive(l) |
+            StatementKind::StorageDead(l) => { self.0.remove(l); }
+            _ => (),
+        }
+    }
+}
+
+struct BorrowedLocals(LiveVarSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_rvalue(&mut self,
+                    rvalue: &Rvalue<'tcx>,
+                    location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            if let Some(local) = borrowed_local(place) {
+                self.0.insert(local);
+            }
+        }
+
+        self.super_rvalue(rvalue, location)
+    }
+}
+
+/// The local whose memory `place` points into, unless the place goes
+/// through a dereference (in which case the memory is not the local's).
+fn borrowed_local<'tcx>(place: &Place<'tcx>) -> Option<Local> {
+    match *place {
+        Place::Local(l) => Some(l),
+        Place::Promoted(_) |
+        Place::Static(..) => None,
+        Place::Projection(ref proj) => {
+            match proj.elem {
+                ProjectionElem::Deref => None,
+                _ => borrowed_local(&proj.base),
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Devirtualization of calls through trait objects.
+//!
+//! When a trait object is created from a reference to a known type right
+//! before a method is called on it, the method of that type can be called
+//! directly instead of going through the vtable:
+//!
+//!     _3 = move _2 as &dyn Trait (Unsize);
+//!     _4 = &(*_3);
+//!     _5 = <dyn Trait as Trait>::method(move _4) -> bb1;
+//!
+//! becomes
+//!
+//!     _3 = move _2 as &dyn Trait (Unsize);
+//!     _4 = &(*_3);
+//!     _5 = <Foo as Trait>::method(_2) -> bb1;
+//!
+//! which is faster and also lets the inliner look into the callee. The
+//! statements producing the trait object are left for other passes to remove.
+//!
+//! Only the statements of the block ending in the call are looked at, and the
+//! original reference must not be touched between the cast and the call.
+//! Only shared references are handled, as the original reference is copied.
+
+use rustc::hir;
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::{self, Instance, InstanceDef, Ty, TyCtxt};
+use transform::{MirPass, MirSource};
+
+pub struct Devirtualize;
+
+impl MirPass for Devirtualize {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // This mostly pays off together with inlining, so run it at the same level.
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let param_env = tcx.param_env(source.def_id);
+
+        let mut calls = vec![];
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            if let Some(call) = devirtualize_call(tcx, param_env, mir, data) {
+                calls.push((block, call));
+            }
+        }
+
+        for (block, (new_func, receiver)) in calls {
+            debug!("Devirtualize: calling {:?} in {:?}", new_func, block);
+            match mir[block].terminator_mut().kind {
+                TerminatorKind::Call { ref mut func, ref mut args, .. } => {
+                    *func = new_func;
+                    args[0] = receiver;
+                }
+                _ => bug!("Devirtualize: {:?} does not end in a call", block),
+            }
+        }
+    }
+}
+
+/// If `data` ends in a virtual call on a trait object that was created from a
+/// known type, returns the function to call instead and its receiver.
+fn devirtualize_call<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                               param_env: ty::ParamEnv<'tcx>,
+                               mir: &Mir<'tcx>,
+                               data: &BasicBlockData<'tcx>)
+                               -> Option<(Operand<'tcx>, Operand<'tcx>)> {
+    let terminator = data.terminator();
+    let (func, args) = match terminator.kind {
+        TerminatorKind::Call { ref func, ref args, .. } => (func, args),
+        _ => return None,
+    };
+    let (def_id, substs) = match func.ty(mir, tcx).sty {
+        ty::FnDef(def_id, substs) => (def_id, substs),
+        _ => return None,
+    };
+    match Instance::resolve(tcx, param_env, def_id, substs) {
+        Some(Instance { def: InstanceDef::Virtual(..), .. }) => {}
+        _ => return None,
+    }
+
+    let receiver = match args.first() {
+        Some(&Operand::Copy(Place::Local(local))) |
+        Some(&Operand::Move(Place::Local(local))) => local,
+        _ => return None,
+    };
+    // The original reference is passed again in place of the receiver, which
+    // is only sound if it may be copied.
+    match mir.local_decls[receiver].ty.sty {
+        ty::Ref(_, _, hir::MutImmutable) => {}
+        _ => return None,
+    }
+    let (source, concrete_ty) = unsize_source(mir, &data.statements, receiver)?;
+    match mir.local_decls[source].ty.sty {
+        ty::Ref(_, _, hir::MutImmutable) => {}
+        _ => return None,
+    }
+
+    let substs = tcx.mk_substs_trait(concrete_ty, &substs[1..]);
+    let instance = Instance::resolve(tcx, param_env, def_id, substs)?;
+    match instance.def {
+        InstanceDef::Item(_) => {}
+        _ => return None,
+    }
+
+    let new_func = Operand::function_handle(tcx,
+                                            instance.def_id(),
+                                            instance.substs,
+                                            terminator.source_info.span);
+    Some((new_func, Operand::Copy(Place::Local(source))))
+}
+
+/// Follows copies and reborrows of `local` back to the reference it was
+/// unsized from. Returns that reference and the type it points to.
+fn unsize_source<'tcx>(mir: &Mir<'tcx>,
+                       statements: &[Statement<'tcx>],
+                       local: Local)
+                       -> Option<(Local, Ty<'tcx>)> {
+    let mut current = local;
+    for (index, statement) in statements.iter().enumerate().rev() {
+        if let StatementKind::Assign(Place::Local(dest), ref rvalue) = statement.kind {
+            if dest == current {
+                match **rvalue {
+                    Rvalue::Use(Operand::Copy(Place::Local(src))) |
+                    Rvalue::Use(Operand::Move(Place::Local(src))) => {
+                        current = src;
+                        continue;
+                    }
+                    Rvalue::Ref(_, _, Place::Projection(ref proj)) => {
+                        match (&proj.base, &proj.elem) {
+                            (&Place::Local(src), &ProjectionElem::Deref) => {
+                                current = src;
+                                continue;
+                            }
+                            _ => return None,
+                        }
+                    }
+                    Rvalue::Cast(CastKind::Unsize, Operand::Copy(Place::Local(src)), _) |
+                    Rvalue::Cast(CastKind::Unsize, Operand::Move(Place::Local(src)), _) => {
+                        let pointee = match mir.local_decls[src].ty.sty {
+                            ty::Ref(_, pointee, _) => pointee,
+                            _ => return None,
+                        };
+                        let touched_later = statements[index + 1..].iter().any(|statement| {
+                            mentions(statement, src)
+                        });
+                        return if touched_later { None } else { Some((src, pointee)) };
+                    }
+                    _ => return None,
+                }
+            }
+        }
+
+        if mentions(statement, current) {
+            return None;
+        }
+    }
+    None
+}
+
+fn mentions<'tcx>(statement: &Statement<'tcx>, local: Local) -> bool {
+    struct Mentions {
+        local: Local,
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for Mentions {
+        fn visit_local(&mut self, &local: &Local, _: PlaceContext<'tcx>, _: Location) {
+            if local == self.local {
+                self.found = true;
+            }
+        }
+    }
+
+    let mut visitor = Mentions { local, found: false };
+    visitor.visit_statement(START_BLOCK, statement, Location {
+        block: START_BLOCK,
+        statement_index: 0,
+    });
+    visitor.found
+}
@@ -21,7 +21,6 @@ use rustc::mir::*;
 use rustc::util::nodemap::FxHashMap;
 use rustc_data_structures::bit_set::BitSet;
 use std::fmt;
-use syntax::ast;
 use syntax_pos::Span;
 use transform::{MirPass, MirSource};
 use util::patch::MirPatch;
@@ -38,7 +37,6 @@ impl MirPass for ElaborateDrops {
     {
         debug!("elaborate_drops({:?} @ {:?})", src, mir.span);
 
-        let id = tcx.hir().as_local_node_id(src.def_id).unwrap();
         let param_env = tcx.param_env(src.def_id).with_reveal_all();
         let move_data = match MoveData::gather_moves(mir, tcx) {
             Ok(move_data) => move_data,
@@ -60,13 +58,13 @@ impl MirPass for ElaborateDrops {
                 move_data,
                 param_env,
             };
-            let dead_unwinds = find_dead_unwinds(tcx, mir, id, &env);
+            let dead_unwinds = find_dead_unwinds(tcx, mir, src, &env);
             let flow_inits =
-                do_dataflow(tcx, mir, id, &[], &dead_unwinds,
+                do_dataflow(tcx, mir, src, &[], &dead_unwinds,
                             MaybeInitializedPlaces::new(tcx, mir, &env),
                             |bd, p| DebugFormatted::new(&bd.move_data().move_paths[p]));
             let flow_uninits =
-                do_dataflow(tcx, mir, id, &[], &dead_unwinds,
+                do_dataflow(tcx, mir, src, &[], &dead_unwinds,
                             MaybeUninitializedPlaces::new(tcx, mir, &env),
                             |bd, p| DebugFormatted::new(&bd.move_data().move_paths[p]));
 
@@ -90,7 +88,7 @@ impl MirPass for ElaborateDrops {
 fn find_dead_unwinds<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     mir: &Mir<'tcx>,
-    id: ast::NodeId,
+    src: MirSource,
     env: &MoveDataParamEnv<'tcx, 'tcx>)
     -> BitSet<BasicBlock>
 {
@@ -99,7 +97,7 @@ fn find_dead_unwinds<'a, 'tcx>(
     // reach cleanup blocks, which can't have unwind edges themselves.
     let mut dead_unwinds = BitSet::new_empty(mir.basic_blocks().len());
     let flow_inits =
-        do_dataflow(tcx, mir, id, &[], &dead_unwinds,
+        do_dataflow(tcx, mir, src, &[], &dead_unwinds,
                     MaybeInitializedPlaces::new(tcx, mir, &env),
                     |bd, p| DebugFormatted::new(&bd.move_data().move_paths[p]));
     for (bb, bb_data) in mir.basic_blocks().iter_enumerated() {
@@ -332,11 +330,48 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
 
     fn create_drop_flag(&mut self, index: MovePathIndex, span: Span) {
         let tcx = self.tcx;
-        let patch = &mut self.patch;
         debug!("create_drop_flag({:?})", self.mir.span);
-        self.drop_flags.entry(index).or_insert_with(|| {
-            patch.new_internal(tcx.types.bool, span)
-        });
+        let created = {
+            let patch = &mut self.patch;
+            let mut created = false;
+            self.drop_flags.entry(index).or_insert_with(|| {
+                created = true;
+                patch.new_internal(tcx.types.bool, span)
+            });
+            created
+        };
+        if created && tcx.sess.opts.debugging_opts.dump_drop_elaboration {
+            let msg = format!("drop flag created for `{}`", self.describe_path(index));
+            tcx.sess.span_note_without_error(span, &msg);
+        }
+    }
+
+    /// Names the place at `path` for `-Z dump-drop-elaboration`.
+    fn describe_path(&self, path: MovePathIndex) -> String {
+        let place = &self.move_data().move_paths[path].place;
+        if let Place::Local(local) = *place {
+            if let Some(name) = self.mir.local_decls[local].name {
+                return name.to_string();
+            }
+        }
+        format!("{:?}", place)
+    }
+
+    /// Reports how the drop of `path` at `span` was elaborated, for
+    /// `-Z dump-drop-elaboration`. Unconditional drops are not reported.
+    fn note_drop_style(&self, span: Span, path: MovePathIndex, style: DropStyle,
+                       is_cleanup: bool) {
+        let what = match style {
+            DropStyle::Static => return,
+            DropStyle::Dead => "is a no-op",
+            DropStyle::Conditional => "is conditional on its drop flag",
+            DropStyle::Open => "is split into drops of its fields",
+        };
+        let msg = format!("drop of `{}`{} {}",
+                          self.describe_path(path),
+                          if is_cleanup { " on unwind" } else { "" },
+                          what);
+        self.tcx.sess.span_note_without_error(span, &msg);
     }
 
     fn drop_flag(&mut self, index: MovePathIndex) -> Option<Place<'tcx>> {
@@ -415,6 +450,14 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
                     let init_data = self.initialization_data_at(loc);
                     match self.move_data().rev_lookup.find(location) {
                         LookupResult::Exact(path) => {
+                            if self.tcx.sess.opts.debugging_opts.dump_drop_elaboration {
+                                let style = Elaborator {
+                                    init_data: &init_data,
+                                    ctxt: self
+                                }.drop_style(path, DropFlagMode::Deep);
+                                self.note_drop_style(terminator.source_info.span, path, style,
+                                                     data.is_cleanup);
+                            }
                             elaborate_drop(
                                 &mut Elaborator {
                                     init_data: &init_data,
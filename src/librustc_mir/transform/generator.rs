@@ -35,6 +35,10 @@
 //!         mir_locals...,
 //!     }
 //!
+//! MIR locals of the same type whose storage is never live at the same time share a field, so
+//! a generator which keeps different values alive across different suspension points only needs
+//! room for the ones that can coexist.
+//!
 //! This pass computes the meaning of the state field and the MIR locals which are live
 //! across a suspension point. There are however two hardcoded generator states:
 //!     0 - Generator have not been resumed yet
@@ -70,14 +74,14 @@ use util::dump_mir;
 use util::liveness::{self, IdentityMap};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::indexed_vec::Idx;
-use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::bit_set::{BitMatrix, BitSet};
 use std::borrow::Cow;
 use std::iter::once;
 use std::mem;
 use transform::{MirPass, MirSource};
 use transform::simplify;
 use transform::no_landing_pads::no_landing_pads;
-use dataflow::{do_dataflow, DebugFormatted, state_for_location};
+use dataflow::{do_dataflow, DataflowResults, DebugFormatted, state_for_location};
 use dataflow::{MaybeStorageLive, HaveBeenBorrowedLocals};
 
 pub struct StateTransform;
@@ -377,15 +381,15 @@ fn locals_live_across_suspend_points(
 ) -> (
     liveness::LiveVarSet<Local>,
     FxHashMap<BasicBlock, liveness::LiveVarSet<Local>>,
+    BitMatrix<Local, Local>,
 ) {
     let dead_unwinds = BitSet::new_empty(mir.basic_blocks().len());
-    let node_id = tcx.hir().as_local_node_id(source.def_id).unwrap();
 
     // Calculate when MIR locals have live storage. This gives us an upper bound of their
     // lifetimes.
     let storage_live_analysis = MaybeStorageLive::new(mir);
     let storage_live =
-        do_dataflow(tcx, mir, node_id, &[], &dead_unwinds, storage_live_analysis,
+        do_dataflow(tcx, mir, source, &[], &dead_unwinds, storage_live_analysis,
                     |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
 
     // Find the MIR locals which do not use StorageLive/StorageDead statements.
@@ -399,7 +403,7 @@ fn locals_live_across_suspend_points(
     let borrowed_locals = if !movable {
         let analysis = HaveBeenBorrowedLocals::new(mir);
         let result =
-            do_dataflow(tcx, mir, node_id, &[], &dead_unwinds, analysis,
+            do_dataflow(tcx, mir, source, &[], &dead_unwinds, analysis,
                         |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
         Some((analysis, result))
     } else {
@@ -476,7 +480,54 @@ fn locals_live_across_suspend_points(
     // The generator argument is ignored
     set.remove(self_arg());
 
-    (set, storage_liveness_map)
+    let storage_conflicts = compute_storage_conflicts(mir, &set, &ignored, &storage_live);
+
+    (set, storage_liveness_map, storage_conflicts)
+}
+
+/// For the locals in `stored_locals`, computes which pairs of them may have
+/// live storage at the same point. Locals that never do can be stored in the
+/// same field of the generator struct.
+fn compute_storage_conflicts<'a, 'tcx>(
+    mir: &Mir<'tcx>,
+    stored_locals: &liveness::LiveVarSet<Local>,
+    ignored: &StorageIgnored,
+    storage_live: &DataflowResults<MaybeStorageLive<'a, 'tcx>>,
+) -> BitMatrix<Local, Local> {
+    let local_count = mir.local_decls.len();
+    let mut conflicts = BitMatrix::new(local_count, local_count);
+
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        // Locals without storage statements have live storage everywhere.
+        let mut live = storage_live.sets().on_entry_set_for(block.index()).to_owned();
+        live.union(&ignored.0);
+        live.intersect(stored_locals);
+
+        for local in live.iter() {
+            for other in live.iter() {
+                conflicts.insert(local, other);
+            }
+        }
+
+        // Only `StorageLive` can introduce a new conflict.
+        for statement in &data.statements {
+            match statement.kind {
+                StatementKind::StorageLive(local) if stored_locals.contains(local) => {
+                    live.insert(local);
+                    for other in live.iter() {
+                        conflicts.insert(local, other);
+                        conflicts.insert(other, local);
+                    }
+                }
+                StatementKind::StorageDead(local) => {
+                    live.remove(local);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    conflicts
 }
 
 fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
@@ -490,10 +541,8 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         FxHashMap<BasicBlock, liveness::LiveVarSet<Local>>)
 {
     // Use a liveness analysis to compute locals which are live across a suspension point
-    let (live_locals, storage_liveness) = locals_live_across_suspend_points(tcx,
-                                                                            mir,
-                                                                            source,
-                                                                            movable);
+    let (live_locals, storage_liveness, storage_conflicts) =
+        locals_live_across_suspend_points(tcx, mir, source, movable);
     // Erase regions from the types passed in from typeck so we can compare them with
     // MIR types
     let allowed_upvars = tcx.erase_regions(&upvars);
@@ -522,19 +571,37 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     let upvar_len = mir.upvar_decls.len();
     let dummy_local = LocalDecl::new_internal(tcx.mk_unit(), mir.span);
 
-    // Gather live locals and their indices replacing values in mir.local_decls with a dummy
-    // to avoid changing local indices
-    let live_decls = live_locals.iter().map(|local| {
-        let var = mem::replace(&mut mir.local_decls[local], dummy_local.clone());
-        (local, var)
-    });
-
     // Create a map from local indices to generator struct indices.
     // These are offset by (upvar_len + 1) because of fields which comes before locals.
-    // We also create a vector of the LocalDecls of these locals.
-    let (remap, vars) = live_decls.enumerate().map(|(idx, (local, var))| {
-        ((local, (var.ty, upvar_len + 1 + idx)), var)
-    }).unzip();
+    // A local reuses the field of earlier locals of the same type if its storage is never
+    // live at the same time as theirs. We also create a vector of the LocalDecls of the
+    // first local stored in each field.
+    let mut remap = FxHashMap::default();
+    let mut vars: Vec<LocalDecl<'tcx>> = vec![];
+    let mut field_locals: Vec<Vec<Local>> = vec![];
+    for local in live_locals.iter() {
+        // Replace the value in mir.local_decls with a dummy to avoid changing local indices
+        let var = mem::replace(&mut mir.local_decls[local], dummy_local.clone());
+
+        let shared_field = (0..vars.len()).find(|&idx| {
+            vars[idx].ty == var.ty &&
+                field_locals[idx].iter().all(|&other| !storage_conflicts.contains(local, other))
+        });
+        let idx = match shared_field {
+            Some(idx) => {
+                debug!("compute_layout: {:?} shares field {} with {:?}",
+                       local, upvar_len + 1 + idx, field_locals[idx]);
+                field_locals[idx].push(local);
+                idx
+            }
+            None => {
+                field_locals.push(vec![local]);
+                vars.push(var.clone());
+                vars.len() - 1
+            }
+        };
+        remap.insert(local, (var.ty, upvar_len + 1 + idx));
+    }
 
     let layout = GeneratorLayout {
         fields: vars
@@ -0,0 +1,234 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Global value numbering.
+//!
+//! This finds pure rvalues that compute a value which has already been computed by a
+//! dominating statement, and replaces them with a copy of the earlier result:
+//!
+//!     _3 = Add(_1, _2);
+//!     ...
+//!     _6 = Add(_1, _2);
+//!
+//! becomes
+//!
+//!     _3 = Add(_1, _2);
+//!     ...
+//!     _6 = _3;
+//!
+//! Only locals that are assigned exactly once and never borrowed take part, so the value of
+//! such a local is the same everywhere it is used. Copies between these locals are looked
+//! through, so `_4 = _1; _5 = Add(_4, _2)` computes the same value as `Add(_1, _2)`.
+//!
+//! The reused local now has to outlive its original scope, so its storage markers are removed,
+//! just like copy propagation does.
+
+use rustc::mir::*;
+use rustc::mir::traversal;
+use rustc::mir::visit::{MutatingUseContext, PlaceContext};
+use rustc::session::Session;
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::indexed_vec::IndexVec;
+use transform::{MirPass, MirSource};
+use util::def_use::DefUseAnalysis;
+
+pub struct GlobalValueNumbering;
+
+impl MirPass for GlobalValueNumbering {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          _tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mut def_use_analysis = DefUseAnalysis::new(mir);
+        def_use_analysis.analyze(mir);
+
+        let replacements = find_redundant_values(mir, &def_use_analysis);
+        if replacements.is_empty() {
+            return;
+        }
+
+        for &(location, reused) in &replacements {
+            debug!("run_pass: replacing rvalue at {:?} with a copy of {:?}", location, reused);
+            let statement = &mut mir[location.block].statements[location.statement_index];
+            if let StatementKind::Assign(_, ref mut rvalue) = statement.kind {
+                **rvalue = Rvalue::Use(Operand::Copy(Place::Local(reused)));
+            }
+        }
+
+        for &(_, reused) in &replacements {
+            for place_use in &def_use_analysis.local_info(reused).defs_and_uses {
+                if place_use.context.is_storage_marker() {
+                    mir.make_statement_nop(place_use.location);
+                }
+            }
+        }
+    }
+}
+
+/// An operand of a value, with locals replaced by the local that first
+/// held their value.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum ValueOperand<'tcx> {
+    Local(Local),
+    Constant(ty::Ty<'tcx>, &'tcx ty::Const<'tcx>),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Value<'tcx> {
+    Len(Local),
+    Discriminant(Local),
+    UnaryOp(UnOp, ValueOperand<'tcx>),
+    BinaryOp(BinOp, ValueOperand<'tcx>, ValueOperand<'tcx>),
+    CheckedBinaryOp(BinOp, ValueOperand<'tcx>, ValueOperand<'tcx>),
+}
+
+struct ValueNumbering<'a, 'tcx: 'a> {
+    mir: &'a Mir<'tcx>,
+    /// Locals that are assigned at most once and never borrowed.
+    ssa: IndexVec<Local, bool>,
+    /// For every local that merely copies another one, the local it copies from.
+    leader: IndexVec<Local, Local>,
+    /// The locals holding each value computed so far, with their definitions.
+    values: FxHashMap<Value<'tcx>, Vec<(Local, Location)>>,
+}
+
+fn find_redundant_values<'tcx>(mir: &Mir<'tcx>,
+                               def_use_analysis: &DefUseAnalysis<'tcx>)
+                               -> Vec<(Location, Local)> {
+    let ssa = mir.local_decls.indices().map(|local| {
+        is_ssa(mir, def_use_analysis, local)
+    }).collect();
+    let mut numbering = ValueNumbering {
+        mir,
+        ssa,
+        leader: mir.local_decls.indices().collect(),
+        values: FxHashMap::default(),
+    };
+
+    let dominators = mir.dominators();
+    let mut replacements = vec![];
+
+    // Visiting blocks in reverse postorder makes sure every definition
+    // is seen before the statements it dominates.
+    for (block, data) in traversal::reverse_postorder(mir) {
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            let location = Location { block, statement_index };
+            let (dest, rvalue) = match statement.kind {
+                StatementKind::Assign(Place::Local(dest), ref rvalue) => (dest, rvalue),
+                _ => continue,
+            };
+            if !numbering.ssa[dest] {
+                continue;
+            }
+
+            if let Rvalue::Use(ref operand) = **rvalue {
+                if let Some(ValueOperand::Local(src)) = numbering.operand(operand) {
+                    numbering.leader[dest] = src;
+                }
+                continue;
+            }
+
+            let value = match numbering.value(rvalue) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let reused = numbering.values.get(&value).and_then(|defs| {
+                defs.iter()
+                    .find(|&&(_, def)| def.dominates(location, &dominators))
+                    .map(|&(local, _)| local)
+            });
+            match reused {
+                Some(reused) => {
+                    numbering.leader[dest] = numbering.leader[reused];
+                    replacements.push((location, reused));
+                }
+                None => {
+                    numbering.values.entry(value).or_insert(vec![]).push((dest, location));
+                }
+            }
+        }
+    }
+
+    replacements
+}
+
+fn is_ssa<'tcx>(mir: &Mir<'tcx>,
+                def_use_analysis: &DefUseAnalysis<'tcx>,
+                local: Local)
+                -> bool {
+    if local == RETURN_PLACE {
+        return false;
+    }
+
+    let info = def_use_analysis.local_info(local);
+    if info.defs_and_uses.iter().any(|place_use| place_use.context.is_borrow()) {
+        return false;
+    }
+
+    let mut defs = info.defs_not_including_drop();
+    match (defs.next(), defs.next()) {
+        (None, _) => mir.local_kind(local) == LocalKind::Arg,
+        (Some(def), None) => match def.context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store) |
+            PlaceContext::MutatingUse(MutatingUseContext::Call) => true,
+            _ => false,
+        },
+        (Some(_), Some(_)) => false,
+    }
+}
+
+impl<'a, 'tcx> ValueNumbering<'a, 'tcx> {
+    fn operand(&self, operand: &Operand<'tcx>) -> Option<ValueOperand<'tcx>> {
+        match *operand {
+            Operand::Copy(Place::Local(local)) |
+            Operand::Move(Place::Local(local)) if self.ssa[local] => {
+                Some(ValueOperand::Local(self.leader[local]))
+            }
+            Operand::Constant(ref constant) => {
+                Some(ValueOperand::Constant(constant.ty, constant.literal))
+            }
+            _ => None,
+        }
+    }
+
+    fn local(&self, place: &Place<'tcx>) -> Option<Local> {
+        match *place {
+            Place::Local(local) if self.ssa[local] => Some(self.leader[local]),
+            _ => None,
+        }
+    }
+
+    fn value(&self, rvalue: &Rvalue<'tcx>) -> Option<Value<'tcx>> {
+        match *rvalue {
+            Rvalue::Len(ref place) => self.local(place).map(Value::Len),
+            Rvalue::Discriminant(ref place) => self.local(place).map(Value::Discriminant),
+            Rvalue::UnaryOp(op, ref operand) => {
+                self.operand(operand).map(|operand| Value::UnaryOp(op, operand))
+            }
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                Some(Value::BinaryOp(op, self.operand(lhs)?, self.operand(rhs)?))
+            }
+            Rvalue::CheckedBinaryOp(op, ref lhs, ref rhs) => {
+                Some(Value::CheckedBinaryOp(op, self.operand(lhs)?, self.operand(rhs)?))
+            }
+            _ => None,
+        }
+    }
+}
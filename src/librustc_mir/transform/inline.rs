@@ -19,19 +19,22 @@ use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 
 use rustc::mir::*;
 use rustc::mir::visit::*;
+use rustc::session::Session;
 use rustc::ty::{self, Instance, InstanceDef, ParamEnv, Ty, TyCtxt};
 use rustc::ty::subst::{Subst,Substs};
 
 use std::collections::VecDeque;
 use std::iter;
+use std::rc::Rc;
 use transform::{MirPass, MirSource};
 use super::simplify::{remove_dead_blocks, CfgSimplifier};
 
 use syntax::{attr};
 use rustc_target::spec::abi::Abi;
 
+/// Used unless `-Z mir-inline-threshold` is given. Functions marked `#[inline]`
+/// get twice the threshold.
 const DEFAULT_THRESHOLD: usize = 50;
-const HINT_THRESHOLD: usize = 100;
 
 const INSTR_COST: usize = 5;
 const CALL_PENALTY: usize = 25;
@@ -49,13 +52,19 @@ struct CallSite<'tcx> {
 }
 
 impl MirPass for Inline {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
                           mir: &mut Mir<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
-            Inliner { tcx, source }.run_pass(mir);
-        }
+        Inliner { tcx, source }.run_pass(mir);
     }
 }
 
@@ -77,6 +86,12 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
         // in depth. It is unclear if this is the best heuristic,
         // really, but that's true of all the heuristics in this
         // file. =)
+        //
+        // Queries only detect cycles through the MIR we are currently
+        // optimizing, so each callsite also carries the functions that were
+        // inlined to get to it. We never inline one of those again (nor the
+        // caller itself), which keeps (mutually) recursive functions from
+        // being unrolled until the cost model gives up.
 
         let mut callsites = VecDeque::new();
 
@@ -93,7 +108,7 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                                                                      bb_data,
                                                                      caller_mir,
                                                                      param_env) {
-                    callsites.push_back(callsite);
+                    callsites.push_back((callsite, Rc::new(vec![])));
                 }
             }
         } else {
@@ -105,7 +120,7 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
 
         loop {
             local_change = false;
-            while let Some(callsite) = callsites.pop_front() {
+            while let Some((callsite, history)) = callsites.pop_front() {
                 debug!("checking whether to inline callsite {:?}", callsite);
                 if !self.tcx.is_mir_available(callsite.callee) {
                     debug!("checking whether to inline callsite {:?} - MIR unavailable", callsite);
@@ -139,14 +154,21 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                 debug!("attempting to inline callsite {:?} - success", callsite);
 
                 // Add callsites from inlined function
+                let mut new_history = (*history).clone();
+                new_history.push(callsite.callee);
+                let new_history = Rc::new(new_history);
                 for (bb, bb_data) in caller_mir.basic_blocks().iter_enumerated().skip(start) {
                     if let Some(new_callsite) = self.get_valid_function_call(bb,
                                                                              bb_data,
                                                                              caller_mir,
                                                                              param_env) {
-                        // Don't inline the same function multiple times.
-                        if callsite.callee != new_callsite.callee {
-                            callsites.push_back(new_callsite);
+                        // Don't inline the same function multiple times along one call chain.
+                        if new_callsite.callee != self.source.def_id &&
+                            !new_history.contains(&new_callsite.callee)
+                        {
+                            callsites.push_back((new_callsite, new_history.clone()));
+                        } else {
+                            debug!("not inlining recursive call {:?}", new_callsite);
                         }
                     }
                 }
@@ -267,10 +289,12 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
             }
         }
 
+        let base_threshold = tcx.sess.opts.debugging_opts.mir_inline_threshold
+            .unwrap_or(DEFAULT_THRESHOLD);
         let mut threshold = if hinted {
-            HINT_THRESHOLD
+            base_threshold * 2
         } else {
-            DEFAULT_THRESHOLD
+            base_threshold
         };
 
         // Significantly lower the threshold for inlining cold functions
@@ -407,14 +431,15 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                 let mut scope_map = IndexVec::with_capacity(callee_mir.source_scopes.len());
                 let mut promoted_map = IndexVec::with_capacity(callee_mir.promoted.len());
 
+                // The callee's scopes keep their spans, so that debuginfo (and
+                // with it backtraces) still points into the callee. Only the
+                // outermost scope is attached to the callsite.
                 for mut scope in callee_mir.source_scopes.iter().cloned() {
                     if scope.parent_scope.is_none() {
                         scope.parent_scope = Some(callsite.location.scope);
                         scope.span = callee_mir.span;
                     }
 
-                    scope.span = callsite.location.span;
-
                     let idx = caller_mir.source_scopes.push(scope);
                     scope_map.push(idx);
                 }
@@ -424,7 +449,10 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
 
                     local.source_info.scope =
                         scope_map[local.source_info.scope];
-                    local.source_info.span = callsite.location.span;
+                    // User variables keep their span for debuginfo, see above.
+                    if local.is_user_variable.is_none() {
+                        local.source_info.span = callsite.location.span;
+                    }
                     local.visibility_scope = scope_map[local.visibility_scope];
 
                     let idx = caller_mir.local_decls.push(local);
@@ -707,6 +735,20 @@ impl<'a, 'tcx> MutVisitor<'tcx> for Integrator<'a, 'tcx> {
         self.in_cleanup_block = false;
     }
 
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &mut Statement<'tcx>,
+                       loc: Location) {
+        // The coverage counters of the callee belong to the callee, the
+        // caller gets its own counters once it is done being optimized.
+        if let StatementKind::CoverageCounter { .. } = statement.kind {
+            statement.make_nop();
+            return;
+        }
+
+        self.super_statement(block, statement, loc);
+    }
+
     fn visit_retag(
         &mut self,
         fn_entry: &mut bool,
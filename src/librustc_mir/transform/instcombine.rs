@@ -12,6 +12,7 @@
 
 use rustc::mir::{Constant, Location, Place, Mir, Operand, ProjectionElem, Rvalue, Local};
 use rustc::mir::visit::{MutVisitor, Visitor};
+use rustc::session::Session;
 use rustc::ty::{TyCtxt, TyKind};
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::Idx;
@@ -21,15 +22,19 @@ use transform::{MirPass, MirSource};
 pub struct InstCombine;
 
 impl MirPass for InstCombine {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // We only run when optimizing MIR (at any level).
+        sess.opts.debugging_opts.mir_opt_level > 0
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           _: MirSource,
                           mir: &mut Mir<'tcx>) {
-        // We only run when optimizing MIR (at any level).
-        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
-            return
-        }
-
         // First, find optimization opportunities. This is done in a pre-pass to keep the MIR
         // read-only so that we can do global analyses on the MIR in the process (e.g.
         // `Place::ty()`).
@@ -0,0 +1,70 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Instruments functions for `-Z instrument-mir-coverage`.
+//!
+//! Every basic block that is not a cleanup block gets a `CoverageCounter`
+//! statement at its start, so that the counter is incremented each time the
+//! block is entered. The span of that statement, which is the span of the
+//! first statement of the block (or of its terminator), is the source region
+//! the counter is reported for in the coverage map emitted by codegen.
+//!
+//! Counting on MIR rather than in the backend means the counters line up with
+//! the user's code independently of how the backend lowers it.
+
+use rustc::hir;
+use rustc::mir::*;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use transform::{MirPass, MirSource};
+
+pub struct InstrumentCoverage;
+
+impl MirPass for InstrumentCoverage {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.instrument_mir_coverage
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // Constants and promoteds are evaluated at compile time, only fn
+        // bodies are ever executed.
+        let id = tcx.hir().as_local_node_id(source.def_id).unwrap();
+        match (tcx.hir().body_owner_kind(id), source.promoted) {
+            (hir::BodyOwnerKind::Fn, None) => {}
+            _ => return,
+        }
+
+        let num_counters = mir.basic_blocks()
+            .iter()
+            .filter(|data| !data.is_cleanup)
+            .count() as u32;
+        debug!("run_pass: {} counters for {:?}", num_counters, source);
+
+        let mut index = 0;
+        for data in mir.basic_blocks_mut() {
+            if data.is_cleanup {
+                continue;
+            }
+
+            let source_info = match data.statements.first() {
+                Some(statement) => statement.source_info,
+                None => data.terminator().source_info,
+            };
+            data.statements.insert(0, Statement {
+                source_info,
+                kind: StatementKind::CoverageCounter { index, num_counters },
+            });
+            index += 1;
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A pass that resolves `SwitchInt` terminators whose discriminant is a local
+//! that was just set to a constant.
+//!
+//! `SimplifyBranches` only handles switches on constant operands. After
+//! constant propagation it is common to see
+//!
+//!     _3 = const true;
+//!     switchInt(move _3) -> [false: bb2, otherwise: bb1];
+//!
+//! which this pass turns into `goto -> bb1`. Jumps into a block that does
+//! nothing but switch on such a local are threaded to the right target as
+//! well:
+//!
+//!     bb0: { _3 = const true; goto -> bb4; }
+//!     bb4: { switchInt(move _3) -> [false: bb2, otherwise: bb1]; }
+//!
+//! makes `bb0` jump to `bb1` directly. The now unreachable arms are left for
+//! `SimplifyCfg` to remove.
+
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::{ParamEnv, Ty, TyCtxt};
+use rustc_data_structures::bit_set::BitSet;
+use transform::{MirPass, MirSource};
+
+pub struct JumpThreading;
+
+impl MirPass for JumpThreading {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // A local that is borrowed may be changed behind our back. Switches are
+        // on scalars, so these can only be borrowed as a whole.
+        let mut borrowed = BorrowedLocals(BitSet::new_empty(mir.local_decls.len()));
+        borrowed.visit_mir(mir);
+        let borrowed = borrowed.0;
+
+        // First thread jumps into blocks that only switch on a local.
+        let mut threaded = vec![];
+        {
+            let predecessors = mir.predecessors();
+            for (block, data) in mir.basic_blocks().iter_enumerated() {
+                let only_switches = data.statements.iter().all(|statement| {
+                    match statement.kind {
+                        StatementKind::Nop => true,
+                        _ => false,
+                    }
+                });
+                if !only_switches {
+                    continue;
+                }
+                let (local, switch_ty) = match switch_on_local(&data.terminator().kind) {
+                    Some(switch) => switch,
+                    None => continue,
+                };
+                if borrowed.contains(local) {
+                    continue;
+                }
+
+                for &pred in &predecessors[block] {
+                    let pred_data = &mir[pred];
+                    match pred_data.terminator().kind {
+                        TerminatorKind::Goto { target } if target == block && pred != block => {}
+                        _ => continue,
+                    }
+                    if let Some(bits) = known_value(tcx, &pred_data.statements, local,
+                                                    switch_ty, &borrowed) {
+                        let target = switch_target(&data.terminator().kind, bits);
+                        threaded.push((pred, target));
+                    }
+                }
+            }
+        }
+        for (pred, target) in threaded {
+            debug!("JumpThreading: threading {:?} to {:?}", pred, target);
+            mir[pred].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+
+        // Then resolve switches on locals assigned in the same block.
+        for data in mir.basic_blocks_mut() {
+            let target = {
+                let (local, switch_ty) = match switch_on_local(&data.terminator().kind) {
+                    Some(switch) => switch,
+                    None => continue,
+                };
+                if borrowed.contains(local) {
+                    continue;
+                }
+                match known_value(tcx, &data.statements, local, switch_ty, &borrowed) {
+                    Some(bits) => switch_target(&data.terminator().kind, bits),
+                    None => continue,
+                }
+            };
+            data.terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+fn switch_on_local<'tcx>(kind: &TerminatorKind<'tcx>) -> Option<(Local, Ty<'tcx>)> {
+    match *kind {
+        TerminatorKind::SwitchInt { discr: Operand::Copy(Place::Local(local)), switch_ty, .. } |
+        TerminatorKind::SwitchInt { discr: Operand::Move(Place::Local(local)), switch_ty, .. } => {
+            Some((local, switch_ty))
+        }
+        _ => None,
+    }
+}
+
+/// The block a `SwitchInt` terminator goes to when its discriminant is `bits`.
+fn switch_target(kind: &TerminatorKind, bits: u128) -> BasicBlock {
+    match *kind {
+        TerminatorKind::SwitchInt { ref values, ref targets, .. } => {
+            let (otherwise, targets) = targets.split_last().unwrap();
+            values.iter()
+                .zip(targets.iter())
+                .find(|&(&v, _)| v == bits)
+                .map_or(*otherwise, |(_, &t)| t)
+        }
+        _ => bug!("switch_target: not a switch: {:?}", kind),
+    }
+}
+
+/// The constant value of `local` at the end of `statements`, if it is set to a
+/// constant (possibly through copies of other locals) within them and not
+/// touched in any other way afterwards.
+fn known_value<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                         statements: &[Statement<'tcx>],
+                         local: Local,
+                         ty: Ty<'tcx>,
+                         borrowed: &BitSet<Local>)
+                         -> Option<u128> {
+    for (index, statement) in statements.iter().enumerate().rev() {
+        match statement.kind {
+            StatementKind::Assign(Place::Local(dest), box Rvalue::Use(ref operand))
+                if dest == local =>
+            {
+                return match *operand {
+                    Operand::Constant(ref c) => {
+                        c.literal.assert_bits(tcx, ParamEnv::empty().and(ty))
+                    }
+                    Operand::Copy(Place::Local(src)) |
+                    Operand::Move(Place::Local(src)) if !borrowed.contains(src) => {
+                        known_value(tcx, &statements[..index], src, ty, borrowed)
+                    }
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+
+        let mut mentions = Mentions { local, found: false };
+        mentions.visit_statement(START_BLOCK, statement, Location {
+            block: START_BLOCK,
+            statement_index: index,
+        });
+        if mentions.found {
+            return None;
+        }
+    }
+    None
+}
+
+struct Mentions {
+    local: Local,
+    found: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for Mentions {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext<'tcx>, _: Location) {
+        if local == self.local {
+            self.found = true;
+        }
+    }
+}
+
+struct BorrowedLocals(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        if context.is_borrow() {
+            self.0.insert(local);
+        }
+    }
+}
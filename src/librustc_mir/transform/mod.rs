@@ -8,10 +8,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use borrow_check::disjoint_fields;
 use borrow_check::nll::type_check;
 use build;
 use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc::mir::{Mir, MirPhase, Promoted};
+use rustc::session::{MirSize, Session};
 use rustc::ty::TyCtxt;
 use rustc::ty::query::Providers;
 use rustc::ty::steal::Steal;
@@ -20,6 +22,7 @@ use rustc::hir::intravisit::{self, Visitor, NestedVisitorMap};
 use rustc::util::nodemap::DefIdSet;
 use rustc_data_structures::sync::Lrc;
 use std::borrow::Cow;
+use std::time::{Duration, Instant};
 use syntax::ast;
 use syntax_pos::Span;
 
@@ -32,6 +35,7 @@ pub mod simplify;
 pub mod erase_regions;
 pub mod no_landing_pads;
 pub mod rustc_peek;
+pub mod scalar_replacement;
 pub mod elaborate_drops;
 pub mod add_call_guards;
 pub mod promote_consts;
@@ -40,13 +44,21 @@ mod qualify_min_const_fn;
 pub mod remove_noop_landing_pads;
 pub mod dump_mir;
 pub mod deaggregator;
+pub mod devirtualize;
 pub mod instcombine;
 pub mod copy_prop;
+pub mod dead_store_elimination;
 pub mod const_prop;
 pub mod generator;
+pub mod gvn;
 pub mod inline;
+pub mod instrument_coverage;
+pub mod jump_threading;
 pub mod lower_128bit;
+pub mod nrvo;
 pub mod uniform_array_move_out;
+pub mod unreachable_propagation;
+pub mod validate;
 
 pub(crate) fn provide(providers: &mut Providers) {
     self::qualify_consts::provide(providers);
@@ -149,6 +161,26 @@ pub trait MirPass {
         default_name::<Self>()
     }
 
+    /// Whether the pass runs by default. Optimizations use this to check the
+    /// MIR optimization level; `-Z mir-enable-passes` overrides the result.
+    fn is_enabled(&self, _sess: &Session) -> bool {
+        true
+    }
+
+    /// Whether the pass also reports lints, in which case it runs even when
+    /// it is disabled, and checks `is_pass_enabled` itself before changing
+    /// the MIR.
+    fn reports_lints(&self) -> bool {
+        false
+    }
+
+    /// Whether the pass is an optimization, which `-Z mir-enable-passes` may
+    /// turn on or off. All other passes are needed for the MIR to be correct,
+    /// so they always run.
+    fn is_optimization(&self) -> bool {
+        false
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
@@ -160,7 +192,7 @@ pub fn run_passes(
     mir: &mut Mir<'tcx>,
     def_id: DefId,
     mir_phase: MirPhase,
-    passes: &[&dyn MirPass],
+    passes: &[Box<dyn MirPass>],
 ) {
     let phase_index = mir_phase.phase_index();
 
@@ -175,19 +207,44 @@ pub fn run_passes(
         };
         let mut index = 0;
         let mut run_pass = |pass: &dyn MirPass| {
+            let name = pass.name();
+            if !pass.reports_lints() && !is_pass_enabled(tcx.sess, pass, &name) {
+                return;
+            }
+
             let run_hooks = |mir: &_, index, is_after| {
                 dump_mir::on_mir_pass(tcx, &format_args!("{:03}-{:03}", phase_index, index),
-                                      &pass.name(), source, mir, is_after);
+                                      &name, source, mir, is_after);
             };
             run_hooks(mir, index, false);
-            pass.run_pass(tcx, source, mir);
+            let size_before = if tcx.sess.opts.debugging_opts.mir_stats {
+                Some(MirSize::of(mir))
+            } else {
+                None
+            };
+            if tcx.sess.time_passes() {
+                let start = Instant::now();
+                pass.run_pass(tcx, source, mir);
+                let mut times = tcx.sess.perf_stats.mir_pass_times.lock();
+                *times.entry(name.to_string()).or_insert(Duration::from_secs(0)) +=
+                    start.elapsed();
+            } else {
+                pass.run_pass(tcx, source, mir);
+            }
             run_hooks(mir, index, true);
+            if let Some(size_before) = size_before {
+                tcx.sess.mir_pass_stats.borrow_mut().record_pass(&name, size_before, MirSize::of(mir));
+            }
+
+            if tcx.sess.opts.debugging_opts.validate_mir {
+                validate::validate(tcx, source, mir, &name);
+            }
 
             index += 1;
         };
 
         for pass in passes {
-            run_pass(*pass);
+            run_pass(&**pass);
         }
 
         mir.phase = mir_phase;
@@ -203,103 +260,159 @@ pub fn run_passes(
     }
 }
 
-fn mir_const<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Steal<Mir<'tcx>> {
-    // Unsafety check uses the raw mir, so make sure it is run
-    let _ = tcx.unsafety_check_result(def_id);
-
-    let mut mir = tcx.mir_built(def_id).steal();
-    run_passes(tcx, &mut mir, def_id, MirPhase::Const, &[
-        // What we need to do constant evaluation.
-        &simplify::SimplifyCfg::new("initial"),
-        &type_check::TypeckMir,
-        &rustc_peek::SanityCheck,
-        &uniform_array_move_out::UniformArrayMoveOut,
-    ]);
-    tcx.alloc_steal_mir(mir)
+/// Reports the passes named in `-Z mir-enable-passes` that cannot be toggled,
+/// and warns about the ones that match no pass at all.
+pub fn check_mir_enable_passes(sess: &Session) {
+    let pipelines = [const_passes(), validated_passes(), optimized_passes()];
+    for &(ref name, _) in &sess.opts.debugging_opts.mir_enable_passes {
+        let pass = pipelines.iter()
+            .flat_map(|passes| passes.iter())
+            .find(|pass| pass.name() == &name[..]);
+        match pass {
+            Some(pass) if pass.is_optimization() => {}
+            Some(_) => sess.err(&format!("`-Z mir-enable-passes` cannot toggle `{}`, \
+                                          which is required for correct MIR", name)),
+            None => sess.warn(&format!("`-Z mir-enable-passes` names unknown MIR pass `{}`",
+                                       name)),
+        }
+    }
 }
 
-fn mir_validated<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Steal<Mir<'tcx>> {
-    let node_id = tcx.hir().as_local_node_id(def_id).unwrap();
-    if let hir::BodyOwnerKind::Const = tcx.hir().body_owner_kind(node_id) {
-        // Ensure that we compute the `mir_const_qualif` for constants at
-        // this point, before we steal the mir-const result.
-        let _ = tcx.mir_const_qualif(def_id);
+/// Optimizations named in `-Z mir-enable-passes` are forced on or off, the
+/// remaining passes decide for themselves.
+fn is_pass_enabled(sess: &Session, pass: &dyn MirPass, name: &str) -> bool {
+    if !pass.is_optimization() {
+        return pass.is_enabled(sess);
     }
-
-    let mut mir = tcx.mir_const(def_id).steal();
-    run_passes(tcx, &mut mir, def_id, MirPhase::Validated, &[
-        // What we need to run borrowck etc.
-        &qualify_consts::QualifyAndPromoteConstants,
-        &simplify::SimplifyCfg::new("qualify-consts"),
-    ]);
-    tcx.alloc_steal_mir(mir)
+    sess.opts.debugging_opts.mir_enable_passes.iter()
+        .find(|&&(ref pass_name, _)| pass_name == name)
+        .map(|&(_, enabled)| enabled)
+        .unwrap_or_else(|| pass.is_enabled(sess))
 }
 
-fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Mir<'tcx> {
-    // (Mir-)Borrowck uses `mir_validated`, so we have to force it to
-    // execute before we can steal.
-    let _ = tcx.mir_borrowck(def_id);
+/// The passes that prepare the MIR for constant evaluation.
+fn const_passes() -> Vec<Box<dyn MirPass>> {
+    vec![
+        // What we need to do constant evaluation.
+        box simplify::SimplifyCfg::new("initial"),
+        box type_check::TypeckMir,
+        box rustc_peek::SanityCheck,
+        box uniform_array_move_out::UniformArrayMoveOut,
+    ]
+}
 
-    if tcx.use_ast_borrowck() {
-        let _ = tcx.borrowck(def_id);
-    }
+/// The passes that prepare the MIR for borrowck.
+fn validated_passes() -> Vec<Box<dyn MirPass>> {
+    vec![
+        // What we need to run borrowck etc.
+        box qualify_consts::QualifyAndPromoteConstants,
+        box simplify::SimplifyCfg::new("qualify-consts"),
+    ]
+}
 
-    let mut mir = tcx.mir_validated(def_id).steal();
-    run_passes(tcx, &mut mir, def_id, MirPhase::Optimized, &[
+/// The passes that turn borrow-checked MIR into the MIR that is codegened.
+fn optimized_passes() -> Vec<Box<dyn MirPass>> {
+    vec![
         // Remove all things not needed by analysis
-        &no_landing_pads::NoLandingPads,
-        &simplify_branches::SimplifyBranches::new("initial"),
-        &remove_noop_landing_pads::RemoveNoopLandingPads,
+        box no_landing_pads::NoLandingPads,
+        box simplify_branches::SimplifyBranches::new("initial"),
+        box remove_noop_landing_pads::RemoveNoopLandingPads,
         // Remove all `AscribeUserType` statements.
-        &cleanup_post_borrowck::CleanAscribeUserType,
+        box cleanup_post_borrowck::CleanAscribeUserType,
         // Remove all `FakeRead` statements and the borrows that are only
         // used for checking matches
-        &cleanup_post_borrowck::CleanFakeReadsAndBorrows,
+        box cleanup_post_borrowck::CleanFakeReadsAndBorrows,
 
-        &simplify::SimplifyCfg::new("early-opt"),
+        box simplify::SimplifyCfg::new("early-opt"),
 
         // These next passes must be executed together
-        &add_call_guards::CriticalCallEdges,
-        &elaborate_drops::ElaborateDrops,
-        &no_landing_pads::NoLandingPads,
+        box add_call_guards::CriticalCallEdges,
+        box elaborate_drops::ElaborateDrops,
+        box no_landing_pads::NoLandingPads,
         // AddMovesForPackedDrops needs to run after drop
         // elaboration.
-        &add_moves_for_packed_drops::AddMovesForPackedDrops,
+        box add_moves_for_packed_drops::AddMovesForPackedDrops,
         // AddRetag needs to run after ElaborateDrops, and it needs
         // an AllCallEdges pass right before it.  Otherwise it should
         // run fairly late, but before optimizations begin.
-        &add_call_guards::AllCallEdges,
-        &add_retag::AddRetag,
+        box add_call_guards::AllCallEdges,
+        box add_retag::AddRetag,
 
-        &simplify::SimplifyCfg::new("elaborate-drops"),
+        box simplify::SimplifyCfg::new("elaborate-drops"),
 
         // No lifetime analysis based on borrowing can be done from here on out.
 
         // From here on out, regions are gone.
-        &erase_regions::EraseRegions,
+        box erase_regions::EraseRegions,
 
-        &lower_128bit::Lower128Bit,
+        box lower_128bit::Lower128Bit,
 
 
         // Optimizations begin.
-        &uniform_array_move_out::RestoreSubsliceArrayMoveOut,
-        &inline::Inline,
+        box uniform_array_move_out::RestoreSubsliceArrayMoveOut,
+        box devirtualize::Devirtualize,
+        box inline::Inline,
 
         // Lowering generator control-flow and variables
         // has to happen before we do anything else to them.
-        &generator::StateTransform,
-
-        &instcombine::InstCombine,
-        &const_prop::ConstProp,
-        &simplify_branches::SimplifyBranches::new("after-const-prop"),
-        &deaggregator::Deaggregator,
-        &copy_prop::CopyPropagation,
-        &remove_noop_landing_pads::RemoveNoopLandingPads,
-        &simplify::SimplifyCfg::new("final"),
-        &simplify::SimplifyLocals,
-
-        &add_call_guards::CriticalCallEdges,
-        &dump_mir::Marker("PreCodegen"),
-    ]);
+        box generator::StateTransform,
+
+        box instcombine::InstCombine,
+        box const_prop::ConstProp,
+        box simplify_branches::SimplifyBranches::new("after-const-prop"),
+        box jump_threading::JumpThreading,
+        box unreachable_propagation::UnreachablePropagation,
+        box deaggregator::Deaggregator,
+        box scalar_replacement::ScalarReplacementOfAggregates,
+        box gvn::GlobalValueNumbering,
+        box copy_prop::CopyPropagation,
+        box dead_store_elimination::DeadStoreElimination,
+        box nrvo::RenameReturnPlace,
+        box remove_noop_landing_pads::RemoveNoopLandingPads,
+        box simplify::SimplifyCfg::new("final"),
+        box simplify::SimplifyLocals,
+
+        box add_call_guards::CriticalCallEdges,
+        // Instrumentation goes last, so that each counter measures a block of
+        // the MIR that is actually codegened.
+        box instrument_coverage::InstrumentCoverage,
+        box dump_mir::Marker("PreCodegen"),
+    ]
+}
+
+fn mir_const<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Steal<Mir<'tcx>> {
+    // Unsafety check uses the raw mir, so make sure it is run
+    let _ = tcx.unsafety_check_result(def_id);
+
+    let mut mir = tcx.mir_built(def_id).steal();
+    run_passes(tcx, &mut mir, def_id, MirPhase::Const, &const_passes());
+    tcx.alloc_steal_mir(mir)
+}
+
+fn mir_validated<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Steal<Mir<'tcx>> {
+    // Ensure that we compute the `promotability` (and with it the
+    // `mir_const_qualif` of constants) at this point, before we steal
+    // the mir-const result.
+    let _ = tcx.promotability(def_id);
+
+    let mut mir = tcx.mir_const(def_id).steal();
+    run_passes(tcx, &mut mir, def_id, MirPhase::Validated, &validated_passes());
+    // Callers rely on the promise of `#[disjoint_fields]`, so it is checked
+    // here rather than in borrowck, which may not run or may only warn.
+    disjoint_fields::check_method_body(tcx, def_id, &mir);
+    tcx.alloc_steal_mir(mir)
+}
+
+fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Mir<'tcx> {
+    // (Mir-)Borrowck uses `mir_validated`, so we have to force it to
+    // execute before we can steal.
+    let _ = tcx.mir_borrowck(def_id);
+
+    if tcx.use_ast_borrowck() {
+        let _ = tcx.borrowck(def_id);
+    }
+
+    let mut mir = tcx.mir_validated(def_id).steal();
+    run_passes(tcx, &mut mir, def_id, MirPhase::Optimized, &optimized_passes());
     tcx.alloc_mir(mir)
 }
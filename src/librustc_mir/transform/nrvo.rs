@@ -0,0 +1,194 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Named return value optimization.
+//!
+//! Functions returning a large value usually build it in a local and only move it into the
+//! return place right before returning:
+//!
+//!     _2 = [const 0u8; const 1024usize];
+//!     ...
+//!     _0 = move _2;
+//!     return;
+//!
+//! When every `return` is preceded by such a copy of the same local, and the return place is
+//! not used anywhere else, this pass renames that local to the return place and removes the
+//! copies, so the value is built in place instead of being copied at the end.
+//!
+//! Like copy propagation, this makes the renamed local disappear from the debug info, so it only
+//! runs when asked to.
+
+use rustc::mir::*;
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::BitSet;
+use transform::{MirPass, MirSource};
+
+pub struct RenameReturnPlace;
+
+impl MirPass for RenameReturnPlace {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          _tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let (returned_local, copies) = match local_eligible_for_nrvo(mir) {
+            Some(eligible) => eligible,
+            None => {
+                debug!("run_pass: {:?} is not eligible for NRVO", source);
+                return;
+            }
+        };
+        debug!("run_pass: renaming {:?} to the return place in {:?}", returned_local, source);
+
+        for location in copies {
+            mir.make_statement_nop(location);
+        }
+
+        RenameToReturnPlace { to_rename: returned_local }.visit_mir(mir);
+    }
+}
+
+/// Returns the local which is copied to the return place right before every `return`, and the
+/// locations of those copies, if that local can be replaced by the return place.
+fn local_eligible_for_nrvo(mir: &Mir) -> Option<(Local, Vec<Location>)> {
+    let mut returned_local = None;
+    let mut copies = vec![];
+
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        if let TerminatorKind::Return = data.terminator().kind {
+            let (local, location) = find_local_assigned_to_return_place(block, mir)?;
+            match mir.local_kind(local) {
+                LocalKind::Var | LocalKind::Temp => {}
+                LocalKind::Arg | LocalKind::ReturnPointer => return None,
+            }
+
+            if returned_local.map_or(false, |returned_local| returned_local != local) {
+                return None;
+            }
+            returned_local = Some(local);
+
+            if !copies.contains(&location) {
+                copies.push(location);
+            }
+        }
+    }
+
+    let returned_local = returned_local?;
+    if mir.local_decls[returned_local].ty != mir.local_decls[RETURN_PLACE].ty {
+        return None;
+    }
+
+    // Any other mention of the return place would now refer to the renamed
+    // local, which may hold a different value at that point.
+    let mut mentions = ReturnPlaceMentions(0);
+    mentions.visit_mir(mir);
+    if mentions.0 != copies.len() {
+        return None;
+    }
+
+    Some((returned_local, copies))
+}
+
+/// Walks back from the `return` in `start` to the assignment of a local to the return place,
+/// through blocks with a single predecessor. Only storage statements may come between the
+/// assignment and the `return`, so that the local cannot be changed after it is copied.
+fn find_local_assigned_to_return_place(start: BasicBlock,
+                                       mir: &Mir) -> Option<(Local, Location)> {
+    let mut seen = BitSet::new_empty(mir.basic_blocks().len());
+    let mut block = start;
+
+    loop {
+        if !seen.insert(block) {
+            return None;
+        }
+
+        let data = &mir[block];
+        if block != start {
+            match data.terminator().kind {
+                TerminatorKind::Goto { .. } => {}
+                _ => return None,
+            }
+        }
+
+        for (statement_index, statement) in data.statements.iter().enumerate().rev() {
+            match statement.kind {
+                StatementKind::Assign(Place::Local(dest), box Rvalue::Use(ref operand))
+                    if dest == RETURN_PLACE =>
+                {
+                    return match *operand {
+                        Operand::Copy(Place::Local(local)) |
+                        Operand::Move(Place::Local(local)) => {
+                            Some((local, Location { block, statement_index }))
+                        }
+                        _ => None,
+                    };
+                }
+                StatementKind::StorageLive(_) |
+                StatementKind::StorageDead(_) |
+                StatementKind::Nop => {}
+                _ => return None,
+            }
+        }
+
+        let predecessors = mir.predecessors_for(block);
+        if predecessors.len() != 1 {
+            return None;
+        }
+        block = predecessors[0];
+    }
+}
+
+struct ReturnPlaceMentions(usize);
+
+impl<'tcx> Visitor<'tcx> for ReturnPlaceMentions {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext<'tcx>, _: Location) {
+        if local == RETURN_PLACE {
+            self.0 += 1;
+        }
+    }
+}
+
+struct RenameToReturnPlace {
+    to_rename: Local,
+}
+
+impl<'tcx> MutVisitor<'tcx> for RenameToReturnPlace {
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &mut Statement<'tcx>,
+                       location: Location) {
+        // The return place has no storage statements.
+        match statement.kind {
+            StatementKind::StorageLive(local) |
+            StatementKind::StorageDead(local) if local == self.to_rename => {
+                statement.make_nop();
+                return;
+            }
+            _ => {}
+        }
+
+        self.super_statement(block, statement, location);
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext<'tcx>, _: Location) {
+        if *local == self.to_rename {
+            *local = RETURN_PLACE;
+        }
+    }
+}
@@ -62,22 +62,6 @@ impl TempState {
     }
 }
 
-/// A "root candidate" for promotion, which will become the
-/// returned value in a promoted MIR, unless it's a subset
-/// of a larger candidate.
-#[derive(Debug)]
-pub enum Candidate {
-    /// Borrow of a constant temporary.
-    Ref(Location),
-
-    /// Currently applied to function calls where the callee has the unstable
-    /// `#[rustc_args_required_const]` attribute as well as the SIMD shuffle
-    /// intrinsic. The intrinsic requires the arguments are indeed constant and
-    /// the attribute currently provides the semantic requirement that arguments
-    /// must be constant.
-    Argument { bb: BasicBlock, index: usize },
-}
-
 struct TempCollector<'tcx> {
     temps: IndexVec<Local, TempState>,
     span: Span,
@@ -293,7 +277,7 @@ impl<'a, 'tcx> Promoter<'a, 'tcx> {
         new_temp
     }
 
-    fn promote_candidate(mut self, candidate: Candidate) {
+    fn promote_candidate(mut self, candidate: PromotionCandidate) {
         let mut operand = {
             let promoted = &mut self.promoted;
             let promoted_id = Promoted::new(self.source.promoted.len());
@@ -305,7 +289,7 @@ impl<'a, 'tcx> Promoter<'a, 'tcx> {
             };
             let (blocks, local_decls) = self.source.basic_blocks_and_local_decls_mut();
             match candidate {
-                Candidate::Ref(loc) => {
+                PromotionCandidate::Ref(loc) => {
                     let ref mut statement = blocks[loc.block].statements[loc.statement_index];
                     match statement.kind {
                         StatementKind::Assign(_, box Rvalue::Ref(_, _, ref mut place)) => {
@@ -324,7 +308,7 @@ impl<'a, 'tcx> Promoter<'a, 'tcx> {
                         _ => bug!()
                     }
                 }
-                Candidate::Argument { bb, index } => {
+                PromotionCandidate::Argument { bb, index } => {
                     let terminator = blocks[bb].terminator_mut();
                     match terminator.kind {
                         TerminatorKind::Call { ref mut args, .. } => {
@@ -374,13 +358,13 @@ impl<'a, 'tcx> MutVisitor<'tcx> for Promoter<'a, 'tcx> {
 pub fn promote_candidates<'a, 'tcx>(mir: &mut Mir<'tcx>,
                                     tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                     mut temps: IndexVec<Local, TempState>,
-                                    candidates: Vec<Candidate>) {
+                                    candidates: Vec<PromotionCandidate>) {
     // Visit candidates in reverse, in case they're nested.
     debug!("promote_candidates({:?})", candidates);
 
     for candidate in candidates.into_iter().rev() {
         match candidate {
-            Candidate::Ref(Location { block, statement_index }) => {
+            PromotionCandidate::Ref(Location { block, statement_index }) => {
                 match mir[block].statements[statement_index].kind {
                     StatementKind::Assign(Place::Local(local), _) => {
                         if temps[local] == TempState::PromotedOut {
@@ -391,7 +375,7 @@ pub fn promote_candidates<'a, 'tcx>(mir: &mut Mir<'tcx>,
                     _ => {}
                 }
             }
-            Candidate::Argument { .. } => {}
+            PromotionCandidate::Argument { .. } => {}
         }
 
 
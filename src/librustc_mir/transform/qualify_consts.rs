@@ -26,6 +26,7 @@ use rustc::traits::{self, TraitEngine};
 use rustc::ty::{self, TyCtxt, Ty, TypeFoldable};
 use rustc::ty::cast::CastTy;
 use rustc::ty::query::Providers;
+use rustc::ty::subst::Substs;
 use rustc::mir::*;
 use rustc::mir::traversal::ReversePostorder;
 use rustc::mir::visit::{PlaceContext, Visitor, MutatingUseContext, NonMutatingUseContext};
@@ -39,7 +40,7 @@ use std::fmt;
 use std::usize;
 
 use transform::{MirPass, MirSource};
-use super::promote_consts::{self, Candidate, TempState};
+use super::promote_consts::{self, TempState};
 
 bitflags! {
     // Borrows of temporaries can be promoted only if
@@ -82,6 +83,19 @@ impl<'a, 'tcx> Qualif {
             *self = *self - Qualif::NEEDS_DROP;
         }
     }
+
+    /// Why a temporary with these (non-empty) qualifications can't be promoted.
+    fn unpromotable(self) -> Unpromotable {
+        if self.contains(Qualif::FN_ARGUMENT) {
+            Unpromotable::FunctionArgument
+        } else if self.contains(Qualif::NEEDS_DROP) {
+            Unpromotable::NeedsDrop
+        } else if self.contains(Qualif::NOT_PROMOTABLE) {
+            Unpromotable::ComplexTemporary
+        } else {
+            Unpromotable::NotConst
+        }
+    }
 }
 
 /// What kind of item we are in.
@@ -94,6 +108,24 @@ enum Mode {
     Fn
 }
 
+impl Mode {
+    fn of(tcx: TyCtxt, def_id: DefId) -> Mode {
+        let id = tcx.hir().as_local_node_id(def_id).unwrap();
+        match tcx.hir().body_owner_kind(id) {
+            hir::BodyOwnerKind::Fn => {
+                if tcx.is_const_fn(def_id) {
+                    Mode::ConstFn
+                } else {
+                    Mode::Fn
+                }
+            }
+            hir::BodyOwnerKind::Const => Mode::Const,
+            hir::BodyOwnerKind::Static(hir::MutImmutable) => Mode::Static,
+            hir::BodyOwnerKind::Static(hir::MutMutable) => Mode::StaticMut,
+        }
+    }
+}
+
 impl fmt::Display for Mode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -117,7 +149,8 @@ struct Qualifier<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
     qualif: Qualif,
     const_fn_arg_vars: BitSet<Local>,
     temp_promotion_state: IndexVec<Local, TempState>,
-    promotion_candidates: Vec<Candidate>
+    promotion_candidates: Vec<PromotionCandidate>,
+    unpromotable: Vec<(Location, Span, Unpromotable)>,
 }
 
 impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
@@ -152,7 +185,8 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
             qualif: Qualif::empty(),
             const_fn_arg_vars: BitSet::new_empty(mir.local_decls.len()),
             temp_promotion_state: temps,
-            promotion_candidates: vec![]
+            promotion_candidates: vec![],
+            unpromotable: vec![],
         }
     }
 
@@ -244,7 +278,9 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
             return;
         }
 
-        if self.tcx.features().const_let {
+        // Branches can assign to the same local in several blocks, so `const_loop` also
+        // requires merging qualifications.
+        if self.tcx.features().const_let || self.tcx.features().const_loop {
             let mut dest = dest;
             let index = loop {
                 match dest {
@@ -304,8 +340,8 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                && self.local_qualif[index].map_or(false, |qualif| {
                     qualif.contains(Qualif::NOT_CONST)
                }) => {
-                // Part of `box expr`, we should've errored
-                // already for the Box allocation Rvalue.
+                // Part of `box expr`, we should've errored already for the Box
+                // allocation Rvalue unless `const_heap` is active.
             }
 
             // This must be an explicit assignment.
@@ -321,10 +357,9 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
         }
     }
 
-    /// Qualify a whole const, static initializer or const fn.
-    fn qualify_const(&mut self) -> (Qualif, Lrc<BitSet<Local>>) {
-        debug!("qualifying {} {:?}", self.mode, self.def_id);
-
+    /// Qualify the blocks of a const without any branches or loops, by following the
+    /// only path through its CFG. Anything else is not const.
+    fn qualify_linear_path(&mut self) {
         let mir = self.mir;
 
         let mut seen_blocks = BitSet::new_empty(mir.basic_blocks().len());
@@ -412,6 +447,44 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 }
             }
         }
+    }
+
+    /// Qualify every block reachable from the start of a const, which allows
+    /// branches and loops. Blocks are visited in reverse postorder, so every
+    /// local is assigned before it is read, except for values flowing back
+    /// along a loop's back edge.
+    fn qualify_all_blocks(&mut self) {
+        while let Some((bb, data)) = self.rpo.next() {
+            // Unwinding aborts const evaluation, so cleanup code never runs.
+            if data.is_cleanup {
+                continue;
+            }
+            self.visit_basic_block_data(bb, data);
+        }
+
+        // A variable's final qualification may only have been assigned after it
+        // was read in an earlier iteration. Stay conservative by assuming any of
+        // them can end up in the return place.
+        let mut qualif = self.local_qualif[RETURN_PLACE].unwrap_or(Qualif::NOT_CONST);
+        for index in self.mir.vars_iter() {
+            if let Some(var_qualif) = self.local_qualif[index] {
+                qualif = qualif | var_qualif;
+            }
+        }
+        self.local_qualif[RETURN_PLACE] = Some(qualif);
+    }
+
+    /// Qualify a whole const, static initializer or const fn.
+    fn qualify_const(&mut self) -> (Qualif, Lrc<BitSet<Local>>) {
+        debug!("qualifying {} {:?}", self.mode, self.def_id);
+
+        let mir = self.mir;
+
+        if self.tcx.features().const_loop {
+            self.qualify_all_blocks();
+        } else {
+            self.qualify_linear_path();
+        }
 
         self.qualif = self.local_qualif[RETURN_PLACE].unwrap_or(Qualif::NOT_CONST);
 
@@ -430,7 +503,7 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
         debug!("qualify_const: promotion_candidates={:?}", self.promotion_candidates);
         for candidate in &self.promotion_candidates {
             match *candidate {
-                Candidate::Ref(Location { block: bb, statement_index: stmt_idx }) => {
+                PromotionCandidate::Ref(Location { block: bb, statement_index: stmt_idx }) => {
                     match self.mir[bb].statements[stmt_idx].kind {
                         StatementKind::Assign(_, box Rvalue::Ref(_, _, Place::Local(index))) => {
                             promoted_temps.insert(index);
@@ -438,16 +511,26 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                         _ => {}
                     }
                 }
-                Candidate::Argument { .. } => {}
+                PromotionCandidate::Argument { .. } => {}
             }
         }
 
         (self.qualif, Lrc::new(promoted_temps))
     }
 
+    /// Whether this is a call of a trait method on a `dyn Trait` receiver.
+    fn is_dyn_method_call(&self, def_id: DefId, substs: &Substs<'tcx>) -> bool {
+        self.tcx.trait_of_item(def_id).is_some() && match substs.type_at(0).sty {
+            ty::Dynamic(..) => true,
+            _ => false,
+        }
+    }
+
     fn is_const_panic_fn(&self, def_id: DefId) -> bool {
         Some(def_id) == self.tcx.lang_items().panic_fn() ||
-        Some(def_id) == self.tcx.lang_items().begin_panic_fn()
+        Some(def_id) == self.tcx.lang_items().panic_fmt_fn() ||
+        Some(def_id) == self.tcx.lang_items().begin_panic_fn() ||
+        Some(def_id) == self.tcx.lang_items().begin_panic_fmt_fn()
     }
 }
 
@@ -724,7 +807,18 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
                     if forbidden_mut {
                         self.add(Qualif::NOT_CONST);
-                        if self.mode != Mode::Fn {
+                        if self.mode == Mode::ConstFn {
+                            // Such a reference cannot end up in the final value of a constant:
+                            // that would require creating a `&mut` in the constant itself,
+                            // which is rejected below.
+                            if !self.tcx.features().const_mut_refs {
+                                emit_feature_err(
+                                    &self.tcx.sess.parse_sess, "const_mut_refs",
+                                    self.span, GateIssue::Language,
+                                    &format!("mutable references in {}s are unstable", self.mode),
+                                );
+                            }
+                        } else if self.mode != Mode::Fn {
                             let mut err = struct_span_err!(self.tcx.sess,  self.span, E0017,
                                                            "references in {}s may only refer \
                                                             to immutable values", self.mode);
@@ -752,7 +846,9 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                         // NOT_CONST (see `if forbidden_mut` below), to avoid
                         // duplicate errors (from reborrowing, for example).
                         self.qualif = self.qualif - Qualif::MUTABLE_INTERIOR;
-                        if self.mode != Mode::Fn {
+                        // With `const_mut_refs`, such constants are instead interned as
+                        // mutable memory, just like the memory of a `static`.
+                        if self.mode != Mode::Fn && !self.tcx.features().const_mut_refs {
                             span_err!(self.tcx.sess, self.span, E0492,
                                       "cannot borrow a constant which may contain \
                                        interior mutability, create a static instead");
@@ -763,38 +859,57 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                     }
                 }
 
+                // We can only promote interior borrows of promotable temps.
+                let mut base = place;
+                while let Place::Projection(ref proj) = *base {
+                    if proj.elem == ProjectionElem::Deref {
+                        break;
+                    }
+                    base = &proj.base;
+                }
+                debug!("visit_rvalue: base={:?}", base);
+                let temp = match *base {
+                    Place::Local(local) if self.mir.local_kind(local) == LocalKind::Temp => {
+                        Some(local)
+                    }
+                    _ => None,
+                };
+
                 debug!("visit_rvalue: forbidden_mut={:?}", forbidden_mut);
-                if forbidden_mut {
+                let unpromotable = if forbidden_mut {
                     self.add(Qualif::NOT_CONST);
-                } else {
-                    // We might have a candidate for promotion.
-                    let candidate = Candidate::Ref(location);
-                    // We can only promote interior borrows of promotable temps.
-                    let mut place = place;
-                    while let Place::Projection(ref proj) = *place {
-                        if proj.elem == ProjectionElem::Deref {
-                            break;
-                        }
-                        place = &proj.base;
+                    if let BorrowKind::Mut { .. } = kind {
+                        Some(Unpromotable::MutableBorrow)
+                    } else {
+                        Some(Unpromotable::InteriorMutability)
                     }
-                    debug!("visit_rvalue: place={:?}", place);
-                    if let Place::Local(local) = *place {
-                        if self.mir.local_kind(local) == LocalKind::Temp {
-                            debug!("visit_rvalue: local={:?}", local);
-                            if let Some(qualif) = self.local_qualif[local] {
-                                // `forbidden_mut` is false, so we can safely ignore
-                                // `MUTABLE_INTERIOR` from the local's qualifications.
-                                // This allows borrowing fields which don't have
-                                // `MUTABLE_INTERIOR`, from a type that does, e.g.:
-                                // `let _: &'static _ = &(Cell::new(1), 2).1;`
-                                debug!("visit_rvalue: qualif={:?}", qualif);
-                                if (qualif - Qualif::MUTABLE_INTERIOR).is_empty() {
-                                    debug!("visit_rvalue: candidate={:?}", candidate);
-                                    self.promotion_candidates.push(candidate);
-                                }
-                            }
+                } else if let Some(local) = temp {
+                    // We might have a candidate for promotion.
+                    let candidate = PromotionCandidate::Ref(location);
+                    debug!("visit_rvalue: local={:?}", local);
+                    if let Some(qualif) = self.local_qualif[local] {
+                        // `forbidden_mut` is false, so we can safely ignore
+                        // `MUTABLE_INTERIOR` from the local's qualifications.
+                        // This allows borrowing fields which don't have
+                        // `MUTABLE_INTERIOR`, from a type that does, e.g.:
+                        // `let _: &'static _ = &(Cell::new(1), 2).1;`
+                        debug!("visit_rvalue: qualif={:?}", qualif);
+                        let qualif = qualif - Qualif::MUTABLE_INTERIOR;
+                        if qualif.is_empty() {
+                            debug!("visit_rvalue: candidate={:?}", candidate);
+                            self.promotion_candidates.push(candidate);
+                            None
+                        } else {
+                            Some(qualif.unpromotable())
                         }
+                    } else {
+                        Some(Unpromotable::ComplexTemporary)
                     }
+                } else {
+                    None
+                };
+                if let (Some(_), Some(reason)) = (temp, unpromotable) {
+                    self.unpromotable.push((location, self.span, reason));
                 }
             }
 
@@ -851,7 +966,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
             Rvalue::NullaryOp(NullOp::Box, _) => {
                 self.add(Qualif::NOT_CONST);
-                if self.mode != Mode::Fn {
+                if self.mode != Mode::Fn && !self.tcx.features().const_heap {
                     let mut err = struct_span_err!(self.tcx.sess, self.span, E0010,
                                                    "allocations are not allowed in {}s", self.mode);
                     err.span_label(self.span, format!("allocation not allowed in {}s", self.mode));
@@ -897,7 +1012,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
             let mut is_const_fn = false;
             let mut is_promotable_const_fn = false;
             match fn_ty.sty {
-                ty::FnDef(def_id, _) => {
+                ty::FnDef(def_id, substs) => {
                     callee_def_id = Some(def_id);
                     match self.tcx.fn_sig(def_id).abi() {
                         Abi::RustIntrinsic |
@@ -945,8 +1060,44 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                                     }
                                 }
 
+                                // pointer offsets depend on allocation bounds, which promotion
+                                // must not observe, so only const contexts may call these
+                                "offset" | "arith_offset" => {
+                                    if self.mode != Mode::Fn {
+                                        is_const_fn = true;
+                                    }
+                                }
+
+                                // SIMD intrinsics are emulated by const eval, but calls to them
+                                // are never promoted
+                                | "simd_add"
+                                | "simd_sub"
+                                | "simd_mul"
+                                | "simd_div"
+                                | "simd_rem"
+                                | "simd_shl"
+                                | "simd_shr"
+                                | "simd_and"
+                                | "simd_or"
+                                | "simd_xor"
+                                | "simd_eq"
+                                | "simd_ne"
+                                | "simd_lt"
+                                | "simd_le"
+                                | "simd_gt"
+                                | "simd_ge"
+                                | "simd_extract"
+                                | "simd_insert" => {
+                                    if self.mode != Mode::Fn {
+                                        is_const_fn = true;
+                                    }
+                                }
+
                                 name if name.starts_with("simd_shuffle") => {
                                     is_shuffle = true;
+                                    if self.mode != Mode::Fn {
+                                        is_const_fn = true;
+                                    }
                                 }
 
                                 _ => {}
@@ -985,6 +1136,21 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                                             &format!("panicking in {}s is unstable", self.mode),
                                         );
                                     }
+                                } else if self.is_dyn_method_call(def_id, substs) {
+                                    // Which method actually gets called is only known at
+                                    // evaluation time, where it's looked up in the vtable.
+                                    if self.tcx.features().const_dyn_dispatch {
+                                        is_const_fn = true;
+                                    } else {
+                                        emit_feature_err(
+                                            &self.tcx.sess.parse_sess,
+                                            "const_dyn_dispatch",
+                                            self.span,
+                                            GateIssue::Language,
+                                            &format!("dynamic dispatch in {}s is unstable",
+                                                     self.mode),
+                                        );
+                                    }
                                 } else if let Some(feature)
                                               = self.tcx.is_unstable_const_fn(def_id) {
                                     // Check `#[unstable]` const fns or `#[rustc_const_unstable]`
@@ -1062,7 +1228,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                     if this.mode != Mode::Fn {
                         return
                     }
-                    let candidate = Candidate::Argument { bb, index: i };
+                    let candidate = PromotionCandidate::Argument { bb, index: i };
                     if is_shuffle && i == 2 {
                         if this.qualif.is_empty() {
                             debug!("visit_terminator_kind: candidate={:?}", candidate);
@@ -1239,6 +1405,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 StatementKind::Retag { .. } |
                 StatementKind::EscapeToRaw { .. } |
                 StatementKind::AscribeUserType(..) |
+                StatementKind::CoverageCounter { .. } |
                 StatementKind::Nop => {}
             }
         });
@@ -1256,6 +1423,8 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 pub fn provide(providers: &mut Providers) {
     *providers = Providers {
         mir_const_qualif,
+        promotability,
+        is_promotable,
         ..*providers
     };
 }
@@ -1263,6 +1432,15 @@ pub fn provide(providers: &mut Providers) {
 fn mir_const_qualif<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                               def_id: DefId)
                               -> (u8, Lrc<BitSet<Local>>) {
+    match tcx.promotability(def_id).const_qualif {
+        Some(ref const_qualif) => const_qualif.clone(),
+        None => (Qualif::NOT_CONST.bits(), Lrc::new(BitSet::new_empty(0))),
+    }
+}
+
+fn promotability<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           def_id: DefId)
+                           -> Lrc<Promotability> {
     // N.B., this `borrow()` is guaranteed to be valid (i.e., the value
     // cannot yet be stolen), because `mir_validated()`, which steals
     // from `mir_const(), forces this query to execute before
@@ -1270,13 +1448,60 @@ fn mir_const_qualif<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     let mir = &tcx.mir_const(def_id).borrow();
 
     if mir.return_ty().references_error() {
-        tcx.sess.delay_span_bug(mir.span, "mir_const_qualif: Mir had errors");
-        return (Qualif::NOT_CONST.bits(), Lrc::new(BitSet::new_empty(0)));
+        tcx.sess.delay_span_bug(mir.span, "promotability: Mir had errors");
+        return Lrc::new(Promotability::default());
     }
 
-    let mut qualifier = Qualifier::new(tcx, def_id, mir, Mode::Const);
-    let (qualif, promoted_temps) = qualifier.qualify_const();
-    (qualif.bits(), promoted_temps)
+    let mode = Mode::of(tcx, def_id);
+    debug!("promotability: mode={:?}", mode);
+
+    let mut qualifier = Qualifier::new(tcx, def_id, mir, mode);
+    let const_qualif = match mode {
+        Mode::Fn => {
+            while let Some((bb, data)) = qualifier.rpo.next() {
+                qualifier.visit_basic_block_data(bb, data);
+            }
+            None
+        }
+        Mode::ConstFn if tcx.is_min_const_fn(def_id) => {
+            // enforce `min_const_fn` for stable const fns
+            use super::qualify_min_const_fn::is_min_const_fn;
+            if let Err((span, err)) = is_min_const_fn(tcx, def_id, mir) {
+                tcx.sess.span_err(span, &err);
+                None
+            } else {
+                // this should not produce any errors, but better safe than sorry
+                // FIXME(#53819)
+                let (qualif, promoted_temps) = qualifier.qualify_const();
+                Some((qualif.bits(), promoted_temps))
+            }
+        }
+        // Enforce a constant-like CFG for `const fn`, and compute the
+        // qualifications of constants and statics.
+        Mode::ConstFn | Mode::Const | Mode::Static | Mode::StaticMut => {
+            let (qualif, promoted_temps) = qualifier.qualify_const();
+            Some((qualif.bits(), promoted_temps))
+        }
+    };
+
+    Lrc::new(Promotability {
+        candidates: qualifier.promotion_candidates,
+        unpromotable: qualifier.unpromotable,
+        const_qualif,
+    })
+}
+
+fn is_promotable<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           (def_id, location): (DefId, Location))
+                           -> Result<(), Unpromotable> {
+    let promotability = tcx.promotability(def_id);
+    if promotability.candidates.contains(&PromotionCandidate::Ref(location)) {
+        return Ok(());
+    }
+    match promotability.unpromotable.iter().find(|&&(loc, ..)| loc == location) {
+        Some(&(.., reason)) => Err(reason),
+        None => Err(Unpromotable::NotATemporaryBorrow),
+    }
 }
 
 pub struct QualifyAndPromoteConstants;
@@ -1298,61 +1523,23 @@ impl MirPass for QualifyAndPromoteConstants {
 
         let def_id = src.def_id;
         let id = tcx.hir().as_local_node_id(def_id).unwrap();
-        let mut const_promoted_temps = None;
-        let mode = match tcx.hir().body_owner_kind(id) {
-            hir::BodyOwnerKind::Fn => {
-                if tcx.is_const_fn(def_id) {
-                    Mode::ConstFn
-                } else {
-                    Mode::Fn
-                }
-            }
-            hir::BodyOwnerKind::Const => {
-                const_promoted_temps = Some(tcx.mir_const_qualif(def_id).1);
-                Mode::Const
-            }
-            hir::BodyOwnerKind::Static(hir::MutImmutable) => Mode::Static,
-            hir::BodyOwnerKind::Static(hir::MutMutable) => Mode::StaticMut,
-        };
+        let mode = Mode::of(tcx, def_id);
 
         debug!("run_pass: mode={:?}", mode);
+        // Already computed by the `promotability` query, which also reported
+        // the errors of constants, statics and `const fn`.
+        let promotability = tcx.promotability(def_id);
         if mode == Mode::Fn || mode == Mode::ConstFn {
-            // This is ugly because Qualifier holds onto mir,
-            // which can't be mutated until its scope ends.
-            let (temps, candidates) = {
-                let mut qualifier = Qualifier::new(tcx, def_id, mir, mode);
-                if mode == Mode::ConstFn {
-                    if tcx.is_min_const_fn(def_id) {
-                        // enforce `min_const_fn` for stable const fns
-                        use super::qualify_min_const_fn::is_min_const_fn;
-                        if let Err((span, err)) = is_min_const_fn(tcx, def_id, mir) {
-                            tcx.sess.span_err(span, &err);
-                        } else {
-                            // this should not produce any errors, but better safe than sorry
-                            // FIXME(#53819)
-                            qualifier.qualify_const();
-                        }
-                    } else {
-                        // Enforce a constant-like CFG for `const fn`.
-                        qualifier.qualify_const();
-                    }
-                } else {
-                    while let Some((bb, data)) = qualifier.rpo.next() {
-                        qualifier.visit_basic_block_data(bb, data);
-                    }
-                }
-
-                (qualifier.temp_promotion_state, qualifier.promotion_candidates)
-            };
+            let mut rpo = traversal::reverse_postorder(mir);
+            let temps = promote_consts::collect_temps(mir, &mut rpo);
 
             // Do the actual promotion, now that we know what's viable.
+            let candidates = promotability.candidates.clone();
             promote_consts::promote_candidates(mir, tcx, temps, candidates);
         } else {
-            let promoted_temps = if mode == Mode::Const {
-                // Already computed by `mir_const_qualif`.
-                const_promoted_temps.unwrap()
-            } else {
-                Qualifier::new(tcx, def_id, mir, mode).qualify_const().1
+            let promoted_temps = match promotability.const_qualif {
+                Some((_, ref promoted_temps)) => promoted_temps.clone(),
+                None => Lrc::new(BitSet::new_empty(0)),
             };
 
             // In `const` and `static` everything without `StorageDead`
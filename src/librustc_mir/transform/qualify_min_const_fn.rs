@@ -65,11 +65,13 @@ pub fn is_min_const_fn(
         }
     }
 
-    for local in mir.vars_iter() {
-        return Err((
-            mir.local_decls[local].source_info.span,
-            "local variables in const fn are unstable".into(),
-        ));
+    if !allow_let(tcx) {
+        for local in mir.vars_iter() {
+            return Err((
+                mir.local_decls[local].source_info.span,
+                "local variables in const fn are unstable".into(),
+            ));
+        }
     }
     for local in &mir.local_decls {
         check_ty(tcx, local.ty, local.source_info.span)?;
@@ -97,7 +99,7 @@ fn check_ty(
 ) -> McfResult {
     for ty in ty.walk() {
         match ty.sty {
-            ty::Ref(_, _, hir::Mutability::MutMutable) => return Err((
+            ty::Ref(_, _, hir::Mutability::MutMutable) if !allow_mut_refs(tcx) => return Err((
                 span,
                 "mutable references in const fn are unstable".into(),
             )),
@@ -230,6 +232,9 @@ fn check_statement(
             check_rvalue(tcx, mir, rval, span)
         }
 
+        // `let` bindings and `match`/`if` scrutinees produce fake reads
+        StatementKind::FakeRead(..)
+            if allow_let(tcx) || allow_loops(tcx) => Ok(()),
         StatementKind::FakeRead(..) => Err((span, "match in const fn is unstable".into())),
 
         // just an assignment
@@ -245,6 +250,7 @@ fn check_statement(
         | StatementKind::Retag { .. }
         | StatementKind::EscapeToRaw { .. }
         | StatementKind::AscribeUserType(..)
+        | StatementKind::CoverageCounter { .. }
         | StatementKind::Nop => Ok(()),
     }
 }
@@ -274,6 +280,7 @@ fn check_place(
         Place::Local(l) => match mode {
             PlaceMode::Assign => match mir.local_kind(*l) {
                 LocalKind::Temp | LocalKind::ReturnPointer => Ok(()),
+                LocalKind::Arg | LocalKind::Var if allow_let(tcx) => Ok(()),
                 LocalKind::Arg | LocalKind::Var => {
                     Err((span, "assignments in const fn are unstable".into()))
                 }
@@ -292,6 +299,9 @@ fn check_place(
                 | ProjectionElem::ConstantIndex { .. } | ProjectionElem::Subslice { .. } => {
                     return Err((span, "slice patterns in const fn are unstable".into()))
                 }
+                | ProjectionElem::Downcast(..) if allow_loops(tcx) => {
+                    check_place(tcx, mir, &proj.base, span, mode)
+                }
                 | ProjectionElem::Downcast(..) => {
                     Err((span, "`match` or `if let` in `const fn` is unstable".into()))
                 }
@@ -300,6 +310,20 @@ fn check_place(
     }
 }
 
+/// Unstable features only extend `min_const_fn` outside of the standard library. Inside of it,
+/// `min_const_fn` is what keeps stable const fns from depending on unstable features.
+fn allow_let(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> bool {
+    !tcx.features().staged_api && tcx.features().const_let
+}
+
+fn allow_loops(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> bool {
+    !tcx.features().staged_api && tcx.features().const_loop
+}
+
+fn allow_mut_refs(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> bool {
+    !tcx.features().staged_api && tcx.features().const_mut_refs
+}
+
 fn check_terminator(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     mir: &'a Mir<'tcx>,
@@ -319,6 +343,10 @@ fn check_terminator(
             check_operand(tcx, mir, value, span)
         },
 
+        TerminatorKind::FalseEdges { .. } if allow_loops(tcx) => Ok(()),
+        TerminatorKind::SwitchInt { discr, .. } if allow_loops(tcx) => {
+            check_operand(tcx, mir, discr, span)
+        }
         TerminatorKind::FalseEdges { .. } | TerminatorKind::SwitchInt { .. } => Err((
             span,
             "`if`, `match`, `&&` and `||` are not stable in const fn".into(),
@@ -386,6 +414,8 @@ fn check_terminator(
             cleanup: _,
         } => check_operand(tcx, mir, cond, span),
 
+        // the interpreter's step limit and loop detector take care of non-terminating loops
+        TerminatorKind::FalseUnwind { .. } if allow_loops(tcx) => Ok(()),
         TerminatorKind::FalseUnwind { .. } => {
             Err((span, "loops are not allowed in const fn".into()))
         },
@@ -12,11 +12,15 @@ use rustc::ty::TyCtxt;
 use rustc::mir::*;
 use rustc_data_structures::bit_set::BitSet;
 use transform::{MirPass, MirSource};
+use transform::simplify;
 use util::patch::MirPatch;
 
+use std::sync::atomic::Ordering;
+
 /// A pass that removes no-op landing pads and replaces jumps to them with
 /// `None`. This is important because otherwise LLVM generates terrible
-/// code for these.
+/// code for these. The cleanup blocks that only led to `resume` are
+/// dropped once nothing jumps to them anymore.
 pub struct RemoveNoopLandingPads;
 
 pub fn remove_noop_landing_pads<'a, 'tcx>(
@@ -28,7 +32,14 @@ pub fn remove_noop_landing_pads<'a, 'tcx>(
     }
     debug!("remove_noop_landing_pads({:?})", mir);
 
-    RemoveNoopLandingPads.remove_nop_landing_pads(mir)
+    let (landing_pads_removed, jumps_folded) = RemoveNoopLandingPads.remove_nop_landing_pads(mir);
+    if landing_pads_removed + jumps_folded > 0 {
+        simplify::remove_dead_blocks(mir);
+    }
+
+    let perf_stats = &tcx.sess.perf_stats;
+    perf_stats.nop_landing_pads_removed.fetch_add(landing_pads_removed, Ordering::Relaxed);
+    perf_stats.nop_landing_pad_jumps_folded.fetch_add(jumps_folded, Ordering::Relaxed);
 }
 
 impl MirPass for RemoveNoopLandingPads {
@@ -66,7 +77,8 @@ impl RemoveNoopLandingPads {
                 StatementKind::SetDiscriminant { .. } |
                 StatementKind::InlineAsm { .. } |
                 StatementKind::Retag { .. } |
-                StatementKind::EscapeToRaw { .. } => {
+                StatementKind::EscapeToRaw { .. } |
+                StatementKind::CoverageCounter { .. } => {
                     return false;
                 }
             }
@@ -97,7 +109,9 @@ impl RemoveNoopLandingPads {
         }
     }
 
-    fn remove_nop_landing_pads(&self, mir: &mut Mir) {
+    /// Removes the no-op landing pads of `mir`, and returns the number of
+    /// unwind edges removed and of other jumps redirected to the resume block.
+    fn remove_nop_landing_pads(&self, mir: &mut Mir) -> (usize, usize) {
         // make sure there's a single resume block
         let resume_block = {
             let patch = MirPatch::new(mir);
@@ -116,6 +130,15 @@ impl RemoveNoopLandingPads {
         let postorder: Vec<_> = traversal::postorder(mir).map(|(bb, _)| bb).collect();
         for bb in postorder {
             debug!("  processing {:?}", bb);
+            // An unwind edge that gets folded below is removed right after, so
+            // it only counts as a removed landing pad.
+            let unwind_folded = match mir[bb].terminator().unwind() {
+                Some(&Some(unwind)) => {
+                    unwind != resume_block && nop_landing_pads.contains(unwind)
+                }
+                _ => false,
+            };
+
             for target in mir[bb].terminator_mut().successors_mut() {
                 if *target != resume_block && nop_landing_pads.contains(*target) {
                     debug!("    folding noop jump to {:?} to resume block", target);
@@ -128,7 +151,9 @@ impl RemoveNoopLandingPads {
                 Some(unwind) => {
                     if *unwind == Some(resume_block) {
                         debug!("    removing noop landing pad");
-                        jumps_folded -= 1;
+                        if unwind_folded {
+                            jumps_folded -= 1;
+                        }
                         landing_pads_removed += 1;
                         *unwind = None;
                     }
@@ -144,5 +169,6 @@ impl RemoveNoopLandingPads {
         }
 
         debug!("removed {:?} jumps and {:?} landing pads", jumps_folded, landing_pads_removed);
+        (landing_pads_removed, jumps_folded)
     }
 }
@@ -22,6 +22,8 @@ use dataflow::MoveDataParamEnv;
 use dataflow::BitDenotation;
 use dataflow::DataflowResults;
 use dataflow::{DefinitelyInitializedPlaces, MaybeInitializedPlaces, MaybeUninitializedPlaces};
+use dataflow::MaybeLiveLocals;
+use dataflow::generic::{Engine, Results};
 use dataflow::move_paths::{MovePathIndex, LookupResult};
 use dataflow::move_paths::{HasMoveData, MoveData};
 use dataflow;
@@ -48,15 +50,15 @@ impl MirPass for SanityCheck {
         let mdpe = MoveDataParamEnv { move_data: move_data, param_env: param_env };
         let dead_unwinds = BitSet::new_empty(mir.basic_blocks().len());
         let flow_inits =
-            do_dataflow(tcx, mir, id, &attributes, &dead_unwinds,
+            do_dataflow(tcx, mir, src, &attributes, &dead_unwinds,
                         MaybeInitializedPlaces::new(tcx, mir, &mdpe),
                         |bd, i| DebugFormatted::new(&bd.move_data().move_paths[i]));
         let flow_uninits =
-            do_dataflow(tcx, mir, id, &attributes, &dead_unwinds,
+            do_dataflow(tcx, mir, src, &attributes, &dead_unwinds,
                         MaybeUninitializedPlaces::new(tcx, mir, &mdpe),
                         |bd, i| DebugFormatted::new(&bd.move_data().move_paths[i]));
         let flow_def_inits =
-            do_dataflow(tcx, mir, id, &attributes, &dead_unwinds,
+            do_dataflow(tcx, mir, src, &attributes, &dead_unwinds,
                         DefinitelyInitializedPlaces::new(tcx, mir, &mdpe),
                         |bd, i| DebugFormatted::new(&bd.move_data().move_paths[i]));
 
@@ -69,6 +71,11 @@ impl MirPass for SanityCheck {
         if has_rustc_mir_with(&attributes, "rustc_peek_definite_init").is_some() {
             sanity_check_via_rustc_peek(tcx, mir, id, &attributes, &flow_def_inits);
         }
+        if has_rustc_mir_with(&attributes, "rustc_peek_liveness").is_some() {
+            let flow_liveness =
+                Engine::new(tcx, mir, def_id, MaybeLiveLocals).iterate_to_fixpoint();
+            sanity_check_liveness_via_rustc_peek(tcx, mir, &flow_liveness);
+        }
         if has_rustc_mir_with(&attributes, "stop_after_dataflow").is_some() {
             tcx.sess.fatal("stop_after_dataflow ended compilation");
         }
@@ -164,6 +171,7 @@ fn each_block<'a, 'tcx, O>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             mir::StatementKind::Retag { .. } |
             mir::StatementKind::EscapeToRaw { .. } |
             mir::StatementKind::AscribeUserType(..) |
+            mir::StatementKind::CoverageCounter { .. } |
             mir::StatementKind::Nop => continue,
             mir::StatementKind::SetDiscriminant{ .. } =>
                 span_bug!(stmt.source_info.span,
@@ -222,6 +230,60 @@ fn each_block<'a, 'tcx, O>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                       form `&expr`"));
 }
 
+/// Like `sanity_check_via_rustc_peek`, but for the `MaybeLiveLocals` analysis of the generic
+/// dataflow engine. Here, calls have the form `rustc_peek(local)`, with `local` passed by
+/// value, and the bit checked is whether `local` is live right after being read for the call.
+fn sanity_check_liveness_via_rustc_peek<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                                  mir: &Mir<'tcx>,
+                                                  results: &Results<'tcx, MaybeLiveLocals>) {
+    for bb in mir.basic_blocks().indices() {
+        let mir::BasicBlockData { ref statements, ref terminator, is_cleanup: _ } = mir[bb];
+
+        let (args, span) = match is_rustc_peek(tcx, terminator) {
+            Some(args_and_span) => args_and_span,
+            None => continue,
+        };
+        assert!(args.len() == 1);
+        let peek_arg_place = match args[0] {
+            mir::Operand::Copy(ref place @ mir::Place::Local(_)) |
+            mir::Operand::Move(ref place @ mir::Place::Local(_)) => place,
+            _ => {
+                tcx.sess.diagnostic().span_err(
+                    span, "dataflow::sanity_check cannot feed a non-temp to rustc_peek.");
+                continue;
+            }
+        };
+
+        // Find the statement copying the peeked local into the argument of `rustc_peek`.
+        let peeked = statements.iter().enumerate().rev().filter_map(|(j, stmt)| {
+            match stmt.kind {
+                mir::StatementKind::Assign(ref place, ref rvalue) if place == peek_arg_place => {
+                    Some((j, &**rvalue))
+                }
+                _ => None,
+            }
+        }).next();
+
+        match peeked {
+            Some((j, &mir::Rvalue::Use(mir::Operand::Copy(mir::Place::Local(local))))) |
+            Some((j, &mir::Rvalue::Use(mir::Operand::Move(mir::Place::Local(local))))) => {
+                // Going backwards, the state "before" the copy is the one right after it.
+                let live = results.state_before(mir, Location { block: bb, statement_index: j });
+                debug!("rustc_peek({:?}) live: {:?}", local, live);
+                if !live.contains(local) {
+                    tcx.sess.span_err(span, "rustc_peek: bit not set");
+                }
+            }
+            _ => {
+                tcx.sess.span_err(span, "rustc_peek: MIR did not match \
+                                         anticipated pattern; note that \
+                                         rustc_peek_liveness expects input \
+                                         of form `local`");
+            }
+        }
+    }
+}
+
 fn is_rustc_peek<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                            terminator: &'a Option<mir::Terminator<'tcx>>)
                            -> Option<(&'a [mir::Operand<'tcx>], Span)> {
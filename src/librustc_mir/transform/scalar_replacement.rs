@@ -0,0 +1,215 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Scalar replacement of aggregates.
+//!
+//! This splits locals of struct or tuple type whose fields are only ever
+//! accessed individually into one local per field:
+//!
+//!     (_2.0: u32) = move _3;
+//!     (_2.1: u32) = const 0u32;
+//!     _0 = (_2.0: u32);
+//!
+//! becomes
+//!
+//!     _4 = move _3;
+//!     _5 = const 0u32;
+//!     _0 = _4;
+//!
+//! A local is left alone if it is used as a whole anywhere, if any part of it
+//! is borrowed (a pointer to a field could be used to reach its siblings), or
+//! if its type needs to be dropped. Arguments and the return place keep their
+//! layout since they are part of the function's ABI.
+
+use rustc::mir::*;
+use rustc::mir::visit::{MutVisitor, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::util::nodemap::FxHashMap;
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::indexed_vec::IndexVec;
+use std::collections::BTreeMap;
+use transform::{MirPass, MirSource};
+
+pub struct ScalarReplacementOfAggregates;
+
+impl MirPass for ScalarReplacementOfAggregates {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // Splitting user variables messes up debug info.
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mut finder = FieldAccessFinder {
+            fields: IndexVec::from_elem_n(BTreeMap::new(), mir.local_decls.len()),
+            escaping: BitSet::new_empty(mir.local_decls.len()),
+        };
+        finder.visit_mir(mir);
+
+        let param_env = tcx.param_env(source.def_id);
+        let mut replacements = FxHashMap::default();
+        let mut split_locals = FxHashMap::default();
+        for (local, fields) in finder.fields.into_iter_enumerated() {
+            if fields.is_empty() || finder.escaping.contains(local) {
+                continue;
+            }
+            match mir.local_kind(local) {
+                LocalKind::Var | LocalKind::Temp => {}
+                LocalKind::Arg | LocalKind::ReturnPointer => continue,
+            }
+            let decl_ty = mir.local_decls[local].ty;
+            let splittable = match decl_ty.sty {
+                ty::Adt(adt_def, _) => adt_def.is_struct(),
+                ty::Tuple(_) => true,
+                _ => false,
+            };
+            if !splittable || decl_ty.needs_drop(tcx, param_env) {
+                continue;
+            }
+
+            debug!("ScalarReplacementOfAggregates: splitting {:?} into {:?}", local, fields);
+            let span = mir.local_decls[local].source_info.span;
+            let mut new_locals = vec![];
+            for (field, ty) in fields {
+                let new_local = mir.local_decls.push(LocalDecl::new_temp(ty, span));
+                replacements.insert((local, field), new_local);
+                new_locals.push(new_local);
+            }
+            split_locals.insert(local, new_locals);
+        }
+
+        if split_locals.is_empty() {
+            return;
+        }
+
+        FieldReplacer { replacements }.visit_mir(mir);
+
+        // The new locals take over the storage markers of the local they replace.
+        for data in mir.basic_blocks_mut() {
+            let statements = data.statements.drain(..).collect::<Vec<_>>();
+            for statement in statements {
+                let (local, storage_live) = match statement.kind {
+                    StatementKind::StorageLive(local) => (local, true),
+                    StatementKind::StorageDead(local) => (local, false),
+                    _ => {
+                        data.statements.push(statement);
+                        continue;
+                    }
+                };
+                match split_locals.get(&local) {
+                    Some(new_locals) => {
+                        for &new_local in new_locals {
+                            data.statements.push(Statement {
+                                source_info: statement.source_info,
+                                kind: if storage_live {
+                                    StatementKind::StorageLive(new_local)
+                                } else {
+                                    StatementKind::StorageDead(new_local)
+                                },
+                            });
+                        }
+                    }
+                    None => data.statements.push(statement),
+                }
+            }
+        }
+    }
+}
+
+/// If `place` is a field of a local, possibly with further projections, returns
+/// that local and field.
+fn local_field<'tcx>(place: &Place<'tcx>) -> Option<(Local, Field, Ty<'tcx>)> {
+    match *place {
+        Place::Projection(ref proj) => match (&proj.base, &proj.elem) {
+            (&Place::Local(local), &ProjectionElem::Field(field, ty)) => Some((local, field, ty)),
+            (base, _) => local_field(base),
+        },
+        _ => None,
+    }
+}
+
+struct FieldAccessFinder<'tcx> {
+    /// The fields of each local that are accessed, with their types.
+    fields: IndexVec<Local, BTreeMap<Field, Ty<'tcx>>>,
+    /// Locals that are used as a whole or borrowed.
+    escaping: BitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for FieldAccessFinder<'tcx> {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        match local_field(place) {
+            Some((local, field, ty)) => {
+                self.fields[local].insert(field, ty);
+                if context.is_borrow() {
+                    self.escaping.insert(local);
+                }
+                // Still look at the locals used for indexing.
+                let mut place = place;
+                while let Place::Projection(ref proj) = *place {
+                    if let ProjectionElem::Index(index) = proj.elem {
+                        self.visit_local(&index,
+                                         PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy),
+                                         location);
+                    }
+                    place = &proj.base;
+                }
+            }
+            None => self.super_place(place, context, location),
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        if !context.is_storage_marker() {
+            self.escaping.insert(local);
+        }
+    }
+}
+
+struct FieldReplacer {
+    replacements: FxHashMap<(Local, Field), Local>,
+}
+
+impl FieldReplacer {
+    fn replace<'tcx>(&self, place: &Place<'tcx>) -> Option<Place<'tcx>> {
+        match *place {
+            Place::Projection(ref proj) => match (&proj.base, &proj.elem) {
+                (&Place::Local(local), &ProjectionElem::Field(field, _)) => {
+                    self.replacements.get(&(local, field)).map(|&new_local| {
+                        Place::Local(new_local)
+                    })
+                }
+                (base, elem) => self.replace(base).map(|base| base.elem(elem.clone())),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<'tcx> MutVisitor<'tcx> for FieldReplacer {
+    fn visit_place(&mut self,
+                   place: &mut Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if let Some(new_place) = self.replace(place) {
+            *place = new_place;
+        }
+        self.super_place(place, context, location);
+    }
+}
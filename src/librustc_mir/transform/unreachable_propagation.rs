@@ -0,0 +1,187 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A pass that removes code which can only be reached by producing a value of an uninhabited
+//! type.
+//!
+//! A block is unreachable if it reads a value of an uninhabited type, and the return edge of a
+//! call returning an uninhabited type is never taken. From there, unreachability is propagated
+//! backwards: a block which does nothing but jump to unreachable blocks is unreachable as well,
+//! and `SwitchInt` arms leading to unreachable blocks are removed. `SimplifyCfg` then gets rid of
+//! the blocks that are no longer used, so they never reach codegen.
+
+use rustc::mir::*;
+use rustc::session::Session;
+use rustc::ty::{Ty, TyCtxt};
+use rustc_data_structures::bit_set::BitSet;
+use std::borrow::Cow;
+use transform::{MirPass, MirSource};
+
+pub struct UnreachablePropagation;
+
+impl MirPass for UnreachablePropagation {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
+    fn is_optimization(&self) -> bool {
+        true
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        mark_uninhabited_unreachable(tcx, mir);
+        propagate_unreachable(mir);
+    }
+}
+
+fn mark_uninhabited_unreachable<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir: &mut Mir<'tcx>) {
+    for block in mir.basic_blocks().indices() {
+        let first_uninhabited_read = mir[block].statements.iter().position(|statement| {
+            match statement.kind {
+                StatementKind::Assign(_, ref rvalue) => {
+                    reads_uninhabited_value(tcx, mir, rvalue)
+                }
+                _ => false,
+            }
+        });
+        if let Some(statement_index) = first_uninhabited_read {
+            debug!("mark_uninhabited_unreachable: {:?} reads an uninhabited value", block);
+            let data = &mut mir[block];
+            data.statements.truncate(statement_index);
+            data.terminator_mut().kind = TerminatorKind::Unreachable;
+            continue;
+        }
+
+        let returns_uninhabited = match mir[block].terminator().kind {
+            TerminatorKind::Call { destination: Some((ref dest, _)), .. } => {
+                tcx.is_ty_uninhabited_from_all_modules(dest.ty(mir, tcx).to_ty(tcx))
+            }
+            _ => false,
+        };
+        if returns_uninhabited {
+            debug!("mark_uninhabited_unreachable: the call in {:?} never returns", block);
+            let terminator = mir[block].terminator_mut();
+            if let TerminatorKind::Call { ref mut destination, .. } = terminator.kind {
+                *destination = None;
+            }
+        }
+    }
+}
+
+fn reads_uninhabited_value<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                     mir: &Mir<'tcx>,
+                                     rvalue: &Rvalue<'tcx>) -> bool {
+    let is_uninhabited = |operand: &Operand<'tcx>| {
+        match *operand {
+            Operand::Copy(_) | Operand::Move(_) => {
+                tcx.is_ty_uninhabited_from_all_modules(operand.ty(mir, tcx))
+            }
+            Operand::Constant(_) => false,
+        }
+    };
+
+    match *rvalue {
+        Rvalue::Use(ref operand) |
+        Rvalue::Cast(_, ref operand, _) => is_uninhabited(operand),
+        Rvalue::Aggregate(_, ref operands) => operands.iter().any(is_uninhabited),
+        _ => false,
+    }
+}
+
+fn propagate_unreachable(mir: &mut Mir) {
+    let mut unreachable = BitSet::new_empty(mir.basic_blocks().len());
+    let postorder: Vec<_> = traversal::postorder(mir).map(|(block, _)| block).collect();
+
+    // Successors are visited before their predecessors, except around loops,
+    // for which we need to go around again.
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for &block in &postorder {
+            if unreachable.contains(block) {
+                continue;
+            }
+
+            let data = &mut mir.basic_blocks_mut()[block];
+            let only_storage = data.statements.iter().all(|statement| {
+                match statement.kind {
+                    StatementKind::StorageLive(_) |
+                    StatementKind::StorageDead(_) |
+                    StatementKind::Nop => true,
+                    _ => false,
+                }
+            });
+
+            let terminator = data.terminator_mut();
+            let new_kind = match terminator.kind {
+                TerminatorKind::Unreachable => None,
+                TerminatorKind::Goto { target } if unreachable.contains(target) => {
+                    Some(TerminatorKind::Unreachable)
+                }
+                TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
+                    simplify_switch(&unreachable, discr, switch_ty, values, targets)
+                }
+                _ => continue,
+            };
+            if let Some(new_kind) = new_kind {
+                terminator.kind = new_kind;
+                changed = true;
+            }
+
+            if let TerminatorKind::Unreachable = terminator.kind {
+                if only_storage {
+                    debug!("propagate_unreachable: {:?} is unreachable", block);
+                    unreachable.insert(block);
+                    changed = true;
+                }
+            }
+        }
+    }
+}
+
+/// Removes the arms of a `SwitchInt` which lead to unreachable blocks, and
+/// returns the new terminator if anything changed.
+fn simplify_switch<'tcx>(unreachable: &BitSet<BasicBlock>,
+                         discr: &Operand<'tcx>,
+                         switch_ty: Ty<'tcx>,
+                         values: &[u128],
+                         targets: &[BasicBlock]) -> Option<TerminatorKind<'tcx>> {
+    let (&otherwise, arms) = targets.split_last().unwrap();
+    let (values, mut arms): (Vec<u128>, Vec<BasicBlock>) = values.iter()
+        .zip(arms)
+        .filter(|&(_, &target)| !unreachable.contains(target))
+        .map(|(&value, &target)| (value, target))
+        .unzip();
+
+    if unreachable.contains(otherwise) {
+        // The value must be one of the reachable arms.
+        match arms.len() {
+            0 => return Some(TerminatorKind::Unreachable),
+            1 => return Some(TerminatorKind::Goto { target: arms[0] }),
+            _ => {}
+        }
+    }
+
+    if arms.len() == targets.len() - 1 {
+        return None;
+    }
+
+    arms.push(otherwise);
+    Some(TerminatorKind::SwitchInt {
+        discr: discr.clone(),
+        switch_ty,
+        values: Cow::from(values),
+        targets: arms,
+    })
+}
@@ -0,0 +1,251 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sanity checks for MIR, run after every MIR pass with `-Z validate-mir`.
+//!
+//! These only check invariants that every pass must preserve, so that a pass
+//! producing broken MIR is caught right away instead of much later in codegen:
+//!
+//! - every local, promoted and block that is referred to exists,
+//! - unwinding out of a regular block goes to a cleanup block,
+//! - projections fit the type they are applied to,
+//! - the value assigned to a place has the type of that place,
+//! - locals are not used while their storage is definitely dead.
+
+use dataflow::{do_dataflow, DataflowResults, DebugFormatted, MaybeStorageLive};
+use rustc::mir::*;
+use rustc::mir::tcx::PlaceTy;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::indexed_vec::Idx;
+use syntax_pos::Span;
+use transform::MirSource;
+
+pub fn validate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &Mir<'tcx>,
+                          pass_name: &str) {
+    let dead_unwinds = BitSet::new_empty(mir.basic_blocks().len());
+    let storage_live =
+        do_dataflow(tcx, mir, source, &[], &dead_unwinds, MaybeStorageLive::new(mir),
+                    |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
+
+    let mut has_storage_markers = BitSet::new_empty(mir.local_decls.len());
+    for data in mir.basic_blocks() {
+        for statement in &data.statements {
+            match statement.kind {
+                StatementKind::StorageLive(l) |
+                StatementKind::StorageDead(l) => { has_storage_markers.insert(l); }
+                _ => (),
+            }
+        }
+    }
+
+    let mut validator = Validator {
+        tcx,
+        mir,
+        span: mir.span,
+        errors: vec![],
+        storage_live_results: storage_live,
+        storage_live: BitSet::new_empty(mir.local_decls.len()),
+        has_storage_markers,
+    };
+    validator.visit_mir(mir);
+
+    if let Some(&(span, ref error)) = validator.errors.first() {
+        span_bug!(span,
+                  "broken MIR in {:?} ({:?}) after pass {}: {}",
+                  source.def_id,
+                  source.promoted,
+                  pass_name,
+                  error);
+    }
+}
+
+struct Validator<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    /// The span of the statement or terminator currently being checked.
+    span: Span,
+    errors: Vec<(Span, String)>,
+    storage_live_results: DataflowResults<MaybeStorageLive<'a, 'tcx>>,
+    /// The locals whose storage may be live before the current statement.
+    storage_live: BitSet<Local>,
+    /// Locals without storage markers are live for the whole body.
+    has_storage_markers: BitSet<Local>,
+}
+
+impl<'a, 'tcx> Validator<'a, 'tcx> {
+    fn error(&mut self, error: String) {
+        self.errors.push((self.span, error));
+    }
+
+    fn check_target(&mut self, from: BasicBlock, target: BasicBlock) {
+        if target.index() >= self.mir.basic_blocks().len() {
+            self.error(format!("{:?} jumps to nonexistent block {:?}", from, target));
+        }
+    }
+
+    /// Computes the type of `place`, checking every projection on the way.
+    /// Returns `None` if the place is broken.
+    fn check_place(&mut self, place: &Place<'tcx>) -> Option<PlaceTy<'tcx>> {
+        match *place {
+            Place::Local(local) => {
+                if local.index() >= self.mir.local_decls.len() {
+                    return None;
+                }
+                Some(PlaceTy::from_ty(self.mir.local_decls[local].ty))
+            }
+            Place::Promoted(ref promoted) => {
+                if promoted.0.index() >= self.mir.promoted.len() {
+                    self.error(format!("use of nonexistent {:?}", promoted.0));
+                    return None;
+                }
+                Some(PlaceTy::from_ty(promoted.1))
+            }
+            Place::Static(ref statik) => Some(PlaceTy::from_ty(statik.ty)),
+            Place::Projection(ref proj) => {
+                let base_ty = self.check_place(&proj.base)?;
+                if !self.projection_fits(base_ty, &proj.elem) {
+                    self.error(format!("projection {:?} does not fit the type {:?} of {:?}",
+                                       proj.elem, base_ty, proj.base));
+                    return None;
+                }
+                Some(base_ty.projection_ty(self.tcx, &proj.elem))
+            }
+        }
+    }
+
+    fn projection_fits(&self, base_ty: PlaceTy<'tcx>, elem: &PlaceElem<'tcx>) -> bool {
+        let ty = base_ty.to_ty(self.tcx);
+        match *elem {
+            ProjectionElem::Deref => ty.builtin_deref(true).is_some(),
+            ProjectionElem::Index(_) |
+            ProjectionElem::ConstantIndex { .. } => ty.builtin_index().is_some(),
+            ProjectionElem::Subslice { .. } => match ty.sty {
+                ty::Array(..) | ty::Slice(..) => true,
+                _ => false,
+            },
+            ProjectionElem::Downcast(..) => match ty.sty {
+                ty::Adt(adt_def, _) => adt_def.is_enum(),
+                _ => false,
+            },
+            ProjectionElem::Field(field, _) => {
+                let field_count = match base_ty {
+                    PlaceTy::Downcast { adt_def, variant_index, .. } => {
+                        Some(adt_def.variants[variant_index].fields.len())
+                    }
+                    PlaceTy::Ty { ty } => match ty.sty {
+                        ty::Adt(adt_def, _) if !adt_def.is_enum() => {
+                            Some(adt_def.non_enum_variant().fields.len())
+                        }
+                        ty::Tuple(tys) => Some(tys.len()),
+                        ty::Closure(def_id, substs) => {
+                            Some(substs.upvar_tys(def_id, self.tcx).count())
+                        }
+                        ty::Adt(..) | ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) |
+                        ty::Float(_) | ty::Ref(..) | ty::RawPtr(_) | ty::FnPtr(_) |
+                        ty::Never => Some(0),
+                        // Generators get their fields from the state transform.
+                        _ => None,
+                    },
+                };
+                field_count.map_or(true, |count| field.index() < count)
+            }
+        }
+    }
+
+    /// Whether a value of type `value_ty` can be stored in a place of type
+    /// `place_ty`. Regions are ignored, and so are types we can't compare
+    /// without normalizing or revealing them.
+    fn types_match(&self, place_ty: Ty<'tcx>, value_ty: Ty<'tcx>) -> bool {
+        let place_ty = self.tcx.erase_regions(&place_ty);
+        let value_ty = self.tcx.erase_regions(&value_ty);
+        place_ty == value_ty ||
+            place_ty.has_projections() || value_ty.has_projections() ||
+            place_ty.has_late_bound_regions() || value_ty.has_late_bound_regions() ||
+            place_ty.references_error() || value_ty.references_error()
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for Validator<'a, 'tcx> {
+    fn visit_basic_block_data(&mut self, block: BasicBlock, data: &BasicBlockData<'tcx>) {
+        self.storage_live.overwrite(
+            self.storage_live_results.sets().on_entry_set_for(block.index()));
+        self.super_basic_block_data(block, data);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, location: Location) {
+        if local.index() >= self.mir.local_decls.len() {
+            self.error(format!("use of undeclared local {:?} at {:?}", local, location));
+            return;
+        }
+
+        if context.is_use() &&
+           self.has_storage_markers.contains(local) &&
+           !self.storage_live.contains(local) {
+            self.error(format!("use of {:?} at {:?} while its storage is dead", local, location));
+        }
+    }
+
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        self.check_place(place);
+        self.super_place(place, context, location);
+    }
+
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        self.span = statement.source_info.span;
+        self.super_statement(block, statement, location);
+
+        match statement.kind {
+            StatementKind::Assign(ref place, ref rvalue) if self.errors.is_empty() => {
+                let place_ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+                let value_ty = rvalue.ty(self.mir, self.tcx);
+                if !self.types_match(place_ty, value_ty) {
+                    self.error(format!("assignment of a {:?} to {:?} of type {:?}",
+                                       value_ty, place, place_ty));
+                }
+            }
+            StatementKind::StorageLive(local) => { self.storage_live.insert(local); }
+            StatementKind::StorageDead(local) => { self.storage_live.remove(local); }
+            _ => (),
+        }
+    }
+
+    fn visit_terminator(&mut self,
+                        block: BasicBlock,
+                        terminator: &Terminator<'tcx>,
+                        location: Location) {
+        self.span = terminator.source_info.span;
+
+        for &target in terminator.successors() {
+            self.check_target(block, target);
+        }
+
+        // Unwinding out of a regular block has to go to a cleanup block.
+        if !self.mir[block].is_cleanup {
+            if let Some(&Some(unwind)) = terminator.unwind() {
+                if unwind.index() < self.mir.basic_blocks().len() &&
+                   !self.mir[unwind].is_cleanup {
+                    self.error(format!("{:?} unwinds to non-cleanup block {:?}", block, unwind));
+                }
+            }
+        }
+
+        self.super_terminator(block, terminator, location);
+    }
+}
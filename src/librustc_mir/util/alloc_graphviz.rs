@@ -0,0 +1,132 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Graphviz dump of the allocations created by constant evaluation, for `-Z dump-const-allocs`.
+
+use dot;
+use rustc::mir::interpret::{AllocId, AllocType, Allocation, GlobalId};
+use rustc::ty::{query, TyCtxt};
+use rustc::ty::layout::Size;
+use rustc_data_structures::fx::FxHashMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Bytes after this many are elided from a node's label.
+const MAX_BYTES_SHOWN: usize = 64;
+
+/// Write a graphviz DOT graph of all allocations currently in the `alloc_map` to `path`.
+pub fn dump_const_allocs<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>, path: &Path) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    write_const_alloc_graphviz(tcx, &mut file)
+}
+
+/// Write a graphviz DOT graph of all allocations currently in the `alloc_map`, with the
+/// constants and statics they hold the value of, and edges for their relocations.
+pub fn write_const_alloc_graphviz<'tcx, W>(tcx: TyCtxt<'_, 'tcx, 'tcx>, w: &mut W) -> io::Result<()>
+    where W: Write
+{
+    let mut owners: FxHashMap<AllocId, Vec<GlobalId<'tcx>>> = FxHashMap::default();
+    for (gid, alloc_id) in query::const_eval_raw_results(tcx) {
+        owners.entry(alloc_id).or_default().push(gid);
+    }
+    let mut allocs = tcx.alloc_map.memory_allocs();
+    allocs.sort_by_key(|&(id, _)| id);
+
+    writeln!(w, "digraph const_allocs {{")?;
+    writeln!(w, r#"    graph [fontname="monospace"];"#)?;
+    writeln!(w, r#"    node [fontname="monospace"];"#)?;
+    writeln!(w, r#"    edge [fontname="monospace"];"#)?;
+
+    for &(id, alloc) in &allocs {
+        write!(w, r#"    {} [shape="none", label=<"#, node(id))?;
+        write_alloc_label(tcx, id, alloc, owners.get(&id).map_or(&[][..], |o| &o[..]), w)?;
+        writeln!(w, ">];")?;
+    }
+
+    for &(id, alloc) in &allocs {
+        for &(offset, ((), target)) in alloc.relocations.iter() {
+            // Functions and statics are not part of `allocs`, give them a node of their own
+            match tcx.alloc_map.get(target) {
+                Some(AllocType::Function(instance)) => {
+                    let label = format!("fn {}", instance);
+                    writeln!(w, r#"    {} [label="{}"];"#, node(target), escape(&label))?;
+                }
+                Some(AllocType::Static(def_id)) => {
+                    let label = format!("static {}", tcx.item_path_str(def_id));
+                    writeln!(w, r#"    {} [label="{}"];"#, node(target), escape(&label))?;
+                }
+                Some(AllocType::Memory(_)) => {}
+                None => writeln!(w, r#"    {} [label="dangling {}"];"#, node(target), target)?,
+            }
+            writeln!(w, r#"    {} -> {} [label="+{}"];"#, node(id), node(target), offset.bytes())?;
+        }
+    }
+
+    writeln!(w, "}}")
+}
+
+fn write_alloc_label<'tcx, W: Write>(
+    tcx: TyCtxt<'_, 'tcx, 'tcx>,
+    id: AllocId,
+    alloc: &Allocation,
+    owners: &[GlobalId<'tcx>],
+    w: &mut W,
+) -> io::Result<()> {
+    write!(w, r#"<table border="0" cellborder="1" cellspacing="0">"#)?;
+    write!(w, r#"<tr><td bgcolor="gray" align="center">alloc{}</td></tr>"#, id)?;
+    write!(w, r#"<tr><td align="left">size {}, align {}, {:?}</td></tr>"#,
+           alloc.bytes.len(), alloc.align.bytes(), alloc.mutability)?;
+    for gid in owners {
+        let path = tcx.item_path_str(gid.instance.def_id());
+        let owner = match gid.promoted {
+            Some(promoted) => format!("{:?} in {}", promoted, path),
+            None => path,
+        };
+        write!(w, r#"<tr><td align="left">{}</td></tr>"#, dot::escape_html(&owner))?;
+    }
+
+    // Undefined bytes are shown as `__`, the bytes of pointers as `pp`
+    let mut bytes = String::new();
+    for (i, byte) in alloc.bytes.iter().enumerate().take(MAX_BYTES_SHOWN) {
+        let offset = Size::from_bytes(i as u64);
+        if i > 0 {
+            bytes.push_str(if i % 16 == 0 { "<br/>" } else { " " });
+        }
+        if !alloc.undef_mask.get(offset) {
+            bytes.push_str("__");
+        } else if is_pointer_byte(tcx, alloc, offset) {
+            bytes.push_str("pp");
+        } else {
+            bytes.push_str(&format!("{:02x}", byte));
+        }
+    }
+    if alloc.bytes.len() > MAX_BYTES_SHOWN {
+        bytes.push_str(" ...");
+    }
+    if !bytes.is_empty() {
+        write!(w, r#"<tr><td align="left" balign="left">{}</td></tr>"#, bytes)?;
+    }
+    write!(w, "</table>")
+}
+
+fn is_pointer_byte(tcx: TyCtxt<'_, '_, '_>, alloc: &Allocation, offset: Size) -> bool {
+    let pointer_size = tcx.data_layout.pointer_size;
+    let start = Size::from_bytes(offset.bytes().saturating_sub(pointer_size.bytes() - 1));
+    !alloc.relocations.range(start..offset + Size::from_bytes(1)).is_empty()
+}
+
+fn node(id: AllocId) -> String {
+    format!("alloc{}", id)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -18,6 +18,7 @@ pub mod def_use;
 pub mod patch;
 
 mod alignment;
+mod alloc_graphviz;
 mod graphviz;
 pub(crate) mod pretty;
 pub mod liveness;
@@ -26,6 +27,7 @@ pub mod collect_writes;
 pub use self::alignment::is_disaligned;
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
 pub use self::graphviz::{write_mir_graphviz};
+pub use self::alloc_graphviz::{dump_const_allocs, write_const_alloc_graphviz};
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
 
 /// If possible, suggest replacing `ref` with `ref mut`.
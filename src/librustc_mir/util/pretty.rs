@@ -168,7 +168,7 @@ fn dump_matched_mir_node<'a, 'gcx, 'tcx, F>(
 /// Returns the path to the filename where we should dump a given MIR.
 /// Also used by other bits of code (e.g., NLL inference) that dump
 /// graphviz data or other things.
-fn dump_path(
+pub(crate) fn dump_path(
     tcx: TyCtxt<'_, '_, '_>,
     extension: &str,
     pass_num: Option<&dyn Display>,
@@ -321,7 +321,7 @@ loop {
 "##
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
     E0226, // only a single explicit lifetime bound is permitted
     E0472, // asm! is unsupported on this target
     E0561, // patterns aren't allowed in function pointer types
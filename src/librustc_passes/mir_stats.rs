@@ -12,6 +12,7 @@
 // pieces of MIR. The resulting numbers are good approximations but not
 // completely accurate (some things might be counted twice, others missed).
 
+use rustc::hir::def_id::LOCAL_CRATE;
 use rustc::mir::{AggregateKind, AssertMessage, BasicBlock, BasicBlockData};
 use rustc::mir::{Constant, Location, Local, LocalDecl};
 use rustc::mir::{Place, PlaceElem, PlaceProjection};
@@ -21,6 +22,7 @@ use rustc::mir::{Terminator, TerminatorKind, SourceScope, SourceScopeData};
 use rustc::mir::interpret::EvalErrorKind;
 use rustc::mir::visit as mir_visit;
 use rustc::ty::{self, ClosureSubsts, TyCtxt};
+use rustc::util::common::to_readable_str;
 use rustc::util::nodemap::{FxHashMap};
 
 struct NodeData {
@@ -33,6 +35,20 @@ struct StatCollector<'a, 'tcx: 'a> {
     data: FxHashMap<&'static str, NodeData>,
 }
 
+/// Prints the sizes and counts of the pieces of the optimized MIR of the
+/// local crate, followed by how much each MIR pass grew or shrank it.
+pub fn print_mir_stats<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, title: &str) {
+    let mut collector = StatCollector {
+        _tcx: tcx,
+        data: FxHashMap::default(),
+    };
+    for &def_id in tcx.mir_keys(LOCAL_CRATE).iter() {
+        mir_visit::Visitor::visit_mir(&mut collector, tcx.optimized_mir(def_id));
+    }
+    collector.print(title);
+    tcx.sess.mir_pass_stats.borrow().print_pass_deltas();
+}
+
 impl<'a, 'tcx> StatCollector<'a, 'tcx> {
 
     fn record_with_size(&mut self, label: &'static str, node_size: usize) {
@@ -48,6 +64,34 @@ impl<'a, 'tcx> StatCollector<'a, 'tcx> {
     fn record<T>(&mut self, label: &'static str, node: &T) {
         self.record_with_size(label, ::std::mem::size_of_val(node));
     }
+
+    fn print(&self, title: &str) {
+        let mut stats: Vec<_> = self.data.iter().collect();
+
+        stats.sort_by_key(|&(label, d)| (d.count * d.size, *label));
+
+        let mut total_size = 0;
+
+        println!("\n{}\n", title);
+
+        println!("{:<44}{:>18}{:>14}{:>14}",
+            "Name", "Accumulated Size", "Count", "Item Size");
+        println!("{}", "-".repeat(90));
+
+        for (label, data) in stats {
+            println!("{:<44}{:>18}{:>14}{:>14}",
+                label,
+                to_readable_str(data.count * data.size),
+                to_readable_str(data.count),
+                to_readable_str(data.size));
+
+            total_size += data.count * data.size;
+        }
+        println!("{}", "-".repeat(90));
+        println!("{:<44}{:>18}\n",
+                "Total",
+                to_readable_str(total_size));
+    }
 }
 
 impl<'a, 'tcx> mir_visit::Visitor<'tcx> for StatCollector<'a, 'tcx> {
@@ -90,6 +134,7 @@ impl<'a, 'tcx> mir_visit::Visitor<'tcx> for StatCollector<'a, 'tcx> {
             StatementKind::StorageDead(..) => "StatementKind::StorageDead",
             StatementKind::InlineAsm { .. } => "StatementKind::InlineAsm",
             StatementKind::AscribeUserType(..) => "StatementKind::AscribeUserType",
+            StatementKind::CoverageCounter { .. } => "StatementKind::CoverageCounter",
             StatementKind::Nop => "StatementKind::Nop",
         }, &statement.kind);
         self.super_statement(block, statement, location);
@@ -14,6 +14,6 @@ register_long_diagnostics! {
 
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
     E0498  // malformed plugin attribute
 }
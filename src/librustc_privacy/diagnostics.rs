@@ -168,6 +168,6 @@ let f = Bar::Foo::new(); // ok!
 
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
 //  E0450, moved into resolve
 }
@@ -1654,7 +1654,7 @@ fn main() {
 
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
 //  E0153, unused error code
 //  E0157, unused error code
 //  E0257,
@@ -60,7 +60,7 @@ use syntax::ext::base::SyntaxExtension;
 use syntax::ext::base::Determinacy::{self, Determined, Undetermined};
 use syntax::ext::base::MacroKind;
 use syntax::symbol::{Symbol, keywords};
-use syntax::util::lev_distance::find_best_match_for_name;
+use syntax::util::lev_distance::NameMatcher;
 
 use syntax::visit::{self, FnKind, Visitor};
 use syntax::attr;
@@ -112,6 +112,9 @@ enum ScopeSet {
 /// A free importable items suggested in case of resolution failure.
 struct ImportSuggestion {
     path: Path,
+    /// Whether the item and every module on the path are visible from the
+    /// module where the resolution failed.
+    accessible: bool,
 }
 
 /// A field or associated item from self type suggested in case of resolution failure.
@@ -174,7 +177,7 @@ enum ResolutionError<'a> {
     /// error E0431: `self` import can only appear in an import list with a non-empty prefix
     SelfImportOnlyInImportListWithNonEmptyPrefix,
     /// error E0433: failed to resolve
-    FailedToResolve(&'a str),
+    FailedToResolve(&'a str, Option<Suggestion>),
     /// error E0434: can't capture dynamic environment in a fn item
     CannotCaptureDynamicEnvironmentInFnItem,
     /// error E0435: attempt to use a non-constant value in a constant
@@ -354,10 +357,23 @@ fn resolve_struct_error<'sess, 'a>(resolver: &'sess Resolver,
                                            E0426,
                                            "use of undeclared label `{}`",
                                            name);
-            if let Some(lev_candidate) = lev_candidate {
-                err.span_label(span, format!("did you mean `{}`?", lev_candidate));
-            } else {
-                err.span_label(span, format!("undeclared label `{}`", name));
+            match lev_candidate {
+                // A label with the same name from another macro expansion.
+                Some(lev_candidate) if lev_candidate == name => {
+                    err.span_label(span, format!("did you mean `{}`?", lev_candidate));
+                }
+                Some(lev_candidate) => {
+                    err.span_label(span, format!("undeclared label `{}`", name));
+                    err.span_suggestion_with_applicability(
+                        span,
+                        "a label with a similar name is in scope",
+                        lev_candidate.to_string(),
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+                None => {
+                    err.span_label(span, format!("undeclared label `{}`", name));
+                }
             }
             err
         }
@@ -381,10 +397,13 @@ fn resolve_struct_error<'sess, 'a>(resolver: &'sess Resolver,
             err.span_label(span, "can only appear in an import list with a non-empty prefix");
             err
         }
-        ResolutionError::FailedToResolve(msg) => {
+        ResolutionError::FailedToResolve(msg, suggestion) => {
             let mut err = struct_span_err!(resolver.session, span, E0433,
                                            "failed to resolve: {}", msg);
             err.span_label(span, msg);
+            if let Some((sugg_span, sugg_msg, sugg, applicability)) = suggestion {
+                err.span_suggestion_with_applicability(sugg_span, &sugg_msg, sugg, applicability);
+            }
             err
         }
         ResolutionError::CannotCaptureDynamicEnvironmentInFnItem => {
@@ -1036,12 +1055,16 @@ impl<'a> PartialEq for ModuleOrUniformRoot<'a> {
     }
 }
 
+/// A structured suggestion for a resolution error: the span to replace, the message and
+/// the replacement.
+type Suggestion = (Span, String, String, Applicability);
+
 #[derive(Clone, Debug)]
 enum PathResult<'a> {
     Module(ModuleOrUniformRoot<'a>),
     NonModule(PathResolution),
     Indeterminate,
-    Failed(Span, String, bool /* is the error from the last segment? */),
+    Failed(Span, String, Option<Suggestion>, bool /* is the error from the last segment? */),
 }
 
 enum ModuleKind {
@@ -1234,7 +1257,14 @@ enum NameBindingKind<'a> {
     }
 }
 
-struct PrivacyError<'a>(Span, Ident, &'a NameBinding<'a>);
+struct PrivacyError<'a> {
+    dedup_span: Span,
+    ident: Ident,
+    binding: &'a NameBinding<'a>,
+    /// Whether the private name is an intermediate segment of an import path,
+    /// rather than the imported name itself.
+    in_import_path: bool,
+}
 
 struct UseError<'a> {
     err: DiagnosticBuilder<'a>,
@@ -1305,6 +1335,8 @@ struct AmbiguityError<'a> {
     b2: &'a NameBinding<'a>,
     misc1: AmbiguityErrorMisc,
     misc2: AmbiguityErrorMisc,
+    /// Further candidates, only collected for import ambiguities.
+    others: Vec<(&'a NameBinding<'a>, AmbiguityErrorMisc)>,
 }
 
 impl<'a> NameBinding<'a> {
@@ -1483,6 +1515,8 @@ pub struct Resolver<'a, 'b: 'a> {
     pub definitions: Definitions,
 
     graph_root: Module<'a>,
+    /// Name of the crate being compiled, only used for diagnostics.
+    crate_name: Symbol,
 
     prelude: Option<Module<'a>>,
     pub extern_prelude: FxHashMap<Ident, ExternPreludeEntry<'a>>,
@@ -1586,7 +1620,8 @@ pub struct Resolver<'a, 'b: 'a> {
     macro_use_prelude: FxHashMap<Name, &'a NameBinding<'a>>,
     pub all_macros: FxHashMap<Name, Def>,
     macro_map: FxHashMap<DefId, Lrc<SyntaxExtension>>,
-    macro_defs: FxHashMap<Mark, DefId>,
+    /// Maps the expansion of each resolved macro invocation to the macro's definition.
+    pub macro_defs: FxHashMap<Mark, DefId>,
     local_macro_def_scopes: FxHashMap<NodeId, Module<'a>>,
     pub found_unresolved_macro: bool,
 
@@ -1771,12 +1806,12 @@ impl<'a, 'crateloader> Resolver<'a, 'crateloader> {
                 path_res.base_def(),
             PathResult::NonModule(..) => {
                 let msg = "type-relative paths are not supported in this context";
-                error_callback(self, span, ResolutionError::FailedToResolve(msg));
+                error_callback(self, span, ResolutionError::FailedToResolve(msg, None));
                 Def::Err
             }
             PathResult::Module(..) | PathResult::Indeterminate => unreachable!(),
-            PathResult::Failed(span, msg, _) => {
-                error_callback(self, span, ResolutionError::FailedToResolve(&msg));
+            PathResult::Failed(span, msg, suggestion, _) => {
+                error_callback(self, span, ResolutionError::FailedToResolve(&msg, suggestion));
                 Def::Err
             }
         };
@@ -1853,6 +1888,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
             // The outermost module has def ID 0; this is not reflected in the
             // AST.
             graph_root,
+            crate_name: Symbol::intern(crate_name),
             prelude: None,
             extern_prelude,
 
@@ -2001,6 +2037,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                     kind, ident, b1, b2,
                     misc1: AmbiguityErrorMisc::None,
                     misc2: AmbiguityErrorMisc::None,
+                    others: Vec::new(),
                 });
             }
             _ => {}
@@ -3667,8 +3704,8 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
             }
             PathResult::Module(ModuleOrUniformRoot::Module(module)) =>
                 PathResolution::new(module.def().unwrap()),
-            PathResult::Failed(span, msg, false) => {
-                resolve_error(self, span, ResolutionError::FailedToResolve(&msg));
+            PathResult::Failed(span, msg, suggestion, false) => {
+                resolve_error(self, span, ResolutionError::FailedToResolve(&msg, suggestion));
                 err_path_resolution()
             }
             PathResult::Module(..) | PathResult::Failed(..) => return None,
@@ -3779,7 +3816,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                         }
                     }
                     let msg = "there are too many initial `super`s.".to_string();
-                    return PathResult::Failed(ident.span, msg, false);
+                    return PathResult::Failed(ident.span, msg, None, false);
                 }
                 if i == 0 {
                     if name == keywords::SelfLower.name() {
@@ -3822,7 +3859,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                 } else {
                     format!("{} in paths can only be used in start position", name_str)
                 };
-                return PathResult::Failed(ident.span, msg, false);
+                return PathResult::Failed(ident.span, msg, None, false);
             }
 
             let binding = if let Some(module) = module {
@@ -3878,6 +3915,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                     } else {
                         return PathResult::Failed(ident.span,
                                                   format!("not a module `{}`", ident),
+                                                  None,
                                                   is_last);
                     }
                 }
@@ -3894,12 +3932,31 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                         Some(ModuleOrUniformRoot::Module(module)) => module.def(),
                         _ => None,
                     };
-                    let msg = if module_def == self.graph_root.def() {
+                    let names_current_crate = ident.name == self.crate_name &&
+                                              ident.span.rust_2018() && match module {
+                        None | Some(ModuleOrUniformRoot::ExternPrelude) => true,
+                        _ => false,
+                    };
+                    let mut span = ident.span;
+                    let mut suggestion = None;
+                    let msg = if names_current_crate {
+                        // Replace a leading `::` along with the crate name.
+                        if i > 0 && path[i - 1].ident.name == keywords::PathRoot.name() {
+                            span = path[i - 1].ident.span.to(span);
+                        }
+                        suggestion = Some((
+                            span,
+                            "use `crate` to refer to the current crate".to_string(),
+                            keywords::Crate.name().to_string(),
+                            Applicability::MachineApplicable,
+                        ));
+                        format!("`{}` is the current crate, refer to it as `crate`", ident)
+                    } else if module_def == self.graph_root.def() {
                         let is_mod = |def| match def { Def::Mod(..) => true, _ => false };
                         let mut candidates =
                             self.lookup_import_candidates(ident, TypeNS, is_mod);
                         candidates.sort_by_cached_key(|c| {
-                            (c.path.segments.len(), c.path.to_string())
+                            (!c.accessible, c.path.segments.len(), c.path.to_string())
                         });
                         if let Some(candidate) = candidates.get(0) {
                             format!("did you mean `{}`?", candidate.path)
@@ -3911,7 +3968,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                     } else {
                         format!("could not find `{}` in `{}`", ident, path[i - 1].ident)
                     };
-                    return PathResult::Failed(ident.span, msg, is_last);
+                    return PathResult::Failed(span, msg, suggestion, is_last);
                 }
             }
         }
@@ -4215,7 +4272,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
         let name = path[path.len() - 1].ident.name;
         // Make sure error reporting is deterministic.
         names.sort_by_cached_key(|name| name.as_str());
-        match find_best_match_for_name(names.iter(), &name.as_str(), None) {
+        match NameMatcher::new(&name.as_str()).best(names.iter()) {
             Some(found) if found != name => Some(found),
             _ => None,
         }
@@ -4270,7 +4327,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                         // the closest match
                         let close_match = self.search_label(label.ident, |rib, ident| {
                             let names = rib.bindings.iter().map(|(id, _)| &id.name);
-                            find_best_match_for_name(names, &*ident.as_str(), None)
+                            NameMatcher::new(&ident.as_str()).best(names)
                         });
                         self.record_def(expr.id, err_path_resolution());
                         resolve_error(self,
@@ -4535,11 +4592,13 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
         let mut candidates = Vec::new();
         let mut seen_modules = FxHashSet::default();
         let not_local_module = crate_name != keywords::Crate.ident();
-        let mut worklist = vec![(start_module, Vec::<ast::PathSegment>::new(), not_local_module)];
+        let mut worklist =
+            vec![(start_module, Vec::<ast::PathSegment>::new(), not_local_module, true)];
 
         while let Some((in_module,
                         path_segments,
-                        in_module_is_extern)) = worklist.pop() {
+                        in_module_is_extern,
+                        in_module_is_accessible)) = worklist.pop() {
             self.populate_module_if_necessary(in_module);
 
             // We have to visit module children in deterministic order to avoid
@@ -4555,7 +4614,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                     if filter_fn(name_binding.def()) {
                         // create the path
                         let mut segms = path_segments.clone();
-                        if lookup_ident.span.rust_2018() {
+                        if lookup_ident.span.rust_2018() || not_local_module {
                             // crate-local absolute paths start with `crate::` in edition 2018
                             // FIXME: may also be stabilized for Rust 2015 (Issues #45477, #44660)
                             segms.insert(
@@ -4576,7 +4635,9 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                         // declared as public (due to pruning, we don't explore
                         // outside crate private modules => no need to check this)
                         if !in_module_is_extern || name_binding.vis == ty::Visibility::Public {
-                            candidates.push(ImportSuggestion { path });
+                            let accessible =
+                                in_module_is_accessible && self.is_accessible(name_binding.vis);
+                            candidates.push(ImportSuggestion { path, accessible });
                         }
                     }
                 }
@@ -4597,8 +4658,10 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                     if !is_extern_crate_that_also_appears_in_prelude && is_visible_to_user {
                         // add the module to the lookup
                         let is_extern = in_module_is_extern || name_binding.is_extern_crate();
+                        let is_accessible =
+                            in_module_is_accessible && self.is_accessible(name_binding.vis);
                         if seen_modules.insert(module.def_id().unwrap()) {
-                            worklist.push((module, path_segments, is_extern));
+                            worklist.push((module, path_segments, is_extern, is_accessible));
                         }
                     }
                 }
@@ -4624,6 +4687,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
     {
         let mut suggestions = self.lookup_import_candidates_from_module(
             lookup_ident, namespace, self.graph_root, keywords::Crate.ident(), &filter_fn);
+        let mut searched_crates = FxHashSet::default();
 
         if lookup_ident.span.rust_2018() {
             let extern_prelude_names = self.extern_prelude.clone();
@@ -4635,6 +4699,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                         index: CRATE_DEF_INDEX,
                     });
                     self.populate_module_if_necessary(&crate_root);
+                    searched_crates.insert(crate_id);
 
                     suggestions.extend(self.lookup_import_candidates_from_module(
                         lookup_ident, namespace, crate_root, ident, &filter_fn));
@@ -4642,6 +4707,25 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
             }
         }
 
+        // Also look into the other crates loaded by this one (e.g. through an
+        // `extern crate` outside of the crate root). Their paths can't always
+        // be written as they are from here, so they are ranked last. Crates
+        // loaded only as dependencies of other crates are skipped; they can't
+        // be named without adding them as a dependency first.
+        for crate_id in self.cstore.crates_untracked() {
+            if searched_crates.contains(&crate_id) ||
+               !self.cstore.is_direct_dependency_untracked(crate_id) {
+                continue;
+            }
+            let crate_root = self.get_module(DefId { krate: crate_id, index: CRATE_DEF_INDEX });
+            let crate_name = Ident::with_empty_ctxt(self.cstore.crate_name_untracked(crate_id));
+            let crate_suggestions = self.lookup_import_candidates_from_module(
+                lookup_ident, namespace, crate_root, crate_name, &filter_fn);
+            suggestions.extend(crate_suggestions.into_iter().map(|suggestion| {
+                ImportSuggestion { accessible: false, ..suggestion }
+            }));
+        }
+
         suggestions
     }
 
@@ -4673,7 +4757,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                             span: name_binding.span,
                             segments: path_segments,
                         };
-                        result = Some((module, ImportSuggestion { path }));
+                        result = Some((module, ImportSuggestion { path, accessible: true }));
                     } else {
                         // add the module to the lookup
                         if seen_modules.insert(module.def_id().unwrap()) {
@@ -4737,7 +4821,12 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                 } else if ident.span.rust_2018() {
                     let msg = "relative paths are not supported in visibilities on 2018 edition";
                     self.session.struct_span_err(ident.span, msg)
-                                .span_suggestion(path.span, "try", format!("crate::{}", path))
+                                .span_suggestion_with_applicability(
+                                    path.span,
+                                    "try",
+                                    format!("crate::{}", path),
+                                    Applicability::MachineApplicable,
+                                )
                                 .emit();
                     return ty::Visibility::Public;
                 } else {
@@ -4835,7 +4924,7 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
     }
 
     fn report_ambiguity_error(&self, ambiguity_error: &AmbiguityError) {
-        let AmbiguityError { kind, ident, b1, b2, misc1, misc2 } = *ambiguity_error;
+        let AmbiguityError { kind, ident, b1, b2, misc1, misc2, ref others } = *ambiguity_error;
         let (b1, b2, misc1, misc2, swapped) = if b2.span.is_dummy() && !b1.span.is_dummy() {
             // We have to print the span-less alternative first, otherwise formatting looks bad.
             (b2, b1, misc2, misc1, true)
@@ -4848,6 +4937,12 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                                        ident = ident, why = kind.descr());
         err.span_label(ident.span, "ambiguous name");
 
+        // Imports written by the user can be fixed up directly, for anything else
+        // (including imports produced by macros) the paths are only mentioned.
+        let suggest_paths = kind == AmbiguityKind::Import &&
+                            ident.span.ctxt().outer().expn_info().is_none();
+        let mut suggestions = Vec::new();
+
         let mut could_refer_to = |b: &NameBinding, misc: AmbiguityErrorMisc, also: &str| {
             let what = self.binding_description(b, ident, misc == AmbiguityErrorMisc::FromPrelude);
             let note_msg = format!("`{ident}` could{also} refer to {what}",
@@ -4858,25 +4953,48 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
                                       kind == AmbiguityKind::GlobVsExpanded ||
                                       kind == AmbiguityKind::GlobVsOuter &&
                                       swapped != also.is_empty()) {
+                // The glob may be in another module than the use site, so name local items
+                // by their path from the crate root. Items of other crates keep their bare
+                // name, since their path depends on how the crate is reached.
+                let path = match b.kind {
+                    NameBindingKind::Import { directive, .. } => {
+                        match directive.imported_module.get() {
+                            Some(ModuleOrUniformRoot::Module(module))
+                                    if is_nameable_local_module(module) => {
+                                let root = if ident.span.rust_2018() { "crate::" } else { "" };
+                                match module_to_string(module) {
+                                    Some(module_str) => {
+                                        format!("{}{}::{}", root, module_str, ident)
+                                    }
+                                    None => format!("{}{}", root, ident),
+                                }
+                            }
+                            _ => ident.to_string(),
+                        }
+                    }
+                    _ => ident.to_string(),
+                };
                 help_msgs.push(format!("consider adding an explicit import of \
-                                        `{ident}` to disambiguate", ident = ident))
+                                        `{path}` to disambiguate", path = path))
             }
+
+            let mut paths = Vec::new();
             if b.is_extern_crate() && ident.span.rust_2018() {
-                help_msgs.push(format!(
-                    "use `::{ident}` to refer to this {thing} unambiguously",
-                    ident = ident, thing = b.descr(),
-                ))
+                paths.push(format!("::{}", ident));
             }
             if misc == AmbiguityErrorMisc::SuggestCrate {
-                help_msgs.push(format!(
-                    "use `crate::{ident}` to refer to this {thing} unambiguously",
-                    ident = ident, thing = b.descr(),
-                ))
+                paths.push(format!("crate::{}", ident));
             } else if misc == AmbiguityErrorMisc::SuggestSelf {
-                help_msgs.push(format!(
-                    "use `self::{ident}` to refer to this {thing} unambiguously",
-                    ident = ident, thing = b.descr(),
-                ))
+                paths.push(format!("self::{}", ident));
+            }
+            for path in paths {
+                let msg = format!("use `{path}` to refer to this {thing} unambiguously",
+                                  path = path, thing = b.descr());
+                if suggest_paths {
+                    suggestions.push((msg, path));
+                } else {
+                    help_msgs.push(msg);
+                }
             }
 
             if b.span.is_dummy() {
@@ -4892,6 +5010,40 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
 
         could_refer_to(b1, misc1, "");
         could_refer_to(b2, misc2, " also");
+        for &(b, misc) in others {
+            could_refer_to(b, misc, " also");
+        }
+
+        if kind == AmbiguityKind::Import && ident.span.rust_2018() {
+            // A 2015 import always refers to the name in the crate root, which is only worth
+            // pointing out if a closer name shadows it in the 2018 edition, so the innermost
+            // candidate is skipped.
+            let crate_root = iter::once((ambiguity_error.b2, ambiguity_error.misc2))
+                .chain(others.iter().cloned())
+                .find(|&(_, misc)| misc == AmbiguityErrorMisc::SuggestCrate);
+            if let Some((b, _)) = crate_root {
+                err.note(&format!("in the 2015 edition, this import would refer to the {} \
+                                   in the crate root", b.descr()));
+            }
+            let has_extern_crate = iter::once(b1)
+                .chain(iter::once(b2))
+                .chain(others.iter().map(|&(b, _)| b))
+                .any(|b| b.is_extern_crate());
+            if has_extern_crate {
+                err.note("in the 2018 edition, imports can refer to names in scope \
+                          as well as to extern crates");
+            }
+        }
+
+        // The paths are alternatives to each other, only one of them can be applied.
+        for (msg, path) in suggestions {
+            err.span_suggestion_with_applicability(
+                ident.span,
+                &msg,
+                path,
+                Applicability::MaybeIncorrect,
+            );
+        }
         err.emit();
     }
 
@@ -4914,11 +5066,25 @@ impl<'a, 'crateloader: 'a> Resolver<'a, 'crateloader> {
         }
 
         let mut reported_spans = FxHashSet::default();
-        for &PrivacyError(dedup_span, ident, binding) in &self.privacy_errors {
-            if reported_spans.insert(dedup_span) {
-                span_err!(self.session, ident.span, E0603, "{} `{}` is private",
-                          binding.descr(), ident.name);
+        for error in &self.privacy_errors {
+            let PrivacyError { dedup_span, ident, binding, in_import_path } = *error;
+            if !reported_spans.insert(dedup_span) {
+                continue;
+            }
+            let descr = binding.descr();
+            let mut err = struct_span_err!(self.session, ident.span, E0603,
+                                           "{} `{}` is private", descr, ident.name);
+            // Nothing below a private local item can be imported through it, point at the
+            // item so it can be made public or the needed names re-exported from it.
+            let is_local_item = !binding.is_import() &&
+                binding.def().opt_def_id().map_or(false, |def_id| def_id.is_local());
+            if in_import_path && is_local_item {
+                let def_span = self.session.source_map().def_span(binding.span);
+                err.span_note(def_span, &format!("the {} `{}` is defined here", descr, ident));
+                err.help(&format!("make `{}` public, or re-export the names you need from it \
+                                   with `pub use`", ident));
             }
+            err.emit();
         }
     }
 
@@ -5146,10 +5312,23 @@ fn show_candidates(err: &mut DiagnosticBuilder,
                    found_use: bool) {
 
     // we want consistent results across executions, but candidates are produced
-    // by iterating through a hash map, so make sure they are ordered:
+    // by iterating through a hash map, so make sure they are ordered. Candidates
+    // that can be imported as they are come first, then shorter paths.
+    let mut candidates: Vec<_> = candidates.iter().map(|c| {
+        (!c.accessible, c.path.segments.len(), path_names_to_string(&c.path))
+    }).collect();
+    candidates.sort();
+    // Hygienic items from different expansions, or items found both through
+    // the crate graph and the loaded crates, can end up with the same path.
+    let mut seen_paths = FxHashSet::default();
+    candidates.retain(|candidate| seen_paths.insert(candidate.2.clone()));
+    let applicability = if candidates.len() == 1 && !candidates[0].0 {
+        Applicability::MachineApplicable
+    } else {
+        Applicability::MaybeIncorrect
+    };
     let mut path_strings: Vec<_> =
-        candidates.into_iter().map(|c| path_names_to_string(&c.path)).collect();
-    path_strings.sort();
+        candidates.into_iter().map(|(_, _, path)| path).collect();
 
     let better = if better { "better " } else { "" };
     let msg_diff = match path_strings.len() {
@@ -5174,7 +5353,7 @@ fn show_candidates(err: &mut DiagnosticBuilder,
             span,
             &msg,
             path_strings.into_iter(),
-            Applicability::Unspecified,
+            applicability,
         );
     } else {
         let mut msg = msg;
@@ -5212,10 +5391,26 @@ fn module_to_string(module: Module) -> Option<String> {
                         .collect::<Vec<_>>()))
 }
 
+/// Whether `module` is a module of the local crate that can be named with a path
+/// from the crate root, i.e. that isn't nested in a block.
+fn is_nameable_local_module(mut module: Module) -> bool {
+    loop {
+        match module.kind {
+            ModuleKind::Def(def, _) if def.def_id().is_local() => match module.parent {
+                Some(parent) => module = parent,
+                None => return true,
+            },
+            _ => return false,
+        }
+    }
+}
+
 fn err_path_resolution() -> PathResolution {
     PathResolution::new(Def::Err)
 }
 
+/// Whether the resolver results should keep the maps that only tools like
+/// save-analysis use: the glob map and the macro definition map.
 #[derive(PartialEq,Copy, Clone)]
 pub enum MakeGlobMap {
     Yes,
@@ -33,7 +33,7 @@ use syntax::feature_gate::{feature_err, is_builtin_attr_name, GateIssue};
 use syntax::fold::{self, Folder};
 use syntax::ptr::P;
 use syntax::symbol::{Symbol, keywords};
-use syntax::util::lev_distance::find_best_match_for_name;
+use syntax::util::lev_distance::NameMatcher;
 use syntax_pos::{Span, DUMMY_SP};
 use errors::Applicability;
 
@@ -537,6 +537,19 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
         // So we have to save the innermost solution and continue searching in outer scopes
         // to detect potential ambiguities.
         let mut innermost_result: Option<(&NameBinding, Flags)> = None;
+        // Import ambiguities don't stop the search, the remaining scopes are still
+        // visited so that every candidate can be listed in the error.
+        let mut import_ambiguity: Option<usize> = None;
+
+        let misc = |f: Flags| if f.contains(Flags::MISC_SUGGEST_CRATE) {
+            AmbiguityErrorMisc::SuggestCrate
+        } else if f.contains(Flags::MISC_SUGGEST_SELF) {
+            AmbiguityErrorMisc::SuggestSelf
+        } else if f.contains(Flags::MISC_FROM_PRELUDE) {
+            AmbiguityErrorMisc::FromPrelude
+        } else {
+            AmbiguityErrorMisc::None
+        };
 
         // Go through all the scopes and try to resolve the name.
         let rust_2015 = orig_ident.span.rust_2015();
@@ -600,8 +613,10 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                     );
                     match binding {
                         Ok(binding) => Ok((binding, Flags::MODULE | Flags::MISC_SUGGEST_CRATE)),
-                        Err((Determinacy::Undetermined, Weak::No)) =>
+                        Err((Determinacy::Undetermined, Weak::No)) if import_ambiguity.is_none() =>
                             return Err(Determinacy::determined(force)),
+                        Err((Determinacy::Undetermined, Weak::No)) =>
+                            Err(Determinacy::Undetermined),
                         Err((Determinacy::Undetermined, Weak::Yes)) =>
                             Err(Determinacy::Undetermined),
                         Err((Determinacy::Determined, _)) => Err(Determinacy::Determined),
@@ -630,8 +645,10 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                             };
                             Ok((binding, Flags::MODULE | misc_flags))
                         }
-                        Err((Determinacy::Undetermined, Weak::No)) =>
+                        Err((Determinacy::Undetermined, Weak::No)) if import_ambiguity.is_none() =>
                             return Err(Determinacy::determined(force)),
+                        Err((Determinacy::Undetermined, Weak::No)) =>
+                            Err(Determinacy::Undetermined),
                         Err((Determinacy::Undetermined, Weak::Yes)) =>
                             Err(Determinacy::Undetermined),
                         Err((Determinacy::Determined, _)) => Err(Determinacy::Determined),
@@ -737,7 +754,13 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                     if let Some((innermost_binding, innermost_flags)) = innermost_result {
                         // Found another solution, if the first one was "weak", report an error.
                         let (def, innermost_def) = (binding.def(), innermost_binding.def());
-                        if def != innermost_def {
+                        if let Some(index) = import_ambiguity {
+                            let error = &mut self.ambiguity_errors[index];
+                            if def != innermost_def && def != error.b2.def() &&
+                               error.others.iter().all(|&(other, _)| other.def() != def) {
+                                error.others.push((binding, misc(flags)));
+                            }
+                        } else if def != innermost_def {
                             let builtin = Def::NonMacroAttr(NonMacroAttrKind::Builtin);
                             let derive_helper = Def::NonMacroAttr(NonMacroAttrKind::DeriveHelper);
                             let legacy_helper =
@@ -774,15 +797,6 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                                 None
                             };
                             if let Some(kind) = ambiguity_error_kind {
-                                let misc = |f: Flags| if f.contains(Flags::MISC_SUGGEST_CRATE) {
-                                    AmbiguityErrorMisc::SuggestCrate
-                                } else if f.contains(Flags::MISC_SUGGEST_SELF) {
-                                    AmbiguityErrorMisc::SuggestSelf
-                                } else if f.contains(Flags::MISC_FROM_PRELUDE) {
-                                    AmbiguityErrorMisc::FromPrelude
-                                } else {
-                                    AmbiguityErrorMisc::None
-                                };
                                 self.ambiguity_errors.push(AmbiguityError {
                                     kind,
                                     ident: orig_ident,
@@ -790,8 +804,12 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                                     b2: binding,
                                     misc1: misc(innermost_flags),
                                     misc2: misc(flags),
+                                    others: Vec::new(),
                                 });
-                                return Ok(innermost_binding);
+                                if kind != AmbiguityKind::Import {
+                                    return Ok(innermost_binding);
+                                }
+                                import_ambiguity = Some(self.ambiguity_errors.len() - 1);
                             }
                         }
                     } else {
@@ -861,6 +879,10 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
 
         // The first found solution was the only one, return it.
         if let Some((binding, flags)) = innermost_result {
+            if import_ambiguity.is_some() {
+                return Ok(binding);
+            }
+
             // We get to here only if there's no ambiguity, in ambiguous cases an error will
             // be reported anyway, so there's no reason to report an additional feature error.
             // The `binding` can actually be introduced by something other than `--extern`,
@@ -961,13 +983,14 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                     check_consistency(self, &path, path_span, kind, initial_def, def);
                 }
                 path_res @ PathResult::NonModule(..) | path_res @  PathResult::Failed(..) => {
-                    let (span, msg) = if let PathResult::Failed(span, msg, ..) = path_res {
-                        (span, msg)
-                    } else {
-                        (path_span, format!("partially resolved path in {} {}",
-                                            kind.article(), kind.descr()))
-                    };
-                    resolve_error(self, span, ResolutionError::FailedToResolve(&msg));
+                    let (span, msg, suggestion) =
+                        if let PathResult::Failed(span, msg, suggestion, _) = path_res {
+                            (span, msg, suggestion)
+                        } else {
+                            (path_span, format!("partially resolved path in {} {}",
+                                                kind.article(), kind.descr()), None)
+                        };
+                    resolve_error(self, span, ResolutionError::FailedToResolve(&msg, suggestion));
                 }
                 PathResult::Module(..) | PathResult::Indeterminate => unreachable!(),
             }
@@ -1011,7 +1034,7 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                           err: &mut DiagnosticBuilder<'a>, span: Span) {
         // First check if this is a locally-defined bang macro.
         let suggestion = if let MacroKind::Bang = kind {
-            find_best_match_for_name(self.macro_names.iter().map(|ident| &ident.name), name, None)
+            NameMatcher::new(name).best(self.macro_names.iter().map(|ident| &ident.name))
         } else {
             None
         // Then check global macros.
@@ -1020,7 +1043,7 @@ impl<'a, 'cl> Resolver<'a, 'cl> {
                                                   .filter_map(|(name, binding)| {
                 if binding.macro_kind() == Some(kind) { Some(name) } else { None }
             });
-            find_best_match_for_name(names, name, None)
+            NameMatcher::new(name).best(names)
         // Then check modules.
         }).or_else(|| {
             let is_macro = |def| {
@@ -30,9 +30,10 @@ use rustc::util::nodemap::FxHashSet;
 
 use syntax::ast::{Ident, Name, NodeId, CRATE_NODE_ID};
 use syntax::ext::base::Determinacy::{self, Determined, Undetermined};
+use syntax::ext::base::{MacroKind, SyntaxExtension};
 use syntax::ext::hygiene::Mark;
 use syntax::symbol::keywords;
-use syntax::util::lev_distance::find_best_match_for_name;
+use syntax::util::lev_distance::NameMatcher;
 use syntax_pos::{MultiSpan, Span};
 
 use std::cell::{Cell, RefCell};
@@ -263,6 +264,7 @@ impl<'a, 'crateloader> Resolver<'a, 'crateloader> {
                                 b2: shadowed_glob,
                                 misc1: AmbiguityErrorMisc::None,
                                 misc2: AmbiguityErrorMisc::None,
+                                others: Vec::new(),
                             });
                         }
                     }
@@ -270,7 +272,12 @@ impl<'a, 'crateloader> Resolver<'a, 'crateloader> {
                     if !self.is_accessible(binding.vis) &&
                        // Remove this together with `PUB_USE_OF_PRIVATE_EXTERN_CRATE`
                        !(self.last_import_segment && binding.is_extern_crate()) {
-                        self.privacy_errors.push(PrivacyError(path_span, ident, binding));
+                        self.privacy_errors.push(PrivacyError {
+                            dedup_span: path_span,
+                            ident,
+                            binding,
+                            in_import_path: false,
+                        });
                     }
 
                     Ok(binding)
@@ -835,6 +842,49 @@ impl<'a, 'b:'a, 'c: 'b> ImportResolver<'a, 'b, 'c> {
         !indeterminate
     }
 
+    /// `macro_rules!` macros live in textual scope rather than in their module, so importing
+    /// one by path fails unless it is `#[macro_export]`ed. Explain that when an import names
+    /// a macro defined in the module it's looked up in.
+    fn macro_rules_import_note(&self, module: Module<'b>, ident: Ident) -> Option<String> {
+        // Look through the macros defined in `module` itself rather than the crate-wide
+        // name table, where a later macro with the same name would shadow this one. The
+        // module may define several of them, so consider them all.
+        let name = ident.name.as_interned_str();
+        let def_ids = self.local_macro_def_scopes.iter()
+            .filter(|&(_, &scope)| ptr::eq(scope, module))
+            .map(|(&node_id, _)| self.definitions.local_def_id(node_id))
+            .filter(|def_id| {
+                self.definitions.def_key(def_id.index).disambiguated_data.data
+                    .get_opt_name() == Some(name) &&
+                match self.macro_map.get(def_id) {
+                    Some(ext) => match **ext {
+                        SyntaxExtension::NormalTT { .. } => true,
+                        _ => false,
+                    },
+                    None => false,
+                }
+            })
+            .collect::<FxHashSet<_>>();
+        if def_ids.is_empty() {
+            return None;
+        }
+
+        let exported = self.graph_root.resolutions.borrow()
+            .get(&(ident.modern(), MacroNS))
+            .and_then(|resolution| resolution.borrow().binding)
+            .map_or(false, |binding| match binding.def() {
+                Def::Macro(def_id, MacroKind::Bang) => def_ids.contains(&def_id),
+                _ => false,
+            });
+        Some(if exported {
+            format!("`{}` is defined with `macro_rules!` and `#[macro_export]`ed, \
+                     so it can only be imported from the crate root", ident)
+        } else {
+            format!("`{}` is defined with `macro_rules!`, so it's only in scope textually; \
+                     add `#[macro_export]` to it to import it from the crate root", ident)
+        })
+    }
+
     // If appropriate, returns an error to report.
     fn finalize_import(
         &mut self,
@@ -844,9 +894,13 @@ impl<'a, 'b:'a, 'c: 'b> ImportResolver<'a, 'b, 'c> {
 
         let orig_vis = directive.vis.replace(ty::Visibility::Invisible);
         let prev_ambiguity_errors_len = self.ambiguity_errors.len();
+        let prev_privacy_errors_len = self.privacy_errors.len();
         let path_res = self.resolve_path(&directive.module_path, None, &directive.parent_scope,
                                          true, directive.span, directive.crate_lint());
         let no_ambiguity = self.ambiguity_errors.len() == prev_ambiguity_errors_len;
+        for error in &mut self.privacy_errors[prev_privacy_errors_len..] {
+            error.in_import_path = true;
+        }
         directive.vis.set(orig_vis);
         let module = match path_res {
             PathResult::Module(module) => {
@@ -865,16 +919,22 @@ impl<'a, 'b:'a, 'c: 'b> ImportResolver<'a, 'b, 'c> {
 
                 module
             }
-            PathResult::Failed(span, msg, false) => {
+            PathResult::Failed(span, msg, suggestion, false) => {
                 if no_ambiguity {
                     assert!(directive.imported_module.get().is_none());
-                    resolve_error(self, span, ResolutionError::FailedToResolve(&msg));
+                    resolve_error(self, span, ResolutionError::FailedToResolve(&msg, suggestion));
                 }
                 return None;
             }
-            PathResult::Failed(span, msg, true) => {
+            PathResult::Failed(span, msg, suggestion, true) => {
                 if no_ambiguity {
                     assert!(directive.imported_module.get().is_none());
+                    if suggestion.is_some() {
+                        // The path names the current crate, e.g. `use my_crate::foo;`.
+                        resolve_error(self, span, ResolutionError::FailedToResolve(&msg,
+                                                                                   suggestion));
+                        return None;
+                    }
                     return Some(match self.make_path_suggestion(span, directive.module_path.clone(),
                                                                 &directive.parent_scope) {
                         Some((suggestion, note)) => (
@@ -1029,12 +1089,14 @@ impl<'a, 'b:'a, 'c: 'b> ImportResolver<'a, 'b, 'c> {
                     }
                 });
                 let lev_suggestion =
-                    match find_best_match_for_name(names, &ident.as_str(), None) {
+                    match NameMatcher::new(&ident.as_str()).best(names) {
                         Some(name) => format!(". Did you mean to use `{}`?", name),
                         None => String::new(),
                     };
+                let mut note = None;
                 let msg = match module {
                     ModuleOrUniformRoot::Module(module) => {
+                        note = self.macro_rules_import_note(module, ident);
                         let module_str = module_to_string(module);
                         if let Some(module_str) = module_str {
                             format!("no `{}` in `{}`{}", ident, module_str, lev_suggestion)
@@ -1043,7 +1105,11 @@ impl<'a, 'b:'a, 'c: 'b> ImportResolver<'a, 'b, 'c> {
                         }
                     }
                     _ => {
-                        if !ident.is_path_segment_keyword() {
+                        if ident.name == self.crate_name && ident.span.rust_2018() {
+                            note = Some(format!("crate root imports need to be explicitly \
+                                                 named: `use crate as {};`", ident));
+                            format!("`{}` is the current crate, refer to it as `crate`", ident)
+                        } else if !ident.is_path_segment_keyword() {
                             format!("no `{}` external crate{}", ident, lev_suggestion)
                         } else {
                             // HACK(eddyb) this shows up for `self` & `super`, which
@@ -1052,7 +1118,7 @@ impl<'a, 'b:'a, 'c: 'b> ImportResolver<'a, 'b, 'c> {
                         }
                     }
                 };
-                Some((directive.span, msg, None))
+                Some((directive.span, msg, note))
             } else {
                 // `resolve_ident_in_module` reported a privacy error.
                 self.import_dummy_binding(directive);
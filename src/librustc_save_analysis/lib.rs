@@ -37,6 +37,7 @@ extern crate rls_span;
 
 mod json_dumper;
 mod dump_visitor;
+mod resolve_map;
 #[macro_use]
 mod span_utils;
 mod sig;
@@ -44,7 +45,7 @@ mod sig;
 use rustc::hir;
 use rustc::hir::def::Def as HirDef;
 use rustc::hir::Node;
-use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc::middle::cstore::ExternCrate;
 use rustc::session::config::{CrateType, Input, OutputType};
 use rustc::ty::{self, TyCtxt};
@@ -55,6 +56,7 @@ use std::cell::Cell;
 use std::default::Default;
 use std::env;
 use std::fs::File;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use syntax::ast::{self, Attribute, DUMMY_NODE_ID, NodeId, PatKind};
@@ -68,6 +70,7 @@ use syntax_pos::*;
 
 use json_dumper::JsonDumper;
 use dump_visitor::DumpVisitor;
+use resolve_map::{Resolution, ResolutionKind, ResolveMap};
 use span_utils::SpanUtils;
 
 use rls_data::{Def, DefKind, ExternalCrateData, GlobalCrateId, MacroRef, Ref, RefKind, Relation,
@@ -885,6 +888,28 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
         })
     }
 
+    /// The macro invocations written in this crate's source, along with the
+    /// macros the resolver picked for them.
+    pub fn get_macro_resolutions(&self) -> Vec<Resolution> {
+        let macro_def_map = match self.analysis.macro_def_map {
+            Some(ref macro_def_map) => macro_def_map,
+            None => return vec![],
+        };
+        macro_def_map.iter().filter_map(|(mark, &def_id)| {
+            let call_site = mark.expn_info()?.call_site;
+            // Builtin macros have no definition to point at, and invocations
+            // produced by other macros aren't visible in the source.
+            if def_id.krate == CrateNum::BuiltinMacros || generated_code(call_site) {
+                return None;
+            }
+            Some(Resolution {
+                kind: ResolutionKind::Macro,
+                span: self.span_from_span(call_site),
+                ref_id: id_from_def_id(def_id),
+            })
+        }).collect()
+    }
+
     fn lookup_ref_id(&self, ref_id: NodeId) -> Option<DefId> {
         match self.get_path_def(ref_id) {
             HirDef::PrimTy(_) | HirDef::SelfTy(..) | HirDef::Err => None,
@@ -1036,37 +1061,43 @@ impl<'a> DumpHandler<'a> {
     }
 
     fn output_file(&self, ctx: &SaveContext) -> File {
-        let sess = &ctx.tcx.sess;
         let file_name = match ctx.config.output_file {
             Some(ref s) => PathBuf::from(s),
-            None => {
-                let mut root_path = match self.odir {
-                    Some(val) => val.join("save-analysis"),
-                    None => PathBuf::from("save-analysis-temp"),
-                };
+            None => self.default_output_path(ctx, ".json"),
+        };
+        self.create_file(ctx, file_name)
+    }
 
-                if let Err(e) = std::fs::create_dir_all(&root_path) {
-                    error!("Could not create directory {}: {}", root_path.display(), e);
-                }
+    fn default_output_path(&self, ctx: &SaveContext, extension: &str) -> PathBuf {
+        let sess = &ctx.tcx.sess;
+        let mut root_path = match self.odir {
+            Some(val) => val.join("save-analysis"),
+            None => PathBuf::from("save-analysis-temp"),
+        };
 
-                let executable = sess.crate_types
-                    .borrow()
-                    .iter()
-                    .any(|ct| *ct == CrateType::Executable);
-                let mut out_name = if executable {
-                    String::new()
-                } else {
-                    "lib".to_owned()
-                };
-                out_name.push_str(&self.cratename);
-                out_name.push_str(&sess.opts.cg.extra_filename);
-                out_name.push_str(".json");
-                root_path.push(&out_name);
+        if let Err(e) = std::fs::create_dir_all(&root_path) {
+            error!("Could not create directory {}: {}", root_path.display(), e);
+        }
 
-                root_path
-            }
+        let executable = sess.crate_types
+            .borrow()
+            .iter()
+            .any(|ct| *ct == CrateType::Executable);
+        let mut out_name = if executable {
+            String::new()
+        } else {
+            "lib".to_owned()
         };
+        out_name.push_str(&self.cratename);
+        out_name.push_str(&sess.opts.cg.extra_filename);
+        out_name.push_str(extension);
+        root_path.push(&out_name);
+
+        root_path
+    }
 
+    fn create_file(&self, ctx: &SaveContext, file_name: PathBuf) -> File {
+        let sess = &ctx.tcx.sess;
         info!("Writing output to {}", file_name.display());
 
         let output_file = File::create(&file_name).unwrap_or_else(
@@ -1122,6 +1153,58 @@ impl<'b> SaveHandler for CallbackHandler<'b> {
     }
 }
 
+/// Dump a map from each resolved path and macro invocation to its definition
+/// (see `-Z save-resolve`), next to where the save-analysis results go.
+pub struct ResolveMapHandler<'a> {
+    files: DumpHandler<'a>,
+}
+
+impl<'a> ResolveMapHandler<'a> {
+    pub fn new(odir: Option<&'a Path>, cratename: &str) -> ResolveMapHandler<'a> {
+        ResolveMapHandler {
+            files: DumpHandler::new(odir, cratename),
+        }
+    }
+}
+
+impl<'a> SaveHandler for ResolveMapHandler<'a> {
+    fn save<'l, 'tcx>(
+        &mut self,
+        save_ctxt: SaveContext<'l, 'tcx>,
+        krate: &ast::Crate,
+        cratename: &str,
+        _input: &'l Input,
+    ) {
+        let file_name = self.files.default_output_path(&save_ctxt, ".resolve.json");
+        let output = &mut self.files.create_file(&save_ctxt, file_name);
+        let mut map = ResolveMap {
+            crate_name: cratename.to_owned(),
+            external_crates: save_ctxt.get_external_crates(),
+            refs: save_ctxt.get_macro_resolutions(),
+        };
+
+        {
+            // Paths and method calls are found the same way as for the full
+            // save-analysis results, of which only the refs are kept.
+            let config = save_ctxt.config.clone();
+            let refs = &mut map.refs;
+            let mut callback = |analysis: &rls_data::Analysis| {
+                refs.extend(analysis.refs.iter().map(Resolution::from));
+            };
+            let mut dumper = JsonDumper::with_callback(&mut callback, config);
+            let mut visitor = DumpVisitor::new(save_ctxt, &mut dumper);
+            visit::walk_crate(&mut visitor, krate);
+        }
+
+        map.refs.sort_by(|a, b| {
+            (&a.span.file_name, a.span.byte_start).cmp(&(&b.span.file_name, b.span.byte_start))
+        });
+        if write!(output, "{}", rustc_serialize::json::as_json(&map)).is_err() {
+            error!("Error writing output");
+        }
+    }
+}
+
 pub fn process_crate<'l, 'tcx, H: SaveHandler>(
     tcx: TyCtxt<'l, 'tcx, 'tcx>,
     krate: &ast::Crate,
@@ -0,0 +1,56 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The data written by `-Z save-resolve`: the span of every path, method call
+//! and macro invocation in the crate together with the definition it resolved
+//! to. Unlike the full save-analysis data this carries no definitions, so tools
+//! only interested in go-to-definition don't have to reimplement resolution.
+
+use rls_data::{ExternalCrateData, Id, Ref, RefKind, SpanData};
+
+#[derive(Debug, RustcEncodable)]
+pub struct ResolveMap {
+    pub crate_name: String,
+    /// The crates `ref_id.krate` can refer to, other than the local one (`0`).
+    pub external_crates: Vec<ExternalCrateData>,
+    pub refs: Vec<Resolution>,
+}
+
+#[derive(Debug, RustcEncodable)]
+pub struct Resolution {
+    pub kind: ResolutionKind,
+    pub span: SpanData,
+    pub ref_id: Id,
+}
+
+#[derive(Debug, RustcEncodable)]
+pub enum ResolutionKind {
+    Function,
+    Mod,
+    Type,
+    Variable,
+    Macro,
+}
+
+impl<'a> From<&'a Ref> for Resolution {
+    fn from(r: &'a Ref) -> Resolution {
+        let kind = match r.kind {
+            RefKind::Function => ResolutionKind::Function,
+            RefKind::Mod => ResolutionKind::Mod,
+            RefKind::Type => ResolutionKind::Type,
+            RefKind::Variable => ResolutionKind::Variable,
+        };
+        Resolution {
+            kind,
+            span: r.span.clone(),
+            ref_id: r.ref_id,
+        }
+    }
+}
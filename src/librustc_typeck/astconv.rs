@@ -32,7 +32,7 @@ use smallvec::SmallVec;
 use syntax::ast;
 use syntax::feature_gate::{GateIssue, emit_feature_err};
 use syntax::ptr::P;
-use syntax::util::lev_distance::find_best_match_for_name;
+use syntax::util::lev_distance::NameMatcher;
 use syntax_pos::{DUMMY_SP, Span, MultiSpan};
 use util::common::ErrorReported;
 use util::nodemap::FxHashMap;
@@ -219,9 +219,9 @@ impl<'o, 'gcx: 'tcx, 'tcx> dyn AstConv<'gcx, 'tcx> + 'o {
                 span,
                 E0632,
                 "cannot provide explicit type parameters when `impl Trait` is \
-                 used in argument position."
+                 used in argument position"
             };
-
+            err.span_label(span, "explicit type parameter not allowed");
             err.emit();
         }
 
@@ -1310,11 +1310,7 @@ impl<'o, 'gcx: 'tcx, 'tcx> dyn AstConv<'gcx, 'tcx> + 'o {
                 );
                 // Check if it was a typo
                 let input = adt_def.variants.iter().map(|variant| &variant.name);
-                if let Some(suggested_name) = find_best_match_for_name(
-                    input,
-                    &assoc_name.as_str(),
-                    None,
-                ) {
+                if let Some(suggested_name) = NameMatcher::new(&assoc_name.as_str()).best(input) {
                     err.span_suggestion_with_applicability(
                         span,
                         "did you mean",
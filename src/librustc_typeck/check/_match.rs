@@ -10,6 +10,7 @@
 
 use check::{FnCtxt, Expectation, Diverges, Needs};
 use check::coercion::CoerceMany;
+use errors::Applicability;
 use rustc::hir::{self, PatKind};
 use rustc::hir::def::{Def, CtorKind};
 use rustc::hir::pat_util::EnumerateAndAdjustIterator;
@@ -20,7 +21,7 @@ use rustc::ty::{self, Ty, TypeFoldable};
 use syntax::ast;
 use syntax::source_map::Spanned;
 use syntax::ptr::P;
-use syntax::util::lev_distance::find_best_match_for_name;
+use syntax::util::lev_distance::NameMatcher;
 use syntax_pos::Span;
 use util::nodemap::FxHashMap;
 
@@ -961,10 +962,14 @@ https://doc.rust-lang.org/reference/types.html#trait-objects");
                                        plural));
                 if plural == "" {
                     let input = unmentioned_fields.iter().map(|field| &field.name);
-                    let suggested_name =
-                        find_best_match_for_name(input, &ident.name.as_str(), None);
+                    let suggested_name = NameMatcher::new(&ident.name.as_str()).best(input);
                     if let Some(suggested_name) = suggested_name {
-                        err.span_suggestion(*span, "did you mean", suggested_name.to_string());
+                        err.span_suggestion_with_applicability(
+                            *span,
+                            "did you mean",
+                            suggested_name.to_string(),
+                            Applicability::MaybeIncorrect,
+                        );
                         // we don't want to throw `E0027` in case we have thrown `E0026` for them
                         unmentioned_fields.retain(|&x| x.as_str() != suggested_name.as_str());
                     }
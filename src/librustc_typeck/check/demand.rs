@@ -10,6 +10,7 @@
 
 use check::FnCtxt;
 use rustc::infer::InferOk;
+use rustc::lint;
 use rustc::traits::ObligationCause;
 
 use syntax::ast;
@@ -344,17 +345,24 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 // we may want to suggest adding a `*`, or removing
                 // a `&`.
                 //
-                // (But, also check check the `expn_info()` to see if this is
-                // a macro; if so, it's hard to extract the text and make a good
-                // suggestion, so don't bother.)
+                // (But, also check if this comes from an external macro; if so,
+                // it's hard to extract the text and make a good suggestion, so
+                // don't bother. Suggestions into local macros are moved to the
+                // invocation, or shown with the expansion, by the emitter.)
                 if self.infcx.can_sub(self.param_env, checked, &expected).is_ok() &&
-                   sp.ctxt().outer().expn_info().is_none() {
+                   !lint::in_external_macro(self.tcx.sess, sp) {
                     match expr.node {
                         // Maybe remove `&`?
                         hir::ExprKind::AddrOf(_, ref expr) => {
                             if !cm.span_to_filename(expr.span).is_real() {
                                 return None;
                             }
+                            // The borrowed expression was passed to the macro
+                            // taking the reference, there's no single place to
+                            // remove the `&` from.
+                            if expr.span.ctxt() != sp.ctxt() {
+                                return None;
+                            }
                             if let Ok(code) = cm.span_to_snippet(expr.span) {
                                 return Some((sp, "consider removing the borrow", code));
                             }
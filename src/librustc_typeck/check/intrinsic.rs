@@ -17,6 +17,12 @@ use rustc::ty::{self, TyCtxt, Ty};
 use rustc::ty::subst::Subst;
 use rustc::util::nodemap::FxHashMap;
 use require_same_types;
+use session_diagnostics::{IntrinsicInconsistentVectorType, IntrinsicMustBeFunction};
+use session_diagnostics::{IntrinsicWrongType, InvalidSimdShuffle, UnrecognizedAtomicOperation};
+use session_diagnostics::{UnrecognizedIntrinsicFunction, UnrecognizedPlatformIntrinsicFunction};
+use session_diagnostics::{WrongNumberOfArgumentsToPlatformIntrinsic};
+use session_diagnostics::{WrongNumberOfTypeArgumentsToIntrinsic};
+use session_diagnostics::{WrongNumberOfTypeArgumentsToPlatformIntrinsic};
 
 use rustc_target::spec::abi::Abi;
 use syntax::ast;
@@ -41,10 +47,7 @@ fn equate_intrinsic_type<'a, 'tcx>(
     match it.node {
         hir::ForeignItemKind::Fn(..) => {}
         _ => {
-            struct_span_err!(tcx.sess, it.span, E0622,
-                             "intrinsic must be a function")
-                .span_label(it.span, "expected a function")
-                .emit();
+            tcx.sess.emit_err(IntrinsicMustBeFunction { span: it.span });
             return;
         }
     }
@@ -56,12 +59,11 @@ fn equate_intrinsic_type<'a, 'tcx>(
             _ => bug!()
         };
 
-        struct_span_err!(tcx.sess, span, E0094,
-                        "intrinsic has wrong number of type \
-                         parameters: found {}, expected {}",
-                        i_n_tps, n_tps)
-            .span_label(span, format!("expected {} type parameter", n_tps))
-            .emit();
+        tcx.sess.emit_err(WrongNumberOfTypeArgumentsToIntrinsic {
+            span,
+            found: i_n_tps,
+            expected: n_tps,
+        });
         return;
     }
 
@@ -116,10 +118,7 @@ pub fn check_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                 (0, Vec::new(), tcx.mk_unit())
             }
             op => {
-                struct_span_err!(tcx.sess, it.span, E0092,
-                      "unrecognized atomic operation function: `{}`", op)
-                  .span_label(it.span, "unrecognized atomic operation")
-                  .emit();
+                tcx.sess.emit_err(UnrecognizedAtomicOperation { span: it.span, op });
                 return;
             }
         };
@@ -380,11 +379,7 @@ pub fn check_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             }
 
             ref other => {
-                struct_span_err!(tcx.sess, it.span, E0093,
-                                 "unrecognized intrinsic function: `{}`",
-                                 *other)
-                                 .span_label(it.span, "unrecognized intrinsic")
-                                 .emit();
+                tcx.sess.emit_err(UnrecognizedIntrinsicFunction { span: it.span, name: *other });
                 return;
             }
         };
@@ -452,8 +447,7 @@ pub fn check_platform_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                     (2, params, param(1))
                 }
                 Err(_) => {
-                    span_err!(tcx.sess, it.span, E0439,
-                              "invalid `simd_shuffle`, needs length: `{}`", name);
+                    tcx.sess.emit_err(InvalidSimdShuffle { span: it.span, name });
                     return
                 }
             }
@@ -463,10 +457,10 @@ pub fn check_platform_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                 Some(intr) => {
                     // this function is a platform specific intrinsic
                     if i_n_tps != 0 {
-                        span_err!(tcx.sess, it.span, E0440,
-                                  "platform-specific intrinsic has wrong number of type \
-                                   parameters: found {}, expected 0",
-                                  i_n_tps);
+                        tcx.sess.emit_err(WrongNumberOfTypeArgumentsToPlatformIntrinsic {
+                            span: it.span,
+                            found: i_n_tps,
+                        });
                         return
                     }
 
@@ -475,10 +469,11 @@ pub fn check_platform_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                     let sig = tcx.fn_sig(def_id);
                     let sig = sig.no_bound_vars().unwrap();
                     if intr.inputs.len() != sig.inputs().len() {
-                        span_err!(tcx.sess, it.span, E0444,
-                                  "platform-specific intrinsic has invalid number of \
-                                   arguments: found {}, expected {}",
-                                  sig.inputs().len(), intr.inputs.len());
+                        tcx.sess.emit_err(WrongNumberOfArgumentsToPlatformIntrinsic {
+                            span: it.span,
+                            found: sig.inputs().len(),
+                            expected: intr.inputs.len(),
+                        });
                         return
                     }
                     let input_pairs = intr.inputs.iter().zip(sig.inputs());
@@ -492,8 +487,10 @@ pub fn check_platform_intrinsic_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                     return
                 }
                 None => {
-                    span_err!(tcx.sess, it.span, E0441,
-                              "unrecognized platform-specific intrinsic function: `{}`", name);
+                    tcx.sess.emit_err(UnrecognizedPlatformIntrinsicFunction {
+                        span: it.span,
+                        name: &name,
+                    });
                     return;
                 }
             }
@@ -516,10 +513,8 @@ fn match_intrinsic_type_to_type<'a, 'tcx>(
 {
     use intrinsics::Type::*;
 
-    let simple_error = |real: &str, expected: &str| {
-        span_err!(tcx.sess, span, E0442,
-                  "intrinsic {} has wrong type: found {}, expected {}",
-                  position, real, expected)
+    let simple_error = |found: &str, expected: &str| {
+        tcx.sess.emit_err(IntrinsicWrongType { span, position, found, expected })
     };
 
     match *expected {
@@ -580,12 +575,12 @@ fn match_intrinsic_type_to_type<'a, 'tcx>(
                 let previous = structural_to_nominal.entry(expected).or_insert(t);
                 if *previous != t {
                     // this gets its own error code because it is non-trivial
-                    span_err!(tcx.sess, span, E0443,
-                              "intrinsic {} has wrong type: found `{}`, expected `{}` which \
-                               was used for this vector type previously in this signature",
-                              position,
-                              t,
-                              *previous);
+                    tcx.sess.emit_err(IntrinsicInconsistentVectorType {
+                        span,
+                        position,
+                        found: t,
+                        previous: *previous,
+                    });
                     return;
                 }
             }
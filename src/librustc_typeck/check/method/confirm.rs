@@ -551,10 +551,14 @@ impl<'a, 'gcx, 'tcx> ConfirmContext<'a, 'gcx, 'tcx> {
                     let mutbl = match mutbl {
                         hir::MutImmutable => AutoBorrowMutability::Immutable,
                         hir::MutMutable => AutoBorrowMutability::Mutable {
-                            // For initial two-phase borrow
-                            // deployment, conservatively omit
-                            // overloaded operators.
-                            allow_two_phase_borrow: AllowTwoPhase::No,
+                            // Overloaded indexing desugars to an `index_mut`
+                            // call, so its base may be a two-phase borrow.
+                            // A deref has no other operands, so it does not
+                            // matter there.
+                            allow_two_phase_borrow: match op {
+                                PlaceOp::Index => AllowTwoPhase::Yes,
+                                PlaceOp::Deref => AllowTwoPhase::No,
+                            },
                         }
                     };
                     adjustment.kind = Adjust::Borrow(AutoBorrow::Ref(region, mutbl));
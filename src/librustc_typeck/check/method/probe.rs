@@ -29,13 +29,12 @@ use rustc::util::nodemap::FxHashSet;
 use rustc::infer::{self, InferOk};
 use rustc::middle::stability;
 use syntax::ast;
-use syntax::util::lev_distance::{lev_distance, find_best_match_for_name};
+use syntax::util::lev_distance::NameMatcher;
 use syntax_pos::{Span, symbol::Symbol};
 use std::iter;
 use std::mem;
 use std::ops::Deref;
 use std::rc::Rc;
-use std::cmp::max;
 
 use self::CandidateKind::*;
 pub use self::PickKind::*;
@@ -1315,9 +1314,7 @@ impl<'a, 'gcx, 'tcx> ProbeContext<'a, 'gcx, 'tcx> {
             } else {
                 let best_name = {
                     let names = applicable_close_candidates.iter().map(|cand| &cand.ident.name);
-                    find_best_match_for_name(names,
-                                             &self.method_name.unwrap().as_str(),
-                                             None)
+                    NameMatcher::new(&self.method_name.unwrap().as_str()).best(names)
                 }.unwrap();
                 Ok(applicable_close_candidates
                    .into_iter()
@@ -1459,12 +1456,12 @@ impl<'a, 'gcx, 'tcx> ProbeContext<'a, 'gcx, 'tcx> {
     fn impl_or_trait_item(&self, def_id: DefId) -> Vec<ty::AssociatedItem> {
         if let Some(name) = self.method_name {
             if self.allow_similar_names {
-                let max_dist = max(name.as_str().len(), 3) / 3;
+                let name = name.as_str();
+                let matcher = NameMatcher::new(&name);
                 self.tcx.associated_items(def_id)
                     .filter(|x| {
-                        let dist = lev_distance(&*name.as_str(), &x.ident.as_str());
-                        Namespace::from(x.kind) == Namespace::Value && dist > 0
-                            && dist <= max_dist
+                        Namespace::from(x.kind) == Namespace::Value &&
+                            matcher.distance(x.ident.name).map_or(false, |dist| dist > 0)
                     })
                     .collect()
             } else {
@@ -28,7 +28,7 @@ use rustc::ty::item_path::with_crate_prefix;
 use util::nodemap::FxHashSet;
 use syntax_pos::{Span, FileName};
 use syntax::ast;
-use syntax::util::lev_distance::find_best_match_for_name;
+use syntax::util::lev_distance::NameMatcher;
 
 use std::cmp::Ordering;
 
@@ -196,9 +196,7 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 } else if actual.is_enum() {
                     if let Adt(ref adt_def, _) = actual.sty {
                         let names = adt_def.variants.iter().map(|s| &s.name);
-                        suggestion = find_best_match_for_name(names,
-                                                              &item_name.as_str(),
-                                                              None);
+                        suggestion = NameMatcher::new(&item_name.as_str()).best(names);
                     }
                     "variant"
                 } else {
@@ -51,6 +51,8 @@ use rustc::hir::def_id::LocalDefId;
 use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc::infer::UpvarRegion;
 use rustc::ty::{self, Ty, TyCtxt, UpvarSubsts};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
 use syntax::ast;
 use syntax_pos::Span;
 
@@ -169,6 +171,7 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
             current_closure_kind: ty::ClosureKind::LATTICE_BOTTOM,
             current_origin: None,
             adjust_upvar_captures: ty::UpvarCaptureMap::default(),
+            adjust_upvar_capture_origins: FxHashMap::default(),
         };
         euv::ExprUseVisitor::with_infer(
             &mut delegate,
@@ -198,6 +201,10 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
             .borrow_mut()
             .upvar_capture_map
             .extend(delegate.adjust_upvar_captures);
+        self.tables
+            .borrow_mut()
+            .upvar_capture_origins
+            .extend(delegate.adjust_upvar_capture_origins);
 
         // Now that we've analyzed the closure, we know how each
         // variable is borrowed, and we know what traits the closure
@@ -298,6 +305,10 @@ struct InferBorrowKind<'a, 'gcx: 'a + 'tcx, 'tcx: 'a> {
     // For each upvar that we access, we track the minimal kind of
     // access we need (ref, ref mut, move, etc).
     adjust_upvar_captures: ty::UpvarCaptureMap<'tcx>,
+
+    // For each upvar in `adjust_upvar_captures`, the span of the access
+    // that required its current capture mode.
+    adjust_upvar_capture_origins: FxHashMap<ty::UpvarId, Span>,
 }
 
 impl<'a, 'gcx, 'tcx> InferBorrowKind<'a, 'gcx, 'tcx> {
@@ -354,8 +365,11 @@ impl<'a, 'gcx, 'tcx> InferBorrowKind<'a, 'gcx, 'tcx> {
                         var_name(tcx, upvar_id.var_path.hir_id),
                     );
 
-                    self.adjust_upvar_captures
+                    let previous = self.adjust_upvar_captures
                         .insert(upvar_id, ty::UpvarCapture::ByValue);
+                    if previous != Some(ty::UpvarCapture::ByValue) {
+                        self.adjust_upvar_capture_origins.insert(upvar_id, guarantor.span);
+                    }
                 }
                 mc::NoteClosureEnv(upvar_id) => {
                     // we get just a closureenv ref if this is a
@@ -462,7 +476,7 @@ impl<'a, 'gcx, 'tcx> InferBorrowKind<'a, 'gcx, 'tcx> {
                 // upvar, then we need to modify the
                 // borrow_kind of the upvar to make sure it
                 // is inferred to mutable if necessary
-                self.adjust_upvar_borrow_kind(upvar_id, borrow_kind);
+                self.adjust_upvar_borrow_kind(upvar_id, borrow_kind, cmt.span);
 
                 // also need to be in an FnMut closure since this is not an ImmBorrow
                 self.adjust_closure_kind(
@@ -494,9 +508,14 @@ impl<'a, 'gcx, 'tcx> InferBorrowKind<'a, 'gcx, 'tcx> {
     /// We infer the borrow_kind with which to borrow upvars in a stack closure.
     /// The borrow_kind basically follows a lattice of `imm < unique-imm < mut`,
     /// moving from left to right as needed (but never right to left).
-    /// Here the argument `mutbl` is the borrow_kind that is required by
-    /// some particular use.
-    fn adjust_upvar_borrow_kind(&mut self, upvar_id: ty::UpvarId, kind: ty::BorrowKind) {
+    /// Here the argument `kind` is the borrow_kind that is required by
+    /// the use at `span`.
+    fn adjust_upvar_borrow_kind(
+        &mut self,
+        upvar_id: ty::UpvarId,
+        kind: ty::BorrowKind,
+        span: Span,
+    ) {
         let upvar_capture = self
             .adjust_upvar_captures
             .get(&upvar_id)
@@ -520,6 +539,7 @@ impl<'a, 'gcx, 'tcx> InferBorrowKind<'a, 'gcx, 'tcx> {
                         upvar_borrow.kind = kind;
                         self.adjust_upvar_captures
                             .insert(upvar_id, ty::UpvarCapture::ByRef(upvar_borrow));
+                        self.adjust_upvar_capture_origins.insert(upvar_id, span);
                     }
                     // Take LHS:
                     (ty::ImmBorrow, ty::ImmBorrow)
@@ -646,6 +666,41 @@ impl<'a, 'gcx, 'tcx> euv::Delegate<'tcx> for InferBorrowKind<'a, 'gcx, 'tcx> {
     }
 }
 
+/// Returns the capture analysis results for the closure or generator
+/// `closure_def_id`, in the order of its freevars.
+pub fn closure_captures<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    closure_def_id: DefId,
+) -> Lrc<Vec<ty::CapturedUpvar<'tcx>>> {
+    let closure_node_id = tcx.hir().as_local_node_id(closure_def_id).unwrap();
+    let closure_expr_id = LocalDefId::from_def_id(closure_def_id);
+    let tables = tcx.typeck_tables_of(closure_def_id);
+
+    let captures = tcx.with_freevars(closure_node_id, |freevars| {
+        freevars
+            .iter()
+            .map(|freevar| {
+                let var_hir_id = tcx.hir().node_to_hir_id(freevar.var_id());
+                let upvar_id = ty::UpvarId {
+                    var_path: ty::UpvarPath { hir_id: var_hir_id },
+                    closure_expr_id,
+                };
+                ty::CapturedUpvar {
+                    var_hir_id,
+                    name: var_name(tcx, var_hir_id),
+                    capture: tables.upvar_capture(upvar_id),
+                    span: tables
+                        .upvar_capture_origins
+                        .get(&upvar_id)
+                        .cloned()
+                        .unwrap_or(freevar.span),
+                }
+            }).collect()
+    });
+
+    Lrc::new(captures)
+}
+
 fn var_name(tcx: TyCtxt, var_hir_id: hir::HirId) -> ast::Name {
     let var_node_id = tcx.hir().hir_to_node_id(var_hir_id);
     tcx.hir().name(var_node_id)
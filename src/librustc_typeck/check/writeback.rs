@@ -321,6 +321,10 @@ impl<'cx, 'gcx, 'tcx> WritebackCx<'cx, 'gcx, 'tcx> {
                 .upvar_capture_map
                 .insert(*upvar_id, new_upvar_capture);
         }
+
+        self.tables
+            .upvar_capture_origins
+            .extend(self.fcx.tables.borrow().upvar_capture_origins.iter());
     }
 
     fn visit_closures(&mut self) {
@@ -13,7 +13,7 @@ use rustc::ty::TyCtxt;
 
 use errors::Applicability;
 use syntax::ast;
-use syntax_pos::Span;
+use syntax_pos::{MultiSpan, Span};
 
 use rustc::hir::def_id::{DefId, LOCAL_CRATE};
 use rustc::hir::itemlikevisit::ItemLikeVisitor;
@@ -130,14 +130,15 @@ fn unused_crates_lint<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) {
         crates_to_lint: &mut crates_to_lint,
     });
 
+    let mut removable = vec![];
     for extern_crate in &crates_to_lint {
         let id = tcx.hir().as_local_node_id(extern_crate.def_id).unwrap();
         let item = tcx.hir().expect_item(id);
 
-        // If the crate is fully unused, we suggest removing it altogether.
-        // We do this in any edition.
-        if extern_crate.warn_if_unused {
-            if let Some(&span) = unused_extern_crates.get(&extern_crate.def_id) {
+        match extern_crate_redundancy(tcx, &unused_extern_crates, extern_crate, item) {
+            // If the crate is fully unused, we suggest removing it altogether.
+            // We do this in any edition.
+            Some(Redundancy::Unused(span)) => {
                 let msg = "unused extern crate";
 
                 // Removal suggestion span needs to include attributes (Issue #54400)
@@ -145,57 +146,123 @@ fn unused_crates_lint<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) {
                     .map(|attr| attr.span)
                     .fold(span, |acc, attr_span| acc.to(attr_span));
 
-                tcx.struct_span_lint_node(lint, id, span, msg)
+                let mut err = tcx.struct_span_lint_node(lint, id, span, msg);
+                err.span_suggestion_short_with_applicability(
+                    span_with_attrs,
+                    "remove it",
+                    String::new(),
+                    Applicability::MachineApplicable);
+
+                // In Rust 2018 a crate passed with `--extern` stays reachable
+                // under its own name even once the item is gone, so say so.
+                if tcx.sess.rust_2018() && extern_crate.orig_name.is_none() &&
+                   tcx.extern_prelude.get(&item.name) == Some(&false) {
+                    err.note(&format!("the crate `{}` is already accessible through \
+                                       the extern prelude", item.name));
+                }
+                err.emit();
+                removable.push((span, item.name));
+            }
+
+            // Otherwise, we can convert it into a `use` of some kind.
+            Some(Redundancy::CoveredByExternPrelude) => {
+                let msg = "`extern crate` is not idiomatic in the new edition";
+                let help = format!(
+                    "convert it to a `{}`",
+                    visibility_qualified(&item.vis, "use")
+                );
+                let base_replacement = match extern_crate.orig_name {
+                    Some(orig_name) => format!("use {} as {};", orig_name, item.name),
+                    None => format!("use {};", item.name),
+                };
+                let replacement = visibility_qualified(&item.vis, base_replacement);
+                tcx.struct_span_lint_node(lint, id, extern_crate.span, msg)
                     .span_suggestion_short_with_applicability(
-                        span_with_attrs,
-                        "remove it",
-                        String::new(),
-                        Applicability::MachineApplicable)
+                        extern_crate.span,
+                        &help,
+                        replacement,
+                        Applicability::MachineApplicable,
+                    )
                     .emit();
-                continue;
             }
-        }
 
-        // If we are not in Rust 2018 edition, then we don't make any further
-        // suggestions.
-        if !tcx.sess.rust_2018() {
-            continue;
+            None => {}
         }
+    }
 
-        // If the extern crate isn't in the extern prelude,
-        // there is no way it can be written as an `use`.
-        let orig_name = extern_crate.orig_name.unwrap_or(item.name);
-        if !tcx.extern_prelude.get(&orig_name).map_or(false, |from_item| !from_item) {
-            continue;
-        }
+    if tcx.sess.opts.debugging_opts.edition_migration_report {
+        report_removable_extern_crates(tcx, &removable);
+    }
+}
+
+/// Why an `extern crate` item can go away.
+enum Redundancy {
+    /// Nothing refers to the item, so it can be deleted in any edition. The
+    /// span is the one recorded by resolve for the unused item.
+    Unused(Span),
 
-        // If the extern crate has any attributes, they may have funky
-        // semantics we can't faithfully represent using `use` (most
-        // notably `#[macro_use]`). Ignore it.
-        if !tcx.get_attrs(extern_crate.def_id).is_empty() {
-            continue;
+    /// In Rust 2018, the item only brings a crate from the extern prelude
+    /// into scope, which a `use` item does just as well.
+    CoveredByExternPrelude,
+}
+
+fn extern_crate_redundancy<'tcx>(
+    tcx: TyCtxt<'_, 'tcx, 'tcx>,
+    unused_extern_crates: &FxHashMap<DefId, Span>,
+    extern_crate: &ExternCrateToLint,
+    item: &hir::Item,
+) -> Option<Redundancy> {
+    if extern_crate.warn_if_unused {
+        if let Some(&span) = unused_extern_crates.get(&extern_crate.def_id) {
+            return Some(Redundancy::Unused(span));
         }
+    }
 
-        // Otherwise, we can convert it into a `use` of some kind.
-        let msg = "`extern crate` is not idiomatic in the new edition";
-        let help = format!(
-            "convert it to a `{}`",
-            visibility_qualified(&item.vis, "use")
-        );
-        let base_replacement = match extern_crate.orig_name {
-            Some(orig_name) => format!("use {} as {};", orig_name, item.name),
-            None => format!("use {};", item.name),
-        };
-        let replacement = visibility_qualified(&item.vis, base_replacement);
-        tcx.struct_span_lint_node(lint, id, extern_crate.span, msg)
-            .span_suggestion_short_with_applicability(
-                extern_crate.span,
-                &help,
-                replacement,
-                Applicability::MachineApplicable,
-            )
-            .emit();
+    // If we are not in Rust 2018 edition, then we don't make any further
+    // suggestions.
+    if !tcx.sess.rust_2018() {
+        return None;
+    }
+
+    // If the extern crate isn't in the extern prelude,
+    // there is no way it can be written as an `use`.
+    let orig_name = extern_crate.orig_name.unwrap_or(item.name);
+    if !tcx.extern_prelude.get(&orig_name).map_or(false, |from_item| !from_item) {
+        return None;
+    }
+
+    // If the extern crate has any attributes other than docs and lint
+    // levels, which carry over to the `use` unchanged, they may have funky
+    // semantics we can't faithfully represent using `use` (most notably
+    // `#[macro_use]`). Ignore it.
+    let has_special_attrs = tcx.get_attrs(extern_crate.def_id).iter().any(|attr| {
+        !attr.check_name("doc") && lint::Level::from_str(&attr.name().as_str()).is_none()
+    });
+    if has_special_attrs {
+        return None;
+    }
+
+    Some(Redundancy::CoveredByExternPrelude)
+}
+
+/// Lists the `extern crate` items that `cargo fix` may delete, for
+/// `-Z edition-migration-report`.
+fn report_removable_extern_crates(tcx: TyCtxt<'_, '_, '_>, removable: &[(Span, ast::Name)]) {
+    if removable.is_empty() {
+        tcx.sess.note_without_error("no `extern crate` item can be removed");
+        return;
+    }
+
+    let mut spans = MultiSpan::from_spans(removable.iter().map(|&(span, _)| span).collect());
+    for &(span, name) in removable {
+        spans.push_span_label(span, format!("`{}` is never used", name));
     }
+    let msg = if removable.len() == 1 {
+        "`cargo fix` may remove this `extern crate` item".to_string()
+    } else {
+        format!("`cargo fix` may remove these {} `extern crate` items", removable.len())
+    };
+    tcx.sess.span_note_without_error(spans, &msg);
 }
 
 struct CollectExternCrateVisitor<'a, 'tcx: 'a> {
@@ -228,7 +295,8 @@ impl<'a, 'tcx, 'v> ItemLikeVisitor<'v> for CollectExternCrateVisitor<'a, 'tcx> {
                 ExternCrateToLint {
                     def_id: extern_crate_def_id,
                     span: item.span,
-                    orig_name,
+                    // `extern crate foo as foo;` does not actually rename anything.
+                    orig_name: orig_name.filter(|&orig_name| orig_name != item.name),
                     warn_if_unused: !item.name.as_str().starts_with('_'),
                 }
             );
@@ -1353,6 +1353,10 @@ fn find_existential_constraints<'a, 'tcx>(
                             span,
                             "defining existential type use differs from previous",
                         );
+                        err.span_label(
+                            span,
+                            format!("expected `{}`, got `{}`", prev_ty, ty),
+                        );
                         err.span_note(prev_span, "previous use here");
                         err.emit();
                     }
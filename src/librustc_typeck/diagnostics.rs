@@ -4828,7 +4828,7 @@ would be ambiguous which override should actually be used.
 
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
 //  E0035, merged into E0087/E0089
 //  E0036, merged into E0087/E0089
 //  E0068,
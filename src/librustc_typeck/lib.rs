@@ -94,6 +94,7 @@ extern crate arena;
 extern crate rustc_platform_intrinsics as intrinsics;
 extern crate rustc_data_structures;
 extern crate rustc_errors as errors;
+#[macro_use] extern crate rustc_macros;
 extern crate rustc_target;
 extern crate smallvec;
 
@@ -111,6 +112,7 @@ mod structured_errors;
 mod impl_wf_check;
 mod namespace;
 mod outlives;
+mod session_diagnostics;
 mod variance;
 
 use hir::Node;
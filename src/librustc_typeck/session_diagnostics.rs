@@ -0,0 +1,136 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Errors emitted by the type checker, declared with `#[derive(SessionDiagnostic)]`.
+
+use rustc::ty::Ty;
+use syntax_pos::Span;
+
+#[derive(SessionDiagnostic)]
+#[code = "E0069"]
+#[slug = "typeck-return-without-value"]
+#[message = "`return;` in a function whose return type is not `()`"]
+pub struct ReturnWithoutValue {
+    #[primary_span]
+    #[label = "return type is not ()"]
+    pub span: Span,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0092"]
+#[slug = "typeck-unrecognized-atomic-operation"]
+#[message = "unrecognized atomic operation function: `{op}`"]
+pub struct UnrecognizedAtomicOperation<'a> {
+    #[primary_span]
+    #[label = "unrecognized atomic operation"]
+    pub span: Span,
+    pub op: &'a str,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0093"]
+#[slug = "typeck-unrecognized-intrinsic-function"]
+#[message = "unrecognized intrinsic function: `{name}`"]
+pub struct UnrecognizedIntrinsicFunction<'a> {
+    #[primary_span]
+    #[label = "unrecognized intrinsic"]
+    pub span: Span,
+    pub name: &'a str,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0094"]
+#[slug = "typeck-wrong-number-of-type-arguments-to-intrinsic"]
+#[message = "intrinsic has wrong number of type parameters: found {found}, expected {expected}"]
+pub struct WrongNumberOfTypeArgumentsToIntrinsic {
+    #[primary_span]
+    #[label = "expected {expected} type parameter"]
+    pub span: Span,
+    pub found: usize,
+    pub expected: usize,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0439"]
+#[slug = "typeck-invalid-simd-shuffle"]
+#[message = "invalid `simd_shuffle`, needs length: `{name}`"]
+pub struct InvalidSimdShuffle<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub name: &'a str,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0440"]
+#[slug = "typeck-wrong-number-of-type-arguments-to-platform-intrinsic"]
+#[message = "platform-specific intrinsic has wrong number of type parameters: \
+             found {found}, expected 0"]
+pub struct WrongNumberOfTypeArgumentsToPlatformIntrinsic {
+    #[primary_span]
+    pub span: Span,
+    pub found: usize,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0441"]
+#[slug = "typeck-unrecognized-platform-intrinsic-function"]
+#[message = "unrecognized platform-specific intrinsic function: `{name}`"]
+pub struct UnrecognizedPlatformIntrinsicFunction<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub name: &'a str,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0442"]
+#[slug = "typeck-intrinsic-wrong-type"]
+#[message = "intrinsic {position} has wrong type: found {found}, expected {expected}"]
+pub struct IntrinsicWrongType<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub position: &'a str,
+    pub found: &'a str,
+    pub expected: &'a str,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0443"]
+#[slug = "typeck-intrinsic-inconsistent-vector-type"]
+#[message = "intrinsic {position} has wrong type: found `{found}`, expected `{previous}` which \
+             was used for this vector type previously in this signature"]
+pub struct IntrinsicInconsistentVectorType<'a, 'tcx> {
+    #[primary_span]
+    pub span: Span,
+    pub position: &'a str,
+    pub found: Ty<'tcx>,
+    pub previous: Ty<'tcx>,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0444"]
+#[slug = "typeck-wrong-number-of-arguments-to-platform-intrinsic"]
+#[message = "platform-specific intrinsic has invalid number of arguments: \
+             found {found}, expected {expected}"]
+pub struct WrongNumberOfArgumentsToPlatformIntrinsic {
+    #[primary_span]
+    pub span: Span,
+    pub found: usize,
+    pub expected: usize,
+}
+
+#[derive(SessionDiagnostic)]
+#[code = "E0622"]
+#[slug = "typeck-intrinsic-must-be-function"]
+#[message = "intrinsic must be a function"]
+pub struct IntrinsicMustBeFunction {
+    #[primary_span]
+    #[label = "expected a function"]
+    pub span: Span,
+}
@@ -35,6 +35,7 @@ use syntax::symbol::keywords;
 use syntax_pos::DUMMY_SP;
 use errors;
 use errors::emitter::{Emitter, EmitterWriter};
+use errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
 use parking_lot::ReentrantMutex;
 
 use std::cell::RefCell;
@@ -305,6 +306,13 @@ pub fn new_handler(error_format: ErrorOutputType,
                 true,
                 false)
         ),
+        ErrorOutputType::HumanAnnotateRs(color_config) => Box::new(
+            AnnotateSnippetEmitterWriter::stderr(
+                color_config,
+                source_map.map(|cm| cm as _),
+                false,
+            ).ui_testing(ui_testing)
+        ),
     };
 
     errors::Handler::with_emitter_and_flags(
@@ -483,6 +491,11 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
             access_levels: Lrc::new(AccessLevels::default()),
             name: name.to_string(),
             glob_map: if resolver.make_glob_map { Some(resolver.glob_map.clone()) } else { None },
+            macro_def_map: if resolver.make_glob_map {
+                Some(resolver.macro_defs.clone())
+            } else {
+                None
+            },
         };
 
         let arenas = AllArenas::new();
@@ -66,7 +66,7 @@ pub fn is_known(attr: &Attribute) -> bool {
 }
 
 pub fn is_known_lint_tool(m_item: Ident) -> bool {
-    ["clippy"].contains(&m_item.as_str().as_ref())
+    ["clippy", "rustdoc"].contains(&m_item.as_str().as_ref())
 }
 
 impl NestedMetaItem {
@@ -390,7 +390,7 @@ Erroneous code example:
 
 }
 
-register_diagnostics! {
+register_pending_diagnostics! {
     E0539, // incorrect meta item
     E0540, // multiple rustc_deprecated attributes
     E0542, // missing 'since'
@@ -187,6 +187,18 @@ macro_rules! register_diagnostics {
     )
 }
 
+/// Registers error codes that do not have an extended explanation yet. Only
+/// these may be emitted without one.
+#[macro_export]
+macro_rules! register_pending_diagnostics {
+    ($($code:tt),*) => (
+        $(register_diagnostic! { $code, pending })*
+    );
+    ($($code:tt),*,) => (
+        $(register_diagnostic! { $code, pending })*
+    )
+}
+
 #[macro_export]
 macro_rules! register_long_diagnostics {
     ($($code:tt: $description:tt),*) => (
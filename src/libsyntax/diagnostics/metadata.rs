@@ -84,7 +84,7 @@ pub fn output_metadata(ecx: &ExtCtxt, prefix: &str, name: &str, err_map: &ErrorM
     let mut metadata_file = File::create(&metadata_path)?;
 
     // Construct a serializable map.
-    let json_map = err_map.iter().map(|(k, &ErrorInfo { description, use_site })| {
+    let json_map = err_map.iter().map(|(k, &ErrorInfo { description, use_site, .. })| {
         let key = k.as_str().to_string();
         let value = ErrorMetadata {
             description: description.map(|n| n.as_str().to_string()),
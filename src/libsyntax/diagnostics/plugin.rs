@@ -32,7 +32,10 @@ const MAX_DESCRIPTION_WIDTH: usize = 80;
 /// Error information type.
 pub struct ErrorInfo {
     pub description: Option<Name>,
-    pub use_site: Option<Span>
+    pub use_site: Option<Span>,
+    /// Whether the code was registered with `register_pending_diagnostics!`,
+    /// as one that is still waiting for an extended explanation.
+    pub pending: bool,
 }
 
 /// Mapping from error codes to metadata.
@@ -50,14 +53,21 @@ pub fn expand_diagnostic_used<'cx>(ecx: &'cx mut ExtCtxt,
     ecx.parse_sess.registered_diagnostics.with_lock(|diagnostics| {
         match diagnostics.get_mut(&code.name) {
             // Previously used errors.
-            Some(&mut ErrorInfo { description: _, use_site: Some(previous_span) }) => {
+            Some(&mut ErrorInfo { use_site: Some(previous_span), .. }) => {
                 ecx.struct_span_warn(span, &format!(
                     "diagnostic code {} already used", code
                 )).span_note(previous_span, "previous invocation")
                   .emit();
             }
-            // Newly used errors.
+            // Newly used errors. Every error the compiler can emit needs an
+            // extended explanation, unless it is explicitly listed as pending
+            // one.
             Some(ref mut info) => {
+                if info.description.is_none() && !info.pending {
+                    ecx.span_err(span, &format!(
+                        "used diagnostic code {} has no extended explanation", code
+                    ));
+                }
                 info.use_site = Some(span);
             }
             // Unregistered errors.
@@ -75,19 +85,25 @@ pub fn expand_register_diagnostic<'cx>(ecx: &'cx mut ExtCtxt,
                                        span: Span,
                                        token_tree: &[TokenTree])
                                        -> Box<dyn MacResult+'cx> {
-    let (code, description) = match (
+    let (code, description, pending) = match (
         token_tree.len(),
         token_tree.get(0),
         token_tree.get(1),
         token_tree.get(2)
     ) {
         (1, Some(&TokenTree::Token(_, token::Ident(ref code, _))), None, None) => {
-            (code, None)
+            (code, None, false)
         },
+        (3, Some(&TokenTree::Token(_, token::Ident(ref code, _))),
+            Some(&TokenTree::Token(_, token::Comma)),
+            Some(&TokenTree::Token(_, token::Ident(ref marker, _))))
+            if marker.name == "pending" => {
+            (code, None, true)
+        }
         (3, Some(&TokenTree::Token(_, token::Ident(ref code, _))),
             Some(&TokenTree::Token(_, token::Comma)),
             Some(&TokenTree::Token(_, token::Literal(token::StrRaw(description, _), None)))) => {
-            (code, Some(description))
+            (code, Some(description), false)
         }
         _ => unreachable!()
     };
@@ -114,12 +130,24 @@ pub fn expand_register_diagnostic<'cx>(ecx: &'cx mut ExtCtxt,
                 code, MAX_DESCRIPTION_WIDTH
             ));
         }
+
+        // The release an error code was introduced in is read off a closing
+        // `Introduced in Rust X.Y.Z.` line by `Registry::find_explanation`.
+        let is_since = |l: &str| l.starts_with("Introduced in Rust ");
+        let last_line = msg.lines().rev().find(|line| !line.trim().is_empty());
+        if msg.lines().any(|line| is_since(line) && Some(line) != last_line) {
+            ecx.span_err(span, &format!(
+                "description for error code {} must end with its `Introduced in Rust` line",
+                code
+            ));
+        }
     });
     // Add the error to the map.
     ecx.parse_sess.registered_diagnostics.with_lock(|diagnostics| {
         let info = ErrorInfo {
             description,
-            use_site: None
+            use_site: None,
+            pending,
         };
         if diagnostics.insert(code.name, info).is_some() {
             ecx.span_err(span, &format!(
@@ -21,6 +21,12 @@ use syntax_pos::MultiSpan;
 pub enum BufferedEarlyLintId {
     /// Usage of `?` as a macro separator is deprecated.
     QuestionMarkMacroSep,
+    /// An attribute that has no effect where it is placed, e.g. `#[derive]` on a macro call.
+    UnusedAttributes,
+    /// Parentheses around a macro argument that are not needed, e.g. `assert!((x))`.
+    UnusedParens,
+    /// A builtin attribute with input it doesn't take, e.g. `#[cold = "yes"]`.
+    IllFormedAttributeInput,
 }
 
 /// Stores buffered lint info which can later be passed to `librustc`.
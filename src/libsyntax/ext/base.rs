@@ -14,6 +14,7 @@ use ast::{self, Attribute, Name, PatKind, MetaItem};
 use attr::HasAttrs;
 use source_map::{self, SourceMap, Spanned, respan};
 use syntax_pos::{Span, MultiSpan, DUMMY_SP};
+use early_buffered_lints::BufferedEarlyLintId;
 use edition::Edition;
 use errors::{DiagnosticBuilder, DiagnosticId};
 use ext::expand::{self, AstFragment, Invocation};
@@ -798,6 +799,9 @@ pub struct ExpansionData {
     pub module: Rc<ModuleData>,
     pub directory_ownership: DirectoryOwnership,
     pub crate_span: Option<Span>,
+    /// The innermost node being expanded that lints buffered during expansion
+    /// are attached to, so that they respect the lint attributes around it.
+    pub lint_node_id: ast::NodeId,
 }
 
 /// One of these is made during expansion and incrementally updated as we go;
@@ -830,6 +834,7 @@ impl<'a> ExtCtxt<'a> {
                 module: Rc::new(ModuleData { mod_path: Vec::new(), directory: PathBuf::new() }),
                 directory_ownership: DirectoryOwnership::Owned { relative: None },
                 crate_span: None,
+                lint_node_id: ast::CRATE_NODE_ID,
             },
             expansions: FxHashMap::default(),
         }
@@ -937,6 +942,14 @@ impl<'a> ExtCtxt<'a> {
     pub fn span_warn<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
         self.parse_sess.span_diagnostic.span_warn(sp, msg);
     }
+    /// Buffers a lint for the early lint pass, where it is emitted according
+    /// to the lint levels in effect at `current_expansion.lint_node_id`.
+    pub fn buffer_lint<S: Into<MultiSpan>>(&self,
+                                           lint_id: BufferedEarlyLintId,
+                                           sp: S,
+                                           msg: &str) {
+        self.parse_sess.buffer_lint(lint_id, sp, self.current_expansion.lint_node_id, msg);
+    }
     pub fn span_unimpl<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> ! {
         self.parse_sess.span_diagnostic.span_unimpl(sp, msg);
     }
@@ -1028,6 +1041,19 @@ pub fn check_zero_tts(cx: &ExtCtxt,
     }
 }
 
+/// Buffers an `unused_parens` lint if `expr`, an argument of the macro being
+/// expanded, is wrapped in parentheses it doesn't need. Parentheses that come
+/// from another macro are left alone.
+pub fn check_unused_parens(cx: &ExtCtxt, expr: &ast::Expr, msg: &str) {
+    if let ast::ExprKind::Paren(_) = expr.node {
+        if expr.span.ctxt() == SyntaxContext::empty() {
+            cx.buffer_lint(BufferedEarlyLintId::UnusedParens,
+                           expr.span,
+                           &format!("unnecessary parentheses around {}", msg));
+        }
+    }
+}
+
 /// Interpreting `tts` as a comma-separated sequence of expressions,
 /// expect exactly one string literal, or emit an error and return None.
 pub fn get_single_str_from_tts(cx: &mut ExtCtxt,
@@ -10,6 +10,7 @@
 
 use attr::HasAttrs;
 use ast;
+use early_buffered_lints::BufferedEarlyLintId;
 use source_map::{hygiene, ExpnInfo, ExpnFormat};
 use ext::base::ExtCtxt;
 use ext::build::AstBuilder;
@@ -29,7 +30,9 @@ pub fn collect_derives(cx: &mut ExtCtxt, attrs: &mut Vec<ast::Attribute>) -> Vec
         match attr.parse_list(cx.parse_sess,
                               |parser| parser.parse_path_allowing_meta(PathStyle::Mod)) {
             Ok(ref traits) if traits.is_empty() => {
-                cx.span_warn(attr.span, "empty trait list in `derive`");
+                cx.buffer_lint(BufferedEarlyLintId::UnusedAttributes,
+                               attr.span,
+                               "empty trait list in `derive`");
                 false
             }
             Ok(traits) => {
@@ -10,6 +10,7 @@
 
 use ast::{self, Block, Ident, NodeId, PatKind, Path};
 use ast::{MacStmtStyle, StmtKind, ItemKind};
+use early_buffered_lints::BufferedEarlyLintId;
 use attr::{self, HasAttrs};
 use source_map::{ExpnInfo, MacroBang, MacroAttribute, dummy_spanned, respan};
 use config::StripUnconfigured;
@@ -1069,6 +1070,13 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// What `InvocationCollector::exit_lint_node` needs to restore and update.
+struct LintNodeScope {
+    old_id: ast::NodeId,
+    lints: usize,
+    invocations: usize,
+}
+
 struct InvocationCollector<'a, 'b: 'a> {
     cx: &'a mut ExtCtxt<'b>,
     cfg: StripUnconfigured<'a>,
@@ -1168,11 +1176,11 @@ impl<'a, 'b> InvocationCollector<'a, 'b> {
         for attr in attrs.iter() {
             self.check_attribute_inner(attr, features);
 
-            // macros are expanded before any lint passes so this warning has to be hardcoded
+            // macros are expanded before any lint passes so this lint has to be buffered
             if attr.path == "derive" {
-                self.cx.struct_span_warn(attr.span, "`#[derive]` does nothing on macro invocations")
-                    .note("this may become a hard error in a future release")
-                    .emit();
+                self.cx.buffer_lint(BufferedEarlyLintId::UnusedAttributes,
+                                    attr.span,
+                                    "`#[derive]` does nothing on macro invocations");
             }
         }
     }
@@ -1185,6 +1193,54 @@ impl<'a, 'b> InvocationCollector<'a, 'b> {
     fn check_attribute_inner(&mut self, at: &ast::Attribute, features: &Features) {
         feature_gate::check_attribute(at, self.cx.parse_sess, features);
     }
+
+    /// Starts folding an item-like node with the given attributes. Its id is only
+    /// assigned while it is folded, so lints buffered in the meantime, and the
+    /// invocations collected from it, are attached to `DUMMY_NODE_ID` until
+    /// `exit_lint_node` replaces it with the real id.
+    fn enter_lint_node(&mut self, attrs: &[ast::Attribute]) -> LintNodeScope {
+        let scope = LintNodeScope {
+            old_id: self.cx.current_expansion.lint_node_id,
+            lints: self.cx.parse_sess.buffered_lints.borrow().len(),
+            invocations: self.invocations.len(),
+        };
+        self.cx.current_expansion.lint_node_id = ast::DUMMY_NODE_ID;
+        for attr in attrs {
+            self.check_attribute_input(attr);
+        }
+        scope
+    }
+
+    fn exit_lint_node(&mut self, scope: LintNodeScope, id: ast::NodeId) {
+        let id = if id == ast::DUMMY_NODE_ID { scope.old_id } else { id };
+        let mut lints = self.cx.parse_sess.buffered_lints.borrow_mut();
+        for lint in lints.iter_mut().skip(scope.lints) {
+            if lint.id == ast::DUMMY_NODE_ID {
+                lint.id = id;
+            }
+        }
+        for invoc in self.invocations.iter_mut().skip(scope.invocations) {
+            if invoc.expansion_data.lint_node_id == ast::DUMMY_NODE_ID {
+                invoc.expansion_data.lint_node_id = id;
+            }
+        }
+        self.cx.current_expansion.lint_node_id = scope.old_id;
+    }
+
+    // Builtin attributes are checked after expansion, but lints can only be
+    // buffered during it.
+    fn check_attribute_input(&mut self, attr: &ast::Attribute) {
+        let name = match feature_gate::BUILTIN_ATTRIBUTES.iter()
+                .find(|&&(name, ..)| attr.path == name) {
+            Some(&(name, _, template, _)) if template.is_word_only() => name,
+            _ => return,
+        };
+        if !attr.tokens.is_empty() {
+            self.cx.buffer_lint(BufferedEarlyLintId::IllFormedAttributeInput,
+                                attr.span,
+                                &format!("attribute must be of the form `#[{}]`", name));
+        }
+    }
 }
 
 impl<'a, 'b> Folder for InvocationCollector<'a, 'b> {
@@ -1408,7 +1464,12 @@ impl<'a, 'b> Folder for InvocationCollector<'a, 'b> {
                 self.check_attributes(&attrs);
                 self.collect_bang(mac, span, AstFragmentKind::TraitItems).make_trait_items()
             }
-            _ => fold::noop_fold_trait_item(item, self),
+            _ => {
+                let scope = self.enter_lint_node(&item.attrs);
+                let items = fold::noop_fold_trait_item(item, self);
+                self.exit_lint_node(scope, items[0].id);
+                items
+            }
         }
     }
 
@@ -1427,7 +1488,12 @@ impl<'a, 'b> Folder for InvocationCollector<'a, 'b> {
                 self.check_attributes(&attrs);
                 self.collect_bang(mac, span, AstFragmentKind::ImplItems).make_impl_items()
             }
-            _ => fold::noop_fold_impl_item(item, self),
+            _ => {
+                let scope = self.enter_lint_node(&item.attrs);
+                let items = fold::noop_fold_impl_item(item, self);
+                self.exit_lint_node(scope, items[0].id);
+                items
+            }
         }
     }
 
@@ -1467,6 +1533,28 @@ impl<'a, 'b> Folder for InvocationCollector<'a, 'b> {
         noop_fold_foreign_item(foreign_item, self)
     }
 
+    fn fold_item_simple(&mut self, item: ast::Item) -> ast::Item {
+        // The crate root is folded as a module without a name and the id it gets
+        // here is thrown away, so lints inside it stay attached to `CRATE_NODE_ID`.
+        match item.node {
+            ast::ItemKind::Mod(..) if item.ident == keywords::Invalid.ident() => {
+                return noop_fold_item_simple(item, self);
+            }
+            _ => {}
+        }
+        let scope = self.enter_lint_node(&item.attrs);
+        let item = noop_fold_item_simple(item, self);
+        self.exit_lint_node(scope, item.id);
+        item
+    }
+
+    fn fold_foreign_item_simple(&mut self, item: ast::ForeignItem) -> ast::ForeignItem {
+        let scope = self.enter_lint_node(&item.attrs);
+        let item = noop_fold_foreign_item_simple(item, self);
+        self.exit_lint_node(scope, item.id);
+        item
+    }
+
     fn fold_item_kind(&mut self, item: ast::ItemKind) -> ast::ItemKind {
         match item {
             ast::ItemKind::MacroDef(..) => item,
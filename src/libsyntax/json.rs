@@ -22,12 +22,13 @@
 use source_map::{SourceMap, FilePathMapping};
 use syntax_pos::{self, MacroBacktrace, Span, SpanLabel, MultiSpan};
 use errors::registry::Registry;
-use errors::{DiagnosticBuilder, SubDiagnostic, CodeSuggestion, SourceMapper};
-use errors::{DiagnosticId, Applicability};
+use errors::{DiagnosticBuilder, SubDiagnostic, Substitution, SourceMapper};
+use errors::{DiagnosticId, Applicability, FutureIncompatLint};
 use errors::emitter::{Emitter, EmitterWriter};
 
 use rustc_data_structures::sync::{self, Lrc};
 use std::io::{self, Write};
+use std::collections::BTreeMap;
 use std::vec;
 use std::sync::{Arc, Mutex};
 
@@ -39,6 +40,7 @@ pub struct JsonEmitter {
     sm: Lrc<dyn SourceMapper + sync::Send + sync::Sync>,
     pretty: bool,
     ui_testing: bool,
+    rendered_ansi: bool,
 }
 
 impl JsonEmitter {
@@ -51,6 +53,7 @@ impl JsonEmitter {
             sm: source_map,
             pretty,
             ui_testing: false,
+            rendered_ansi: false,
         }
     }
 
@@ -70,12 +73,19 @@ impl JsonEmitter {
             sm: source_map,
             pretty,
             ui_testing: false,
+            rendered_ansi: false,
         }
     }
 
     pub fn ui_testing(self, ui_testing: bool) -> Self {
         Self { ui_testing, ..self }
     }
+
+    /// Keep the ANSI color codes of the human readable output in the
+    /// `rendered` field of each diagnostic.
+    pub fn rendered_ansi(self, rendered_ansi: bool) -> Self {
+        Self { rendered_ansi, ..self }
+    }
 }
 
 impl Emitter for JsonEmitter {
@@ -90,6 +100,26 @@ impl Emitter for JsonEmitter {
             panic!("failed to print diagnostics: {:?}", e);
         }
     }
+
+    fn emit_future_incompat_report(&mut self, lints: &[FutureIncompatLint]) {
+        let data = FutureIncompatReport {
+            future_incompat_report: lints.iter().map(|lint| {
+                FutureIncompatReportLint {
+                    lint: lint.lint.clone(),
+                    spans: DiagnosticSpan::from_multispan(&lint.span, self),
+                    reference: lint.reference.clone(),
+                }
+            }).collect(),
+        };
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", as_pretty_json(&data))
+        } else {
+            writeln!(&mut self.dst, "{}", as_json(&data))
+        };
+        if let Err(e) = result {
+            panic!("failed to print future incompatibility report: {:?}", e);
+        }
+    }
 }
 
 // The following data types are provided just for serialisation.
@@ -102,10 +132,15 @@ struct Diagnostic {
     /// "error: internal compiler error", "error", "warning", "note", "help".
     level: &'static str,
     spans: Vec<DiagnosticSpan>,
-    /// Associated diagnostic messages.
+    /// Associated diagnostic messages, in the order they were attached,
+    /// followed by one child for each suggested substitution.
     children: Vec<Diagnostic>,
-    /// The message as rustc would render it.
+    /// The message as rustc would render it, including the color codes when
+    /// `--json=diagnostic-rendered-ansi` is passed. Only set on the
+    /// top-level diagnostic.
     rendered: Option<String>,
+    /// Machine-readable details about the diagnostic, if it has any.
+    metadata: Option<BTreeMap<String, String>>,
 }
 
 #[derive(RustcEncodable)]
@@ -160,6 +195,23 @@ struct DiagnosticSpanMacroExpansion {
     def_site_span: Option<DiagnosticSpan>,
 }
 
+/// The future-incompatible lints that fired during the compilation, emitted
+/// once at its end so that tools like cargo can warn about dependencies that
+/// are going to break even when their lints aren't shown.
+#[derive(RustcEncodable)]
+struct FutureIncompatReport {
+    future_incompat_report: Vec<FutureIncompatReportLint>,
+}
+
+#[derive(RustcEncodable)]
+struct FutureIncompatReportLint {
+    /// The name of the lint, like `safe_packed_borrows`.
+    lint: String,
+    spans: Vec<DiagnosticSpan>,
+    /// Where to read about the change, usually its tracking issue.
+    reference: String,
+}
+
 #[derive(RustcEncodable)]
 struct DiagnosticCode {
     /// The code itself.
@@ -172,15 +224,23 @@ impl Diagnostic {
     fn from_diagnostic_builder(db: &DiagnosticBuilder,
                                je: &JsonEmitter)
                                -> Diagnostic {
-        let sugg = db.suggestions.iter().map(|sugg| {
-            Diagnostic {
-                message: sugg.msg.clone(),
-                code: None,
-                level: "help",
-                spans: DiagnosticSpan::from_suggestion(sugg, je),
-                children: vec![],
-                rendered: None,
-            }
+        // Each substitution of a suggestion is an alternative to the others,
+        // so each one gets its own child. The spans of a child are the parts
+        // of that substitution, which must all be applied together.
+        let sugg = db.suggestions.iter().flat_map(|sugg| {
+            sugg.substitutions.iter().map(move |substitution| {
+                Diagnostic {
+                    message: sugg.msg.clone(),
+                    code: None,
+                    level: "help",
+                    spans: DiagnosticSpan::from_substitution(substitution,
+                                                             sugg.applicability,
+                                                             je),
+                    children: vec![],
+                    rendered: None,
+                    metadata: None,
+                }
+            })
         });
 
         // generate regular command line output and store it in the json
@@ -199,8 +259,12 @@ impl Diagnostic {
         }
         let buf = BufWriter::default();
         let output = buf.clone();
-        EmitterWriter::new(Box::new(buf), Some(je.sm.clone()), false, false)
-            .ui_testing(je.ui_testing).emit(db);
+        let emitter = if je.rendered_ansi {
+            EmitterWriter::new_ansi(Box::new(buf), Some(je.sm.clone()), false, false)
+        } else {
+            EmitterWriter::new(Box::new(buf), Some(je.sm.clone()), false, false)
+        };
+        emitter.ui_testing(je.ui_testing).emit(db);
         let output = Arc::try_unwrap(output.0).unwrap().into_inner().unwrap();
         let output = String::from_utf8(output).unwrap();
 
@@ -213,6 +277,11 @@ impl Diagnostic {
                 Diagnostic::from_sub_diagnostic(c, je)
             }).chain(sugg).collect(),
             rendered: Some(output),
+            metadata: if db.metadata.is_empty() {
+                None
+            } else {
+                Some(db.metadata.iter().cloned().collect())
+            },
         }
     }
 
@@ -226,6 +295,7 @@ impl Diagnostic {
                      .unwrap_or_else(|| DiagnosticSpan::from_multispan(&db.span, je)),
             children: vec![],
             rendered: None,
+            metadata: None,
         }
     }
 }
@@ -318,24 +388,23 @@ impl DiagnosticSpan {
            .collect()
     }
 
-    fn from_suggestion(suggestion: &CodeSuggestion, je: &JsonEmitter)
-                       -> Vec<DiagnosticSpan> {
-        suggestion.substitutions
-                      .iter()
-                      .flat_map(|substitution| {
-                          substitution.parts.iter().map(move |suggestion_inner| {
-                              let span_label = SpanLabel {
-                                  span: suggestion_inner.span,
-                                  is_primary: true,
-                                  label: None,
-                              };
-                              DiagnosticSpan::from_span_label(span_label,
-                                                              Some((&suggestion_inner.snippet,
-                                                                   suggestion.applicability)),
-                                                              je)
-                          })
-                      })
-                      .collect()
+    fn from_substitution(substitution: &Substitution,
+                         applicability: Applicability,
+                         je: &JsonEmitter)
+                         -> Vec<DiagnosticSpan> {
+        substitution.parts
+                    .iter()
+                    .map(|part| {
+                        let span_label = SpanLabel {
+                            span: part.span,
+                            is_primary: true,
+                            label: None,
+                        };
+                        DiagnosticSpan::from_span_label(span_label,
+                                                        Some((&part.snippet, applicability)),
+                                                        je)
+                    })
+                    .collect()
     }
 }
 
@@ -948,6 +948,57 @@ impl SourceMap {
 
         None
     }
+
+    /// Maps a suggestion replacing the macro-expanded `sp` with `snippet` back to the outermost
+    /// macro call site, returning the call site span and the snippet to put there.
+    ///
+    /// This is only possible if the replacement does not depend on the expansion, that is, if
+    /// `sp` is everything the macro expands to (`ZERO` in `() => { ZERO }`) and `snippet` only
+    /// adds code before or after it (`*ZERO`). The same code is then added to the macro
+    /// invocation (`*zero!()`). Returns `None` if `sp` doesn't come from a macro expansion or if
+    /// the replacement depends on the expanded code.
+    pub fn suggestion_at_call_site(&self, sp: Span, snippet: &str) -> Option<(Span, String)> {
+        if sp.ctxt().outer().expn_info().is_none() {
+            return None;
+        }
+        let call_site = sp.source_callsite();
+        if !self.span_to_filename(call_site).is_real() {
+            return None;
+        }
+
+        // Check that `sp` covers the whole right hand side of a `macro_rules!` arm.
+        let prev = self.span_to_prev_source(sp).ok()?;
+        let next = self.span_to_source(sp, |src, _, end_index| src[end_index..].to_string()).ok()?;
+        let mut prev_chars = prev.trim_right().chars();
+        let close = match prev_chars.next_back()? {
+            '{' => '}',
+            '(' => ')',
+            '[' => ']',
+            _ => return None,
+        };
+        if !prev_chars.as_str().trim_right().ends_with("=>") ||
+           !next.trim_left().starts_with(close) {
+            return None;
+        }
+
+        let expanded = self.span_to_snippet(sp).ok()?;
+        let invocation = self.span_to_snippet(call_site).ok()?;
+        if expanded.is_empty() {
+            return None;
+        }
+        // The expanded code has to stay intact at one end of `snippet`, with code only added
+        // before it (`*ZERO`) or after it (`ZERO.into()`). If `snippet` both starts and ends
+        // with it, e.g. `ZERO + ZERO`, we can't tell which occurrence stands for the expansion.
+        let appends = snippet.starts_with(&expanded[..]);
+        let prepends = snippet.ends_with(&expanded[..]);
+        let start = match (prepends, appends) {
+            (true, false) => snippet.len() - expanded.len(),
+            (false, true) => 0,
+            _ => return None,
+        };
+        let end = start + expanded.len();
+        Some((call_site, format!("{}{}{}", &snippet[..start], invocation, &snippet[end..])))
+    }
 }
 
 impl SourceMapper for SourceMap {
@@ -975,6 +1026,9 @@ impl SourceMapper for SourceMap {
         }
         sp
     }
+    fn suggestion_at_call_site(&self, sp: Span, snippet: &str) -> Option<(Span, String)> {
+        self.suggestion_at_call_site(sp, snippet)
+    }
     fn ensure_source_file_source_present(&self, source_file: Lrc<SourceFile>) -> bool {
         source_file.add_external_src(
             || match source_file.name {
@@ -1110,3 +1110,156 @@ error: foo
 "#);
 }
 
+#[test]
+fn four_overlapping() {
+    test_harness(r#"
+fn foo() {
+  X0 Y0 Z0
+  X1 Y1 Z1
+  X2 Y2 Z2
+  X3 Y3 Z3
+  X4 Y4 Z4
+  X5 Y5 Z5
+}
+"#,
+    vec![
+        SpanLabel {
+            start: Position {
+                string: "X0",
+                count: 1,
+            },
+            end: Position {
+                string: "Y1",
+                count: 1,
+            },
+            label: "`A`",
+        },
+        SpanLabel {
+            start: Position {
+                string: "Z1",
+                count: 1,
+            },
+            end: Position {
+                string: "Y5",
+                count: 1,
+            },
+            label: "`B`",
+        },
+        SpanLabel {
+            start: Position {
+                string: "Y2",
+                count: 1,
+            },
+            end: Position {
+                string: "Z4",
+                count: 1,
+            },
+            label: "`C`",
+        },
+        SpanLabel {
+            start: Position {
+                string: "X3",
+                count: 1,
+            },
+            end: Position {
+                string: "X5",
+                count: 1,
+            },
+            label: "`D`",
+        },
+    ],
+    r#"
+error: foo
+ --> test.rs:3:3
+  |
+3 |  /    X0 Y0 Z0
+4 |  |    X1 Y1 Z1
+  |  |________^_-
+  | ||________|
+  | |         `A`
+5 | |     X2 Y2 Z2
+  | | _______-
+6 | ||    X3 Y3 Z3
+  | || ___-
+7 | |||   X4 Y4 Z4
+  | |||__________- `C`
+8 | | |   X5 Y5 Z5
+  | |_|____-__- `B`
+  |   |____|
+  |        `D`
+
+"#);
+}
+
+#[test]
+fn overlapping_long_snippets() {
+    test_harness(r#"
+fn foo() {
+  X0 Y0 Z0
+  X1 Y1 Z1
+  X2 Y2 Z2
+  X3 Y3 Z3
+  X4 Y4 Z4
+  X5 Y5 Z5
+  X6 Y6 Z6
+  X7 Y7 Z7
+  X8 Y8 Z8
+  X9 Y9 Z9
+}
+"#,
+    vec![
+        SpanLabel {
+            start: Position {
+                string: "X0",
+                count: 1,
+            },
+            end: Position {
+                string: "Y8",
+                count: 1,
+            },
+            label: "`X` is a good letter",
+        },
+        SpanLabel {
+            start: Position {
+                string: "Z1",
+                count: 1,
+            },
+            end: Position {
+                string: "X9",
+                count: 1,
+            },
+            label: "`Y` is a good letter too",
+        },
+        SpanLabel {
+            start: Position {
+                string: "Y2",
+                count: 1,
+            },
+            end: Position {
+                string: "Z3",
+                count: 1,
+            },
+            label: "`Z`",
+        },
+    ],
+    r#"
+error: foo
+  --> test.rs:3:3
+   |
+3  |   /   X0 Y0 Z0
+4  |   |   X1 Y1 Z1
+   |   |_________-
+5  |  ||   X2 Y2 Z2
+   |  ||______-
+6  | |||   X3 Y3 Z3
+   | |||__________- `Z`
+7  |  ||   X4 Y4 Z4
+...   ||
+10 |  ||   X7 Y7 Z7
+11 |  ||   X8 Y8 Z8
+   |  ||_______^ `X` is a good letter
+12 |  |    X9 Y9 Z9
+   |  |_____- `Y` is a good letter too
+
+"#);
+}
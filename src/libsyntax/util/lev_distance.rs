@@ -9,7 +9,7 @@
 // except according to those terms.
 
 use std::cmp;
-use symbol::Symbol;
+use symbol::{Ident, Symbol};
 
 /// Find the Levenshtein distance between two strings
 pub fn lev_distance(a: &str, b: &str) -> usize {
@@ -42,49 +42,72 @@ pub fn lev_distance(a: &str, b: &str) -> usize {
     dcol[t_last + 1]
 }
 
-/// Find the best match for a given word in the given iterator
+/// Finds the names closest to a misspelled name, for "did you mean" suggestions.
 ///
-/// As a loose rule to avoid the obviously incorrect suggestions, it takes
-/// an optional limit for the maximum allowable edit distance, which defaults
-/// to one-third of the given word.
-///
-/// Besides Levenshtein, we use case insensitive comparison to improve accuracy on an edge case with
-/// a lower(upper)case letters mismatch.
-pub fn find_best_match_for_name<'a, T>(iter_names: T,
-                                       lookup: &str,
-                                       dist: Option<usize>) -> Option<Symbol>
-    where T: Iterator<Item = &'a Symbol> {
-    let max_dist = dist.map_or_else(|| cmp::max(lookup.len(), 3) / 3, |d| d);
-
-    let (case_insensitive_match, levenstein_match) = iter_names
-    .filter_map(|&name| {
-        let dist = lev_distance(lookup, &name.as_str());
-        if dist <= max_dist {
-            Some((name, dist))
+/// A candidate is suggested if its edit distance to the lookup is within a threshold that adapts
+/// to the length of the lookup (a third of it, but at least one edit), or if it only differs
+/// from the lookup in case, however many letters that is. Case-insensitive matches rank first,
+/// then candidates by edit distance, and candidates at the same distance keep their order.
+/// Special identifiers like `_` or `{{root}}` are never suggested.
+#[derive(Clone, Copy, Debug)]
+pub struct NameMatcher<'a> {
+    lookup: &'a str,
+    max_dist: usize,
+}
+
+impl<'a> NameMatcher<'a> {
+    pub fn new(lookup: &'a str) -> NameMatcher<'a> {
+        NameMatcher {
+            lookup,
+            max_dist: cmp::max(lookup.chars().count(), 3) / 3,
+        }
+    }
+
+    /// Overrides the maximum edit distance of the suggested candidates.
+    pub fn max_distance(self, max_dist: usize) -> NameMatcher<'a> {
+        NameMatcher { max_dist, ..self }
+    }
+
+    /// Returns the edit distance between the lookup and `candidate` if it's close enough to be
+    /// suggested.
+    pub fn distance(&self, candidate: Symbol) -> Option<usize> {
+        if Ident::with_empty_ctxt(candidate).is_special() {
+            return None;
+        }
+        let candidate = candidate.as_str();
+        let dist = lev_distance(self.lookup, &candidate);
+        if dist <= self.max_dist || self.is_case_insensitive_match(&candidate) {
+            Some(dist)
         } else {
             None
         }
-    })
-    // Here we are collecting the next structure:
-    // (case_insensitive_match, (levenstein_match, levenstein_distance))
-    .fold((None, None), |result, (candidate, dist)| {
-        (
-            if candidate.as_str().to_uppercase() == lookup.to_uppercase() {
-                Some(candidate)
-            } else {
-                result.0
-            },
-            match result.1 {
-                None => Some((candidate, dist)),
-                Some((c, d)) => Some(if dist < d { (candidate, dist) } else { (c, d) })
-            }
-        )
-    });
+    }
+
+    /// Returns the candidates close enough to be suggested, the best match first.
+    pub fn ranked<'b, T>(&self, candidates: T) -> Vec<Symbol>
+        where T: Iterator<Item = &'b Symbol>
+    {
+        let mut matches = candidates
+            .filter_map(|&candidate| {
+                self.distance(candidate).map(|dist| {
+                    let case_insensitive = self.is_case_insensitive_match(&candidate.as_str());
+                    (!case_insensitive, dist, candidate)
+                })
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|&(case_sensitive, dist, _)| (case_sensitive, dist));
+        matches.into_iter().map(|(_, _, candidate)| candidate).collect()
+    }
+
+    /// Returns the best match among `candidates`, if any is close enough to be suggested.
+    pub fn best<'b, T>(&self, candidates: T) -> Option<Symbol>
+        where T: Iterator<Item = &'b Symbol>
+    {
+        self.ranked(candidates).into_iter().next()
+    }
 
-    if let Some(candidate) = case_insensitive_match {
-        Some(candidate) // exact case insensitive match has a higher priority
-    } else {
-        if let Some((candidate, _)) = levenstein_match { Some(candidate) } else { None }
+    fn is_case_insensitive_match(&self, candidate: &str) -> bool {
+        candidate.to_uppercase() == self.lookup.to_uppercase()
     }
 }
 
@@ -110,37 +133,33 @@ fn test_lev_distance() {
 }
 
 #[test]
-fn test_find_best_match_for_name() {
+fn test_name_matcher() {
     use with_globals;
     with_globals(|| {
         let input = vec![Symbol::intern("aaab"), Symbol::intern("aaabc")];
-        assert_eq!(
-            find_best_match_for_name(input.iter(), "aaaa", None),
-            Some(Symbol::intern("aaab"))
-        );
-
-        assert_eq!(
-            find_best_match_for_name(input.iter(), "1111111111", None),
-            None
-        );
+        assert_eq!(NameMatcher::new("aaaa").best(input.iter()), Some(Symbol::intern("aaab")));
+        assert_eq!(NameMatcher::new("aaaa").ranked(input.iter()), vec![Symbol::intern("aaab")]);
+        assert_eq!(NameMatcher::new("1111111111").best(input.iter()), None);
 
-        let input = vec![Symbol::intern("aAAA")];
+        let input = vec![Symbol::intern("aaab"), Symbol::intern("aAAA")];
         assert_eq!(
-            find_best_match_for_name(input.iter(), "AAAA", None),
-            Some(Symbol::intern("aAAA"))
+            NameMatcher::new("AAAA").ranked(input.iter()),
+            vec![Symbol::intern("aAAA"), Symbol::intern("aaab")]
         );
 
+        // Differences in case don't count towards the maximum distance.
         let input = vec![Symbol::intern("AAAA")];
-        // Returns None because `lev_distance > max_dist / 3`
-        assert_eq!(
-            find_best_match_for_name(input.iter(), "aaaa", None),
-            None
-        );
+        assert_eq!(NameMatcher::new("aaaa").best(input.iter()), Some(Symbol::intern("AAAA")));
 
-        let input = vec![Symbol::intern("AAAA")];
+        let input = vec![Symbol::intern("abcdef"), Symbol::intern("abcxyz")];
+        assert_eq!(NameMatcher::new("abcxxx").best(input.iter()), None);
         assert_eq!(
-            find_best_match_for_name(input.iter(), "aaaa", Some(4)),
-            Some(Symbol::intern("AAAA"))
+            NameMatcher::new("abcxxx").max_distance(3).ranked(input.iter()),
+            vec![Symbol::intern("abcxyz"), Symbol::intern("abcdef")]
         );
+
+        // Special identifiers are never suggested.
+        let input = vec![Symbol::intern("_")];
+        assert_eq!(NameMatcher::new("a").best(input.iter()), None);
     })
 }
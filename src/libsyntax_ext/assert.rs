@@ -25,6 +25,7 @@ pub fn expand_assert<'cx>(
 ) -> Box<dyn MacResult + 'cx> {
     let mut parser = cx.new_parser_from_tts(tts);
     let cond_expr = panictry!(parser.parse_expr());
+    check_unused_parens(cx, &cond_expr, "`assert!` condition");
     let custom_msg_args = if parser.eat(&token::Comma) {
         let ts = parser.parse_tokens();
         if !ts.is_empty() {
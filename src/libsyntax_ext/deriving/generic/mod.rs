@@ -475,7 +475,8 @@ impl<'a> TraitDef<'a> {
                     .iter()
                     .filter(|a| {
                         match &*a.name().as_str() {
-                            "allow" | "warn" | "deny" | "forbid" | "stable" | "unstable" => true,
+                            "allow" | "expect" | "warn" | "deny" | "forbid" | "stable" |
+                            "unstable" => true,
                             _ => false,
                         }
                     })
@@ -185,6 +185,7 @@ fn parse_args(ecx: &mut ExtCtxt,
 
             panictry!(p.expect(&token::Eq));
             let e = panictry!(p.parse_expr());
+            check_unused_parens(ecx, &e, "format argument");
             if let Some(prev) = names.get(name) {
                 ecx.struct_span_err(e.span, &format!("duplicate argument named `{}`", name))
                     .span_note(args[*prev].span, "previously here")
@@ -200,7 +201,9 @@ fn parse_args(ecx: &mut ExtCtxt,
             names.insert(name.to_string(), slot);
             args.push(e);
         } else {
-            args.push(panictry!(p.parse_expr()));
+            let e = panictry!(p.parse_expr());
+            check_unused_parens(ecx, &e, "format argument");
+            args.push(e);
         }
     }
     Some((fmtstr, args, names))
@@ -21,6 +21,7 @@ use std::str;
 use std::cmp::{PartialEq, Ordering, PartialOrd, Ord};
 use std::hash::{Hash, Hasher};
 
+use edition::Edition;
 use hygiene::SyntaxContext;
 use {Span, DUMMY_SP, GLOBALS};
 
@@ -438,6 +439,18 @@ impl Symbol {
     fn is_unused_keyword_2018(self) -> bool {
         self >= keywords::Async.name() && self <= keywords::Try.name()
     }
+
+    /// Returns the edition in which this symbol becomes a keyword, if it's
+    /// still a plain identifier in earlier editions. `await` is included even
+    /// though it's not in the keyword table: it's reserved in the 2018
+    /// edition unless the `async_await` feature is enabled.
+    pub fn keyword_edition(self) -> Option<Edition> {
+        if self.is_used_keyword_2018() || self.is_unused_keyword_2018() || self == "await" {
+            Some(Edition::Edition2018)
+        } else {
+            None
+        }
+    }
 }
 
 impl Ident {
@@ -731,4 +744,15 @@ mod tests {
             assert_eq!(i.without_first_quote().name, keywords::Break.name());
         });
     }
+
+    #[test]
+    fn keyword_edition_test() {
+        GLOBALS.set(&Globals::new(), || {
+            assert_eq!(keywords::Async.name().keyword_edition(), Some(Edition::Edition2018));
+            assert_eq!(keywords::Dyn.name().keyword_edition(), Some(Edition::Edition2018));
+            assert_eq!(Symbol::intern("await").keyword_edition(), Some(Edition::Edition2018));
+            assert_eq!(keywords::Fn.name().keyword_edition(), None);
+            assert_eq!(Symbol::intern("dog").keyword_edition(), None);
+        });
+    }
 }
@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C no-prepopulate-passes
+
+#![crate_type = "lib"]
+
+use std::fmt::Debug;
+
+// A trait object is never `Freeze`, but the memory behind a `&dyn Trait` constant
+// is still immutable, so it has to be emitted as an LLVM constant.
+
+// CHECK: @{{[0-9]+}} = private unnamed_addr constant <{ [4 x i8] }> <{ [4 x i8] c"****" }>
+
+pub const DEBUG: &dyn Debug = &0x2a2a2a2au32;
+
+// CHECK-LABEL: @debug
+#[no_mangle]
+pub fn debug() -> &'static dyn Debug {
+    DEBUG
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C no-prepopulate-passes
+
+#![crate_type = "lib"]
+
+// Identical string literals share a single allocation, and therefore a single global.
+
+// CHECK: c"dedup me"
+// CHECK-NOT: c"dedup me"
+
+pub static FIRST: &str = "dedup me";
+pub static SECOND: &str = "dedup me";
+
+#[no_mangle]
+pub fn third() -> &'static str {
+    "dedup me"
+}
@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:mir_small_leaf_fns.rs
+// compile-flags: -C no-prepopulate-passes -Z mir-opt-level=3
+
+// Check that the MIR inliner can inline functions from other crates whose MIR was encoded by
+// `-Z always-encode-mir=<size>`, but not the ones that had to be left out.
+
+#![crate_type = "lib"]
+
+extern crate mir_small_leaf_fns;
+
+// CHECK-LABEL: @inlined
+#[no_mangle]
+pub fn inlined(x: u32) -> u32 {
+    // CHECK-NOT: call {{.*}}add_one
+    mir_small_leaf_fns::add_one(x)
+}
+
+// CHECK-LABEL: @not_inlined
+#[no_mangle]
+pub fn not_inlined(x: u32) -> u32 {
+    // CHECK: call {{.*}}triple
+    mir_small_leaf_fns::triple(x)
+}
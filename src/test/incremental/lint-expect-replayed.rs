@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Checks that the `#[expect]` attributes fulfilled inside a module are still
+// fulfilled in a later session where the module did not change.
+
+// revisions: rpass1 rpass2
+
+#![feature(lint_reasons)]
+#![deny(unfulfilled_lint_expectations)]
+
+mod m {
+    #[expect(non_camel_case_types)]
+    pub struct lower_case;
+}
+
+#[cfg(rpass1)]
+fn main() {
+    let _ = m::lower_case;
+}
+
+#[cfg(rpass2)]
+fn main() {
+    let _x = m::lower_case;
+}
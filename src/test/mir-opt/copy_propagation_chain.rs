@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that CopyPropagation collapses chains of copies, even when a
+// local in the chain is used more than once.
+
+fn chain(x: u32) -> u32 {
+    let a = x;
+    let b = a;
+    let c = b;
+    c ^ a
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    chain(0);
+}
+
+// END RUST SOURCE
+// START rustc.chain.CopyPropagation.before.mir
+// bb0: {
+//     ...
+//     _2 = _1;
+//     ...
+//     _3 = _2;
+//     ...
+//     _4 = _3;
+//     ...
+//     _0 = BitXor(move _5, move _6);
+//     ...
+//     return;
+// }
+// END rustc.chain.CopyPropagation.before.mir
+// START rustc.chain.CopyPropagation.after.mir
+// bb0: {
+//     ...
+//     _0 = BitXor(_1, _1);
+//     ...
+//     return;
+// }
+// END rustc.chain.CopyPropagation.after.mir
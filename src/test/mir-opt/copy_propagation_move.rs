@@ -0,0 +1,80 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that CopyPropagation never moves out of a source that is read again after the merge,
+// and that it forwards a move when the source is dead afterwards.
+
+struct Pair(u32, String);
+
+fn id(x: u32) -> u32 {
+    x
+}
+
+fn use_u32(_: u32) {}
+
+fn read_after_move(x: u32) -> u32 {
+    let y = x;
+    // `x` is moved into `id` once `y` is propagated, but read again below.
+    let z = id(y);
+    z + x
+}
+
+fn forward(p: Pair) -> Pair {
+    let n = p.0;
+    let q = p;
+    use_u32(n);
+    q
+}
+
+fn main() {
+    read_after_move(0);
+    forward(Pair(0, String::new()));
+}
+
+// END RUST SOURCE
+// START rustc.read_after_move.CopyPropagation.before.mir
+// bb0: {
+//     ...
+//     _2 = _1;
+//     ...
+//     _4 = _2;
+//     _3 = const id(move _4) -> bb1;
+// }
+// END rustc.read_after_move.CopyPropagation.before.mir
+// START rustc.read_after_move.CopyPropagation.after.mir
+// bb0: {
+//     ...
+//     _3 = const id(_1) -> bb1;
+// }
+// END rustc.read_after_move.CopyPropagation.after.mir
+// START rustc.forward.CopyPropagation.before.mir
+// bb0: {
+//     ...
+//     _3 = move _1;
+//     ...
+// }
+// bb1: {
+//     ...
+//     _0 = move _3;
+//     ...
+// }
+// END rustc.forward.CopyPropagation.before.mir
+// START rustc.forward.CopyPropagation.after.mir
+// bb0: {
+//     ...
+//     _2 = (_1.0: u32);
+//     ...
+// }
+// bb1: {
+//     ...
+//     _0 = move _1;
+//     ...
+// }
+// END rustc.forward.CopyPropagation.after.mir
@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn test(x: u32) -> u32 {
+    let mut y;
+    y = x;
+    y = 5;
+    y + x
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    test(0);
+}
+
+// END RUST SOURCE
+// START rustc.test.DeadStoreElimination.before.mir
+//  bb0: {
+//      StorageLive(_2);
+//      _2 = _1;
+//      _2 = const 5u32;
+//      ...
+//  }
+// END rustc.test.DeadStoreElimination.before.mir
+// START rustc.test.DeadStoreElimination.after.mir
+//  bb0: {
+//      StorageLive(_2);
+//      nop;
+//      _2 = const 5u32;
+//      ...
+//  }
+// END rustc.test.DeadStoreElimination.after.mir
@@ -0,0 +1,62 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z span_free_formats
+
+trait Foo {
+    fn foo(&self) -> u32;
+    fn bar(&mut self) -> u32;
+}
+
+struct S;
+
+impl Foo for S {
+    fn foo(&self) -> u32 {
+        1
+    }
+
+    fn bar(&mut self) -> u32 {
+        2
+    }
+}
+
+fn test(s: &S) -> u32 {
+    (s as &dyn Foo).foo()
+}
+
+// Not devirtualized, the `&mut S` can't be passed on a second time.
+fn test_mut(s: &mut S) -> u32 {
+    (s as &mut dyn Foo).bar()
+}
+
+fn main() {
+    println!("{}", test(&S));
+    println!("{}", test_mut(&mut S));
+}
+
+// END RUST SOURCE
+// START rustc.test.Devirtualize.before.mir
+// bb0: {
+// ...
+//     _0 = const Foo::foo(move _2) -> bb1;
+// }
+// END rustc.test.Devirtualize.before.mir
+// START rustc.test.Devirtualize.after.mir
+// bb0: {
+// ...
+//     _0 = const <S as Foo>::foo(_5) -> bb1;
+// }
+// END rustc.test.Devirtualize.after.mir
+// START rustc.test_mut.Devirtualize.after.mir
+// bb0: {
+// ...
+//     _0 = const Foo::bar(move _2) -> bb1;
+// }
+// END rustc.test_mut.Devirtualize.after.mir
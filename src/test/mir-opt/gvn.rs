@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn test(x: u32, y: u32) -> u32 {
+    let a = x & y;
+    let b = x & y;
+    a | b
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    test(0, 0);
+}
+
+// END RUST SOURCE
+// START rustc.test.GlobalValueNumbering.before.mir
+//  bb0: {
+//      ...
+//      _3 = BitAnd(move _4, move _5);
+//      ...
+//      _6 = BitAnd(move _7, move _8);
+//      ...
+//  }
+// END rustc.test.GlobalValueNumbering.before.mir
+// START rustc.test.GlobalValueNumbering.after.mir
+//  bb0: {
+//      ...
+//      _3 = BitAnd(move _4, move _5);
+//      ...
+//      _6 = _3;
+//      ...
+//  }
+// END rustc.test.GlobalValueNumbering.after.mir
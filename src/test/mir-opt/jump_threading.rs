@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn test() -> u32 {
+    let x = 3 > 2;
+    if x { 1 } else { 2 }
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    test();
+}
+
+// END RUST SOURCE
+// START rustc.test.JumpThreading.before.mir
+//  bb0: {
+//      ...
+//      _1 = const true;
+//      ...
+//      switchInt(move _2) -> [false: bb2, otherwise: bb1];
+//  }
+// END rustc.test.JumpThreading.before.mir
+// START rustc.test.JumpThreading.after.mir
+//  bb0: {
+//      ...
+//      _1 = const true;
+//      ...
+//      goto -> bb1;
+//  }
+// END rustc.test.JumpThreading.after.mir
@@ -0,0 +1,85 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Inner {
+    a: [u32; 64],
+}
+
+struct Outer {
+    inner: Inner,
+    b: u64,
+}
+
+fn array(init: fn(&mut [u8; 1024])) -> [u8; 1024] {
+    let mut buf = [0; 1024];
+    init(&mut buf);
+    buf
+}
+
+fn nested(b: u64) -> Outer {
+    let outer = Outer { inner: Inner { a: [0; 64] }, b };
+    outer
+}
+
+fn main() {
+    // Make sure the functions actually get instantiated.
+    array(|buf| buf[0] = 1);
+    nested(0);
+}
+
+// END RUST SOURCE
+// START rustc.array.RenameReturnPlace.before.mir
+//  bb0: {
+//      ...
+//      _2 = [const 0u8; const 1024usize];
+//      ...
+//  }
+//  ...
+//  bb2: {
+//      ...
+//      _0 = _2;
+//      ...
+//      return;
+//  }
+// END rustc.array.RenameReturnPlace.before.mir
+// START rustc.array.RenameReturnPlace.after.mir
+//  bb0: {
+//      ...
+//      _0 = [const 0u8; const 1024usize];
+//      ...
+//  }
+//  ...
+//  bb2: {
+//      ...
+//      nop;
+//      ...
+//      return;
+//  }
+// END rustc.array.RenameReturnPlace.after.mir
+// START rustc.nested.RenameReturnPlace.before.mir
+//  bb0: {
+//      ...
+//      (_2.1: u64) = _1;
+//      ...
+//      _0 = move _2;
+//      ...
+//      return;
+//  }
+// END rustc.nested.RenameReturnPlace.before.mir
+// START rustc.nested.RenameReturnPlace.after.mir
+//  bb0: {
+//      ...
+//      (_0.1: u64) = _1;
+//      ...
+//      nop;
+//      ...
+//      return;
+//  }
+// END rustc.nested.RenameReturnPlace.after.mir
@@ -0,0 +1,45 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-wasm32-bare compiled with panic=abort by default
+
+// Check that calls whose cleanup only resumes lose their unwind edge, and
+// that the then unreachable resume block is removed.
+
+fn foo() {}
+
+fn main() {
+    foo();
+}
+
+// END RUST SOURCE
+// START rustc.main.SimplifyBranches-initial.after.mir
+// bb0: {
+//     ...
+//     _1 = const foo() -> [return: bb2, unwind: bb1];
+// }
+// bb1: {
+//     resume;
+// }
+// bb2: {
+//     ...
+//     return;
+// }
+// END rustc.main.SimplifyBranches-initial.after.mir
+// START rustc.main.CleanAscribeUserType.before.mir
+// bb0: {
+//     ...
+//     _1 = const foo() -> bb1;
+// }
+// bb1: {
+//     ...
+//     return;
+// }
+// END rustc.main.CleanAscribeUserType.before.mir
@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn test(x: u32) -> u32 {
+    let p = (x, 0u32);
+    p.0
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    test(0);
+}
+
+// END RUST SOURCE
+// START rustc.test.ScalarReplacementOfAggregates.before.mir
+//  bb0: {
+//      ...
+//      (_2.0: u32) = move _3;
+//      (_2.1: u32) = const 0u32;
+//      ...
+//      _0 = (_2.0: u32);
+//      ...
+//  }
+// END rustc.test.ScalarReplacementOfAggregates.before.mir
+// START rustc.test.ScalarReplacementOfAggregates.after.mir
+//  bb0: {
+//      ...
+//      _4 = move _3;
+//      _5 = const 0u32;
+//      ...
+//      _0 = _4;
+//      ...
+//  }
+// END rustc.test.ScalarReplacementOfAggregates.after.mir
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+enum Empty {}
+
+enum E {
+    A(u32),
+    B(Empty),
+}
+
+fn test(e: E) -> u32 {
+    match e {
+        E::A(x) => x,
+        E::B(v) => match v {},
+    }
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    test(E::A(0));
+}
+
+// END RUST SOURCE
+// START rustc.test.UnreachablePropagation.before.mir
+//  bb0: {
+//      ...
+//      _2 = discriminant(_1);
+//      switchInt(move _2) -> [0isize: bb2, 1isize: bb3, otherwise: bb1];
+//  }
+// END rustc.test.UnreachablePropagation.before.mir
+// START rustc.test.UnreachablePropagation.after.mir
+//  bb0: {
+//      ...
+//      _2 = discriminant(_1);
+//      goto -> bb2;
+//  }
+// END rustc.test.UnreachablePropagation.after.mir
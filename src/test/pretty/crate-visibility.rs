@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(crate_visibility_modifier)]
+
+// pp-exact
+
+crate struct Named {
+    crate x: u8,
+    pub(crate) y: u8,
+}
+
+pub(crate) struct Tuple(crate u8, pub(crate) u8);
+
+mod m {
+    pub(in crate::m) fn f() { }
+
+    crate fn g() {
+        f();
+    }
+}
+
+use crate::m::g;
+
+fn main() {
+    g();
+}
@@ -0,0 +1,117 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(box_syntax, rustc_private)]
+
+#[macro_use]
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_lint;
+extern crate rustc_metadata;
+extern crate rustc_errors;
+extern crate rustc_codegen_utils;
+extern crate syntax;
+
+use rustc::lint::{EarlyContext, EarlyLintPass, LintArray, LintContext, LintId, LintPass,
+                  LintStore};
+use rustc::session::{build_session, Session};
+use rustc::session::config::{Input, Options,
+                             OutputType, OutputTypes};
+use rustc_driver::driver::{self, compile_input, CompileController};
+use rustc_metadata::cstore::CStore;
+use rustc_errors::registry::Registry;
+use syntax::ast;
+use syntax::source_map::FileName;
+use rustc_codegen_utils::codegen_backend::CodegenBackend;
+
+use std::path::PathBuf;
+use std::rc::Rc;
+
+declare_tool_lint!(pub clippy::TEST_LINT, Warn, "Warn about items named 'lintme'");
+
+struct Pass;
+
+impl LintPass for Pass {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(TEST_LINT)
+    }
+}
+
+impl EarlyLintPass for Pass {
+    fn check_item(&mut self, cx: &EarlyContext, it: &ast::Item) {
+        if it.ident.name == "lintme" {
+            cx.span_lint(TEST_LINT, it.span, "item is named 'lintme'");
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 {
+        panic!("expected rustc path");
+    }
+
+    let tmpdir = PathBuf::from(&args[1]);
+
+    let mut sysroot = PathBuf::from(&args[2]);
+    sysroot.pop();
+    sysroot.pop();
+
+    // The lint itself, its group and its old name are all known to the
+    // attribute checker, so each of these is an error.
+    for attr in &["clippy::test_lint", "clippy::test_group", "clippy::old_test_lint"] {
+        let src = format!("#![deny({})]\nfn lintme() {{}}\nfn main() {{}}\n", attr);
+        assert!(compile(src, tmpdir.join("out"), sysroot.clone()).is_err());
+    }
+
+    let src = "#![allow(clippy::test_group)]\nfn lintme() {}\nfn main() {}\n";
+    assert!(compile(src.to_string(), tmpdir.join("out"), sysroot.clone()).is_ok());
+}
+
+fn basic_sess(opts: Options) -> (Session, Rc<CStore>, Box<CodegenBackend>) {
+    let descriptions = Registry::new(&rustc::DIAGNOSTICS);
+    let sess = build_session(opts, None, descriptions);
+    let codegen_backend = rustc_driver::get_codegen_backend(&sess);
+    let cstore = Rc::new(CStore::new(codegen_backend.metadata_loader()));
+    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+    (sess, cstore, codegen_backend)
+}
+
+fn compile(code: String, output: PathBuf, sysroot: PathBuf) -> Result<(), ()> {
+    syntax::with_globals(|| {
+        let mut opts = Options::default();
+        opts.output_types = OutputTypes::new(&[(OutputType::Metadata, None)]);
+        opts.maybe_sysroot = Some(sysroot);
+        driver::spawn_thread_pool(opts, |opts| {
+            let (sess, cstore, codegen_backend) = basic_sess(opts);
+            let mut control = CompileController::basic();
+            control.register_lints = box |sess: &Session, store: &mut LintStore| {
+                store.register_early_pass(Some(sess), true, box Pass);
+                store.register_group(Some(sess), true, "clippy::test_group", None,
+                                     vec![LintId::of(TEST_LINT)]);
+                store.register_renamed("clippy::old_test_lint", "clippy::test_lint");
+            };
+            let name = FileName::anon_source_code(&code);
+            let input = Input::Str { name, input: code };
+            compile_input(
+                codegen_backend,
+                &sess,
+                &cstore,
+                &None,
+                &input,
+                &None,
+                &Some(output),
+                None,
+                &control
+            ).map_err(|_| ())
+        })
+    })
+}
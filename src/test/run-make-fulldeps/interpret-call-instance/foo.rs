@@ -0,0 +1,114 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private)]
+
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_lint;
+extern crate rustc_metadata;
+extern crate rustc_mir;
+extern crate rustc_errors;
+extern crate rustc_codegen_utils;
+extern crate syntax;
+
+use rustc::session::{build_session, Session};
+use rustc::session::config::{Input, Options};
+use rustc::ty::{Instance, ParamEnv, TyCtxt};
+use rustc::ty::layout::LayoutOf;
+use rustc_driver::Compilation;
+use rustc_driver::driver::{self, compile_input, CompileController};
+use rustc_driver::interpret::{MemoryKind, Scalar};
+use rustc_metadata::cstore::CStore;
+use rustc_mir::const_eval::mk_eval_cx;
+use rustc_errors::registry::Registry;
+use syntax::source_map::FileName;
+use rustc_codegen_utils::codegen_backend::CodegenBackend;
+
+use std::path::PathBuf;
+use std::rc::Rc;
+
+fn main() {
+    let src = r#"
+    fn add_one(x: u32) -> u32 {
+        let mut sum = 0;
+        while sum < x {
+            sum += 1;
+        }
+        sum + 1
+    }
+
+    fn main() {}
+    "#;
+
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 {
+        panic!("expected rustc path");
+    }
+
+    let mut sysroot = PathBuf::from(&args[2]);
+    sysroot.pop();
+    sysroot.pop();
+
+    syntax::with_globals(|| {
+        let mut opts = Options::default();
+        opts.maybe_sysroot = Some(sysroot);
+        driver::spawn_thread_pool(opts, |opts| {
+            let (sess, cstore, codegen_backend) = basic_sess(opts);
+            let mut control = CompileController::basic();
+            control.after_analysis.stop = Compilation::Stop;
+            control.after_analysis.callback = Box::new(|state| {
+                call_add_one(state.tcx.unwrap());
+            });
+            let name = FileName::anon_source_code(src);
+            let input = Input::Str { name, input: src.to_string() };
+            let _ = compile_input(
+                codegen_backend,
+                &sess,
+                &cstore,
+                &None,
+                &input,
+                &None,
+                &None,
+                None,
+                &control
+            );
+        });
+    });
+}
+
+fn basic_sess(opts: Options) -> (Session, Rc<CStore>, Box<CodegenBackend>) {
+    let descriptions = Registry::new(&rustc::DIAGNOSTICS);
+    let sess = build_session(opts, None, descriptions);
+    let codegen_backend = rustc_driver::get_codegen_backend(&sess);
+    let cstore = Rc::new(CStore::new(codegen_backend.metadata_loader()));
+    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+    (sess, cstore, codegen_backend)
+}
+
+// Calls `add_one(41)` through the interpreter and checks the returned value.
+fn call_add_one<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    let def_id = tcx.hir().krate().items.values()
+        .find(|item| item.ident.name == "add_one")
+        .map(|item| tcx.hir().local_def_id(item.id))
+        .expect("`add_one` not found");
+    let instance = Instance::mono(tcx, def_id);
+    let mut ecx = mk_eval_cx(tcx, instance, ParamEnv::reveal_all()).unwrap();
+
+    let u32_layout = ecx.layout_of(tcx.types.u32).unwrap();
+    let arg = ecx.allocate(u32_layout, MemoryKind::Stack).unwrap();
+    ecx.write_scalar(Scalar::from_uint(41u32, u32_layout.size), arg.into()).unwrap();
+    let dest = ecx.allocate(u32_layout, MemoryKind::Stack).unwrap();
+
+    ecx.call_instance(instance, &[arg.into()], dest.into()).unwrap();
+    let result = ecx.read_scalar(dest.into()).unwrap().not_undef().unwrap();
+    assert_eq!(result.to_u32().unwrap(), 42);
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate krate_macros;
+
+macro_rules! square {
+    ($e:expr) => { $e * $e };
+}
+
+struct S;
+
+impl S {
+    fn get(&self) -> u32 {
+        square!(3)
+    }
+}
+
+fn main() {
+    let s = S;
+    let n = s.get();
+    let _ = exported!(n);
+}
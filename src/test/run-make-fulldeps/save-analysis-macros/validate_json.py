@@ -0,0 +1,32 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import sys
+import json
+
+data = json.loads(sys.stdin.readline().strip())
+
+if sys.argv[1] == "analysis":
+    # Both invocations show up in the save-analysis data, at their call sites.
+    refs = dict((r["qualname"], r["span"]["line_start"]) for r in data["macro_refs"])
+    assert refs.get("square") == 22, refs
+    assert refs.get("exported") == 29, refs
+else:
+    # The resolve map points each invocation at the macro that was picked for it.
+    crates = dict((c["num"], c["id"]["name"]) for c in data["external_crates"])
+    macros = dict((r["span"]["line_start"], r["ref_id"]["krate"])
+                  for r in data["refs"] if r["kind"] == "Macro")
+    assert macros.get(22) == 0, macros
+    assert crates.get(macros.get(29)) == "krate_macros", (macros, crates)
+    # Method calls are resolved by typeck, but end up in the map all the same.
+    assert any(r["kind"] == "Function" and r["span"]["line_start"] == 28
+               for r in data["refs"])
@@ -0,0 +1,59 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+#![feature(nll)]
+
+// Check that the base of an overloaded index expression is a two-phase
+// borrow, so the index can read the indexed value.
+
+use std::ops::{AddAssign, Index, IndexMut};
+
+struct Counts(Vec<usize>);
+
+impl Counts {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl Index<usize> for Counts {
+    type Output = usize;
+    fn index(&self, i: usize) -> &usize {
+        &self.0[i]
+    }
+}
+
+impl IndexMut<usize> for Counts {
+    fn index_mut(&mut self, i: usize) -> &mut usize {
+        &mut self.0[i]
+    }
+}
+
+struct Total(usize);
+
+impl AddAssign<usize> for Total {
+    fn add_assign(&mut self, rhs: usize) {
+        self.0 += rhs;
+    }
+}
+
+fn main() {
+    let mut c = Counts(vec![0, 1, 2]);
+    c[c.len() - 1] = 5;
+    c[c[1]] += c[0] + 1;
+    c[c[0]] = c[2];
+    assert_eq!(c.0, vec![5, 2, 5]);
+
+    let mut t = Total(1);
+    t += t.0;
+    assert_eq!(t.0, 2);
+}
@@ -0,0 +1,14 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub const ANSWER: u32 = 42;
+pub const NAME: &str = "value";
+pub const TABLE: [&[u8]; 2] = [b"ab", b"cde"];
+pub const NESTED: &(u8, &[u16]) = &(3, &[1, 2, 3]);
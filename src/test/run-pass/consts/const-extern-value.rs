@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+// aux-build:const_value_lib.rs
+
+// Use the values of upstream constants as exported in their crate's metadata.
+
+extern crate const_value_lib;
+
+use const_value_lib::{ANSWER, NAME, TABLE, NESTED};
+
+const LEN: usize = ANSWER as usize;
+const DOUBLED: u32 = ANSWER * 2;
+
+fn main() {
+    let arr = [0u8; LEN];
+    assert_eq!(arr.len(), 42);
+    assert_eq!(DOUBLED, 84);
+    match NAME {
+        "value" => {}
+        _ => panic!(),
+    }
+    assert_eq!(TABLE[1], b"cde");
+    assert_eq!(NESTED.0, 3);
+    assert_eq!(NESTED.1[2], 3);
+}
@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+#![feature(const_ptr_offset, const_raw_ptr_deref, const_raw_ptr_to_usize_cast)]
+
+const DATA: [u16; 4] = [1, 2, 3, 4];
+
+const fn nth(ptr: *const u16, n: isize) -> u16 {
+    unsafe { *ptr.offset(n) }
+}
+
+const fn wrapped(ptr: *const u16) -> u16 {
+    // Leaving the allocation is fine as long as we come back before reading.
+    unsafe { *ptr.wrapping_offset(100).wrapping_offset(-98) }
+}
+
+const BASE: *const u16 = &DATA as *const [u16; 4] as *const u16;
+const SECOND: u16 = nth(BASE, 1);
+const LAST: u16 = nth(unsafe { BASE.offset(4) }, -1);
+const THIRD: u16 = wrapped(BASE);
+const NULL_ADDR: usize = 0 as *const u16 as usize;
+
+fn main() {
+    assert_eq!(SECOND, 2);
+    assert_eq!(LAST, 4);
+    assert_eq!(THIRD, 3);
+    assert_eq!(NULL_ADDR, 0);
+}
@@ -0,0 +1,42 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+// Check that locals of the same type which are live across different
+// suspension points share a field of the generator, and keep their values.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+fn main() {
+    let mut gen = || {
+        {
+            let a = [1u8; 1024];
+            yield a[0];
+            assert_eq!(a[1023], 1);
+        }
+        {
+            let b = [2u8; 1024];
+            yield b[0];
+            assert_eq!(b[1023], 2);
+        }
+    };
+
+    // `a` and `b` are never alive at the same time.
+    assert!(std::mem::size_of_val(&gen) < 2048);
+
+    unsafe {
+        assert_eq!(gen.resume(), GeneratorState::Yielded(1));
+        assert_eq!(gen.resume(), GeneratorState::Yielded(2));
+        assert_eq!(gen.resume(), GeneratorState::Complete(()));
+    }
+}
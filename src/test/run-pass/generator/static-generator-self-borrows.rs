@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that NLL accepts immovable generators that hold borrows of their
+// own locals and upvars across suspension points.
+
+#![feature(nll, generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+fn main() {
+    let upvar = String::from("upvar");
+    let mut generator = static move || {
+        let mut buf = [0u32; 4];
+        let name = &upvar;
+        let (head, tail) = buf.split_at_mut(2);
+        head[0] = 1;
+        yield name.len();
+        tail[1] = 2;
+        yield head[0] as usize + tail[1] as usize;
+        assert_eq!(buf, [1, 0, 0, 2]);
+        name.clone()
+    };
+    unsafe {
+        assert_eq!(generator.resume(), GeneratorState::Yielded(5));
+        assert_eq!(generator.resume(), GeneratorState::Yielded(3));
+        assert_eq!(generator.resume(), GeneratorState::Complete(String::from("upvar")));
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z always-encode-mir=20
+
+pub fn add_one(x: u32) -> u32 {
+    x + 1
+}
+
+pub fn abs_diff(a: i64, b: i64) -> i64 {
+    if a > b { a - b } else { b - a }
+}
+
+static COUNTER_BASE: u32 = 100;
+
+// Refers to a private static, so its MIR must not be encoded.
+pub fn from_base(x: u32) -> u32 {
+    COUNTER_BASE + x
+}
+
+fn private_helper(x: u32) -> u32 {
+    x * 3
+}
+
+// Calls a function that is not exported, so its MIR must not be encoded.
+pub fn triple(x: u32) -> u32 {
+    private_helper(x)
+}
@@ -0,0 +1,99 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z instrument-mir-coverage -Z mir-opt-level=2
+// only-linux
+
+// Check that functions instrumented with coverage counters, including ones
+// that get inlined into an instrumented caller, still run correctly, and
+// that the records in the `__rust_mir_cov` section count their executions.
+
+use std::{mem, slice, str};
+
+// The layout of the records emitted by codegen.
+#[repr(C)]
+struct Record {
+    symbol_name: *const u8,
+    symbol_name_len: usize,
+    regions: *const u8,
+    regions_len: usize,
+    counters: *mut u64,
+    num_counters: usize,
+}
+
+extern "C" {
+    // Defined by the linker around the section.
+    #[link_name = "__start___rust_mir_cov"]
+    static SECTION_START: Record;
+    #[link_name = "__stop___rust_mir_cov"]
+    static SECTION_STOP: Record;
+}
+
+struct Function {
+    symbol_name: &'static str,
+    regions: &'static str,
+    counters: &'static [u64],
+}
+
+fn functions() -> Vec<Function> {
+    unsafe {
+        let start = &SECTION_START as *const Record;
+        let stop = &SECTION_STOP as *const Record;
+        let len = (stop as usize - start as usize) / mem::size_of::<Record>();
+        slice::from_raw_parts(start, len).iter().map(|record| Function {
+            symbol_name: str::from_utf8_unchecked(
+                slice::from_raw_parts(record.symbol_name, record.symbol_name_len)),
+            regions: str::from_utf8_unchecked(
+                slice::from_raw_parts(record.regions, record.regions_len)),
+            counters: slice::from_raw_parts(record.counters, record.num_counters),
+        }).collect()
+    }
+}
+
+fn function(name: &str) -> Function {
+    functions().into_iter().find(|function| function.symbol_name.contains(name))
+        .unwrap_or_else(|| panic!("no coverage record for `{}`", name))
+}
+
+#[inline]
+fn collatz_step(n: u64) -> u64 {
+    if n % 2 == 0 { n / 2 } else { 3 * n + 1 }
+}
+
+fn collatz_len(mut n: u64) -> u32 {
+    let mut len = 0;
+    while n != 1 {
+        n = collatz_step(n);
+        len += 1;
+    }
+    len
+}
+
+fn main() {
+    assert_eq!(collatz_len(27), 111);
+    assert_eq!((1..10).map(collatz_len).sum::<u32>(), 61);
+
+    // The first counter is the one of the entry block.
+    let main = function("mir_coverage_instrumentation4main");
+    assert_eq!(main.counters[0], 1);
+
+    let collatz_len = function("collatz_len");
+    assert_eq!(collatz_len.counters[0], 10);
+    // Every step of the loop runs the blocks of the inlined `collatz_step`,
+    // which are counted as blocks of `collatz_len`.
+    assert!(collatz_len.counters.iter().any(|&count| count >= 111 + 61));
+    assert!(collatz_len.regions.lines().count() > 0);
+    for line in collatz_len.regions.lines() {
+        let mut parts = line.splitn(2, '\t');
+        let index: usize = parts.next().unwrap().parse().unwrap();
+        assert!(index < collatz_len.counters.len(), "{}", line);
+        assert!(parts.next().unwrap().contains("mir-coverage-instrumentation.rs:"), "{}", line);
+    }
+}
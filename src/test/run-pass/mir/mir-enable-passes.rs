@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-enable-passes=+Inline,+CopyPropagation,-ConstProp -Z validate-mir
+
+// Check that forcing MIR passes on and off and validating the MIR after each
+// pass still produces a working program.
+
+#[inline]
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn sum(v: &[u32]) -> u32 {
+    let mut total = 0;
+    for &x in v {
+        total = add(total, x);
+    }
+    total
+}
+
+fn main() {
+    assert_eq!(sum(&[1, 2, 3, 4]), 10);
+}
@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:mir_small_leaf_fns.rs
+// compile-flags: -Z mir-opt-level=3
+
+// Check that functions whose MIR was encoded by `-Z always-encode-mir=<size>`
+// can be inlined across crates, and that the ones referring to private items
+// still link.
+
+extern crate mir_small_leaf_fns;
+
+use mir_small_leaf_fns::{abs_diff, add_one, from_base, triple};
+
+fn main() {
+    assert_eq!(add_one(41), 42);
+    assert_eq!(abs_diff(3, 10), 7);
+    assert_eq!(abs_diff(10, 3), 7);
+    assert_eq!(from_base(5), 105);
+    assert_eq!(triple(5), 15);
+}
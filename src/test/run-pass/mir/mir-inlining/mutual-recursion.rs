@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+// compile-flags:-Zmir-opt-level=2 -Zmir-inline-threshold=1000
+
+// Mutually recursive functions must not be inlined into each other over and over,
+// even with a threshold that would allow it.
+
+#[inline]
+fn is_even(n: u32) -> bool {
+    if n == 0 { true } else { is_odd(n - 1) }
+}
+
+#[inline]
+fn is_odd(n: u32) -> bool {
+    if n == 0 { false } else { is_even(n - 1) }
+}
+
+#[inline(never)]
+fn opaque(n: u32) -> u32 {
+    n
+}
+
+#[inline(always)]
+fn double(n: u32) -> u32 {
+    opaque(n) * 2
+}
+
+fn main() {
+    assert!(is_even(10));
+    assert!(is_odd(7));
+    assert_eq!(double(21), 42);
+}
@@ -0,0 +1,62 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z validate-mir -Z mir-opt-level=3
+
+// Check that the MIR of some common constructs passes validation after
+// every MIR pass, including the optimizations.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+enum Shape {
+    Circle(u32),
+    Rect { w: u32, h: u32 },
+}
+
+fn area(shape: &Shape) -> u32 {
+    match *shape {
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Rect { w, h } => w * h,
+    }
+}
+
+fn sum_slice(v: &[u32]) -> u32 {
+    let mut total = 0;
+    for i in 0..v.len() {
+        total += v[i];
+    }
+    total
+}
+
+fn main() {
+    let shapes = [Shape::Circle(2), Shape::Rect { w: 3, h: 4 }];
+    let areas: Vec<u32> = shapes.iter().map(area).collect();
+    assert_eq!(sum_slice(&areas), 24);
+
+    let offset = 1;
+    let add = |x: u32| x + offset;
+    assert_eq!(add(1), 2);
+
+    let mut gen = || {
+        let x = String::from("hello");
+        yield x.len();
+        x.len() + 1
+    };
+    match unsafe { gen.resume() } {
+        GeneratorState::Yielded(5) => {}
+        _ => panic!(),
+    }
+    match unsafe { gen.resume() } {
+        GeneratorState::Complete(6) => {}
+        _ => panic!(),
+    }
+}
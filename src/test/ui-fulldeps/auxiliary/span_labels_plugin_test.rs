@@ -0,0 +1,80 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// force-host
+
+#![feature(plugin_registrar)]
+#![feature(box_syntax, rustc_private)]
+
+extern crate syntax;
+extern crate syntax_pos;
+
+// Load rustc as a plugin to get macros
+#[macro_use]
+extern crate rustc;
+extern crate rustc_plugin;
+
+use rustc::lint::{EarlyContext, LintContext, LintPass, EarlyLintPass,
+                  EarlyLintPassObject, LintArray};
+use rustc_plugin::Registry;
+use syntax::ast;
+use syntax_pos::BytePos;
+declare_lint!(SPAN_LABELS, Warn, "Warn with the labels given in the docs of a constant");
+
+struct Pass;
+
+impl LintPass for Pass {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(SPAN_LABELS)
+    }
+}
+
+impl EarlyLintPass for Pass {
+    fn check_item(&mut self, cx: &EarlyContext, it: &ast::Item) {
+        let expr = match it.node {
+            ast::ItemKind::Const(_, ref expr) => expr,
+            _ => return,
+        };
+        let snippet = cx.sess.source_map().span_to_snippet(expr.span).unwrap();
+
+        // Every doc line `label: start..end` labels the value of the constant
+        // from the first `start` to the first `end` after it.
+        let mut labels = vec![];
+        for attr in &it.attrs {
+            if !attr.check_name("doc") {
+                continue;
+            }
+            let value = attr.value_str().unwrap().as_str();
+            let doc = value.trim_left_matches("///").trim();
+            let colon = doc.rfind(": ").unwrap();
+            let (label, range) = (&doc[..colon], &doc[colon + 2..]);
+            let dots = range.find("..").unwrap();
+            let (start, end) = (&range[..dots], &range[dots + 2..]);
+            let lo = snippet.find(start).unwrap();
+            let hi = lo + snippet[lo..].find(end).unwrap() + end.len();
+            let span = expr.span.with_lo(expr.span.lo() + BytePos(lo as u32))
+                                .with_hi(expr.span.lo() + BytePos(hi as u32));
+            labels.push((span, label.to_string()));
+        }
+
+        if let Some(&(primary_span, _)) = labels.first() {
+            let mut err = cx.struct_span_lint(SPAN_LABELS, primary_span, "labelled spans");
+            for (span, label) in labels {
+                err.span_label(span, label);
+            }
+            err.emit();
+        }
+    }
+}
+
+#[plugin_registrar]
+pub fn plugin_registrar(reg: &mut Registry) {
+    reg.register_early_lint_pass(box Pass as EarlyLintPassObject);
+}
@@ -0,0 +1,52 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+// aux-build:span_labels_plugin_test.rs
+// ignore-stage1
+
+#![feature(plugin)]
+#![plugin(span_labels_plugin_test)]
+#![allow(dead_code)]
+
+/// the function: foo..)
+/// a: a1..a1
+/// b: b2..b2
+/// c: c3..c3
+const SHORT_LABELS: &str = "fn foo(a1: u32, b2: u32, c3: u32) {}";
+
+/// `A`: X0..Y1
+/// `B`: Z1..Y5
+/// `C`: Y2..Z4
+/// `D`: X3..X5
+const FOUR_OVERLAPPING: &str = r#"
+  X0 Y0 Z0
+  X1 Y1 Z1
+  X2 Y2 Z2
+  X3 Y3 Z3
+  X4 Y4 Z4
+  X5 Y5 Z5
+"#;
+
+/// `X` is a good letter: X0..Y8
+/// `Y` is a good letter too: Z1..X9
+/// `Z`: Y2..Z3
+const OVERLAPPING_LONG_SNIPPETS: &str = r#"
+  X0 Y0 Z0
+  X1 Y1 Z1
+  X2 Y2 Z2
+  X3 Y3 Z3
+  X4 Y4 Z4
+  X5 Y5 Z5
+  X6 Y6 Z6
+  X7 Y7 Z7
+  X8 Y8 Z8
+  X9 Y9 Z9
+"#;
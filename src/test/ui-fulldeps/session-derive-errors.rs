@@ -0,0 +1,71 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Checks the errors `#[derive(SessionDiagnostic)]` reports for misuses.
+
+#![feature(rustc_private)]
+#![crate_type = "lib"]
+
+extern crate rustc;
+#[macro_use]
+extern crate rustc_macros;
+extern crate syntax_pos;
+
+use syntax_pos::Span;
+
+#[derive(SessionDiagnostic)]
+#[message = "enum"]
+enum NotAStruct {}
+//~^ ERROR `SessionDiagnostic` can only be derived for structs
+
+#[derive(SessionDiagnostic)]
+#[message = "tuple struct"]
+struct TupleStruct(Span);
+//~^ ERROR `SessionDiagnostic` can only be derived for structs with named fields
+
+#[derive(SessionDiagnostic)]
+struct MissingMessage {
+//~^ ERROR `#[message = "..."]` is required to derive `SessionDiagnostic`
+    #[primary_span]
+    span: Span,
+}
+
+#[derive(SessionDiagnostic)]
+#[message = "primary span with a value"]
+struct PrimarySpanWithValue {
+    #[primary_span = "here"]
+    //~^ ERROR expected `#[primary_span]`
+    span: Span,
+}
+
+#[derive(SessionDiagnostic)]
+#[message = "label without a message"]
+struct LabelWithoutMessage {
+    #[label]
+    //~^ ERROR expected `#[label = "..."]`
+    span: Span,
+}
+
+#[derive(SessionDiagnostic)]
+#[message = "suggestion without a code"]
+struct SuggestionWithoutCode {
+    #[suggestion(message = "remove this")]
+    //~^ ERROR suggestions need both a `message` and a `code`
+    span: Span,
+}
+
+#[derive(SessionDiagnostic)]
+#[message = "unknown suggestion argument"]
+struct UnknownSuggestionArgument {
+    #[suggestion(message = "remove this", code = "", style = "short")]
+    //~^ ERROR unknown suggestion argument
+    span: Span,
+}
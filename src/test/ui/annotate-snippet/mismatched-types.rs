@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --error-format=human-annotate-rs -Z unstable-options
+
+fn pair(a: u32, b: &str) {}
+
+fn main() {
+    pair(0);
+    //~^ ERROR this function takes 2 parameters but 1 parameter was supplied
+    let x: u32 = "hello";
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the warnings emitted by borrowck=migrate are controlled by the
+// `borrowck_migration` lint, so they can be silenced or turned into errors.
+
+// ignore-compare-mode-nll
+// compile-flags: -Z borrowck=migrate
+
+#[allow(borrowck_migration)]
+fn allowed() {
+    match Some(&4) {
+        None => {},
+        ref mut foo
+            if {
+                (|| { let bar = foo; bar.take() })();
+                false
+            } => {},
+        _ => {},
+    }
+}
+
+#[deny(borrowck_migration)]
+fn denied() {
+    match Some(&4) {
+        None => {},
+        ref mut foo
+            if {
+                (|| { let bar = foo; bar.take() })();
+                //~^ ERROR cannot move out of borrowed content
+                //~| WARNING this was previously accepted by the compiler but is being phased out
+                false
+            } => {},
+        _ => {},
+    }
+}
+
+fn main() {
+    allowed();
+    denied();
+}
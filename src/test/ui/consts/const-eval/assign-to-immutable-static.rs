@@ -0,0 +1,22 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Writing to an immutable static at compile-time is reported together with the static the
+// memory belongs to.
+
+#![feature(const_raw_ptr_deref)]
+
+static FOO: u32 = 42;
+
+static BAR: () = unsafe {
+    *(&FOO as *const u32 as *mut u32) = 5; //~ ERROR could not evaluate static initializer
+};
+
+fn main() {}
@@ -0,0 +1,19 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --error-format pretty-json -Zunstable-options
+// The JSON output of const evaluation errors carries the class of the failure.
+
+#![feature(const_transmute)]
+
+const ADDR: usize = unsafe { std::mem::transmute::<&i32, usize>(&0) + 1 };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+#![feature(const_fn, const_let, const_mut_refs)]
+
+const fn inc(x: &mut u32) {
+    *x += 1;
+}
+
+const fn twice_incremented(mut x: u32) -> u32 {
+    inc(&mut x);
+    inc(&mut x);
+    x
+}
+
+const TWO: u32 = twice_incremented(0);
+
+fn main() {
+    assert_eq!(TWO, 2);
+}
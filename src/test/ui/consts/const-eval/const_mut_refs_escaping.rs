@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_mut_refs, const_raw_ptr_deref)]
+
+const fn to_mut(r: &'static u32) -> &'static mut u32 {
+    unsafe { &mut *(r as *const u32 as *mut u32) }
+}
+
+const ESCAPED: &mut u32 = to_mut(&5);
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+#![feature(const_mut_refs)]
+
+use std::cell::Cell;
+
+// Memory behind these references is interned as mutable, so writing to it
+// at run-time must not fault.
+const CELL: &Cell<u32> = &Cell::new(1);
+
+struct Wrapper {
+    cell: &'static Cell<u32>,
+}
+
+const WRAPPED: Wrapper = Wrapper { cell: &Cell::new(2) };
+
+fn main() {
+    CELL.set(3);
+    WRAPPED.cell.set(4);
+    assert_eq!(WRAPPED.cell.get(), 4);
+}
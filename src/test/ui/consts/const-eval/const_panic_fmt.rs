@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_panic, const_let)]
+#![crate_type = "lib"]
+
+pub const Z: () = panic!("x = {}", 42);
+//~^ ERROR any use of this value will cause an error
+
+pub const Y: () = panic!("{:?} is not {}", 'a', true);
+//~^ ERROR any use of this value will cause an error
+
+pub const X: () = panic!("{1} before {0}", 1u8, -2i32);
+//~^ ERROR any use of this value will cause an error
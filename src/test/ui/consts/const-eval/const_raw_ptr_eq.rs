@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+#![feature(const_fn, const_compare_raw_pointers)]
+
+const fn same_element(arr: &[u8; 2], i: usize, j: usize) -> bool {
+    &arr[i] as *const u8 == &arr[j] as *const u8
+}
+
+const fn is_null(ptr: *const u8) -> bool {
+    ptr == 0 as *const u8
+}
+
+const ARR: [u8; 2] = [1, 2];
+
+// comparisons within one allocation only depend on the offsets
+const SAME: bool = same_element(&ARR, 1, 1);
+const DIFFERENT: bool = same_element(&ARR, 0, 1);
+// pointers to actual memory are never null
+const NOT_NULL: bool = !is_null(&ARR as *const [u8; 2] as *const u8);
+const NULL: bool = is_null(0 as *const u8);
+
+fn main() {
+    assert!(SAME);
+    assert!(!DIFFERENT);
+    assert!(NOT_NULL);
+    assert!(NULL);
+}
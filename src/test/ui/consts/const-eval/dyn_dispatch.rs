@@ -0,0 +1,56 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+#![feature(const_dyn_dispatch)]
+
+trait Shape {
+    fn area(&self) -> u32;
+    fn sides(&self) -> u32;
+}
+
+struct Square(u32);
+
+struct Triangle {
+    base: u32,
+    height: u32,
+}
+
+impl Shape for Square {
+    const fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+
+    const fn sides(&self) -> u32 {
+        4
+    }
+}
+
+impl Shape for Triangle {
+    const fn area(&self) -> u32 {
+        self.base * self.height / 2
+    }
+
+    const fn sides(&self) -> u32 {
+        3
+    }
+}
+
+const SQUARE: &dyn Shape = &Square(3);
+const TRIANGLE: &dyn Shape = &Triangle { base: 4, height: 5 };
+
+const AREA: u32 = SQUARE.area() + TRIANGLE.area();
+const SIDES: u32 = SQUARE.sides() + TRIANGLE.sides();
+
+fn main() {
+    assert_eq!(AREA, 19);
+    assert_eq!(SIDES, 7);
+}
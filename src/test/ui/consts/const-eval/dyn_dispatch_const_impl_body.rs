@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A `const fn` impl method may be called through a vtable, so the calls it makes
+// itself are checked like in any other `const fn`.
+
+#![feature(const_dyn_dispatch)]
+
+trait Shape {
+    fn area(&self) -> u32;
+    fn side(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    const fn area(&self) -> u32 {
+        self.side() * self.side()
+        //~^ ERROR can only call other `min_const_fn` within a `min_const_fn`
+    }
+
+    fn side(&self) -> u32 {
+        self.0
+    }
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z force-const-eval-validation
+
+#![feature(const_transmute, const_let)]
+
+use std::mem;
+
+const FOO: u8 = unsafe { let b: bool = mem::transmute(3u8); b as u8 };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// run-pass
+
+#![feature(const_fn, const_box_new)]
+
+const fn boxed(x: u32) -> Box<u32> {
+    Box::new(x)
+}
+
+static BOXED: Box<u32> = boxed(42);
+static NESTED: Box<Box<(u8, &'static str)>> = Box::new(Box::new((7, "seven")));
+
+fn main() {
+    assert_eq!(*BOXED, 42);
+    assert_eq!(NESTED.0, 7);
+    assert_eq!(NESTED.1, "seven");
+}
@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Every use of a constant is a copy of its value, so any of them could free heap
+// memory that belongs to the compiler.
+
+#![feature(box_syntax, const_fn, const_heap)]
+
+const fn numbers() -> Vec<u32> {
+    vec![1, 2, 3]
+}
+
+const BOXED: Box<u32> = box 1;
+//~^ ERROR any use of this value will cause an error
+const NUMBERS: Vec<u32> = numbers();
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Writes to a mutable static, or through interior mutability, could free or
+// reallocate heap memory that belongs to the compiler.
+
+#![feature(box_syntax, const_fn, const_heap)]
+
+use std::sync::atomic::AtomicUsize;
+
+const fn numbers() -> Vec<u32> {
+    vec![1, 2, 3]
+}
+
+static mut BOXED: Box<u32> = box 1;
+//~^ ERROR could not evaluate static initializer
+static mut NUMBERS: Vec<u32> = numbers();
+//~^ ERROR could not evaluate static initializer
+static ATOMIC: Box<AtomicUsize> = box AtomicUsize::new(1);
+//~^ ERROR could not evaluate static initializer
+
+fn main() {}
@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(const_fn, const_box_new, const_let)]
+
+use std::mem::ManuallyDrop;
+
+const fn leak() -> u32 {
+    ManuallyDrop::new(Box::new(42u32));
+    3
+}
+
+static LEAKED: u32 = leak(); //~ ERROR could not evaluate static
+
+fn main() {}
@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// run-pass
+
+#![feature(const_fn, const_heap)]
+
+const fn numbers() -> Vec<u32> {
+    vec![1, 2, 3]
+}
+
+static NUMBERS: Vec<u32> = numbers();
+static WORDS: Vec<&str> = vec!["one", "two"];
+
+fn main() {
+    assert_eq!(NUMBERS, [1, 2, 3]);
+    assert_eq!(NUMBERS.capacity(), 3);
+    assert_eq!(WORDS.len(), 2);
+    assert_eq!(WORDS[1], "two");
+}
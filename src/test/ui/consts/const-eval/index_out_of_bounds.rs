@@ -9,6 +9,6 @@
 // except according to those terms.
 
 static FOO: i32 = [][0];
-//~^ ERROR E0080
+//~^ ERROR E0727
 
 fn main() {}
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+#![feature(const_let, const_loop)]
+
+const fn triangle(n: u32) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        i += 1;
+        sum += i;
+    }
+    sum
+}
+
+const fn collatz_steps(mut n: u64) -> u32 {
+    let mut steps = 0;
+    while n != 1 {
+        n = match n % 2 {
+            0 => n / 2,
+            _ => 3 * n + 1,
+        };
+        steps += 1;
+    }
+    steps
+}
+
+const TRIANGLE: u32 = triangle(10);
+const STEPS: u32 = collatz_steps(27);
+
+fn main() {
+    assert_eq!(TRIANGLE, 55);
+    assert_eq!(STEPS, 111);
+    assert_eq!(triangle(100), 5050);
+}
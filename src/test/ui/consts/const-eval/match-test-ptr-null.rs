@@ -10,12 +10,12 @@
 
 fn main() {
     // Make sure match uses the usual pointer comparison code path -- i.e., it should complain
-    // that pointer comparison is disallowed, not that parts of a pointer are accessed as raw
-    // bytes.
+    // that the result of the pointer comparison is unknown, not that parts of a pointer are
+    // accessed as raw bytes. (Comparing against 0 is fine, pointers are never null.)
     let _: [u8; 0] = [4; {
         match &1 as *const i32 as usize { //~ ERROR casting pointers to integers in constants
-            0 => 42, //~ ERROR constant contains unimplemented expression type
-            //~^ NOTE "pointer arithmetic or comparison" needs an rfc before being allowed
+            1 => 42, //~ ERROR constant contains unimplemented expression type
+            //~^ NOTE tried to compare pointers whose relation is not known at compile time
             //~| ERROR evaluation of constant value failed
             n => n,
         }
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Reading a pointer-sized value that only partially overlaps a pointer is an error.
+
+#![crate_type = "lib"]
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+struct Misaligned {
+    _pad: u8,
+    ptr: usize,
+}
+
+union Pointers {
+    refs: [&'static u8; 2],
+    misaligned: Misaligned,
+}
+
+const X: u8 = 0;
+
+pub const Y: usize = unsafe { Pointers { refs: [&X, &X] }.misaligned.ptr };
+//~^ ERROR any use of this value will cause an error
@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// normalize-stderr-test "allocation \d+" -> "allocation N"
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::offset;
+
+const DATA: [u8; 3] = [1, 2, 3];
+
+const ONE_PAST_END: *const u8 = unsafe { offset(&DATA as *const [u8; 3] as *const u8, 3) };
+const PAST_END: *const u8 = unsafe { offset(&DATA as *const [u8; 3] as *const u8, 4) };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+// ignore-emscripten FIXME(#45351) hits an LLVM assert
+
+#![feature(repr_simd, platform_intrinsics)]
+
+#[repr(simd)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[allow(non_camel_case_types)]
+struct i32x4(i32, i32, i32, i32);
+
+extern "platform-intrinsic" {
+    fn simd_add<T>(x: T, y: T) -> T;
+    fn simd_mul<T>(x: T, y: T) -> T;
+    fn simd_lt<T, U>(x: T, y: T) -> U;
+    fn simd_extract<T, E>(x: T, idx: u32) -> E;
+    fn simd_insert<T, E>(x: T, idx: u32, val: E) -> T;
+    fn simd_shuffle4<T, U>(x: T, y: T, idx: [u32; 4]) -> U;
+}
+
+const A: i32x4 = i32x4(1, 2, 3, 4);
+const B: i32x4 = i32x4(10, 20, 30, 40);
+
+const SUM: i32x4 = unsafe { simd_add(A, B) };
+const PRODUCT: i32x4 = unsafe { simd_mul(A, i32x4(i32::max_value(), -1, 0, 2)) };
+const LESS: i32x4 = unsafe { simd_lt(i32x4(1, 5, 3, 0), A) };
+const THIRD: i32 = unsafe { simd_extract(B, 2) };
+const INSERTED: i32x4 = unsafe { simd_insert(A, 0, 7) };
+const SHUFFLED: i32x4 = unsafe { simd_shuffle4(A, B, [7, 0, 5, 2]) };
+
+fn main() {
+    assert_eq!(SUM, i32x4(11, 22, 33, 44));
+    assert_eq!(PRODUCT, i32x4(i32::max_value(), -2, 0, 8));
+    assert_eq!(LESS, i32x4(0, 0, 0, -1));
+    assert_eq!(THIRD, 30);
+    assert_eq!(INSERTED, i32x4(7, 2, 3, 4));
+    assert_eq!(SHUFFLED, i32x4(40, 1, 20, 3));
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_transmute)]
+
+use std::mem;
+
+union Pun {
+    ptr: &'static i32,
+    int: usize,
+}
+
+const ADDR: usize = unsafe { mem::transmute::<&i32, usize>(&0) + 1 };
+//~^ ERROR any use of this value will cause an error
+
+const MASKED: usize = unsafe { Pun { ptr: &0 }.int & 7 };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
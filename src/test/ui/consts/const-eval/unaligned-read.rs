@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that memory accesses during const evaluation have to be sufficiently aligned.
+
+#![feature(const_raw_ptr_deref, core_intrinsics)]
+
+use std::intrinsics::offset;
+
+const DATA: [u16; 2] = [0, 0];
+const DATA_PTR: *const u8 = &DATA as *const [u16; 2] as *const u8;
+
+const UNALIGNED_ALLOC: u32 = unsafe { *(&[0u8; 8] as *const [u8; 8] as *const u32) };
+//~^ ERROR any use of this value will cause an error
+
+const UNALIGNED_OFFSET: u16 = unsafe { *(offset(DATA_PTR, 1) as *const u16) };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
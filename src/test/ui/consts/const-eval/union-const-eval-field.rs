@@ -35,8 +35,8 @@ const fn read_field2() -> Field2 {
 
 const fn read_field3() -> Field3 {
     const FIELD3: Field3 = unsafe { UNION.field3 };
-    //~^ ERROR it is undefined behavior to use this value
-    FIELD3
+    //~^ ERROR any use of this value will cause an error
+    FIELD3 //~ ERROR erroneous constant used
 }
 
 fn main() {
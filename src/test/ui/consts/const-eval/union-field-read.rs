@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-pass
+
+// Reading union fields only requires the bytes covered by the field to be initialized.
+
+union Bytes {
+    word: u32,
+    byte: u8,
+    pair: (u8, u16),
+}
+
+union Transmute {
+    reference: &'static u8,
+    raw: *const u8,
+}
+
+const LOW: u8 = unsafe { Bytes { word: 0x0102_0304 }.byte };
+const FIRST: u8 = unsafe { Bytes { byte: 7 }.byte };
+// the padding of the tuple stays uninitialized, which is fine for aggregates
+const PAIR: (u8, u16) = unsafe { Bytes { pair: (1, 2) }.pair };
+const RAW: *const u8 = unsafe { Transmute { reference: &42 }.raw };
+
+fn main() {
+    assert!(LOW == 0x04 || LOW == 0x01);
+    assert_eq!(FIRST, 7);
+    assert_eq!(PAIR, (1, 2));
+    assert_eq!(unsafe { *RAW }, 42);
+}
@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-32bit (the offset of the dangling pointer depends on the pointer size)
+
+#![feature(const_let)]
+
+const PTRS: [*const u32; 2] = { //~ ERROR any use of this value will cause an error
+    let x = 42;
+    [&42, &x]
+};
+
+fn main() {}
@@ -0,0 +1,50 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that borrowck explains why a temporary that has to live for `'static`
+// was not promoted.
+
+#![feature(nll)]
+
+use std::cell::Cell;
+
+struct WithDtor;
+
+impl Drop for WithDtor {
+    fn drop(&mut self) {}
+}
+
+fn not_const() -> i32 { 0 }
+
+fn mutable_borrow() {
+    let _: &'static mut i32 = &mut 0; //~ ERROR temporary value dropped while borrowed
+}
+
+fn interior_mutability() {
+    let _: &'static Cell<i32> = &Cell::new(0); //~ ERROR temporary value dropped while borrowed
+}
+
+fn needs_drop() {
+    let _: &'static WithDtor = &WithDtor; //~ ERROR temporary value dropped while borrowed
+}
+
+fn function_argument(x: i32) {
+    let _: &'static i32 = &(x + 1); //~ ERROR temporary value dropped while borrowed
+}
+
+fn complex_temporary(c: bool) {
+    let _: &'static i32 = &(if c { 1 } else { 2 }); //~ ERROR temporary value dropped while borrowed
+}
+
+fn not_const_call() {
+    let _: &'static i32 = &not_const(); //~ ERROR temporary value dropped while borrowed
+}
+
+fn main() {}
@@ -11,6 +11,7 @@
 // run-pass
 
 #![allow(dead_code)]
+#![warn(unused_attributes)]
 
 #[derive]   //~ WARNING empty trait list in `derive`
 struct Foo;
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Only error codes explicitly registered as pending may be used without an
+// extended explanation.
+
+#![feature(rustc_diagnostic_macros)]
+
+__register_diagnostic! { E9997, r##"
+An error with an explanation.
+"## }
+__register_diagnostic! { E9998 }
+__register_diagnostic! { E9999, pending }
+
+fn main() {
+    __diagnostic_used!(E9997);
+    __diagnostic_used!(E9998);
+    //~^ ERROR used diagnostic code E9998 has no extended explanation
+    __diagnostic_used!(E9999);
+}
@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Names differing from the lookup only in case are suggested however long they are, and ahead
+// of closer names with a different spelling.
+
+#![allow(non_snake_case)]
+
+struct Limits {
+    MAX_VALUE: u8,
+    max_valve: u8,
+}
+
+fn main() {
+    let l = Limits { MAX_VALUE: 1, max_valve: 2 };
+    l.max_value; //~ ERROR no field
+}
@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `-Z dump-drop-elaboration` reports the drop flags it creates
+// and the drops it turns into no-ops or leaves conditional.
+
+// compile-pass
+// compile-flags: -Z dump-drop-elaboration
+
+fn consume(_: String) {}
+
+fn maybe_moved(cond: bool) {
+    let s = String::new();
+    if cond {
+        consume(s);
+    }
+}
+
+fn main() {
+    maybe_moved(true);
+}
@@ -8,11 +8,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-enum Enum {
-    X = (1 << 500), //~ ERROR E0080
-    //~| shift left with overflow
-    Y = (1 / 0) //~ ERROR E0080
+#![allow(safe_extern_statics)]
+
+extern {
+    static FOO: u32;
 }
 
+static BAR: u32 = FOO; //~ ERROR E0080
+
 fn main() {
 }
@@ -42,7 +42,7 @@
 
 // skip-codegen
 #![warn(unused_attributes, unknown_lints)]
-#![allow(dead_code)]
+#![allow(dead_code, ill_formed_attribute_input)]
 #![allow(stable_features)]
 
 // UNGATED WHITE-LISTED BUILT-IN ATTRIBUTES
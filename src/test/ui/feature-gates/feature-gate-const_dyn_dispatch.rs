@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Answer {
+    fn answer(&self) -> u32;
+}
+
+impl Answer for () {
+    fn answer(&self) -> u32 {
+        42
+    }
+}
+
+const DYN: &dyn Answer = &();
+const ANSWER: u32 = DYN.answer(); //~ ERROR dynamic dispatch in constants is unstable
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![allow(dead_code)]
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+impl Pair {
+    #[disjoint_fields(a)] //~ ERROR `#[disjoint_fields]` is an experimental feature
+    fn a_mut(&mut self) -> &mut u32 {
+        &mut self.a
+    }
+}
+
+fn main() {}
@@ -83,4 +83,15 @@ fn main() {
     // lint includes comparison operators
     2 == 3; //~ WARN unused comparison
     m == n; //~ WARN unused comparison
+
+    // only the function's reason is shown when it returns a `#[must_use]` type
+    need_to_use_this_wrapped_value(); //~ WARN unused return value
+}
+
+#[must_use = "the wrapper is the point"]
+struct Wrapper(usize);
+
+#[must_use = "the wrapped value is important"]
+fn need_to_use_this_wrapped_value() -> Wrapper {
+    Wrapper(1)
 }
@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// edition:2018
+
+// The suggested explicit import names local items by their path from the crate root, and
+// items of other crates by their bare name.
+
+mod m {
+    pub fn min(_: u8, _: u8) -> u8 { 0 }
+}
+
+use ::std::cmp::*;
+use self::m::*;
+
+fn main() {
+    min(1, 2); //~ ERROR `min` is ambiguous
+}
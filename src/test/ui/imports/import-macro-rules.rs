@@ -0,0 +1,52 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `macro_rules!` macros aren't items of the module they're defined in.
+
+mod foo {
+    macro_rules! bar {
+        () => {};
+    }
+
+    #[macro_export]
+    macro_rules! baz {
+        () => {};
+    }
+}
+
+// Same-named macros defined later in another module don't hide the ones above.
+mod qux {
+    macro_rules! bar {
+        () => {};
+    }
+
+    macro_rules! baz {
+        () => {};
+    }
+}
+
+// Only one of the definitions is exported.
+mod twice {
+    macro_rules! quux {
+        () => {};
+    }
+
+    #[macro_export]
+    macro_rules! quux {
+        () => {};
+    }
+}
+
+use foo::bar; //~ ERROR unresolved import `foo::bar`
+use foo::baz; //~ ERROR unresolved import `foo::baz`
+use qux::baz as qux_baz; //~ ERROR unresolved import `qux::baz`
+use twice::quux; //~ ERROR unresolved import `twice::quux`
+
+fn main() {}
@@ -62,7 +62,7 @@ fn boxed_boxed_borrowed_scribble<'a>(s: Box<Box<&'a mut Scribble>>) -> &'a mut u
 fn scribbled<'a>(s: Scribble<'a>) -> &'a mut u32 {
     &mut *s.0 //[nll]~ ERROR borrow may still be in use when destructor runs [E0713]
     //[migrate]~^ WARNING borrow may still be in use when destructor runs [E0713]
-    //[migrate]~| WARNING This error has been downgraded to a warning for backwards compatibility
+    //[migrate]~| WARNING this was previously accepted by the compiler but is being phased out
 }
 
 // This, by analogy to previous case, is *also* not okay.
@@ -72,7 +72,7 @@ fn scribbled<'a>(s: Scribble<'a>) -> &'a mut u32 {
 fn boxed_scribbled<'a>(s: Box<Scribble<'a>>) -> &'a mut u32 {
     &mut *(*s).0 //[nll]~ ERROR borrow may still be in use when destructor runs [E0713]
     //[migrate]~^ WARNING borrow may still be in use when destructor runs [E0713]
-    //[migrate]~| WARNING This error has been downgraded to a warning for backwards compatibility
+    //[migrate]~| WARNING this was previously accepted by the compiler but is being phased out
 }
 
 // This, by analogy to previous case, is *also* not okay.
@@ -82,7 +82,7 @@ fn boxed_scribbled<'a>(s: Box<Scribble<'a>>) -> &'a mut u32 {
 fn boxed_boxed_scribbled<'a>(s: Box<Box<Scribble<'a>>>) -> &'a mut u32 {
     &mut *(**s).0 //[nll]~ ERROR borrow may still be in use when destructor runs [E0713]
     //[migrate]~^ WARNING borrow may still be in use when destructor runs [E0713]
-    //[migrate]~| WARNING This error has been downgraded to a warning for backwards compatibility
+    //[migrate]~| WARNING this was previously accepted by the compiler but is being phased out
 }
 
 #[rustc_error]
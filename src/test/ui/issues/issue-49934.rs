@@ -29,7 +29,6 @@ fn main() {
     // fold_stmt (Mac)
     #[derive(Debug)]
     //~^ WARN `#[derive]` does nothing on macro invocations
-    //~| NOTE this may become a hard error in a future release
     println!("Hello, world!");
 
     // fold_stmt (Semi)
@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --error-format=json --json=future-incompat -Z unstable-options --cap-lints=allow
+// compile-pass
+
+// Future-incompatible lints are reported at the end of the compilation, even
+// when they are allowed.
+
+trait Tr {
+    fn f(mut arg: u8);
+}
+
+fn main() {}
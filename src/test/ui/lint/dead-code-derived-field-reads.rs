@@ -0,0 +1,42 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(dead_code, unused_tuple_struct_fields)]
+
+// Field reads in derived `Debug` and `Clone` impls don't count as uses.
+#[derive(Debug, Clone)]
+struct Named {
+    read: u8,
+    unread: u8, //~ ERROR field is never used
+}
+
+#[derive(Debug)]
+struct Tuple(u8, u8, u8, u8); //~ ERROR field is never used
+
+#[derive(Clone)]
+enum Shape {
+    Circle(u8),
+    Square { side: u8 }, //~ ERROR field is never used
+}
+
+fn main() {
+    let named = Named { read: 0, unread: 1 };
+    let _ = named.clone().read;
+    let Tuple(first, .., last) = Tuple(0, 1, 2, 3);
+    let _ = (first, last);
+    let _ = Tuple(0, 1, 2, 3).1;
+    let shapes = vec![Shape::Circle(0), Shape::Square { side: 1 }];
+    for shape in shapes {
+        if let Shape::Circle(radius) = shape {
+            let _ = radius;
+        }
+    }
+    println!("{:?}", Tuple(0, 1, 2, 3));
+}
@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(excessive_padding)]
+#![allow(dead_code)]
+
+#[repr(C)]
+struct Padded { //~ ERROR struct `Padded` is 12 bytes, 4 of which are padding
+    a: u8,
+    b: u32,
+    c: u8,
+}
+
+#[repr(C)]
+struct Ordered {
+    b: u32,
+    a: u8,
+    c: u8,
+}
+
+// The compiler already reorders these fields itself.
+struct Reordered {
+    a: u8,
+    b: u32,
+    c: u8,
+}
+
+#[repr(C, packed)]
+struct Packed {
+    a: u8,
+    b: u32,
+    c: u8,
+}
+
+#[repr(C)]
+struct Generic<T> {
+    a: u8,
+    b: T,
+    c: u8,
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z explain-lint-levels -D bad-style
+
+fn main() {
+    let _FirstVariable = true; //~ ERROR should have a snake
+    let _SecondVariable = true; //~ ERROR should have a snake
+    other();
+}
+
+#[warn(non_snake_case)]
+fn other() {
+    let _ThirdVariable = true; //~ WARN should have a snake
+    let _FourthVariable = true; //~ WARN should have a snake
+}
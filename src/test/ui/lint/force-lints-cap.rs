@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --cap-lints allow -Z force-lints unused
+// compile-pass
+
+// Lints in a group named by `-Z force-lints` aren't capped; everything else
+// still is.
+
+#![warn(unused)]
+#![deny(non_snake_case)]
+
+use std::option; //~ WARN unused import
+
+fn main() {
+    let _FirstVariable = true;
+}
+
+// Warnings that aren't lints are still silenced.
+#[macro_escape]
+mod foo {}
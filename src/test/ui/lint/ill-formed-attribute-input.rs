@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-pass
+
+#[cold = "yes"] //~ WARN attribute must be of the form `#[cold]`
+fn cold() {}
+
+#[no_mangle(x)] //~ WARN attribute must be of the form `#[no_mangle]`
+pub extern fn no_mangle() {}
+
+#[allow(ill_formed_attribute_input)]
+#[cold(x)]
+fn allowed() {}
+
+fn main() {
+    cold();
+    allowed();
+}
@@ -22,8 +22,8 @@ fn field_read(f: Foo) -> usize {
 }
 
 enum XYZ {
-    X, //~ ERROR variant is never constructed
-    Y { //~ ERROR variant is never constructed
+    X, //~ ERROR variants are never constructed: `X`, `Y`
+    Y {
         a: String,
         b: i32,
         c: i32,
@@ -43,13 +43,13 @@ enum ABC { //~ ERROR enum is never used
 
 // ensure struct variants get warning for their fields
 enum IJK {
-    I, //~ ERROR variant is never constructed
+    I, //~ ERROR variants are never constructed: `I`, `K`
     J {
         a: String,
         b: i32, //~ ERROR field is never used
         c: i32, //~ ERROR field is never used
     },
-    K //~ ERROR variant is never constructed
+    K
 
 }
 
@@ -20,8 +20,8 @@ enum Enum2 {
     Variant3(bool),
     #[allow(dead_code)]
     Variant4(isize),
-    Variant5 { _x: isize }, //~ ERROR: variant is never constructed: `Variant5`
-    Variant6(isize), //~ ERROR: variant is never constructed: `Variant6`
+    Variant5 { _x: isize }, //~ ERROR: variants are never constructed: `Variant5`, `Variant6`
+    Variant6(isize),
     _Variant7,
 }
 
@@ -30,6 +30,13 @@ enum Enum3 { //~ ERROR: enum is never used
     Variant9
 }
 
+enum Enum4 {
+    Variant10(bool),
+    Variant11, //~ ERROR: variant is never constructed: `Variant11`
+    #[warn(dead_code)]
+    Variant12, //~ WARN: variant is never constructed: `Variant12`
+}
+
 fn main() {
     let v = Enum1::Variant1(1);
     match v {
@@ -37,4 +44,5 @@ fn main() {
         Enum1::Variant2 => ()
     }
     let x = Enum2::Variant3(true);
+    let y = Enum4::Variant10(false);
 }
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Late lints run on each module separately; check that a module still sees
+// the lint levels set on the modules around it.
+
+#![deny(non_snake_case)]
+
+#[allow(non_snake_case)]
+mod allowed {
+    pub mod nested {
+        pub fn BadName() {}
+    }
+}
+
+mod denied {
+    pub mod nested {
+        pub fn BadName() {} //~ ERROR function `BadName` should have a snake case name
+    }
+}
+
+fn main() {}
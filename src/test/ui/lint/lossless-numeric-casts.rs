@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-rustfix
+
+#![deny(lossless_numeric_casts)]
+
+fn main() {
+    let a: u8 = 1;
+    let _ = a as u32; //~ ERROR numeric cast that can't lose information
+    let b: f32 = 1.5;
+    let _ = b as f64; //~ ERROR numeric cast that can't lose information
+    let c: u16 = 1;
+    let _ = c as usize; //~ ERROR numeric cast that can't lose information
+    let d: i16 = 1;
+    let _ = d as isize; //~ ERROR numeric cast that can't lose information
+
+    // may lose information on some targets
+    let _ = 1u32 as usize;
+    let _ = 1usize as u64;
+    let _ = 1isize as i64;
+    let _ = 1usize as f64;
+}
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(lossy_numeric_casts)]
+
+fn main() {
+    let a: u64 = 300;
+    let _ = a as u8; //~ ERROR lossy numeric cast
+    let b: i32 = -1;
+    let _ = b as u32; //~ ERROR lossy numeric cast
+    let c: f64 = 1.5;
+    let _ = c as i32; //~ ERROR lossy numeric cast
+    let d: usize = 1;
+    let _ = d as u64; //~ ERROR lossy numeric cast
+    let _ = d as f64; //~ ERROR lossy numeric cast
+    let e: isize = 1;
+    let _ = e as i128; //~ ERROR lossy numeric cast
+
+    // these can't lose information on any target
+    let _ = 1u8 as u32;
+    let _ = 1u16 as f32;
+    let _ = 1u16 as usize;
+    let _ = 1i32 as f64;
+}
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+
+#![feature(lint_reasons)]
+
+#[expect(dead_code, reason = "only called from the tests")]
+fn unused() {}
+
+#[expect(dead_code, reason = "not used anywhere yet")]
+fn used() {}
+//~^^ WARN this lint expectation for `dead_code` is unfulfilled
+//~| NOTE not used anywhere yet
+
+// The unfulfilled expectation is reported at the item, so it can be allowed there.
+#[allow(unfulfilled_lint_expectations)]
+#[expect(dead_code)]
+fn used_too() {}
+
+fn main() {
+    used();
+    used_too();
+}
@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+
+// Lints buffered while expanding macros respect the lint levels of the items around them.
+
+#![warn(unused_attributes)]
+
+#[allow(unused_attributes)]
+mod allowed {
+    #[derive()]
+    struct Empty;
+
+    fn in_module() {
+        #[derive(Debug)]
+        println!();
+    }
+}
+
+#[allow(unused_attributes)]
+fn allowed() {
+    #[derive(Debug)]
+    println!();
+}
+
+trait Trait {
+    #[allow(unused_attributes)]
+    fn allowed() {
+        #[derive(Debug)]
+        println!();
+    }
+
+    fn warned() {
+        #[derive(Debug)] //~ WARN `#[derive]` does nothing on macro invocations
+        println!();
+    }
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-pass
+
+// Parentheses around the arguments of builtin macros are linted while expanding
+// them, since they are gone by the time the lint passes run.
+
+#![warn(unused_parens)]
+
+#[allow(unused_parens)]
+fn allowed(x: i32) {
+    assert!((x == 1));
+    println!("{}", (x));
+}
+
+macro_rules! wrapped {
+    ($e:expr) => { println!("{}", ($e)) }
+}
+
+fn main() {
+    let x = 1;
+    allowed(x);
+    assert!((x == 1)); //~ WARN unnecessary parentheses around `assert!` condition
+    println!("{}", (x)); //~ WARN unnecessary parentheses around format argument
+    println!("{y}", y = (x)); //~ WARN unnecessary parentheses around format argument
+    println!("{:?}", (x, x));
+    wrapped!(x);
+}
@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// General test of the liveness computed by the generic dataflow engine.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_liveness, stop_after_dataflow)]
+fn foo(test: bool) -> i32 {
+    let mut x: i32;
+    let p: *const i32;
+
+    x = 0;
+
+    // `x` is live here since it is borrowed in the next statement...
+    unsafe { rustc_peek(x); }
+
+    p = &x;
+
+    // ... but not here, even though it can still be read through `p`.
+    unsafe { rustc_peek(x); } //~ ERROR rustc_peek: bit not set
+
+    let tmp = unsafe { *p };
+    x = tmp + 1;
+
+    // `x` is only read on one of the two paths, which is enough to keep it live.
+    unsafe { rustc_peek(x); }
+
+    if test {
+        x = 5;
+    }
+
+    x
+}
+
+fn main() {
+    foo(true);
+}
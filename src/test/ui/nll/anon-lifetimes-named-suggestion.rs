@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that returning data with one anonymous lifetime where another one is
+// expected suggests naming both in the function signature.
+
+#![feature(nll)]
+
+struct Foo;
+
+impl Foo {
+    fn get(&self, other: &str) -> &str {
+        other //~ ERROR unsatisfied lifetime constraints
+    }
+
+    // The `impl Trait` parameter is not written in the generics, so the
+    // lifetime has to go into a new list.
+    fn get_with(&self, other: &str, _f: impl Fn()) -> &str {
+        other //~ ERROR unsatisfied lifetime constraints
+    }
+}
+
+// The visibility must not be mistaken for the parameter list.
+pub(crate) fn set(slot: &mut &u32, value: &u32) {
+    *slot = value; //~ ERROR unsatisfied lifetime constraints
+}
+
+fn main() {}
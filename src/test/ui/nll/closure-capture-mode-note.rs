@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a closure requires unique access to a variable because of
+// a use other than the first one, borrowck points at that use.
+
+#![feature(nll)]
+
+fn read(_: &i32) {}
+
+fn read_then_write(x: &mut i32) {
+    let c = || {
+        read(x);
+        *x = 1;
+    };
+    read(x); //~ ERROR cannot borrow `*x` as immutable
+    drop(c);
+}
+
+fn main() {}
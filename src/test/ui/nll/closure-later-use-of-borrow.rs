@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a borrow is later used through a closure, we also point
+// at where the closure body uses it.
+
+#![feature(nll)]
+
+fn main() {
+    let mut x = 0;
+    let r = &x;
+    let get = || *r;
+    x = 1; //~ ERROR cannot assign to `x` because it is borrowed
+    get();
+    hygiene();
+}
+
+macro_rules! add_hidden_r {
+    ($e:expr) => {{
+        let r = 1;
+        move || r + $e
+    }}
+}
+
+// The closure captures two variables named `r`; point at the one holding the borrow.
+fn hygiene() {
+    let mut x = 0;
+    let r = &x;
+    let get = add_hidden_r!(*r);
+    x = 1; //~ ERROR cannot assign to `x` because it is borrowed
+    get();
+}
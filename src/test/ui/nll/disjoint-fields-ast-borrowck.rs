@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the body of a `#[disjoint_fields]` method is checked even when the
+// AST borrow checker is used, as it is by default in the 2015 edition.
+
+#![feature(disjoint_fields)]
+#![allow(dead_code)]
+
+struct Point {
+    x: Vec<u32>,
+    y: Vec<u32>,
+}
+
+impl Point {
+    #[disjoint_fields(x)]
+    fn sneaky(&mut self) -> &mut Vec<u32> {
+        &mut self.y //~ ERROR uses `self` outside of its declared fields
+    }
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the body of a `#[disjoint_fields]` method is limited to its
+// declared fields, and that callers still see overlapping views conflict.
+
+#![feature(nll, disjoint_fields)]
+#![allow(dead_code)]
+
+struct Point {
+    x: Vec<u32>,
+    y: Vec<u32>,
+}
+
+impl Point {
+    #[disjoint_fields(x)]
+    fn x_mut(&mut self) -> &mut Vec<u32> {
+        &mut self.x
+    }
+
+    #[disjoint_fields(x, y)]
+    fn both_mut(&mut self) -> &mut Vec<u32> {
+        &mut self.y
+    }
+
+    #[disjoint_fields(x)]
+    fn sneaky(&mut self) -> &mut Vec<u32> {
+        &mut self.y //~ ERROR uses `self` outside of its declared fields
+    }
+
+    #[disjoint_fields(x)]
+    fn whole(&mut self) -> &mut Self {
+        self //~ ERROR uses `self` outside of its declared fields
+    }
+
+    #[disjoint_fields(z)] //~ ERROR no field `z` on `self`
+    fn missing(&self) {}
+}
+
+fn overlap(point: &mut Point) {
+    let x = point.x_mut();
+    let both = point.both_mut(); //~ ERROR cannot borrow
+    x.push(1);
+    both.push(2);
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// edition:2018
+
+// Test that the body of a `#[disjoint_fields]` method is rejected in migrate
+// mode, even though MIR borrowck errors are downgraded, or dropped entirely
+// when `borrowck_migration` is allowed.
+
+#![feature(disjoint_fields)]
+#![allow(borrowck_migration, dead_code)]
+
+struct Point {
+    x: Vec<u32>,
+    y: Vec<u32>,
+}
+
+impl Point {
+    #[disjoint_fields(x)]
+    fn sneaky(&mut self) -> &mut Vec<u32> {
+        &mut self.y //~ ERROR uses `self` outside of its declared fields
+    }
+}
+
+fn main() {}
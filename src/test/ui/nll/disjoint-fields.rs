@@ -0,0 +1,52 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that calls to `#[disjoint_fields]` methods that name disjoint
+// fields of `self` may hold their results at the same time.
+
+// compile-pass
+
+#![feature(nll, disjoint_fields)]
+
+struct Point {
+    x: Vec<u32>,
+    y: Vec<u32>,
+    len: usize,
+}
+
+impl Point {
+    #[disjoint_fields(x)]
+    fn x_mut(&mut self) -> &mut Vec<u32> {
+        &mut self.x
+    }
+
+    #[disjoint_fields(y)]
+    fn y_mut(&mut self) -> &mut Vec<u32> {
+        &mut self.y
+    }
+
+    #[disjoint_fields(x, len)]
+    fn push_x(&mut self, value: u32) {
+        self.x_mut().push(value);
+        self.len += 1;
+    }
+}
+
+fn main() {
+    let mut point = Point { x: vec![], y: vec![], len: 0 };
+    let x = point.x_mut();
+    let y = point.y_mut();
+    x.push(1);
+    y.push(2);
+    point.push_x(3);
+    assert_eq!(point.x, [1, 3]);
+    assert_eq!(point.y, [2]);
+    assert_eq!(point.len, 1);
+}
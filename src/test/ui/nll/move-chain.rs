@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that "use of moved value" errors follow the value through the
+// bindings and closures it was moved into before the use.
+
+#![feature(nll)]
+
+fn chain() {
+    let s = String::new();
+    let x = s;
+    let c = move || x;
+    println!("{}", s); //~ ERROR borrow of moved value: `s`
+    c();
+}
+
+fn conditional(cond: bool) {
+    let s = String::new();
+    let x = s;
+    if cond {
+        drop(x);
+    }
+    println!("{}", s); //~ ERROR borrow of moved value: `s`
+}
+
+fn main() {}
@@ -0,0 +1,66 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the in-tree Polonius solver accepts conditionally returning a
+// borrow and then mutating the borrowed data on the other path, which the
+// location-insensitive NLL analysis rejects (see `get_default.rs`).
+
+// compile-pass
+// ignore-compare-mode-nll
+// compile-flags: -Z borrowck=mir -Zpolonius
+
+use std::collections::HashMap;
+
+struct Map {
+}
+
+impl Map {
+    fn get(&self) -> Option<&String> { None }
+    fn set(&mut self, _v: String) { }
+}
+
+fn ok(map: &mut Map) -> &String {
+    loop {
+        match map.get() {
+            Some(v) => {
+                return v;
+            }
+            None => {
+                map.set(String::new());
+            }
+        }
+    }
+}
+
+fn get_default<'r>(map: &'r mut HashMap<usize, String>, key: usize) -> &'r mut String {
+    match map.get_mut(&key) {
+        Some(value) => value,
+        None => {
+            map.insert(key, String::new());
+            map.get_mut(&key).unwrap()
+        }
+    }
+}
+
+fn first_or_push(v: &mut Vec<u32>) -> &u32 {
+    if let Some(first) = v.first() {
+        return first;
+    }
+    v.push(0);
+    &v[0]
+}
+
+fn main() {
+    let mut map = Map {};
+    ok(&mut map);
+    let mut map = HashMap::new();
+    get_default(&mut map, 0).push('a');
+    first_or_push(&mut vec![]);
+}
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-
+// compile-flags: -Z expand-similar-errors
 fn foo<N>(_x: N) {}
 
 fn main() {
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(crate_visibility_modifier)]
+
+mod a {
+    pub struct S {
+        crate x: u8,
+        pub(crate) y: u8,
+        pub(in crate::a) z: u8,
+    }
+
+    pub fn s() -> S {
+        S { x: 0, y: 0, z: 0 }
+    }
+}
+
+fn main() {
+    let s = a::s();
+    let _ = s.x;
+    let _ = s.y;
+    let _ = s.z; //~ ERROR field `z` of struct `a::S` is private
+}
@@ -0,0 +1,19 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(crate_visibility_modifier)]
+
+// Fields of enum variants are as visible as the enum itself.
+pub enum E {
+    A { crate x: u8 }, //~ ERROR unnecessary visibility qualifier
+    B(pub(crate) u8), //~ ERROR unnecessary visibility qualifier
+}
+
+fn main() {}
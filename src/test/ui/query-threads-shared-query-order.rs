@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// revisions: serial parallel
+//[serial] compile-flags: -Z query-threads=1
+//[parallel] compile-flags: -Z query-threads=4
+
+// Checking `first` needs to know whether the type returned by `third` is `Send`,
+// which type-checks `third` from within `first`. The error in `third` is still
+// reported after the one in `second`, no matter how many threads are used.
+
+fn is_send<T: Send>(_: T) {}
+
+fn first() -> u32 {
+    is_send(third());
+    "first" //~ ERROR mismatched types
+}
+
+fn second() -> u32 {
+    "second" //~ ERROR mismatched types
+}
+
+fn third() -> impl Copy {
+    let x: u32 = "third"; //~ ERROR mismatched types
+    x
+}
+
+fn main() {}
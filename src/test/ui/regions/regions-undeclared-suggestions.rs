@@ -0,0 +1,45 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-rustfix
+
+#![allow(dead_code)]
+
+struct Wrapper {
+    inner: &'a str, //~ ERROR use of undeclared lifetime name `'a`
+}
+
+struct Pair<T> {
+    first: T,
+    second: &'b u8, //~ ERROR use of undeclared lifetime name `'b`
+}
+
+fn pick<T>(x: &'c T) -> &T { //~ ERROR use of undeclared lifetime name `'c`
+    x
+}
+
+fn greeting() -> &'statc str { //~ ERROR use of undeclared lifetime name `'statc`
+    "hello"
+}
+
+struct Twice {
+    first: &'t u8, //~ ERROR use of undeclared lifetime name `'t`
+    second: &'t u8, //~ ERROR use of undeclared lifetime name `'t`
+}
+
+struct Holder<'a>(&'a str);
+
+impl Holder<'h> { //~ ERROR use of undeclared lifetime name `'h`
+    fn get(&self) -> &str {
+        self.0
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-rustfix
+// rustfix-only-machine-applicable
+
+// A single candidate that can be imported as it is gets a machine applicable suggestion.
+
+mod m {
+    pub struct Foo;
+}
+
+fn main() {
+    let _ = Foo; //~ ERROR cannot find value `Foo` in this scope
+}
@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:import-candidates-ranking.rs
+
+// Candidates that can be imported as they are come first, then shorter paths.
+// Items of crates loaded outside of the crate root are suggested too.
+
+mod a {
+    pub mod b {
+        pub struct Foo;
+    }
+}
+
+mod c {
+    struct Foo;
+}
+
+mod d {
+    pub struct Foo;
+}
+
+mod e {
+    extern crate import_candidates_ranking;
+}
+
+fn main() {
+    let _ = Foo; //~ ERROR cannot find value `Foo` in this scope
+}
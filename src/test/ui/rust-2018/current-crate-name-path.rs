@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// edition:2018
+// run-rustfix
+
+mod foo {
+    pub struct Bar;
+
+    pub fn bar() {}
+}
+
+use current_crate_name_path::foo::bar;
+//~^ ERROR failed to resolve: `current_crate_name_path` is the current crate
+
+use current_crate_name_path::foo as quux;
+//~^ ERROR failed to resolve: `current_crate_name_path` is the current crate
+
+fn baz(_: current_crate_name_path::foo::Bar) {}
+//~^ ERROR failed to resolve: `current_crate_name_path` is the current crate
+
+fn main() {
+    ::current_crate_name_path::foo::bar();
+    //~^ ERROR failed to resolve: `current_crate_name_path` is the current crate
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// edition:2018
+// compile-pass
+// aux-build:remove-extern-crate.rs
+// compile-flags:--extern remove_extern_crate -Z edition-migration-report
+
+#![warn(rust_2018_idioms)]
+
+extern crate core;
+extern crate remove_extern_crate as remove_extern_crate;
+
+fn main() {
+    remove_extern_crate::foo!();
+}
+
+mod inner {
+    extern crate core;
+}
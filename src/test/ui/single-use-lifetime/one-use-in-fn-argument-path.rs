@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-rustfix
+
+#![deny(single_use_lifetimes)]
+#![allow(dead_code)]
+#![allow(unused_variables)]
+
+// Test that a lifetime used once inside a path in a fn argument is
+// suggested to become `'_`, since it can't be elided entirely there.
+
+struct Foo<'f> {
+    data: &'f u32
+}
+
+fn a<'a>(x: Foo<'a>) { //~ ERROR `'a` only used once
+    //~^ HELP use `'_` for the single-use lifetime
+}
+
+fn main() { }
@@ -20,6 +20,7 @@ struct Foo<'f> {
 }
 
 impl<'f> Foo<'f> { //~ ERROR `'f` only used once
+    //~^ HELP use `'_` for the single-use lifetime
     fn inherent_a(&self) {
     }
 }
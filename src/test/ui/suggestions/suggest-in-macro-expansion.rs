@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Suggestions pointing into a macro expansion are moved to the macro invocation if the
+// replacement doesn't depend on the expanded code, and are shown with the expansion otherwise.
+
+const SMALL: u16 = 1;
+static ZERO: &u32 = &0;
+
+macro_rules! small {
+    () => { SMALL } //~ ERROR mismatched types
+}
+
+macro_rules! zero {
+    () => { ZERO } //~ ERROR mismatched types
+}
+
+macro_rules! take_small {
+    () => { foo(SMALL) } //~ ERROR mismatched types
+}
+
+fn foo(_: u32) {}
+
+fn main() {
+    foo(small!());
+    foo(zero!());
+    take_small!();
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z terminal-width=60
+
+// Labels that would run past the edge of the terminal are wrapped.
+
+#![feature(on_unimplemented)]
+
+#[rustc_on_unimplemented(
+    label = "`{Self}` has no wire format; implement `Wire` for it or send another type"
+)]
+trait Wire {}
+
+fn send<T: Wire>(_: T) {}
+
+fn main() {
+    send(1u8); //~ ERROR the trait bound `u8: Wire` is not satisfied
+}
@@ -0,0 +1,5 @@
+# French translations used by `translate-additional-ftl.rs`.
+
+typeck-unrecognized-atomic-operation =
+    fonction d'opération atomique non reconnue : `{ $op }`
+    .span-label = opération atomique non reconnue
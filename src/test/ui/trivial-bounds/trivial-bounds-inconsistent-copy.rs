@@ -11,7 +11,7 @@
 // run-pass
 // Check tautalogically false `Copy` bounds
 #![feature(trivial_bounds)]
-#![allow(unused)]
+#![allow(unused, unsatisfiable_bounds)]
 
 fn copy_string(t: String) -> String where String: Copy {
     is_copy(&t);
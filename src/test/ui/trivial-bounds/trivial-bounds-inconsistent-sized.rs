@@ -11,7 +11,7 @@
 // run-pass
 // Check tautalogically false `Sized` bounds
 #![feature(trivial_bounds)]
-#![allow(unused)]
+#![allow(unused, unsatisfiable_bounds)]
 
 trait A {}
 
@@ -11,6 +11,7 @@
 // run-pass
 // Test that inconsistent bounds are used in well-formedness checks
 #![feature(trivial_bounds)]
+#![allow(unsatisfiable_bounds)]
 
 use std::fmt::Debug;
 
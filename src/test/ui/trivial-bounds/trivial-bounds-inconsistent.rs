@@ -13,7 +13,7 @@
 // Check that tautalogically false bounds are accepted, and are used
 // in type inference.
 #![feature(trivial_bounds)]
-#![allow(unused)]
+#![allow(unused, unsatisfiable_bounds)]
 
 pub trait Foo {
     fn test(&self);
@@ -9,7 +9,7 @@
 // except according to those terms.
 
 #![feature(trivial_bounds)]
-#![allow(unused)]
+#![allow(unused, unsatisfiable_bounds)]
 #![deny(trivial_bounds)]
 
 struct A where i32: Copy; //~ ERROR
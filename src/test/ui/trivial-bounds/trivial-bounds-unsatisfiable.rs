@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `unsatisfiable_bounds` is an error by default, independently of
+// `trivial_bounds`.
+
+#![feature(trivial_bounds)]
+#![allow(unused, trivial_bounds)]
+
+trait X {}
+
+fn f() where i32: X {} //~ ERROR the trait bound `i32: X` can never be satisfied
+
+fn g() where String: Copy {} //~ ERROR can never be satisfied
+
+fn main() {}
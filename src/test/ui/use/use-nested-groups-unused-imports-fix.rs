@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// run-rustfix
+// compile-pass
+
+// Unused imports are removed leaf by leaf, keeping the used ones.
+
+#![warn(unused_imports)]
+#![allow(dead_code)]
+
+mod foo {
+    pub mod bar {
+        pub mod baz {
+            pub struct Bar;
+        }
+        pub mod foobar {}
+    }
+
+    pub struct Foo;
+    pub struct Qux;
+}
+
+use foo::{Foo, bar::{baz::{}, foobar::*}, *};
+//~^ WARN unused imports: `*`, `Foo`, `baz::{}`, `foobar::*`
+use foo::bar::baz::{*, *};
+//~^ WARN unused import: `*`
+use foo::{Foo as Used, bar::foobar::*, Qux};
+//~^ WARN unused imports: `Qux`, `bar::foobar::*`
+use foo::{};
+//~^ WARN unused import: `use foo::{};`
+
+fn main() {
+    let _: Bar;
+    let _ = Used;
+}
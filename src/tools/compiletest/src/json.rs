@@ -27,6 +27,39 @@ struct Diagnostic {
     rendered: Option<String>,
 }
 
+/// Emitted once at the end of the compilation with `--json=future-incompat`.
+#[derive(Deserialize)]
+struct FutureIncompatReport {
+    future_incompat_report: Vec<FutureIncompatReportLint>,
+}
+
+#[derive(Deserialize)]
+struct FutureIncompatReportLint {
+    lint: String,
+    spans: Vec<DiagnosticSpan>,
+    reference: String,
+}
+
+impl FutureIncompatReport {
+    /// Renders one line per lint, as the report has no rendered form of its own.
+    fn render(&self) -> String {
+        self.future_incompat_report
+            .iter()
+            .map(|lint| {
+                let location = lint
+                    .spans
+                    .iter()
+                    .find(|span| span.is_primary)
+                    .map(|span| format!(" at {}:{}:{}", span.file_name, span.line_start,
+                                        span.column_start))
+                    .unwrap_or_default();
+                format!("future incompatibility report: `{}`{}, see {}\n",
+                        lint.lint, location, lint.reference)
+            })
+            .collect()
+    }
+}
+
 #[derive(Deserialize, Clone)]
 struct DiagnosticSpan {
     file_name: String,
@@ -79,6 +112,16 @@ pub fn extract_rendered(output: &str, proc_res: &ProcRes) -> String {
             if line.starts_with('{') {
                 match serde_json::from_str::<Diagnostic>(line) {
                     Ok(diagnostic) => diagnostic.rendered,
+                    Err(_) if line.starts_with("{\"future_incompat_report\"") => {
+                        match serde_json::from_str::<FutureIncompatReport>(line) {
+                            Ok(report) => Some(report.render()),
+                            Err(error) => proc_res.fatal(Some(&format!(
+                                "failed to decode future incompatibility report: \
+                                 `{}`\nline: {}",
+                                error, line
+                            ))),
+                        }
+                    }
                     Err(error) => {
                         proc_res.fatal(Some(&format!(
                             "failed to decode compiler output as json: \
@@ -111,6 +154,8 @@ fn parse_line(file_name: &str, line: &str, output: &str, proc_res: &ProcRes) ->
                 push_expected_errors(&mut expected_errors, &diagnostic, &[], file_name);
                 expected_errors
             }
+            // The report only repeats lints that are already diagnostics.
+            Err(_) if line.starts_with("{\"future_incompat_report\"") => vec![],
             Err(error) => {
                 proc_res.fatal(Some(&format!(
                     "failed to decode compiler output as json: \
@@ -107,7 +107,9 @@ const WHITELIST: &[Crate] = &[
     Crate("parking_lot_core"),
     Crate("pkg-config"),
     Crate("polonius-engine"),
+    Crate("proc-macro2"),
     Crate("quick-error"),
+    Crate("quote"),
     Crate("rand"),
     Crate("rand_core"),
     Crate("redox_syscall"),
@@ -123,6 +125,7 @@ const WHITELIST: &[Crate] = &[
     Crate("scopeguard"),
     Crate("smallvec"),
     Crate("stable_deref_trait"),
+    Crate("syn"),
     Crate("tempfile"),
     Crate("termcolor"),
     Crate("terminon"),
@@ -130,6 +133,7 @@ const WHITELIST: &[Crate] = &[
     Crate("thread_local"),
     Crate("ucd-util"),
     Crate("unicode-width"),
+    Crate("unicode-xid"),
     Crate("unreachable"),
     Crate("utf8-ranges"),
     Crate("version_check"),